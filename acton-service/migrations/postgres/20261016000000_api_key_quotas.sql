@@ -0,0 +1,16 @@
+-- Daily and monthly API key quotas for PgApiKeyStorage
+--
+-- Adds the per-key allowance columns and the usage counters the rate
+-- limiters increment. Safe to run more than once.
+
+ALTER TABLE api_keys
+    ADD COLUMN IF NOT EXISTS daily_quota BIGINT,
+    ADD COLUMN IF NOT EXISTS monthly_quota BIGINT;
+
+-- Daily (YYYY-MM-DD) and monthly (YYYY-MM) usage counters
+CREATE TABLE IF NOT EXISTS api_key_usage (
+    key_id VARCHAR(255) NOT NULL,
+    period VARCHAR(10) NOT NULL,
+    count BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (key_id, period)
+);
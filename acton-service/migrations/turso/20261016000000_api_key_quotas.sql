@@ -0,0 +1,16 @@
+-- Daily and monthly API key quotas for TursoApiKeyStorage
+--
+-- Adds the per-key allowance columns and the usage counters the rate
+-- limiters increment. SQLite has no ADD COLUMN IF NOT EXISTS, so run this
+-- once; a second run fails on the duplicate columns and changes nothing.
+
+ALTER TABLE api_keys ADD COLUMN daily_quota INTEGER;
+ALTER TABLE api_keys ADD COLUMN monthly_quota INTEGER;
+
+-- Daily (YYYY-MM-DD) and monthly (YYYY-MM) usage counters
+CREATE TABLE IF NOT EXISTS api_key_usage (
+    key_id TEXT NOT NULL,
+    period TEXT NOT NULL,
+    count INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (key_id, period)
+);
@@ -0,0 +1,680 @@
+//! Pre-built token endpoints: password login, refresh, logout, and revoke
+//!
+//! Requires feature: `account-handlers`
+//!
+//! - `POST {prefix}/token` checks an email and password with
+//!   [`AccountService::authenticate`] and returns a
+//!   [`TokenPair`](crate::auth::tokens::TokenPair). Accounts with MFA get a
+//!   challenge instead, completed at `POST {prefix}/token/mfa`.
+//! - `POST {prefix}/passkey/options` and `POST {prefix}/passkey` sign in
+//!   with a WebAuthn passkey (`passkeys` feature). The challenge is kept in
+//!   an `Extension<Arc<dyn PasskeyChallengeStore>>`, or in the session when
+//!   a session layer is installed.
+//! - `POST {prefix}/refresh` rotates a refresh token. Presenting a rotated
+//!   token again revokes its whole family (see
+//!   [`RefreshTokenConfig`](crate::auth::RefreshTokenConfig)).
+//! - `POST {prefix}/logout` revokes the refresh token's family and, when the
+//!   route sits behind the token middleware, the access token's `jti`.
+//! - `POST {prefix}/revoke` lets a client revoke a token it holds
+//!   (RFC 7009). Unknown tokens are not an error.
+//!
+//! With the `login-lockout` feature, failed logins (password, second
+//! factor, or passkey) count against [`LoginLockout`](crate::lockout::LoginLockout) per
+//! email. Every route emits `auth.*` audit events when an `AuditLogger`
+//! extension is present.
+
+use axum::{
+    extract::FromRequestParts,
+    http::{request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Extension, Json, Router,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::sync::Arc;
+
+use super::handler_audit::{AuthAudit, AuthStep};
+#[cfg(feature = "passkeys")]
+use super::passkeys::{Challenges, PasskeyAssertion, PasskeyRequestOptions};
+use super::{Account, AccountError, AccountService, AuthOutcome};
+use crate::auth::config::AuthConfig;
+use crate::auth::tokens::issuer::{refresh_token_id, RefreshLookup, TokenIssuer};
+use crate::auth::tokens::refresh::RefreshTokenMetadata;
+use crate::auth::tokens::{ClaimsBuilder, TokenPair};
+use crate::error::Error;
+use crate::middleware::request_context::RequestContext;
+use crate::middleware::token::extract_token;
+use crate::middleware::Claims;
+
+/// Build the token endpoint routes
+///
+/// Paths and enabled routes come from `[auth.routes]`. Requires
+/// `Extension<Arc<AccountService>>` and `Extension<Arc<TokenEndpoints>>`:
+/// ```rust,ignore
+/// let issuer = TokenIssuer::new(generator, refresh_storage, &auth_config.refresh_tokens);
+/// let endpoints = TokenEndpoints::new(issuer)
+///     .with_lockout(lockout)
+///     .with_revocation(RedisTokenRevocation::new(redis_pool));
+///
+/// let app = Router::new()
+///     .merge(auth_routes(&auth_config))
+///     .layer(Extension(Arc::new(endpoints)))
+///     .layer(Extension(account_service));
+/// ```
+///
+/// Add the token, MFA, refresh, and revoke paths to the token middleware's
+/// `public_paths`; keep logout protected so its access token can be revoked.
+pub fn auth_routes(config: &AuthConfig) -> Router {
+    let routes = &config.routes;
+    let path = |suffix: &str| route_path(&routes.prefix, suffix);
+
+    let mut router = Router::new();
+    if routes.login_enabled {
+        router = router
+            .route(&path("/token"), post(token))
+            .route(&path("/token/mfa"), post(token_mfa));
+        #[cfg(feature = "passkeys")]
+        {
+            router = router
+                .route(&path("/passkey/options"), post(passkey_options))
+                .route(&path("/passkey"), post(passkey_login));
+        }
+    }
+    if routes.refresh_enabled {
+        router = router.route(&path("/refresh"), post(refresh));
+    }
+    if routes.logout_enabled {
+        router = router.route(&path("/logout"), post(logout));
+    }
+    if routes.revoke_enabled {
+        router = router.route(&path("/revoke"), post(revoke));
+    }
+    router
+}
+
+/// Token issuance and revocation backends for [`auth_routes`]
+pub struct TokenEndpoints {
+    issuer: TokenIssuer,
+    #[cfg(feature = "login-lockout")]
+    lockout: Option<crate::lockout::LoginLockout>,
+    #[cfg(feature = "cache")]
+    revocation: Option<Arc<dyn crate::middleware::TokenRevocation>>,
+}
+
+impl TokenEndpoints {
+    /// Create the endpoints around a token issuer
+    pub fn new(issuer: TokenIssuer) -> Self {
+        Self {
+            issuer,
+            #[cfg(feature = "login-lockout")]
+            lockout: None,
+            #[cfg(feature = "cache")]
+            revocation: None,
+        }
+    }
+
+    /// Enforce login lockout on the token endpoints
+    #[cfg(feature = "login-lockout")]
+    pub fn with_lockout(mut self, lockout: crate::lockout::LoginLockout) -> Self {
+        self.lockout = Some(lockout);
+        self
+    }
+
+    /// Revoke access tokens by `jti` on logout and revoke
+    ///
+    /// Use the same store as the token middleware's `with_revocation`.
+    #[cfg(feature = "cache")]
+    pub fn with_revocation<R: crate::middleware::TokenRevocation + 'static>(
+        mut self,
+        revocation: R,
+    ) -> Self {
+        self.revocation = Some(Arc::new(revocation));
+        self
+    }
+
+    #[cfg_attr(not(feature = "login-lockout"), allow(unused_variables))]
+    async fn check_lockout(&self, identity: &str) -> Result<(), Error> {
+        #[cfg(feature = "login-lockout")]
+        if let Some(lockout) = &self.lockout {
+            let status = lockout.check(identity).await?;
+            if status.locked {
+                return Err(Error::AccountLocked {
+                    message: format!(
+                        "Account locked. Try again in {} seconds",
+                        status.lockout_remaining_secs
+                    ),
+                    retry_after_secs: status.lockout_remaining_secs,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "login-lockout"), allow(unused_variables))]
+    async fn record_failure(&self, identity: &str) -> Result<(), Error> {
+        #[cfg(feature = "login-lockout")]
+        if let Some(lockout) = &self.lockout {
+            let status = lockout.record_failure(identity).await?;
+            if status.delay_ms > 0 {
+                tokio::time::sleep(std::time::Duration::from_millis(status.delay_ms)).await;
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg_attr(not(feature = "login-lockout"), allow(unused_variables))]
+    async fn record_success(&self, identity: &str) -> Result<(), Error> {
+        #[cfg(feature = "login-lockout")]
+        if let Some(lockout) = &self.lockout {
+            lockout.record_success(identity).await?;
+        }
+        Ok(())
+    }
+
+    /// Revoke an access token by `jti` until it would have expired anyway
+    ///
+    /// Returns whether anything was revoked.
+    #[cfg_attr(not(feature = "cache"), allow(unused_variables))]
+    async fn revoke_access_token(&self, claims: &Claims) -> Result<bool, Error> {
+        #[cfg(feature = "cache")]
+        if let (Some(revocation), Some(jti)) = (&self.revocation, &claims.jti) {
+            let ttl_secs = (claims.exp - Utc::now().timestamp()).max(1) as u64;
+            revocation.revoke(jti, ttl_secs).await?;
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
+
+// ============================================================================
+// Request/Response types
+// ============================================================================
+
+/// Request body for password login
+#[derive(Debug, Deserialize)]
+pub struct PasswordLoginRequest {
+    pub email: String,
+    pub password: String,
+}
+
+/// Request body for completing an MFA challenge
+#[derive(Debug, Deserialize)]
+pub struct MfaLoginRequest {
+    /// The challenge from the password step
+    pub challenge: String,
+    /// A TOTP code or recovery code
+    pub code: String,
+}
+
+/// Response when the password was right but a second factor is required
+#[derive(Debug, Serialize)]
+pub struct MfaRequiredResponse {
+    /// Always `true`
+    pub mfa_required: bool,
+    /// Present this to `POST {prefix}/token/mfa`, or to `POST /auth/mfa`
+    /// after an OAuth login
+    pub challenge: String,
+    /// When the challenge stops being accepted
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Request body for starting a passkey login
+#[cfg(feature = "passkeys")]
+#[derive(Debug, Default, Deserialize)]
+pub struct PasskeyOptionsRequest {
+    /// Limits the login to this account's passkeys; omit for usernameless login
+    #[serde(default)]
+    pub email: Option<String>,
+}
+
+/// Request body for refreshing a token pair
+#[derive(Debug, Deserialize)]
+pub struct RefreshRequest {
+    pub refresh_token: String,
+}
+
+/// Request body for logging out
+#[derive(Debug, Default, Deserialize)]
+pub struct LogoutRequest {
+    /// The session's refresh token; its family is revoked
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+}
+
+/// Request body for revoking a token (RFC 7009)
+#[derive(Debug, Deserialize)]
+pub struct RevokeRequest {
+    pub token: String,
+    /// `refresh_token` or `access_token`; only a lookup hint
+    #[serde(default)]
+    pub token_type_hint: Option<String>,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+async fn token(
+    Extension(svc): Extension<Arc<AccountService>>,
+    Extension(endpoints): Extension<Arc<TokenEndpoints>>,
+    caller: Caller,
+    Json(body): Json<PasswordLoginRequest>,
+) -> Result<Response, Error> {
+    let email = body.email.trim().to_lowercase();
+    endpoints.check_lockout(&email).await?;
+
+    match svc.authenticate(&email, &body.password).await {
+        Ok(AuthOutcome::Authenticated(account)) => {
+            endpoints.record_success(&email).await?;
+            let pair = login_success(&endpoints, &caller, &account, "password").await?;
+            Ok(Json(pair).into_response())
+        }
+        Ok(AuthOutcome::MfaRequired(challenge)) => Ok(Json(MfaRequiredResponse {
+            mfa_required: true,
+            challenge: challenge.token,
+            expires_at: challenge.expires_at,
+        })
+        .into_response()),
+        Err(e) => Err(login_failure(&endpoints, &caller, Some(&email), "password", e).await),
+    }
+}
+
+async fn token_mfa(
+    Extension(svc): Extension<Arc<AccountService>>,
+    Extension(endpoints): Extension<Arc<TokenEndpoints>>,
+    caller: Caller,
+    Json(body): Json<MfaLoginRequest>,
+) -> Result<Response, Error> {
+    let account_id = svc.challenge_account_id(&body.challenge)?;
+    let email = svc
+        .get_account(&account_id)
+        .await?
+        .ok_or(AccountError::InvalidCredentials)?
+        .email;
+    endpoints.check_lockout(&email).await?;
+
+    match svc.verify_mfa(&body.challenge, &body.code).await {
+        Ok(account) => {
+            endpoints.record_success(&email).await?;
+            let pair = login_success(&endpoints, &caller, &account, "password+mfa").await?;
+            Ok(Json(pair).into_response())
+        }
+        Err(e) => Err(login_failure(&endpoints, &caller, Some(&email), "password+mfa", e).await),
+    }
+}
+
+#[cfg(feature = "passkeys")]
+async fn passkey_options(
+    Extension(svc): Extension<Arc<AccountService>>,
+    Challenges(challenges): Challenges,
+    body: Option<Json<PasskeyOptionsRequest>>,
+) -> Result<Json<PasskeyRequestOptions>, Error> {
+    let body = body.map(|Json(body)| body).unwrap_or_default();
+    let options = svc
+        .start_passkey_login(body.email.as_deref(), challenges.as_ref())
+        .await?;
+    Ok(Json(options))
+}
+
+#[cfg(feature = "passkeys")]
+async fn passkey_login(
+    Extension(svc): Extension<Arc<AccountService>>,
+    Extension(endpoints): Extension<Arc<TokenEndpoints>>,
+    Challenges(challenges): Challenges,
+    caller: Caller,
+    Json(body): Json<PasskeyAssertion>,
+) -> Result<Response, Error> {
+    // Lockout is per email, so it applies once the passkey's owner is known
+    let email = svc.passkey_account(&body.id).await?.map(|a| a.email);
+    if let Some(email) = &email {
+        endpoints.check_lockout(email).await?;
+    }
+
+    match svc.finish_passkey_login(&body, challenges.as_ref()).await {
+        Ok(account) => {
+            endpoints.record_success(&account.email).await?;
+            let pair = login_success(&endpoints, &caller, &account, "passkey").await?;
+            Ok(Json(pair).into_response())
+        }
+        Err(e) => Err(login_failure(&endpoints, &caller, email.as_deref(), "passkey", e).await),
+    }
+}
+
+async fn refresh(
+    Extension(svc): Extension<Arc<AccountService>>,
+    Extension(endpoints): Extension<Arc<TokenEndpoints>>,
+    caller: Caller,
+    Json(body): Json<RefreshRequest>,
+) -> Result<Response, Error> {
+    let issuer = &endpoints.issuer;
+    let current = match issuer.lookup_refresh(&body.refresh_token).await? {
+        RefreshLookup::Valid(current) => current,
+        RefreshLookup::Reused(data) => {
+            caller
+                .audit
+                .emit(
+                    AuthStep::RefreshReuse,
+                    Some(data.user_id.as_str()),
+                    serde_json::json!({
+                        "reason": "refresh_token_reuse",
+                        "family_id": data.family_id,
+                    }),
+                )
+                .await;
+            return Err(invalid_refresh_token());
+        }
+        RefreshLookup::Revoked(data) | RefreshLookup::Expired(data) => {
+            let reason = if data.is_revoked {
+                "revoked"
+            } else {
+                "expired"
+            };
+            caller
+                .audit
+                .emit(
+                    AuthStep::RefreshFailed,
+                    Some(data.user_id.as_str()),
+                    serde_json::json!({ "reason": reason }),
+                )
+                .await;
+            return Err(invalid_refresh_token());
+        }
+        RefreshLookup::Unknown => {
+            caller
+                .audit
+                .emit(
+                    AuthStep::RefreshFailed,
+                    None,
+                    serde_json::json!({ "reason": "unknown" }),
+                )
+                .await;
+            return Err(invalid_refresh_token());
+        }
+    };
+
+    // Re-read the account so disabled accounts and role changes take effect
+    let account = match current.user_id.strip_prefix("user:") {
+        Some(account_id) => svc.get_account(account_id).await?,
+        None => None,
+    };
+    let account = match account.map(|a| super::ensure_active(&a).map(|_| a)) {
+        Some(Ok(account)) => account,
+        other => {
+            issuer
+                .refresh_storage()
+                .revoke_family(&current.family_id)
+                .await?;
+            let reason = match other {
+                Some(Err(e)) => e.to_string(),
+                _ => "account not found".to_string(),
+            };
+            caller
+                .audit
+                .emit(
+                    AuthStep::RefreshFailed,
+                    Some(current.user_id.as_str()),
+                    serde_json::json!({ "reason": reason, "family_id": current.family_id }),
+                )
+                .await;
+            return Err(invalid_refresh_token());
+        }
+    };
+
+    let claims = account_claims(&account)?;
+    let pair = issuer
+        .refresh(&body.refresh_token, &current, &claims, &caller.metadata)
+        .await?;
+
+    caller
+        .audit
+        .emit(
+            AuthStep::Refresh,
+            Some(account.id.as_str()),
+            serde_json::json!({
+                "account_id": account.id.as_str(),
+                "family_id": current.family_id,
+            }),
+        )
+        .await;
+
+    Ok(Json(pair).into_response())
+}
+
+async fn logout(
+    Extension(endpoints): Extension<Arc<TokenEndpoints>>,
+    caller: Caller,
+    body: Option<Json<LogoutRequest>>,
+) -> Result<StatusCode, Error> {
+    let body = body.map(|Json(body)| body).unwrap_or_default();
+    if body.refresh_token.is_none() && caller.claims.is_none() {
+        return Err(Error::BadRequest(
+            "Logout requires an access token or a refresh token".to_string(),
+        ));
+    }
+
+    let mut subject = caller.claims.as_ref().map(|c| c.sub.clone());
+    let mut families_revoked = 0;
+    if let Some(refresh_token) = &body.refresh_token {
+        let storage = endpoints.issuer.refresh_storage();
+        if let Some(data) = storage.get(&refresh_token_id(refresh_token)).await? {
+            if subject.as_deref().is_some_and(|sub| sub != data.user_id) {
+                return Err(Error::Forbidden(
+                    "Refresh token belongs to a different user".to_string(),
+                ));
+            }
+            families_revoked = storage.revoke_family(&data.family_id).await?;
+            subject.get_or_insert(data.user_id);
+        }
+    }
+
+    let access_token_revoked = match &caller.claims {
+        Some(claims) => endpoints.revoke_access_token(claims).await?,
+        None => false,
+    };
+
+    caller
+        .audit
+        .emit(
+            AuthStep::Logout,
+            subject.as_deref(),
+            serde_json::json!({
+                "refresh_tokens_revoked": families_revoked,
+                "access_token_revoked": access_token_revoked,
+            }),
+        )
+        .await;
+
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn revoke(
+    Extension(endpoints): Extension<Arc<TokenEndpoints>>,
+    caller: Caller,
+    Json(body): Json<RevokeRequest>,
+) -> Result<StatusCode, Error> {
+    // An access token can only be revoked by its bearer, since only then
+    // are its claims (and `jti`) known here
+    let is_own_access_token = caller.bearer.as_deref() == Some(body.token.as_str());
+    let prefer_access = body.token_type_hint.as_deref() == Some("access_token");
+
+    if is_own_access_token && prefer_access {
+        if let Some(claims) = &caller.claims {
+            if endpoints.revoke_access_token(claims).await? {
+                emit_revoked(&caller, Some(claims.sub.as_str()), "access_token").await;
+            }
+            return Ok(StatusCode::OK);
+        }
+    }
+
+    let storage = endpoints.issuer.refresh_storage();
+    if let Some(data) = storage.get(&refresh_token_id(&body.token)).await? {
+        storage.revoke_family(&data.family_id).await?;
+        emit_revoked(&caller, Some(data.user_id.as_str()), "refresh_token").await;
+    } else if is_own_access_token {
+        if let Some(claims) = &caller.claims {
+            if endpoints.revoke_access_token(claims).await? {
+                emit_revoked(&caller, Some(claims.sub.as_str()), "access_token").await;
+            }
+        }
+    }
+
+    // RFC 7009 §2.2: invalid tokens don't produce an error
+    Ok(StatusCode::OK)
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// What the handlers need from the request besides its body
+struct Caller {
+    audit: AuthAudit,
+    metadata: RefreshTokenMetadata,
+    /// Claims set by the token middleware, when it covers the route
+    claims: Option<Claims>,
+    /// The raw bearer token those claims came from
+    bearer: Option<String>,
+}
+
+impl<S> FromRequestParts<S> for Caller
+where
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let context = parts.extensions.get::<RequestContext>();
+        let metadata = RefreshTokenMetadata {
+            user_agent: context.and_then(|c| c.user_agent.clone()),
+            ip_address: context.and_then(|c| c.ip).map(|ip| ip.to_string()),
+            ..Default::default()
+        };
+        let claims = parts.extensions.get::<Claims>().cloned();
+        let bearer = claims
+            .as_ref()
+            .and_then(|_| extract_token(&parts.headers).ok());
+
+        Ok(Self {
+            audit: AuthAudit::from_parts(parts),
+            metadata,
+            claims,
+            bearer,
+        })
+    }
+}
+
+/// Join the configured prefix and a route suffix
+///
+/// An empty or `/` prefix mounts the routes at the root.
+fn route_path(prefix: &str, suffix: &str) -> String {
+    format!("{}{}", prefix.trim_end_matches('/'), suffix)
+}
+
+/// Access token claims for an account
+pub(crate) fn account_claims(account: &Account) -> Result<Claims, Error> {
+    ClaimsBuilder::new()
+        .user(account.id.as_str())
+        .email(account.email.as_str())
+        .roles(account.roles.iter().cloned())
+        .build()
+}
+
+async fn login_success(
+    endpoints: &TokenEndpoints,
+    caller: &Caller,
+    account: &Account,
+    method: &str,
+) -> Result<TokenPair, Error> {
+    let claims = account_claims(account)?;
+    let pair = endpoints.issuer.issue(&claims, &caller.metadata).await?;
+
+    caller
+        .audit
+        .emit(
+            AuthStep::Success,
+            Some(account.id.as_str()),
+            serde_json::json!({
+                "method": method,
+                "account_id": account.id.as_str(),
+            }),
+        )
+        .await;
+
+    Ok(pair)
+}
+
+/// Record a rejected login and turn it into the response error
+///
+/// Unknown emails, wrong passwords or codes, rejected passkeys, and inactive
+/// accounts all look the same to the client; the audit log keeps the real
+/// reason. Without an email (an unknown passkey) nothing counts toward
+/// lockout.
+async fn login_failure(
+    endpoints: &TokenEndpoints,
+    caller: &Caller,
+    email: Option<&str>,
+    method: &str,
+    err: AccountError,
+) -> Error {
+    let rejected = matches!(
+        err,
+        AccountError::NotFound(_)
+            | AccountError::InvalidCredentials
+            | AccountError::AccountInactive { .. }
+    );
+    #[cfg(feature = "passkeys")]
+    let rejected = rejected || matches!(err, AccountError::Passkey(_));
+    if !rejected {
+        return err.into();
+    }
+
+    if let Some(email) = email {
+        if let Err(e) = endpoints.record_failure(email).await {
+            return e;
+        }
+    }
+
+    caller
+        .audit
+        .emit(
+            AuthStep::Failed,
+            email,
+            serde_json::json!({
+                "method": method,
+                "reason": err.to_string(),
+            }),
+        )
+        .await;
+
+    Error::Unauthorized("Invalid credentials".to_string())
+}
+
+fn invalid_refresh_token() -> Error {
+    Error::Unauthorized("Invalid refresh token".to_string())
+}
+
+async fn emit_revoked(caller: &Caller, subject: Option<&str>, token_type: &str) {
+    caller
+        .audit
+        .emit(
+            AuthStep::Revoked,
+            subject,
+            serde_json::json!({ "token_type": token_type }),
+        )
+        .await;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_path() {
+        assert_eq!(route_path("/auth", "/token"), "/auth/token");
+        assert_eq!(route_path("/api/auth/", "/refresh"), "/api/auth/refresh");
+        assert_eq!(route_path("", "/logout"), "/logout");
+        assert_eq!(route_path("/", "/revoke"), "/revoke");
+    }
+}
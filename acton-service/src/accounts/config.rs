@@ -27,6 +27,23 @@ pub struct AccountsConfig {
     /// Whether to emit audit events for account lifecycle changes
     #[serde(default = "default_true")]
     pub audit_events: bool,
+
+    /// Seconds a password reset token stays valid
+    #[serde(default = "default_password_reset_ttl")]
+    pub password_reset_ttl_secs: u64,
+
+    /// Seconds an email verification token stays valid
+    #[serde(default = "default_email_verification_ttl")]
+    pub email_verification_ttl_secs: u64,
+
+    /// TOTP multi-factor authentication settings
+    #[serde(default)]
+    pub mfa: MfaConfig,
+
+    /// WebAuthn passkey settings
+    #[cfg(feature = "passkeys")]
+    #[serde(default)]
+    pub passkeys: PasskeyConfig,
 }
 
 impl Default for AccountsConfig {
@@ -37,7 +54,108 @@ impl Default for AccountsConfig {
             inactivity_expiry_days: 0,
             unique_usernames: false,
             audit_events: true,
+            password_reset_ttl_secs: default_password_reset_ttl(),
+            email_verification_ttl_secs: default_email_verification_ttl(),
+            mfa: MfaConfig::default(),
+            #[cfg(feature = "passkeys")]
+            passkeys: PasskeyConfig::default(),
+        }
+    }
+}
+
+/// Configuration for TOTP multi-factor authentication
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct MfaConfig {
+    /// Issuer shown in authenticator apps
+    #[serde(default = "default_mfa_issuer")]
+    pub issuer: String,
+
+    /// Time steps of clock drift accepted either side of now
+    #[serde(default = "default_totp_skew")]
+    pub totp_skew_steps: u32,
+
+    /// Seconds an MFA challenge stays valid after the password check
+    #[serde(default = "default_challenge_ttl")]
+    pub challenge_ttl_secs: u64,
+
+    /// Wrong codes allowed per challenge before it stops being accepted
+    #[serde(default = "default_mfa_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Number of recovery codes issued at a time
+    #[serde(default = "default_recovery_codes")]
+    pub recovery_codes: usize,
+}
+
+impl Default for MfaConfig {
+    fn default() -> Self {
+        Self {
+            issuer: default_mfa_issuer(),
+            totp_skew_steps: default_totp_skew(),
+            challenge_ttl_secs: default_challenge_ttl(),
+            max_attempts: default_mfa_max_attempts(),
+            recovery_codes: default_recovery_codes(),
+        }
+    }
+}
+
+/// Configuration for WebAuthn passkeys
+#[cfg(feature = "passkeys")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[non_exhaustive]
+pub struct PasskeyConfig {
+    /// Relying party ID: the domain passkeys are scoped to
+    ///
+    /// Browsers only offer a passkey on this domain and its subdomains, so
+    /// changing it orphans every registered passkey.
+    #[serde(default = "default_rp_id")]
+    pub rp_id: String,
+
+    /// Relying party name shown in the browser's passkey prompt
+    #[serde(default = "default_rp_name")]
+    pub rp_name: String,
+
+    /// Origins allowed to run ceremonies, e.g. `https://app.example.com`
+    ///
+    /// Empty means `https://{rp_id}` only.
+    #[serde(default)]
+    pub origins: Vec<String>,
+
+    /// Seconds a registration or login challenge stays valid
+    #[serde(default = "default_challenge_ttl")]
+    pub challenge_ttl_secs: u64,
+
+    /// Whether the authenticator must verify the user (PIN or biometric)
+    ///
+    /// When `false`, user presence (a touch) is enough.
+    #[serde(default = "default_true")]
+    pub require_user_verification: bool,
+}
+
+#[cfg(feature = "passkeys")]
+impl Default for PasskeyConfig {
+    fn default() -> Self {
+        Self {
+            rp_id: default_rp_id(),
+            rp_name: default_rp_name(),
+            origins: Vec::new(),
+            challenge_ttl_secs: default_challenge_ttl(),
+            require_user_verification: true,
+        }
+    }
+}
+
+#[cfg(feature = "passkeys")]
+impl PasskeyConfig {
+    /// Whether a ceremony from `origin` may be accepted
+    pub fn allows_origin(&self, origin: &str) -> bool {
+        if self.origins.is_empty() {
+            return origin == format!("https://{}", self.rp_id);
         }
+        self.origins
+            .iter()
+            .any(|o| o.trim_end_matches('/') == origin)
     }
 }
 
@@ -45,6 +163,44 @@ fn default_status() -> AccountStatus {
     AccountStatus::PendingVerification
 }
 
+fn default_mfa_issuer() -> String {
+    "acton-service".to_string()
+}
+
+#[cfg(feature = "passkeys")]
+fn default_rp_id() -> String {
+    "localhost".to_string()
+}
+
+#[cfg(feature = "passkeys")]
+fn default_rp_name() -> String {
+    "acton-service".to_string()
+}
+
+fn default_totp_skew() -> u32 {
+    1
+}
+
+fn default_challenge_ttl() -> u64 {
+    300
+}
+
+fn default_mfa_max_attempts() -> u32 {
+    5
+}
+
+fn default_recovery_codes() -> usize {
+    10
+}
+
+fn default_password_reset_ttl() -> u64 {
+    3600
+}
+
+fn default_email_verification_ttl() -> u64 {
+    86400
+}
+
 fn default_true() -> bool {
     true
 }
@@ -85,5 +241,26 @@ mod tests {
         let config: AccountsConfig = serde_json::from_str(json).unwrap();
         assert_eq!(config.default_status, AccountStatus::PendingVerification);
         assert!(config.require_email_verification);
+        assert_eq!(config.password_reset_ttl_secs, 3600);
+        assert_eq!(config.email_verification_ttl_secs, 86400);
+        assert_eq!(config.mfa.totp_skew_steps, 1);
+        assert_eq!(config.mfa.challenge_ttl_secs, 300);
+        assert_eq!(config.mfa.recovery_codes, 10);
+    }
+
+    #[cfg(feature = "passkeys")]
+    #[test]
+    fn test_passkey_origins() {
+        let mut config = PasskeyConfig {
+            rp_id: "example.com".to_string(),
+            ..Default::default()
+        };
+        assert!(config.allows_origin("https://example.com"));
+        assert!(!config.allows_origin("http://example.com"));
+        assert!(!config.allows_origin("https://app.example.com"));
+
+        config.origins = vec!["https://app.example.com/".to_string()];
+        assert!(config.allows_origin("https://app.example.com"));
+        assert!(!config.allows_origin("https://example.com"));
     }
 }
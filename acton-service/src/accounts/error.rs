@@ -1,6 +1,7 @@
 //! Account-specific error types
 
 use super::types::AccountStatus;
+use crate::auth::PasswordPolicyError;
 
 /// Errors specific to account management operations
 #[derive(Debug, thiserror::Error)]
@@ -40,6 +41,10 @@ pub enum AccountError {
     #[error("validation error: {0}")]
     Validation(String),
 
+    /// Password rejected by the password policy or history
+    #[error("password rejected: {0}")]
+    PasswordPolicy(#[from] PasswordPolicyError),
+
     /// Storage backend error
     #[error("storage error: {0}")]
     Storage(String),
@@ -47,4 +52,17 @@ pub enum AccountError {
     /// Invalid account ID format
     #[error("invalid account ID: {0}")]
     InvalidId(String),
+
+    /// Password reset or email verification token is unknown, used, or expired
+    #[error("invalid or expired token")]
+    InvalidToken,
+
+    /// MFA is misconfigured or its stored state is unusable
+    #[error("MFA error: {0}")]
+    Mfa(String),
+
+    /// A passkey ceremony failed verification
+    #[cfg(feature = "passkeys")]
+    #[error("passkey rejected: {0}")]
+    Passkey(String),
 }
@@ -0,0 +1,121 @@
+//! Audit events emitted by the pre-built authentication routes
+//!
+//! The login, token, and OAuth handlers record their own audit events with
+//! the caller's request source. Without the `audit` feature, or when no
+//! `AuditLogger` extension is installed, emitting is a no-op.
+
+use axum::http::request::Parts;
+
+#[cfg(feature = "audit")]
+use crate::middleware::request_context::RequestContext;
+
+/// The authentication step being recorded
+pub(crate) enum AuthStep {
+    /// OAuth login started
+    #[cfg(feature = "oauth")]
+    Authorize,
+    /// OAuth provider callback succeeded
+    #[cfg(feature = "oauth")]
+    Callback,
+    /// OAuth provider callback failed
+    #[cfg(feature = "oauth")]
+    CallbackFailed,
+    /// Login completed
+    Success,
+    /// Login rejected
+    Failed,
+    /// Refresh token exchanged
+    Refresh,
+    /// Refresh token refused (unknown, expired, or account inactive)
+    RefreshFailed,
+    /// A rotated refresh token was presented again
+    RefreshReuse,
+    /// Session ended by its holder
+    Logout,
+    /// Token revoked by its holder
+    Revoked,
+}
+
+/// Audit emitter captured from the request
+pub(crate) struct AuthAudit {
+    #[cfg(feature = "audit")]
+    inner: Option<(crate::audit::AuditLogger, crate::audit::event::AuditSource)>,
+}
+
+impl AuthAudit {
+    #[cfg_attr(not(feature = "audit"), allow(unused_variables))]
+    pub(crate) fn from_parts(parts: &Parts) -> Self {
+        #[cfg(feature = "audit")]
+        {
+            let inner = parts
+                .extensions
+                .get::<crate::audit::AuditLogger>()
+                .cloned()
+                .map(|logger| {
+                    let source = parts
+                        .extensions
+                        .get::<RequestContext>()
+                        .map(RequestContext::audit_source)
+                        .unwrap_or_else(|| {
+                            crate::middleware::request_context::audit_source_from_headers(
+                                &parts.headers,
+                            )
+                        });
+                    (logger, source)
+                });
+            Self { inner }
+        }
+        #[cfg(not(feature = "audit"))]
+        Self {}
+    }
+
+    #[cfg_attr(not(feature = "audit"), allow(unused_variables))]
+    pub(crate) async fn emit(
+        &self,
+        step: AuthStep,
+        subject: Option<&str>,
+        metadata: serde_json::Value,
+    ) {
+        #[cfg(feature = "audit")]
+        if let Some((logger, source)) = &self.inner {
+            use crate::audit::event::{AuditEvent, AuditEventKind, AuditSeverity};
+
+            if !logger.config().audit_auth_events {
+                return;
+            }
+            let (kind, severity) = match step {
+                #[cfg(feature = "oauth")]
+                AuthStep::Authorize => (
+                    AuditEventKind::AuthOAuthAuthorize,
+                    AuditSeverity::Informational,
+                ),
+                #[cfg(feature = "oauth")]
+                AuthStep::Callback => (AuditEventKind::AuthOAuthCallback, AuditSeverity::Notice),
+                #[cfg(feature = "oauth")]
+                AuthStep::CallbackFailed => {
+                    (AuditEventKind::AuthOAuthCallback, AuditSeverity::Warning)
+                }
+                AuthStep::Success => (AuditEventKind::AuthLoginSuccess, AuditSeverity::Notice),
+                AuthStep::Failed => (AuditEventKind::AuthLoginFailed, AuditSeverity::Warning),
+                AuthStep::Refresh => (
+                    AuditEventKind::AuthTokenRefresh,
+                    AuditSeverity::Informational,
+                ),
+                AuthStep::RefreshFailed => {
+                    (AuditEventKind::AuthTokenRefresh, AuditSeverity::Warning)
+                }
+                AuthStep::RefreshReuse => {
+                    (AuditEventKind::AuthTokenRevoked, AuditSeverity::Critical)
+                }
+                AuthStep::Logout => (AuditEventKind::AuthLogout, AuditSeverity::Informational),
+                AuthStep::Revoked => (AuditEventKind::AuthTokenRevoked, AuditSeverity::Notice),
+            };
+            let mut source = source.clone();
+            source.subject = subject.map(str::to_string);
+            let event = AuditEvent::new(kind, severity, logger.service_name().to_string())
+                .with_source(source)
+                .with_metadata(metadata);
+            logger.log(event).await;
+        }
+    }
+}
@@ -3,6 +3,15 @@
 //! Requires feature: `account-handlers`
 //!
 //! These handlers use `AccountService` from `Extension` or `State`.
+//!
+//! The password reset and email verification routes are self-service: they
+//! take an email address or a token rather than an account ID, and the
+//! request routes answer `202 Accepted` whether or not the address is known.
+//!
+//! With the `passkeys` feature, `/accounts/{id}/passkeys` registers, lists,
+//! and removes an account's WebAuthn passkeys. Registration keeps its
+//! challenge in an `Extension<Arc<dyn PasskeyChallengeStore>>`, or in the
+//! session when a session layer is installed.
 
 use axum::{
     extract::{Path, Query},
@@ -14,6 +23,8 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+#[cfg(feature = "passkeys")]
+use super::passkeys::{Challenges, PasskeyRegistration};
 use super::{AccountError, AccountService, AccountStatus, CreateAccount, UpdateAccount};
 
 /// Build the account management routes
@@ -25,8 +36,18 @@ use super::{AccountError, AccountService, AccountStatus, CreateAccount, UpdateAc
 ///     .layer(Extension(account_service));
 /// ```
 pub fn account_routes() -> Router {
-    Router::new()
+    let router = Router::new()
         .route("/accounts", post(create_account).get(list_accounts))
+        .route("/accounts/password-reset", post(request_password_reset))
+        .route("/accounts/password-reset/confirm", post(reset_password))
+        .route(
+            "/accounts/email-verification",
+            post(request_email_verification),
+        )
+        .route(
+            "/accounts/email-verification/confirm",
+            post(confirm_email_verification),
+        )
         .route(
             "/accounts/{id}",
             get(get_account)
@@ -39,6 +60,29 @@ pub fn account_routes() -> Router {
         .route("/accounts/{id}/unlock", post(unlock_account))
         .route("/accounts/{id}/verify-email", post(verify_email))
         .route("/accounts/{id}/change-password", post(change_password))
+        .route(
+            "/accounts/{id}/mfa/totp",
+            post(enroll_totp).delete(disable_totp),
+        )
+        .route("/accounts/{id}/mfa/totp/confirm", post(confirm_totp))
+        .route(
+            "/accounts/{id}/mfa/recovery-codes",
+            post(regenerate_recovery_codes),
+        );
+
+    #[cfg(feature = "passkeys")]
+    let router = router
+        .route(
+            "/accounts/{id}/passkeys",
+            post(register_passkey).get(list_passkeys),
+        )
+        .route("/accounts/{id}/passkeys/options", post(passkey_options))
+        .route(
+            "/accounts/{id}/passkeys/{credential_id}",
+            axum::routing::delete(remove_passkey),
+        );
+
+    router
 }
 
 // ============================================================================
@@ -68,6 +112,48 @@ pub struct ChangePasswordRequest {
     pub new_password: String,
 }
 
+/// Request body for starting password reset or email verification
+#[derive(Debug, Deserialize)]
+pub struct EmailRequest {
+    pub email: String,
+}
+
+/// Request body for completing a password reset
+#[derive(Debug, Deserialize)]
+pub struct ResetPasswordRequest {
+    pub token: String,
+    pub new_password: String,
+}
+
+/// Request body for completing email verification
+#[derive(Debug, Deserialize)]
+pub struct TokenRequest {
+    pub token: String,
+}
+
+/// Request body for confirming TOTP enrollment
+#[derive(Debug, Deserialize)]
+pub struct ConfirmTotpRequest {
+    pub code: String,
+}
+
+/// Recovery codes, shown once
+#[derive(Debug, Serialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+/// Request body for completing passkey registration
+#[cfg(feature = "passkeys")]
+#[derive(Debug, Deserialize)]
+pub struct RegisterPasskeyRequest {
+    /// Label for the passkey, e.g. "Work laptop"
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The browser's `PublicKeyCredential.toJSON()` output
+    pub credential: PasskeyRegistration,
+}
+
 /// Account list response
 #[derive(Debug, Serialize)]
 pub struct AccountListResponse {
@@ -192,3 +278,111 @@ async fn change_password(
     svc.change_password(&id, &body.new_password).await?;
     Ok(StatusCode::NO_CONTENT)
 }
+
+async fn request_password_reset(
+    Extension(svc): Extension<Arc<AccountService>>,
+    Json(body): Json<EmailRequest>,
+) -> Result<impl IntoResponse, crate::error::Error> {
+    svc.request_password_reset(&body.email).await?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn reset_password(
+    Extension(svc): Extension<Arc<AccountService>>,
+    Json(body): Json<ResetPasswordRequest>,
+) -> Result<impl IntoResponse, crate::error::Error> {
+    svc.reset_password(&body.token, &body.new_password).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn request_email_verification(
+    Extension(svc): Extension<Arc<AccountService>>,
+    Json(body): Json<EmailRequest>,
+) -> Result<impl IntoResponse, crate::error::Error> {
+    svc.request_email_verification(&body.email).await?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn confirm_email_verification(
+    Extension(svc): Extension<Arc<AccountService>>,
+    Json(body): Json<TokenRequest>,
+) -> Result<impl IntoResponse, crate::error::Error> {
+    svc.confirm_email_verification(&body.token).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn enroll_totp(
+    Extension(svc): Extension<Arc<AccountService>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, crate::error::Error> {
+    let setup = svc.enroll_totp(&id).await?;
+    Ok((StatusCode::CREATED, Json(setup)))
+}
+
+async fn confirm_totp(
+    Extension(svc): Extension<Arc<AccountService>>,
+    Path(id): Path<String>,
+    Json(body): Json<ConfirmTotpRequest>,
+) -> Result<impl IntoResponse, crate::error::Error> {
+    let recovery_codes = svc.confirm_totp(&id, &body.code).await?;
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
+
+async fn disable_totp(
+    Extension(svc): Extension<Arc<AccountService>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, crate::error::Error> {
+    svc.disable_totp(&id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn regenerate_recovery_codes(
+    Extension(svc): Extension<Arc<AccountService>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, crate::error::Error> {
+    let recovery_codes = svc.regenerate_recovery_codes(&id).await?;
+    Ok(Json(RecoveryCodesResponse { recovery_codes }))
+}
+
+#[cfg(feature = "passkeys")]
+async fn passkey_options(
+    Extension(svc): Extension<Arc<AccountService>>,
+    Path(id): Path<String>,
+    Challenges(challenges): Challenges,
+) -> Result<impl IntoResponse, crate::error::Error> {
+    let options = svc
+        .start_passkey_registration(&id, challenges.as_ref())
+        .await?;
+    Ok(Json(options))
+}
+
+#[cfg(feature = "passkeys")]
+async fn register_passkey(
+    Extension(svc): Extension<Arc<AccountService>>,
+    Path(id): Path<String>,
+    Challenges(challenges): Challenges,
+    Json(body): Json<RegisterPasskeyRequest>,
+) -> Result<impl IntoResponse, crate::error::Error> {
+    let credential = svc
+        .finish_passkey_registration(&id, &body.credential, body.name, challenges.as_ref())
+        .await?;
+    Ok((StatusCode::CREATED, Json(credential)))
+}
+
+#[cfg(feature = "passkeys")]
+async fn list_passkeys(
+    Extension(svc): Extension<Arc<AccountService>>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, crate::error::Error> {
+    let passkeys = svc.list_passkeys(&id).await?;
+    Ok(Json(passkeys))
+}
+
+#[cfg(feature = "passkeys")]
+async fn remove_passkey(
+    Extension(svc): Extension<Arc<AccountService>>,
+    Path((id, credential_id)): Path<(String, String)>,
+) -> Result<impl IntoResponse, crate::error::Error> {
+    svc.remove_passkey(&id, &credential_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,473 @@
+//! TOTP multi-factor authentication primitives
+//!
+//! RFC 6238 TOTP (HMAC-SHA1, 6 digits, 30-second steps), the `otpauth://`
+//! URI authenticator apps scan, and single-use recovery codes.
+//!
+//! [`MfaSealer`] uses PASETO v4.local under the service's MFA key for two
+//! jobs: encrypting TOTP secrets before they reach account storage, and
+//! carrying the short-lived challenge between the password check and the
+//! second factor. Both are bound to the account ID and tagged with a purpose,
+//! so neither can be swapped between accounts or presented as the other.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, PoisonError};
+
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
+use rusty_paseto::prelude::*;
+use sha1::Sha1;
+
+use super::error::AccountError;
+
+/// Seconds per TOTP time step
+const TOTP_PERIOD_SECS: i64 = 30;
+
+/// Digits per TOTP code
+const TOTP_DIGITS: usize = 6;
+
+/// 160-bit secrets, as RFC 4226 recommends for HMAC-SHA1
+const SECRET_LEN: usize = 20;
+
+const BASE32_ALPHABET: &[u8; 32] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZ234567";
+
+const PURPOSE_SECRET: &str = "totp_secret";
+const PURPOSE_CHALLENGE: &str = "mfa_challenge";
+
+/// Generate a new TOTP secret, base32-encoded
+pub(crate) fn generate_secret() -> String {
+    let bytes: [u8; SECRET_LEN] = rand::random();
+    base32_encode(&bytes)
+}
+
+/// The TOTP time step containing `at`
+pub(crate) fn time_step(at: DateTime<Utc>) -> i64 {
+    at.timestamp().div_euclid(TOTP_PERIOD_SECS)
+}
+
+/// The code for a time step (RFC 6238 with RFC 4226 dynamic truncation)
+pub(crate) fn totp_code(secret: &[u8], step: i64) -> String {
+    let mut mac = Hmac::<Sha1>::new_from_slice(secret).expect("HMAC accepts any key length");
+    mac.update(&step.to_be_bytes());
+    let digest = mac.finalize().into_bytes();
+
+    let offset = (digest[digest.len() - 1] & 0x0f) as usize;
+    let binary = u32::from_be_bytes([
+        digest[offset] & 0x7f,
+        digest[offset + 1],
+        digest[offset + 2],
+        digest[offset + 3],
+    ]);
+    format!(
+        "{:0width$}",
+        binary % 10u32.pow(TOTP_DIGITS as u32),
+        width = TOTP_DIGITS
+    )
+}
+
+/// Whether the input has the shape of a TOTP code (rather than a recovery code)
+pub(crate) fn is_totp_code(code: &str) -> bool {
+    code.len() == TOTP_DIGITS && code.bytes().all(|b| b.is_ascii_digit())
+}
+
+/// Find the time step within `skew_steps` of `now` whose code matches
+///
+/// Every candidate step is compared so timing doesn't reveal which one
+/// matched. The caller must still reject steps at or before the last one
+/// accepted, or a code could be replayed within its window.
+pub(crate) fn verify_totp(
+    secret: &[u8],
+    code: &str,
+    now: DateTime<Utc>,
+    skew_steps: u32,
+) -> Option<i64> {
+    let code = code.trim();
+    if !is_totp_code(code) {
+        return None;
+    }
+
+    let current = time_step(now);
+    let skew = i64::from(skew_steps);
+    let mut matched = None;
+    for step in (current - skew)..=(current + skew) {
+        if constant_time_compare(&totp_code(secret, step), code) && matched.is_none() {
+            matched = Some(step);
+        }
+    }
+    matched
+}
+
+/// The `otpauth://` URI authenticator apps import (usually via QR code)
+pub(crate) fn otpauth_uri(issuer: &str, account_name: &str, secret: &str) -> String {
+    format!(
+        "otpauth://totp/{}:{}?secret={}&issuer={}&algorithm=SHA1&digits={}&period={}",
+        percent_encode(issuer),
+        percent_encode(account_name),
+        secret,
+        percent_encode(issuer),
+        TOTP_DIGITS,
+        TOTP_PERIOD_SECS
+    )
+}
+
+/// Generate `count` recovery codes formatted as `xxxxx-xxxxx`
+pub(crate) fn generate_recovery_codes(count: usize) -> Vec<String> {
+    (0..count)
+        .map(|_| {
+            let bytes: [u8; 10] = rand::random();
+            // 256 is a multiple of 32, so the modulo keeps characters uniform
+            let chars: String = bytes
+                .iter()
+                .map(|b| BASE32_ALPHABET[(*b % 32) as usize].to_ascii_lowercase() as char)
+                .collect();
+            format!("{}-{}", &chars[..5], &chars[5..])
+        })
+        .collect()
+}
+
+/// The stored form of a recovery code (BLAKE3 of the normalized code)
+///
+/// Case, whitespace, and the dash are ignored so users can type the code
+/// however they copied it.
+pub(crate) fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    blake3::hash(normalized.as_bytes()).to_hex().to_string()
+}
+
+/// Seals TOTP secrets and MFA challenges with the service's MFA key
+#[derive(Clone)]
+pub(crate) struct MfaSealer {
+    key: [u8; 32],
+}
+
+impl MfaSealer {
+    pub(crate) fn new(key: [u8; 32]) -> Self {
+        Self { key }
+    }
+
+    /// Encrypt a base32 TOTP secret for storage
+    pub(crate) fn seal_secret(
+        &self,
+        account_id: &str,
+        secret: &str,
+    ) -> Result<String, AccountError> {
+        self.seal(PURPOSE_SECRET, account_id, Some(secret), None)
+    }
+
+    /// Decrypt a stored TOTP secret to its raw bytes
+    pub(crate) fn open_secret(
+        &self,
+        account_id: &str,
+        sealed: &str,
+    ) -> Result<Vec<u8>, AccountError> {
+        let claims = self
+            .open(PURPOSE_SECRET, sealed)
+            .filter(|claims| claims.get("sub").and_then(|v| v.as_str()) == Some(account_id))
+            .ok_or_else(|| AccountError::Mfa("stored TOTP secret could not be decrypted".into()))?;
+
+        claims
+            .get("value")
+            .and_then(|v| v.as_str())
+            .and_then(base32_decode)
+            .ok_or_else(|| AccountError::Mfa("stored TOTP secret is malformed".into()))
+    }
+
+    /// Issue a challenge token for an account that passed the password check
+    pub(crate) fn issue_challenge(
+        &self,
+        account_id: &str,
+        expires_at: DateTime<Utc>,
+    ) -> Result<String, AccountError> {
+        self.seal(PURPOSE_CHALLENGE, account_id, None, Some(expires_at))
+    }
+
+    /// The account ID from a valid, unexpired challenge token
+    pub(crate) fn open_challenge(&self, token: &str) -> Result<String, AccountError> {
+        self.open(PURPOSE_CHALLENGE, token)
+            .and_then(|claims| {
+                claims
+                    .get("sub")
+                    .and_then(|v| v.as_str())
+                    .map(str::to_string)
+            })
+            .ok_or(AccountError::InvalidCredentials)
+    }
+
+    fn seal(
+        &self,
+        purpose: &str,
+        account_id: &str,
+        value: Option<&str>,
+        expires_at: Option<DateTime<Utc>>,
+    ) -> Result<String, AccountError> {
+        let key = PasetoSymmetricKey::<V4, Local>::from(Key::from(&self.key));
+        let exp = expires_at.map(|t| t.to_rfc3339());
+
+        let mut builder = PasetoBuilder::<V4, Local>::default();
+        builder.set_claim(SubjectClaim::from(account_id));
+        builder.set_claim(
+            CustomClaim::try_from(("purpose", purpose))
+                .map_err(|e| AccountError::Mfa(format!("invalid purpose claim: {}", e)))?,
+        );
+        if let Some(value) = value {
+            builder.set_claim(
+                CustomClaim::try_from(("value", value))
+                    .map_err(|e| AccountError::Mfa(format!("invalid value claim: {}", e)))?,
+            );
+        }
+        match exp.as_deref() {
+            Some(exp) => {
+                builder.set_claim(
+                    ExpirationClaim::try_from(exp)
+                        .map_err(|e| AccountError::Mfa(format!("invalid expiration: {}", e)))?,
+                );
+            }
+            // Sealed secrets live as long as the enrollment
+            None => {
+                builder.set_no_expiration_danger_acknowledged();
+            }
+        }
+
+        builder
+            .build(&key)
+            .map_err(|e| AccountError::Mfa(format!("failed to seal {}: {}", purpose, e)))
+    }
+
+    /// Decrypt and check expiry and purpose; `None` for anything invalid
+    fn open(&self, purpose: &str, token: &str) -> Option<serde_json::Value> {
+        let key = PasetoSymmetricKey::<V4, Local>::from(Key::from(&self.key));
+        let mut parser = PasetoParser::<V4, Local>::default();
+        // Sealed secrets carry no expiry (see `seal`); challenges must
+        if purpose == PURPOSE_SECRET {
+            parser.set_no_expiration_danger_acknowledged();
+        }
+        let claims = parser.parse(token, &key).ok()?;
+        (claims.get("purpose").and_then(|v| v.as_str()) == Some(purpose)).then_some(claims)
+    }
+}
+
+/// Wrong codes presented per MFA challenge
+///
+/// A challenge can be presented any number of times until it expires, so
+/// without a count one password check would buy unlimited guesses at a
+/// six-digit code. Counts are kept in process memory, keyed by a hash of the
+/// challenge, and dropped once the challenge can no longer be valid.
+#[derive(Default)]
+pub(crate) struct ChallengeAttempts {
+    failures: Mutex<HashMap<String, (u32, DateTime<Utc>)>>,
+}
+
+impl ChallengeAttempts {
+    /// Whether `challenge` has used up its `max_attempts`
+    pub(crate) fn exhausted(&self, challenge: &str, max_attempts: u32) -> bool {
+        let failures = self.failures.lock().unwrap_or_else(PoisonError::into_inner);
+        failures
+            .get(&challenge_digest(challenge))
+            .is_some_and(|(count, _)| *count >= max_attempts)
+    }
+
+    /// Count a wrong code, remembered until `forget_at`
+    pub(crate) fn record_failure(&self, challenge: &str, forget_at: DateTime<Utc>) {
+        let now = Utc::now();
+        let mut failures = self.failures.lock().unwrap_or_else(PoisonError::into_inner);
+        failures.retain(|_, (_, until)| *until > now);
+        failures
+            .entry(challenge_digest(challenge))
+            .or_insert((0, forget_at))
+            .0 += 1;
+    }
+}
+
+fn challenge_digest(challenge: &str) -> String {
+    blake3::hash(challenge.as_bytes()).to_hex().to_string()
+}
+
+/// RFC 4648 base32 without padding
+fn base32_encode(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity(bytes.len().div_ceil(5) * 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for &byte in bytes {
+        buffer = (buffer << 8) | u32::from(byte);
+        bits += 8;
+        while bits >= 5 {
+            bits -= 5;
+            out.push(BASE32_ALPHABET[((buffer >> bits) & 0x1f) as usize] as char);
+        }
+    }
+    if bits > 0 {
+        out.push(BASE32_ALPHABET[((buffer << (5 - bits)) & 0x1f) as usize] as char);
+    }
+    out
+}
+
+/// Decode RFC 4648 base32, ignoring padding and case
+fn base32_decode(encoded: &str) -> Option<Vec<u8>> {
+    let mut out = Vec::with_capacity(encoded.len() * 5 / 8);
+    let mut buffer: u32 = 0;
+    let mut bits = 0;
+    for c in encoded.trim_end_matches('=').bytes() {
+        let value = BASE32_ALPHABET
+            .iter()
+            .position(|&a| a == c.to_ascii_uppercase())? as u32;
+        buffer = (buffer << 5) | value;
+        bits += 5;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+        }
+    }
+    Some(out)
+}
+
+/// Percent-encode everything outside RFC 3986 unreserved characters
+fn percent_encode(value: &str) -> String {
+    value
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+/// Compare in time independent of where the inputs differ
+fn constant_time_compare(a: &str, b: &str) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.bytes()
+        .zip(b.bytes())
+        .fold(0u8, |acc, (x, y)| acc | (x ^ y))
+        == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    /// RFC 6238 Appendix B SHA1 seed
+    const RFC_SECRET: &[u8] = b"12345678901234567890";
+
+    #[test]
+    fn test_totp_rfc6238_vectors() {
+        // Appendix B lists 8-digit codes; the 6-digit code is their suffix
+        let vectors = [
+            (59, "287082"),
+            (1111111109, "081804"),
+            (1111111111, "050471"),
+            (1234567890, "005924"),
+            (2000000000, "279037"),
+        ];
+        for (timestamp, expected) in vectors {
+            let at = Utc.timestamp_opt(timestamp, 0).unwrap();
+            assert_eq!(totp_code(RFC_SECRET, time_step(at)), expected);
+        }
+    }
+
+    #[test]
+    fn test_verify_totp_accepts_drift_within_skew() {
+        let now = Utc.timestamp_opt(1111111109, 0).unwrap();
+        let previous = totp_code(RFC_SECRET, time_step(now) - 1);
+        let too_old = totp_code(RFC_SECRET, time_step(now) - 2);
+
+        assert_eq!(
+            verify_totp(RFC_SECRET, &previous, now, 1),
+            Some(time_step(now) - 1)
+        );
+        assert_eq!(verify_totp(RFC_SECRET, &previous, now, 0), None);
+        assert_eq!(verify_totp(RFC_SECRET, &too_old, now, 1), None);
+        assert_eq!(verify_totp(RFC_SECRET, "12345", now, 1), None);
+    }
+
+    #[test]
+    fn test_base32_roundtrip() {
+        let secret = generate_secret();
+        assert_eq!(secret.len(), 32);
+        let decoded = base32_decode(&secret).unwrap();
+        assert_eq!(decoded.len(), SECRET_LEN);
+        assert_eq!(base32_encode(&decoded), secret);
+        assert_eq!(base32_encode(b"foobar"), "MZXW6YTBOI");
+        assert_eq!(base32_decode("mzxw6ytboi======").unwrap(), b"foobar");
+        assert!(base32_decode("not base32!").is_none());
+    }
+
+    #[test]
+    fn test_otpauth_uri() {
+        let uri = otpauth_uri("Acme Corp", "admin@example.com", "MZXW6YTBOI");
+        assert_eq!(
+            uri,
+            "otpauth://totp/Acme%20Corp:admin%40example.com?secret=MZXW6YTBOI\
+             &issuer=Acme%20Corp&algorithm=SHA1&digits=6&period=30"
+        );
+    }
+
+    #[test]
+    fn test_recovery_codes_normalize_before_hashing() {
+        let codes = generate_recovery_codes(3);
+        assert_eq!(codes.len(), 3);
+        assert_eq!(codes[0].len(), 11);
+        assert!(!is_totp_code(&codes[0]));
+
+        let code = &codes[0];
+        assert_eq!(
+            hash_recovery_code(code),
+            hash_recovery_code(&code.replace('-', " ").to_uppercase())
+        );
+        assert_ne!(hash_recovery_code(code), hash_recovery_code(&codes[1]));
+    }
+
+    #[test]
+    fn test_sealed_secret_is_bound_to_account() {
+        let sealer = MfaSealer::new([7u8; 32]);
+        let sealed = sealer.seal_secret("acct_a", "MZXW6YTBOI").unwrap();
+        assert!(!sealed.contains("MZXW6YTBOI"));
+
+        assert_eq!(sealer.open_secret("acct_a", &sealed).unwrap(), b"foobar");
+        assert!(sealer.open_secret("acct_b", &sealed).is_err());
+        assert!(MfaSealer::new([8u8; 32])
+            .open_secret("acct_a", &sealed)
+            .is_err());
+    }
+
+    #[test]
+    fn test_challenge_attempts_run_out_and_expire() {
+        let attempts = ChallengeAttempts::default();
+        let later = Utc::now() + chrono::Duration::minutes(5);
+        for _ in 0..3 {
+            assert!(!attempts.exhausted("challenge", 3));
+            attempts.record_failure("challenge", later);
+        }
+        assert!(attempts.exhausted("challenge", 3));
+        assert!(!attempts.exhausted("other", 3));
+
+        // Entries past their deadline are pruned on the next failure
+        attempts.record_failure("stale", Utc::now() - chrono::Duration::seconds(1));
+        attempts.record_failure("other", later);
+        assert!(!attempts.exhausted("stale", 1));
+    }
+
+    #[test]
+    fn test_challenge_expiry_and_purpose() {
+        let sealer = MfaSealer::new([7u8; 32]);
+        let challenge = sealer
+            .issue_challenge("acct_a", Utc::now() + chrono::Duration::minutes(5))
+            .unwrap();
+        assert_eq!(sealer.open_challenge(&challenge).unwrap(), "acct_a");
+
+        let expired = sealer
+            .issue_challenge("acct_a", Utc::now() - chrono::Duration::minutes(1))
+            .unwrap();
+        assert!(sealer.open_challenge(&expired).is_err());
+
+        // A sealed secret is not a challenge
+        let sealed = sealer.seal_secret("acct_a", "MZXW6YTBOI").unwrap();
+        assert!(sealer.open_challenge(&sealed).is_err());
+    }
+}
@@ -1,7 +1,9 @@
 //! Account lifecycle management (NIST SP 800-53 AC-2)
 //!
 //! Provides account CRUD, lifecycle state management, email verification,
-//! password management, and notification hooks for provisioning/deprovisioning.
+//! password management, self-service password reset and email verification
+//! tokens, TOTP multi-factor authentication (IA-2(1)), WebAuthn passkeys
+//! (`passkeys` feature), and notification hooks for provisioning/deprovisioning.
 //!
 //! # Feature Dependencies
 //!
@@ -14,24 +16,43 @@
 //! - **AccountNotification**: Event hooks for lifecycle changes
 //! - **Audit integration**: Bridges account events to the audit log (when `audit` active)
 
+#[cfg(feature = "account-handlers")]
+pub mod auth_handlers;
 pub mod config;
 pub mod error;
 #[cfg(feature = "account-handlers")]
+mod handler_audit;
+#[cfg(feature = "account-handlers")]
 pub mod handlers;
+mod mfa;
 pub mod notification;
+#[cfg(all(feature = "account-handlers", feature = "oauth"))]
+pub mod oauth_handlers;
+#[cfg(feature = "passkeys")]
+pub mod passkeys;
 pub mod storage;
 pub mod types;
 
-pub use config::AccountsConfig;
+#[cfg(feature = "passkeys")]
+pub use config::PasskeyConfig;
+pub use config::{AccountsConfig, MfaConfig};
 pub use error::AccountError;
 pub use notification::{AccountEvent, AccountNotification};
 pub use storage::AccountStorage;
-pub use types::{Account, AccountId, AccountIdError, AccountStatus, CreateAccount, UpdateAccount};
-
-use chrono::Utc;
+#[cfg(feature = "passkeys")]
+pub use types::PasskeyCredential;
+pub use types::{
+    Account, AccountId, AccountIdError, AccountStatus, AccountToken, AccountTokenPurpose,
+    AuthOutcome, CreateAccount, IdentitySignIn, LinkedIdentity, MfaChallenge, MfaMethod,
+    TotpEnrollment, TotpSetup, UpdateAccount,
+};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
 use std::sync::Arc;
 
-use crate::auth::PasswordHasher;
+use crate::auth::{PasswordHasher, PasswordViolation, RefreshTokenStorage};
 use crate::error::Error;
 
 /// Central service for account lifecycle management
@@ -44,6 +65,10 @@ pub struct AccountService {
     hasher: PasswordHasher,
     config: AccountsConfig,
     notifications: Vec<Arc<dyn AccountNotification>>,
+    mfa: Option<mfa::MfaSealer>,
+    mfa_attempts: Arc<mfa::ChallengeAttempts>,
+    refresh_storage: Option<Arc<dyn RefreshTokenStorage>>,
+    service_name: Option<String>,
 }
 
 impl AccountService {
@@ -58,9 +83,42 @@ impl AccountService {
             hasher,
             config,
             notifications: Vec::new(),
+            mfa: None,
+            mfa_attempts: Arc::default(),
+            refresh_storage: None,
+            service_name: None,
         }
     }
 
+    /// Revoke refresh tokens when a password reset or email verification
+    /// token is redeemed
+    ///
+    /// Tokens are revoked for the subject `user:{account_id}`, which is what
+    /// [`ClaimsBuilder::user`](crate::auth::tokens::ClaimsBuilder::user) sets.
+    pub fn with_refresh_token_storage(mut self, storage: Arc<dyn RefreshTokenStorage>) -> Self {
+        self.refresh_storage = Some(storage);
+        self
+    }
+
+    /// Enable TOTP multi-factor authentication
+    ///
+    /// `key` encrypts TOTP secrets at rest and signs MFA challenge tokens.
+    /// Keep it stable across restarts and shared between replicas: losing it
+    /// locks enrolled users out until they fall back to recovery codes.
+    pub fn with_mfa_key(mut self, key: [u8; 32]) -> Self {
+        self.mfa = Some(mfa::MfaSealer::new(key));
+        self
+    }
+
+    /// Name of the service, usually `config.service.name`
+    ///
+    /// Passwords may not contain it, alongside the account's email and
+    /// username.
+    pub fn with_service_name(mut self, name: impl Into<String>) -> Self {
+        self.service_name = Some(name.into());
+        self
+    }
+
     /// Register a notification handler
     pub fn with_notification(mut self, handler: Arc<dyn AccountNotification>) -> Self {
         self.notifications.push(handler);
@@ -78,7 +136,7 @@ impl AccountService {
     ///
     /// - Validates email format and normalizes to lowercase
     /// - Checks for duplicate email
-    /// - Hashes password (if provided)
+    /// - Checks the password policy and hashes the password (if provided)
     /// - Sets initial status per config
     pub async fn create_account(&self, data: CreateAccount) -> Result<Account, AccountError> {
         // Validate email format
@@ -119,11 +177,13 @@ impl AccountService {
             }
         }
 
-        // Hash password
+        // Check the password policy, then hash
         let password_hash = if let Some(ref password) = data.password {
+            self.check_password_policy(password, &email, data.username.as_deref())
+                .await?;
             Some(
                 self.hasher
-                    .hash(password)
+                    .hash_unchecked(password)
                     .map_err(|e| AccountError::Validation(e.to_string()))?,
             )
         } else {
@@ -422,15 +482,23 @@ impl AccountService {
     }
 
     /// Change an account's password
+    ///
+    /// The new password must pass the password policy and, when
+    /// `history_size` is set, must not match the current password or any
+    /// recent one kept in the account's password history.
     pub async fn change_password(&self, id: &str, new_password: &str) -> Result<(), AccountError> {
         let mut account = self.require_account(id).await?;
 
+        self.check_password_policy(new_password, &account.email, account.username.as_deref())
+            .await?;
+        self.check_password_history(&account, new_password).await?;
+
         let hash = self
             .hasher
-            .hash(new_password)
+            .hash_unchecked(new_password)
             .map_err(|e| AccountError::Validation(e.to_string()))?;
 
-        account.password_hash = Some(hash);
+        let previous_hash = account.password_hash.replace(hash);
         account.password_changed_at = Some(Utc::now());
         account.updated_at = Utc::now();
 
@@ -439,6 +507,15 @@ impl AccountService {
             .await
             .map_err(|e| AccountError::Storage(e.to_string()))?;
 
+        // The current hash counts toward the history size, so keep one fewer
+        let keep = self.hasher.history_size().saturating_sub(1);
+        if let Some(previous_hash) = previous_hash.filter(|_| keep > 0) {
+            self.storage
+                .push_password_history(id, &previous_hash, keep)
+                .await
+                .map_err(|e| AccountError::Storage(e.to_string()))?;
+        }
+
         self.notify(AccountEvent::PasswordChanged {
             account_id: id.to_string(),
         });
@@ -446,6 +523,117 @@ impl AccountService {
         Ok(())
     }
 
+    /// Start a "forgot password" flow
+    ///
+    /// Issues a single-use reset token, valid for `password_reset_ttl_secs`,
+    /// and delivers it through [`AccountEvent::PasswordResetRequested`].
+    /// Returns `Ok(())` whether or not the email belongs to an active account,
+    /// so the response doesn't reveal which addresses are registered.
+    pub async fn request_password_reset(&self, email: &str) -> Result<(), AccountError> {
+        let email = email.trim().to_lowercase();
+        let Some(account) = self.get_account_by_email(&email).await? else {
+            return Ok(());
+        };
+        if ensure_active(&account).is_err() {
+            return Ok(());
+        }
+
+        let (token, expires_at) = self
+            .issue_account_token(
+                &account,
+                AccountTokenPurpose::PasswordReset,
+                self.config.password_reset_ttl_secs,
+            )
+            .await?;
+
+        self.notify(AccountEvent::PasswordResetRequested {
+            account_id: account.id.to_string(),
+            email: account.email,
+            token,
+            expires_at,
+        });
+
+        Ok(())
+    }
+
+    /// Set a new password with a reset token
+    ///
+    /// The token is consumed even if the account turns out to be inactive,
+    /// but a password the policy rejects leaves it usable, so the user can
+    /// pick another without requesting a new email. Revokes the account's
+    /// refresh tokens so existing sessions can't outlive the old password.
+    pub async fn reset_password(
+        &self,
+        token: &str,
+        new_password: &str,
+    ) -> Result<(), AccountError> {
+        let (stored, account) = self
+            .take_account_token(token, AccountTokenPurpose::PasswordReset)
+            .await?;
+        ensure_active(&account)?;
+
+        let id = account.id.to_string();
+        match self.change_password(&id, new_password).await {
+            Ok(()) => {}
+            Err(e @ AccountError::PasswordPolicy(_)) => {
+                self.storage
+                    .store_account_token(&stored)
+                    .await
+                    .map_err(|e| AccountError::Storage(e.to_string()))?;
+                return Err(e);
+            }
+            Err(e) => return Err(e),
+        }
+        self.revoke_refresh_tokens(&id).await
+    }
+
+    /// Send (or re-send) an email verification token
+    ///
+    /// Issues a single-use token, valid for `email_verification_ttl_secs`,
+    /// and delivers it through [`AccountEvent::EmailVerificationRequested`].
+    /// Like [`request_password_reset`](Self::request_password_reset), returns
+    /// `Ok(())` for unknown or already verified addresses.
+    pub async fn request_email_verification(&self, email: &str) -> Result<(), AccountError> {
+        let email = email.trim().to_lowercase();
+        let Some(account) = self.get_account_by_email(&email).await? else {
+            return Ok(());
+        };
+        if account.email_verified {
+            return Ok(());
+        }
+
+        let (token, expires_at) = self
+            .issue_account_token(
+                &account,
+                AccountTokenPurpose::EmailVerification,
+                self.config.email_verification_ttl_secs,
+            )
+            .await?;
+
+        self.notify(AccountEvent::EmailVerificationRequested {
+            account_id: account.id.to_string(),
+            email: account.email,
+            token,
+            expires_at,
+        });
+
+        Ok(())
+    }
+
+    /// Verify an account's email address with a verification token
+    ///
+    /// Same effect as [`verify_email`](Self::verify_email), and also revokes
+    /// the account's refresh tokens.
+    pub async fn confirm_email_verification(&self, token: &str) -> Result<(), AccountError> {
+        let account = self
+            .redeem_account_token(token, AccountTokenPurpose::EmailVerification)
+            .await?;
+
+        let id = account.id.to_string();
+        self.verify_email(&id).await?;
+        self.revoke_refresh_tokens(&id).await
+    }
+
     /// Hard delete an account (GDPR)
     pub async fn delete_account(&self, id: &str) -> Result<(), AccountError> {
         let deleted = self
@@ -492,8 +680,14 @@ impl AccountService {
     /// 1. Account exists
     /// 2. Account is Active
     /// 3. Password matches
-    /// 4. Updates `last_login_at`
-    pub async fn authenticate(&self, email: &str, password: &str) -> Result<Account, AccountError> {
+    /// 4. If a confirmed TOTP authenticator is enrolled, returns
+    ///    [`AuthOutcome::MfaRequired`] with a challenge for [`verify_mfa`](Self::verify_mfa)
+    /// 5. Otherwise updates `last_login_at` and returns [`AuthOutcome::Authenticated`]
+    pub async fn authenticate(
+        &self,
+        email: &str,
+        password: &str,
+    ) -> Result<AuthOutcome, AccountError> {
         let email = email.trim().to_lowercase();
 
         let account = self
@@ -503,27 +697,7 @@ impl AccountService {
             .map_err(|e| AccountError::Storage(e.to_string()))?
             .ok_or_else(|| AccountError::NotFound(email.clone()))?;
 
-        // Check status
-        if account.status != AccountStatus::Active {
-            let reason = match account.status {
-                AccountStatus::PendingVerification => "email not verified".to_string(),
-                AccountStatus::Disabled => account
-                    .disabled_reason
-                    .clone()
-                    .unwrap_or_else(|| "administratively disabled".to_string()),
-                AccountStatus::Locked => account
-                    .locked_reason
-                    .clone()
-                    .unwrap_or_else(|| "account locked".to_string()),
-                AccountStatus::Expired => "account expired".to_string(),
-                AccountStatus::Suspended => "account suspended".to_string(),
-                _ => "account not active".to_string(),
-            };
-            return Err(AccountError::AccountInactive {
-                status: account.status,
-                reason,
-            });
-        }
+        ensure_active(&account)?;
 
         // Check password
         let password_hash = account
@@ -540,12 +714,411 @@ impl AccountService {
             return Err(AccountError::InvalidCredentials);
         }
 
+        // Second factor, if enrolled
+        if let Some(challenge) = self.mfa_challenge(account.id.as_str()).await? {
+            return Ok(AuthOutcome::MfaRequired(challenge));
+        }
+
         // Record login
         let _ = self.storage.record_login(account.id.as_str()).await;
 
+        Ok(AuthOutcome::Authenticated(Box::new(account)))
+    }
+
+    /// A challenge for [`verify_mfa`](Self::verify_mfa), if the account has a
+    /// confirmed TOTP authenticator
+    async fn mfa_challenge(&self, account_id: &str) -> Result<Option<MfaChallenge>, AccountError> {
+        if self.confirmed_totp(account_id).await?.is_none() {
+            return Ok(None);
+        }
+        let expires_at =
+            Utc::now() + chrono::Duration::seconds(self.config.mfa.challenge_ttl_secs as i64);
+        let token = self.sealer()?.issue_challenge(account_id, expires_at)?;
+        Ok(Some(MfaChallenge { token, expires_at }))
+    }
+
+    /// Complete an MFA challenge with a TOTP or recovery code
+    ///
+    /// Six-digit codes are checked against the authenticator, allowing
+    /// `mfa.totp_skew_steps` of clock drift; a time step is accepted at most
+    /// once. Anything else is tried as a recovery code, which is consumed.
+    /// After `mfa.max_attempts` wrong codes the challenge is refused like an
+    /// expired one, and the user has to sign in again.
+    /// Updates `last_login_at` on success.
+    pub async fn verify_mfa(&self, challenge: &str, code: &str) -> Result<Account, AccountError> {
+        let sealer = self.sealer()?;
+        let account_id = sealer.open_challenge(challenge)?;
+        if self
+            .mfa_attempts
+            .exhausted(challenge, self.config.mfa.max_attempts)
+        {
+            return Err(AccountError::InvalidCredentials);
+        }
+        let account = self.require_account(&account_id).await?;
+        ensure_active(&account)?;
+
+        let enrollment = self
+            .confirmed_totp(&account_id)
+            .await?
+            .ok_or(AccountError::InvalidCredentials)?;
+
+        let code = code.trim();
+        let (method, verified) = if mfa::is_totp_code(code) {
+            let secret = sealer.open_secret(&account_id, &enrollment.secret_encrypted)?;
+            let verified = match mfa::verify_totp(
+                &secret,
+                code,
+                Utc::now(),
+                self.config.mfa.totp_skew_steps,
+            ) {
+                Some(step) => self
+                    .storage
+                    .advance_totp_step(&account_id, step)
+                    .await
+                    .map_err(|e| AccountError::Storage(e.to_string()))?,
+                None => false,
+            };
+            (MfaMethod::Totp, verified)
+        } else {
+            let verified = self
+                .storage
+                .consume_recovery_code(&account_id, &mfa::hash_recovery_code(code))
+                .await
+                .map_err(|e| AccountError::Storage(e.to_string()))?;
+            (MfaMethod::RecoveryCode, verified)
+        };
+
+        if !verified {
+            // The challenge cannot outlive its TTL from now
+            let forget_at =
+                Utc::now() + chrono::Duration::seconds(self.config.mfa.challenge_ttl_secs as i64);
+            self.mfa_attempts.record_failure(challenge, forget_at);
+            self.notify(AccountEvent::MfaFailed {
+                account_id: account_id.clone(),
+                method,
+            });
+            return Err(AccountError::InvalidCredentials);
+        }
+
+        let _ = self.storage.record_login(&account_id).await;
+
+        self.notify(AccountEvent::MfaVerified { account_id, method });
+
         Ok(account)
     }
 
+    /// Start TOTP enrollment for an account
+    ///
+    /// Replaces any unconfirmed enrollment. The authenticator isn't required
+    /// at login until [`confirm_totp`](Self::confirm_totp) succeeds.
+    pub async fn enroll_totp(&self, account_id: &str) -> Result<TotpSetup, AccountError> {
+        let sealer = self.sealer()?;
+        let account = self.require_account(account_id).await?;
+
+        if self.confirmed_totp(account_id).await?.is_some() {
+            return Err(AccountError::AlreadyExists(format!(
+                "TOTP authenticator for {}",
+                account_id
+            )));
+        }
+
+        let secret = mfa::generate_secret();
+        let enrollment = TotpEnrollment {
+            account_id: account.id.clone(),
+            secret_encrypted: sealer.seal_secret(account_id, &secret)?,
+            confirmed_at: None,
+            last_used_step: None,
+            created_at: Utc::now(),
+        };
+
+        self.storage
+            .save_totp(&enrollment)
+            .await
+            .map_err(|e| AccountError::Storage(e.to_string()))?;
+
+        let otpauth_uri = mfa::otpauth_uri(&self.config.mfa.issuer, &account.email, &secret);
+        Ok(TotpSetup {
+            secret,
+            otpauth_uri,
+        })
+    }
+
+    /// Confirm TOTP enrollment with a first code from the authenticator
+    ///
+    /// Returns the recovery codes. They are shown once; only hashes are stored.
+    pub async fn confirm_totp(
+        &self,
+        account_id: &str,
+        code: &str,
+    ) -> Result<Vec<String>, AccountError> {
+        let sealer = self.sealer()?;
+        let mut enrollment = self
+            .storage
+            .get_totp(account_id)
+            .await
+            .map_err(|e| AccountError::Storage(e.to_string()))?
+            .ok_or_else(|| AccountError::NotFound(format!("TOTP enrollment for {}", account_id)))?;
+
+        if enrollment.is_confirmed() {
+            return Err(AccountError::AlreadyExists(format!(
+                "TOTP authenticator for {}",
+                account_id
+            )));
+        }
+
+        let secret = sealer.open_secret(account_id, &enrollment.secret_encrypted)?;
+        let step = mfa::verify_totp(&secret, code, Utc::now(), self.config.mfa.totp_skew_steps)
+            .ok_or(AccountError::InvalidCredentials)?;
+
+        enrollment.confirmed_at = Some(Utc::now());
+        enrollment.last_used_step = Some(step);
+        self.storage
+            .save_totp(&enrollment)
+            .await
+            .map_err(|e| AccountError::Storage(e.to_string()))?;
+
+        let codes = self.store_recovery_codes(account_id).await?;
+
+        self.notify(AccountEvent::MfaEnrolled {
+            account_id: account_id.to_string(),
+        });
+
+        Ok(codes)
+    }
+
+    /// Remove an account's TOTP authenticator and recovery codes
+    pub async fn disable_totp(&self, account_id: &str) -> Result<(), AccountError> {
+        let deleted = self
+            .storage
+            .delete_totp(account_id)
+            .await
+            .map_err(|e| AccountError::Storage(e.to_string()))?;
+
+        if !deleted {
+            return Err(AccountError::NotFound(format!(
+                "TOTP enrollment for {}",
+                account_id
+            )));
+        }
+
+        self.notify(AccountEvent::MfaDisabled {
+            account_id: account_id.to_string(),
+        });
+
+        Ok(())
+    }
+
+    /// Replace an account's recovery codes, invalidating the old ones
+    pub async fn regenerate_recovery_codes(
+        &self,
+        account_id: &str,
+    ) -> Result<Vec<String>, AccountError> {
+        if self.confirmed_totp(account_id).await?.is_none() {
+            return Err(AccountError::NotFound(format!(
+                "TOTP enrollment for {}",
+                account_id
+            )));
+        }
+
+        let codes = self.store_recovery_codes(account_id).await?;
+
+        self.notify(AccountEvent::RecoveryCodesRegenerated {
+            account_id: account_id.to_string(),
+        });
+
+        Ok(codes)
+    }
+
+    /// Sign in with an external identity (OAuth/OIDC login)
+    ///
+    /// Resolves `provider` + `subject` to an account:
+    /// 1. An identity already linked signs in to its account
+    /// 2. Otherwise, if the provider vouches for the email (`email_verified`),
+    ///    the identity is linked to the account with that email, unless that
+    ///    account never verified the address itself or has a confirmed TOTP
+    ///    authenticator. Whoever registered an unverified address may not own
+    ///    it, and an email match must not bypass a second factor, so in both
+    ///    cases the owner has to sign in and call
+    ///    [`link_identity`](Self::link_identity) instead. The account must be
+    ///    Active before anything is linked to it
+    /// 3. Otherwise a password-less account is created and linked; it needs
+    ///    email verification unless the provider verified the address
+    ///
+    /// Unverified provider emails never link to existing accounts, since
+    /// anyone can claim an address at a provider that doesn't check it.
+    ///
+    /// The account must be Active. As with [`authenticate`](Self::authenticate),
+    /// an account with a confirmed TOTP authenticator gets
+    /// [`AuthOutcome::MfaRequired`] and is signed in by
+    /// [`verify_mfa`](Self::verify_mfa); otherwise `last_login_at` is updated
+    /// and the outcome is [`AuthOutcome::Authenticated`].
+    pub async fn sign_in_with_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+        email: Option<&str>,
+        email_verified: bool,
+    ) -> Result<(AuthOutcome, IdentitySignIn), AccountError> {
+        let linked = self
+            .storage
+            .get_identity(provider, subject)
+            .await
+            .map_err(|e| AccountError::Storage(e.to_string()))?;
+
+        let (account, outcome) = match linked {
+            Some(identity) => {
+                let account = self.require_account(identity.account_id.as_str()).await?;
+                (account, IdentitySignIn::Existing)
+            }
+            None => {
+                let email = email
+                    .map(|e| e.trim().to_lowercase())
+                    .filter(|e| !e.is_empty())
+                    .ok_or_else(|| {
+                        AccountError::Validation(format!(
+                            "{} did not provide an email address",
+                            provider
+                        ))
+                    })?;
+
+                let existing = if email_verified {
+                    self.storage
+                        .get_by_email(&email)
+                        .await
+                        .map_err(|e| AccountError::Storage(e.to_string()))?
+                } else {
+                    None
+                };
+
+                if let Some(account) = &existing {
+                    if !account.email_verified {
+                        return Err(AccountError::AlreadyExists(format!(
+                            "{} (the address is not verified on the account; sign in to it to link {})",
+                            email, provider
+                        )));
+                    }
+                    ensure_active(account)?;
+                    if self.confirmed_totp(account.id.as_str()).await?.is_some() {
+                        return Err(AccountError::AlreadyExists(format!(
+                            "{} (the account uses MFA; sign in to it to link {})",
+                            email, provider
+                        )));
+                    }
+                }
+
+                let (account, outcome) = match existing {
+                    Some(account) => (account, IdentitySignIn::Linked),
+                    None => {
+                        let account = self
+                            .create_account(CreateAccount {
+                                email: email.clone(),
+                                username: None,
+                                password: None,
+                                roles: Vec::new(),
+                                expires_at: None,
+                                metadata: None,
+                                require_email_verification: Some(!email_verified),
+                            })
+                            .await?;
+                        (account, IdentitySignIn::Created)
+                    }
+                };
+
+                self.link_identity(account.id.as_str(), provider, subject, Some(&email))
+                    .await?;
+                (account, outcome)
+            }
+        };
+
+        ensure_active(&account)?;
+
+        if let Some(challenge) = self.mfa_challenge(account.id.as_str()).await? {
+            return Ok((AuthOutcome::MfaRequired(challenge), outcome));
+        }
+
+        let _ = self.storage.record_login(account.id.as_str()).await;
+
+        Ok((AuthOutcome::Authenticated(Box::new(account)), outcome))
+    }
+
+    /// Link an external identity to an existing account
+    pub async fn link_identity(
+        &self,
+        account_id: &str,
+        provider: &str,
+        subject: &str,
+        email: Option<&str>,
+    ) -> Result<LinkedIdentity, AccountError> {
+        let account = self.require_account(account_id).await?;
+
+        if let Some(existing) = self
+            .storage
+            .get_identity(provider, subject)
+            .await
+            .map_err(|e| AccountError::Storage(e.to_string()))?
+        {
+            return Err(AccountError::AlreadyExists(format!(
+                "{} identity {} (linked to {})",
+                provider, subject, existing.account_id
+            )));
+        }
+
+        let identity = LinkedIdentity {
+            provider: provider.to_string(),
+            subject: subject.to_string(),
+            account_id: account.id.clone(),
+            email: email.map(str::to_string),
+            linked_at: Utc::now(),
+        };
+
+        self.storage
+            .link_identity(&identity)
+            .await
+            .map_err(|e| AccountError::Storage(e.to_string()))?;
+
+        self.notify(AccountEvent::IdentityLinked {
+            account_id: account_id.to_string(),
+            provider: provider.to_string(),
+            subject: subject.to_string(),
+        });
+
+        Ok(identity)
+    }
+
+    /// Unlink an external identity
+    pub async fn unlink_identity(&self, provider: &str, subject: &str) -> Result<(), AccountError> {
+        let identity = self
+            .storage
+            .get_identity(provider, subject)
+            .await
+            .map_err(|e| AccountError::Storage(e.to_string()))?
+            .ok_or_else(|| AccountError::NotFound(format!("{} identity {}", provider, subject)))?;
+
+        self.storage
+            .unlink_identity(provider, subject)
+            .await
+            .map_err(|e| AccountError::Storage(e.to_string()))?;
+
+        self.notify(AccountEvent::IdentityUnlinked {
+            account_id: identity.account_id.to_string(),
+            provider: provider.to_string(),
+            subject: subject.to_string(),
+        });
+
+        Ok(())
+    }
+
+    /// List the external identities linked to an account
+    pub async fn list_identities(
+        &self,
+        account_id: &str,
+    ) -> Result<Vec<LinkedIdentity>, AccountError> {
+        self.storage
+            .list_identities(account_id)
+            .await
+            .map_err(|e| AccountError::Storage(e.to_string()))
+    }
+
     // ========================================================================
     // Internal helpers
     // ========================================================================
@@ -558,6 +1131,118 @@ impl AccountService {
             .ok_or_else(|| AccountError::NotFound(id.to_string()))
     }
 
+    async fn issue_account_token(
+        &self,
+        account: &Account,
+        purpose: AccountTokenPurpose,
+        ttl_secs: u64,
+    ) -> Result<(String, DateTime<Utc>), AccountError> {
+        let token = new_account_token();
+        let now = Utc::now();
+        let expires_at = now + chrono::Duration::seconds(ttl_secs as i64);
+
+        self.storage
+            .store_account_token(&AccountToken {
+                token_hash: account_token_hash(&token),
+                account_id: account.id.clone(),
+                purpose,
+                email: account.email.clone(),
+                expires_at,
+                created_at: now,
+            })
+            .await
+            .map_err(|e| AccountError::Storage(e.to_string()))?;
+
+        Ok((token, expires_at))
+    }
+
+    /// Consume a token and load its account
+    ///
+    /// A token sent to an address the account no longer has is rejected.
+    async fn redeem_account_token(
+        &self,
+        token: &str,
+        purpose: AccountTokenPurpose,
+    ) -> Result<Account, AccountError> {
+        let (_, account) = self.take_account_token(token, purpose).await?;
+        Ok(account)
+    }
+
+    /// Like [`redeem_account_token`](Self::redeem_account_token), but also
+    /// returns the stored token so the caller can put it back
+    async fn take_account_token(
+        &self,
+        token: &str,
+        purpose: AccountTokenPurpose,
+    ) -> Result<(AccountToken, Account), AccountError> {
+        let stored = self
+            .storage
+            .take_account_token(&account_token_hash(token.trim()), purpose)
+            .await
+            .map_err(|e| AccountError::Storage(e.to_string()))?
+            .filter(|t| !t.is_expired())
+            .ok_or(AccountError::InvalidToken)?;
+
+        let account = self
+            .storage
+            .get_by_id(stored.account_id.as_str())
+            .await
+            .map_err(|e| AccountError::Storage(e.to_string()))?
+            .ok_or(AccountError::InvalidToken)?;
+
+        if account.email != stored.email {
+            return Err(AccountError::InvalidToken);
+        }
+
+        Ok((stored, account))
+    }
+
+    async fn revoke_refresh_tokens(&self, account_id: &str) -> Result<(), AccountError> {
+        if let Some(storage) = &self.refresh_storage {
+            storage
+                .revoke_all_for_user(&format!("user:{}", account_id))
+                .await
+                .map_err(|e| AccountError::Storage(e.to_string()))?;
+        }
+        Ok(())
+    }
+
+    /// The account an MFA challenge was issued for, if it is still valid
+    #[cfg(feature = "account-handlers")]
+    pub(crate) fn challenge_account_id(&self, challenge: &str) -> Result<String, AccountError> {
+        self.sealer()?.open_challenge(challenge)
+    }
+
+    fn sealer(&self) -> Result<&mfa::MfaSealer, AccountError> {
+        self.mfa
+            .as_ref()
+            .ok_or_else(|| AccountError::Mfa("MFA key not configured".into()))
+    }
+
+    async fn confirmed_totp(
+        &self,
+        account_id: &str,
+    ) -> Result<Option<TotpEnrollment>, AccountError> {
+        Ok(self
+            .storage
+            .get_totp(account_id)
+            .await
+            .map_err(|e| AccountError::Storage(e.to_string()))?
+            .filter(TotpEnrollment::is_confirmed))
+    }
+
+    async fn store_recovery_codes(&self, account_id: &str) -> Result<Vec<String>, AccountError> {
+        let codes = mfa::generate_recovery_codes(self.config.mfa.recovery_codes);
+        let hashes: Vec<String> = codes.iter().map(|c| mfa::hash_recovery_code(c)).collect();
+
+        self.storage
+            .replace_recovery_codes(account_id, &hashes)
+            .await
+            .map_err(|e| AccountError::Storage(e.to_string()))?;
+
+        Ok(codes)
+    }
+
     fn validate_transition(
         &self,
         account: &Account,
@@ -581,6 +1266,55 @@ impl AccountService {
             });
         }
     }
+
+    /// Check a password against the policy, with the account's email,
+    /// username, and the service name as words it must not contain
+    async fn check_password_policy(
+        &self,
+        password: &str,
+        email: &str,
+        username: Option<&str>,
+    ) -> Result<(), AccountError> {
+        let mut context = vec![email];
+        context.extend(self.service_name.as_deref());
+        context.extend(username);
+
+        self.hasher.check_policy_async(password, &context).await?;
+        Ok(())
+    }
+
+    /// Reject a password that matches the current one or a recent one
+    async fn check_password_history(
+        &self,
+        account: &Account,
+        password: &str,
+    ) -> Result<(), AccountError> {
+        let history_size = self.hasher.history_size();
+        if history_size == 0 {
+            return Ok(());
+        }
+
+        let mut recent: Vec<String> = account.password_hash.iter().cloned().collect();
+        if history_size > 1 {
+            recent.extend(
+                self.storage
+                    .get_password_history(account.id.as_ref(), history_size - 1)
+                    .await
+                    .map_err(|e| AccountError::Storage(e.to_string()))?,
+            );
+        }
+
+        for hash in recent.iter().take(history_size) {
+            // An unparseable stored hash can't match; skip it
+            if self.hasher.verify(password, hash).unwrap_or(false) {
+                return Err(AccountError::PasswordPolicy(
+                    PasswordViolation::Reused.into(),
+                ));
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Convert AccountError to the framework Error type
@@ -593,12 +1327,53 @@ impl From<AccountError> for Error {
             AccountError::InvalidCredentials => Error::Unauthorized(err.to_string()),
             AccountError::AccountInactive { .. } => Error::Forbidden(err.to_string()),
             AccountError::Validation(_) => Error::BadRequest(err.to_string()),
+            AccountError::PasswordPolicy(policy) => Error::from(policy.clone()),
             AccountError::Storage(_) => Error::Internal(err.to_string()),
             AccountError::InvalidId(_) => Error::BadRequest(err.to_string()),
+            AccountError::InvalidToken => Error::BadRequest(err.to_string()),
+            AccountError::Mfa(_) => Error::Internal(err.to_string()),
+            #[cfg(feature = "passkeys")]
+            AccountError::Passkey(_) => Error::BadRequest(err.to_string()),
         }
     }
 }
 
+/// Reject accounts that may not sign in, with the reason
+fn ensure_active(account: &Account) -> Result<(), AccountError> {
+    if account.status != AccountStatus::Active {
+        let reason = match account.status {
+            AccountStatus::PendingVerification => "email not verified".to_string(),
+            AccountStatus::Disabled => account
+                .disabled_reason
+                .clone()
+                .unwrap_or_else(|| "administratively disabled".to_string()),
+            AccountStatus::Locked => account
+                .locked_reason
+                .clone()
+                .unwrap_or_else(|| "account locked".to_string()),
+            AccountStatus::Expired => "account expired".to_string(),
+            AccountStatus::Suspended => "account suspended".to_string(),
+            _ => "account not active".to_string(),
+        };
+        return Err(AccountError::AccountInactive {
+            status: account.status,
+            reason,
+        });
+    }
+    Ok(())
+}
+
+/// A new self-service token: 256 random bits, base64url-encoded
+fn new_account_token() -> String {
+    let bytes: [u8; 32] = rand::random();
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// The storage key for a self-service token (its BLAKE3 hash)
+fn account_token_hash(token: &str) -> String {
+    blake3::hash(token.as_bytes()).to_hex().to_string()
+}
+
 /// Basic email format validation (lowercase, contains @, has domain)
 fn is_valid_email(email: &str) -> bool {
     let parts: Vec<&str> = email.split('@').collect();
@@ -738,6 +1513,131 @@ mod audit_integration {
                         "action": "profile_updated",
                     }),
                 ),
+                AccountEvent::IdentityLinked {
+                    ref account_id,
+                    ref provider,
+                    ref subject,
+                } => (
+                    AuditEventKind::AccountUpdated,
+                    AuditSeverity::Notice,
+                    serde_json::json!({
+                        "account_id": account_id,
+                        "action": "identity_linked",
+                        "provider": provider,
+                        "subject": subject,
+                    }),
+                ),
+                AccountEvent::IdentityUnlinked {
+                    ref account_id,
+                    ref provider,
+                    ref subject,
+                } => (
+                    AuditEventKind::AccountUpdated,
+                    AuditSeverity::Notice,
+                    serde_json::json!({
+                        "account_id": account_id,
+                        "action": "identity_unlinked",
+                        "provider": provider,
+                        "subject": subject,
+                    }),
+                ),
+                AccountEvent::MfaEnrolled { ref account_id } => (
+                    AuditEventKind::AccountMfaEnrolled,
+                    AuditSeverity::Notice,
+                    serde_json::json!({ "account_id": account_id }),
+                ),
+                AccountEvent::MfaDisabled { ref account_id } => (
+                    AuditEventKind::AccountMfaDisabled,
+                    AuditSeverity::Warning,
+                    serde_json::json!({ "account_id": account_id }),
+                ),
+                AccountEvent::MfaVerified {
+                    ref account_id,
+                    method,
+                } => (
+                    AuditEventKind::AccountMfaVerified,
+                    AuditSeverity::Informational,
+                    serde_json::json!({
+                        "account_id": account_id,
+                        "method": method.to_string(),
+                    }),
+                ),
+                AccountEvent::MfaFailed {
+                    ref account_id,
+                    method,
+                } => (
+                    AuditEventKind::AccountMfaFailed,
+                    AuditSeverity::Warning,
+                    serde_json::json!({
+                        "account_id": account_id,
+                        "method": method.to_string(),
+                    }),
+                ),
+                #[cfg(feature = "passkeys")]
+                AccountEvent::PasskeyRegistered {
+                    ref account_id,
+                    ref credential_id,
+                } => (
+                    AuditEventKind::AccountPasskeyRegistered,
+                    AuditSeverity::Notice,
+                    serde_json::json!({
+                        "account_id": account_id,
+                        "credential_id": credential_id,
+                    }),
+                ),
+                #[cfg(feature = "passkeys")]
+                AccountEvent::PasskeyRemoved {
+                    ref account_id,
+                    ref credential_id,
+                } => (
+                    AuditEventKind::AccountPasskeyRemoved,
+                    AuditSeverity::Warning,
+                    serde_json::json!({
+                        "account_id": account_id,
+                        "credential_id": credential_id,
+                    }),
+                ),
+                #[cfg(feature = "passkeys")]
+                AccountEvent::PasskeySignCountRegressed {
+                    ref account_id,
+                    ref credential_id,
+                    stored,
+                    received,
+                } => (
+                    AuditEventKind::AccountPasskeySignCountRegressed,
+                    AuditSeverity::Critical,
+                    serde_json::json!({
+                        "account_id": account_id,
+                        "credential_id": credential_id,
+                        "stored_sign_count": stored,
+                        "received_sign_count": received,
+                    }),
+                ),
+                // The token itself never reaches the audit log
+                AccountEvent::PasswordResetRequested { ref account_id, .. } => (
+                    AuditEventKind::AccountUpdated,
+                    AuditSeverity::Notice,
+                    serde_json::json!({
+                        "account_id": account_id,
+                        "action": "password_reset_requested",
+                    }),
+                ),
+                AccountEvent::EmailVerificationRequested { ref account_id, .. } => (
+                    AuditEventKind::AccountUpdated,
+                    AuditSeverity::Informational,
+                    serde_json::json!({
+                        "account_id": account_id,
+                        "action": "email_verification_requested",
+                    }),
+                ),
+                AccountEvent::RecoveryCodesRegenerated { ref account_id } => (
+                    AuditEventKind::AccountUpdated,
+                    AuditSeverity::Notice,
+                    serde_json::json!({
+                        "account_id": account_id,
+                        "action": "recovery_codes_regenerated",
+                    }),
+                ),
             };
 
             let audit_event =
@@ -767,6 +1667,19 @@ mod tests {
         assert!(!is_valid_email(""));
     }
 
+    #[test]
+    fn test_account_tokens_are_random_and_hashed() {
+        let a = new_account_token();
+        let b = new_account_token();
+        assert_ne!(a, b);
+        assert_eq!(a.len(), 43);
+
+        let hash = account_token_hash(&a);
+        assert_eq!(hash.len(), 64);
+        assert_ne!(hash, a);
+        assert_eq!(hash, account_token_hash(&a));
+    }
+
     #[test]
     fn test_account_error_to_framework_error() {
         let err: Error = AccountError::NotFound("test".into()).into();
@@ -788,7 +1701,317 @@ mod tests {
         let err: Error = AccountError::Validation("bad".into()).into();
         assert!(matches!(err, Error::BadRequest(_)));
 
+        let err: Error = AccountError::PasswordPolicy(PasswordViolation::Reused.into()).into();
+        match err {
+            Error::FieldValidation(errors) => {
+                assert_eq!(errors.errors.get("password").unwrap()[0].code, "REUSED");
+            }
+            other => panic!("Expected FieldValidation, got {:?}", other),
+        }
+
         let err: Error = AccountError::Storage("fail".into()).into();
         assert!(matches!(err, Error::Internal(_)));
+
+        let err: Error = AccountError::InvalidToken.into();
+        assert!(matches!(err, Error::BadRequest(_)));
+
+        let err: Error = AccountError::Mfa("no key".into()).into();
+        assert!(matches!(err, Error::Internal(_)));
+
+        #[cfg(feature = "passkeys")]
+        {
+            let err: Error = AccountError::Passkey("bad signature".into()).into();
+            assert!(matches!(err, Error::BadRequest(_)));
+        }
+    }
+
+    /// A service on a throwaway Turso file, with MFA enabled
+    #[cfg(feature = "turso")]
+    async fn turso_service(dir: &tempfile::TempDir) -> (AccountService, Arc<dyn AccountStorage>) {
+        let db = libsql::Builder::new_local(dir.path().join("accounts.db"))
+            .build()
+            .await
+            .unwrap();
+        let storage: Arc<dyn AccountStorage> = Arc::new(
+            storage::turso::TursoAccountStorage::new(Arc::new(db))
+                .await
+                .unwrap(),
+        );
+        let service = AccountService::new(
+            storage.clone(),
+            PasswordHasher::default(),
+            AccountsConfig::default(),
+        )
+        .with_mfa_key([7; 32]);
+        (service, storage)
+    }
+
+    /// Mark the account's authenticator confirmed, as `confirm_totp` would
+    #[cfg(feature = "turso")]
+    async fn confirm_authenticator(storage: &Arc<dyn AccountStorage>, account: &Account) {
+        storage
+            .save_totp(&TotpEnrollment {
+                account_id: account.id.clone(),
+                secret_encrypted: "sealed".to_string(),
+                confirmed_at: Some(Utc::now()),
+                last_used_step: None,
+                created_at: Utc::now(),
+            })
+            .await
+            .unwrap();
+    }
+
+    #[cfg(feature = "turso")]
+    async fn passwordless_account(service: &AccountService, email: &str) -> Account {
+        service
+            .create_account(CreateAccount {
+                email: email.to_string(),
+                username: None,
+                password: None,
+                roles: Vec::new(),
+                expires_at: None,
+                metadata: None,
+                require_email_verification: Some(false),
+            })
+            .await
+            .unwrap()
+    }
+
+    #[cfg(feature = "turso")]
+    #[tokio::test]
+    async fn test_password_may_not_contain_the_service_name() {
+        let dir = tempfile::tempdir().unwrap();
+        let (service, _) = turso_service(&dir).await;
+        let mut config = AccountsConfig::default();
+        config.mfa.issuer = "Zebra Corp".to_string();
+        let service = AccountService { config, ..service }.with_service_name("billing-api");
+
+        let create = |email: &str, password: &str| CreateAccount {
+            email: email.to_string(),
+            username: None,
+            password: Some(password.to_string()),
+            roles: Vec::new(),
+            expires_at: None,
+            metadata: None,
+            require_email_verification: Some(false),
+        };
+
+        let err = service
+            .create_account(create("ada@example.com", "my-billing-api-pass"))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AccountError::PasswordPolicy(_)), "{err}");
+
+        // The authenticator issuer is not the service name
+        service
+            .create_account(create("ada@example.com", "zebra corp crossing"))
+            .await
+            .unwrap();
+    }
+
+    #[cfg(feature = "turso")]
+    #[tokio::test]
+    async fn test_rejected_reset_password_keeps_the_token() {
+        let dir = tempfile::tempdir().unwrap();
+        let (service, _) = turso_service(&dir).await;
+        let service = service.with_service_name("billing-api");
+        let account = passwordless_account(&service, "ada@example.com").await;
+        let (token, _) = service
+            .issue_account_token(
+                &account,
+                AccountTokenPurpose::PasswordReset,
+                service.config.password_reset_ttl_secs,
+            )
+            .await
+            .unwrap();
+
+        let err = service
+            .reset_password(&token, "my-billing-api-pass")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AccountError::PasswordPolicy(_)), "{err}");
+
+        service
+            .reset_password(&token, "zebra corp crossing")
+            .await
+            .unwrap();
+        let err = service
+            .reset_password(&token, "another fine crossing")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AccountError::InvalidToken), "{err}");
+    }
+
+    #[cfg(feature = "turso")]
+    #[tokio::test]
+    async fn test_mfa_challenge_is_refused_after_max_attempts() {
+        let dir = tempfile::tempdir().unwrap();
+        let (service, storage) = turso_service(&dir).await;
+        let account = passwordless_account(&service, "ada@example.com").await;
+        confirm_authenticator(&storage, &account).await;
+        storage
+            .replace_recovery_codes(
+                account.id.as_str(),
+                &[mfa::hash_recovery_code("abcde-fghij")],
+            )
+            .await
+            .unwrap();
+
+        let challenge = service
+            .sealer()
+            .unwrap()
+            .issue_challenge(
+                account.id.as_str(),
+                Utc::now() + chrono::Duration::minutes(5),
+            )
+            .unwrap();
+        for _ in 0..service.config.mfa.max_attempts {
+            let err = service
+                .verify_mfa(&challenge, "wrong-guess")
+                .await
+                .unwrap_err();
+            assert!(matches!(err, AccountError::InvalidCredentials), "{err}");
+        }
+
+        // The right code no longer helps on this challenge...
+        let err = service
+            .verify_mfa(&challenge, "abcde-fghij")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AccountError::InvalidCredentials), "{err}");
+
+        // ...and was not consumed, so a fresh challenge accepts it
+        let fresh = service
+            .sealer()
+            .unwrap()
+            .issue_challenge(
+                account.id.as_str(),
+                Utc::now() + chrono::Duration::minutes(4),
+            )
+            .unwrap();
+        let verified = service.verify_mfa(&fresh, "abcde-fghij").await.unwrap();
+        assert_eq!(verified.id, account.id);
+    }
+
+    #[cfg(feature = "turso")]
+    #[tokio::test]
+    async fn test_identity_sign_in_asks_for_the_second_factor() {
+        let dir = tempfile::tempdir().unwrap();
+        let (service, storage) = turso_service(&dir).await;
+        let account = passwordless_account(&service, "ada@example.com").await;
+        service
+            .link_identity(
+                account.id.as_str(),
+                "google",
+                "g-1",
+                Some("ada@example.com"),
+            )
+            .await
+            .unwrap();
+
+        let (outcome, identity) = service
+            .sign_in_with_identity("google", "g-1", Some("ada@example.com"), true)
+            .await
+            .unwrap();
+        assert_eq!(identity, IdentitySignIn::Existing);
+        assert!(matches!(outcome, AuthOutcome::Authenticated(ref a) if a.id == account.id));
+
+        confirm_authenticator(&storage, &account).await;
+        let (outcome, _) = service
+            .sign_in_with_identity("google", "g-1", Some("ada@example.com"), true)
+            .await
+            .unwrap();
+        let AuthOutcome::MfaRequired(challenge) = outcome else {
+            panic!("a linked identity must not skip the authenticator");
+        };
+        assert_eq!(
+            service.challenge_account_id(&challenge.token).unwrap(),
+            account.id.as_str()
+        );
+    }
+
+    #[cfg(feature = "turso")]
+    #[tokio::test]
+    async fn test_verified_email_does_not_link_to_an_unverified_account() {
+        let dir = tempfile::tempdir().unwrap();
+        let (service, _) = turso_service(&dir).await;
+        // Registered by someone who never proved they own the address
+        let squatter = service
+            .create_account(CreateAccount {
+                email: "ada@example.com".to_string(),
+                username: None,
+                password: None,
+                roles: Vec::new(),
+                expires_at: None,
+                metadata: None,
+                require_email_verification: Some(true),
+            })
+            .await
+            .unwrap();
+
+        let err = service
+            .sign_in_with_identity("google", "g-1", Some("ada@example.com"), true)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AccountError::AlreadyExists(_)), "{err}");
+        assert!(service
+            .list_identities(squatter.id.as_str())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[cfg(feature = "turso")]
+    #[tokio::test]
+    async fn test_identity_is_not_linked_to_a_locked_account() {
+        let dir = tempfile::tempdir().unwrap();
+        let (service, _) = turso_service(&dir).await;
+        let account = passwordless_account(&service, "ada@example.com").await;
+        service
+            .lock_account(account.id.as_str(), "too many attempts")
+            .await
+            .unwrap();
+
+        let err = service
+            .sign_in_with_identity("google", "g-1", Some("ada@example.com"), true)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AccountError::AccountInactive { .. }), "{err}");
+        assert!(service
+            .list_identities(account.id.as_str())
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[cfg(feature = "turso")]
+    #[tokio::test]
+    async fn test_verified_email_does_not_link_to_an_mfa_account() {
+        let dir = tempfile::tempdir().unwrap();
+        let (service, storage) = turso_service(&dir).await;
+        let account = passwordless_account(&service, "ada@example.com").await;
+        confirm_authenticator(&storage, &account).await;
+
+        let err = service
+            .sign_in_with_identity("google", "g-1", Some("ada@example.com"), true)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, AccountError::AlreadyExists(_)), "{err}");
+        assert!(service
+            .list_identities(account.id.as_str())
+            .await
+            .unwrap()
+            .is_empty());
+
+        // Linking from the signed-in account still works
+        service
+            .link_identity(
+                account.id.as_str(),
+                "google",
+                "g-1",
+                Some("ada@example.com"),
+            )
+            .await
+            .unwrap();
     }
 }
@@ -3,8 +3,16 @@
 //! Provides a trait for receiving account lifecycle events (creation,
 //! status changes, email verification, etc.). Notifications are dispatched
 //! via `tokio::spawn` so they never block account operations.
+//!
+//! Password reset and email verification tokens are delivered through these
+//! hooks too: implement [`AccountNotification`] with your mailer and send the
+//! token from [`AccountEvent::PasswordResetRequested`] and
+//! [`AccountEvent::EmailVerificationRequested`].
 
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+
+use super::types::MfaMethod;
 
 /// Events emitted during the account lifecycle
 ///
@@ -83,6 +91,109 @@ pub enum AccountEvent {
         /// The account ID
         account_id: String,
     },
+    /// An external identity was linked to the account
+    IdentityLinked {
+        /// The account ID
+        account_id: String,
+        /// Provider name
+        provider: String,
+        /// The user's ID at the provider
+        subject: String,
+    },
+    /// An external identity was unlinked from the account
+    IdentityUnlinked {
+        /// The account ID
+        account_id: String,
+        /// Provider name
+        provider: String,
+        /// The user's ID at the provider
+        subject: String,
+    },
+    /// A TOTP authenticator was confirmed; MFA is now required at login
+    MfaEnrolled {
+        /// The account ID
+        account_id: String,
+    },
+    /// TOTP was removed from the account
+    MfaDisabled {
+        /// The account ID
+        account_id: String,
+    },
+    /// A second factor completed a login
+    MfaVerified {
+        /// The account ID
+        account_id: String,
+        /// The factor used
+        method: MfaMethod,
+    },
+    /// A second factor was rejected (wrong, replayed, or used recovery code)
+    MfaFailed {
+        /// The account ID
+        account_id: String,
+        /// The factor attempted
+        method: MfaMethod,
+    },
+    /// Recovery codes were regenerated, invalidating the previous set
+    RecoveryCodesRegenerated {
+        /// The account ID
+        account_id: String,
+    },
+    /// A passkey was registered to the account
+    #[cfg(feature = "passkeys")]
+    PasskeyRegistered {
+        /// The account ID
+        account_id: String,
+        /// The new passkey's credential ID
+        credential_id: String,
+    },
+    /// A passkey was removed from the account
+    #[cfg(feature = "passkeys")]
+    PasskeyRemoved {
+        /// The account ID
+        account_id: String,
+        /// The removed passkey's credential ID
+        credential_id: String,
+    },
+    /// A passkey presented a signature counter at or below the stored one
+    ///
+    /// The login was refused. The authenticator may have been cloned;
+    /// consider removing the passkey and asking the user to register again.
+    #[cfg(feature = "passkeys")]
+    PasskeySignCountRegressed {
+        /// The account ID
+        account_id: String,
+        /// The passkey's credential ID
+        credential_id: String,
+        /// The counter on record
+        stored: u32,
+        /// The counter the authenticator reported
+        received: u32,
+    },
+    /// A password reset was requested; deliver `token` to `email`
+    ///
+    /// The token is a bearer credential for the account. Send it only to
+    /// `email` and keep it out of logs.
+    PasswordResetRequested {
+        /// The account ID
+        account_id: String,
+        /// Where to send the token
+        email: String,
+        /// The single-use reset token
+        token: String,
+        /// When the token stops being accepted
+        expires_at: DateTime<Utc>,
+    },
+    /// Email verification was requested; deliver `token` to `email`
+    EmailVerificationRequested {
+        /// The account ID
+        account_id: String,
+        /// The address being verified
+        email: String,
+        /// The single-use verification token
+        token: String,
+        /// When the token stops being accepted
+        expires_at: DateTime<Utc>,
+    },
 }
 
 /// Trait for receiving account lifecycle notifications
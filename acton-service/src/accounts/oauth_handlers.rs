@@ -0,0 +1,706 @@
+//! Pre-built OAuth login routes with account linking
+//!
+//! Requires features: `account-handlers` and `oauth`
+//!
+//! `GET /auth/{provider}/login` stores CSRF state (with a PKCE verifier and
+//! nonce) and redirects to the provider. `GET /auth/{provider}/callback`
+//! validates the state, completes the login with the provider, resolves the
+//! external identity to an `Account` via
+//! [`AccountService::sign_in_with_identity`], and then either returns a
+//! [`TokenPair`](crate::auth::tokens::TokenPair) or logs the account into the
+//! session.
+//!
+//! Accounts with a confirmed TOTP authenticator are not signed in by the
+//! callback. With token completion it returns the same `mfa_required` body
+//! as the password login; with session completion it keeps the challenge in
+//! the session and redirects to the MFA page (default `/auth/mfa`). Either
+//! way the code is posted to `POST /auth/mfa` to finish signing in.
+//!
+//! The handlers emit `AuthOAuthAuthorize`, `AuthOAuthCallback`, and
+//! `AuthLoginSuccess`/`AuthLoginFailed` audit events themselves, so providers
+//! registered here should not also be wrapped in `AuditedOAuthProvider`.
+
+use axum::{
+    extract::{Path, Query, Request},
+    http::request::Parts,
+    response::{IntoResponse, Redirect, Response},
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use super::auth_handlers::{account_claims, MfaRequiredResponse};
+use super::handler_audit::{AuthAudit, AuthStep};
+use super::{Account, AccountService, AuthOutcome, MfaChallenge};
+use crate::auth::oauth::{OAuthProvider, OAuthStateManager, StateData};
+use crate::auth::tokens::issuer::TokenIssuer;
+use crate::auth::tokens::refresh::RefreshTokenMetadata;
+use crate::error::Error;
+use crate::middleware::request_context::RequestContext;
+
+/// Build the OAuth login routes
+///
+/// Requires `Extension<Arc<AccountService>>` and `Extension<Arc<OAuthLogin>>`:
+/// ```rust,ignore
+/// let login = OAuthLogin::with_tokens(state_manager, token_issuer)
+///     .with_provider(GoogleProvider::new(&google_config)?)
+///     .with_provider(OidcProvider::discover("okta", &okta_config).await?);
+///
+/// let app = Router::new()
+///     .merge(oauth_login_routes())
+///     .layer(Extension(Arc::new(login)))
+///     .layer(Extension(account_service));
+/// ```
+pub fn oauth_login_routes() -> Router {
+    Router::new()
+        .route("/auth/{provider}/login", get(oauth_login))
+        .route("/auth/{provider}/callback", get(oauth_callback))
+        .route("/auth/mfa", post(oauth_mfa))
+}
+
+/// Session key holding the challenge between the callback and the MFA step
+#[cfg(feature = "session")]
+const PENDING_MFA_KEY: &str = "oauth.pending_mfa";
+
+/// An OAuth login waiting for its second factor (session completion)
+#[cfg(feature = "session")]
+#[derive(serde::Serialize, Deserialize)]
+struct PendingMfa {
+    provider: String,
+    challenge: String,
+    redirect_uri: Option<String>,
+}
+
+/// What the callback hands back once the account is resolved
+pub enum LoginCompletion {
+    /// Respond with a JSON `TokenPair` for the account
+    Tokens(TokenIssuer),
+    /// Log the account into the `AuthSession` and redirect
+    #[cfg(feature = "session")]
+    Session,
+}
+
+/// Providers and completion strategy for [`oauth_login_routes`]
+pub struct OAuthLogin {
+    providers: HashMap<String, Arc<dyn OAuthProvider>>,
+    state_manager: Arc<dyn OAuthStateManager>,
+    completion: LoginCompletion,
+    default_redirect: String,
+    #[cfg_attr(not(feature = "session"), allow(dead_code))]
+    mfa_redirect: String,
+}
+
+impl OAuthLogin {
+    /// Create a login flow with the given completion strategy
+    pub fn new(state_manager: Arc<dyn OAuthStateManager>, completion: LoginCompletion) -> Self {
+        Self {
+            providers: HashMap::new(),
+            state_manager,
+            completion,
+            default_redirect: "/".to_string(),
+            mfa_redirect: "/auth/mfa".to_string(),
+        }
+    }
+
+    /// Create a login flow that issues token pairs
+    pub fn with_tokens(state_manager: Arc<dyn OAuthStateManager>, issuer: TokenIssuer) -> Self {
+        Self::new(state_manager, LoginCompletion::Tokens(issuer))
+    }
+
+    /// Create a login flow that establishes a session
+    ///
+    /// Requires `SessionManagerLayer` on the router.
+    #[cfg(feature = "session")]
+    pub fn with_session(state_manager: Arc<dyn OAuthStateManager>) -> Self {
+        Self::new(state_manager, LoginCompletion::Session)
+    }
+
+    /// Register a provider under its `name()`
+    pub fn with_provider<P: OAuthProvider + 'static>(mut self, provider: P) -> Self {
+        self.providers
+            .insert(provider.name().to_string(), Arc::new(provider));
+        self
+    }
+
+    /// Where session logins land when no `redirect_uri` was requested
+    /// (default: `/`)
+    pub fn with_default_redirect(mut self, path: impl Into<String>) -> Self {
+        self.default_redirect = path.into();
+        self
+    }
+
+    /// Where session logins are sent to enter a second factor
+    /// (default: `/auth/mfa`)
+    ///
+    /// The page should post the code as a form field named `code` to
+    /// `POST /auth/mfa`.
+    #[cfg(feature = "session")]
+    pub fn with_mfa_redirect(mut self, path: impl Into<String>) -> Self {
+        self.mfa_redirect = path.into();
+        self
+    }
+
+    fn provider(&self, name: &str) -> Result<&Arc<dyn OAuthProvider>, Error> {
+        self.providers
+            .get(name)
+            .ok_or_else(|| Error::NotFound(format!("Unknown OAuth provider: {}", name)))
+    }
+}
+
+// ============================================================================
+// Request types
+// ============================================================================
+
+/// Query parameters for starting a login
+#[derive(Debug, Deserialize)]
+pub struct LoginQuery {
+    /// Local path to return to after a session login
+    pub redirect_uri: Option<String>,
+}
+
+/// Query parameters the provider sends to the callback
+#[derive(Debug, Deserialize)]
+pub struct CallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+    pub error_description: Option<String>,
+}
+
+/// Request body for `POST /auth/mfa`
+///
+/// JSON with token completion, a form with session completion.
+#[derive(Debug, Deserialize)]
+pub struct OAuthMfaRequest {
+    /// The challenge from the callback; session logins keep it in the
+    /// session instead
+    #[serde(default)]
+    pub challenge: Option<String>,
+    /// A TOTP code or recovery code
+    pub code: String,
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+async fn oauth_login(
+    Extension(login): Extension<Arc<OAuthLogin>>,
+    Path(provider_name): Path<String>,
+    Query(query): Query<LoginQuery>,
+    request: Request,
+) -> Result<Redirect, Error> {
+    let (parts, _) = request.into_parts();
+    let provider = login.provider(&provider_name)?;
+
+    let mut data = StateData::new(provider_name.as_str()).with_pkce();
+    if let Some(redirect_uri) = query.redirect_uri {
+        if !is_local_path(&redirect_uri) {
+            return Err(Error::BadRequest(
+                "redirect_uri must be a local path".to_string(),
+            ));
+        }
+        data = data.with_redirect_uri(redirect_uri);
+    }
+
+    let state = login.state_manager.create_state(&data).await?;
+    let url = provider.authorization_url_for(&state, &data, &[]);
+
+    AuthAudit::from_parts(&parts)
+        .emit(
+            AuthStep::Authorize,
+            None,
+            serde_json::json!({ "provider": provider_name }),
+        )
+        .await;
+
+    Ok(Redirect::to(&url))
+}
+
+async fn oauth_callback(
+    Extension(svc): Extension<Arc<AccountService>>,
+    Extension(login): Extension<Arc<OAuthLogin>>,
+    Path(provider_name): Path<String>,
+    Query(query): Query<CallbackQuery>,
+    request: Request,
+) -> Result<Response, Error> {
+    let (parts, _) = request.into_parts();
+    let audit = AuthAudit::from_parts(&parts);
+    let provider = login.provider(&provider_name)?;
+
+    if let Some(error) = query.error {
+        let description = query.error_description.unwrap_or_default();
+        audit
+            .emit(
+                AuthStep::CallbackFailed,
+                None,
+                serde_json::json!({
+                    "provider": provider_name,
+                    "outcome": "failure",
+                    "error": error,
+                    "error_description": description,
+                }),
+            )
+            .await;
+        return Err(Error::BadRequest(format!(
+            "{} login failed: {} {}",
+            provider_name, error, description
+        )));
+    }
+
+    let (Some(code), Some(state)) = (query.code, query.state) else {
+        return Err(Error::BadRequest(
+            "OAuth callback is missing code or state".to_string(),
+        ));
+    };
+
+    let data = login.state_manager.validate_state(&state).await?;
+    if data.provider != provider_name {
+        return Err(Error::BadRequest(
+            "OAuth state was issued for a different provider".to_string(),
+        ));
+    }
+
+    let user = match provider.complete_login(&code, &data).await {
+        Ok((_, user)) => {
+            audit
+                .emit(
+                    AuthStep::Callback,
+                    Some(&user.provider_user_id),
+                    serde_json::json!({ "provider": provider_name, "outcome": "success" }),
+                )
+                .await;
+            user
+        }
+        Err(e) => {
+            audit
+                .emit(
+                    AuthStep::CallbackFailed,
+                    None,
+                    serde_json::json!({
+                        "provider": provider_name,
+                        "outcome": "failure",
+                        "error": e.to_string(),
+                    }),
+                )
+                .await;
+            return Err(e);
+        }
+    };
+
+    let (signed_in, outcome) = match svc
+        .sign_in_with_identity(
+            &provider_name,
+            &user.provider_user_id,
+            user.email.as_deref(),
+            user.email_verified,
+        )
+        .await
+    {
+        Ok(signed_in) => signed_in,
+        Err(e) => {
+            audit
+                .emit(
+                    AuthStep::Failed,
+                    Some(&user.provider_user_id),
+                    serde_json::json!({
+                        "method": "oauth",
+                        "provider": provider_name,
+                        "reason": e.to_string(),
+                    }),
+                )
+                .await;
+            return Err(e.into());
+        }
+    };
+
+    let account = match signed_in {
+        AuthOutcome::Authenticated(account) => *account,
+        AuthOutcome::MfaRequired(challenge) => {
+            return require_mfa(&login, parts, &provider_name, challenge, data.redirect_uri).await;
+        }
+    };
+
+    audit
+        .emit(
+            AuthStep::Success,
+            Some(account.id.as_str()),
+            serde_json::json!({
+                "method": "oauth",
+                "provider": provider_name,
+                "account_id": account.id.as_str(),
+                "identity": outcome,
+            }),
+        )
+        .await;
+
+    complete_login(&login, parts, &account, data.redirect_uri).await
+}
+
+/// Finish an OAuth login that stopped for a second factor
+async fn oauth_mfa(
+    Extension(svc): Extension<Arc<AccountService>>,
+    Extension(login): Extension<Arc<OAuthLogin>>,
+    request: Request,
+) -> Result<Response, Error> {
+    use axum::extract::FromRequest;
+    #[cfg(feature = "session")]
+    use axum::extract::FromRequestParts;
+
+    let (parts, body) = request.into_parts();
+    let audit = AuthAudit::from_parts(&parts);
+    let request = Request::from_parts(parts.clone(), body);
+
+    let (provider, challenge, code, redirect_uri) = match &login.completion {
+        LoginCompletion::Tokens(_) => {
+            let Json(body) = Json::<OAuthMfaRequest>::from_request(request, &())
+                .await
+                .map_err(|e| Error::BadRequest(e.body_text()))?;
+            let challenge = body
+                .challenge
+                .ok_or_else(|| Error::BadRequest("challenge is required".to_string()))?;
+            (None, challenge, body.code, None)
+        }
+        #[cfg(feature = "session")]
+        LoginCompletion::Session => {
+            let axum::Form(body) = axum::Form::<OAuthMfaRequest>::from_request(request, &())
+                .await
+                .map_err(|e| Error::BadRequest(e.body_text()))?;
+            let pending = crate::session::SessionAuth::from_request_parts(&mut parts.clone(), &())
+                .await?
+                .session()
+                .get::<PendingMfa>(PENDING_MFA_KEY)
+                .await
+                .map_err(|e| Error::Session(format!("Failed to read MFA challenge: {e}")))?
+                .ok_or_else(|| Error::Unauthorized("No sign-in is waiting for MFA".to_string()))?;
+            (
+                Some(pending.provider),
+                pending.challenge,
+                body.code,
+                pending.redirect_uri,
+            )
+        }
+    };
+
+    let account = match svc.verify_mfa(&challenge, &code).await {
+        Ok(account) => account,
+        Err(e) => {
+            audit
+                .emit(
+                    AuthStep::Failed,
+                    None,
+                    serde_json::json!({
+                        "method": "oauth+mfa",
+                        "provider": provider,
+                        "reason": e.to_string(),
+                    }),
+                )
+                .await;
+            return Err(e.into());
+        }
+    };
+
+    audit
+        .emit(
+            AuthStep::Success,
+            Some(account.id.as_str()),
+            serde_json::json!({
+                "method": "oauth+mfa",
+                "provider": provider,
+                "account_id": account.id.as_str(),
+            }),
+        )
+        .await;
+
+    complete_login(&login, parts, &account, redirect_uri).await
+}
+
+/// Hand back the challenge for an account that has MFA enabled
+#[cfg_attr(not(feature = "session"), allow(unused_variables))]
+async fn require_mfa(
+    login: &OAuthLogin,
+    parts: Parts,
+    provider_name: &str,
+    challenge: MfaChallenge,
+    redirect_uri: Option<String>,
+) -> Result<Response, Error> {
+    match &login.completion {
+        LoginCompletion::Tokens(_) => Ok(Json(MfaRequiredResponse {
+            mfa_required: true,
+            challenge: challenge.token,
+            expires_at: challenge.expires_at,
+        })
+        .into_response()),
+        #[cfg(feature = "session")]
+        LoginCompletion::Session => {
+            use axum::extract::FromRequestParts;
+
+            let mut parts = parts;
+            let session = crate::session::SessionAuth::from_request_parts(&mut parts, &()).await?;
+            session
+                .session()
+                .insert(
+                    PENDING_MFA_KEY,
+                    PendingMfa {
+                        provider: provider_name.to_string(),
+                        challenge: challenge.token,
+                        redirect_uri,
+                    },
+                )
+                .await
+                .map_err(|e| Error::Session(format!("Failed to store MFA challenge: {e}")))?;
+            Ok(Redirect::to(&login.mfa_redirect).into_response())
+        }
+    }
+}
+
+/// Issue tokens for, or log in, an account that has completed sign-in
+async fn complete_login(
+    login: &OAuthLogin,
+    parts: Parts,
+    account: &Account,
+    redirect_uri: Option<String>,
+) -> Result<Response, Error> {
+    match &login.completion {
+        LoginCompletion::Tokens(issuer) => {
+            let claims = account_claims(account)?;
+            let context = parts.extensions.get::<RequestContext>();
+            let metadata = RefreshTokenMetadata {
+                user_agent: context.and_then(|c| c.user_agent.clone()),
+                ip_address: context.and_then(|c| c.ip).map(|ip| ip.to_string()),
+                ..Default::default()
+            };
+            let pair = issuer.issue(&claims, &metadata).await?;
+            Ok(Json(pair).into_response())
+        }
+        #[cfg(feature = "session")]
+        LoginCompletion::Session => {
+            use axum::extract::FromRequestParts;
+
+            let mut parts = parts;
+            let mut session =
+                crate::session::SessionAuth::from_request_parts(&mut parts, &()).await?;
+            // New session ID on privilege change (session fixation)
+            session.regenerate().await?;
+            session
+                .session()
+                .remove::<PendingMfa>(PENDING_MFA_KEY)
+                .await
+                .map_err(|e| Error::Session(format!("Failed to clear MFA challenge: {e}")))?;
+            session
+                .data_mut()
+                .login(account.id.to_string(), account.roles.clone());
+            session.save().await?;
+
+            let target = redirect_uri.unwrap_or_else(|| login.default_redirect.clone());
+            Ok(Redirect::to(&target).into_response())
+        }
+    }
+}
+
+/// Only same-origin paths are accepted as post-login redirects
+fn is_local_path(uri: &str) -> bool {
+    uri.starts_with('/') && !uri.starts_with("//") && !uri.contains('\\')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_is_local_path() {
+        assert!(is_local_path("/"));
+        assert!(is_local_path("/dashboard?tab=1"));
+        assert!(!is_local_path("https://evil.example"));
+        assert!(!is_local_path("//evil.example/path"));
+        assert!(!is_local_path("/\\evil.example"));
+        assert!(!is_local_path("dashboard"));
+    }
+
+    #[cfg(feature = "turso")]
+    mod mfa {
+        use super::*;
+        use crate::accounts::storage::turso::TursoAccountStorage;
+        use crate::accounts::{AccountStorage, AccountsConfig, CreateAccount, TotpEnrollment};
+        use crate::auth::oauth::provider::{OAuthTokens, OAuthUserInfo};
+        use crate::auth::tokens::refresh::TursoRefreshStorage;
+        use crate::auth::tokens::TokenGenerator;
+        use crate::auth::{PasswordHasher, RefreshTokenConfig};
+        use crate::middleware::Claims;
+        use axum::body::Body;
+        use axum::http::StatusCode;
+        use std::time::Duration;
+        use tower::ServiceExt;
+
+        /// Signs everyone in as `g-1`
+        struct StubProvider;
+
+        #[async_trait::async_trait]
+        impl OAuthProvider for StubProvider {
+            fn name(&self) -> &str {
+                "stub"
+            }
+
+            fn authorization_url(&self, _state: &str, _scopes: &[String]) -> String {
+                "https://example.com/auth".to_string()
+            }
+
+            async fn exchange_code(&self, _code: &str) -> Result<OAuthTokens, Error> {
+                Ok(OAuthTokens {
+                    access_token: "provider-token".to_string(),
+                    refresh_token: None,
+                    expires_in: None,
+                    token_type: "Bearer".to_string(),
+                    id_token: None,
+                })
+            }
+
+            async fn get_user_info(&self, _access_token: &str) -> Result<OAuthUserInfo, Error> {
+                Ok(OAuthUserInfo {
+                    provider: "stub".to_string(),
+                    provider_user_id: "g-1".to_string(),
+                    email: Some("ada@example.com".to_string()),
+                    email_verified: true,
+                    name: None,
+                    picture: None,
+                    raw: serde_json::Value::Null,
+                })
+            }
+
+            async fn refresh_token(&self, _refresh_token: &str) -> Result<OAuthTokens, Error> {
+                Err(Error::Internal("unused".to_string()))
+            }
+        }
+
+        /// Accepts any state
+        struct AnyState;
+
+        #[async_trait::async_trait]
+        impl OAuthStateManager for AnyState {
+            async fn create_state(&self, _data: &StateData) -> Result<String, Error> {
+                Ok("state".to_string())
+            }
+
+            async fn validate_state(&self, _state: &str) -> Result<StateData, Error> {
+                Ok(StateData::new("stub"))
+            }
+        }
+
+        #[derive(Clone)]
+        struct UnusedGenerator;
+
+        impl TokenGenerator for UnusedGenerator {
+            fn generate_token(&self, _claims: &Claims) -> Result<String, Error> {
+                Err(Error::Internal("no token may be issued".to_string()))
+            }
+
+            fn generate_token_with_expiry(
+                &self,
+                claims: &Claims,
+                _expires_in: Duration,
+            ) -> Result<String, Error> {
+                self.generate_token(claims)
+            }
+
+            fn default_lifetime(&self) -> Duration {
+                Duration::from_secs(900)
+            }
+        }
+
+        async fn body_json(response: Response) -> serde_json::Value {
+            let bytes = axum::body::to_bytes(response.into_body(), usize::MAX)
+                .await
+                .unwrap();
+            serde_json::from_slice(&bytes).unwrap()
+        }
+
+        #[tokio::test]
+        async fn test_callback_routes_mfa_accounts_to_the_mfa_step() {
+            let dir = tempfile::tempdir().unwrap();
+            let db = Arc::new(
+                libsql::Builder::new_local(dir.path().join("accounts.db"))
+                    .build()
+                    .await
+                    .unwrap(),
+            );
+            let storage: Arc<dyn AccountStorage> =
+                Arc::new(TursoAccountStorage::new(db.clone()).await.unwrap());
+            let svc = AccountService::new(
+                storage.clone(),
+                PasswordHasher::default(),
+                AccountsConfig::default(),
+            )
+            .with_mfa_key([7; 32]);
+
+            let account = svc
+                .create_account(CreateAccount {
+                    email: "ada@example.com".to_string(),
+                    username: None,
+                    password: None,
+                    roles: Vec::new(),
+                    expires_at: None,
+                    metadata: None,
+                    require_email_verification: Some(false),
+                })
+                .await
+                .unwrap();
+            svc.link_identity(account.id.as_str(), "stub", "g-1", None)
+                .await
+                .unwrap();
+            storage
+                .save_totp(&TotpEnrollment {
+                    account_id: account.id.clone(),
+                    secret_encrypted: "sealed".to_string(),
+                    confirmed_at: Some(chrono::Utc::now()),
+                    last_used_step: None,
+                    created_at: chrono::Utc::now(),
+                })
+                .await
+                .unwrap();
+
+            let issuer = TokenIssuer::new(
+                UnusedGenerator,
+                Arc::new(TursoRefreshStorage::new(Arc::new(db.connect().unwrap()))),
+                &RefreshTokenConfig::default(),
+            );
+            let login =
+                OAuthLogin::with_tokens(Arc::new(AnyState), issuer).with_provider(StubProvider);
+            let app = oauth_login_routes()
+                .layer(Extension(Arc::new(login)))
+                .layer(Extension(Arc::new(svc)));
+
+            let response = app
+                .clone()
+                .oneshot(
+                    Request::get("/auth/stub/callback?code=c&state=state")
+                        .body(Body::empty())
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let body = body_json(response).await;
+            assert_eq!(body["mfa_required"], true);
+            assert!(body.get("access_token").is_none(), "{body}");
+
+            let response = app
+                .oneshot(
+                    Request::post("/auth/mfa")
+                        .header("content-type", "application/json")
+                        .body(Body::from(
+                            serde_json::json!({
+                                "challenge": body["challenge"],
+                                "code": "wrong-guess",
+                            })
+                            .to_string(),
+                        ))
+                        .unwrap(),
+                )
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+}
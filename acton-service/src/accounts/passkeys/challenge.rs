@@ -0,0 +1,290 @@
+//! Pending ceremony storage
+//!
+//! The challenge in a ceremony's options has to outlive the request that
+//! issued it and be accepted at most once, or a signed response could be
+//! replayed. [`PasskeyChallengeStore`] holds each [`PasskeyCeremony`] from
+//! the options request until the browser's response consumes it.
+//!
+//! # Available Backends
+//!
+//! - **Redis** (`cache` feature): [`RedisPasskeyChallengeStore`], shared
+//!   between replicas
+//! - **Session** (`session` feature): [`SessionPasskeyChallengeStore`], kept
+//!   in the caller's own session
+
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::error::Error;
+
+/// Which ceremony a challenge was issued for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PasskeyCeremonyKind {
+    /// Registering a new passkey
+    Registration,
+    /// Signing in with a passkey
+    Authentication,
+}
+
+/// A ceremony waiting for the browser's response
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PasskeyCeremony {
+    challenge: String,
+    kind: PasskeyCeremonyKind,
+    account_id: Option<String>,
+    expires_at: DateTime<Utc>,
+}
+
+impl PasskeyCeremony {
+    /// Start a ceremony with a fresh 256-bit challenge
+    pub(crate) fn new(
+        kind: PasskeyCeremonyKind,
+        account_id: Option<String>,
+        ttl_secs: u64,
+    ) -> Self {
+        use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        use base64::Engine;
+
+        let bytes: [u8; 32] = rand::random();
+        Self {
+            challenge: URL_SAFE_NO_PAD.encode(bytes),
+            kind,
+            account_id,
+            expires_at: Utc::now() + Duration::seconds(ttl_secs as i64),
+        }
+    }
+
+    /// The challenge (base64url), which is also the storage key
+    pub fn challenge(&self) -> &str {
+        &self.challenge
+    }
+
+    /// Which ceremony this is
+    pub fn kind(&self) -> PasskeyCeremonyKind {
+        self.kind
+    }
+
+    /// The account registering, or the account named at login
+    pub fn account_id(&self) -> Option<&str> {
+        self.account_id.as_deref()
+    }
+
+    /// When the challenge stops being accepted
+    pub fn expires_at(&self) -> DateTime<Utc> {
+        self.expires_at
+    }
+
+    /// Whether the challenge has expired
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+}
+
+/// Trait for pending ceremony storage backends
+#[async_trait]
+pub trait PasskeyChallengeStore: Send + Sync {
+    /// Keep a ceremony until it is taken or expires
+    async fn save(&self, ceremony: &PasskeyCeremony) -> Result<(), Error>;
+
+    /// Remove and return the ceremony for a challenge
+    ///
+    /// Must be atomic: a ceremony is returned to at most one caller. Expired
+    /// ceremonies may still be returned; the caller checks expiry.
+    async fn take(&self, challenge: &str) -> Result<Option<PasskeyCeremony>, Error>;
+}
+
+// ============================================================================
+// Redis backend
+// ============================================================================
+
+/// Redis-backed ceremony storage
+///
+/// Stores each ceremony as JSON under `passkey_challenge:{challenge}` with a
+/// TTL matching its expiry, and takes it with `GETDEL`.
+#[cfg(feature = "cache")]
+#[derive(Clone)]
+pub struct RedisPasskeyChallengeStore {
+    pool: deadpool_redis::Pool,
+    key_prefix: String,
+}
+
+#[cfg(feature = "cache")]
+impl RedisPasskeyChallengeStore {
+    /// Create a new Redis ceremony store
+    pub fn new(pool: deadpool_redis::Pool) -> Self {
+        Self {
+            pool,
+            key_prefix: "passkey_challenge".to_string(),
+        }
+    }
+
+    fn key(&self, challenge: &str) -> String {
+        format!("{}:{}", self.key_prefix, challenge)
+    }
+
+    async fn connection(&self) -> Result<deadpool_redis::Connection, Error> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to get Redis connection: {}", e)))
+    }
+}
+
+#[cfg(feature = "cache")]
+#[async_trait]
+impl PasskeyChallengeStore for RedisPasskeyChallengeStore {
+    async fn save(&self, ceremony: &PasskeyCeremony) -> Result<(), Error> {
+        use redis::AsyncCommands;
+
+        let mut conn = self.connection().await?;
+        let json = serde_json::to_string(ceremony)
+            .map_err(|e| Error::Internal(format!("Failed to serialize passkey ceremony: {}", e)))?;
+        let ttl_secs = (ceremony.expires_at - Utc::now()).num_seconds().max(1) as u64;
+
+        conn.set_ex::<_, _, ()>(self.key(&ceremony.challenge), json, ttl_secs)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to save passkey ceremony: {}", e)))
+    }
+
+    async fn take(&self, challenge: &str) -> Result<Option<PasskeyCeremony>, Error> {
+        use std::ops::DerefMut;
+
+        let mut conn = self.connection().await?;
+        let json: Option<String> = redis::cmd("GETDEL")
+            .arg(self.key(challenge))
+            .query_async(conn.deref_mut())
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to take passkey ceremony: {}", e)))?;
+
+        json.map(|j| {
+            serde_json::from_str(&j)
+                .map_err(|e| Error::Internal(format!("Failed to parse passkey ceremony: {}", e)))
+        })
+        .transpose()
+    }
+}
+
+// ============================================================================
+// Session backend
+// ============================================================================
+
+/// Ceremony storage in the caller's session
+///
+/// Holds one ceremony per session: starting another replaces it, so only
+/// the most recent options a browser received can be completed. Needs no
+/// shared store, but the session must reach the response request (the
+/// session cookie's `SameSite` setting has to allow it).
+#[cfg(feature = "session")]
+#[derive(Clone)]
+pub struct SessionPasskeyChallengeStore {
+    session: tower_sessions::Session,
+}
+
+#[cfg(feature = "session")]
+impl SessionPasskeyChallengeStore {
+    const SESSION_KEY: &'static str = "_passkey_ceremony";
+
+    /// Store ceremonies in this request's session
+    pub fn new(session: tower_sessions::Session) -> Self {
+        Self { session }
+    }
+}
+
+#[cfg(feature = "session")]
+#[async_trait]
+impl PasskeyChallengeStore for SessionPasskeyChallengeStore {
+    async fn save(&self, ceremony: &PasskeyCeremony) -> Result<(), Error> {
+        self.session
+            .insert(Self::SESSION_KEY, ceremony)
+            .await
+            .map_err(|e| Error::Session(format!("Failed to save passkey ceremony: {e}")))
+    }
+
+    async fn take(&self, challenge: &str) -> Result<Option<PasskeyCeremony>, Error> {
+        let ceremony: Option<PasskeyCeremony> = self
+            .session
+            .remove(Self::SESSION_KEY)
+            .await
+            .map_err(|e| Error::Session(format!("Failed to take passkey ceremony: {e}")))?;
+
+        Ok(ceremony.filter(|c| c.challenge == challenge))
+    }
+}
+
+// ============================================================================
+// Handler integration
+// ============================================================================
+
+/// The ceremony store for a request
+///
+/// An installed `Extension<Arc<dyn PasskeyChallengeStore>>` wins; otherwise
+/// the request's session is used when a session layer is present.
+#[cfg(feature = "account-handlers")]
+pub(crate) struct Challenges(pub(crate) std::sync::Arc<dyn PasskeyChallengeStore>);
+
+#[cfg(feature = "account-handlers")]
+impl<S> axum::extract::FromRequestParts<S> for Challenges
+where
+    S: Send + Sync,
+{
+    type Rejection = Error;
+
+    async fn from_request_parts(
+        parts: &mut axum::http::request::Parts,
+        _state: &S,
+    ) -> Result<Self, Self::Rejection> {
+        if let Some(store) = parts
+            .extensions
+            .get::<std::sync::Arc<dyn PasskeyChallengeStore>>()
+        {
+            return Ok(Self(store.clone()));
+        }
+
+        #[cfg(feature = "session")]
+        if let Some(session) = parts.extensions.get::<tower_sessions::Session>() {
+            return Ok(Self(std::sync::Arc::new(
+                SessionPasskeyChallengeStore::new(session.clone()),
+            )));
+        }
+
+        Err(Error::Internal(
+            "No passkey challenge store: add Extension<Arc<dyn PasskeyChallengeStore>> or a session layer"
+                .to_string(),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ceremony_challenges_are_random() {
+        let a = PasskeyCeremony::new(PasskeyCeremonyKind::Registration, None, 300);
+        let b = PasskeyCeremony::new(PasskeyCeremonyKind::Registration, None, 300);
+        assert_eq!(a.challenge().len(), 43);
+        assert_ne!(a.challenge(), b.challenge());
+        assert!(!a.is_expired());
+    }
+
+    #[test]
+    fn test_ceremony_serde_roundtrip() {
+        let ceremony = PasskeyCeremony::new(
+            PasskeyCeremonyKind::Authentication,
+            Some("acct_01k7q9r3fhe2tb6t2v7r5y9w4d".to_string()),
+            60,
+        );
+        let json = serde_json::to_string(&ceremony).unwrap();
+        assert!(json.contains("\"kind\":\"authentication\""));
+        let parsed: PasskeyCeremony = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, ceremony);
+    }
+
+    #[test]
+    fn test_zero_ttl_ceremony_is_expired() {
+        let ceremony = PasskeyCeremony::new(PasskeyCeremonyKind::Registration, None, 0);
+        assert!(ceremony.is_expired());
+    }
+}
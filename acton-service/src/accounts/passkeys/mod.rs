@@ -0,0 +1,394 @@
+//! WebAuthn passkeys: passwordless login with a platform authenticator or
+//! security key
+//!
+//! Requires feature: `passkeys`
+//!
+//! Each ceremony takes two requests. The server issues options with a
+//! single-use challenge, kept in a [`PasskeyChallengeStore`] until the
+//! browser returns the authenticator's response:
+//!
+//! 1. [`AccountService::start_passkey_registration`] /
+//!    [`finish_passkey_registration`](AccountService::finish_passkey_registration)
+//!    add a passkey to a signed-in account.
+//! 2. [`AccountService::start_passkey_login`] /
+//!    [`finish_passkey_login`](AccountService::finish_passkey_login) sign in.
+//!    Passkeys are discoverable, so the email is optional.
+//!
+//! Supported algorithms are ES256 and EdDSA, which covers platform
+//! authenticators, password managers, and current security keys.
+//! Attestation is not requested. A signature counter that fails to increase
+//! refuses the login and emits
+//! [`AccountEvent::PasskeySignCountRegressed`](super::AccountEvent::PasskeySignCountRegressed).
+
+mod challenge;
+mod verify;
+pub mod webauthn;
+
+#[cfg(feature = "account-handlers")]
+pub(crate) use challenge::Challenges;
+#[cfg(feature = "cache")]
+pub use challenge::RedisPasskeyChallengeStore;
+#[cfg(feature = "session")]
+pub use challenge::SessionPasskeyChallengeStore;
+pub use challenge::{PasskeyCeremony, PasskeyCeremonyKind, PasskeyChallengeStore};
+pub use webauthn::{
+    PasskeyAssertion, PasskeyCreationOptions, PasskeyRegistration, PasskeyRequestOptions,
+};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::Utc;
+
+use super::types::PasskeyCredential;
+use super::{ensure_active, Account, AccountError, AccountEvent, AccountService};
+use crate::error::Error;
+use webauthn::{
+    AuthenticatorSelection, CredentialDescriptor, CredentialParameters, PasskeyUser, RelyingParty,
+};
+
+impl AccountService {
+    /// Start adding a passkey to an account
+    ///
+    /// Returns options for `navigator.credentials.create()`. Passkeys the
+    /// account already has are excluded, so an authenticator can't register
+    /// twice.
+    pub async fn start_passkey_registration(
+        &self,
+        account_id: &str,
+        challenges: &dyn PasskeyChallengeStore,
+    ) -> Result<PasskeyCreationOptions, AccountError> {
+        let account = self.require_account(account_id).await?;
+        ensure_active(&account)?;
+
+        let existing = self.list_passkeys(account_id).await?;
+        let config = &self.config.passkeys;
+        let ceremony = PasskeyCeremony::new(
+            PasskeyCeremonyKind::Registration,
+            Some(account_id.to_string()),
+            config.challenge_ttl_secs,
+        );
+        challenges.save(&ceremony).await.map_err(storage_error)?;
+
+        Ok(PasskeyCreationOptions {
+            rp: RelyingParty {
+                id: config.rp_id.clone(),
+                name: config.rp_name.clone(),
+            },
+            user: PasskeyUser {
+                id: URL_SAFE_NO_PAD.encode(account.id.as_str()),
+                name: account.email.clone(),
+                display_name: account.username.clone().unwrap_or(account.email),
+            },
+            challenge: ceremony.challenge().to_string(),
+            pub_key_cred_params: [verify::COSE_ES256, verify::COSE_EDDSA]
+                .into_iter()
+                .map(|alg| CredentialParameters {
+                    kind: PUBLIC_KEY.to_string(),
+                    alg,
+                })
+                .collect(),
+            timeout: config.challenge_ttl_secs * 1000,
+            exclude_credentials: existing.iter().map(descriptor).collect(),
+            authenticator_selection: AuthenticatorSelection {
+                resident_key: "required".to_string(),
+                require_resident_key: true,
+                user_verification: self.user_verification(),
+            },
+            attestation: "none".to_string(),
+        })
+    }
+
+    /// Complete passkey registration with the browser's response
+    ///
+    /// Verifies the response against the challenge issued to this account
+    /// and stores the new passkey under an optional label.
+    pub async fn finish_passkey_registration(
+        &self,
+        account_id: &str,
+        response: &PasskeyRegistration,
+        name: Option<String>,
+        challenges: &dyn PasskeyChallengeStore,
+    ) -> Result<PasskeyCredential, AccountError> {
+        let client_data_json = decode(&response.response.client_data_json)?;
+        let ceremony = self
+            .take_ceremony(
+                &client_data_json,
+                PasskeyCeremonyKind::Registration,
+                challenges,
+            )
+            .await?;
+        if ceremony.account_id() != Some(account_id) {
+            return Err(AccountError::InvalidToken);
+        }
+
+        let account = self.require_account(account_id).await?;
+        ensure_active(&account)?;
+
+        let verified = verify::verify_registration(
+            &client_data_json,
+            &decode(&response.response.attestation_object)?,
+            ceremony.challenge(),
+            &self.config.passkeys,
+        )?;
+        let credential_id = URL_SAFE_NO_PAD.encode(&verified.credential_id);
+        if response.id != credential_id {
+            return Err(AccountError::Passkey(
+                "credential ID does not match authenticator data".into(),
+            ));
+        }
+
+        let credential = PasskeyCredential {
+            credential_id: credential_id.clone(),
+            account_id: account.id.clone(),
+            public_key: URL_SAFE_NO_PAD.encode(&verified.public_key),
+            sign_count: verified.sign_count,
+            aaguid: uuid::Uuid::from_bytes(verified.aaguid).to_string(),
+            transports: response.response.transports.clone(),
+            backup_eligible: verified.backup_eligible,
+            backed_up: verified.backed_up,
+            name: name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+            created_at: Utc::now(),
+            last_used_at: None,
+        };
+
+        match self.storage.create_passkey(&credential).await {
+            Ok(()) => {}
+            Err(Error::Conflict(_)) => {
+                return Err(AccountError::AlreadyExists(format!(
+                    "passkey {}",
+                    credential_id
+                )))
+            }
+            Err(e) => return Err(storage_error(e)),
+        }
+
+        self.notify(AccountEvent::PasskeyRegistered {
+            account_id: account_id.to_string(),
+            credential_id,
+        });
+
+        Ok(credential)
+    }
+
+    /// Start a passkey login
+    ///
+    /// Returns options for `navigator.credentials.get()`. With an email for
+    /// a known account, only that account's passkeys are offered and the
+    /// login must come from one of them; without one, the browser lets the
+    /// user pick any passkey for this site. An unknown email is treated as
+    /// no email.
+    pub async fn start_passkey_login(
+        &self,
+        email: Option<&str>,
+        challenges: &dyn PasskeyChallengeStore,
+    ) -> Result<PasskeyRequestOptions, AccountError> {
+        let account = match email.map(|e| e.trim()).filter(|e| !e.is_empty()) {
+            Some(email) => self.get_account_by_email(&email.to_lowercase()).await?,
+            None => None,
+        };
+        let allow_credentials = match &account {
+            Some(account) => self
+                .list_passkeys(account.id.as_str())
+                .await?
+                .iter()
+                .map(descriptor)
+                .collect(),
+            None => Vec::new(),
+        };
+
+        let config = &self.config.passkeys;
+        let ceremony = PasskeyCeremony::new(
+            PasskeyCeremonyKind::Authentication,
+            account.map(|a| a.id.to_string()),
+            config.challenge_ttl_secs,
+        );
+        challenges.save(&ceremony).await.map_err(storage_error)?;
+
+        Ok(PasskeyRequestOptions {
+            challenge: ceremony.challenge().to_string(),
+            timeout: config.challenge_ttl_secs * 1000,
+            rp_id: config.rp_id.clone(),
+            allow_credentials,
+            user_verification: self.user_verification(),
+        })
+    }
+
+    /// Complete a passkey login with the browser's response
+    ///
+    /// Checks:
+    /// 1. The challenge was issued for a login and hasn't been used
+    /// 2. The passkey is registered, to the account named at the start if any
+    /// 3. The signature verifies with the stored public key
+    /// 4. Account is Active
+    /// 5. The signature counter moved forward
+    ///
+    /// Updates the passkey's counter and `last_login_at` on success.
+    pub async fn finish_passkey_login(
+        &self,
+        response: &PasskeyAssertion,
+        challenges: &dyn PasskeyChallengeStore,
+    ) -> Result<Account, AccountError> {
+        let client_data_json = decode(&response.response.client_data_json)?;
+        let ceremony = self
+            .take_ceremony(
+                &client_data_json,
+                PasskeyCeremonyKind::Authentication,
+                challenges,
+            )
+            .await?;
+
+        let credential = self
+            .storage
+            .get_passkey(&response.id)
+            .await
+            .map_err(storage_error)?
+            .ok_or(AccountError::InvalidCredentials)?;
+        let account_id = credential.account_id.as_str();
+
+        if ceremony
+            .account_id()
+            .is_some_and(|expected| expected != account_id)
+        {
+            return Err(AccountError::InvalidCredentials);
+        }
+        if let Some(user_handle) = &response.response.user_handle {
+            if decode(user_handle)? != account_id.as_bytes() {
+                return Err(AccountError::InvalidCredentials);
+            }
+        }
+
+        let verified = verify::verify_assertion(
+            &client_data_json,
+            &decode(&response.response.authenticator_data)?,
+            &decode(&response.response.signature)?,
+            &decode(&credential.public_key)?,
+            ceremony.challenge(),
+            &self.config.passkeys,
+        )?;
+
+        let account = self.require_account(account_id).await?;
+        ensure_active(&account)?;
+
+        let recorded = !verify::sign_count_regressed(credential.sign_count, verified.sign_count)
+            && self
+                .storage
+                .record_passkey_use(
+                    &credential.credential_id,
+                    verified.sign_count,
+                    verified.backed_up,
+                    Utc::now(),
+                )
+                .await
+                .map_err(storage_error)?;
+        if !recorded {
+            self.notify(AccountEvent::PasskeySignCountRegressed {
+                account_id: account_id.to_string(),
+                credential_id: credential.credential_id.clone(),
+                stored: credential.sign_count,
+                received: verified.sign_count,
+            });
+            return Err(AccountError::Passkey(
+                "signature counter did not increase".into(),
+            ));
+        }
+
+        let _ = self.storage.record_login(account_id).await;
+
+        Ok(account)
+    }
+
+    /// List the passkeys registered to an account, oldest first
+    pub async fn list_passkeys(
+        &self,
+        account_id: &str,
+    ) -> Result<Vec<PasskeyCredential>, AccountError> {
+        self.storage
+            .list_passkeys(account_id)
+            .await
+            .map_err(storage_error)
+    }
+
+    /// Remove a passkey from an account
+    pub async fn remove_passkey(
+        &self,
+        account_id: &str,
+        credential_id: &str,
+    ) -> Result<(), AccountError> {
+        let removed = self
+            .storage
+            .delete_passkey(account_id, credential_id)
+            .await
+            .map_err(storage_error)?;
+        if !removed {
+            return Err(AccountError::NotFound(format!("passkey {}", credential_id)));
+        }
+
+        self.notify(AccountEvent::PasskeyRemoved {
+            account_id: account_id.to_string(),
+            credential_id: credential_id.to_string(),
+        });
+
+        Ok(())
+    }
+
+    /// The account a passkey belongs to, for per-account login lockout
+    #[cfg(feature = "account-handlers")]
+    pub(crate) async fn passkey_account(
+        &self,
+        credential_id: &str,
+    ) -> Result<Option<Account>, AccountError> {
+        match self
+            .storage
+            .get_passkey(credential_id)
+            .await
+            .map_err(storage_error)?
+        {
+            Some(credential) => self.get_account(credential.account_id.as_str()).await,
+            None => Ok(None),
+        }
+    }
+
+    /// Consume the ceremony a response's client data names
+    async fn take_ceremony(
+        &self,
+        client_data_json: &[u8],
+        kind: PasskeyCeremonyKind,
+        challenges: &dyn PasskeyChallengeStore,
+    ) -> Result<PasskeyCeremony, AccountError> {
+        let client_data = verify::parse_client_data(client_data_json)?;
+        challenges
+            .take(&client_data.challenge)
+            .await
+            .map_err(storage_error)?
+            .filter(|c| c.kind() == kind && !c.is_expired())
+            .ok_or(AccountError::InvalidToken)
+    }
+
+    fn user_verification(&self) -> String {
+        if self.config.passkeys.require_user_verification {
+            "required".to_string()
+        } else {
+            "preferred".to_string()
+        }
+    }
+}
+
+const PUBLIC_KEY: &str = "public-key";
+
+fn descriptor(credential: &PasskeyCredential) -> CredentialDescriptor {
+    CredentialDescriptor {
+        kind: PUBLIC_KEY.to_string(),
+        id: credential.credential_id.clone(),
+        transports: credential.transports.clone(),
+    }
+}
+
+fn decode(field: &str) -> Result<Vec<u8>, AccountError> {
+    URL_SAFE_NO_PAD
+        .decode(field.trim_end_matches('='))
+        .map_err(|_| AccountError::Passkey("malformed base64url field".into()))
+}
+
+fn storage_error(err: Error) -> AccountError {
+    AccountError::Storage(err.to_string())
+}
@@ -0,0 +1,590 @@
+//! WebAuthn ceremony verification
+//!
+//! Checks what the browser and authenticator return against what the server
+//! asked for (WebAuthn Level 2, §7.1 registration and §7.2 authentication).
+//!
+//! Attestation statements are not verified. Registration asks for
+//! `attestation: "none"`, so the authenticator is trusted only for the
+//! public key it creates, which is all a passkey login relies on.
+
+use ciborium::Value;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+
+use crate::accounts::config::PasskeyConfig;
+use crate::accounts::error::AccountError;
+
+/// COSE algorithm: ECDSA with P-256 and SHA-256
+pub(crate) const COSE_ES256: i64 = -7;
+
+/// COSE algorithm: Ed25519
+pub(crate) const COSE_EDDSA: i64 = -8;
+
+const FLAG_USER_PRESENT: u8 = 0x01;
+const FLAG_USER_VERIFIED: u8 = 0x04;
+const FLAG_BACKUP_ELIGIBLE: u8 = 0x08;
+const FLAG_BACKED_UP: u8 = 0x10;
+const FLAG_ATTESTED_CREDENTIAL: u8 = 0x40;
+
+/// rpIdHash, flags, and signCount
+const AUTH_DATA_HEADER_LEN: usize = 37;
+
+const TYPE_CREATE: &str = "webauthn.create";
+const TYPE_GET: &str = "webauthn.get";
+
+/// The fields of `clientDataJSON` the server checks
+#[derive(Debug, Deserialize)]
+pub(crate) struct ClientData {
+    #[serde(rename = "type")]
+    pub ceremony_type: String,
+    pub challenge: String,
+    pub origin: String,
+    #[serde(default, rename = "crossOrigin")]
+    pub cross_origin: bool,
+}
+
+/// A passkey accepted at registration
+#[derive(Debug)]
+pub(crate) struct VerifiedRegistration {
+    pub credential_id: Vec<u8>,
+    /// The COSE key exactly as the authenticator encoded it
+    pub public_key: Vec<u8>,
+    pub sign_count: u32,
+    pub aaguid: [u8; 16],
+    pub backup_eligible: bool,
+    pub backed_up: bool,
+}
+
+/// A login signature accepted for a stored passkey
+#[derive(Debug)]
+pub(crate) struct VerifiedAssertion {
+    pub sign_count: u32,
+    pub backed_up: bool,
+}
+
+struct AuthenticatorData {
+    rp_id_hash: [u8; 32],
+    flags: u8,
+    sign_count: u32,
+    attested: Option<AttestedCredential>,
+}
+
+struct AttestedCredential {
+    aaguid: [u8; 16],
+    credential_id: Vec<u8>,
+    public_key: Vec<u8>,
+}
+
+enum PublicKey {
+    Es256(p256::ecdsa::VerifyingKey),
+    EdDsa(ed25519_dalek::VerifyingKey),
+}
+
+/// Parse `clientDataJSON`
+pub(crate) fn parse_client_data(json: &[u8]) -> Result<ClientData, AccountError> {
+    serde_json::from_slice(json).map_err(|e| reject(format!("malformed client data: {}", e)))
+}
+
+/// Verify a registration response (`navigator.credentials.create()`)
+pub(crate) fn verify_registration(
+    client_data_json: &[u8],
+    attestation_object: &[u8],
+    challenge: &str,
+    config: &PasskeyConfig,
+) -> Result<VerifiedRegistration, AccountError> {
+    check_client_data(client_data_json, TYPE_CREATE, challenge, config)?;
+
+    let attestation: Value = ciborium::de::from_reader(attestation_object)
+        .map_err(|e| reject(format!("malformed attestation object: {}", e)))?;
+    let auth_data = attestation
+        .as_map()
+        .and_then(|map| text_key(map, "authData"))
+        .and_then(Value::as_bytes)
+        .ok_or_else(|| reject("attestation object has no authenticator data"))?;
+
+    let data = parse_authenticator_data(auth_data)?;
+    check_authenticator_data(&data, config)?;
+
+    let attested = data
+        .attested
+        .ok_or_else(|| reject("no credential in authenticator data"))?;
+    // Fails for unsupported algorithms, so they're refused up front rather
+    // than at the first login
+    parse_cose_key(&attested.public_key)?;
+
+    let backup_eligible = data.flags & FLAG_BACKUP_ELIGIBLE != 0;
+    let backed_up = data.flags & FLAG_BACKED_UP != 0;
+    if backed_up && !backup_eligible {
+        return Err(reject("backed up credential is not backup eligible"));
+    }
+
+    Ok(VerifiedRegistration {
+        credential_id: attested.credential_id,
+        public_key: attested.public_key,
+        sign_count: data.sign_count,
+        aaguid: attested.aaguid,
+        backup_eligible,
+        backed_up,
+    })
+}
+
+/// Verify a login response (`navigator.credentials.get()`) against a stored key
+pub(crate) fn verify_assertion(
+    client_data_json: &[u8],
+    authenticator_data: &[u8],
+    signature: &[u8],
+    public_key: &[u8],
+    challenge: &str,
+    config: &PasskeyConfig,
+) -> Result<VerifiedAssertion, AccountError> {
+    check_client_data(client_data_json, TYPE_GET, challenge, config)?;
+
+    let data = parse_authenticator_data(authenticator_data)?;
+    check_authenticator_data(&data, config)?;
+
+    let mut signed = authenticator_data.to_vec();
+    signed.extend_from_slice(&Sha256::digest(client_data_json));
+    if !parse_cose_key(public_key)?.verify(&signed, signature) {
+        return Err(reject("invalid signature"));
+    }
+
+    Ok(VerifiedAssertion {
+        sign_count: data.sign_count,
+        backed_up: data.flags & FLAG_BACKED_UP != 0,
+    })
+}
+
+/// Whether a reported signature counter suggests a cloned authenticator
+///
+/// Authenticators without a counter always report 0. Any other counter must
+/// increase on every use (WebAuthn §6.1.1), so a value at or below the one
+/// on record means two copies of the key are in use.
+pub(crate) fn sign_count_regressed(stored: u32, received: u32) -> bool {
+    (stored != 0 || received != 0) && received <= stored
+}
+
+fn check_client_data(
+    json: &[u8],
+    ceremony_type: &str,
+    challenge: &str,
+    config: &PasskeyConfig,
+) -> Result<(), AccountError> {
+    let client_data = parse_client_data(json)?;
+
+    if client_data.ceremony_type != ceremony_type {
+        return Err(reject(format!(
+            "expected a {} response, got {}",
+            ceremony_type, client_data.ceremony_type
+        )));
+    }
+    if client_data.challenge != challenge {
+        return Err(reject("challenge mismatch"));
+    }
+    if !config.allows_origin(&client_data.origin) {
+        return Err(reject(format!(
+            "origin not allowed: {}",
+            client_data.origin
+        )));
+    }
+    if client_data.cross_origin {
+        return Err(reject("cross-origin ceremonies are not allowed"));
+    }
+    Ok(())
+}
+
+fn check_authenticator_data(
+    data: &AuthenticatorData,
+    config: &PasskeyConfig,
+) -> Result<(), AccountError> {
+    let expected: [u8; 32] = Sha256::digest(config.rp_id.as_bytes()).into();
+    if data.rp_id_hash != expected {
+        return Err(reject("relying party ID mismatch"));
+    }
+    if data.flags & FLAG_USER_PRESENT == 0 {
+        return Err(reject("user not present"));
+    }
+    if config.require_user_verification && data.flags & FLAG_USER_VERIFIED == 0 {
+        return Err(reject("user not verified"));
+    }
+    Ok(())
+}
+
+fn parse_authenticator_data(bytes: &[u8]) -> Result<AuthenticatorData, AccountError> {
+    if bytes.len() < AUTH_DATA_HEADER_LEN {
+        return Err(reject("authenticator data too short"));
+    }
+    let mut rp_id_hash = [0u8; 32];
+    rp_id_hash.copy_from_slice(&bytes[..32]);
+    let flags = bytes[32];
+    let sign_count = u32::from_be_bytes([bytes[33], bytes[34], bytes[35], bytes[36]]);
+
+    let attested = if flags & FLAG_ATTESTED_CREDENTIAL != 0 {
+        Some(parse_attested_credential(&bytes[AUTH_DATA_HEADER_LEN..])?)
+    } else {
+        None
+    };
+
+    Ok(AuthenticatorData {
+        rp_id_hash,
+        flags,
+        sign_count,
+        attested,
+    })
+}
+
+/// aaguid (16), credentialIdLength (2), credentialId, then the COSE key
+///
+/// Extensions may follow the key, so its length comes from decoding it.
+fn parse_attested_credential(bytes: &[u8]) -> Result<AttestedCredential, AccountError> {
+    if bytes.len() < 18 {
+        return Err(reject("attested credential data too short"));
+    }
+    let mut aaguid = [0u8; 16];
+    aaguid.copy_from_slice(&bytes[..16]);
+    let id_len = u16::from_be_bytes([bytes[16], bytes[17]]) as usize;
+    let rest = &bytes[18..];
+    if id_len == 0 || rest.len() < id_len {
+        return Err(reject("credential ID length out of range"));
+    }
+    let (credential_id, key_and_extensions) = rest.split_at(id_len);
+
+    let mut cursor = std::io::Cursor::new(key_and_extensions);
+    let _: Value = ciborium::de::from_reader(&mut cursor)
+        .map_err(|e| reject(format!("malformed credential public key: {}", e)))?;
+    let key_len = cursor.position() as usize;
+
+    Ok(AttestedCredential {
+        aaguid,
+        credential_id: credential_id.to_vec(),
+        public_key: key_and_extensions[..key_len].to_vec(),
+    })
+}
+
+/// Decode a COSE_Key (RFC 9053) for one of the supported algorithms
+fn parse_cose_key(encoded: &[u8]) -> Result<PublicKey, AccountError> {
+    let value: Value = ciborium::de::from_reader(encoded)
+        .map_err(|e| reject(format!("malformed COSE key: {}", e)))?;
+    let map = value
+        .as_map()
+        .ok_or_else(|| reject("COSE key is not a map"))?;
+
+    let int = |label: i64| {
+        int_key(map, label)
+            .and_then(Value::as_integer)
+            .map(i128::from)
+    };
+    let bytes = |label: i64| int_key(map, label).and_then(Value::as_bytes);
+
+    // kty (1), alg (3), crv (-1), x (-2), y (-3)
+    match (int(1), int(3), int(-1)) {
+        (Some(2), Some(alg), Some(1)) if alg == COSE_ES256 as i128 => {
+            let (x, y) = bytes(-2)
+                .zip(bytes(-3))
+                .filter(|(x, y)| x.len() == 32 && y.len() == 32)
+                .ok_or_else(|| reject("invalid P-256 coordinates"))?;
+            let mut sec1 = Vec::with_capacity(65);
+            sec1.push(0x04);
+            sec1.extend_from_slice(x);
+            sec1.extend_from_slice(y);
+            p256::ecdsa::VerifyingKey::from_sec1_bytes(&sec1)
+                .map(PublicKey::Es256)
+                .map_err(|_| reject("invalid P-256 public key"))
+        }
+        (Some(1), Some(alg), Some(6)) if alg == COSE_EDDSA as i128 => {
+            let x: [u8; 32] = bytes(-2)
+                .and_then(|x| x.as_slice().try_into().ok())
+                .ok_or_else(|| reject("invalid Ed25519 public key"))?;
+            ed25519_dalek::VerifyingKey::from_bytes(&x)
+                .map(PublicKey::EdDsa)
+                .map_err(|_| reject("invalid Ed25519 public key"))
+        }
+        (kty, alg, crv) => Err(reject(format!(
+            "unsupported COSE key (kty {:?}, alg {:?}, crv {:?})",
+            kty, alg, crv
+        ))),
+    }
+}
+
+impl PublicKey {
+    fn verify(&self, message: &[u8], signature: &[u8]) -> bool {
+        match self {
+            Self::Es256(key) => {
+                use p256::ecdsa::signature::Verifier;
+
+                p256::ecdsa::Signature::from_der(signature)
+                    .map(|sig| key.verify(message, &sig).is_ok())
+                    .unwrap_or(false)
+            }
+            Self::EdDsa(key) => ed25519_dalek::Signature::from_slice(signature)
+                .map(|sig| key.verify_strict(message, &sig).is_ok())
+                .unwrap_or(false),
+        }
+    }
+}
+
+fn text_key<'a>(map: &'a [(Value, Value)], key: &str) -> Option<&'a Value> {
+    map.iter()
+        .find(|(k, _)| k.as_text() == Some(key))
+        .map(|(_, v)| v)
+}
+
+fn int_key(map: &[(Value, Value)], key: i64) -> Option<&Value> {
+    map.iter()
+        .find(|(k, _)| k.as_integer().map(i128::from) == Some(key as i128))
+        .map(|(_, v)| v)
+}
+
+fn reject(reason: impl Into<String>) -> AccountError {
+    AccountError::Passkey(reason.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::accounts::passkeys::webauthn::{PasskeyAssertion, PasskeyRegistration};
+    use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+    use base64::Engine;
+
+    /// A registration and a login recorded from one authenticator
+    #[derive(Deserialize)]
+    struct Fixture {
+        rp_id: String,
+        origin: String,
+        user_handle: String,
+        registration: Recorded<PasskeyRegistration>,
+        authentication: Recorded<PasskeyAssertion>,
+    }
+
+    #[derive(Deserialize)]
+    struct Recorded<T> {
+        challenge: String,
+        response: T,
+    }
+
+    /// ES256 security key with a signature counter
+    const ES256: &str = include_str!("fixtures/es256.json");
+
+    /// EdDSA synced passkey: backup eligible, backed up, no counter
+    const EDDSA: &str = include_str!("fixtures/eddsa.json");
+
+    fn fixture(json: &str) -> (Fixture, PasskeyConfig) {
+        let fixture: Fixture = serde_json::from_str(json).unwrap();
+        let config = PasskeyConfig {
+            rp_id: fixture.rp_id.clone(),
+            origins: vec![fixture.origin.clone()],
+            ..Default::default()
+        };
+        (fixture, config)
+    }
+
+    fn b64(s: &str) -> Vec<u8> {
+        URL_SAFE_NO_PAD.decode(s).unwrap()
+    }
+
+    fn register(fixture: &Fixture, config: &PasskeyConfig) -> VerifiedRegistration {
+        let response = &fixture.registration.response.response;
+        verify_registration(
+            &b64(&response.client_data_json),
+            &b64(&response.attestation_object),
+            &fixture.registration.challenge,
+            config,
+        )
+        .unwrap()
+    }
+
+    fn login(
+        fixture: &Fixture,
+        config: &PasskeyConfig,
+        public_key: &[u8],
+    ) -> Result<VerifiedAssertion, AccountError> {
+        let response = &fixture.authentication.response.response;
+        verify_assertion(
+            &b64(&response.client_data_json),
+            &b64(&response.authenticator_data),
+            &b64(&response.signature),
+            public_key,
+            &fixture.authentication.challenge,
+            config,
+        )
+    }
+
+    #[test]
+    fn test_es256_ceremonies() {
+        let (fixture, config) = fixture(ES256);
+        let registered = register(&fixture, &config);
+
+        assert_eq!(
+            URL_SAFE_NO_PAD.encode(&registered.credential_id),
+            fixture.registration.response.id
+        );
+        assert_eq!(registered.sign_count, 1);
+        assert!(!registered.backup_eligible);
+
+        let assertion = login(&fixture, &config, &registered.public_key).unwrap();
+        assert_eq!(assertion.sign_count, 2);
+        assert!(!sign_count_regressed(
+            registered.sign_count,
+            assertion.sign_count
+        ));
+
+        let user_handle = fixture
+            .authentication
+            .response
+            .response
+            .user_handle
+            .unwrap();
+        assert_eq!(b64(&user_handle), fixture.user_handle.as_bytes());
+    }
+
+    #[test]
+    fn test_eddsa_ceremonies() {
+        let (fixture, config) = fixture(EDDSA);
+        let registered = register(&fixture, &config);
+
+        assert_eq!(registered.sign_count, 0);
+        assert!(registered.backup_eligible);
+        assert!(registered.backed_up);
+
+        let assertion = login(&fixture, &config, &registered.public_key).unwrap();
+        assert_eq!(assertion.sign_count, 0);
+        assert!(assertion.backed_up);
+        assert!(!sign_count_regressed(0, 0));
+    }
+
+    #[test]
+    fn test_registration_rejects_wrong_challenge() {
+        let (mut fixture, config) = fixture(ES256);
+        fixture.registration.challenge = fixture.authentication.challenge.clone();
+
+        let response = &fixture.registration.response.response;
+        let err = verify_registration(
+            &b64(&response.client_data_json),
+            &b64(&response.attestation_object),
+            &fixture.registration.challenge,
+            &config,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("challenge mismatch"));
+    }
+
+    #[test]
+    fn test_registration_rejects_foreign_origin_and_rp_id() {
+        let (fixture, config) = fixture(ES256);
+        let response = &fixture.registration.response.response;
+        let verify = |config: &PasskeyConfig| {
+            verify_registration(
+                &b64(&response.client_data_json),
+                &b64(&response.attestation_object),
+                &fixture.registration.challenge,
+                config,
+            )
+            .unwrap_err()
+            .to_string()
+        };
+
+        let other_origin = PasskeyConfig {
+            origins: vec!["https://evil.example".to_string()],
+            ..config.clone()
+        };
+        assert!(verify(&other_origin).contains("origin not allowed"));
+
+        let other_rp = PasskeyConfig {
+            rp_id: "example.com".to_string(),
+            ..config.clone()
+        };
+        assert!(verify(&other_rp).contains("relying party ID mismatch"));
+    }
+
+    #[test]
+    fn test_login_response_is_not_a_registration() {
+        let (fixture, config) = fixture(ES256);
+        let registered = register(&fixture, &config);
+        let response = &fixture.authentication.response.response;
+
+        // A signed login replayed at the registration endpoint
+        let err = verify_registration(
+            &b64(&response.client_data_json),
+            &registered.public_key,
+            &fixture.authentication.challenge,
+            &config,
+        )
+        .unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("expected a webauthn.create response"));
+    }
+
+    #[test]
+    fn test_login_rejects_tampered_signature_and_wrong_key() {
+        let (fixture, config) = fixture(ES256);
+        let registered = register(&fixture, &config);
+        let response = &fixture.authentication.response.response;
+
+        let mut authenticator_data = b64(&response.authenticator_data);
+        authenticator_data[36] ^= 0x01; // forge the sign count
+        let err = verify_assertion(
+            &b64(&response.client_data_json),
+            &authenticator_data,
+            &b64(&response.signature),
+            &registered.public_key,
+            &fixture.authentication.challenge,
+            &config,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("invalid signature"));
+
+        let (other, other_config) = self::fixture(EDDSA);
+        let other_key = register(&other, &other_config).public_key;
+        assert!(login(&fixture, &config, &other_key).is_err());
+    }
+
+    #[test]
+    fn test_login_rejects_wrong_challenge() {
+        let (mut fixture, config) = fixture(EDDSA);
+        let registered = register(&fixture, &config);
+        fixture.authentication.challenge = fixture.registration.challenge.clone();
+
+        let err = login(&fixture, &config, &registered.public_key).unwrap_err();
+        assert!(err.to_string().contains("challenge mismatch"));
+    }
+
+    #[test]
+    fn test_user_verification_flag() {
+        let (fixture, mut config) = fixture(ES256);
+        let response = &fixture.authentication.response.response;
+        let mut authenticator_data = b64(&response.authenticator_data);
+        authenticator_data[32] &= !FLAG_USER_VERIFIED;
+        let check = |config: &PasskeyConfig| {
+            let data = parse_authenticator_data(&authenticator_data).unwrap();
+            check_authenticator_data(&data, config)
+        };
+
+        assert!(check(&config)
+            .unwrap_err()
+            .to_string()
+            .contains("user not verified"));
+        config.require_user_verification = false;
+        assert!(check(&config).is_ok());
+    }
+
+    #[test]
+    fn test_sign_count_regression() {
+        assert!(!sign_count_regressed(0, 0));
+        assert!(!sign_count_regressed(0, 1));
+        assert!(!sign_count_regressed(41, 42));
+        assert!(sign_count_regressed(42, 42));
+        assert!(sign_count_regressed(42, 7));
+        assert!(sign_count_regressed(42, 0));
+    }
+
+    #[test]
+    fn test_truncated_authenticator_data() {
+        assert!(parse_authenticator_data(&[0u8; 36]).is_err());
+
+        let mut data = vec![0u8; AUTH_DATA_HEADER_LEN];
+        data[32] = FLAG_ATTESTED_CREDENTIAL;
+        data.extend_from_slice(&[0u8; 16]);
+        data.extend_from_slice(&[0x00, 0x10]); // 16-byte ID, but none follows
+        assert!(parse_authenticator_data(&data).is_err());
+    }
+}
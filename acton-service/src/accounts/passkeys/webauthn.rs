@@ -0,0 +1,156 @@
+//! WebAuthn JSON exchanged with the browser
+//!
+//! Options serialize to the shapes `PublicKeyCredential.parseCreationOptionsFromJSON()`
+//! and `parseRequestOptionsFromJSON()` accept, and responses deserialize from
+//! what `PublicKeyCredential.toJSON()` produces. Binary fields are base64url
+//! without padding throughout.
+
+use serde::{Deserialize, Serialize};
+
+/// Options for `navigator.credentials.create()`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyCreationOptions {
+    /// The relying party the passkey is scoped to
+    pub rp: RelyingParty,
+    /// The account the passkey is for
+    pub user: PasskeyUser,
+    /// Single-use challenge the authenticator signs
+    pub challenge: String,
+    /// Accepted key algorithms, most preferred first
+    pub pub_key_cred_params: Vec<CredentialParameters>,
+    /// Milliseconds the browser waits for the user
+    pub timeout: u64,
+    /// Passkeys the account already has, so an authenticator isn't registered twice
+    pub exclude_credentials: Vec<CredentialDescriptor>,
+    /// Authenticator requirements
+    pub authenticator_selection: AuthenticatorSelection,
+    /// Always `"none"`: attestation statements aren't verified
+    pub attestation: String,
+}
+
+/// Options for `navigator.credentials.get()`
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyRequestOptions {
+    /// Single-use challenge the authenticator signs
+    pub challenge: String,
+    /// Milliseconds the browser waits for the user
+    pub timeout: u64,
+    /// The relying party ID
+    pub rp_id: String,
+    /// Passkeys that may answer; empty lets the user pick any passkey for this site
+    pub allow_credentials: Vec<CredentialDescriptor>,
+    /// `"required"` or `"preferred"`
+    pub user_verification: String,
+}
+
+/// Relying party identity shown by the browser
+#[derive(Debug, Clone, Serialize)]
+pub struct RelyingParty {
+    /// The domain passkeys are scoped to
+    pub id: String,
+    /// Display name
+    pub name: String,
+}
+
+/// User identity stored with a new passkey
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PasskeyUser {
+    /// User handle: the account ID (base64url)
+    pub id: String,
+    /// Account email
+    pub name: String,
+    /// Username, or the email when the account has none
+    pub display_name: String,
+}
+
+/// An accepted key algorithm
+#[derive(Debug, Clone, Serialize)]
+pub struct CredentialParameters {
+    /// Always `"public-key"`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// COSE algorithm identifier
+    pub alg: i64,
+}
+
+/// A reference to a registered passkey
+#[derive(Debug, Clone, Serialize)]
+pub struct CredentialDescriptor {
+    /// Always `"public-key"`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Credential ID (base64url)
+    pub id: String,
+    /// Transport hints from registration
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub transports: Vec<String>,
+}
+
+/// Authenticator requirements for registration
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthenticatorSelection {
+    /// Always `"required"`: passkeys must be discoverable for usernameless login
+    pub resident_key: String,
+    /// Level 1 spelling of `resident_key`, for older browsers
+    pub require_resident_key: bool,
+    /// `"required"` or `"preferred"`
+    pub user_verification: String,
+}
+
+/// The browser's answer to [`PasskeyCreationOptions`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct PasskeyRegistration {
+    /// Credential ID (base64url)
+    pub id: String,
+    /// Always `"public-key"`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Authenticator output
+    pub response: AttestationResponse,
+}
+
+/// Authenticator output from registration
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AttestationResponse {
+    /// Client data the browser built (base64url JSON)
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    /// CBOR attestation object holding the new public key (base64url)
+    pub attestation_object: String,
+    /// Transports the authenticator can be reached over
+    #[serde(default)]
+    pub transports: Vec<String>,
+}
+
+/// The browser's answer to [`PasskeyRequestOptions`]
+#[derive(Debug, Clone, Deserialize)]
+pub struct PasskeyAssertion {
+    /// Credential ID (base64url)
+    pub id: String,
+    /// Always `"public-key"`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// Authenticator output
+    pub response: AssertionResponse,
+}
+
+/// Authenticator output from login
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssertionResponse {
+    /// Client data the browser built (base64url JSON)
+    #[serde(rename = "clientDataJSON")]
+    pub client_data_json: String,
+    /// Authenticator data the signature covers (base64url)
+    pub authenticator_data: String,
+    /// Signature over the authenticator data and client data hash (base64url)
+    pub signature: String,
+    /// User handle stored with the passkey (base64url)
+    #[serde(default)]
+    pub user_handle: Option<String>,
+}
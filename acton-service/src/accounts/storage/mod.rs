@@ -1,6 +1,9 @@
 //! Account storage trait and backend implementations
 //!
-//! The `AccountStorage` trait defines the interface for persisting accounts.
+//! The `AccountStorage` trait defines the interface for persisting accounts,
+//! the external identities linked to them, their MFA enrollments and
+//! passkeys, their previous password hashes, and the hashed tokens behind
+//! password reset and email verification.
 //!
 //! # Available Backends
 //!
@@ -11,7 +14,11 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 
-use super::types::{Account, AccountStatus};
+#[cfg(feature = "passkeys")]
+use super::types::PasskeyCredential;
+use super::types::{
+    Account, AccountStatus, AccountToken, AccountTokenPurpose, LinkedIdentity, TotpEnrollment,
+};
 use crate::error::Error;
 
 #[cfg(feature = "database")]
@@ -75,4 +82,116 @@ pub trait AccountStorage: Send + Sync {
         cutoff: DateTime<Utc>,
         limit: usize,
     ) -> Result<Vec<Account>, Error>;
+
+    /// Link an external identity to an account
+    ///
+    /// Fails if the provider subject is already linked.
+    async fn link_identity(&self, identity: &LinkedIdentity) -> Result<(), Error>;
+
+    /// Get the identity linked for a provider subject
+    async fn get_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<LinkedIdentity>, Error>;
+
+    /// List the identities linked to an account
+    async fn list_identities(&self, account_id: &str) -> Result<Vec<LinkedIdentity>, Error>;
+
+    /// Remove a linked identity
+    async fn unlink_identity(&self, provider: &str, subject: &str) -> Result<bool, Error>;
+
+    /// Create or replace the TOTP enrollment for an account
+    async fn save_totp(&self, enrollment: &TotpEnrollment) -> Result<(), Error>;
+
+    /// Get the TOTP enrollment for an account
+    async fn get_totp(&self, account_id: &str) -> Result<Option<TotpEnrollment>, Error>;
+
+    /// Remove an account's TOTP enrollment and recovery codes
+    async fn delete_totp(&self, account_id: &str) -> Result<bool, Error>;
+
+    /// Record an accepted TOTP time step if it is later than the last one
+    ///
+    /// Must be atomic: returns `false` without changes when `step` is not
+    /// newer than the last accepted step, which is how replayed codes are
+    /// rejected even when two requests race.
+    async fn advance_totp_step(&self, account_id: &str, step: i64) -> Result<bool, Error>;
+
+    /// Replace an account's recovery codes with new hashes
+    async fn replace_recovery_codes(
+        &self,
+        account_id: &str,
+        code_hashes: &[String],
+    ) -> Result<(), Error>;
+
+    /// Consume a recovery code by hash
+    ///
+    /// Must be atomic: returns `false` when the code doesn't exist or was
+    /// already used.
+    async fn consume_recovery_code(&self, account_id: &str, code_hash: &str)
+        -> Result<bool, Error>;
+
+    /// Store a newly registered passkey
+    ///
+    /// Fails if the credential ID is already registered.
+    #[cfg(feature = "passkeys")]
+    async fn create_passkey(&self, credential: &PasskeyCredential) -> Result<(), Error>;
+
+    /// Get a passkey by credential ID
+    #[cfg(feature = "passkeys")]
+    async fn get_passkey(&self, credential_id: &str) -> Result<Option<PasskeyCredential>, Error>;
+
+    /// List the passkeys registered to an account, oldest first
+    #[cfg(feature = "passkeys")]
+    async fn list_passkeys(&self, account_id: &str) -> Result<Vec<PasskeyCredential>, Error>;
+
+    /// Record a passkey login if its signature counter moved forward
+    ///
+    /// Must be atomic: returns `false` without changes when `sign_count` is
+    /// not greater than the stored counter, unless both are 0 (authenticators
+    /// without a counter). A cloned authenticator replaying an old counter is
+    /// caught even when two requests race.
+    #[cfg(feature = "passkeys")]
+    async fn record_passkey_use(
+        &self,
+        credential_id: &str,
+        sign_count: u32,
+        backed_up: bool,
+        used_at: DateTime<Utc>,
+    ) -> Result<bool, Error>;
+
+    /// Remove a passkey from an account
+    #[cfg(feature = "passkeys")]
+    async fn delete_passkey(&self, account_id: &str, credential_id: &str) -> Result<bool, Error>;
+
+    /// Get an account's previous password hashes, newest first
+    async fn get_password_history(
+        &self,
+        account_id: &str,
+        limit: usize,
+    ) -> Result<Vec<String>, Error>;
+
+    /// Record a replaced password hash, keeping only the newest `keep` entries
+    async fn push_password_history(
+        &self,
+        account_id: &str,
+        password_hash: &str,
+        keep: usize,
+    ) -> Result<(), Error>;
+
+    /// Store a self-service token
+    ///
+    /// Replaces any earlier token with the same purpose for the account, so
+    /// only the most recently sent link works.
+    async fn store_account_token(&self, token: &AccountToken) -> Result<(), Error>;
+
+    /// Remove and return a self-service token by hash
+    ///
+    /// Must be atomic: a token is returned to at most one caller. Expired
+    /// tokens are still returned (and removed); the caller checks expiry.
+    async fn take_account_token(
+        &self,
+        token_hash: &str,
+        purpose: AccountTokenPurpose,
+    ) -> Result<Option<AccountToken>, Error>;
 }
@@ -5,7 +5,12 @@ use chrono::{DateTime, Utc};
 use sqlx::PgPool;
 
 use super::AccountStorage;
-use crate::accounts::types::{Account, AccountId, AccountStatus};
+#[cfg(feature = "passkeys")]
+use crate::accounts::types::PasskeyCredential;
+use crate::accounts::types::{
+    Account, AccountId, AccountStatus, AccountToken, AccountTokenPurpose, LinkedIdentity,
+    TotpEnrollment,
+};
 use crate::error::Error;
 
 /// PostgreSQL-backed account storage
@@ -71,8 +76,249 @@ impl PgAccountStorage {
             .await
             .map_err(|e| Error::Internal(format!("Failed to create expires_at index: {}", e)))?;
 
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS account_identities (
+                provider VARCHAR(64) NOT NULL,
+                subject VARCHAR(255) NOT NULL,
+                account_id VARCHAR(36) NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+                email VARCHAR(255),
+                linked_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                PRIMARY KEY (provider, subject)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            Error::Internal(format!("Failed to create account_identities table: {}", e))
+        })?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_account_identities_account ON account_identities(account_id)")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to create identity account index: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS account_totp (
+                account_id VARCHAR(36) PRIMARY KEY REFERENCES accounts(id) ON DELETE CASCADE,
+                secret_encrypted TEXT NOT NULL,
+                confirmed_at TIMESTAMPTZ,
+                last_used_step BIGINT,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to create account_totp table: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS account_recovery_codes (
+                account_id VARCHAR(36) NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+                code_hash VARCHAR(64) NOT NULL,
+                PRIMARY KEY (account_id, code_hash)
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            Error::Internal(format!(
+                "Failed to create account_recovery_codes table: {}",
+                e
+            ))
+        })?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS account_tokens (
+                token_hash VARCHAR(64) PRIMARY KEY,
+                account_id VARCHAR(36) NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+                purpose VARCHAR(32) NOT NULL,
+                email VARCHAR(255) NOT NULL,
+                expires_at TIMESTAMPTZ NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to create account_tokens table: {}", e)))?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_account_tokens_account ON account_tokens(account_id, purpose)")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to create account token index: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS account_password_history (
+                id BIGSERIAL PRIMARY KEY,
+                account_id VARCHAR(36) NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+                password_hash TEXT NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| {
+            Error::Internal(format!(
+                "Failed to create account_password_history table: {}",
+                e
+            ))
+        })?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_account_password_history_account ON account_password_history(account_id, id)")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to create password history index: {}", e)))?;
+
+        #[cfg(feature = "passkeys")]
+        self.initialize_passkeys().await?;
+
         Ok(())
     }
+
+    #[cfg(feature = "passkeys")]
+    async fn initialize_passkeys(&self) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS account_passkeys (
+                credential_id TEXT PRIMARY KEY,
+                account_id VARCHAR(36) NOT NULL REFERENCES accounts(id) ON DELETE CASCADE,
+                public_key TEXT NOT NULL,
+                sign_count BIGINT NOT NULL DEFAULT 0,
+                aaguid VARCHAR(36) NOT NULL,
+                transports JSONB NOT NULL DEFAULT '[]',
+                backup_eligible BOOLEAN NOT NULL DEFAULT FALSE,
+                backed_up BOOLEAN NOT NULL DEFAULT FALSE,
+                name VARCHAR(255),
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                last_used_at TIMESTAMPTZ
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to create account_passkeys table: {}", e)))?;
+
+        sqlx::query("CREATE INDEX IF NOT EXISTS idx_account_passkeys_account ON account_passkeys(account_id)")
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to create passkey account index: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+/// Internal row type for passkeys
+#[cfg(feature = "passkeys")]
+#[derive(sqlx::FromRow)]
+struct PasskeyRow {
+    credential_id: String,
+    account_id: String,
+    public_key: String,
+    sign_count: i64,
+    aaguid: String,
+    transports: serde_json::Value,
+    backup_eligible: bool,
+    backed_up: bool,
+    name: Option<String>,
+    created_at: DateTime<Utc>,
+    last_used_at: Option<DateTime<Utc>>,
+}
+
+#[cfg(feature = "passkeys")]
+impl From<PasskeyRow> for PasskeyCredential {
+    fn from(row: PasskeyRow) -> Self {
+        PasskeyCredential {
+            credential_id: row.credential_id,
+            account_id: row.account_id.parse().unwrap_or_else(|_| AccountId::new()),
+            public_key: row.public_key,
+            sign_count: u32::try_from(row.sign_count).unwrap_or(u32::MAX),
+            aaguid: row.aaguid,
+            transports: serde_json::from_value(row.transports).unwrap_or_default(),
+            backup_eligible: row.backup_eligible,
+            backed_up: row.backed_up,
+            name: row.name,
+            created_at: row.created_at,
+            last_used_at: row.last_used_at,
+        }
+    }
+}
+
+/// Internal row type for TOTP enrollments
+#[derive(sqlx::FromRow)]
+struct TotpRow {
+    account_id: String,
+    secret_encrypted: String,
+    confirmed_at: Option<DateTime<Utc>>,
+    last_used_step: Option<i64>,
+    created_at: DateTime<Utc>,
+}
+
+impl From<TotpRow> for TotpEnrollment {
+    fn from(row: TotpRow) -> Self {
+        TotpEnrollment {
+            account_id: row.account_id.parse().unwrap_or_else(|_| AccountId::new()),
+            secret_encrypted: row.secret_encrypted,
+            confirmed_at: row.confirmed_at,
+            last_used_step: row.last_used_step,
+            created_at: row.created_at,
+        }
+    }
+}
+
+/// Internal row type for self-service tokens
+#[derive(sqlx::FromRow)]
+struct AccountTokenRow {
+    token_hash: String,
+    account_id: String,
+    purpose: String,
+    email: String,
+    expires_at: DateTime<Utc>,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<AccountTokenRow> for AccountToken {
+    type Error = Error;
+
+    fn try_from(row: AccountTokenRow) -> Result<Self, Self::Error> {
+        Ok(AccountToken {
+            token_hash: row.token_hash,
+            account_id: row.account_id.parse().unwrap_or_else(|_| AccountId::new()),
+            purpose: row.purpose.parse().map_err(Error::Internal)?,
+            email: row.email,
+            expires_at: row.expires_at,
+            created_at: row.created_at,
+        })
+    }
+}
+
+/// Internal row type for linked identities
+#[derive(sqlx::FromRow)]
+struct IdentityRow {
+    provider: String,
+    subject: String,
+    account_id: String,
+    email: Option<String>,
+    linked_at: DateTime<Utc>,
+}
+
+impl From<IdentityRow> for LinkedIdentity {
+    fn from(row: IdentityRow) -> Self {
+        LinkedIdentity {
+            provider: row.provider,
+            subject: row.subject,
+            account_id: row.account_id.parse().unwrap_or_else(|_| AccountId::new()),
+            email: row.email,
+            linked_at: row.linked_at,
+        }
+    }
 }
 
 /// Internal row type for sqlx mapping
@@ -402,4 +648,391 @@ impl AccountStorage for PgAccountStorage {
 
         Ok(rows.into_iter().map(Into::into).collect())
     }
+
+    async fn link_identity(&self, identity: &LinkedIdentity) -> Result<(), Error> {
+        sqlx::query(
+            "INSERT INTO account_identities (provider, subject, account_id, email, linked_at) VALUES ($1, $2, $3, $4, $5)",
+        )
+        .bind(&identity.provider)
+        .bind(&identity.subject)
+        .bind(identity.account_id.as_str())
+        .bind(&identity.email)
+        .bind(identity.linked_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to link identity: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<LinkedIdentity>, Error> {
+        let row = sqlx::query_as::<_, IdentityRow>(
+            "SELECT * FROM account_identities WHERE provider = $1 AND subject = $2",
+        )
+        .bind(provider)
+        .bind(subject)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to get identity: {}", e)))?;
+
+        Ok(row.map(Into::into))
+    }
+
+    async fn list_identities(&self, account_id: &str) -> Result<Vec<LinkedIdentity>, Error> {
+        let rows = sqlx::query_as::<_, IdentityRow>(
+            "SELECT * FROM account_identities WHERE account_id = $1 ORDER BY linked_at ASC",
+        )
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to list identities: {}", e)))?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn unlink_identity(&self, provider: &str, subject: &str) -> Result<bool, Error> {
+        let result =
+            sqlx::query("DELETE FROM account_identities WHERE provider = $1 AND subject = $2")
+                .bind(provider)
+                .bind(subject)
+                .execute(&self.pool)
+                .await
+                .map_err(|e| Error::Internal(format!("Failed to unlink identity: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn save_totp(&self, enrollment: &TotpEnrollment) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            INSERT INTO account_totp (account_id, secret_encrypted, confirmed_at, last_used_step, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (account_id) DO UPDATE SET
+                secret_encrypted = EXCLUDED.secret_encrypted,
+                confirmed_at = EXCLUDED.confirmed_at,
+                last_used_step = EXCLUDED.last_used_step,
+                created_at = EXCLUDED.created_at
+            "#,
+        )
+        .bind(enrollment.account_id.as_str())
+        .bind(&enrollment.secret_encrypted)
+        .bind(enrollment.confirmed_at)
+        .bind(enrollment.last_used_step)
+        .bind(enrollment.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to save TOTP enrollment: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_totp(&self, account_id: &str) -> Result<Option<TotpEnrollment>, Error> {
+        let row = sqlx::query_as::<_, TotpRow>("SELECT * FROM account_totp WHERE account_id = $1")
+            .bind(account_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to get TOTP enrollment: {}", e)))?;
+
+        Ok(row.map(Into::into))
+    }
+
+    async fn delete_totp(&self, account_id: &str) -> Result<bool, Error> {
+        sqlx::query("DELETE FROM account_recovery_codes WHERE account_id = $1")
+            .bind(account_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to delete recovery codes: {}", e)))?;
+
+        let result = sqlx::query("DELETE FROM account_totp WHERE account_id = $1")
+            .bind(account_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to delete TOTP enrollment: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn advance_totp_step(&self, account_id: &str, step: i64) -> Result<bool, Error> {
+        let result = sqlx::query(
+            "UPDATE account_totp SET last_used_step = $2 WHERE account_id = $1 AND (last_used_step IS NULL OR last_used_step < $2)",
+        )
+        .bind(account_id)
+        .bind(step)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to record TOTP step: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn replace_recovery_codes(
+        &self,
+        account_id: &str,
+        code_hashes: &[String],
+    ) -> Result<(), Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to begin transaction: {}", e)))?;
+
+        sqlx::query("DELETE FROM account_recovery_codes WHERE account_id = $1")
+            .bind(account_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to delete recovery codes: {}", e)))?;
+
+        for code_hash in code_hashes {
+            sqlx::query(
+                "INSERT INTO account_recovery_codes (account_id, code_hash) VALUES ($1, $2)",
+            )
+            .bind(account_id)
+            .bind(code_hash)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to store recovery code: {}", e)))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to commit recovery codes: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn consume_recovery_code(
+        &self,
+        account_id: &str,
+        code_hash: &str,
+    ) -> Result<bool, Error> {
+        let result = sqlx::query(
+            "DELETE FROM account_recovery_codes WHERE account_id = $1 AND code_hash = $2",
+        )
+        .bind(account_id)
+        .bind(code_hash)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to consume recovery code: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[cfg(feature = "passkeys")]
+    async fn create_passkey(&self, credential: &PasskeyCredential) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO account_passkeys (
+                credential_id, account_id, public_key, sign_count, aaguid, transports,
+                backup_eligible, backed_up, name, created_at, last_used_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+            ON CONFLICT (credential_id) DO NOTHING
+            "#,
+        )
+        .bind(&credential.credential_id)
+        .bind(credential.account_id.as_str())
+        .bind(&credential.public_key)
+        .bind(i64::from(credential.sign_count))
+        .bind(&credential.aaguid)
+        .bind(serde_json::to_value(&credential.transports).unwrap_or_default())
+        .bind(credential.backup_eligible)
+        .bind(credential.backed_up)
+        .bind(&credential.name)
+        .bind(credential.created_at)
+        .bind(credential.last_used_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to create passkey: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::Conflict(format!(
+                "Passkey already registered: {}",
+                credential.credential_id
+            )));
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "passkeys")]
+    async fn get_passkey(&self, credential_id: &str) -> Result<Option<PasskeyCredential>, Error> {
+        let row = sqlx::query_as::<_, PasskeyRow>(
+            "SELECT * FROM account_passkeys WHERE credential_id = $1",
+        )
+        .bind(credential_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to get passkey: {}", e)))?;
+
+        Ok(row.map(Into::into))
+    }
+
+    #[cfg(feature = "passkeys")]
+    async fn list_passkeys(&self, account_id: &str) -> Result<Vec<PasskeyCredential>, Error> {
+        let rows = sqlx::query_as::<_, PasskeyRow>(
+            "SELECT * FROM account_passkeys WHERE account_id = $1 ORDER BY created_at ASC",
+        )
+        .bind(account_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to list passkeys: {}", e)))?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    #[cfg(feature = "passkeys")]
+    async fn record_passkey_use(
+        &self,
+        credential_id: &str,
+        sign_count: u32,
+        backed_up: bool,
+        used_at: DateTime<Utc>,
+    ) -> Result<bool, Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE account_passkeys SET sign_count = $2, backed_up = $3, last_used_at = $4
+            WHERE credential_id = $1 AND (sign_count < $2 OR (sign_count = 0 AND $2 = 0))
+            "#,
+        )
+        .bind(credential_id)
+        .bind(i64::from(sign_count))
+        .bind(backed_up)
+        .bind(used_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to record passkey use: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    #[cfg(feature = "passkeys")]
+    async fn delete_passkey(&self, account_id: &str, credential_id: &str) -> Result<bool, Error> {
+        let result = sqlx::query(
+            "DELETE FROM account_passkeys WHERE account_id = $1 AND credential_id = $2",
+        )
+        .bind(account_id)
+        .bind(credential_id)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to delete passkey: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn get_password_history(
+        &self,
+        account_id: &str,
+        limit: usize,
+    ) -> Result<Vec<String>, Error> {
+        let hashes = sqlx::query_scalar::<_, String>(
+            "SELECT password_hash FROM account_password_history WHERE account_id = $1 ORDER BY id DESC LIMIT $2",
+        )
+        .bind(account_id)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to get password history: {}", e)))?;
+
+        Ok(hashes)
+    }
+
+    async fn push_password_history(
+        &self,
+        account_id: &str,
+        password_hash: &str,
+        keep: usize,
+    ) -> Result<(), Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to begin transaction: {}", e)))?;
+
+        sqlx::query(
+            "INSERT INTO account_password_history (account_id, password_hash, created_at) VALUES ($1, $2, $3)",
+        )
+        .bind(account_id)
+        .bind(password_hash)
+        .bind(Utc::now())
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to record password history: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM account_password_history
+            WHERE account_id = $1 AND id NOT IN (
+                SELECT id FROM account_password_history
+                WHERE account_id = $1 ORDER BY id DESC LIMIT $2
+            )
+            "#,
+        )
+        .bind(account_id)
+        .bind(keep as i64)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to trim password history: {}", e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to commit password history: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn store_account_token(&self, token: &AccountToken) -> Result<(), Error> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to begin transaction: {}", e)))?;
+
+        sqlx::query("DELETE FROM account_tokens WHERE account_id = $1 AND purpose = $2")
+            .bind(token.account_id.as_str())
+            .bind(token.purpose.to_string())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to replace account token: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO account_tokens (token_hash, account_id, purpose, email, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            "#,
+        )
+        .bind(&token.token_hash)
+        .bind(token.account_id.as_str())
+        .bind(token.purpose.to_string())
+        .bind(&token.email)
+        .bind(token.expires_at)
+        .bind(token.created_at)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to store account token: {}", e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to commit account token: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn take_account_token(
+        &self,
+        token_hash: &str,
+        purpose: AccountTokenPurpose,
+    ) -> Result<Option<AccountToken>, Error> {
+        let row = sqlx::query_as::<_, AccountTokenRow>(
+            "DELETE FROM account_tokens WHERE token_hash = $1 AND purpose = $2 RETURNING *",
+        )
+        .bind(token_hash)
+        .bind(purpose.to_string())
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to redeem account token: {}", e)))?;
+
+        row.map(TryInto::try_into).transpose()
+    }
 }
@@ -7,7 +7,12 @@ use std::sync::Arc;
 use surrealdb::types::SurrealValue;
 
 use super::AccountStorage;
-use crate::accounts::types::{Account, AccountId, AccountStatus};
+#[cfg(feature = "passkeys")]
+use crate::accounts::types::PasskeyCredential;
+use crate::accounts::types::{
+    Account, AccountId, AccountStatus, AccountToken, AccountTokenPurpose, LinkedIdentity,
+    TotpEnrollment,
+};
 use crate::error::Error;
 use crate::surrealdb_backend::SurrealClient;
 
@@ -49,11 +54,67 @@ impl SurrealDbAccountStorage {
                 DEFINE FIELD IF NOT EXISTS updated_at ON accounts TYPE string;
                 DEFINE INDEX IF NOT EXISTS idx_accounts_email ON accounts FIELDS email UNIQUE;
                 DEFINE INDEX IF NOT EXISTS idx_accounts_status ON accounts FIELDS status;
+                DEFINE TABLE IF NOT EXISTS account_identities SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS provider ON account_identities TYPE string;
+                DEFINE FIELD IF NOT EXISTS subject ON account_identities TYPE string;
+                DEFINE FIELD IF NOT EXISTS account_id ON account_identities TYPE string;
+                DEFINE FIELD IF NOT EXISTS email ON account_identities TYPE option<string>;
+                DEFINE FIELD IF NOT EXISTS linked_at ON account_identities TYPE string;
+                DEFINE INDEX IF NOT EXISTS idx_account_identities_subject ON account_identities FIELDS provider, subject UNIQUE;
+                DEFINE INDEX IF NOT EXISTS idx_account_identities_account ON account_identities FIELDS account_id;
+                DEFINE TABLE IF NOT EXISTS account_totp SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS account_id ON account_totp TYPE string;
+                DEFINE FIELD IF NOT EXISTS secret_encrypted ON account_totp TYPE string;
+                DEFINE FIELD IF NOT EXISTS confirmed_at ON account_totp TYPE option<string>;
+                DEFINE FIELD IF NOT EXISTS last_used_step ON account_totp TYPE option<int>;
+                DEFINE FIELD IF NOT EXISTS created_at ON account_totp TYPE string;
+                DEFINE INDEX IF NOT EXISTS idx_account_totp_account ON account_totp FIELDS account_id UNIQUE;
+                DEFINE TABLE IF NOT EXISTS account_recovery_codes SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS account_id ON account_recovery_codes TYPE string;
+                DEFINE FIELD IF NOT EXISTS code_hash ON account_recovery_codes TYPE string;
+                DEFINE INDEX IF NOT EXISTS idx_account_recovery_codes ON account_recovery_codes FIELDS account_id, code_hash UNIQUE;
+                DEFINE TABLE IF NOT EXISTS account_tokens SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS token_hash ON account_tokens TYPE string;
+                DEFINE FIELD IF NOT EXISTS account_id ON account_tokens TYPE string;
+                DEFINE FIELD IF NOT EXISTS purpose ON account_tokens TYPE string;
+                DEFINE FIELD IF NOT EXISTS email ON account_tokens TYPE string;
+                DEFINE FIELD IF NOT EXISTS expires_at ON account_tokens TYPE string;
+                DEFINE FIELD IF NOT EXISTS created_at ON account_tokens TYPE string;
+                DEFINE INDEX IF NOT EXISTS idx_account_tokens_hash ON account_tokens FIELDS token_hash UNIQUE;
+                DEFINE INDEX IF NOT EXISTS idx_account_tokens_account ON account_tokens FIELDS account_id, purpose;
+                DEFINE TABLE IF NOT EXISTS account_password_history SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS account_id ON account_password_history TYPE string;
+                DEFINE FIELD IF NOT EXISTS password_hash ON account_password_history TYPE string;
+                DEFINE FIELD IF NOT EXISTS created_at ON account_password_history TYPE int;
+                DEFINE INDEX IF NOT EXISTS idx_account_password_history_account ON account_password_history FIELDS account_id, created_at;
                 "#,
             )
             .await
             .map_err(|e| Error::Internal(format!("Failed to initialize accounts schema: {}", e)))?;
 
+        #[cfg(feature = "passkeys")]
+        self.client
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS account_passkeys SCHEMAFULL;
+                DEFINE FIELD IF NOT EXISTS credential_id ON account_passkeys TYPE string;
+                DEFINE FIELD IF NOT EXISTS account_id ON account_passkeys TYPE string;
+                DEFINE FIELD IF NOT EXISTS public_key ON account_passkeys TYPE string;
+                DEFINE FIELD IF NOT EXISTS sign_count ON account_passkeys TYPE int;
+                DEFINE FIELD IF NOT EXISTS aaguid ON account_passkeys TYPE string;
+                DEFINE FIELD IF NOT EXISTS transports ON account_passkeys TYPE string;
+                DEFINE FIELD IF NOT EXISTS backup_eligible ON account_passkeys TYPE bool;
+                DEFINE FIELD IF NOT EXISTS backed_up ON account_passkeys TYPE bool;
+                DEFINE FIELD IF NOT EXISTS name ON account_passkeys TYPE option<string>;
+                DEFINE FIELD IF NOT EXISTS created_at ON account_passkeys TYPE string;
+                DEFINE FIELD IF NOT EXISTS last_used_at ON account_passkeys TYPE option<string>;
+                DEFINE INDEX IF NOT EXISTS idx_account_passkeys_credential ON account_passkeys FIELDS credential_id UNIQUE;
+                DEFINE INDEX IF NOT EXISTS idx_account_passkeys_account ON account_passkeys FIELDS account_id;
+                "#,
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to initialize passkeys schema: {}", e)))?;
+
         Ok(())
     }
 }
@@ -103,6 +164,136 @@ struct AccountRow {
     updated_at: String,
 }
 
+#[derive(Serialize, Deserialize, SurrealValue)]
+struct IdentityRecord {
+    provider: String,
+    subject: String,
+    account_id: String,
+    email: Option<String>,
+    linked_at: String,
+}
+
+impl From<IdentityRecord> for LinkedIdentity {
+    fn from(record: IdentityRecord) -> Self {
+        LinkedIdentity {
+            provider: record.provider,
+            subject: record.subject,
+            account_id: record
+                .account_id
+                .parse()
+                .unwrap_or_else(|_| AccountId::new()),
+            email: record.email,
+            linked_at: parse_dt(&record.linked_at).unwrap_or_else(Utc::now),
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, SurrealValue)]
+struct TotpRecord {
+    account_id: String,
+    secret_encrypted: String,
+    confirmed_at: Option<String>,
+    last_used_step: Option<i64>,
+    created_at: String,
+}
+
+impl From<TotpRecord> for TotpEnrollment {
+    fn from(record: TotpRecord) -> Self {
+        TotpEnrollment {
+            account_id: record
+                .account_id
+                .parse()
+                .unwrap_or_else(|_| AccountId::new()),
+            secret_encrypted: record.secret_encrypted,
+            confirmed_at: record.confirmed_at.and_then(|s| parse_dt(&s)),
+            last_used_step: record.last_used_step,
+            created_at: parse_dt(&record.created_at).unwrap_or_else(Utc::now),
+        }
+    }
+}
+
+#[derive(Serialize, SurrealValue)]
+struct RecoveryCodeRecord {
+    account_id: String,
+    code_hash: String,
+}
+
+#[derive(Serialize, Deserialize, SurrealValue)]
+struct AccountTokenRecord {
+    token_hash: String,
+    account_id: String,
+    purpose: String,
+    email: String,
+    expires_at: String,
+    created_at: String,
+}
+
+/// Previous password hash; `created_at` is in microseconds so entries
+/// written in the same second still order correctly
+#[derive(Serialize, Deserialize, SurrealValue)]
+struct PasswordHistoryRecord {
+    account_id: String,
+    password_hash: String,
+    created_at: i64,
+}
+
+impl TryFrom<AccountTokenRecord> for AccountToken {
+    type Error = Error;
+
+    fn try_from(record: AccountTokenRecord) -> Result<Self, Self::Error> {
+        Ok(AccountToken {
+            token_hash: record.token_hash,
+            account_id: record
+                .account_id
+                .parse()
+                .unwrap_or_else(|_| AccountId::new()),
+            purpose: record.purpose.parse().map_err(Error::Internal)?,
+            email: record.email,
+            // An unreadable expiry is treated as already expired
+            expires_at: parse_dt(&record.expires_at).unwrap_or(DateTime::<Utc>::MIN_UTC),
+            created_at: parse_dt(&record.created_at).unwrap_or_else(Utc::now),
+        })
+    }
+}
+
+#[cfg(feature = "passkeys")]
+#[derive(Serialize, Deserialize, SurrealValue)]
+struct PasskeyRecord {
+    credential_id: String,
+    account_id: String,
+    public_key: String,
+    sign_count: i64,
+    aaguid: String,
+    transports: String,
+    backup_eligible: bool,
+    backed_up: bool,
+    name: Option<String>,
+    created_at: String,
+    last_used_at: Option<String>,
+}
+
+#[cfg(feature = "passkeys")]
+impl From<PasskeyRecord> for PasskeyCredential {
+    fn from(record: PasskeyRecord) -> Self {
+        PasskeyCredential {
+            credential_id: record.credential_id,
+            account_id: record
+                .account_id
+                .parse()
+                .unwrap_or_else(|_| AccountId::new()),
+            public_key: record.public_key,
+            sign_count: u32::try_from(record.sign_count).unwrap_or(u32::MAX),
+            aaguid: record.aaguid,
+            transports: serde_json::from_str(&record.transports).unwrap_or_default(),
+            backup_eligible: record.backup_eligible,
+            backed_up: record.backed_up,
+            name: record.name,
+            created_at: parse_dt(&record.created_at).unwrap_or_else(Utc::now),
+            last_used_at: record.last_used_at.and_then(|s| parse_dt(&s)),
+        }
+    }
+}
+
 fn parse_dt(s: &str) -> Option<DateTime<Utc>> {
     DateTime::parse_from_rfc3339(s)
         .ok()
@@ -380,7 +571,15 @@ impl AccountStorage for SurrealDbAccountStorage {
         }
 
         self.client
-            .query("DELETE type::thing('accounts', $id)")
+            .query(
+                "DELETE type::thing('accounts', $id); \
+                 DELETE account_identities WHERE account_id = $id; \
+                 DELETE account_totp WHERE account_id = $id; \
+                 DELETE account_recovery_codes WHERE account_id = $id; \
+                 DELETE account_tokens WHERE account_id = $id; \
+                 DELETE account_password_history WHERE account_id = $id; \
+                 DELETE account_passkeys WHERE account_id = $id",
+            )
             .bind(("id", id.to_string()))
             .await
             .map_err(|e| Error::Internal(format!("Failed to delete account: {}", e)))?;
@@ -440,4 +639,400 @@ impl AccountStorage for SurrealDbAccountStorage {
 
         Ok(rows.into_iter().map(Into::into).collect())
     }
+
+    async fn link_identity(&self, identity: &LinkedIdentity) -> Result<(), Error> {
+        let record = IdentityRecord {
+            provider: identity.provider.clone(),
+            subject: identity.subject.clone(),
+            account_id: identity.account_id.to_string(),
+            email: identity.email.clone(),
+            linked_at: identity.linked_at.to_rfc3339(),
+        };
+
+        self.client
+            .query("CREATE account_identities CONTENT $data")
+            .bind(("data", record))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to link identity: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<LinkedIdentity>, Error> {
+        let mut result = self
+            .client
+            .query("SELECT provider, subject, account_id, email, linked_at FROM account_identities WHERE provider = $provider AND subject = $subject LIMIT 1")
+            .bind(("provider", provider.to_string()))
+            .bind(("subject", subject.to_string()))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to get identity: {}", e)))?;
+
+        let rows: Vec<IdentityRecord> = result
+            .take(0)
+            .map_err(|e| Error::Internal(format!("Failed to deserialize identity: {}", e)))?;
+
+        Ok(rows.into_iter().next().map(Into::into))
+    }
+
+    async fn list_identities(&self, account_id: &str) -> Result<Vec<LinkedIdentity>, Error> {
+        let mut result = self
+            .client
+            .query("SELECT provider, subject, account_id, email, linked_at FROM account_identities WHERE account_id = $account_id ORDER BY linked_at ASC")
+            .bind(("account_id", account_id.to_string()))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to list identities: {}", e)))?;
+
+        let rows: Vec<IdentityRecord> = result
+            .take(0)
+            .map_err(|e| Error::Internal(format!("Failed to deserialize: {}", e)))?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    async fn unlink_identity(&self, provider: &str, subject: &str) -> Result<bool, Error> {
+        let mut result = self
+            .client
+            .query("DELETE account_identities WHERE provider = $provider AND subject = $subject RETURN BEFORE")
+            .bind(("provider", provider.to_string()))
+            .bind(("subject", subject.to_string()))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to unlink identity: {}", e)))?;
+
+        let deleted: Vec<serde_json::Value> = result.take(0).unwrap_or_default();
+        Ok(!deleted.is_empty())
+    }
+
+    async fn save_totp(&self, enrollment: &TotpEnrollment) -> Result<(), Error> {
+        let record = TotpRecord {
+            account_id: enrollment.account_id.to_string(),
+            secret_encrypted: enrollment.secret_encrypted.clone(),
+            confirmed_at: opt_dt(&enrollment.confirmed_at),
+            last_used_step: enrollment.last_used_step,
+            created_at: enrollment.created_at.to_rfc3339(),
+        };
+
+        self.client
+            .query(
+                "BEGIN TRANSACTION; \
+                 DELETE account_totp WHERE account_id = $account_id; \
+                 CREATE account_totp CONTENT $data; \
+                 COMMIT TRANSACTION;",
+            )
+            .bind(("account_id", record.account_id.clone()))
+            .bind(("data", record))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to save TOTP enrollment: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_totp(&self, account_id: &str) -> Result<Option<TotpEnrollment>, Error> {
+        let mut result = self
+            .client
+            .query("SELECT account_id, secret_encrypted, confirmed_at, last_used_step, created_at FROM account_totp WHERE account_id = $account_id LIMIT 1")
+            .bind(("account_id", account_id.to_string()))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to get TOTP enrollment: {}", e)))?;
+
+        let rows: Vec<TotpRecord> = result.take(0).map_err(|e| {
+            Error::Internal(format!("Failed to deserialize TOTP enrollment: {}", e))
+        })?;
+
+        Ok(rows.into_iter().next().map(Into::into))
+    }
+
+    async fn delete_totp(&self, account_id: &str) -> Result<bool, Error> {
+        let mut result = self
+            .client
+            .query(
+                "DELETE account_totp WHERE account_id = $account_id RETURN BEFORE; \
+                 DELETE account_recovery_codes WHERE account_id = $account_id",
+            )
+            .bind(("account_id", account_id.to_string()))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to delete TOTP enrollment: {}", e)))?;
+
+        let deleted: Vec<serde_json::Value> = result.take(0).unwrap_or_default();
+        Ok(!deleted.is_empty())
+    }
+
+    async fn advance_totp_step(&self, account_id: &str, step: i64) -> Result<bool, Error> {
+        let mut result = self
+            .client
+            .query(
+                "UPDATE account_totp SET last_used_step = $step \
+                 WHERE account_id = $account_id AND (last_used_step = NONE OR last_used_step < $step) \
+                 RETURN AFTER",
+            )
+            .bind(("account_id", account_id.to_string()))
+            .bind(("step", step))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to record TOTP step: {}", e)))?;
+
+        let updated: Vec<serde_json::Value> = result.take(0).unwrap_or_default();
+        Ok(!updated.is_empty())
+    }
+
+    async fn replace_recovery_codes(
+        &self,
+        account_id: &str,
+        code_hashes: &[String],
+    ) -> Result<(), Error> {
+        let records: Vec<RecoveryCodeRecord> = code_hashes
+            .iter()
+            .map(|code_hash| RecoveryCodeRecord {
+                account_id: account_id.to_string(),
+                code_hash: code_hash.clone(),
+            })
+            .collect();
+
+        self.client
+            .query(
+                "BEGIN TRANSACTION; \
+                 DELETE account_recovery_codes WHERE account_id = $account_id; \
+                 INSERT INTO account_recovery_codes $codes; \
+                 COMMIT TRANSACTION;",
+            )
+            .bind(("account_id", account_id.to_string()))
+            .bind(("codes", records))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to replace recovery codes: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn consume_recovery_code(
+        &self,
+        account_id: &str,
+        code_hash: &str,
+    ) -> Result<bool, Error> {
+        let mut result = self
+            .client
+            .query("DELETE account_recovery_codes WHERE account_id = $account_id AND code_hash = $code_hash RETURN BEFORE")
+            .bind(("account_id", account_id.to_string()))
+            .bind(("code_hash", code_hash.to_string()))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to consume recovery code: {}", e)))?;
+
+        let deleted: Vec<serde_json::Value> = result.take(0).unwrap_or_default();
+        Ok(!deleted.is_empty())
+    }
+
+    #[cfg(feature = "passkeys")]
+    async fn create_passkey(&self, credential: &PasskeyCredential) -> Result<(), Error> {
+        let record = PasskeyRecord {
+            credential_id: credential.credential_id.clone(),
+            account_id: credential.account_id.to_string(),
+            public_key: credential.public_key.clone(),
+            sign_count: i64::from(credential.sign_count),
+            aaguid: credential.aaguid.clone(),
+            transports: serde_json::to_string(&credential.transports)
+                .map_err(|e| Error::Internal(format!("Failed to serialize transports: {}", e)))?,
+            backup_eligible: credential.backup_eligible,
+            backed_up: credential.backed_up,
+            name: credential.name.clone(),
+            created_at: credential.created_at.to_rfc3339(),
+            last_used_at: opt_dt(&credential.last_used_at),
+        };
+
+        let mut result = self
+            .client
+            .query("CREATE account_passkeys CONTENT $data")
+            .bind(("data", record))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to create passkey: {}", e)))?;
+
+        // The unique index on credential_id is the only constraint a
+        // well-formed record can violate
+        result.take::<Vec<serde_json::Value>>(0).map_err(|e| {
+            Error::Conflict(format!(
+                "Passkey already registered: {} ({})",
+                credential.credential_id, e
+            ))
+        })?;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "passkeys")]
+    async fn get_passkey(&self, credential_id: &str) -> Result<Option<PasskeyCredential>, Error> {
+        let mut result = self
+            .client
+            .query("SELECT credential_id, account_id, public_key, sign_count, aaguid, transports, backup_eligible, backed_up, name, created_at, last_used_at FROM account_passkeys WHERE credential_id = $credential_id LIMIT 1")
+            .bind(("credential_id", credential_id.to_string()))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to get passkey: {}", e)))?;
+
+        let rows: Vec<PasskeyRecord> = result
+            .take(0)
+            .map_err(|e| Error::Internal(format!("Failed to deserialize passkey: {}", e)))?;
+
+        Ok(rows.into_iter().next().map(Into::into))
+    }
+
+    #[cfg(feature = "passkeys")]
+    async fn list_passkeys(&self, account_id: &str) -> Result<Vec<PasskeyCredential>, Error> {
+        let mut result = self
+            .client
+            .query("SELECT credential_id, account_id, public_key, sign_count, aaguid, transports, backup_eligible, backed_up, name, created_at, last_used_at FROM account_passkeys WHERE account_id = $account_id ORDER BY created_at ASC")
+            .bind(("account_id", account_id.to_string()))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to list passkeys: {}", e)))?;
+
+        let rows: Vec<PasskeyRecord> = result
+            .take(0)
+            .map_err(|e| Error::Internal(format!("Failed to deserialize: {}", e)))?;
+
+        Ok(rows.into_iter().map(Into::into).collect())
+    }
+
+    #[cfg(feature = "passkeys")]
+    async fn record_passkey_use(
+        &self,
+        credential_id: &str,
+        sign_count: u32,
+        backed_up: bool,
+        used_at: DateTime<Utc>,
+    ) -> Result<bool, Error> {
+        let mut result = self
+            .client
+            .query(
+                "UPDATE account_passkeys SET sign_count = $sign_count, backed_up = $backed_up, last_used_at = $used_at \
+                 WHERE credential_id = $credential_id AND (sign_count < $sign_count OR (sign_count = 0 AND $sign_count = 0)) \
+                 RETURN AFTER",
+            )
+            .bind(("credential_id", credential_id.to_string()))
+            .bind(("sign_count", i64::from(sign_count)))
+            .bind(("backed_up", backed_up))
+            .bind(("used_at", used_at.to_rfc3339()))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to record passkey use: {}", e)))?;
+
+        let updated: Vec<serde_json::Value> = result.take(0).unwrap_or_default();
+        Ok(!updated.is_empty())
+    }
+
+    #[cfg(feature = "passkeys")]
+    async fn delete_passkey(&self, account_id: &str, credential_id: &str) -> Result<bool, Error> {
+        let mut result = self
+            .client
+            .query("DELETE account_passkeys WHERE account_id = $account_id AND credential_id = $credential_id RETURN BEFORE")
+            .bind(("account_id", account_id.to_string()))
+            .bind(("credential_id", credential_id.to_string()))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to delete passkey: {}", e)))?;
+
+        let deleted: Vec<serde_json::Value> = result.take(0).unwrap_or_default();
+        Ok(!deleted.is_empty())
+    }
+
+    async fn get_password_history(
+        &self,
+        account_id: &str,
+        limit: usize,
+    ) -> Result<Vec<String>, Error> {
+        let mut result = self
+            .client
+            .query("SELECT account_id, password_hash, created_at FROM account_password_history WHERE account_id = $account_id ORDER BY created_at DESC LIMIT $limit")
+            .bind(("account_id", account_id.to_string()))
+            .bind(("limit", limit as i64))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to get password history: {}", e)))?;
+
+        let rows: Vec<PasswordHistoryRecord> = result
+            .take(0)
+            .map_err(|e| Error::Internal(format!("Failed to parse password history: {}", e)))?;
+
+        Ok(rows.into_iter().map(|row| row.password_hash).collect())
+    }
+
+    async fn push_password_history(
+        &self,
+        account_id: &str,
+        password_hash: &str,
+        keep: usize,
+    ) -> Result<(), Error> {
+        let record = PasswordHistoryRecord {
+            account_id: account_id.to_string(),
+            password_hash: password_hash.to_string(),
+            created_at: Utc::now().timestamp_micros(),
+        };
+
+        self.client
+            .query(
+                "BEGIN TRANSACTION; \
+                 CREATE account_password_history CONTENT $data; \
+                 LET $stale = (SELECT id, created_at FROM account_password_history \
+                     WHERE account_id = $account_id ORDER BY created_at DESC START $keep); \
+                 DELETE $stale.id; \
+                 COMMIT TRANSACTION;",
+            )
+            .bind(("account_id", account_id.to_string()))
+            .bind(("keep", keep as i64))
+            .bind(("data", record))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to record password history: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn store_account_token(&self, token: &AccountToken) -> Result<(), Error> {
+        let record = AccountTokenRecord {
+            token_hash: token.token_hash.clone(),
+            account_id: token.account_id.to_string(),
+            purpose: token.purpose.to_string(),
+            email: token.email.clone(),
+            expires_at: token.expires_at.to_rfc3339(),
+            created_at: token.created_at.to_rfc3339(),
+        };
+
+        self.client
+            .query(
+                "BEGIN TRANSACTION; \
+                 DELETE account_tokens WHERE account_id = $account_id AND purpose = $purpose; \
+                 CREATE account_tokens CONTENT $data; \
+                 COMMIT TRANSACTION;",
+            )
+            .bind(("account_id", record.account_id.clone()))
+            .bind(("purpose", record.purpose.clone()))
+            .bind(("data", record))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to store account token: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn take_account_token(
+        &self,
+        token_hash: &str,
+        purpose: AccountTokenPurpose,
+    ) -> Result<Option<AccountToken>, Error> {
+        let mut result = self
+            .client
+            .query("DELETE account_tokens WHERE token_hash = $token_hash AND purpose = $purpose RETURN BEFORE")
+            .bind(("token_hash", token_hash.to_string()))
+            .bind(("purpose", purpose.to_string()))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to redeem account token: {}", e)))?;
+
+        let deleted: Vec<serde_json::Value> = result
+            .take(0)
+            .map_err(|e| Error::Internal(format!("Failed to deserialize account token: {}", e)))?;
+
+        deleted
+            .into_iter()
+            .next()
+            .map(|value| {
+                serde_json::from_value::<AccountTokenRecord>(value)
+                    .map_err(|e| {
+                        Error::Internal(format!("Failed to deserialize account token: {}", e))
+                    })
+                    .and_then(TryInto::try_into)
+            })
+            .transpose()
+    }
 }
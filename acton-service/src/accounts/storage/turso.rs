@@ -5,7 +5,12 @@ use chrono::{DateTime, Utc};
 use std::sync::Arc;
 
 use super::AccountStorage;
-use crate::accounts::types::{Account, AccountId, AccountStatus};
+#[cfg(feature = "passkeys")]
+use crate::accounts::types::PasskeyCredential;
+use crate::accounts::types::{
+    Account, AccountId, AccountStatus, AccountToken, AccountTokenPurpose, LinkedIdentity,
+    TotpEnrollment,
+};
 use crate::error::Error;
 
 /// Turso-backed account storage
@@ -70,6 +75,146 @@ impl TursoAccountStorage {
         .await
         .map_err(|e| Error::Internal(format!("Failed to create status index: {}", e)))?;
 
+        conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS account_identities (
+                provider TEXT NOT NULL,
+                subject TEXT NOT NULL,
+                account_id TEXT NOT NULL,
+                email TEXT,
+                linked_at TEXT NOT NULL,
+                PRIMARY KEY (provider, subject)
+            )
+            "#,
+            (),
+        )
+        .await
+        .map_err(|e| {
+            Error::Internal(format!("Failed to create account_identities table: {}", e))
+        })?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_account_identities_account ON account_identities(account_id)",
+            (),
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to create identity account index: {}", e)))?;
+
+        conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS account_totp (
+                account_id TEXT PRIMARY KEY,
+                secret_encrypted TEXT NOT NULL,
+                confirmed_at TEXT,
+                last_used_step INTEGER,
+                created_at TEXT NOT NULL
+            )
+            "#,
+            (),
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to create account_totp table: {}", e)))?;
+
+        conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS account_recovery_codes (
+                account_id TEXT NOT NULL,
+                code_hash TEXT NOT NULL,
+                PRIMARY KEY (account_id, code_hash)
+            )
+            "#,
+            (),
+        )
+        .await
+        .map_err(|e| {
+            Error::Internal(format!(
+                "Failed to create account_recovery_codes table: {}",
+                e
+            ))
+        })?;
+
+        conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS account_tokens (
+                token_hash TEXT PRIMARY KEY,
+                account_id TEXT NOT NULL,
+                purpose TEXT NOT NULL,
+                email TEXT NOT NULL,
+                expires_at TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            )
+            "#,
+            (),
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to create account_tokens table: {}", e)))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_account_tokens_account ON account_tokens(account_id, purpose)",
+            (),
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to create account token index: {}", e)))?;
+
+        conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS account_password_history (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                account_id TEXT NOT NULL,
+                password_hash TEXT NOT NULL,
+                created_at TEXT NOT NULL
+            )
+            "#,
+            (),
+        )
+        .await
+        .map_err(|e| {
+            Error::Internal(format!(
+                "Failed to create account_password_history table: {}",
+                e
+            ))
+        })?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_account_password_history_account ON account_password_history(account_id, id)",
+            (),
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to create password history index: {}", e)))?;
+
+        #[cfg(feature = "passkeys")]
+        {
+            conn.execute(
+                r#"
+                CREATE TABLE IF NOT EXISTS account_passkeys (
+                    credential_id TEXT PRIMARY KEY,
+                    account_id TEXT NOT NULL,
+                    public_key TEXT NOT NULL,
+                    sign_count INTEGER NOT NULL DEFAULT 0,
+                    aaguid TEXT NOT NULL,
+                    transports TEXT NOT NULL DEFAULT '[]',
+                    backup_eligible INTEGER NOT NULL DEFAULT 0,
+                    backed_up INTEGER NOT NULL DEFAULT 0,
+                    name TEXT,
+                    created_at TEXT NOT NULL,
+                    last_used_at TEXT
+                )
+                "#,
+                (),
+            )
+            .await
+            .map_err(|e| {
+                Error::Internal(format!("Failed to create account_passkeys table: {}", e))
+            })?;
+
+            conn.execute(
+                "CREATE INDEX IF NOT EXISTS idx_account_passkeys_account ON account_passkeys(account_id)",
+                (),
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to create passkey account index: {}", e)))?;
+        }
+
         Ok(())
     }
 
@@ -143,6 +288,105 @@ fn row_to_account(row: &libsql::Row) -> Result<Account, Error> {
     })
 }
 
+fn row_to_identity(row: &libsql::Row) -> Result<LinkedIdentity, Error> {
+    let map_err = |field: &str, e: libsql::Error| {
+        Error::Internal(format!("Failed to read field '{}': {}", field, e))
+    };
+
+    let provider: String = row.get(0).map_err(|e| map_err("provider", e))?;
+    let subject: String = row.get(1).map_err(|e| map_err("subject", e))?;
+    let account_id: String = row.get(2).map_err(|e| map_err("account_id", e))?;
+    let email: Option<String> = row.get(3).map_err(|e| map_err("email", e))?;
+    let linked_at: String = row.get(4).map_err(|e| map_err("linked_at", e))?;
+
+    Ok(LinkedIdentity {
+        provider,
+        subject,
+        account_id: account_id.parse().unwrap_or_else(|_| AccountId::new()),
+        email,
+        linked_at: parse_datetime(&linked_at).unwrap_or_else(Utc::now),
+    })
+}
+
+fn row_to_totp(row: &libsql::Row) -> Result<TotpEnrollment, Error> {
+    let map_err = |field: &str, e: libsql::Error| {
+        Error::Internal(format!("Failed to read field '{}': {}", field, e))
+    };
+
+    let account_id: String = row.get(0).map_err(|e| map_err("account_id", e))?;
+    let secret_encrypted: String = row.get(1).map_err(|e| map_err("secret_encrypted", e))?;
+    let confirmed_at: Option<String> = row.get(2).map_err(|e| map_err("confirmed_at", e))?;
+    let last_used_step: Option<i64> = row.get(3).map_err(|e| map_err("last_used_step", e))?;
+    let created_at: String = row.get(4).map_err(|e| map_err("created_at", e))?;
+
+    Ok(TotpEnrollment {
+        account_id: account_id.parse().unwrap_or_else(|_| AccountId::new()),
+        secret_encrypted,
+        confirmed_at: confirmed_at.and_then(|s| parse_datetime(&s)),
+        last_used_step,
+        created_at: parse_datetime(&created_at).unwrap_or_else(Utc::now),
+    })
+}
+
+fn row_to_account_token(row: &libsql::Row) -> Result<AccountToken, Error> {
+    let map_err = |field: &str, e: libsql::Error| {
+        Error::Internal(format!("Failed to read field '{}': {}", field, e))
+    };
+
+    let token_hash: String = row.get(0).map_err(|e| map_err("token_hash", e))?;
+    let account_id: String = row.get(1).map_err(|e| map_err("account_id", e))?;
+    let purpose: String = row.get(2).map_err(|e| map_err("purpose", e))?;
+    let email: String = row.get(3).map_err(|e| map_err("email", e))?;
+    let expires_at: String = row.get(4).map_err(|e| map_err("expires_at", e))?;
+    let created_at: String = row.get(5).map_err(|e| map_err("created_at", e))?;
+
+    Ok(AccountToken {
+        token_hash,
+        account_id: account_id.parse().unwrap_or_else(|_| AccountId::new()),
+        purpose: purpose.parse().map_err(Error::Internal)?,
+        email,
+        // An unreadable expiry is treated as already expired
+        expires_at: parse_datetime(&expires_at).unwrap_or(DateTime::<Utc>::MIN_UTC),
+        created_at: parse_datetime(&created_at).unwrap_or_else(Utc::now),
+    })
+}
+
+#[cfg(feature = "passkeys")]
+const PASSKEY_COLUMNS: &str = "credential_id, account_id, public_key, sign_count, aaguid, transports, backup_eligible, backed_up, name, created_at, last_used_at";
+
+#[cfg(feature = "passkeys")]
+fn row_to_passkey(row: &libsql::Row) -> Result<PasskeyCredential, Error> {
+    let map_err = |field: &str, e: libsql::Error| {
+        Error::Internal(format!("Failed to read field '{}': {}", field, e))
+    };
+
+    let credential_id: String = row.get(0).map_err(|e| map_err("credential_id", e))?;
+    let account_id: String = row.get(1).map_err(|e| map_err("account_id", e))?;
+    let public_key: String = row.get(2).map_err(|e| map_err("public_key", e))?;
+    let sign_count: i64 = row.get(3).map_err(|e| map_err("sign_count", e))?;
+    let aaguid: String = row.get(4).map_err(|e| map_err("aaguid", e))?;
+    let transports: String = row.get(5).map_err(|e| map_err("transports", e))?;
+    let backup_eligible: i64 = row.get(6).map_err(|e| map_err("backup_eligible", e))?;
+    let backed_up: i64 = row.get(7).map_err(|e| map_err("backed_up", e))?;
+    let name: Option<String> = row.get(8).map_err(|e| map_err("name", e))?;
+    let created_at: String = row.get(9).map_err(|e| map_err("created_at", e))?;
+    let last_used_at: Option<String> = row.get(10).map_err(|e| map_err("last_used_at", e))?;
+
+    Ok(PasskeyCredential {
+        credential_id,
+        account_id: account_id.parse().unwrap_or_else(|_| AccountId::new()),
+        public_key,
+        sign_count: u32::try_from(sign_count).unwrap_or(u32::MAX),
+        aaguid,
+        transports: serde_json::from_str(&transports).unwrap_or_default(),
+        backup_eligible: backup_eligible != 0,
+        backed_up: backed_up != 0,
+        name,
+        created_at: parse_datetime(&created_at).unwrap_or_else(Utc::now),
+        last_used_at: last_used_at.and_then(|s| parse_datetime(&s)),
+    })
+}
+
 fn opt_dt(dt: &Option<DateTime<Utc>>) -> Option<String> {
     dt.map(|d| d.to_rfc3339())
 }
@@ -397,6 +641,50 @@ impl AccountStorage for TursoAccountStorage {
             .await
             .map_err(|e| Error::Internal(format!("Failed to delete account: {}", e)))?;
 
+        // SQLite leaves foreign keys unenforced unless enabled per connection
+        conn.execute(
+            "DELETE FROM account_identities WHERE account_id = ?1",
+            libsql::params![id],
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to delete account identities: {}", e)))?;
+
+        conn.execute(
+            "DELETE FROM account_totp WHERE account_id = ?1",
+            libsql::params![id],
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to delete TOTP enrollment: {}", e)))?;
+
+        conn.execute(
+            "DELETE FROM account_recovery_codes WHERE account_id = ?1",
+            libsql::params![id],
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to delete recovery codes: {}", e)))?;
+
+        conn.execute(
+            "DELETE FROM account_tokens WHERE account_id = ?1",
+            libsql::params![id],
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to delete account tokens: {}", e)))?;
+
+        conn.execute(
+            "DELETE FROM account_password_history WHERE account_id = ?1",
+            libsql::params![id],
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to delete password history: {}", e)))?;
+
+        #[cfg(feature = "passkeys")]
+        conn.execute(
+            "DELETE FROM account_passkeys WHERE account_id = ?1",
+            libsql::params![id],
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to delete passkeys: {}", e)))?;
+
         Ok(affected > 0)
     }
 
@@ -457,4 +745,450 @@ impl AccountStorage for TursoAccountStorage {
 
         Ok(accounts)
     }
+
+    async fn link_identity(&self, identity: &LinkedIdentity) -> Result<(), Error> {
+        let conn = self.conn()?;
+
+        conn.execute(
+            "INSERT INTO account_identities (provider, subject, account_id, email, linked_at) VALUES (?1, ?2, ?3, ?4, ?5)",
+            libsql::params![
+                identity.provider.clone(),
+                identity.subject.clone(),
+                identity.account_id.to_string(),
+                identity.email.clone(),
+                identity.linked_at.to_rfc3339(),
+            ],
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to link identity: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_identity(
+        &self,
+        provider: &str,
+        subject: &str,
+    ) -> Result<Option<LinkedIdentity>, Error> {
+        let conn = self.conn()?;
+        let mut rows = conn
+            .query(
+                "SELECT provider, subject, account_id, email, linked_at FROM account_identities WHERE provider = ?1 AND subject = ?2",
+                libsql::params![provider, subject],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to get identity: {}", e)))?;
+
+        match rows.next().await {
+            Ok(Some(row)) => Ok(Some(row_to_identity(&row)?)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(Error::Internal(format!("Failed to read row: {}", e))),
+        }
+    }
+
+    async fn list_identities(&self, account_id: &str) -> Result<Vec<LinkedIdentity>, Error> {
+        let conn = self.conn()?;
+        let mut identities = Vec::new();
+
+        let mut rows = conn
+            .query(
+                "SELECT provider, subject, account_id, email, linked_at FROM account_identities WHERE account_id = ?1 ORDER BY linked_at ASC",
+                libsql::params![account_id],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to list identities: {}", e)))?;
+
+        while let Ok(Some(row)) = rows.next().await {
+            identities.push(row_to_identity(&row)?);
+        }
+
+        Ok(identities)
+    }
+
+    async fn unlink_identity(&self, provider: &str, subject: &str) -> Result<bool, Error> {
+        let conn = self.conn()?;
+        let affected = conn
+            .execute(
+                "DELETE FROM account_identities WHERE provider = ?1 AND subject = ?2",
+                libsql::params![provider, subject],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to unlink identity: {}", e)))?;
+
+        Ok(affected > 0)
+    }
+
+    async fn save_totp(&self, enrollment: &TotpEnrollment) -> Result<(), Error> {
+        let conn = self.conn()?;
+
+        conn.execute(
+            r#"
+            INSERT INTO account_totp (account_id, secret_encrypted, confirmed_at, last_used_step, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5)
+            ON CONFLICT (account_id) DO UPDATE SET
+                secret_encrypted = excluded.secret_encrypted,
+                confirmed_at = excluded.confirmed_at,
+                last_used_step = excluded.last_used_step,
+                created_at = excluded.created_at
+            "#,
+            libsql::params![
+                enrollment.account_id.to_string(),
+                enrollment.secret_encrypted.clone(),
+                opt_dt(&enrollment.confirmed_at),
+                enrollment.last_used_step,
+                enrollment.created_at.to_rfc3339(),
+            ],
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to save TOTP enrollment: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get_totp(&self, account_id: &str) -> Result<Option<TotpEnrollment>, Error> {
+        let conn = self.conn()?;
+        let mut rows = conn
+            .query(
+                "SELECT account_id, secret_encrypted, confirmed_at, last_used_step, created_at FROM account_totp WHERE account_id = ?1",
+                libsql::params![account_id],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to get TOTP enrollment: {}", e)))?;
+
+        match rows.next().await {
+            Ok(Some(row)) => Ok(Some(row_to_totp(&row)?)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(Error::Internal(format!("Failed to read row: {}", e))),
+        }
+    }
+
+    async fn delete_totp(&self, account_id: &str) -> Result<bool, Error> {
+        let conn = self.conn()?;
+
+        conn.execute(
+            "DELETE FROM account_recovery_codes WHERE account_id = ?1",
+            libsql::params![account_id],
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to delete recovery codes: {}", e)))?;
+
+        let affected = conn
+            .execute(
+                "DELETE FROM account_totp WHERE account_id = ?1",
+                libsql::params![account_id],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to delete TOTP enrollment: {}", e)))?;
+
+        Ok(affected > 0)
+    }
+
+    async fn advance_totp_step(&self, account_id: &str, step: i64) -> Result<bool, Error> {
+        let conn = self.conn()?;
+        let affected = conn
+            .execute(
+                "UPDATE account_totp SET last_used_step = ?2 WHERE account_id = ?1 AND (last_used_step IS NULL OR last_used_step < ?2)",
+                libsql::params![account_id, step],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to record TOTP step: {}", e)))?;
+
+        Ok(affected > 0)
+    }
+
+    async fn replace_recovery_codes(
+        &self,
+        account_id: &str,
+        code_hashes: &[String],
+    ) -> Result<(), Error> {
+        let conn = self.conn()?;
+        let tx = conn
+            .transaction()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to begin transaction: {}", e)))?;
+
+        tx.execute(
+            "DELETE FROM account_recovery_codes WHERE account_id = ?1",
+            libsql::params![account_id],
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to delete recovery codes: {}", e)))?;
+
+        for code_hash in code_hashes {
+            tx.execute(
+                "INSERT INTO account_recovery_codes (account_id, code_hash) VALUES (?1, ?2)",
+                libsql::params![account_id, code_hash.clone()],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to store recovery code: {}", e)))?;
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to commit recovery codes: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn consume_recovery_code(
+        &self,
+        account_id: &str,
+        code_hash: &str,
+    ) -> Result<bool, Error> {
+        let conn = self.conn()?;
+        let affected = conn
+            .execute(
+                "DELETE FROM account_recovery_codes WHERE account_id = ?1 AND code_hash = ?2",
+                libsql::params![account_id, code_hash],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to consume recovery code: {}", e)))?;
+
+        Ok(affected > 0)
+    }
+
+    #[cfg(feature = "passkeys")]
+    async fn create_passkey(&self, credential: &PasskeyCredential) -> Result<(), Error> {
+        let conn = self.conn()?;
+        let transports = serde_json::to_string(&credential.transports)
+            .map_err(|e| Error::Internal(format!("Failed to serialize transports: {}", e)))?;
+
+        let inserted = conn
+            .execute(
+                &format!(
+                    "INSERT INTO account_passkeys ({}) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11) ON CONFLICT (credential_id) DO NOTHING",
+                    PASSKEY_COLUMNS
+                ),
+                libsql::params![
+                    credential.credential_id.clone(),
+                    credential.account_id.to_string(),
+                    credential.public_key.clone(),
+                    i64::from(credential.sign_count),
+                    credential.aaguid.clone(),
+                    transports,
+                    credential.backup_eligible as i64,
+                    credential.backed_up as i64,
+                    credential.name.clone(),
+                    credential.created_at.to_rfc3339(),
+                    opt_dt(&credential.last_used_at),
+                ],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to create passkey: {}", e)))?;
+
+        if inserted == 0 {
+            return Err(Error::Conflict(format!(
+                "Passkey already registered: {}",
+                credential.credential_id
+            )));
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "passkeys")]
+    async fn get_passkey(&self, credential_id: &str) -> Result<Option<PasskeyCredential>, Error> {
+        let conn = self.conn()?;
+        let mut rows = conn
+            .query(
+                &format!(
+                    "SELECT {} FROM account_passkeys WHERE credential_id = ?1",
+                    PASSKEY_COLUMNS
+                ),
+                libsql::params![credential_id],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to get passkey: {}", e)))?;
+
+        match rows.next().await {
+            Ok(Some(row)) => Ok(Some(row_to_passkey(&row)?)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(Error::Internal(format!("Failed to read row: {}", e))),
+        }
+    }
+
+    #[cfg(feature = "passkeys")]
+    async fn list_passkeys(&self, account_id: &str) -> Result<Vec<PasskeyCredential>, Error> {
+        let conn = self.conn()?;
+        let mut passkeys = Vec::new();
+
+        let mut rows = conn
+            .query(
+                &format!(
+                    "SELECT {} FROM account_passkeys WHERE account_id = ?1 ORDER BY created_at ASC",
+                    PASSKEY_COLUMNS
+                ),
+                libsql::params![account_id],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to list passkeys: {}", e)))?;
+
+        while let Ok(Some(row)) = rows.next().await {
+            passkeys.push(row_to_passkey(&row)?);
+        }
+
+        Ok(passkeys)
+    }
+
+    #[cfg(feature = "passkeys")]
+    async fn record_passkey_use(
+        &self,
+        credential_id: &str,
+        sign_count: u32,
+        backed_up: bool,
+        used_at: DateTime<Utc>,
+    ) -> Result<bool, Error> {
+        let conn = self.conn()?;
+        let affected = conn
+            .execute(
+                "UPDATE account_passkeys SET sign_count = ?2, backed_up = ?3, last_used_at = ?4 WHERE credential_id = ?1 AND (sign_count < ?2 OR (sign_count = 0 AND ?2 = 0))",
+                libsql::params![
+                    credential_id,
+                    i64::from(sign_count),
+                    backed_up as i64,
+                    used_at.to_rfc3339(),
+                ],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to record passkey use: {}", e)))?;
+
+        Ok(affected > 0)
+    }
+
+    #[cfg(feature = "passkeys")]
+    async fn delete_passkey(&self, account_id: &str, credential_id: &str) -> Result<bool, Error> {
+        let conn = self.conn()?;
+        let affected = conn
+            .execute(
+                "DELETE FROM account_passkeys WHERE account_id = ?1 AND credential_id = ?2",
+                libsql::params![account_id, credential_id],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to delete passkey: {}", e)))?;
+
+        Ok(affected > 0)
+    }
+
+    async fn get_password_history(
+        &self,
+        account_id: &str,
+        limit: usize,
+    ) -> Result<Vec<String>, Error> {
+        let conn = self.conn()?;
+        let mut hashes = Vec::new();
+
+        let mut rows = conn
+            .query(
+                "SELECT password_hash FROM account_password_history WHERE account_id = ?1 ORDER BY id DESC LIMIT ?2",
+                libsql::params![account_id, limit as i64],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to get password history: {}", e)))?;
+
+        while let Ok(Some(row)) = rows.next().await {
+            let hash: String = row
+                .get(0)
+                .map_err(|e| Error::Internal(format!("Failed to read password_hash: {}", e)))?;
+            hashes.push(hash);
+        }
+
+        Ok(hashes)
+    }
+
+    async fn push_password_history(
+        &self,
+        account_id: &str,
+        password_hash: &str,
+        keep: usize,
+    ) -> Result<(), Error> {
+        let conn = self.conn()?;
+        let tx = conn
+            .transaction()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to begin transaction: {}", e)))?;
+
+        tx.execute(
+            "INSERT INTO account_password_history (account_id, password_hash, created_at) VALUES (?1, ?2, ?3)",
+            libsql::params![account_id, password_hash, Utc::now().to_rfc3339()],
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to record password history: {}", e)))?;
+
+        tx.execute(
+            r#"
+            DELETE FROM account_password_history
+            WHERE account_id = ?1 AND id NOT IN (
+                SELECT id FROM account_password_history
+                WHERE account_id = ?1 ORDER BY id DESC LIMIT ?2
+            )
+            "#,
+            libsql::params![account_id, keep as i64],
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to trim password history: {}", e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to commit password history: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn store_account_token(&self, token: &AccountToken) -> Result<(), Error> {
+        let conn = self.conn()?;
+        let tx = conn
+            .transaction()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to begin transaction: {}", e)))?;
+
+        tx.execute(
+            "DELETE FROM account_tokens WHERE account_id = ?1 AND purpose = ?2",
+            libsql::params![token.account_id.to_string(), token.purpose.to_string()],
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to replace account token: {}", e)))?;
+
+        tx.execute(
+            r#"
+            INSERT INTO account_tokens (token_hash, account_id, purpose, email, expires_at, created_at)
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+            "#,
+            libsql::params![
+                token.token_hash.clone(),
+                token.account_id.to_string(),
+                token.purpose.to_string(),
+                token.email.clone(),
+                token.expires_at.to_rfc3339(),
+                token.created_at.to_rfc3339(),
+            ],
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to store account token: {}", e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to commit account token: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn take_account_token(
+        &self,
+        token_hash: &str,
+        purpose: AccountTokenPurpose,
+    ) -> Result<Option<AccountToken>, Error> {
+        let conn = self.conn()?;
+        let mut rows = conn
+            .query(
+                "DELETE FROM account_tokens WHERE token_hash = ?1 AND purpose = ?2 RETURNING token_hash, account_id, purpose, email, expires_at, created_at",
+                libsql::params![token_hash, purpose.to_string()],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to redeem account token: {}", e)))?;
+
+        match rows.next().await {
+            Ok(Some(row)) => Ok(Some(row_to_account_token(&row)?)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(Error::Internal(format!("Failed to read row: {}", e))),
+        }
+    }
 }
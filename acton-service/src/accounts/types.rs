@@ -295,6 +295,219 @@ pub struct UpdateAccount {
     pub metadata: Option<serde_json::Value>,
 }
 
+// ============================================================================
+// Linked external identities
+// ============================================================================
+
+/// An external identity (e.g. an OAuth/OIDC login) linked to an account
+///
+/// Identified by the provider name and the provider's stable subject ID, so
+/// a user keeps their account when their email changes at the provider.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LinkedIdentity {
+    /// Provider name (e.g. "google", "corp")
+    pub provider: String,
+    /// The user's ID at the provider (OIDC `sub`)
+    pub subject: String,
+    /// The account this identity signs in to
+    pub account_id: AccountId,
+    /// Email the provider reported when the identity was linked
+    pub email: Option<String>,
+    /// When the identity was linked
+    pub linked_at: DateTime<Utc>,
+}
+
+/// How [`AccountService::sign_in_with_identity`](super::AccountService::sign_in_with_identity)
+/// resolved an external identity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IdentitySignIn {
+    /// The identity was already linked
+    Existing,
+    /// The identity was linked to an account with the same verified email
+    Linked,
+    /// A new account was created for the identity
+    Created,
+}
+
+// ============================================================================
+// Multi-factor authentication
+// ============================================================================
+
+/// A TOTP authenticator enrolled for an account
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TotpEnrollment {
+    /// The account this authenticator belongs to
+    pub account_id: AccountId,
+    /// The TOTP secret, sealed with the service's MFA key
+    pub secret_encrypted: String,
+    /// When the first code was verified; unconfirmed enrollments don't gate login
+    pub confirmed_at: Option<DateTime<Utc>>,
+    /// The last accepted time step, so a code can't be replayed
+    pub last_used_step: Option<i64>,
+    /// When enrollment started
+    pub created_at: DateTime<Utc>,
+}
+
+impl TotpEnrollment {
+    /// Whether this authenticator is required at login
+    pub fn is_confirmed(&self) -> bool {
+        self.confirmed_at.is_some()
+    }
+}
+
+/// What an authenticator app needs to enroll
+///
+/// Shown to the user once; only the sealed secret is stored.
+#[derive(Clone, Serialize)]
+pub struct TotpSetup {
+    /// Base32 TOTP secret for manual entry
+    pub secret: String,
+    /// `otpauth://` URI for QR codes
+    pub otpauth_uri: String,
+}
+
+impl fmt::Debug for TotpSetup {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TotpSetup")
+            .field("secret", &"[REDACTED]")
+            .field("otpauth_uri", &"[REDACTED]")
+            .finish()
+    }
+}
+
+/// A pending second-factor step after a successful password check
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfaChallenge {
+    /// Opaque token to present with the TOTP or recovery code
+    pub token: String,
+    /// When the challenge stops being accepted
+    pub expires_at: DateTime<Utc>,
+}
+
+/// Result of [`AccountService::authenticate`](super::AccountService::authenticate)
+#[derive(Debug, Clone)]
+pub enum AuthOutcome {
+    /// Credentials were sufficient; the user is signed in
+    Authenticated(Box<Account>),
+    /// The password was correct, but the account requires a second factor
+    MfaRequired(MfaChallenge),
+}
+
+/// Which second factor completed an MFA challenge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MfaMethod {
+    /// A code from the authenticator app
+    Totp,
+    /// A single-use recovery code
+    RecoveryCode,
+}
+
+impl fmt::Display for MfaMethod {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Totp => write!(f, "totp"),
+            Self::RecoveryCode => write!(f, "recovery_code"),
+        }
+    }
+}
+
+// ============================================================================
+// Passkeys
+// ============================================================================
+
+/// A WebAuthn passkey registered to an account
+#[cfg(feature = "passkeys")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PasskeyCredential {
+    /// Credential ID chosen by the authenticator (base64url)
+    pub credential_id: String,
+    /// The account this passkey signs in to
+    pub account_id: AccountId,
+    /// COSE public key from registration (base64url)
+    pub public_key: String,
+    /// Last signature counter seen; 0 for authenticators without one
+    pub sign_count: u32,
+    /// Authenticator model (AAGUID), all zeros when not disclosed
+    pub aaguid: String,
+    /// Transports the browser reported, as hints for later logins
+    #[serde(default)]
+    pub transports: Vec<String>,
+    /// Whether the passkey may be synced to other devices
+    pub backup_eligible: bool,
+    /// Whether the passkey was synced when last used
+    pub backed_up: bool,
+    /// Label chosen by the user, e.g. "Work laptop"
+    pub name: Option<String>,
+    /// When the passkey was registered
+    pub created_at: DateTime<Utc>,
+    /// When the passkey last completed a login
+    pub last_used_at: Option<DateTime<Utc>>,
+}
+
+// ============================================================================
+// Self-service tokens
+// ============================================================================
+
+/// What a self-service account token may be redeemed for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountTokenPurpose {
+    /// "Forgot password": set a new password without the old one
+    PasswordReset,
+    /// Prove ownership of the account's email address
+    EmailVerification,
+}
+
+impl fmt::Display for AccountTokenPurpose {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::PasswordReset => write!(f, "password_reset"),
+            Self::EmailVerification => write!(f, "email_verification"),
+        }
+    }
+}
+
+impl FromStr for AccountTokenPurpose {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "password_reset" => Ok(Self::PasswordReset),
+            "email_verification" => Ok(Self::EmailVerification),
+            other => Err(format!("unknown account token purpose: {}", other)),
+        }
+    }
+}
+
+/// A single-use, expiring token sent to the account's email address
+///
+/// Only the token's BLAKE3 hash is stored; the token itself goes out in the
+/// [`AccountEvent`](super::AccountEvent) for delivery and is never persisted.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AccountToken {
+    /// BLAKE3 hash of the token (hex)
+    pub token_hash: String,
+    /// The account the token acts on
+    pub account_id: AccountId,
+    /// What the token may be redeemed for
+    pub purpose: AccountTokenPurpose,
+    /// The address the token was sent to; it is void if the account's email changes
+    pub email: String,
+    /// When the token stops being accepted
+    pub expires_at: DateTime<Utc>,
+    /// When the token was issued
+    pub created_at: DateTime<Utc>,
+}
+
+impl AccountToken {
+    /// Whether the token is past its expiry
+    pub fn is_expired(&self) -> bool {
+        self.expires_at <= Utc::now()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -435,4 +648,20 @@ mod tests {
         let deserialized: AccountStatus = serde_json::from_str(&json).unwrap();
         assert_eq!(status, deserialized);
     }
+
+    #[test]
+    fn test_account_token_purpose_display_fromstr_roundtrip() {
+        for purpose in [
+            AccountTokenPurpose::PasswordReset,
+            AccountTokenPurpose::EmailVerification,
+        ] {
+            let s = purpose.to_string();
+            assert_eq!(
+                serde_json::to_string(&purpose).unwrap(),
+                format!("\"{}\"", s)
+            );
+            assert_eq!(AccountTokenPurpose::from_str(&s).unwrap(), purpose);
+        }
+        assert!(AccountTokenPurpose::from_str("mfa_challenge").is_err());
+    }
 }
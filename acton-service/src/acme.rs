@@ -0,0 +1,1435 @@
+//! ACME certificate provisioning (requires `acme` feature)
+//!
+//! With [`TlsConfig::acme`] set, a listener obtains its default certificate
+//! from an ACME (RFC 8555) certificate authority such as Let's Encrypt and
+//! renews it before it expires, with no ingress or sidecar in front of it:
+//!
+//! ```toml
+//! [tls]
+//! cert_path = "/var/lib/acme/cert.pem"
+//! key_path = "/var/lib/acme/key.pem"
+//!
+//! [tls.acme]
+//! directory_url = "https://acme-v02.api.letsencrypt.org/directory"
+//! domains = ["api.example.com"]
+//! contact = ["mailto:ops@example.com"]
+//! accept_terms_of_service = true
+//! account_key_path = "/var/lib/acme/account.pem"
+//! ```
+//!
+//! # How it fits the rest of TLS
+//!
+//! ACME is a writer of `cert_path` and `key_path`, nothing more. An issued
+//! certificate is written to those files and installed with
+//! [`TlsConfigSource::reload`], the same fail-closed reload every other
+//! rotation uses, so expiry monitoring, the reload poll and
+//! [`TlsConfigSource::certificates`] all see it like any other certificate.
+//! The account key is kept at `account_key_path`, so a restart reuses the
+//! account instead of registering a new one.
+//!
+//! On a first start there is nothing to serve yet. [`TlsConfigSource`] then
+//! writes a self-signed placeholder valid for two days, which keeps the
+//! listener up while the first certificate is requested. It is inside the
+//! renewal window from the start, so it is replaced as soon as the CA
+//! answers, and the `tls_certificates` readiness check reports it as expiring
+//! until then.
+//!
+//! # Challenges
+//!
+//! Both challenges are answered by the listeners the service already runs:
+//!
+//! - **`tls-alpn-01`** (the default): the CA connects to port 443 offering the
+//!   `acme-tls/1` protocol, and the listener presents a challenge certificate
+//!   for that one handshake (RFC 8737). Ordinary handshakes are unaffected.
+//! - **`http-01`**: the CA fetches `/.well-known/acme-challenge/<token>` on
+//!   port 80. `ServiceBuilder` mounts that route on the HTTP router outside
+//!   every middleware layer, since the CA carries no credentials. Port 80
+//!   only has to redirect to the HTTPS listener: CAs follow a redirect to
+//!   port 443 and do not check the certificate they find there.
+//!
+//! Wildcard names need `dns-01`, which is not supported.
+//!
+//! # Renewal
+//!
+//! A background task per listener checks the certificate on disk every
+//! twelve hours and requests a new one when it is within
+//! `renew_before_days` of expiry, does not cover every configured domain, or
+//! cannot be read. A failed attempt is logged at `ERROR` and retried with
+//! backoff from one minute up to an hour; the previous certificate keeps
+//! serving throughout. [`renew_if_due`] runs the same check on demand.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Write;
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use axum::extract::{Path as UrlPath, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use p256::ecdsa::signature::Signer;
+use p256::ecdsa::{Signature, SigningKey};
+use p256::pkcs8::DecodePrivateKey;
+use rustls_pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tokio_rustls::rustls::server::{ClientHello, ResolvesServerCert};
+use tokio_rustls::rustls::sign::CertifiedKey;
+use tokio_rustls::rustls::ServerConfig;
+use zeroize::Zeroizing;
+
+use crate::config::{AcmeChallenge, AcmeConfig, TlsConfig};
+use crate::error::{Error, Result};
+use crate::tls::{TlsConfigSource, TlsListenerKind};
+
+/// The ALPN protocol an ACME server offers when validating `tls-alpn-01`.
+pub const ACME_TLS_ALPN_PROTOCOL: &[u8] = b"acme-tls/1";
+
+/// The path prefix `http-01` challenges are fetched under.
+pub const HTTP01_PATH_PREFIX: &str = "/.well-known/acme-challenge/";
+
+/// The renewal window used when `renew_before_days` is unset.
+pub const DEFAULT_RENEW_BEFORE_DAYS: u64 = 30;
+
+/// How often the renewal task checks the certificate on disk.
+const RENEWAL_CHECK_INTERVAL: Duration = Duration::from_secs(12 * 60 * 60);
+
+/// The first retry delay after a failed renewal, doubled on each failure.
+const RETRY_MIN: Duration = Duration::from_secs(60);
+
+/// The longest a failed renewal waits before trying again.
+const RETRY_MAX: Duration = Duration::from_secs(60 * 60);
+
+/// How long the placeholder certificate written on a first start is valid.
+const PLACEHOLDER_VALIDITY_DAYS: u64 = 2;
+
+/// Per-request timeout for calls to the ACME server.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// The wait between polls of a pending authorization or order, when the
+/// server sends no `Retry-After`.
+const POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// The longest `Retry-After` honoured between polls.
+const POLL_INTERVAL_MAX: Duration = Duration::from_secs(30);
+
+/// How many times a pending authorization or order is polled before giving up.
+const POLL_ATTEMPTS: u32 = 60;
+
+// ---------------------------------------------------------------------------
+// Challenge responses
+// ---------------------------------------------------------------------------
+
+/// The challenge responses a listener is currently offering.
+///
+/// Shared between the renewal task, which publishes a response for the
+/// lifetime of one authorization, and whatever answers the CA: the TLS
+/// listener for `tls-alpn-01`, the HTTP router for `http-01`. Cloning is cheap
+/// and every clone sees the same responses.
+#[derive(Clone, Debug, Default)]
+pub struct AcmeChallenges {
+    inner: Arc<RwLock<PendingChallenges>>,
+}
+
+#[derive(Debug, Default)]
+struct PendingChallenges {
+    /// Key authorizations by `http-01` token.
+    http01: HashMap<String, String>,
+    /// Challenge certificates by lowercased domain, for `tls-alpn-01`.
+    tls_alpn01: HashMap<String, Arc<CertifiedKey>>,
+}
+
+impl AcmeChallenges {
+    /// The key authorization to answer an `http-01` request for `token` with.
+    #[must_use]
+    pub fn http01_response(&self, token: &str) -> Option<String> {
+        self.read().http01.get(token).cloned()
+    }
+
+    /// The certificate to present to a `tls-alpn-01` validation of
+    /// `server_name`.
+    fn tls_alpn01_certificate(&self, server_name: &str) -> Option<Arc<CertifiedKey>> {
+        self.read()
+            .tls_alpn01
+            .get(&server_name.to_ascii_lowercase())
+            .cloned()
+    }
+
+    /// Offer a response until the returned guard is dropped.
+    fn publish(
+        &self,
+        kind: AcmeChallenge,
+        domain: &str,
+        token: &str,
+        key_authorization: &str,
+    ) -> Result<PublishedChallenge> {
+        let key = match kind {
+            AcmeChallenge::Http01 => {
+                self.write()
+                    .http01
+                    .insert(token.to_string(), key_authorization.to_string());
+                token.to_string()
+            }
+            AcmeChallenge::TlsAlpn01 => {
+                let certificate = challenge_certificate(domain, key_authorization)?;
+                let domain = domain.to_ascii_lowercase();
+                self.write().tls_alpn01.insert(domain.clone(), certificate);
+                domain
+            }
+        };
+        Ok(PublishedChallenge {
+            challenges: self.clone(),
+            kind,
+            key,
+        })
+    }
+
+    /// Wrap a freshly built configuration so it also answers `tls-alpn-01`.
+    ///
+    /// Handshakes that offer `acme-tls/1` get the challenge certificate for
+    /// their SNI name, or no certificate at all; every other handshake is
+    /// passed to the configuration's own resolver untouched.
+    pub(crate) fn wrap_server_config(&self, server_config: Arc<ServerConfig>) -> Arc<ServerConfig> {
+        let mut config = Arc::unwrap_or_clone(server_config);
+        config.cert_resolver = Arc::new(AcmeCertResolver {
+            inner: Arc::clone(&config.cert_resolver),
+            challenges: self.clone(),
+        });
+        config.alpn_protocols.push(ACME_TLS_ALPN_PROTOCOL.to_vec());
+        Arc::new(config)
+    }
+
+    fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+
+    fn read(&self) -> std::sync::RwLockReadGuard<'_, PendingChallenges> {
+        self.inner.read().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn write(&self) -> std::sync::RwLockWriteGuard<'_, PendingChallenges> {
+        self.inner.write().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Withdraws a published challenge response when dropped, so an order that
+/// fails part-way leaves nothing behind.
+struct PublishedChallenge {
+    challenges: AcmeChallenges,
+    kind: AcmeChallenge,
+    key: String,
+}
+
+impl Drop for PublishedChallenge {
+    fn drop(&mut self) {
+        let mut pending = self.challenges.write();
+        match self.kind {
+            AcmeChallenge::Http01 => {
+                pending.http01.remove(&self.key);
+            }
+            AcmeChallenge::TlsAlpn01 => {
+                pending.tls_alpn01.remove(&self.key);
+            }
+        }
+    }
+}
+
+/// Presents challenge certificates to `acme-tls/1` handshakes and defers
+/// everything else.
+#[derive(Debug)]
+struct AcmeCertResolver {
+    inner: Arc<dyn ResolvesServerCert>,
+    challenges: AcmeChallenges,
+}
+
+impl ResolvesServerCert for AcmeCertResolver {
+    fn resolve(&self, client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        let is_validation = client_hello
+            .alpn()
+            .is_some_and(|mut protocols| protocols.any(|p| p == ACME_TLS_ALPN_PROTOCOL));
+        if is_validation {
+            // Never fall through to the real certificate: RFC 8737 requires a
+            // validation handshake to see the challenge certificate or fail.
+            return client_hello
+                .server_name()
+                .and_then(|name| self.challenges.tls_alpn01_certificate(name));
+        }
+        self.inner.resolve(client_hello)
+    }
+}
+
+/// A self-signed certificate for `domain` carrying the `acmeIdentifier`
+/// extension that proves control of it (RFC 8737 §3).
+fn challenge_certificate(domain: &str, key_authorization: &str) -> Result<Arc<CertifiedKey>> {
+    let digest = Sha256::digest(key_authorization.as_bytes());
+    let key_pair = rcgen::KeyPair::generate().map_err(certificate_error)?;
+    let mut params =
+        rcgen::CertificateParams::new(vec![domain.to_string()]).map_err(certificate_error)?;
+    params.custom_extensions = vec![rcgen::CustomExtension::new_acme_identifier(&digest)];
+    let certificate = params.self_signed(&key_pair).map_err(certificate_error)?;
+
+    let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key_pair.serialize_der()));
+    let signing_key = crate::tls::default_crypto_provider()?
+        .key_provider
+        .load_private_key(key)
+        .map_err(|e| Error::Tls(format!("ACME challenge key is not usable: {e}")))?;
+    Ok(Arc::new(CertifiedKey::new(
+        vec![certificate.der().clone()],
+        signing_key,
+    )))
+}
+
+fn certificate_error(e: rcgen::Error) -> Error {
+    Error::Tls(format!(
+        "Failed to generate an ACME certificate or key: {e}"
+    ))
+}
+
+/// Routes answering `http-01` challenges for every source that uses them.
+///
+/// `None` when none of `sources` provisions through `http-01`, so a service
+/// that does not need the route does not mount it. The route sits outside
+/// every middleware layer it is merged beside: the CA presents no credentials.
+pub fn http01_router<'a>(sources: impl IntoIterator<Item = &'a TlsConfigSource>) -> Option<Router> {
+    let mut challenges: Vec<AcmeChallenges> = Vec::new();
+    for source in sources {
+        let uses_http01 = source
+            .origin()
+            .and_then(|origin| origin.acme.as_ref())
+            .is_some_and(|acme| acme.challenge == AcmeChallenge::Http01);
+        if let Some(pending) = source.acme_challenges().filter(|_| uses_http01) {
+            if !challenges.iter().any(|known| known.ptr_eq(pending)) {
+                challenges.push(pending.clone());
+            }
+        }
+    }
+    if challenges.is_empty() {
+        return None;
+    }
+
+    let path = format!("{HTTP01_PATH_PREFIX}{{token}}");
+    Some(
+        Router::new()
+            .route(&path, get(http01_handler))
+            .with_state(Arc::new(challenges)),
+    )
+}
+
+async fn http01_handler(
+    State(challenges): State<Arc<Vec<AcmeChallenges>>>,
+    UrlPath(token): UrlPath<String>,
+) -> Response {
+    match challenges.iter().find_map(|c| c.http01_response(&token)) {
+        Some(key_authorization) => (
+            [(header::CONTENT_TYPE, "application/octet-stream")],
+            key_authorization,
+        )
+            .into_response(),
+        None => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Startup
+// ---------------------------------------------------------------------------
+
+/// Check a listener's ACME configuration and make sure it has something to
+/// serve.
+///
+/// Called by [`TlsConfigSource::from_tls_config`] before it loads the files:
+/// when `cert_path` or `key_path` does not exist yet, a placeholder is written
+/// so the listener can come up and answer the challenges that replace it.
+pub(crate) fn prepare(tls_config: &TlsConfig) -> Result<()> {
+    let Some(ref acme) = tls_config.acme else {
+        return Ok(());
+    };
+    validate(tls_config, acme)?;
+
+    if tls_config.cert_path.exists() && tls_config.key_path.exists() {
+        return Ok(());
+    }
+
+    let issued = placeholder_certificate(&acme.domains)?;
+    install_files(tls_config, &issued)?;
+    tracing::warn!(
+        cert_path = %tls_config.cert_path.display(),
+        domains = ?acme.domains,
+        "no certificate on disk yet; serving a self-signed placeholder until the \
+         ACME server issues one"
+    );
+    Ok(())
+}
+
+/// Refuse configurations that could never obtain a certificate.
+fn validate(tls_config: &TlsConfig, acme: &AcmeConfig) -> Result<()> {
+    let refuse = |reason: String| Err(Error::Tls(format!("[tls.acme] {reason}")));
+
+    if !acme.directory_url.starts_with("https://") {
+        return refuse(format!(
+            "directory_url '{}' is not an https:// URL; RFC 8555 requires ACME over HTTPS",
+            acme.directory_url
+        ));
+    }
+    if acme.domains.is_empty() {
+        return refuse("lists no domains to request a certificate for".to_string());
+    }
+    if let Some(wildcard) = acme.domains.iter().find(|d| d.contains('*')) {
+        return refuse(format!(
+            "domain '{wildcard}' is a wildcard, which only the dns-01 challenge can \
+             validate, and dns-01 is not supported"
+        ));
+    }
+    if !acme.accept_terms_of_service {
+        return refuse(
+            "requires accept_terms_of_service = true: the CA will not create an account \
+             without agreement to its terms"
+                .to_string(),
+        );
+    }
+    if acme.renew_before_days == Some(0) {
+        return refuse(
+            "sets renew_before_days = 0, which would wait for the certificate to expire \
+             before renewing it. Omit the field to use the default of 30 days."
+                .to_string(),
+        );
+    }
+    if acme.challenge == AcmeChallenge::TlsAlpn01
+        && tls_config.client_ca_path.is_some()
+        && !tls_config.client_auth_optional
+    {
+        return refuse(
+            "uses tls-alpn-01 on a listener that requires client certificates; the CA's \
+             validation handshake presents none and would be refused. Use \
+             challenge = \"http-01\", or set client_auth_optional = true."
+                .to_string(),
+        );
+    }
+    Ok(())
+}
+
+/// A certificate and key to write to a listener's files.
+struct IssuedCertificate {
+    cert_pem: String,
+    key_pem: Zeroizing<String>,
+}
+
+/// A self-signed certificate for `domains` that expires within the renewal
+/// window, so the first renewal check replaces it.
+fn placeholder_certificate(domains: &[String]) -> Result<IssuedCertificate> {
+    use chrono::Datelike;
+
+    let date = |day: chrono::NaiveDate| {
+        rcgen::date_time_ymd(day.year(), day.month() as u8, day.day() as u8)
+    };
+    let today = Utc::now().date_naive();
+
+    let key_pair = rcgen::KeyPair::generate().map_err(certificate_error)?;
+    let mut params = rcgen::CertificateParams::new(domains.to_vec()).map_err(certificate_error)?;
+    params.not_before = date(today);
+    params.not_after = date(today + chrono::Days::new(PLACEHOLDER_VALIDITY_DAYS));
+    let certificate = params.self_signed(&key_pair).map_err(certificate_error)?;
+
+    Ok(IssuedCertificate {
+        cert_pem: certificate.pem(),
+        key_pem: Zeroizing::new(key_pair.serialize_pem()),
+    })
+}
+
+/// Write a certificate and key to a listener's files.
+///
+/// Each file is written beside its target and renamed over it, so a reader
+/// sees the old file or the new one, never half of one. The key goes first:
+/// a reload that lands between the two renames sees a mismatched pair and
+/// keeps serving the previous certificate, rather than serving nothing.
+fn install_files(tls_config: &TlsConfig, issued: &IssuedCertificate) -> Result<()> {
+    write_atomically(&tls_config.key_path, issued.key_pem.as_bytes(), true)?;
+    write_atomically(&tls_config.cert_path, issued.cert_pem.as_bytes(), false)?;
+    Ok(())
+}
+
+/// Replace `path` with `contents` by writing a sibling and renaming it over.
+///
+/// `private` restricts the new file to its owner on Unix; it holds a key.
+fn write_atomically(path: &Path, contents: &[u8], private: bool) -> Result<()> {
+    let io_error = |e: std::io::Error| {
+        Error::Tls(format!(
+            "Failed to write ACME file '{}': {}",
+            path.display(),
+            e
+        ))
+    };
+
+    if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+        std::fs::create_dir_all(parent).map_err(io_error)?;
+    }
+
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let staging = path.with_file_name(format!(".{file_name}.acme-tmp"));
+    // A leftover from an interrupted write may carry looser permissions than
+    // the ones set below, which only apply to a newly created file.
+    let _ = std::fs::remove_file(&staging);
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    #[cfg(not(unix))]
+    let _ = private;
+
+    let mut file = options.open(&staging).map_err(io_error)?;
+    file.write_all(contents).map_err(io_error)?;
+    file.sync_all().map_err(io_error)?;
+    std::fs::rename(&staging, path).map_err(io_error)
+}
+
+// ---------------------------------------------------------------------------
+// Renewal
+// ---------------------------------------------------------------------------
+
+/// Renew `source`'s certificate if it is due, and install the new one.
+///
+/// Due means the certificate in `cert_path` cannot be read, does not cover
+/// every configured domain, or expires within `renew_before_days`. Returns
+/// whether a new certificate was installed.
+///
+/// # Errors
+///
+/// Returns an error when `source` does not provision through ACME, when any
+/// step of the order fails, or when the issued certificate cannot be written
+/// or installed. The previously installed certificate keeps serving in every
+/// case.
+pub async fn renew_if_due(source: &TlsConfigSource) -> Result<bool> {
+    let (tls_config, acme, challenges) = match (
+        source.origin(),
+        source.origin().and_then(|o| o.acme.as_ref()),
+        source.acme_challenges(),
+    ) {
+        (Some(tls_config), Some(acme), Some(challenges)) => {
+            (tls_config.clone(), acme.clone(), challenges.clone())
+        }
+        _ => {
+            return Err(Error::Tls(
+                "this TLS source does not provision its certificate through ACME".to_string(),
+            ))
+        }
+    };
+
+    // The certificate file is read with blocking `std::fs`, like every other
+    // credential read, so it runs on the blocking pool.
+    let check_config = tls_config.clone();
+    let due = tokio::task::spawn_blocking(move || {
+        let window = renew_before(check_config.acme.as_ref());
+        let chain = crate::tls::read_cert_chain(&check_config.cert_path).ok();
+        let domains = check_config
+            .acme
+            .as_ref()
+            .map(|acme| acme.domains.clone())
+            .unwrap_or_default();
+        renewal_reason(chain.as_deref(), &domains, window, Utc::now())
+    })
+    .await
+    .map_err(|e| Error::Internal(format!("ACME renewal check did not complete: {e}")))?;
+
+    let Some(reason) = due else {
+        return Ok(false);
+    };
+    tracing::info!(
+        domains = ?acme.domains,
+        directory_url = %acme.directory_url,
+        reason = %reason,
+        "requesting a certificate from the ACME server"
+    );
+
+    let issued = issue(&acme, &challenges).await?;
+
+    let install_source = source.clone();
+    tokio::task::spawn_blocking(move || {
+        install_files(&tls_config, &issued)?;
+        install_source.reload()
+    })
+    .await
+    .map_err(|e| Error::Internal(format!("ACME certificate install did not complete: {e}")))??;
+
+    tracing::info!(
+        domains = ?acme.domains,
+        "ACME certificate issued and installed; new handshakes use it"
+    );
+    Ok(true)
+}
+
+/// Keep `source`'s certificate renewed until the task is aborted.
+///
+/// Checks immediately, so a placeholder is replaced as soon as the service is
+/// serving, then every twelve hours. A failure retries sooner, with backoff,
+/// rather than waiting out the full interval with an expiring certificate.
+pub(crate) fn spawn_renewal(
+    source: TlsConfigSource,
+    listener: TlsListenerKind,
+) -> tokio::task::JoinHandle<()> {
+    tracing::info!(
+        listener = listener.as_str(),
+        "ACME renewal enabled for the {listener} listener"
+    );
+
+    tokio::spawn(async move {
+        let mut retry = RETRY_MIN;
+        loop {
+            let delay = match renew_if_due(&source).await {
+                Ok(_) => {
+                    retry = RETRY_MIN;
+                    RENEWAL_CHECK_INTERVAL
+                }
+                Err(e) => {
+                    tracing::error!(
+                        listener = listener.as_str(),
+                        error = %e,
+                        retry_in_secs = retry.as_secs(),
+                        "ACME certificate renewal failed; continuing to serve the \
+                         current certificate"
+                    );
+                    let delay = retry;
+                    retry = (retry * 2).min(RETRY_MAX);
+                    delay
+                }
+            };
+            tokio::time::sleep(delay).await;
+        }
+    })
+}
+
+/// The configured renewal window.
+fn renew_before(acme: Option<&AcmeConfig>) -> chrono::Duration {
+    let days = acme
+        .and_then(|acme| acme.renew_before_days)
+        .unwrap_or(DEFAULT_RENEW_BEFORE_DAYS);
+    // Anything past a century is as good as "always"; the clamp keeps the
+    // conversion in range.
+    chrono::Duration::days(days.min(36_500) as i64)
+}
+
+/// Why the certificate needs replacing, or `None` if it does not.
+fn renewal_reason(
+    chain: Option<&[CertificateDer<'_>]>,
+    domains: &[String],
+    window: chrono::Duration,
+    now: DateTime<Utc>,
+) -> Option<String> {
+    use x509_parser::extensions::GeneralName;
+    use x509_parser::prelude::{FromDer, X509Certificate};
+
+    let Some(leaf) = chain.and_then(|chain| chain.first()) else {
+        return Some("no readable certificate on disk".to_string());
+    };
+    let Ok((_, cert)) = X509Certificate::from_der(leaf.as_ref()) else {
+        return Some("the certificate on disk does not parse".to_string());
+    };
+
+    let names: Vec<String> = match cert.subject_alternative_name() {
+        Ok(Some(san)) => san
+            .value
+            .general_names
+            .iter()
+            .filter_map(|name| match name {
+                GeneralName::DNSName(dns) => Some(dns.to_ascii_lowercase()),
+                _ => None,
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    if let Some(missing) = domains
+        .iter()
+        .find(|domain| !names.contains(&domain.to_ascii_lowercase()))
+    {
+        return Some(format!(
+            "the certificate on disk does not cover '{missing}'"
+        ));
+    }
+
+    let not_after = DateTime::from_timestamp(cert.validity().not_after.timestamp(), 0)?;
+    if not_after - now <= window {
+        return Some(format!(
+            "the certificate on disk expires at {}",
+            not_after.to_rfc3339()
+        ));
+    }
+    None
+}
+
+// ---------------------------------------------------------------------------
+// Protocol
+// ---------------------------------------------------------------------------
+
+/// Run one order to completion and return the issued certificate.
+async fn issue(acme: &AcmeConfig, challenges: &AcmeChallenges) -> Result<IssuedCertificate> {
+    let key_path = acme.account_key_path.clone();
+    let account_key = tokio::task::spawn_blocking(move || AccountKey::load_or_create(&key_path))
+        .await
+        .map_err(|e| Error::Internal(format!("ACME account key load did not complete: {e}")))??;
+
+    let mut client = AcmeClient::connect(acme, account_key).await?;
+    client.register(acme).await?;
+
+    let (order_url, order) = client.new_order(&acme.domains).await?;
+    for authorization in &order.authorizations {
+        client
+            .authorize(authorization, acme.challenge, challenges)
+            .await?;
+    }
+
+    let order: Order = client.poll(&order_url, &["pending"]).await?;
+    if order.status != "ready" {
+        return Err(order_failed(
+            &order,
+            "did not become ready after validation",
+        ));
+    }
+
+    let key_pair = rcgen::KeyPair::generate().map_err(certificate_error)?;
+    let csr = rcgen::CertificateParams::new(acme.domains.clone())
+        .and_then(|params| params.serialize_request(&key_pair))
+        .map_err(certificate_error)?;
+    client
+        .post(
+            &order.finalize,
+            Some(&json!({ "csr": URL_SAFE_NO_PAD.encode(csr.der()) })),
+        )
+        .await?;
+
+    let order: Order = client.poll(&order_url, &["ready", "processing"]).await?;
+    let certificate_url = match (order.status.as_str(), order.certificate.as_deref()) {
+        ("valid", Some(url)) => url.to_string(),
+        _ => return Err(order_failed(&order, "was not issued after finalization")),
+    };
+    let cert_pem = client.post(&certificate_url, None).await?.body;
+
+    Ok(IssuedCertificate {
+        cert_pem,
+        key_pem: Zeroizing::new(key_pair.serialize_pem()),
+    })
+}
+
+fn order_failed(order: &Order, what: &str) -> Error {
+    let detail = order
+        .error
+        .as_ref()
+        .map(|problem| format!(": {problem}"))
+        .unwrap_or_default();
+    Error::External(format!(
+        "ACME order {what} (status '{}'){detail}",
+        order.status
+    ))
+}
+
+/// The ACME account key: an ECDSA P-256 key signing every request as ES256.
+struct AccountKey {
+    signing_key: SigningKey,
+    /// The public key as a JWK, sent when registering.
+    jwk: Value,
+    /// The RFC 7638 thumbprint of `jwk`, part of every key authorization.
+    thumbprint: String,
+}
+
+impl AccountKey {
+    /// Read the key at `path`, generating and saving one if there is none.
+    fn load_or_create(path: &Path) -> Result<Self> {
+        if !path.exists() {
+            let key_pair = rcgen::KeyPair::generate_for(&rcgen::PKCS_ECDSA_P256_SHA256)
+                .map_err(certificate_error)?;
+            write_atomically(path, key_pair.serialize_pem().as_bytes(), true)?;
+            tracing::info!(
+                account_key_path = %path.display(),
+                "generated a new ACME account key"
+            );
+        }
+
+        let key = crate::tls::read_private_key(path)?;
+        let PrivateKeyDer::Pkcs8(ref pkcs8) = key else {
+            return Err(Error::Tls(format!(
+                "ACME account key '{}' is not a PKCS#8 key",
+                path.display()
+            )));
+        };
+        let signing_key = SigningKey::from_pkcs8_der(pkcs8.secret_pkcs8_der()).map_err(|e| {
+            Error::Tls(format!(
+                "ACME account key '{}' is not an ECDSA P-256 key: {}",
+                path.display(),
+                e
+            ))
+        })?;
+        Ok(Self::new(signing_key))
+    }
+
+    fn new(signing_key: SigningKey) -> Self {
+        let point = signing_key.verifying_key().to_encoded_point(false);
+        // An uncompressed SEC1 point is 0x04 followed by the two coordinates.
+        let (x, y) = point.as_bytes()[1..].split_at(32);
+        let x = URL_SAFE_NO_PAD.encode(x);
+        let y = URL_SAFE_NO_PAD.encode(y);
+
+        // RFC 7638: the required members, in lexicographic order, with no
+        // whitespace. Formatted by hand so no serializer can reorder them.
+        let canonical = format!(r#"{{"crv":"P-256","kty":"EC","x":"{x}","y":"{y}"}}"#);
+        let thumbprint = URL_SAFE_NO_PAD.encode(Sha256::digest(canonical.as_bytes()));
+
+        Self {
+            signing_key,
+            jwk: json!({ "crv": "P-256", "kty": "EC", "x": x, "y": y }),
+            thumbprint,
+        }
+    }
+
+    /// The key authorization for a challenge token (RFC 8555 §8.1).
+    fn key_authorization(&self, token: &str) -> String {
+        format!("{token}.{}", self.thumbprint)
+    }
+
+    /// A flattened JWS over `payload`, already base64url-encoded.
+    fn sign(&self, protected: &Value, payload: &str) -> Value {
+        let protected = URL_SAFE_NO_PAD.encode(protected.to_string());
+        let signature: Signature = self
+            .signing_key
+            .sign(format!("{protected}.{payload}").as_bytes());
+        json!({
+            "protected": protected,
+            "payload": payload,
+            "signature": URL_SAFE_NO_PAD.encode(signature.to_bytes()),
+        })
+    }
+}
+
+/// The endpoints an ACME server publishes in its directory.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Directory {
+    new_nonce: String,
+    new_account: String,
+    new_order: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Order {
+    status: String,
+    #[serde(default)]
+    authorizations: Vec<String>,
+    finalize: String,
+    #[serde(default)]
+    certificate: Option<String>,
+    #[serde(default)]
+    error: Option<Problem>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Authorization {
+    status: String,
+    identifier: Identifier,
+    #[serde(default)]
+    challenges: Vec<ChallengeObject>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Identifier {
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ChallengeObject {
+    #[serde(rename = "type")]
+    kind: String,
+    url: String,
+    #[serde(default)]
+    token: String,
+    #[serde(default)]
+    error: Option<Problem>,
+}
+
+/// An RFC 7807 problem document, as ACME servers report errors.
+#[derive(Debug, Default, Deserialize)]
+struct Problem {
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    detail: String,
+}
+
+impl fmt::Display for Problem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (self.kind.is_empty(), self.detail.is_empty()) {
+            (true, true) => f.write_str("no problem document"),
+            (false, true) => f.write_str(&self.kind),
+            (true, false) => f.write_str(&self.detail),
+            (false, false) => write!(f, "{} ({})", self.detail, self.kind),
+        }
+    }
+}
+
+/// Objects that move through ACME's status values while the server works.
+trait HasStatus {
+    fn status(&self) -> &str;
+}
+
+impl HasStatus for Order {
+    fn status(&self) -> &str {
+        &self.status
+    }
+}
+
+impl HasStatus for Authorization {
+    fn status(&self) -> &str {
+        &self.status
+    }
+}
+
+/// A successful reply from the ACME server.
+struct AcmeResponse {
+    location: Option<String>,
+    retry_after: Option<Duration>,
+    body: String,
+}
+
+impl AcmeResponse {
+    fn json<T: DeserializeOwned>(&self, url: &str) -> Result<T> {
+        serde_json::from_str(&self.body).map_err(|e| {
+            Error::External(format!(
+                "ACME server sent an unexpected reply from {url}: {e}"
+            ))
+        })
+    }
+}
+
+/// One conversation with an ACME server, under one account.
+struct AcmeClient {
+    http: reqwest::Client,
+    directory: Directory,
+    key: AccountKey,
+    /// The `Replay-Nonce` from the last reply, spent by the next request.
+    nonce: Option<String>,
+    /// The account URL, used as `kid` once registered.
+    account_url: Option<String>,
+}
+
+impl AcmeClient {
+    async fn connect(acme: &AcmeConfig, key: AccountKey) -> Result<Self> {
+        crate::crypto::ensure_default_crypto_provider();
+
+        let mut builder = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .user_agent(concat!("acton-service/", env!("CARGO_PKG_VERSION")));
+        if let Some(ref path) = acme.directory_ca_path {
+            let pem = std::fs::read(path).map_err(|e| {
+                Error::Tls(format!(
+                    "Failed to read ACME directory CA bundle '{}': {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            let certs = reqwest::Certificate::from_pem_bundle(&pem).map_err(|e| {
+                Error::Tls(format!(
+                    "Failed to parse ACME directory CA bundle '{}': {}",
+                    path.display(),
+                    e
+                ))
+            })?;
+            for cert in certs {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+        let http = builder
+            .build()
+            .map_err(|e| Error::Tls(format!("Failed to build the ACME HTTP client: {e}")))?;
+
+        let response = http
+            .get(&acme.directory_url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| unreachable_server(&acme.directory_url, e))?;
+        let body = response
+            .text()
+            .await
+            .map_err(|e| unreachable_server(&acme.directory_url, e))?;
+        let directory = serde_json::from_str(&body).map_err(|e| {
+            Error::External(format!(
+                "'{}' is not an ACME directory: {}",
+                acme.directory_url, e
+            ))
+        })?;
+
+        Ok(Self {
+            http,
+            directory,
+            key,
+            nonce: None,
+            account_url: None,
+        })
+    }
+
+    /// Register the account key, or look up the account it already has.
+    async fn register(&mut self, acme: &AcmeConfig) -> Result<()> {
+        let url = self.directory.new_account.clone();
+        let response = self
+            .post(
+                &url,
+                Some(&json!({
+                    "termsOfServiceAgreed": acme.accept_terms_of_service,
+                    "contact": acme.contact,
+                })),
+            )
+            .await?;
+        let account_url = response.location.ok_or_else(|| {
+            Error::External("ACME server registered the account without a Location".to_string())
+        })?;
+        self.account_url = Some(account_url);
+        Ok(())
+    }
+
+    async fn new_order(&mut self, domains: &[String]) -> Result<(String, Order)> {
+        let url = self.directory.new_order.clone();
+        let identifiers: Vec<Value> = domains
+            .iter()
+            .map(|domain| json!({ "type": "dns", "value": domain }))
+            .collect();
+        let response = self
+            .post(&url, Some(&json!({ "identifiers": identifiers })))
+            .await?;
+        let order_url = response.location.clone().ok_or_else(|| {
+            Error::External("ACME server created the order without a Location".to_string())
+        })?;
+        Ok((order_url, response.json(&url)?))
+    }
+
+    /// Complete one authorization with the configured challenge.
+    async fn authorize(
+        &mut self,
+        url: &str,
+        kind: AcmeChallenge,
+        challenges: &AcmeChallenges,
+    ) -> Result<()> {
+        let authorization: Authorization = self.post(url, None).await?.json(url)?;
+        if authorization.status == "valid" {
+            return Ok(());
+        }
+
+        let domain = authorization.identifier.value;
+        let challenge = authorization
+            .challenges
+            .iter()
+            .find(|challenge| challenge.kind == kind.as_str())
+            .ok_or_else(|| {
+                Error::External(format!(
+                    "ACME server offers no {kind} challenge for '{domain}'",
+                    kind = kind.as_str()
+                ))
+            })?;
+
+        let key_authorization = self.key.key_authorization(&challenge.token);
+        let _published = challenges.publish(kind, &domain, &challenge.token, &key_authorization)?;
+        self.post(&challenge.url, Some(&json!({}))).await?;
+
+        let authorization: Authorization = self.poll(url, &["pending"]).await?;
+        if authorization.status == "valid" {
+            return Ok(());
+        }
+        let detail = authorization
+            .challenges
+            .iter()
+            .find(|challenge| challenge.kind == kind.as_str())
+            .and_then(|challenge| challenge.error.as_ref())
+            .map(|problem| format!(": {problem}"))
+            .unwrap_or_default();
+        Err(Error::External(format!(
+            "ACME {kind} validation of '{domain}' failed (status '{status}'){detail}",
+            kind = kind.as_str(),
+            status = authorization.status
+        )))
+    }
+
+    /// Fetch `url` until its status leaves `pending`.
+    async fn poll<T: DeserializeOwned + HasStatus>(
+        &mut self,
+        url: &str,
+        pending: &[&str],
+    ) -> Result<T> {
+        for _ in 0..POLL_ATTEMPTS {
+            let response = self.post(url, None).await?;
+            let object: T = response.json(url)?;
+            if !pending.contains(&object.status()) {
+                return Ok(object);
+            }
+            let wait = response
+                .retry_after
+                .unwrap_or(POLL_INTERVAL)
+                .min(POLL_INTERVAL_MAX);
+            tokio::time::sleep(wait).await;
+        }
+        Err(Error::External(format!(
+            "ACME server left {url} pending after {POLL_ATTEMPTS} polls"
+        )))
+    }
+
+    /// A signed POST, or a POST-as-GET when `payload` is `None`.
+    ///
+    /// A `badNonce` rejection is retried once with a fresh nonce, as RFC 8555
+    /// §6.5 expects clients to.
+    async fn post(&mut self, url: &str, payload: Option<&Value>) -> Result<AcmeResponse> {
+        let payload = payload
+            .map(|payload| URL_SAFE_NO_PAD.encode(payload.to_string()))
+            .unwrap_or_default();
+        let mut retried = false;
+        loop {
+            let nonce = match self.nonce.take() {
+                Some(nonce) => nonce,
+                None => self.fetch_nonce().await?,
+            };
+            let mut protected = json!({ "alg": "ES256", "nonce": nonce, "url": url });
+            match self.account_url {
+                Some(ref kid) => protected["kid"] = json!(kid),
+                None => protected["jwk"] = self.key.jwk.clone(),
+            }
+            let body = self.key.sign(&protected, &payload);
+
+            let response = self
+                .http
+                .post(url)
+                .header(header::CONTENT_TYPE, "application/jose+json")
+                .body(body.to_string())
+                .send()
+                .await
+                .map_err(|e| unreachable_server(url, e))?;
+
+            self.nonce = header_value(&response, "replay-nonce");
+            let status = response.status();
+            let location = header_value(&response, header::LOCATION.as_str());
+            let retry_after = header_value(&response, header::RETRY_AFTER.as_str())
+                .and_then(|value| value.parse().ok())
+                .map(Duration::from_secs);
+            let body = response
+                .text()
+                .await
+                .map_err(|e| unreachable_server(url, e))?;
+
+            if status.is_success() {
+                return Ok(AcmeResponse {
+                    location,
+                    retry_after,
+                    body,
+                });
+            }
+
+            let problem: Problem = serde_json::from_str(&body).unwrap_or_default();
+            if problem.kind == "urn:ietf:params:acme:error:badNonce" && !retried {
+                retried = true;
+                continue;
+            }
+            return Err(Error::External(format!(
+                "ACME server rejected the request to {url} with {status}: {problem}"
+            )));
+        }
+    }
+
+    async fn fetch_nonce(&mut self) -> Result<String> {
+        let url = &self.directory.new_nonce;
+        let response = self
+            .http
+            .head(url)
+            .send()
+            .await
+            .map_err(|e| unreachable_server(url, e))?;
+        header_value(&response, "replay-nonce")
+            .ok_or_else(|| Error::External(format!("ACME server sent no Replay-Nonce from {url}")))
+    }
+}
+
+fn header_value(response: &reqwest::Response, name: &str) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+}
+
+fn unreachable_server(url: &str, e: reqwest::Error) -> Error {
+    Error::External(format!("ACME request to {url} failed: {e}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use p256::ecdsa::signature::Verifier;
+    use std::path::PathBuf;
+
+    fn acme_config(dir: &Path) -> AcmeConfig {
+        AcmeConfig {
+            directory_url: "https://acme.test/directory".to_string(),
+            domains: vec!["api.example.test".to_string()],
+            contact: Vec::new(),
+            accept_terms_of_service: true,
+            account_key_path: dir.join("account.pem"),
+            challenge: AcmeChallenge::TlsAlpn01,
+            renew_before_days: None,
+            directory_ca_path: None,
+        }
+    }
+
+    fn tls_config(dir: &Path, acme: AcmeConfig) -> TlsConfig {
+        serde_json::from_value(json!({
+            "cert_path": dir.join("cert.pem"),
+            "key_path": dir.join("key.pem"),
+        }))
+        .map(|config: TlsConfig| TlsConfig {
+            acme: Some(acme),
+            ..config
+        })
+        .expect("tls config")
+    }
+
+    fn chain_of(pem: &str) -> Vec<CertificateDer<'static>> {
+        use rustls_pki_types::pem::PemObject;
+        CertificateDer::pem_slice_iter(pem.as_bytes())
+            .collect::<std::result::Result<_, _>>()
+            .expect("pem chain")
+    }
+
+    fn certificate_for(domains: &[&str]) -> Vec<CertificateDer<'static>> {
+        let key = rcgen::KeyPair::generate().expect("key");
+        let params = rcgen::CertificateParams::new(
+            domains.iter().map(|d| d.to_string()).collect::<Vec<_>>(),
+        )
+        .expect("params");
+        vec![params.self_signed(&key).expect("cert").der().clone()]
+    }
+
+    #[test]
+    fn the_placeholder_is_due_for_renewal_immediately() {
+        let placeholder =
+            placeholder_certificate(&["api.example.test".to_string()]).expect("placeholder");
+        let reason = renewal_reason(
+            Some(&chain_of(&placeholder.cert_pem)),
+            &["api.example.test".to_string()],
+            renew_before(None),
+            Utc::now(),
+        );
+        assert!(
+            reason.is_some_and(|r| r.contains("expires")),
+            "a two-day placeholder must sit inside the default window"
+        );
+    }
+
+    #[test]
+    fn a_long_lived_certificate_covering_every_domain_is_not_due() {
+        // rcgen's default validity runs for decades.
+        let chain = certificate_for(&["api.example.test", "www.example.test"]);
+        let reason = renewal_reason(
+            Some(&chain),
+            &[
+                "API.example.test".to_string(),
+                "www.example.test".to_string(),
+            ],
+            renew_before(None),
+            Utc::now(),
+        );
+        assert_eq!(reason, None);
+    }
+
+    #[test]
+    fn a_certificate_missing_a_configured_domain_is_due() {
+        let chain = certificate_for(&["api.example.test"]);
+        let reason = renewal_reason(
+            Some(&chain),
+            &[
+                "api.example.test".to_string(),
+                "new.example.test".to_string(),
+            ],
+            renew_before(None),
+            Utc::now(),
+        )
+        .expect("due");
+        assert!(reason.contains("new.example.test"), "{reason}");
+    }
+
+    #[test]
+    fn no_certificate_is_due() {
+        assert!(renewal_reason(None, &[], renew_before(None), Utc::now()).is_some());
+    }
+
+    #[test]
+    fn prepare_writes_a_placeholder_only_when_nothing_is_on_disk() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let config = tls_config(dir.path(), acme_config(dir.path()));
+
+        prepare(&config).expect("prepare");
+        let written = std::fs::read(&config.cert_path).expect("placeholder cert");
+        assert!(config.key_path.exists(), "placeholder key");
+        crate::tls::load_server_config(&config).expect("the placeholder must load");
+
+        prepare(&config).expect("prepare again");
+        assert_eq!(
+            std::fs::read(&config.cert_path).expect("cert"),
+            written,
+            "an existing certificate must be left alone"
+        );
+    }
+
+    /// Breaks an otherwise issuable configuration
+    type Mutation = fn(&mut TlsConfig);
+
+    #[test]
+    fn prepare_refuses_configurations_that_cannot_issue() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let cases: [(&str, Mutation); 6] = [
+            ("https", |c| {
+                c.acme.as_mut().unwrap().directory_url = "http://acme.test".into()
+            }),
+            ("no domains", |c| c.acme.as_mut().unwrap().domains.clear()),
+            ("wildcard", |c| {
+                c.acme.as_mut().unwrap().domains = vec!["*.example.test".into()]
+            }),
+            ("accept_terms_of_service", |c| {
+                c.acme.as_mut().unwrap().accept_terms_of_service = false
+            }),
+            ("renew_before_days = 0", |c| {
+                c.acme.as_mut().unwrap().renew_before_days = Some(0)
+            }),
+            ("client certificates", |c| {
+                c.client_ca_path = Some(PathBuf::from("/ca.pem"))
+            }),
+        ];
+
+        for (expected, mutate) in cases {
+            let mut config = tls_config(dir.path(), acme_config(dir.path()));
+            mutate(&mut config);
+            let err = prepare(&config).expect_err(expected).to_string();
+            assert!(err.contains(expected), "{expected}: {err}");
+            assert!(!config.cert_path.exists(), "{expected}: nothing written");
+        }
+    }
+
+    #[test]
+    fn the_account_key_is_generated_once_and_reused() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = dir.path().join("acme").join("account.pem");
+
+        let first = AccountKey::load_or_create(&path).expect("generate");
+        let second = AccountKey::load_or_create(&path).expect("reload");
+        assert_eq!(first.thumbprint, second.thumbprint);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path)
+                .expect("metadata")
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600, "the account key must be owner-only");
+        }
+    }
+
+    #[test]
+    fn requests_are_signed_es256_over_protected_and_payload() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let key = AccountKey::load_or_create(&dir.path().join("account.pem")).expect("key");
+
+        let payload = URL_SAFE_NO_PAD.encode(r#"{"identifiers":[]}"#);
+        let jws = key.sign(
+            &json!({ "alg": "ES256", "url": "https://acme.test/x" }),
+            &payload,
+        );
+
+        let signing_input = format!(
+            "{}.{}",
+            jws["protected"].as_str().expect("protected"),
+            jws["payload"].as_str().expect("payload")
+        );
+        let signature_bytes = URL_SAFE_NO_PAD
+            .decode(jws["signature"].as_str().expect("signature"))
+            .expect("base64url");
+        assert_eq!(signature_bytes.len(), 64, "ES256 signatures are r || s");
+        let signature = Signature::from_slice(&signature_bytes).expect("signature");
+        key.signing_key
+            .verifying_key()
+            .verify(signing_input.as_bytes(), &signature)
+            .expect("the signature must verify against the account key");
+    }
+
+    #[test]
+    fn key_authorization_is_the_token_and_the_jwk_thumbprint() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let key = AccountKey::load_or_create(&dir.path().join("account.pem")).expect("key");
+
+        let canonical = format!(
+            r#"{{"crv":"P-256","kty":"EC","x":"{}","y":"{}"}}"#,
+            key.jwk["x"].as_str().expect("x"),
+            key.jwk["y"].as_str().expect("y")
+        );
+        let thumbprint = URL_SAFE_NO_PAD.encode(Sha256::digest(canonical.as_bytes()));
+        assert_eq!(key.key_authorization("tok"), format!("tok.{thumbprint}"));
+    }
+
+    #[test]
+    fn the_challenge_certificate_carries_the_key_authorization_digest() {
+        use x509_parser::prelude::{FromDer, X509Certificate};
+
+        let certified = challenge_certificate("api.example.test", "tok.thumb").expect("cert");
+        let (_, cert) = X509Certificate::from_der(certified.cert[0].as_ref()).expect("parse");
+
+        let extension = cert
+            .extensions()
+            .iter()
+            .find(|ext| ext.oid.to_id_string() == "1.3.6.1.5.5.7.1.31")
+            .expect("acmeIdentifier extension");
+        assert!(extension.critical, "RFC 8737 requires it to be critical");
+        // The extension value is a DER OCTET STRING wrapping the digest.
+        let digest = Sha256::digest(b"tok.thumb");
+        assert_eq!(&extension.value[2..], digest.as_slice());
+    }
+
+    #[test]
+    fn published_challenges_are_withdrawn_when_dropped() {
+        let challenges = AcmeChallenges::default();
+        {
+            let _published = challenges
+                .publish(
+                    AcmeChallenge::Http01,
+                    "api.example.test",
+                    "tok",
+                    "tok.thumb",
+                )
+                .expect("publish");
+            assert_eq!(
+                challenges.http01_response("tok").as_deref(),
+                Some("tok.thumb")
+            );
+        }
+        assert_eq!(challenges.http01_response("tok"), None);
+
+        {
+            let _published = challenges
+                .publish(
+                    AcmeChallenge::TlsAlpn01,
+                    "API.example.test",
+                    "tok",
+                    "tok.thumb",
+                )
+                .expect("publish");
+            assert!(challenges
+                .tls_alpn01_certificate("api.example.test")
+                .is_some());
+        }
+        assert!(challenges
+            .tls_alpn01_certificate("api.example.test")
+            .is_none());
+    }
+}
@@ -565,11 +565,17 @@ pub struct NatsPoolState {
     pub last_error: Option<String>,
     /// Callers parked in `WaitForPoolReady` until the first attempt settles
     pub(crate) ready_waiters: Vec<OutboundEnvelope>,
+    /// Handlers for the JetStream consumers declared in the config
+    pub(crate) event_handlers: crate::events::EventHandlers,
+    /// Consumers started once the client connected, drained on shutdown
+    pub(crate) consumers: Option<crate::events::ConsumerSupervisor>,
 }
 
 /// Agent-based NATS client manager
 ///
 /// Manages a NATS client connection with automatic connection and graceful shutdown.
+/// When handlers are registered, it also runs the configured JetStream consumers
+/// and drains them before the connection is closed.
 #[cfg(feature = "events")]
 pub struct NatsPoolAgent;
 
@@ -582,10 +588,12 @@ impl NatsPoolAgent {
     /// * `runtime` - The agent runtime to spawn into
     /// * `config` - NATS connection configuration
     /// * `shared_client` - Shared storage that will be updated when the client connects.
-    pub async fn spawn(
+    /// * `event_handlers` - Handlers for the consumers in `config.consumers`
+    pub(crate) async fn spawn(
         runtime: &mut ActorRuntime,
         config: crate::config::NatsConfig,
         shared_client: Option<SharedNatsClient>,
+        event_handlers: crate::events::EventHandlers,
     ) -> anyhow::Result<ActorHandle> {
         let mut agent = runtime.new_actor::<NatsPoolState>();
 
@@ -593,6 +601,7 @@ impl NatsPoolAgent {
         agent.model.config = Some(config);
         agent.model.connecting = true;
         agent.model.shared_client = shared_client;
+        agent.model.event_handlers = event_handlers;
 
         // Handle client connected message
         agent.mutate_on::<NatsClientConnected>(|agent, envelope| {
@@ -600,6 +609,18 @@ impl NatsPoolAgent {
             agent.model.client = Some(client.clone());
             agent.model.connecting = false;
             agent.model.last_error = None;
+
+            // Consumers start only once there is a connection to bind them to.
+            if !agent.model.event_handlers.is_empty() && agent.model.consumers.is_none() {
+                if let Some(cfg) = agent.model.config.as_ref() {
+                    agent.model.consumers = Some(crate::events::ConsumerSupervisor::start(
+                        client.clone(),
+                        cfg,
+                        &agent.model.event_handlers,
+                    ));
+                }
+            }
+
             // Everyone parked in WaitForPoolReady is released here, in
             // the same handler that records the success.
             let health = pool_health("events", true, false, None);
@@ -714,12 +735,29 @@ impl NatsPoolAgent {
             })
         });
 
-        // Close client on shutdown
+        // Drain consumers, then close the client on shutdown
         agent.before_stop(|agent| {
             let client = agent.model.client.clone();
+            let consumers = agent.model.consumers.clone();
+            let drain_timeout = std::time::Duration::from_secs(
+                agent
+                    .model
+                    .config
+                    .as_ref()
+                    .map_or(30, |cfg| cfg.drain_timeout_secs),
+            );
             Reply::pending(async move {
+                // Handlers still running need the connection to ack, so the
+                // consumers go first.
+                if let Some(consumers) = consumers {
+                    tracing::info!("Draining JetStream consumers...");
+                    consumers.drain(drain_timeout).await;
+                }
                 if let Some(c) = client {
                     tracing::info!("NATS pool agent stopping, closing connection...");
+                    if let Err(e) = c.flush().await {
+                        tracing::warn!("Failed to flush NATS client: {}", e);
+                    }
                     drop(c);
                     tracing::info!("NATS client closed");
                 }
@@ -8,8 +8,7 @@ use acton_reactive::prelude::*;
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use super::alert::AuditAlertHook;
-use super::alert_webhook::WebhookAlertHook;
+use super::alert_webhook::hooks_from_config;
 use super::chain::AuditChain;
 use super::config::AuditConfig;
 use super::event::AuditEvent;
@@ -117,16 +116,8 @@ impl AuditAgent {
         // Set up failure tracker if alert hooks are configured
         let failure_tracker = if let Some(ref alert_config) = config.alerts {
             if alert_config.enabled {
-                let mut hooks: Vec<Arc<dyn AuditAlertHook>> = Vec::new();
-                for wh in &alert_config.webhooks {
-                    hooks.push(Arc::new(WebhookAlertHook::new(
-                        wh.url.clone(),
-                        std::time::Duration::from_secs(wh.timeout_secs),
-                        wh.headers.clone(),
-                    )));
-                }
                 Some(Arc::new(FailureTracker::new(
-                    hooks,
+                    hooks_from_config(alert_config),
                     alert_config.threshold_secs,
                     alert_config.cooldown_secs,
                     alert_config.notify_recovery,
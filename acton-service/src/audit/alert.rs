@@ -1,7 +1,8 @@
 //! Audit alert hooks
 //!
-//! Provides a trait for receiving audit storage failure alerts (storage
-//! unreachable, storage recovered). Notifications are dispatched via
+//! Provides a trait for receiving audit alerts: audit storage failures
+//! (storage unreachable, storage recovered) and, with the `tls` feature,
+//! certificates nearing or past expiry. Notifications are dispatched via
 //! `tokio::spawn` so they never block audit event processing.
 
 use async_trait::async_trait;
@@ -41,11 +42,45 @@ pub enum AuditAlertEvent {
         /// Name of the service that recovered
         service_name: String,
     },
+    /// A monitored TLS certificate has entered its expiry warning window
+    /// (requires `tls` feature)
+    #[cfg(feature = "tls")]
+    CertificateExpiring {
+        /// What the certificate is for (`server_certificate`, `client_ca`,
+        /// `client_identity`)
+        role: String,
+        /// The listener or client identity the certificate belongs to
+        owner: String,
+        /// The file the certificate was read from
+        path: String,
+        /// When the certificate expires
+        not_after: DateTime<Utc>,
+        /// Whole days left before it expires
+        days_remaining: i64,
+        /// Name of the service presenting or trusting the certificate
+        service_name: String,
+    },
+    /// A monitored TLS certificate has expired (requires `tls` feature)
+    #[cfg(feature = "tls")]
+    CertificateExpired {
+        /// What the certificate is for (`server_certificate`, `client_ca`,
+        /// `client_identity`)
+        role: String,
+        /// The listener or client identity the certificate belongs to
+        owner: String,
+        /// The file the certificate was read from
+        path: String,
+        /// When the certificate expired
+        not_after: DateTime<Utc>,
+        /// Name of the service presenting or trusting the certificate
+        service_name: String,
+    },
 }
 
-/// Trait for receiving audit storage failure alerts
+/// Trait for receiving audit alerts
 ///
-/// Implement this trait to react to storage health changes (e.g., send
+/// Implement this trait to react to storage health changes and certificate
+/// expiry (e.g., send
 /// webhook notifications, page on-call, emit metrics). Handlers are
 /// invoked asynchronously and must not panic.
 ///
@@ -67,7 +102,8 @@ pub enum AuditAlertEvent {
 /// ```
 #[async_trait]
 pub trait AuditAlertHook: Send + Sync + 'static {
-    /// Called when audit storage health changes
+    /// Called when audit storage health changes or a monitored certificate
+    /// nears or passes expiry
     ///
     /// This method is invoked inside `tokio::spawn`, so it will not
     /// block audit event processing. Implementations should handle their
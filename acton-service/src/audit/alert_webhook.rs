@@ -6,9 +6,11 @@
 
 use async_trait::async_trait;
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 
 use super::alert::{AuditAlertEvent, AuditAlertHook};
+use super::config::AlertConfig;
 
 /// Webhook alert hook that POSTs audit alert events as JSON
 pub struct WebhookAlertHook {
@@ -38,6 +40,28 @@ impl WebhookAlertHook {
     }
 }
 
+/// Build one [`WebhookAlertHook`] per webhook in `[audit.alerts]`.
+///
+/// Empty when alerts are disabled. Shared by the audit agent's storage-failure
+/// tracker and the certificate expiry monitor, so both notify the same
+/// endpoints.
+pub(crate) fn hooks_from_config(alert_config: &AlertConfig) -> Vec<Arc<dyn AuditAlertHook>> {
+    if !alert_config.enabled {
+        return Vec::new();
+    }
+    alert_config
+        .webhooks
+        .iter()
+        .map(|wh| {
+            Arc::new(WebhookAlertHook::new(
+                wh.url.clone(),
+                Duration::from_secs(wh.timeout_secs),
+                wh.headers.clone(),
+            )) as Arc<dyn AuditAlertHook>
+        })
+        .collect()
+}
+
 #[async_trait]
 impl AuditAlertHook for WebhookAlertHook {
     async fn on_alert(&self, event: AuditAlertEvent) {
@@ -21,9 +21,11 @@
 use async_trait::async_trait;
 use chrono::{DateTime, Utc};
 
-use crate::auth::api_keys::{ApiKey, ApiKeyStorage};
+use crate::auth::api_keys::{ApiKey, ApiKeyStorage, ApiKeyUsage};
 #[cfg(feature = "oauth")]
 use crate::auth::oauth::provider::{OAuthProvider, OAuthTokens, OAuthUserInfo};
+#[cfg(feature = "oauth")]
+use crate::auth::oauth::state::StateData;
 use crate::auth::tokens::refresh::{RefreshTokenData, RefreshTokenMetadata, RefreshTokenStorage};
 use crate::error::Error;
 
@@ -227,11 +229,23 @@ impl<S: ApiKeyStorage> ApiKeyStorage for AuditedApiKeyStorage<S> {
     async fn delete(&self, id: &str) -> Result<(), Error> {
         self.inner.delete(id).await
     }
+
+    async fn record_usage(&self, id: &str) -> Result<ApiKeyUsage, Error> {
+        self.inner.record_usage(id).await
+    }
+
+    async fn get_usage(&self, id: &str) -> Result<ApiKeyUsage, Error> {
+        self.inner.get_usage(id).await
+    }
 }
 
 /// [`OAuthProvider`] wrapper that emits `AuthOAuthCallback` when an
 /// authorization code is exchanged — the framework-visible moment of the
 /// OAuth callback — on both success and failure.
+///
+/// `complete_login` delegates to the inner provider's own implementation, so
+/// PKCE and ID-token verification in providers such as
+/// [`OidcProvider`](crate::auth::oauth::OidcProvider) still apply when wrapped.
 #[cfg(feature = "oauth")]
 pub struct AuditedOAuthProvider<P> {
     inner: P,
@@ -244,6 +258,34 @@ impl<P: OAuthProvider> AuditedOAuthProvider<P> {
     pub fn new(inner: P, logger: AuditLogger) -> Self {
         Self { inner, logger }
     }
+
+    async fn emit_callback(&self, subject: Option<String>, error: Option<&Error>) {
+        let (severity, metadata) = match error {
+            None => (
+                AuditSeverity::Notice,
+                serde_json::json!({
+                    "provider": self.inner.name(),
+                    "outcome": "success",
+                }),
+            ),
+            Some(e) => (
+                AuditSeverity::Warning,
+                serde_json::json!({
+                    "provider": self.inner.name(),
+                    "outcome": "failure",
+                    "error": e.to_string(),
+                }),
+            ),
+        };
+        emit(
+            &self.logger,
+            AuditEventKind::AuthOAuthCallback,
+            severity,
+            subject_source(subject),
+            metadata,
+        )
+        .await;
+    }
 }
 
 #[cfg(feature = "oauth")]
@@ -257,38 +299,30 @@ impl<P: OAuthProvider> OAuthProvider for AuditedOAuthProvider<P> {
         self.inner.authorization_url(state, scopes)
     }
 
+    fn authorization_url_for(&self, state: &str, data: &StateData, scopes: &[String]) -> String {
+        self.inner.authorization_url_for(state, data, scopes)
+    }
+
     async fn exchange_code(&self, code: &str) -> Result<OAuthTokens, Error> {
-        match self.inner.exchange_code(code).await {
-            Ok(tokens) => {
-                emit(
-                    &self.logger,
-                    AuditEventKind::AuthOAuthCallback,
-                    AuditSeverity::Notice,
-                    subject_source(None),
-                    serde_json::json!({
-                        "provider": self.inner.name(),
-                        "outcome": "success",
-                    }),
-                )
-                .await;
-                Ok(tokens)
-            }
-            Err(e) => {
-                emit(
-                    &self.logger,
-                    AuditEventKind::AuthOAuthCallback,
-                    AuditSeverity::Warning,
-                    subject_source(None),
-                    serde_json::json!({
-                        "provider": self.inner.name(),
-                        "outcome": "failure",
-                        "error": e.to_string(),
-                    }),
-                )
-                .await;
-                Err(e)
+        let result = self.inner.exchange_code(code).await;
+        self.emit_callback(None, result.as_ref().err()).await;
+        result
+    }
+
+    async fn complete_login(
+        &self,
+        code: &str,
+        data: &StateData,
+    ) -> Result<(OAuthTokens, OAuthUserInfo), Error> {
+        let result = self.inner.complete_login(code, data).await;
+        match &result {
+            Ok((_, user)) => {
+                self.emit_callback(Some(user.provider_user_id.clone()), None)
+                    .await
             }
+            Err(e) => self.emit_callback(None, Some(e)).await,
         }
+        result
     }
 
     async fn get_user_info(&self, access_token: &str) -> Result<OAuthUserInfo, Error> {
@@ -606,6 +640,8 @@ mod tests {
             key_hash: "not-logged".to_string(),
             scopes: vec!["deploy".to_string()],
             rate_limit: None,
+            daily_quota: None,
+            monthly_quota: None,
             is_revoked: false,
             last_used_at: None,
             expires_at: None,
@@ -695,6 +731,7 @@ mod tests {
     mod oauth_tests {
         use super::*;
         use crate::auth::oauth::provider::{OAuthProvider, OAuthTokens, OAuthUserInfo};
+        use crate::auth::oauth::state::StateData;
 
         struct FailingProvider;
 
@@ -737,5 +774,94 @@ mod tests {
             assert_eq!(metadata["provider"], "test-provider");
             assert_eq!(metadata["outcome"], "failure");
         }
+
+        /// Only completes logins through its own `complete_login`, which
+        /// insists on a PKCE verifier; the default path always fails.
+        struct PkceProvider;
+
+        #[async_trait]
+        impl OAuthProvider for PkceProvider {
+            fn name(&self) -> &str {
+                "pkce-provider"
+            }
+
+            fn authorization_url(&self, _state: &str, _scopes: &[String]) -> String {
+                "https://example.com/auth".to_string()
+            }
+
+            fn authorization_url_for(
+                &self,
+                _state: &str,
+                data: &StateData,
+                _scopes: &[String],
+            ) -> String {
+                format!(
+                    "https://example.com/auth?challenge={}",
+                    data.pkce_verifier.as_deref().unwrap_or_default()
+                )
+            }
+
+            async fn exchange_code(&self, _code: &str) -> Result<OAuthTokens, Error> {
+                Err(Error::Internal("default path used".to_string()))
+            }
+
+            async fn complete_login(
+                &self,
+                _code: &str,
+                data: &StateData,
+            ) -> Result<(OAuthTokens, OAuthUserInfo), Error> {
+                if data.pkce_verifier.is_none() {
+                    return Err(Error::BadRequest("missing verifier".to_string()));
+                }
+                Ok((
+                    OAuthTokens {
+                        access_token: "at".to_string(),
+                        refresh_token: None,
+                        expires_in: None,
+                        token_type: "Bearer".to_string(),
+                        id_token: None,
+                    },
+                    OAuthUserInfo {
+                        provider: "pkce-provider".to_string(),
+                        provider_user_id: "sub-123".to_string(),
+                        email: None,
+                        email_verified: false,
+                        name: None,
+                        picture: None,
+                        raw: serde_json::Value::Null,
+                    },
+                ))
+            }
+
+            async fn get_user_info(&self, _access_token: &str) -> Result<OAuthUserInfo, Error> {
+                Err(Error::Internal("unused".to_string()))
+            }
+
+            async fn refresh_token(&self, _refresh_token: &str) -> Result<OAuthTokens, Error> {
+                Err(Error::Internal("unused".to_string()))
+            }
+        }
+
+        #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+        async fn complete_login_delegates_to_inner_provider() {
+            let (_runtime, storage, logger) = capturing_logger().await;
+            let wrapped = AuditedOAuthProvider::new(PkceProvider, logger);
+            let data = StateData::new("pkce-provider").with_pkce();
+
+            let url = wrapped.authorization_url_for("s", &data, &[]);
+            assert!(url.ends_with(data.pkce_verifier.as_deref().unwrap()));
+
+            let (_, user) = wrapped
+                .complete_login("code", &data)
+                .await
+                .expect("inner complete_login used");
+            assert_eq!(user.provider_user_id, "sub-123");
+
+            let event = wait_for_event(&storage, &AuditEventKind::AuthOAuthCallback)
+                .await
+                .expect("AuthOAuthCallback emitted on success");
+            assert_eq!(event.severity, AuditSeverity::Notice);
+            assert_eq!(event.source.subject.as_deref(), Some("sub-123"));
+        }
     }
 }
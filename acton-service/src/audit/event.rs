@@ -124,6 +124,8 @@ pub enum AuditEventKind {
     AuthApiKeyCreated,
     /// API key revoked
     AuthApiKeyRevoked,
+    /// OAuth login started: user redirected to the provider
+    AuthOAuthAuthorize,
     /// OAuth callback processed
     AuthOAuthCallback,
     /// Permission denied
@@ -158,6 +160,32 @@ pub enum AuditEventKind {
     /// Account updated (profile, email verified, password changed, roles) (requires `accounts` feature)
     #[cfg(feature = "accounts")]
     AccountUpdated,
+    /// TOTP MFA enrolled for an account (requires `accounts` feature)
+    #[cfg(feature = "accounts")]
+    AccountMfaEnrolled,
+    /// TOTP MFA removed from an account (requires `accounts` feature)
+    #[cfg(feature = "accounts")]
+    AccountMfaDisabled,
+    /// Second factor accepted at login (requires `accounts` feature)
+    #[cfg(feature = "accounts")]
+    AccountMfaVerified,
+    /// Second factor rejected at login (requires `accounts` feature)
+    #[cfg(feature = "accounts")]
+    AccountMfaFailed,
+    /// Passkey registered to an account (requires `passkeys` feature)
+    #[cfg(feature = "passkeys")]
+    AccountPasskeyRegistered,
+    /// Passkey removed from an account (requires `passkeys` feature)
+    #[cfg(feature = "passkeys")]
+    AccountPasskeyRemoved,
+    /// Passkey signature counter went backwards; possible cloned authenticator
+    /// (requires `passkeys` feature)
+    #[cfg(feature = "passkeys")]
+    AccountPasskeySignCountRegressed,
+    /// TLS handshake rejected because the client certificate is on a configured
+    /// CRL (requires `tls` feature)
+    #[cfg(feature = "tls")]
+    TlsClientCertRevoked,
     /// Signing key was rotated (new key activated, old key moved to draining)
     AuthKeyRotated,
     /// Signing key was retired (drain period expired)
@@ -189,6 +217,7 @@ impl std::fmt::Display for AuditEventKind {
             Self::AuthPasswordChanged => write!(f, "auth.password.changed"),
             Self::AuthApiKeyCreated => write!(f, "auth.apikey.created"),
             Self::AuthApiKeyRevoked => write!(f, "auth.apikey.revoked"),
+            Self::AuthOAuthAuthorize => write!(f, "auth.oauth.authorize"),
             Self::AuthOAuthCallback => write!(f, "auth.oauth.callback"),
             Self::AuthPermissionDenied => write!(f, "auth.permission.denied"),
             #[cfg(feature = "login-lockout")]
@@ -211,6 +240,24 @@ impl std::fmt::Display for AuditEventKind {
             Self::AccountDeleted => write!(f, "account.deleted"),
             #[cfg(feature = "accounts")]
             Self::AccountUpdated => write!(f, "account.updated"),
+            #[cfg(feature = "accounts")]
+            Self::AccountMfaEnrolled => write!(f, "account.mfa.enrolled"),
+            #[cfg(feature = "accounts")]
+            Self::AccountMfaDisabled => write!(f, "account.mfa.disabled"),
+            #[cfg(feature = "accounts")]
+            Self::AccountMfaVerified => write!(f, "account.mfa.verified"),
+            #[cfg(feature = "accounts")]
+            Self::AccountMfaFailed => write!(f, "account.mfa.failed"),
+            #[cfg(feature = "passkeys")]
+            Self::AccountPasskeyRegistered => write!(f, "account.passkey.registered"),
+            #[cfg(feature = "passkeys")]
+            Self::AccountPasskeyRemoved => write!(f, "account.passkey.removed"),
+            #[cfg(feature = "passkeys")]
+            Self::AccountPasskeySignCountRegressed => {
+                write!(f, "account.passkey.sign_count_regressed")
+            }
+            #[cfg(feature = "tls")]
+            Self::TlsClientCertRevoked => write!(f, "tls.client_cert.revoked"),
             Self::AuthKeyRotated => write!(f, "auth.key.rotated"),
             Self::AuthKeyRetired => write!(f, "auth.key.retired"),
             Self::AuthKeyRotationFailed => write!(f, "auth.key.rotation_failed"),
@@ -249,6 +296,7 @@ impl AuditEventKind {
             "auth.password.changed" => Some(Self::AuthPasswordChanged),
             "auth.apikey.created" => Some(Self::AuthApiKeyCreated),
             "auth.apikey.revoked" => Some(Self::AuthApiKeyRevoked),
+            "auth.oauth.authorize" => Some(Self::AuthOAuthAuthorize),
             "auth.oauth.callback" => Some(Self::AuthOAuthCallback),
             "auth.permission.denied" => Some(Self::AuthPermissionDenied),
             #[cfg(feature = "login-lockout")]
@@ -271,6 +319,22 @@ impl AuditEventKind {
             "account.deleted" => Some(Self::AccountDeleted),
             #[cfg(feature = "accounts")]
             "account.updated" => Some(Self::AccountUpdated),
+            #[cfg(feature = "accounts")]
+            "account.mfa.enrolled" => Some(Self::AccountMfaEnrolled),
+            #[cfg(feature = "accounts")]
+            "account.mfa.disabled" => Some(Self::AccountMfaDisabled),
+            #[cfg(feature = "accounts")]
+            "account.mfa.verified" => Some(Self::AccountMfaVerified),
+            #[cfg(feature = "accounts")]
+            "account.mfa.failed" => Some(Self::AccountMfaFailed),
+            #[cfg(feature = "passkeys")]
+            "account.passkey.registered" => Some(Self::AccountPasskeyRegistered),
+            #[cfg(feature = "passkeys")]
+            "account.passkey.removed" => Some(Self::AccountPasskeyRemoved),
+            #[cfg(feature = "passkeys")]
+            "account.passkey.sign_count_regressed" => Some(Self::AccountPasskeySignCountRegressed),
+            #[cfg(feature = "tls")]
+            "tls.client_cert.revoked" => Some(Self::TlsClientCertRevoked),
             "auth.key.rotated" => Some(Self::AuthKeyRotated),
             "auth.key.retired" => Some(Self::AuthKeyRetired),
             "auth.key.rotation_failed" => Some(Self::AuthKeyRotationFailed),
@@ -399,6 +463,7 @@ mod tests {
             AuditEventKind::AuthPasswordChanged,
             AuditEventKind::AuthApiKeyCreated,
             AuditEventKind::AuthApiKeyRevoked,
+            AuditEventKind::AuthOAuthAuthorize,
             AuditEventKind::AuthOAuthCallback,
             AuditEventKind::AuthPermissionDenied,
             AuditEventKind::AuthKeyRotated,
@@ -434,9 +499,29 @@ mod tests {
                 AuditEventKind::AccountExpired,
                 AuditEventKind::AccountDeleted,
                 AuditEventKind::AccountUpdated,
+                AuditEventKind::AccountMfaEnrolled,
+                AuditEventKind::AccountMfaDisabled,
+                AuditEventKind::AccountMfaVerified,
+                AuditEventKind::AccountMfaFailed,
             ]);
             kinds
         };
+        #[cfg(feature = "passkeys")]
+        let kinds = {
+            let mut kinds = kinds;
+            kinds.extend([
+                AuditEventKind::AccountPasskeyRegistered,
+                AuditEventKind::AccountPasskeyRemoved,
+                AuditEventKind::AccountPasskeySignCountRegressed,
+            ]);
+            kinds
+        };
+        #[cfg(feature = "tls")]
+        let kinds = {
+            let mut kinds = kinds;
+            kinds.push(AuditEventKind::TlsClientCertRevoked);
+            kinds
+        };
 
         for kind in kinds {
             let wire = kind.to_string();
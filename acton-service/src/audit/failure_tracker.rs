@@ -244,6 +244,9 @@ mod tests {
                     self.last_events_affected
                         .store(events_affected, Ordering::SeqCst);
                 }
+                #[cfg(feature = "tls")]
+                AuditAlertEvent::CertificateExpiring { .. }
+                | AuditAlertEvent::CertificateExpired { .. } => {}
             }
         }
     }
@@ -147,6 +147,7 @@ impl From<AuditQueryRow> for AuditEvent {
             "auth.password.changed" => AuditEventKind::AuthPasswordChanged,
             "auth.apikey.created" => AuditEventKind::AuthApiKeyCreated,
             "auth.apikey.revoked" => AuditEventKind::AuthApiKeyRevoked,
+            "auth.oauth.authorize" => AuditEventKind::AuthOAuthAuthorize,
             "auth.oauth.callback" => AuditEventKind::AuthOAuthCallback,
             "auth.permission.denied" => AuditEventKind::AuthPermissionDenied,
             "auth.key.rotated" => AuditEventKind::AuthKeyRotated,
@@ -172,6 +173,16 @@ impl From<AuditQueryRow> for AuditEvent {
             "account.deleted" => AuditEventKind::AccountDeleted,
             #[cfg(feature = "accounts")]
             "account.updated" => AuditEventKind::AccountUpdated,
+            #[cfg(feature = "accounts")]
+            "account.mfa.enrolled" => AuditEventKind::AccountMfaEnrolled,
+            #[cfg(feature = "accounts")]
+            "account.mfa.disabled" => AuditEventKind::AccountMfaDisabled,
+            #[cfg(feature = "accounts")]
+            "account.mfa.verified" => AuditEventKind::AccountMfaVerified,
+            #[cfg(feature = "accounts")]
+            "account.mfa.failed" => AuditEventKind::AccountMfaFailed,
+            #[cfg(feature = "tls")]
+            "tls.client_cert.revoked" => AuditEventKind::TlsClientCertRevoked,
             "config.loaded" => AuditEventKind::ConfigLoaded,
             "config.drift_detected" => AuditEventKind::ConfigDriftDetected,
             "http.request" => AuditEventKind::HttpRequest,
@@ -603,6 +614,7 @@ mod tests {
             ("auth.password.changed", "auth.password.changed"),
             ("auth.apikey.created", "auth.apikey.created"),
             ("auth.apikey.revoked", "auth.apikey.revoked"),
+            ("auth.oauth.authorize", "auth.oauth.authorize"),
             ("auth.oauth.callback", "auth.oauth.callback"),
             ("auth.permission.denied", "auth.permission.denied"),
             ("auth.key.rotated", "auth.key.rotated"),
@@ -633,6 +645,16 @@ mod tests {
             k.push(("account.expired", "account.expired"));
             k.push(("account.deleted", "account.deleted"));
             k.push(("account.updated", "account.updated"));
+            k.push(("account.mfa.enrolled", "account.mfa.enrolled"));
+            k.push(("account.mfa.disabled", "account.mfa.disabled"));
+            k.push(("account.mfa.verified", "account.mfa.verified"));
+            k.push(("account.mfa.failed", "account.mfa.failed"));
+            k
+        };
+        #[cfg(feature = "tls")]
+        let kinds = {
+            let mut k = kinds;
+            k.push(("tls.client_cert.revoked", "tls.client_cert.revoked"));
             k
         };
 
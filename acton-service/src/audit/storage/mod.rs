@@ -37,8 +37,8 @@ pub mod surrealdb_impl;
 pub mod clickhouse_impl;
 
 /// Returns true if the stored event-kind string looks like a framework-owned
-/// kind (`auth.*`, `http.*`, `account.*`, `config.*`) that should have been
-/// recognized by the parser. Used by parser catch-alls to detect likely
+/// kind (`auth.*`, `http.*`, `account.*`, `config.*`, `tls.*`) that should
+/// have been recognized by the parser. Used by parser catch-alls to detect likely
 /// version skew between an emitter and a reader.
 ///
 /// Only compiled alongside a storage backend — nothing parses stored rows without one.
@@ -53,6 +53,7 @@ pub(crate) fn looks_like_framework_kind(s: &str) -> bool {
         || s.starts_with("http.")
         || s.starts_with("account.")
         || s.starts_with("config.")
+        || s.starts_with("tls.")
 }
 
 /// Helper for storage-backend parser catch-alls.
@@ -159,6 +160,7 @@ mod helper_tests {
         assert!(looks_like_framework_kind("http.request.denied"));
         assert!(looks_like_framework_kind("account.created"));
         assert!(looks_like_framework_kind("config.drift_detected"));
+        assert!(looks_like_framework_kind("tls.client_cert.revoked"));
     }
 
     #[test]
@@ -369,6 +369,7 @@ fn parse_event_kind(s: &str) -> AuditEventKind {
         "auth.password.changed" => AuditEventKind::AuthPasswordChanged,
         "auth.apikey.created" => AuditEventKind::AuthApiKeyCreated,
         "auth.apikey.revoked" => AuditEventKind::AuthApiKeyRevoked,
+        "auth.oauth.authorize" => AuditEventKind::AuthOAuthAuthorize,
         "auth.oauth.callback" => AuditEventKind::AuthOAuthCallback,
         "auth.permission.denied" => AuditEventKind::AuthPermissionDenied,
         "auth.key.rotated" => AuditEventKind::AuthKeyRotated,
@@ -394,6 +395,16 @@ fn parse_event_kind(s: &str) -> AuditEventKind {
         "account.deleted" => AuditEventKind::AccountDeleted,
         #[cfg(feature = "accounts")]
         "account.updated" => AuditEventKind::AccountUpdated,
+        #[cfg(feature = "accounts")]
+        "account.mfa.enrolled" => AuditEventKind::AccountMfaEnrolled,
+        #[cfg(feature = "accounts")]
+        "account.mfa.disabled" => AuditEventKind::AccountMfaDisabled,
+        #[cfg(feature = "accounts")]
+        "account.mfa.verified" => AuditEventKind::AccountMfaVerified,
+        #[cfg(feature = "accounts")]
+        "account.mfa.failed" => AuditEventKind::AccountMfaFailed,
+        #[cfg(feature = "tls")]
+        "tls.client_cert.revoked" => AuditEventKind::TlsClientCertRevoked,
         "config.loaded" => AuditEventKind::ConfigLoaded,
         "config.drift_detected" => AuditEventKind::ConfigDriftDetected,
         "http.request" => AuditEventKind::HttpRequest,
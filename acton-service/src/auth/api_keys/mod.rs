@@ -21,11 +21,11 @@
 //! }
 //! ```
 
-#[cfg(any(feature = "database", feature = "turso"))]
-use chrono::DateTime;
-use chrono::Utc;
+use chrono::{DateTime, Datelike, Days, Months, Utc};
+use serde::{Deserialize, Serialize};
 
 use crate::auth::password::PasswordHasher;
+use crate::config::{ApiKeyScopeQuota, RateLimitConfig};
 use crate::error::Error;
 
 // Inner module isolates the `SurrealValue` trait import the derive needs.
@@ -63,6 +63,14 @@ mod model {
         /// Rate limit (requests per minute, None = default)
         pub rate_limit: Option<u32>,
 
+        /// Requests allowed per UTC day (None = scope quota or unlimited)
+        #[serde(default)]
+        pub daily_quota: Option<u64>,
+
+        /// Requests allowed per UTC calendar month (None = scope quota or unlimited)
+        #[serde(default)]
+        pub monthly_quota: Option<u64>,
+
         /// Whether this key has been revoked
         #[serde(default)]
         pub is_revoked: bool,
@@ -102,6 +110,176 @@ impl ApiKey {
     }
 }
 
+/// Request counters for an API key
+///
+/// Counters cover the current UTC day and UTC calendar month.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiKeyUsage {
+    /// Requests counted today
+    pub daily: u64,
+
+    /// Requests counted this month
+    pub monthly: u64,
+}
+
+/// Effective limits for an API key
+///
+/// Each value comes from the key itself, then from the first of its scopes
+/// listed in [`RateLimitConfig::api_key_scopes`], then from the defaults:
+/// `[auth.api_keys] default_rate_limit` or else `per_client_rpm`, with no
+/// daily or monthly allowance.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ApiKeyQuota {
+    /// Requests per minute
+    pub requests_per_minute: u32,
+
+    /// Requests per UTC day (None = unlimited)
+    pub daily_quota: Option<u64>,
+
+    /// Requests per UTC calendar month (None = unlimited)
+    pub monthly_quota: Option<u64>,
+}
+
+impl ApiKeyQuota {
+    /// Resolve the limits that apply to `key`
+    ///
+    /// `default_rate_limit` is the `[auth.api_keys]` default, used for keys
+    /// whose own value and scopes leave the per-minute limit unset.
+    pub fn resolve(
+        key: &ApiKey,
+        config: &RateLimitConfig,
+        default_rate_limit: Option<u32>,
+    ) -> Self {
+        let scoped: Vec<&ApiKeyScopeQuota> = key
+            .scopes
+            .iter()
+            .filter_map(|scope| config.api_key_scopes.get(scope))
+            .collect();
+
+        Self {
+            requests_per_minute: key
+                .rate_limit
+                .or_else(|| scoped.iter().find_map(|q| q.requests_per_minute))
+                .or(default_rate_limit)
+                .unwrap_or(config.per_client_rpm),
+            daily_quota: key
+                .daily_quota
+                .or_else(|| scoped.iter().find_map(|q| q.daily_quota)),
+            monthly_quota: key
+                .monthly_quota
+                .or_else(|| scoped.iter().find_map(|q| q.monthly_quota)),
+        }
+    }
+
+    /// Whether a daily or monthly allowance applies
+    pub fn has_allowance(&self) -> bool {
+        self.daily_quota.is_some() || self.monthly_quota.is_some()
+    }
+
+    /// Count one request against the key's allowances
+    ///
+    /// Returns [`Error::QuotaExceeded`] once either allowance is used up.
+    /// Rejected requests are still counted. Keys without an allowance are
+    /// not counted at all.
+    pub async fn consume(
+        &self,
+        storage: &dyn ApiKeyStorage,
+        key_id: &str,
+    ) -> Result<ApiKeyUsage, Error> {
+        if !self.has_allowance() {
+            return Ok(ApiKeyUsage::default());
+        }
+
+        let usage = storage.record_usage(key_id).await?;
+        self.check(&usage, Utc::now())?;
+        Ok(usage)
+    }
+
+    /// Compare usage against the allowances
+    ///
+    /// The monthly allowance is checked first so that a key over both is
+    /// told to wait for the later rollover.
+    fn check(&self, usage: &ApiKeyUsage, now: DateTime<Utc>) -> Result<(), Error> {
+        if let Some(limit) = self.monthly_quota {
+            if usage.monthly > limit {
+                return Err(Error::QuotaExceeded {
+                    message: format!("Monthly API key quota of {} requests exhausted", limit),
+                    retry_after_secs: secs_until_next_month(now),
+                });
+            }
+        }
+
+        if let Some(limit) = self.daily_quota {
+            if usage.daily > limit {
+                return Err(Error::QuotaExceeded {
+                    message: format!("Daily API key quota of {} requests exhausted", limit),
+                    retry_after_secs: secs_until_next_day(now),
+                });
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Day (`YYYY-MM-DD`) and month (`YYYY-MM`) usage period labels
+#[cfg_attr(
+    not(any(
+        feature = "cache",
+        feature = "database",
+        feature = "turso",
+        feature = "surrealdb"
+    )),
+    allow(dead_code)
+)]
+fn usage_periods(now: DateTime<Utc>) -> (String, String) {
+    (
+        now.format("%Y-%m-%d").to_string(),
+        now.format("%Y-%m").to_string(),
+    )
+}
+
+/// Seconds until the next UTC midnight
+fn secs_until_next_day(now: DateTime<Utc>) -> u64 {
+    let next = now
+        .date_naive()
+        .checked_add_days(Days::new(1))
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc());
+    secs_until(now, next)
+}
+
+/// Seconds until the first of the next UTC month
+fn secs_until_next_month(now: DateTime<Utc>) -> u64 {
+    let next = now
+        .date_naive()
+        .with_day(1)
+        .and_then(|d| d.checked_add_months(Months::new(1)))
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc());
+    secs_until(now, next)
+}
+
+/// Fold `(period, count)` rows from the usage table into counters
+#[cfg(any(feature = "database", feature = "turso"))]
+fn usage_from_rows(rows: Vec<(String, i64)>, day: &str) -> ApiKeyUsage {
+    let mut usage = ApiKeyUsage::default();
+    for (period, count) in rows {
+        let count = count.max(0) as u64;
+        if period == day {
+            usage.daily = count;
+        } else {
+            usage.monthly = count;
+        }
+    }
+    usage
+}
+
+fn secs_until(now: DateTime<Utc>, next: Option<DateTime<Utc>>) -> u64 {
+    next.map(|next| (next - now).num_seconds().max(1) as u64)
+        .unwrap_or(1)
+}
+
 /// API key generator
 ///
 /// Generates API keys in the format `{prefix}_{random_base32}`.
@@ -231,6 +409,24 @@ pub trait ApiKeyStorage: Send + Sync {
 
     /// Delete an API key
     async fn delete(&self, id: &str) -> Result<(), Error>;
+
+    /// Count one request against the key's daily and monthly usage
+    ///
+    /// Returns the counters after the increment. Only called for keys with
+    /// a daily or monthly allowance; the default rejects them, so storages
+    /// that do not track usage keep compiling and quotas stay opt-in.
+    async fn record_usage(&self, _id: &str) -> Result<ApiKeyUsage, Error> {
+        Err(Error::Internal(
+            "API key quotas are not supported by this storage".to_string(),
+        ))
+    }
+
+    /// Get the key's usage for the current UTC day and month
+    ///
+    /// The default reports no usage, for storages that do not track it.
+    async fn get_usage(&self, _id: &str) -> Result<ApiKeyUsage, Error> {
+        Ok(ApiKeyUsage::default())
+    }
 }
 
 /// Redis-based API key storage
@@ -239,6 +435,7 @@ pub mod redis_storage {
     use super::*;
     use deadpool_redis::Pool;
     use redis::AsyncCommands;
+    use std::ops::DerefMut;
 
     /// Redis-backed API key storage
     #[derive(Clone)]
@@ -270,6 +467,10 @@ pub mod redis_storage {
         fn user_key(&self, user_id: &str) -> String {
             format!("{}:user:{}", self.key_prefix, user_id)
         }
+
+        fn usage_key(&self, id: &str, period: &str) -> String {
+            format!("{}:usage:{}:{}", self.key_prefix, id, period)
+        }
     }
 
     #[async_trait]
@@ -441,6 +642,50 @@ pub mod redis_storage {
             }
             Ok(())
         }
+
+        async fn record_usage(&self, id: &str) -> Result<ApiKeyUsage, Error> {
+            let mut conn =
+                self.pool.get().await.map_err(|e| {
+                    Error::Internal(format!("Failed to get Redis connection: {}", e))
+                })?;
+
+            let (day, month) = usage_periods(Utc::now());
+            let day_key = self.usage_key(id, &day);
+            let month_key = self.usage_key(id, &month);
+
+            // Counters outlive their period slightly so late reads still see them
+            let (daily, monthly): (u64, u64) = redis::pipe()
+                .atomic()
+                .incr(&day_key, 1)
+                .expire(&day_key, 2 * 24 * 3600)
+                .ignore()
+                .incr(&month_key, 1)
+                .expire(&month_key, 32 * 24 * 3600)
+                .ignore()
+                .query_async(conn.deref_mut())
+                .await
+                .map_err(|e| Error::Internal(format!("Failed to record API key usage: {}", e)))?;
+
+            Ok(ApiKeyUsage { daily, monthly })
+        }
+
+        async fn get_usage(&self, id: &str) -> Result<ApiKeyUsage, Error> {
+            let mut conn =
+                self.pool.get().await.map_err(|e| {
+                    Error::Internal(format!("Failed to get Redis connection: {}", e))
+                })?;
+
+            let (day, month) = usage_periods(Utc::now());
+            let (daily, monthly): (Option<u64>, Option<u64>) = conn
+                .mget(&[self.usage_key(id, &day), self.usage_key(id, &month)])
+                .await
+                .map_err(|e| Error::Internal(format!("Failed to get API key usage: {}", e)))?;
+
+            Ok(ApiKeyUsage {
+                daily: daily.unwrap_or(0),
+                monthly: monthly.unwrap_or(0),
+            })
+        }
     }
 }
 
@@ -487,9 +732,9 @@ pub mod pg_storage {
         }
 
         async fn get_by_prefix(&self, prefix: &str) -> Result<Option<ApiKey>, Error> {
-            let row = sqlx::query_as::<_, (String, String, String, String, String, serde_json::Value, Option<i32>, bool, Option<DateTime<Utc>>, Option<DateTime<Utc>>, DateTime<Utc>)>(
+            let row = sqlx::query_as::<_, (String, String, String, String, String, serde_json::Value, Option<i32>, bool, Option<DateTime<Utc>>, Option<DateTime<Utc>>, DateTime<Utc>, Option<i64>, Option<i64>)>(
                 r#"
-                SELECT id, user_id, name, key_prefix, key_hash, scopes, rate_limit, is_revoked, last_used_at, expires_at, created_at
+                SELECT id, user_id, name, key_prefix, key_hash, scopes, rate_limit, is_revoked, last_used_at, expires_at, created_at, daily_quota, monthly_quota
                 FROM api_keys
                 WHERE key_prefix = $1
                 "#,
@@ -512,6 +757,8 @@ pub mod pg_storage {
                     last_used_at,
                     expires_at,
                     created_at,
+                    daily_quota,
+                    monthly_quota,
                 )) => {
                     let scopes: Vec<String> =
                         serde_json::from_value(scopes_json).unwrap_or_default();
@@ -527,6 +774,8 @@ pub mod pg_storage {
                         last_used_at,
                         expires_at,
                         created_at,
+                        daily_quota: daily_quota.map(|q| q as u64),
+                        monthly_quota: monthly_quota.map(|q| q as u64),
                     }))
                 }
                 None => Ok(None),
@@ -534,9 +783,9 @@ pub mod pg_storage {
         }
 
         async fn get_by_id(&self, id: &str) -> Result<Option<ApiKey>, Error> {
-            let row = sqlx::query_as::<_, (String, String, String, String, String, serde_json::Value, Option<i32>, bool, Option<DateTime<Utc>>, Option<DateTime<Utc>>, DateTime<Utc>)>(
+            let row = sqlx::query_as::<_, (String, String, String, String, String, serde_json::Value, Option<i32>, bool, Option<DateTime<Utc>>, Option<DateTime<Utc>>, DateTime<Utc>, Option<i64>, Option<i64>)>(
                 r#"
-                SELECT id, user_id, name, key_prefix, key_hash, scopes, rate_limit, is_revoked, last_used_at, expires_at, created_at
+                SELECT id, user_id, name, key_prefix, key_hash, scopes, rate_limit, is_revoked, last_used_at, expires_at, created_at, daily_quota, monthly_quota
                 FROM api_keys
                 WHERE id = $1
                 "#,
@@ -559,6 +808,8 @@ pub mod pg_storage {
                     last_used_at,
                     expires_at,
                     created_at,
+                    daily_quota,
+                    monthly_quota,
                 )) => {
                     let scopes: Vec<String> =
                         serde_json::from_value(scopes_json).unwrap_or_default();
@@ -574,6 +825,8 @@ pub mod pg_storage {
                         last_used_at,
                         expires_at,
                         created_at,
+                        daily_quota: daily_quota.map(|q| q as u64),
+                        monthly_quota: monthly_quota.map(|q| q as u64),
                     }))
                 }
                 None => Ok(None),
@@ -586,8 +839,8 @@ pub mod pg_storage {
 
             sqlx::query(
                 r#"
-                INSERT INTO api_keys (id, user_id, name, key_prefix, key_hash, scopes, rate_limit, is_revoked, expires_at, created_at)
-                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                INSERT INTO api_keys (id, user_id, name, key_prefix, key_hash, scopes, rate_limit, is_revoked, expires_at, created_at, daily_quota, monthly_quota)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12)
                 "#,
             )
             .bind(&api_key.id)
@@ -600,6 +853,8 @@ pub mod pg_storage {
             .bind(api_key.is_revoked)
             .bind(api_key.expires_at)
             .bind(api_key.created_at)
+            .bind(api_key.daily_quota.map(|q| q as i64))
+            .bind(api_key.monthly_quota.map(|q| q as i64))
             .execute(&self.pool)
             .await
             .map_err(|e| Error::Internal(format!("Failed to create API key: {}", e)))?;
@@ -628,9 +883,9 @@ pub mod pg_storage {
         }
 
         async fn list_by_user(&self, user_id: &str) -> Result<Vec<ApiKey>, Error> {
-            let rows = sqlx::query_as::<_, (String, String, String, String, String, serde_json::Value, Option<i32>, bool, Option<DateTime<Utc>>, Option<DateTime<Utc>>, DateTime<Utc>)>(
+            let rows = sqlx::query_as::<_, (String, String, String, String, String, serde_json::Value, Option<i32>, bool, Option<DateTime<Utc>>, Option<DateTime<Utc>>, DateTime<Utc>, Option<i64>, Option<i64>)>(
                 r#"
-                SELECT id, user_id, name, key_prefix, key_hash, scopes, rate_limit, is_revoked, last_used_at, expires_at, created_at
+                SELECT id, user_id, name, key_prefix, key_hash, scopes, rate_limit, is_revoked, last_used_at, expires_at, created_at, daily_quota, monthly_quota
                 FROM api_keys
                 WHERE user_id = $1
                 ORDER BY created_at DESC
@@ -656,6 +911,8 @@ pub mod pg_storage {
                         last_used_at,
                         expires_at,
                         created_at,
+                        daily_quota,
+                        monthly_quota,
                     )| {
                         let scopes: Vec<String> =
                             serde_json::from_value(scopes_json).unwrap_or_default();
@@ -671,6 +928,8 @@ pub mod pg_storage {
                             last_used_at,
                             expires_at,
                             created_at,
+                            daily_quota: daily_quota.map(|q| q as u64),
+                            monthly_quota: monthly_quota.map(|q| q as u64),
                         }
                     },
                 )
@@ -688,6 +947,41 @@ pub mod pg_storage {
 
             Ok(())
         }
+
+        async fn record_usage(&self, id: &str) -> Result<ApiKeyUsage, Error> {
+            let (day, month) = usage_periods(Utc::now());
+            let rows = sqlx::query_as::<_, (String, i64)>(
+                r#"
+                INSERT INTO api_key_usage (key_id, period, count)
+                VALUES ($1, $2, 1), ($1, $3, 1)
+                ON CONFLICT (key_id, period) DO UPDATE SET count = api_key_usage.count + 1
+                RETURNING period, count
+                "#,
+            )
+            .bind(id)
+            .bind(&day)
+            .bind(&month)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to record API key usage: {}", e)))?;
+
+            Ok(usage_from_rows(rows, &day))
+        }
+
+        async fn get_usage(&self, id: &str) -> Result<ApiKeyUsage, Error> {
+            let (day, month) = usage_periods(Utc::now());
+            let rows = sqlx::query_as::<_, (String, i64)>(
+                "SELECT period, count FROM api_key_usage WHERE key_id = $1 AND period IN ($2, $3)",
+            )
+            .bind(id)
+            .bind(&day)
+            .bind(&month)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to get API key usage: {}", e)))?;
+
+            Ok(usage_from_rows(rows, &day))
+        }
     }
 }
 
@@ -736,7 +1030,7 @@ pub mod turso_storage {
             let mut rows = self
                 .conn
                 .query(
-                    "SELECT id, user_id, name, key_prefix, key_hash, scopes, rate_limit, is_revoked, last_used_at, expires_at, created_at FROM api_keys WHERE key_prefix = ?1",
+                    "SELECT id, user_id, name, key_prefix, key_hash, scopes, rate_limit, is_revoked, last_used_at, expires_at, created_at, daily_quota, monthly_quota FROM api_keys WHERE key_prefix = ?1",
                     libsql::params![prefix],
                 )
                 .await
@@ -758,7 +1052,7 @@ pub mod turso_storage {
             let mut rows = self
                 .conn
                 .query(
-                    "SELECT id, user_id, name, key_prefix, key_hash, scopes, rate_limit, is_revoked, last_used_at, expires_at, created_at FROM api_keys WHERE id = ?1",
+                    "SELECT id, user_id, name, key_prefix, key_hash, scopes, rate_limit, is_revoked, last_used_at, expires_at, created_at, daily_quota, monthly_quota FROM api_keys WHERE id = ?1",
                     libsql::params![id],
                 )
                 .await
@@ -784,7 +1078,7 @@ pub mod turso_storage {
 
             self.conn
                 .execute(
-                    "INSERT INTO api_keys (id, user_id, name, key_prefix, key_hash, scopes, rate_limit, is_revoked, expires_at, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10)",
+                    "INSERT INTO api_keys (id, user_id, name, key_prefix, key_hash, scopes, rate_limit, is_revoked, expires_at, created_at, daily_quota, monthly_quota) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
                     libsql::params![
                         api_key.id.clone(),
                         api_key.user_id.clone(),
@@ -795,7 +1089,9 @@ pub mod turso_storage {
                         api_key.rate_limit.map(|r| r as i64),
                         if api_key.is_revoked { 1i64 } else { 0i64 },
                         expires_at,
-                        api_key.created_at.to_rfc3339()
+                        api_key.created_at.to_rfc3339(),
+                        api_key.daily_quota.map(|q| q as i64),
+                        api_key.monthly_quota.map(|q| q as i64)
                     ],
                 )
                 .await
@@ -832,7 +1128,7 @@ pub mod turso_storage {
             let mut rows = self
                 .conn
                 .query(
-                    "SELECT id, user_id, name, key_prefix, key_hash, scopes, rate_limit, is_revoked, last_used_at, expires_at, created_at FROM api_keys WHERE user_id = ?1 ORDER BY created_at DESC",
+                    "SELECT id, user_id, name, key_prefix, key_hash, scopes, rate_limit, is_revoked, last_used_at, expires_at, created_at, daily_quota, monthly_quota FROM api_keys WHERE user_id = ?1 ORDER BY created_at DESC",
                     libsql::params![user_id],
                 )
                 .await
@@ -858,6 +1154,52 @@ pub mod turso_storage {
 
             Ok(())
         }
+
+        async fn record_usage(&self, id: &str) -> Result<ApiKeyUsage, Error> {
+            let (day, month) = usage_periods(Utc::now());
+            let mut rows = self
+                .conn
+                .query(
+                    "INSERT INTO api_key_usage (key_id, period, count) VALUES (?1, ?2, 1), (?1, ?3, 1) ON CONFLICT (key_id, period) DO UPDATE SET count = api_key_usage.count + 1 RETURNING period, count",
+                    libsql::params![id, day.clone(), month],
+                )
+                .await
+                .map_err(|e| Error::Internal(format!("Failed to record API key usage: {}", e)))?;
+
+            Ok(usage_from_rows(collect_usage_rows(&mut rows).await?, &day))
+        }
+
+        async fn get_usage(&self, id: &str) -> Result<ApiKeyUsage, Error> {
+            let (day, month) = usage_periods(Utc::now());
+            let mut rows = self
+                .conn
+                .query(
+                    "SELECT period, count FROM api_key_usage WHERE key_id = ?1 AND period IN (?2, ?3)",
+                    libsql::params![id, day.clone(), month],
+                )
+                .await
+                .map_err(|e| Error::Internal(format!("Failed to get API key usage: {}", e)))?;
+
+            Ok(usage_from_rows(collect_usage_rows(&mut rows).await?, &day))
+        }
+    }
+
+    async fn collect_usage_rows(rows: &mut libsql::Rows) -> Result<Vec<(String, i64)>, Error> {
+        let mut usage = Vec::new();
+        while let Some(row) = rows
+            .next()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to fetch row: {}", e)))?
+        {
+            let period: String = row
+                .get(0)
+                .map_err(|e| Error::Internal(format!("Failed to get period: {}", e)))?;
+            let count: i64 = row
+                .get(1)
+                .map_err(|e| Error::Internal(format!("Failed to get count: {}", e)))?;
+            usage.push((period, count));
+        }
+        Ok(usage)
     }
 
     fn parse_api_key_row(row: &libsql::Row) -> Result<ApiKey, Error> {
@@ -888,6 +1230,8 @@ pub mod turso_storage {
         let created_at_str: String = row
             .get(10)
             .map_err(|e| Error::Internal(format!("Failed to get created_at: {}", e)))?;
+        let daily_quota: Option<i64> = row.get(11).ok();
+        let monthly_quota: Option<i64> = row.get(12).ok();
 
         let scopes: Vec<String> = serde_json::from_str(&scopes_str).unwrap_or_default();
         let last_used_at = last_used_at_str
@@ -912,6 +1256,8 @@ pub mod turso_storage {
             last_used_at,
             expires_at,
             created_at,
+            daily_quota: daily_quota.map(|q| q as u64),
+            monthly_quota: monthly_quota.map(|q| q as u64),
         })
     }
 }
@@ -1041,6 +1387,60 @@ pub mod surrealdb_storage {
 
             Ok(())
         }
+
+        async fn record_usage(&self, id: &str) -> Result<ApiKeyUsage, Error> {
+            let (day, month) = usage_periods(Utc::now());
+            let mut result = self
+                .client
+                .query(
+                    "UPSERT type::thing('api_key_usage', [$id, $day]) SET count += 1 RETURN VALUE count;
+                     UPSERT type::thing('api_key_usage', [$id, $month]) SET count += 1 RETURN VALUE count;",
+                )
+                .bind(("id", id.to_string()))
+                .bind(("day", day))
+                .bind(("month", month))
+                .await
+                .map_err(|e| Error::Internal(format!("Failed to record API key usage: {}", e)))?;
+
+            let daily: Option<u64> = result
+                .take(0)
+                .map_err(|e| Error::Internal(format!("Failed to parse API key usage: {}", e)))?;
+            let monthly: Option<u64> = result
+                .take(1)
+                .map_err(|e| Error::Internal(format!("Failed to parse API key usage: {}", e)))?;
+
+            Ok(ApiKeyUsage {
+                daily: daily.unwrap_or(0),
+                monthly: monthly.unwrap_or(0),
+            })
+        }
+
+        async fn get_usage(&self, id: &str) -> Result<ApiKeyUsage, Error> {
+            let (day, month) = usage_periods(Utc::now());
+            let mut result = self
+                .client
+                .query(
+                    "SELECT VALUE count FROM type::thing('api_key_usage', [$id, $day]);
+                     SELECT VALUE count FROM type::thing('api_key_usage', [$id, $month]);",
+                )
+                .bind(("id", id.to_string()))
+                .bind(("day", day))
+                .bind(("month", month))
+                .await
+                .map_err(|e| Error::Internal(format!("Failed to get API key usage: {}", e)))?;
+
+            let daily: Option<u64> = result
+                .take(0)
+                .map_err(|e| Error::Internal(format!("Failed to parse API key usage: {}", e)))?;
+            let monthly: Option<u64> = result
+                .take(1)
+                .map_err(|e| Error::Internal(format!("Failed to parse API key usage: {}", e)))?;
+
+            Ok(ApiKeyUsage {
+                daily: daily.unwrap_or(0),
+                monthly: monthly.unwrap_or(0),
+            })
+        }
     }
 }
 
@@ -1101,6 +1501,8 @@ mod tests {
             key_hash: "hash".to_string(),
             scopes: vec!["read".to_string(), "write".to_string()],
             rate_limit: None,
+            daily_quota: None,
+            monthly_quota: None,
             is_revoked: false,
             last_used_at: None,
             expires_at: None,
@@ -1123,6 +1525,8 @@ mod tests {
             key_hash: "hash".to_string(),
             scopes: vec![],
             rate_limit: None,
+            daily_quota: None,
+            monthly_quota: None,
             is_revoked: true,
             last_used_at: None,
             expires_at: None,
@@ -1142,6 +1546,8 @@ mod tests {
             key_hash: "hash".to_string(),
             scopes: vec![],
             rate_limit: None,
+            daily_quota: None,
+            monthly_quota: None,
             is_revoked: false,
             last_used_at: None,
             expires_at: Some(Utc::now() - chrono::Duration::hours(1)),
@@ -1151,6 +1557,180 @@ mod tests {
         assert!(!key.is_valid());
     }
 
+    fn quota_test_key(scopes: &[&str]) -> ApiKey {
+        ApiKey {
+            id: "1".to_string(),
+            user_id: "user:123".to_string(),
+            name: "Test Key".to_string(),
+            prefix: "sk_live".to_string(),
+            key_hash: "hash".to_string(),
+            scopes: scopes.iter().map(|s| s.to_string()).collect(),
+            rate_limit: None,
+            daily_quota: None,
+            monthly_quota: None,
+            is_revoked: false,
+            last_used_at: None,
+            expires_at: None,
+            created_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_quota_resolution_order() {
+        let mut config = RateLimitConfig::default();
+        config.api_key_scopes.insert(
+            "tier:free".to_string(),
+            ApiKeyScopeQuota {
+                requests_per_minute: Some(60),
+                daily_quota: Some(1000),
+                monthly_quota: None,
+            },
+        );
+        config.api_key_scopes.insert(
+            "tier:pro".to_string(),
+            ApiKeyScopeQuota {
+                requests_per_minute: Some(600),
+                daily_quota: None,
+                monthly_quota: Some(500_000),
+            },
+        );
+
+        // Scopes fill in fields in the key's scope order
+        let key = quota_test_key(&["read", "tier:free", "tier:pro"]);
+        assert_eq!(
+            ApiKeyQuota::resolve(&key, &config, None),
+            ApiKeyQuota {
+                requests_per_minute: 60,
+                daily_quota: Some(1000),
+                monthly_quota: Some(500_000),
+            }
+        );
+
+        // Values stored with the key win
+        let mut key = quota_test_key(&["tier:free"]);
+        key.rate_limit = Some(5);
+        key.daily_quota = Some(10);
+        let quota = ApiKeyQuota::resolve(&key, &config, None);
+        assert_eq!(quota.requests_per_minute, 5);
+        assert_eq!(quota.daily_quota, Some(10));
+
+        // No match falls back to the per-client limit with no allowance
+        let quota = ApiKeyQuota::resolve(&quota_test_key(&[]), &config, None);
+        assert_eq!(quota.requests_per_minute, config.per_client_rpm);
+        assert!(!quota.has_allowance());
+
+        // The `[auth.api_keys]` default stands in for the per-client limit,
+        // but not for a key's own value or its scopes
+        let quota = ApiKeyQuota::resolve(&quota_test_key(&[]), &config, Some(30));
+        assert_eq!(quota.requests_per_minute, 30);
+        let quota = ApiKeyQuota::resolve(&quota_test_key(&["tier:free"]), &config, Some(30));
+        assert_eq!(quota.requests_per_minute, 60);
+    }
+
+    #[test]
+    fn test_quota_check() {
+        let quota = ApiKeyQuota {
+            requests_per_minute: 60,
+            daily_quota: Some(100),
+            monthly_quota: Some(1000),
+        };
+        let now = DateTime::parse_from_rfc3339("2026-01-31T23:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let usage = ApiKeyUsage {
+            daily: 100,
+            monthly: 1000,
+        };
+        assert!(quota.check(&usage, now).is_ok());
+
+        let usage = ApiKeyUsage {
+            daily: 101,
+            monthly: 500,
+        };
+        match quota.check(&usage, now) {
+            Err(Error::QuotaExceeded {
+                retry_after_secs, ..
+            }) => assert_eq!(retry_after_secs, 3600),
+            other => panic!("expected daily quota error, got {:?}", other),
+        }
+
+        // Over both: wait for the monthly rollover
+        let usage = ApiKeyUsage {
+            daily: 101,
+            monthly: 1001,
+        };
+        match quota.check(&usage, now) {
+            Err(Error::QuotaExceeded { message, .. }) => assert!(message.starts_with("Monthly")),
+            other => panic!("expected monthly quota error, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn test_usage_periods() {
+        let now = DateTime::parse_from_rfc3339("2026-12-31T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert_eq!(
+            usage_periods(now),
+            ("2026-12-31".to_string(), "2026-12".to_string())
+        );
+        assert_eq!(secs_until_next_day(now), 12 * 3600);
+        assert_eq!(secs_until_next_month(now), 12 * 3600);
+    }
+
+    /// Storage that implements only the required methods
+    struct KeysOnlyStorage;
+
+    #[async_trait]
+    impl ApiKeyStorage for KeysOnlyStorage {
+        async fn get_by_key(&self, _key: &str) -> Result<Option<ApiKey>, Error> {
+            Ok(None)
+        }
+        async fn get_by_prefix(&self, _prefix: &str) -> Result<Option<ApiKey>, Error> {
+            Ok(None)
+        }
+        async fn get_by_id(&self, _id: &str) -> Result<Option<ApiKey>, Error> {
+            Ok(None)
+        }
+        async fn create(&self, _key: &ApiKey) -> Result<(), Error> {
+            Ok(())
+        }
+        async fn update_last_used(&self, _id: &str) -> Result<(), Error> {
+            Ok(())
+        }
+        async fn revoke(&self, _id: &str) -> Result<(), Error> {
+            Ok(())
+        }
+        async fn list_by_user(&self, _user_id: &str) -> Result<Vec<ApiKey>, Error> {
+            Ok(Vec::new())
+        }
+        async fn delete(&self, _id: &str) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_quotas_are_opt_in_for_storages_without_usage() {
+        let storage = KeysOnlyStorage;
+        assert_eq!(
+            storage.get_usage("key").await.unwrap(),
+            ApiKeyUsage::default()
+        );
+
+        let unlimited = ApiKeyQuota::resolve(&quota_test_key(&[]), &RateLimitConfig::default(), None);
+        assert!(unlimited.consume(&storage, "key").await.is_ok());
+
+        let mut key = quota_test_key(&[]);
+        key.daily_quota = Some(10);
+        let limited = ApiKeyQuota::resolve(&key, &RateLimitConfig::default(), None);
+        assert!(matches!(
+            limited.consume(&storage, "key").await,
+            Err(Error::Internal(_))
+        ));
+    }
+
     #[test]
     fn test_base32_encode() {
         // Test with known values
@@ -0,0 +1,1051 @@
+//! Token and introspection endpoints for the client-credentials grant
+//!
+//! - `POST {token_path}`: RFC 6749 §4.4 client-credentials grant
+//! - `POST {introspection_path}`: RFC 7662 token introspection
+//!
+//! Clients authenticate with HTTP Basic (`client_secret_basic`) or with
+//! `client_id`/`client_secret` form fields (`client_secret_post`), not both.
+//! Basic credentials are form-urlencoded before base64 (RFC 6749 §2.3.1),
+//! so an ID or secret containing `:` or `%` is sent escaped.
+//! Failures use the RFC 6749 §5.2 error body (`{"error", "error_description"}`).
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    extract::{rejection::FormRejection, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::post,
+    Form, Json, Router,
+};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+
+use super::{generate_client_secret, ClientRegistration, ClientStorage, NewClient};
+use crate::auth::config::ClientCredentialsConfig;
+use crate::auth::tokens::{ClaimsBuilder, TokenGenerator};
+use crate::error::Error;
+use crate::middleware::{Claims, TokenValidator};
+
+/// The only grant type the token endpoint accepts
+pub const CLIENT_CREDENTIALS_GRANT: &str = "client_credentials";
+
+type SignFn = dyn Fn(&Claims, Duration) -> Result<String, Error> + Send + Sync;
+type ValidateFn = dyn Fn(&str) -> Result<Claims, Error> + Send + Sync;
+
+/// Issues access tokens to registered service clients
+///
+/// ```rust,ignore
+/// let generator = PasetoGenerator::new(&paseto_config, &token_config)?
+///     .with_key_manager(key_manager);
+/// let server = ClientCredentialsServer::new(generator, storage, &client_config)
+///     .with_introspection(paseto_auth);
+///
+/// let (client, secret) = server
+///     .register(NewClient {
+///         client_id: "billing".into(),
+///         name: "Billing service".into(),
+///         scopes: vec!["orders:read".into()],
+///         audiences: vec!["orders-api".into()],
+///         ..Default::default()
+///     })
+///     .await?;
+///
+/// let app = Router::new().merge(server.router());
+/// ```
+#[derive(Clone)]
+pub struct ClientCredentialsServer {
+    sign: Arc<SignFn>,
+    lifetime: Duration,
+    storage: Arc<dyn ClientStorage>,
+    validate: Option<Arc<ValidateFn>>,
+    #[cfg(feature = "cache")]
+    revocation: Option<Arc<dyn crate::middleware::TokenRevocation>>,
+    config: ClientCredentialsConfig,
+}
+
+/// Successful token endpoint response (RFC 6749 §5.1)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ClientTokenResponse {
+    /// The signed access token
+    pub access_token: String,
+
+    /// Always `Bearer`
+    pub token_type: String,
+
+    /// Seconds until the access token expires
+    pub expires_in: i64,
+
+    /// Granted scopes, space-delimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+}
+
+/// Token introspection response (RFC 7662 §2.2)
+///
+/// Inactive tokens carry no other fields.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntrospectionResponse {
+    /// Whether the token is currently valid
+    pub active: bool,
+
+    /// Granted scopes (the token's `perms`), space-delimited
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scope: Option<String>,
+
+    /// Client the token was issued to, for client tokens
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_id: Option<String>,
+
+    /// Username, for user tokens that carry one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub username: Option<String>,
+
+    /// Always `Bearer` for active tokens
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token_type: Option<String>,
+
+    /// Expiration time (Unix timestamp)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exp: Option<i64>,
+
+    /// Issued at (Unix timestamp)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iat: Option<i64>,
+
+    /// Subject
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sub: Option<String>,
+
+    /// Audience
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+
+    /// Issuer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iss: Option<String>,
+
+    /// Token ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>,
+}
+
+impl IntrospectionResponse {
+    /// The response for an unknown, expired, or revoked token
+    pub fn inactive() -> Self {
+        Self::default()
+    }
+
+    fn from_claims(claims: &Claims) -> Self {
+        Self {
+            active: true,
+            scope: (!claims.perms.is_empty()).then(|| claims.perms.join(" ")),
+            client_id: claims.client_id().map(str::to_string),
+            username: claims.username.clone(),
+            token_type: Some("Bearer".to_string()),
+            exp: Some(claims.exp),
+            iat: claims.iat,
+            sub: Some(claims.sub.clone()),
+            aud: claims.aud.clone(),
+            iss: claims.iss.clone(),
+            jti: claims.jti.clone(),
+        }
+    }
+}
+
+/// Errors from the token and introspection endpoints
+///
+/// Each variant except `Internal` maps to an RFC 6749 §5.2 error code.
+#[derive(Debug, thiserror::Error)]
+pub enum ClientCredentialsError {
+    /// Missing or malformed parameters
+    #[error("invalid_request: {0}")]
+    InvalidRequest(String),
+
+    /// Unknown client, wrong secret, or revoked client
+    #[error("invalid_client")]
+    InvalidClient,
+
+    /// The client is not allowed to use this endpoint
+    #[error("unauthorized_client: {0}")]
+    UnauthorizedClient(String),
+
+    /// A grant type other than `client_credentials`
+    #[error("unsupported_grant_type: {0}")]
+    UnsupportedGrantType(String),
+
+    /// A requested scope the client is not registered for
+    #[error("invalid_scope: {0}")]
+    InvalidScope(String),
+
+    /// A requested audience the client is not registered for (RFC 8707)
+    #[error("invalid_target: {0}")]
+    InvalidTarget(String),
+
+    /// Storage or signing failure
+    #[error(transparent)]
+    Internal(#[from] Error),
+}
+
+impl ClientCredentialsError {
+    /// The RFC 6749 error code
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::InvalidRequest(_) => "invalid_request",
+            Self::InvalidClient => "invalid_client",
+            Self::UnauthorizedClient(_) => "unauthorized_client",
+            Self::UnsupportedGrantType(_) => "unsupported_grant_type",
+            Self::InvalidScope(_) => "invalid_scope",
+            Self::InvalidTarget(_) => "invalid_target",
+            Self::Internal(_) => "server_error",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct OAuthErrorBody {
+    error: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error_description: Option<String>,
+}
+
+impl IntoResponse for ClientCredentialsError {
+    fn into_response(self) -> Response {
+        let status = match self {
+            Self::Internal(e) => return no_store(e.into_response()),
+            Self::InvalidClient => StatusCode::UNAUTHORIZED,
+            Self::UnauthorizedClient(_) => StatusCode::FORBIDDEN,
+            _ => StatusCode::BAD_REQUEST,
+        };
+        let error_description = match &self {
+            Self::InvalidRequest(d)
+            | Self::UnauthorizedClient(d)
+            | Self::UnsupportedGrantType(d)
+            | Self::InvalidScope(d)
+            | Self::InvalidTarget(d) => Some(d.clone()),
+            _ => None,
+        };
+        let body = OAuthErrorBody {
+            error: self.code(),
+            error_description,
+        };
+
+        let mut response = (status, Json(body)).into_response();
+        if status == StatusCode::UNAUTHORIZED {
+            response.headers_mut().insert(
+                header::WWW_AUTHENTICATE,
+                HeaderValue::from_static("Basic realm=\"oauth\""),
+            );
+        }
+        no_store(response)
+    }
+}
+
+impl ClientCredentialsServer {
+    /// Create a server from a token generator and client storage
+    ///
+    /// Build the generator `with_key_manager` to sign with the rotating key.
+    /// Leave the generator's own audience unset: it would override the
+    /// audience each client requests.
+    pub fn new<G>(
+        generator: G,
+        storage: Arc<dyn ClientStorage>,
+        config: &ClientCredentialsConfig,
+    ) -> Self
+    where
+        G: TokenGenerator + 'static,
+    {
+        let lifetime = match config.access_token_lifetime_secs {
+            Some(secs) => Duration::from_secs(secs.max(0) as u64),
+            None => generator.default_lifetime(),
+        };
+        Self {
+            sign: Arc::new(move |claims: &Claims, expires_in: Duration| {
+                generator.generate_token_with_expiry(claims, expires_in)
+            }),
+            lifetime,
+            storage,
+            validate: None,
+            #[cfg(feature = "cache")]
+            revocation: None,
+            config: config.clone(),
+        }
+    }
+
+    /// Serve the introspection endpoint, validating tokens with `validator`
+    ///
+    /// Use the validator downstream services use, e.g. a `PasetoAuth` or
+    /// `JwtAuth` with the same key manager.
+    pub fn with_introspection<V>(mut self, validator: V) -> Self
+    where
+        V: TokenValidator + 'static,
+    {
+        self.validate = Some(Arc::new(move |token: &str| validator.validate_token(token)));
+        self
+    }
+
+    /// Report tokens with a revoked `jti` as inactive
+    #[cfg(feature = "cache")]
+    pub fn with_revocation<R: crate::middleware::TokenRevocation + 'static>(
+        mut self,
+        revocation: R,
+    ) -> Self {
+        self.revocation = Some(Arc::new(revocation));
+        self
+    }
+
+    /// The client storage backing this server
+    pub fn storage(&self) -> &Arc<dyn ClientStorage> {
+        &self.storage
+    }
+
+    /// Register a client
+    ///
+    /// Returns the stored registration and the plaintext secret. The secret
+    /// is not stored and cannot be recovered; hand it to the client once.
+    pub async fn register(
+        &self,
+        new_client: NewClient,
+    ) -> Result<(ClientRegistration, String), Error> {
+        let client_id = new_client.client_id.trim();
+        if client_id.is_empty() || client_id.contains(':') {
+            return Err(Error::ValidationError(
+                "client_id must be non-empty and must not contain ':'".to_string(),
+            ));
+        }
+
+        let (secret, secret_hash) = generate_client_secret();
+        let client = ClientRegistration {
+            client_id: client_id.to_string(),
+            name: new_client.name,
+            secret_hash,
+            scopes: new_client.scopes,
+            audiences: new_client.audiences,
+            can_introspect: new_client.can_introspect,
+            is_revoked: false,
+            created_at: Utc::now(),
+        };
+        self.storage.create(&client).await?;
+        Ok((client, secret))
+    }
+
+    /// Replace a client's secret, returning the new plaintext secret
+    ///
+    /// The old secret stops working immediately. Tokens already issued stay
+    /// valid until they expire.
+    pub async fn rotate_secret(&self, client_id: &str) -> Result<String, Error> {
+        let mut client = self.get_client(client_id).await?;
+        let (secret, secret_hash) = generate_client_secret();
+        client.secret_hash = secret_hash;
+        self.storage.update(&client).await?;
+        Ok(secret)
+    }
+
+    /// Revoke a client
+    ///
+    /// The client can no longer obtain tokens, and its outstanding tokens
+    /// introspect as inactive.
+    pub async fn revoke_client(&self, client_id: &str) -> Result<(), Error> {
+        let mut client = self.get_client(client_id).await?;
+        client.is_revoked = true;
+        self.storage.update(&client).await
+    }
+
+    /// Check a client's credentials
+    pub async fn authenticate(
+        &self,
+        client_id: &str,
+        client_secret: &str,
+    ) -> Result<ClientRegistration, ClientCredentialsError> {
+        match self.storage.get(client_id).await? {
+            Some(client) if !client.is_revoked && client.verify_secret(client_secret) => Ok(client),
+            _ => Err(ClientCredentialsError::InvalidClient),
+        }
+    }
+
+    /// Issue an access token to an authenticated client
+    ///
+    /// `scope` is space-delimited; when absent, every registered scope is
+    /// granted. `audience` defaults to the client's first registered audience.
+    pub async fn issue(
+        &self,
+        client: &ClientRegistration,
+        scope: Option<&str>,
+        audience: Option<&str>,
+    ) -> Result<ClientTokenResponse, ClientCredentialsError> {
+        let scopes = granted_scopes(client, scope)?;
+        let audience = granted_audience(client, audience)?;
+
+        let mut builder = ClaimsBuilder::new()
+            .client(client.client_id.as_str())
+            .permissions(scopes.iter().cloned());
+        if let Some(audience) = audience {
+            builder = builder.audience(audience);
+        }
+        let claims = builder.build()?;
+        let access_token = (self.sign)(&claims, self.lifetime)?;
+
+        Ok(ClientTokenResponse {
+            access_token,
+            token_type: "Bearer".to_string(),
+            expires_in: self.lifetime.as_secs() as i64,
+            scope: (!scopes.is_empty()).then(|| scopes.join(" ")),
+        })
+    }
+
+    /// Describe a token (RFC 7662)
+    ///
+    /// Tokens that fail validation, have expired, carry a revoked `jti`, or
+    /// belong to a revoked or deleted client are inactive.
+    pub async fn introspect(&self, token: &str) -> Result<IntrospectionResponse, Error> {
+        let validate = self.validate.as_ref().ok_or_else(|| {
+            Error::NotSupported("Token introspection requires a validator".to_string())
+        })?;
+
+        let Ok(claims) = validate(token) else {
+            return Ok(IntrospectionResponse::inactive());
+        };
+        if claims.exp <= Utc::now().timestamp() {
+            return Ok(IntrospectionResponse::inactive());
+        }
+
+        #[cfg(feature = "cache")]
+        if let (Some(revocation), Some(jti)) = (&self.revocation, &claims.jti) {
+            if revocation.is_revoked(jti).await? {
+                return Ok(IntrospectionResponse::inactive());
+            }
+        }
+
+        if let Some(client_id) = claims.client_id() {
+            match self.storage.get(client_id).await? {
+                Some(client) if !client.is_revoked => {}
+                _ => return Ok(IntrospectionResponse::inactive()),
+            }
+        }
+
+        Ok(IntrospectionResponse::from_claims(&claims))
+    }
+
+    /// Build the token endpoint route, plus the introspection route when a
+    /// validator is configured
+    ///
+    /// Returns an empty router when the issuer is disabled in configuration.
+    /// Both endpoints authenticate their callers themselves, so add their
+    /// paths to the token middleware's `public_paths`.
+    pub fn router(self) -> Router {
+        if !self.config.enabled {
+            return Router::new();
+        }
+
+        let mut router = Router::new().route(&self.config.token_path, post(token_handler));
+        if self.validate.is_some() {
+            router = router.route(&self.config.introspection_path, post(introspect_handler));
+        }
+        router.with_state(Arc::new(self))
+    }
+
+    async fn get_client(&self, client_id: &str) -> Result<ClientRegistration, Error> {
+        self.storage
+            .get(client_id)
+            .await?
+            .ok_or_else(|| Error::NotFound(format!("OAuth client not found: {}", client_id)))
+    }
+}
+
+// ============================================================================
+// Handlers
+// ============================================================================
+
+/// Token endpoint form (RFC 6749 §4.4.2)
+#[derive(Debug, Deserialize)]
+struct TokenRequest {
+    grant_type: Option<String>,
+    scope: Option<String>,
+    audience: Option<String>,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+}
+
+/// Introspection endpoint form (RFC 7662 §2.1)
+#[derive(Debug, Deserialize)]
+struct IntrospectRequest {
+    token: Option<String>,
+    #[allow(dead_code)]
+    token_type_hint: Option<String>,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+}
+
+async fn token_handler(
+    State(server): State<Arc<ClientCredentialsServer>>,
+    headers: HeaderMap,
+    form: Result<Form<TokenRequest>, FormRejection>,
+) -> Result<Response, ClientCredentialsError> {
+    let Form(form) = form.map_err(|e| ClientCredentialsError::InvalidRequest(e.body_text()))?;
+
+    match form.grant_type.as_deref() {
+        Some(CLIENT_CREDENTIALS_GRANT) => {}
+        Some(other) => {
+            return Err(ClientCredentialsError::UnsupportedGrantType(
+                other.to_string(),
+            ))
+        }
+        None => {
+            return Err(ClientCredentialsError::InvalidRequest(
+                "grant_type is required".to_string(),
+            ))
+        }
+    }
+
+    let (client_id, client_secret) =
+        presented_credentials(&headers, form.client_id, form.client_secret)?;
+    let client = server.authenticate(&client_id, &client_secret).await?;
+    let token = server
+        .issue(&client, form.scope.as_deref(), form.audience.as_deref())
+        .await?;
+
+    Ok(no_store(Json(token).into_response()))
+}
+
+async fn introspect_handler(
+    State(server): State<Arc<ClientCredentialsServer>>,
+    headers: HeaderMap,
+    form: Result<Form<IntrospectRequest>, FormRejection>,
+) -> Result<Response, ClientCredentialsError> {
+    let Form(form) = form.map_err(|e| ClientCredentialsError::InvalidRequest(e.body_text()))?;
+
+    let (client_id, client_secret) =
+        presented_credentials(&headers, form.client_id, form.client_secret)?;
+    let client = server.authenticate(&client_id, &client_secret).await?;
+    if !client.can_introspect {
+        return Err(ClientCredentialsError::UnauthorizedClient(
+            "client may not introspect tokens".to_string(),
+        ));
+    }
+
+    let token = form
+        .token
+        .ok_or_else(|| ClientCredentialsError::InvalidRequest("token is required".to_string()))?;
+    let response = server.introspect(&token).await?;
+
+    Ok(no_store(Json(response).into_response()))
+}
+
+// ============================================================================
+// Helpers
+// ============================================================================
+
+/// The client credentials from the Basic header or the form body
+///
+/// Using both methods at once is rejected (RFC 6749 §2.3). A form
+/// `client_id` alongside Basic credentials must name the same client.
+fn presented_credentials(
+    headers: &HeaderMap,
+    form_id: Option<String>,
+    form_secret: Option<String>,
+) -> Result<(String, String), ClientCredentialsError> {
+    let basic = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.split_once(' '))
+        .filter(|(scheme, _)| scheme.eq_ignore_ascii_case("basic"))
+        .map(|(_, encoded)| encoded.trim());
+
+    let Some(encoded) = basic else {
+        return match (form_id, form_secret) {
+            (Some(id), Some(secret)) => Ok((id, secret)),
+            _ => Err(ClientCredentialsError::InvalidClient),
+        };
+    };
+
+    if form_secret.is_some() {
+        return Err(ClientCredentialsError::InvalidRequest(
+            "use only one client authentication method".to_string(),
+        ));
+    }
+
+    let decoded = STANDARD
+        .decode(encoded)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or(ClientCredentialsError::InvalidClient)?;
+    let (id, secret) = decoded
+        .split_once(':')
+        .and_then(|(id, secret)| Some((form_urldecode(id)?, form_urldecode(secret)?)))
+        .ok_or(ClientCredentialsError::InvalidClient)?;
+
+    if form_id.is_some_and(|form_id| form_id != id) {
+        return Err(ClientCredentialsError::InvalidRequest(
+            "client_id does not match the Authorization header".to_string(),
+        ));
+    }
+    Ok((id, secret))
+}
+
+/// Undo `application/x-www-form-urlencoded` escaping: `+` is a space and
+/// `%XX` a byte. Malformed escapes or non-UTF-8 results give `None`.
+fn form_urldecode(value: &str) -> Option<String> {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'+' => out.push(b' '),
+            b'%' => {
+                let hex = bytes.get(i + 1..i + 3)?;
+                if !hex.iter().all(u8::is_ascii_hexdigit) {
+                    return None;
+                }
+                out.push(u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok()?);
+                i += 2;
+            }
+            b => out.push(b),
+        }
+        i += 1;
+    }
+    String::from_utf8(out).ok()
+}
+
+/// Resolve the requested scopes against the client's registration
+fn granted_scopes(
+    client: &ClientRegistration,
+    requested: Option<&str>,
+) -> Result<Vec<String>, ClientCredentialsError> {
+    let requested: Vec<&str> = requested
+        .map(|s| s.split_whitespace().collect())
+        .unwrap_or_default();
+    if requested.is_empty() {
+        return Ok(client.scopes.clone());
+    }
+
+    let mut granted: Vec<String> = Vec::with_capacity(requested.len());
+    for scope in requested {
+        if !client.allows_scope(scope) {
+            return Err(ClientCredentialsError::InvalidScope(format!(
+                "scope not allowed for this client: {}",
+                scope
+            )));
+        }
+        if !granted.iter().any(|g| g == scope) {
+            granted.push(scope.to_string());
+        }
+    }
+    Ok(granted)
+}
+
+/// Resolve the requested audience against the client's registration
+fn granted_audience(
+    client: &ClientRegistration,
+    requested: Option<&str>,
+) -> Result<Option<String>, ClientCredentialsError> {
+    match requested.map(str::trim).filter(|a| !a.is_empty()) {
+        Some(audience) if client.allows_audience(audience) => Ok(Some(audience.to_string())),
+        Some(audience) => Err(ClientCredentialsError::InvalidTarget(format!(
+            "audience not allowed for this client: {}",
+            audience
+        ))),
+        None => Ok(client.audiences.first().cloned()),
+    }
+}
+
+/// Token responses must not be cached (RFC 6749 §5.1)
+fn no_store(mut response: Response) -> Response {
+    let headers = response.headers_mut();
+    headers.insert(header::CACHE_CONTROL, HeaderValue::from_static("no-store"));
+    headers.insert(header::PRAGMA, HeaderValue::from_static("no-cache"));
+    response
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use axum::body::Body;
+    use axum::http::Request;
+    use std::sync::Mutex;
+    use tower::ServiceExt;
+
+    /// Encodes claims as JSON so `JsonValidator` can read them back
+    #[derive(Clone)]
+    struct JsonGenerator;
+
+    impl TokenGenerator for JsonGenerator {
+        fn generate_token(&self, claims: &Claims) -> Result<String, Error> {
+            self.generate_token_with_expiry(claims, self.default_lifetime())
+        }
+
+        fn generate_token_with_expiry(
+            &self,
+            claims: &Claims,
+            expires_in: Duration,
+        ) -> Result<String, Error> {
+            let mut claims = claims.clone();
+            claims.exp = Utc::now().timestamp() + expires_in.as_secs() as i64;
+            Ok(serde_json::to_string(&claims).unwrap())
+        }
+
+        fn default_lifetime(&self) -> Duration {
+            Duration::from_secs(300)
+        }
+    }
+
+    #[derive(Clone)]
+    struct JsonValidator;
+
+    impl TokenValidator for JsonValidator {
+        fn validate_token(&self, token: &str) -> Result<Claims, Error> {
+            serde_json::from_str(token).map_err(|e| Error::Unauthorized(e.to_string()))
+        }
+    }
+
+    #[derive(Default)]
+    struct MemoryClientStorage {
+        clients: Mutex<Vec<ClientRegistration>>,
+    }
+
+    #[async_trait]
+    impl ClientStorage for MemoryClientStorage {
+        async fn create(&self, client: &ClientRegistration) -> Result<(), Error> {
+            let mut clients = self.clients.lock().unwrap();
+            if clients.iter().any(|c| c.client_id == client.client_id) {
+                return Err(Error::Conflict(client.client_id.clone()));
+            }
+            clients.push(client.clone());
+            Ok(())
+        }
+
+        async fn get(&self, client_id: &str) -> Result<Option<ClientRegistration>, Error> {
+            Ok(self
+                .clients
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|c| c.client_id == client_id)
+                .cloned())
+        }
+
+        async fn list(&self) -> Result<Vec<ClientRegistration>, Error> {
+            Ok(self.clients.lock().unwrap().clone())
+        }
+
+        async fn update(&self, client: &ClientRegistration) -> Result<(), Error> {
+            let mut clients = self.clients.lock().unwrap();
+            let existing = clients
+                .iter_mut()
+                .find(|c| c.client_id == client.client_id)
+                .ok_or_else(|| Error::NotFound(client.client_id.clone()))?;
+            *existing = client.clone();
+            Ok(())
+        }
+
+        async fn delete(&self, client_id: &str) -> Result<(), Error> {
+            self.clients
+                .lock()
+                .unwrap()
+                .retain(|c| c.client_id != client_id);
+            Ok(())
+        }
+
+        async fn initialize(&self) -> Result<(), Error> {
+            Ok(())
+        }
+    }
+
+    fn server() -> ClientCredentialsServer {
+        ClientCredentialsServer::new(
+            JsonGenerator,
+            Arc::new(MemoryClientStorage::default()),
+            &ClientCredentialsConfig::default(),
+        )
+        .with_introspection(JsonValidator)
+    }
+
+    fn billing() -> NewClient {
+        NewClient {
+            client_id: "billing".to_string(),
+            name: "Billing service".to_string(),
+            scopes: vec!["orders:read".to_string(), "orders:write".to_string()],
+            audiences: vec!["orders-api".to_string(), "ledger-api".to_string()],
+            can_introspect: false,
+        }
+    }
+
+    fn form_request(path: &str, basic: Option<(&str, &str)>, body: &str) -> Request<Body> {
+        let mut builder = Request::builder()
+            .method("POST")
+            .uri(path)
+            .header(header::CONTENT_TYPE, "application/x-www-form-urlencoded");
+        if let Some((id, secret)) = basic {
+            let encoded = STANDARD.encode(format!("{}:{}", urlencode(id), urlencode(secret)));
+            builder = builder.header(header::AUTHORIZATION, format!("Basic {}", encoded));
+        }
+        builder.body(Body::from(body.to_string())).unwrap()
+    }
+
+    async fn json_body(response: Response) -> serde_json::Value {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice(&body).unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_issue_grants_requested_scopes_and_audience() {
+        let server = server();
+        let (client, _) = server.register(billing()).await.unwrap();
+
+        let token = server
+            .issue(&client, Some("orders:read orders:read"), Some("ledger-api"))
+            .await
+            .unwrap();
+        assert_eq!(token.token_type, "Bearer");
+        assert_eq!(token.expires_in, 300);
+        assert_eq!(token.scope.as_deref(), Some("orders:read"));
+
+        let claims = JsonValidator.validate_token(&token.access_token).unwrap();
+        assert!(claims.is_client());
+        assert_eq!(claims.client_id(), Some("billing"));
+        assert_eq!(claims.perms, vec!["orders:read"]);
+        assert_eq!(claims.aud.as_deref(), Some("ledger-api"));
+    }
+
+    #[tokio::test]
+    async fn test_issue_defaults_to_all_scopes_and_first_audience() {
+        let server = server();
+        let (client, _) = server.register(billing()).await.unwrap();
+
+        let token = server.issue(&client, None, None).await.unwrap();
+        assert_eq!(token.scope.as_deref(), Some("orders:read orders:write"));
+        let claims = JsonValidator.validate_token(&token.access_token).unwrap();
+        assert_eq!(claims.aud.as_deref(), Some("orders-api"));
+    }
+
+    #[tokio::test]
+    async fn test_issue_rejects_unregistered_scope_and_audience() {
+        let server = server();
+        let (client, _) = server.register(billing()).await.unwrap();
+
+        let err = server
+            .issue(&client, Some("orders:delete"), None)
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), "invalid_scope");
+
+        let err = server
+            .issue(&client, None, Some("payroll-api"))
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), "invalid_target");
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_rejects_wrong_rotated_and_revoked_secrets() {
+        let server = server();
+        let (_, secret) = server.register(billing()).await.unwrap();
+
+        assert!(server.authenticate("billing", &secret).await.is_ok());
+        assert!(server.authenticate("billing", "wrong").await.is_err());
+        assert!(server.authenticate("unknown", &secret).await.is_err());
+
+        let rotated = server.rotate_secret("billing").await.unwrap();
+        assert!(server.authenticate("billing", &secret).await.is_err());
+        assert!(server.authenticate("billing", &rotated).await.is_ok());
+
+        server.revoke_client("billing").await.unwrap();
+        let err = server.authenticate("billing", &rotated).await.unwrap_err();
+        assert_eq!(err.code(), "invalid_client");
+    }
+
+    #[tokio::test]
+    async fn test_register_rejects_colon_in_client_id() {
+        let server = server();
+        let mut client = billing();
+        client.client_id = "billing:v2".to_string();
+        assert!(server.register(client).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_introspect_reflects_client_revocation() {
+        let server = server();
+        let (client, _) = server.register(billing()).await.unwrap();
+        let token = server
+            .issue(&client, Some("orders:read"), None)
+            .await
+            .unwrap();
+
+        let active = server.introspect(&token.access_token).await.unwrap();
+        assert!(active.active);
+        assert_eq!(active.client_id.as_deref(), Some("billing"));
+        assert_eq!(active.scope.as_deref(), Some("orders:read"));
+        assert_eq!(active.aud.as_deref(), Some("orders-api"));
+
+        assert!(!server.introspect("garbage").await.unwrap().active);
+
+        server.revoke_client("billing").await.unwrap();
+        let inactive = server.introspect(&token.access_token).await.unwrap();
+        assert_eq!(inactive, IntrospectionResponse::inactive());
+    }
+
+    #[tokio::test]
+    async fn test_token_endpoint() {
+        let server = server();
+        let (_, secret) = server.register(billing()).await.unwrap();
+        let router = server.router();
+
+        let response = router
+            .clone()
+            .oneshot(form_request(
+                "/oauth/token",
+                Some(("billing", &secret)),
+                "grant_type=client_credentials&scope=orders:write",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CACHE_CONTROL], "no-store");
+        let body = json_body(response).await;
+        assert_eq!(body["token_type"], "Bearer");
+        assert_eq!(body["scope"], "orders:write");
+
+        let response = router
+            .clone()
+            .oneshot(form_request(
+                "/oauth/token",
+                None,
+                &format!(
+                    "grant_type=client_credentials&client_id=billing&client_secret={}",
+                    secret
+                ),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = router
+            .clone()
+            .oneshot(form_request(
+                "/oauth/token",
+                Some(("billing", "wrong")),
+                "grant_type=client_credentials",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        assert!(response.headers().contains_key(header::WWW_AUTHENTICATE));
+        assert_eq!(json_body(response).await["error"], "invalid_client");
+
+        let response = router
+            .oneshot(form_request(
+                "/oauth/token",
+                Some(("billing", &secret)),
+                "grant_type=password",
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(json_body(response).await["error"], "unsupported_grant_type");
+    }
+
+    #[tokio::test]
+    async fn test_introspection_endpoint_requires_permission() {
+        let server = server();
+        let (client, caller_secret) = server.register(billing()).await.unwrap();
+        let mut gateway = billing();
+        gateway.client_id = "gateway".to_string();
+        gateway.can_introspect = true;
+        let (_, gateway_secret) = server.register(gateway).await.unwrap();
+        let token = server.issue(&client, None, None).await.unwrap();
+        let router = server.router();
+        let body = format!("token={}", urlencode(&token.access_token));
+
+        let response = router
+            .clone()
+            .oneshot(form_request(
+                "/oauth/introspect",
+                Some(("billing", &caller_secret)),
+                &body,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        let response = router
+            .oneshot(form_request(
+                "/oauth/introspect",
+                Some(("gateway", &gateway_secret)),
+                &body,
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = json_body(response).await;
+        assert_eq!(body["active"], true);
+        assert_eq!(body["client_id"], "billing");
+    }
+
+    #[test]
+    fn test_presented_credentials_rejects_two_methods() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Basic {}", STANDARD.encode("billing:s"))).unwrap(),
+        );
+
+        let (id, secret) = presented_credentials(&headers, None, None).unwrap();
+        assert_eq!((id.as_str(), secret.as_str()), ("billing", "s"));
+
+        let err =
+            presented_credentials(&headers, Some("billing".into()), Some("s".into())).unwrap_err();
+        assert_eq!(err.code(), "invalid_request");
+
+        let err =
+            presented_credentials(&HeaderMap::new(), Some("billing".into()), None).unwrap_err();
+        assert_eq!(err.code(), "invalid_client");
+    }
+
+    #[test]
+    fn test_presented_credentials_form_urldecodes_basic() {
+        let basic = |raw: &str| {
+            let mut headers = HeaderMap::new();
+            headers.insert(
+                header::AUTHORIZATION,
+                HeaderValue::from_str(&format!("Basic {}", STANDARD.encode(raw))).unwrap(),
+            );
+            presented_credentials(&headers, None, None)
+        };
+
+        // RFC 6749 §2.3.1: both parts are form-urlencoded before base64
+        let (id, secret) = basic("team%3Abilling:p%40ss+word%25").unwrap();
+        assert_eq!(id, "team:billing");
+        assert_eq!(secret, "p@ss word%");
+
+        // A form client_id is compared with the decoded Basic ID
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            header::AUTHORIZATION,
+            HeaderValue::from_str(&format!("Basic {}", STANDARD.encode("a%2Bb:s"))).unwrap(),
+        );
+        assert!(presented_credentials(&headers, Some("a+b".into()), None).is_ok());
+
+        for malformed in ["billing:%zz", "billing:%4", "%C3%28:s"] {
+            let err = basic(malformed).unwrap_err();
+            assert_eq!(err.code(), "invalid_client", "{malformed}");
+        }
+    }
+
+    /// Percent-encode everything outside the unreserved set
+    fn urlencode(value: &str) -> String {
+        value
+            .bytes()
+            .map(|b| match b {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                    (b as char).to_string()
+                }
+                _ => format!("%{:02X}", b),
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,50 @@
+//! Client registration storage trait and backend implementations
+//!
+//! # Available Backends
+//!
+//! - **Redis** (`cache` feature): Uses `deadpool_redis::Pool`
+//! - **PostgreSQL** (`database` feature): Uses `sqlx::PgPool`
+//! - **Turso** (`turso` feature): Uses `Arc<libsql::Database>`
+
+use async_trait::async_trait;
+
+use super::ClientRegistration;
+use crate::error::Error;
+
+#[cfg(feature = "cache")]
+pub mod redis_impl;
+
+#[cfg(feature = "database")]
+pub mod pg;
+
+#[cfg(feature = "turso")]
+pub mod turso;
+
+/// Trait for client registration persistence backends
+#[async_trait]
+pub trait ClientStorage: Send + Sync {
+    /// Store a new client
+    ///
+    /// Fails if a client with the same `client_id` already exists.
+    async fn create(&self, client: &ClientRegistration) -> Result<(), Error>;
+
+    /// Look up a client by ID
+    async fn get(&self, client_id: &str) -> Result<Option<ClientRegistration>, Error>;
+
+    /// List all clients, ordered by `client_id`
+    async fn list(&self) -> Result<Vec<ClientRegistration>, Error>;
+
+    /// Replace a client's name, secret hash, grants, and revocation flag
+    ///
+    /// Returns an error if the client does not exist.
+    async fn update(&self, client: &ClientRegistration) -> Result<(), Error>;
+
+    /// Delete a client
+    async fn delete(&self, client_id: &str) -> Result<(), Error>;
+
+    /// Create the client table and indexes
+    ///
+    /// Should be called once during application startup. Implementations
+    /// use `IF NOT EXISTS` semantics so this is safe to call repeatedly.
+    async fn initialize(&self) -> Result<(), Error>;
+}
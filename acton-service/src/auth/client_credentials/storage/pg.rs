@@ -0,0 +1,179 @@
+//! PostgreSQL client registration storage backend
+//!
+//! Stores clients in an `oauth_clients` table with scopes and audiences as
+//! JSONB arrays.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use super::ClientStorage;
+use crate::auth::client_credentials::ClientRegistration;
+use crate::error::Error;
+
+type ClientRow = (
+    String,
+    String,
+    String,
+    serde_json::Value,
+    serde_json::Value,
+    bool,
+    bool,
+    DateTime<Utc>,
+);
+
+const CLIENT_COLUMNS: &str =
+    "client_id, name, secret_hash, scopes, audiences, can_introspect, is_revoked, created_at";
+
+/// PostgreSQL-backed client registration storage
+#[derive(Clone)]
+pub struct PgClientStorage {
+    pool: PgPool,
+}
+
+impl PgClientStorage {
+    /// Create a new PostgreSQL client storage
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ClientStorage for PgClientStorage {
+    async fn initialize(&self) -> Result<(), Error> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS oauth_clients (
+                client_id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                secret_hash TEXT NOT NULL,
+                scopes JSONB NOT NULL DEFAULT '[]',
+                audiences JSONB NOT NULL DEFAULT '[]',
+                can_introspect BOOLEAN NOT NULL DEFAULT FALSE,
+                is_revoked BOOLEAN NOT NULL DEFAULT FALSE,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to create oauth_clients table: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn create(&self, client: &ClientRegistration) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO oauth_clients (
+                client_id, name, secret_hash, scopes, audiences,
+                can_introspect, is_revoked, created_at
+            ) VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            ON CONFLICT (client_id) DO NOTHING
+            "#,
+        )
+        .bind(&client.client_id)
+        .bind(&client.name)
+        .bind(&client.secret_hash)
+        .bind(to_json(&client.scopes)?)
+        .bind(to_json(&client.audiences)?)
+        .bind(client.can_introspect)
+        .bind(client.is_revoked)
+        .bind(client.created_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to create OAuth client: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::Conflict(format!(
+                "OAuth client already exists: {}",
+                client.client_id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn get(&self, client_id: &str) -> Result<Option<ClientRegistration>, Error> {
+        let row = sqlx::query_as::<_, ClientRow>(&format!(
+            "SELECT {} FROM oauth_clients WHERE client_id = $1",
+            CLIENT_COLUMNS
+        ))
+        .bind(client_id)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to get OAuth client: {}", e)))?;
+
+        Ok(row.map(row_to_client))
+    }
+
+    async fn list(&self) -> Result<Vec<ClientRegistration>, Error> {
+        let rows = sqlx::query_as::<_, ClientRow>(&format!(
+            "SELECT {} FROM oauth_clients ORDER BY client_id",
+            CLIENT_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to list OAuth clients: {}", e)))?;
+
+        Ok(rows.into_iter().map(row_to_client).collect())
+    }
+
+    async fn update(&self, client: &ClientRegistration) -> Result<(), Error> {
+        let result = sqlx::query(
+            r#"
+            UPDATE oauth_clients
+            SET name = $2, secret_hash = $3, scopes = $4, audiences = $5,
+                can_introspect = $6, is_revoked = $7
+            WHERE client_id = $1
+            "#,
+        )
+        .bind(&client.client_id)
+        .bind(&client.name)
+        .bind(&client.secret_hash)
+        .bind(to_json(&client.scopes)?)
+        .bind(to_json(&client.audiences)?)
+        .bind(client.can_introspect)
+        .bind(client.is_revoked)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to update OAuth client: {}", e)))?;
+
+        if result.rows_affected() == 0 {
+            return Err(Error::NotFound(format!(
+                "OAuth client not found: {}",
+                client.client_id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, client_id: &str) -> Result<(), Error> {
+        sqlx::query("DELETE FROM oauth_clients WHERE client_id = $1")
+            .bind(client_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to delete OAuth client: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+fn to_json(values: &[String]) -> Result<serde_json::Value, Error> {
+    serde_json::to_value(values)
+        .map_err(|e| Error::Internal(format!("Failed to serialize OAuth client grants: {}", e)))
+}
+
+fn row_to_client(row: ClientRow) -> ClientRegistration {
+    let (client_id, name, secret_hash, scopes, audiences, can_introspect, is_revoked, created_at) =
+        row;
+    ClientRegistration {
+        client_id,
+        name,
+        secret_hash,
+        scopes: serde_json::from_value(scopes).unwrap_or_default(),
+        audiences: serde_json::from_value(audiences).unwrap_or_default(),
+        can_introspect,
+        is_revoked,
+        created_at,
+    }
+}
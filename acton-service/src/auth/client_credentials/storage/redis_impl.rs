@@ -0,0 +1,151 @@
+//! Redis client registration storage backend
+//!
+//! Stores each client as JSON under `oauth_client:id:{client_id}` and keeps
+//! the set of client IDs in `oauth_client:all` for listing.
+
+use async_trait::async_trait;
+use deadpool_redis::Pool;
+use redis::AsyncCommands;
+use std::ops::DerefMut;
+
+use super::ClientStorage;
+use crate::auth::client_credentials::ClientRegistration;
+use crate::error::Error;
+
+/// Redis-backed client registration storage
+#[derive(Clone)]
+pub struct RedisClientStorage {
+    pool: Pool,
+    key_prefix: String,
+}
+
+impl RedisClientStorage {
+    /// Create a new Redis client storage
+    pub fn new(pool: Pool) -> Self {
+        Self {
+            pool,
+            key_prefix: "oauth_client".to_string(),
+        }
+    }
+
+    fn id_key(&self, client_id: &str) -> String {
+        format!("{}:id:{}", self.key_prefix, client_id)
+    }
+
+    fn index_key(&self) -> String {
+        format!("{}:all", self.key_prefix)
+    }
+
+    async fn connection(&self) -> Result<deadpool_redis::Connection, Error> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to get Redis connection: {}", e)))
+    }
+}
+
+#[async_trait]
+impl ClientStorage for RedisClientStorage {
+    async fn initialize(&self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    async fn create(&self, client: &ClientRegistration) -> Result<(), Error> {
+        let mut conn = self.connection().await?;
+        let json = to_json(client)?;
+
+        let created: bool = conn
+            .set_nx(self.id_key(&client.client_id), &json)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to create OAuth client: {}", e)))?;
+        if !created {
+            return Err(Error::Conflict(format!(
+                "OAuth client already exists: {}",
+                client.client_id
+            )));
+        }
+
+        conn.sadd::<_, _, ()>(self.index_key(), &client.client_id)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to index OAuth client: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn get(&self, client_id: &str) -> Result<Option<ClientRegistration>, Error> {
+        let mut conn = self.connection().await?;
+
+        let json: Option<String> = conn
+            .get(self.id_key(client_id))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to get OAuth client: {}", e)))?;
+
+        json.map(|j| {
+            serde_json::from_str(&j)
+                .map_err(|e| Error::Internal(format!("Failed to parse OAuth client: {}", e)))
+        })
+        .transpose()
+    }
+
+    async fn list(&self) -> Result<Vec<ClientRegistration>, Error> {
+        let mut conn = self.connection().await?;
+
+        let mut ids: Vec<String> = conn
+            .smembers(self.index_key())
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to list OAuth clients: {}", e)))?;
+        ids.sort();
+        drop(conn);
+
+        let mut clients = Vec::with_capacity(ids.len());
+        for id in ids {
+            if let Some(client) = self.get(&id).await? {
+                clients.push(client);
+            }
+        }
+        Ok(clients)
+    }
+
+    async fn update(&self, client: &ClientRegistration) -> Result<(), Error> {
+        let mut conn = self.connection().await?;
+        let json = to_json(client)?;
+
+        // SET ... XX only replaces an existing key
+        let updated: Option<String> = redis::cmd("SET")
+            .arg(self.id_key(&client.client_id))
+            .arg(&json)
+            .arg("XX")
+            .query_async(conn.deref_mut())
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to update OAuth client: {}", e)))?;
+
+        if updated.is_none() {
+            return Err(Error::NotFound(format!(
+                "OAuth client not found: {}",
+                client.client_id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, client_id: &str) -> Result<(), Error> {
+        let mut conn = self.connection().await?;
+
+        redis::pipe()
+            .atomic()
+            .del(self.id_key(client_id))
+            .ignore()
+            .srem(self.index_key(), client_id)
+            .ignore()
+            .query_async::<()>(conn.deref_mut())
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to delete OAuth client: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+fn to_json(client: &ClientRegistration) -> Result<String, Error> {
+    serde_json::to_string(client)
+        .map_err(|e| Error::Internal(format!("Failed to serialize OAuth client: {}", e)))
+}
@@ -0,0 +1,237 @@
+//! Turso/libsql client registration storage backend
+//!
+//! Stores clients in an `oauth_clients` table with scopes and audiences as
+//! JSON text and timestamps as RFC 3339 strings.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+use super::ClientStorage;
+use crate::auth::client_credentials::ClientRegistration;
+use crate::error::Error;
+
+const CLIENT_COLUMNS: &str =
+    "client_id, name, secret_hash, scopes, audiences, can_introspect, is_revoked, created_at";
+
+/// Turso-backed client registration storage
+pub struct TursoClientStorage {
+    db: Arc<libsql::Database>,
+}
+
+impl TursoClientStorage {
+    /// Create a new Turso client storage
+    pub fn new(db: Arc<libsql::Database>) -> Self {
+        Self { db }
+    }
+
+    /// Get a connection from the database
+    fn connect(&self) -> Result<libsql::Connection, Error> {
+        self.db
+            .connect()
+            .map_err(|e| Error::Internal(format!("Failed to connect for OAuth clients: {}", e)))
+    }
+}
+
+#[async_trait]
+impl ClientStorage for TursoClientStorage {
+    async fn initialize(&self) -> Result<(), Error> {
+        let conn = self.connect()?;
+
+        conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS oauth_clients (
+                client_id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                secret_hash TEXT NOT NULL,
+                scopes TEXT NOT NULL DEFAULT '[]',
+                audiences TEXT NOT NULL DEFAULT '[]',
+                can_introspect INTEGER NOT NULL DEFAULT 0,
+                is_revoked INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL
+            )
+            "#,
+            (),
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to create oauth_clients table: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn create(&self, client: &ClientRegistration) -> Result<(), Error> {
+        let conn = self.connect()?;
+
+        let inserted = conn
+            .execute(
+                r#"
+                INSERT INTO oauth_clients (
+                    client_id, name, secret_hash, scopes, audiences,
+                    can_introspect, is_revoked, created_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
+                ON CONFLICT (client_id) DO NOTHING
+                "#,
+                libsql::params![
+                    client.client_id.clone(),
+                    client.name.clone(),
+                    client.secret_hash.clone(),
+                    to_json(&client.scopes)?,
+                    to_json(&client.audiences)?,
+                    client.can_introspect as i64,
+                    client.is_revoked as i64,
+                    client.created_at.to_rfc3339(),
+                ],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to create OAuth client: {}", e)))?;
+
+        if inserted == 0 {
+            return Err(Error::Conflict(format!(
+                "OAuth client already exists: {}",
+                client.client_id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn get(&self, client_id: &str) -> Result<Option<ClientRegistration>, Error> {
+        let conn = self.connect()?;
+
+        let mut rows = conn
+            .query(
+                &format!(
+                    "SELECT {} FROM oauth_clients WHERE client_id = ?1",
+                    CLIENT_COLUMNS
+                ),
+                libsql::params![client_id.to_string()],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to get OAuth client: {}", e)))?;
+
+        match rows.next().await {
+            Ok(Some(row)) => Ok(Some(row_to_client(&row)?)),
+            Ok(None) => Ok(None),
+            Err(e) => Err(Error::Internal(format!(
+                "Failed to read OAuth client row: {}",
+                e
+            ))),
+        }
+    }
+
+    async fn list(&self) -> Result<Vec<ClientRegistration>, Error> {
+        let conn = self.connect()?;
+
+        let mut rows = conn
+            .query(
+                &format!(
+                    "SELECT {} FROM oauth_clients ORDER BY client_id",
+                    CLIENT_COLUMNS
+                ),
+                (),
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to list OAuth clients: {}", e)))?;
+
+        let mut clients = Vec::new();
+        while let Some(row) = rows
+            .next()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to read OAuth client row: {}", e)))?
+        {
+            clients.push(row_to_client(&row)?);
+        }
+        Ok(clients)
+    }
+
+    async fn update(&self, client: &ClientRegistration) -> Result<(), Error> {
+        let conn = self.connect()?;
+
+        let updated = conn
+            .execute(
+                r#"
+                UPDATE oauth_clients
+                SET name = ?2, secret_hash = ?3, scopes = ?4, audiences = ?5,
+                    can_introspect = ?6, is_revoked = ?7
+                WHERE client_id = ?1
+                "#,
+                libsql::params![
+                    client.client_id.clone(),
+                    client.name.clone(),
+                    client.secret_hash.clone(),
+                    to_json(&client.scopes)?,
+                    to_json(&client.audiences)?,
+                    client.can_introspect as i64,
+                    client.is_revoked as i64,
+                ],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to update OAuth client: {}", e)))?;
+
+        if updated == 0 {
+            return Err(Error::NotFound(format!(
+                "OAuth client not found: {}",
+                client.client_id
+            )));
+        }
+        Ok(())
+    }
+
+    async fn delete(&self, client_id: &str) -> Result<(), Error> {
+        let conn = self.connect()?;
+
+        conn.execute(
+            "DELETE FROM oauth_clients WHERE client_id = ?1",
+            libsql::params![client_id.to_string()],
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to delete OAuth client: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+fn to_json(values: &[String]) -> Result<String, Error> {
+    serde_json::to_string(values)
+        .map_err(|e| Error::Internal(format!("Failed to serialize OAuth client grants: {}", e)))
+}
+
+fn row_to_client(row: &libsql::Row) -> Result<ClientRegistration, Error> {
+    let client_id: String = row
+        .get(0)
+        .map_err(|e| Error::Internal(format!("Failed to read client_id: {}", e)))?;
+    let name: String = row
+        .get(1)
+        .map_err(|e| Error::Internal(format!("Failed to read name: {}", e)))?;
+    let secret_hash: String = row
+        .get(2)
+        .map_err(|e| Error::Internal(format!("Failed to read secret_hash: {}", e)))?;
+    let scopes: String = row
+        .get(3)
+        .map_err(|e| Error::Internal(format!("Failed to read scopes: {}", e)))?;
+    let audiences: String = row
+        .get(4)
+        .map_err(|e| Error::Internal(format!("Failed to read audiences: {}", e)))?;
+    let can_introspect: i64 = row
+        .get(5)
+        .map_err(|e| Error::Internal(format!("Failed to read can_introspect: {}", e)))?;
+    let is_revoked: i64 = row
+        .get(6)
+        .map_err(|e| Error::Internal(format!("Failed to read is_revoked: {}", e)))?;
+    let created_at_str: String = row
+        .get(7)
+        .map_err(|e| Error::Internal(format!("Failed to read created_at: {}", e)))?;
+    let created_at = DateTime::parse_from_rfc3339(&created_at_str)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| Error::Internal(format!("Failed to parse created_at: {}", e)))?;
+
+    Ok(ClientRegistration {
+        client_id,
+        name,
+        secret_hash,
+        scopes: serde_json::from_str(&scopes).unwrap_or_default(),
+        audiences: serde_json::from_str(&audiences).unwrap_or_default(),
+        can_introspect: can_introspect != 0,
+        is_revoked: is_revoked != 0,
+        created_at,
+    })
+}
@@ -46,6 +46,14 @@ pub struct AuthConfig {
     /// Key rotation configuration (NIST SC-12)
     #[serde(default)]
     pub key_rotation: Option<KeyRotationConfig>,
+
+    /// Pre-built token endpoint configuration (used by `auth_routes()`)
+    #[serde(default)]
+    pub routes: AuthRoutesConfig,
+
+    /// OAuth 2.0 client-credentials issuer configuration
+    #[serde(default)]
+    pub client_credentials: Option<ClientCredentialsConfig>,
 }
 
 /// Password hashing configuration following OWASP guidelines
@@ -65,9 +73,47 @@ pub struct PasswordConfig {
     #[serde(default = "default_parallelism")]
     pub parallelism: u32,
 
-    /// Minimum password length (default: 8)
+    /// Minimum password length in characters (default: 8)
     #[serde(default = "default_min_length")]
     pub min_password_length: usize,
+
+    /// Maximum password length in characters (default: 128)
+    #[serde(default = "default_max_length")]
+    pub max_password_length: usize,
+
+    /// Require at least one uppercase letter (default: false)
+    #[serde(default)]
+    pub require_uppercase: bool,
+
+    /// Require at least one lowercase letter (default: false)
+    #[serde(default)]
+    pub require_lowercase: bool,
+
+    /// Require at least one digit (default: false)
+    #[serde(default)]
+    pub require_digit: bool,
+
+    /// Require at least one character that is not a letter or digit (default: false)
+    #[serde(default)]
+    pub require_symbol: bool,
+
+    /// Words a password must not contain, compared case-insensitively
+    ///
+    /// Checked in addition to the per-call context (email, username, service
+    /// name) passed to `PasswordHasher::check_policy`.
+    #[serde(default)]
+    pub blocked_words: Vec<String>,
+
+    /// Offline breached-password check (disabled when absent)
+    #[serde(default)]
+    pub breached: Option<BreachedPasswordConfig>,
+
+    /// Number of previous passwords an account may not reuse (default: 0 = no history)
+    ///
+    /// Counts the current password, so `history_size = 5` rejects the current
+    /// password and the four before it.
+    #[serde(default)]
+    pub history_size: usize,
 }
 
 impl Default for PasswordConfig {
@@ -77,10 +123,51 @@ impl Default for PasswordConfig {
             time_cost: default_time_cost(),
             parallelism: default_parallelism(),
             min_password_length: default_min_length(),
+            max_password_length: default_max_length(),
+            require_uppercase: false,
+            require_lowercase: false,
+            require_digit: false,
+            require_symbol: false,
+            blocked_words: Vec::new(),
+            breached: None,
+            history_size: 0,
         }
     }
 }
 
+/// Offline breached-password corpus configuration
+///
+/// Passwords are looked up by SHA-1 digest, either in a directory of
+/// k-anonymity range files (the Have I Been Pwned layout, one `{PREFIX}.txt`
+/// file per 5-hex-character prefix holding `SUFFIX:COUNT` lines) or in a
+/// bloom filter built with `BloomFilter`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BreachedPasswordConfig {
+    /// Corpus format (default: "sha1-prefix")
+    #[serde(default)]
+    pub format: BreachedPasswordFormat,
+
+    /// Path to the range-file directory or bloom filter file
+    pub path: PathBuf,
+
+    /// Minimum breach count for a range-file match to reject a password (default: 1)
+    ///
+    /// Ignored for bloom filters, which carry no counts.
+    #[serde(default = "default_min_occurrences")]
+    pub min_occurrences: u64,
+}
+
+/// Format of the breached-password corpus
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum BreachedPasswordFormat {
+    /// Directory of SHA-1 prefix range files
+    #[default]
+    Sha1Prefix,
+    /// Bloom filter file of SHA-1 digests
+    Bloom,
+}
+
 /// Token generation configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenGenerationConfig {
@@ -212,9 +299,13 @@ pub struct ApiKeyConfig {
     #[serde(default)]
     pub default_rate_limit: Option<u32>,
 
-    /// Storage backend: "redis", "postgres", or "turso"
+    /// Storage backend: "redis", "postgres", "turso", or "surrealdb"
     #[serde(default = "default_storage_backend")]
     pub storage: String,
+
+    /// Path prefixes that bypass API key authentication
+    #[serde(default)]
+    pub public_paths: Vec<String>,
 }
 
 impl Default for ApiKeyConfig {
@@ -225,6 +316,86 @@ impl Default for ApiKeyConfig {
             header: default_api_key_header(),
             default_rate_limit: None,
             storage: default_storage_backend(),
+            public_paths: Vec::new(),
+        }
+    }
+}
+
+/// Pre-built token endpoint configuration
+///
+/// Controls which routes `auth_routes()` (requires `account-handlers`)
+/// mounts and where. Refresh rotation and reuse detection follow
+/// [`RefreshTokenConfig`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AuthRoutesConfig {
+    /// Path prefix for the routes (default: "/auth")
+    #[serde(default = "default_auth_routes_prefix")]
+    pub prefix: String,
+
+    /// Mount `POST {prefix}/token` and `POST {prefix}/token/mfa` (default: true)
+    #[serde(default = "default_true")]
+    pub login_enabled: bool,
+
+    /// Mount `POST {prefix}/refresh` (default: true)
+    #[serde(default = "default_true")]
+    pub refresh_enabled: bool,
+
+    /// Mount `POST {prefix}/logout` (default: true)
+    #[serde(default = "default_true")]
+    pub logout_enabled: bool,
+
+    /// Mount `POST {prefix}/revoke` (default: true)
+    #[serde(default = "default_true")]
+    pub revoke_enabled: bool,
+}
+
+impl Default for AuthRoutesConfig {
+    fn default() -> Self {
+        Self {
+            prefix: default_auth_routes_prefix(),
+            login_enabled: true,
+            refresh_enabled: true,
+            logout_enabled: true,
+            revoke_enabled: true,
+        }
+    }
+}
+
+/// OAuth 2.0 client-credentials issuer configuration
+///
+/// Used by [`ClientCredentialsServer`](super::client_credentials::ClientCredentialsServer)
+/// to issue access tokens to registered service clients.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ClientCredentialsConfig {
+    /// Enable the client-credentials issuer (default: true)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Path of the token endpoint (default: "/oauth/token")
+    #[serde(default = "default_client_token_path")]
+    pub token_path: String,
+
+    /// Path of the RFC 7662 introspection endpoint (default: "/oauth/introspect")
+    #[serde(default = "default_client_introspection_path")]
+    pub introspection_path: String,
+
+    /// Access token lifetime in seconds (default: the generator's lifetime)
+    #[serde(default)]
+    pub access_token_lifetime_secs: Option<i64>,
+
+    /// Storage backend: "redis", "postgres", or "turso"
+    #[serde(default = "default_storage_backend")]
+    pub storage: String,
+}
+
+impl Default for ClientCredentialsConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            token_path: default_client_token_path(),
+            introspection_path: default_client_introspection_path(),
+            access_token_lifetime_secs: None,
+            storage: default_storage_backend(),
         }
     }
 }
@@ -274,6 +445,15 @@ pub struct OAuthProviderConfig {
     /// Userinfo endpoint (for custom OIDC providers)
     #[serde(default)]
     pub userinfo_endpoint: Option<String>,
+
+    /// OpenID Connect issuer URL
+    ///
+    /// When set, [`OidcProvider`](crate::auth::oauth::OidcProvider) discovers
+    /// the endpoints and signing keys from
+    /// `{issuer}/.well-known/openid-configuration`, so the endpoint fields
+    /// above can be left empty.
+    #[serde(default)]
+    pub issuer: Option<String>,
 }
 
 // Default value functions
@@ -294,6 +474,14 @@ fn default_min_length() -> usize {
     8
 }
 
+fn default_max_length() -> usize {
+    128
+}
+
+fn default_min_occurrences() -> u64 {
+    1
+}
+
 fn default_access_token_lifetime() -> i64 {
     900 // 15 minutes
 }
@@ -326,6 +514,18 @@ fn default_api_key_header() -> String {
     "X-API-Key".to_string()
 }
 
+fn default_auth_routes_prefix() -> String {
+    "/auth".to_string()
+}
+
+fn default_client_token_path() -> String {
+    "/oauth/token".to_string()
+}
+
+fn default_client_introspection_path() -> String {
+    "/oauth/introspect".to_string()
+}
+
 #[cfg(feature = "oauth")]
 fn default_oauth_state_ttl() -> u64 {
     600 // 10 minutes
@@ -342,6 +542,22 @@ mod tests {
         assert_eq!(config.time_cost, 3);
         assert_eq!(config.parallelism, 4);
         assert_eq!(config.min_password_length, 8);
+        assert_eq!(config.max_password_length, 128);
+        assert!(!config.require_uppercase);
+        assert!(config.breached.is_none());
+        assert_eq!(config.history_size, 0);
+    }
+
+    #[test]
+    fn test_breached_password_config_parsing() {
+        let config: BreachedPasswordConfig =
+            serde_json::from_str(r#"{"path": "/var/lib/pwned"}"#).unwrap();
+        assert_eq!(config.format, BreachedPasswordFormat::Sha1Prefix);
+        assert_eq!(config.min_occurrences, 1);
+
+        let config: BreachedPasswordConfig =
+            serde_json::from_str(r#"{"format": "bloom", "path": "/var/lib/pwned.bloom"}"#).unwrap();
+        assert_eq!(config.format, BreachedPasswordFormat::Bloom);
     }
 
     #[test]
@@ -368,4 +584,24 @@ mod tests {
         assert_eq!(config.prefix, "sk_live");
         assert_eq!(config.header, "X-API-Key");
     }
+
+    #[test]
+    fn test_auth_routes_config_defaults() {
+        let config: AuthRoutesConfig = serde_json::from_str("{}").unwrap();
+        assert_eq!(config.prefix, "/auth");
+        assert!(config.login_enabled);
+        assert!(config.refresh_enabled);
+        assert!(config.logout_enabled);
+        assert!(config.revoke_enabled);
+    }
+
+    #[test]
+    fn test_client_credentials_config_defaults() {
+        let config: ClientCredentialsConfig = serde_json::from_str("{}").unwrap();
+        assert!(config.enabled);
+        assert_eq!(config.token_path, "/oauth/token");
+        assert_eq!(config.introspection_path, "/oauth/introspect");
+        assert_eq!(config.access_token_lifetime_secs, None);
+        assert_eq!(config.storage, "redis");
+    }
 }
@@ -15,7 +15,7 @@
 
 use base64::engine::general_purpose::STANDARD as BASE64;
 use base64::Engine;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::RwLock;
@@ -47,6 +47,9 @@ pub struct CachedKey {
 
     /// Current lifecycle status
     pub status: KeyStatus,
+
+    /// When this key became the active signing key
+    pub activated_at: Option<DateTime<Utc>>,
 }
 
 // ---------------------------------------------------------------------------
@@ -328,6 +331,11 @@ impl KeyManager {
         &self.service_name
     }
 
+    /// Get the rotation configuration this manager was created with
+    pub fn config(&self) -> &KeyRotationConfig {
+        &self.config
+    }
+
     /// Get a reference to the underlying storage backend
     ///
     /// Useful for service builder initialization and direct storage operations
@@ -366,6 +374,7 @@ fn decode_key_metadata(meta: &SigningKeyMetadata) -> Result<CachedKey, Error> {
         format: meta.format,
         key_material,
         status: meta.status,
+        activated_at: meta.activated_at,
     })
 }
 
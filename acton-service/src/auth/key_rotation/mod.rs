@@ -11,6 +11,9 @@
 //! - **Draining**: No longer signs. Still validates during the drain window.
 //! - **Retired**: Metadata retained for audit trail only.
 //!
+//! Public keys for Active and Draining keys are served at
+//! `/.well-known/jwks.json` and `/.well-known/paserk.json` (see [`publish`]).
+//!
 //! # Feature Interactions
 //!
 //! - `auth` alone: Key rotation types and configuration available
@@ -28,6 +31,7 @@ pub mod agent;
 pub mod config;
 pub mod key_metadata;
 pub mod manager;
+pub mod publish;
 pub mod storage;
 
 pub use agent::{CheckRotation, ForceRotation, KeyRotationAgent};
@@ -36,6 +40,7 @@ pub use key_metadata::{
     KeyFormat, KeyStatus, ParseKeyFormatError, ParseKeyStatusError, SigningKeyMetadata,
 };
 pub use manager::{CachedKey, KeyManager};
+pub use publish::{KeyPublisher, PaserkKey, PaserkSet, JWKS_PATH, PASERK_PATH};
 pub use storage::KeyRotationStorage;
 
 #[cfg(feature = "database")]
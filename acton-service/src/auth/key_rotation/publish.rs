@@ -0,0 +1,320 @@
+//! Publishing verification keys to downstream services
+//!
+//! Serves the public half of every key the [`KeyManager`] still verifies with
+//! (Active + Draining), so services that validate tokens can fetch keys
+//! instead of having key files copied to them:
+//!
+//! - `GET /.well-known/jwks.json`: RS256/ES256 JWT keys as a JWK Set (requires `jwt`)
+//! - `GET /.well-known/paserk.json`: PASETO v4.public keys as PASERK strings
+//!
+//! Symmetric PASETO v4.local keys are never published.
+//!
+//! # Caching
+//!
+//! Responses carry `Cache-Control: public, max-age=N` where `N` is the time
+//! left until the active key is due for rotation, capped at the agent's
+//! `check_interval_secs`. A verifier that honours the header therefore never
+//! holds a key set older than the current signing key, and a forced rotation
+//! or a removed key reaches it within one check interval. Verifiers should
+//! still refetch when they see an unknown `kid`.
+
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::header,
+    response::{IntoResponse, Response},
+    routing::get,
+    Json, Router,
+};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::config::KeyRotationConfig;
+use super::key_metadata::{KeyFormat, KeyStatus};
+use super::manager::{CachedKey, KeyManager};
+use crate::error::Error;
+
+/// Path of the JWK Set endpoint
+pub const JWKS_PATH: &str = "/.well-known/jwks.json";
+
+/// Path of the PASERK listing endpoint
+pub const PASERK_PATH: &str = "/.well-known/paserk.json";
+
+/// A published PASETO v4.public verification key
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaserkKey {
+    /// Key identifier from the rotation system
+    pub kid: String,
+
+    /// The key in PASERK form (`k4.public.<base64url>`)
+    pub paserk: String,
+
+    /// Whether the key still signs (`Active`) or only verifies (`Draining`)
+    pub status: KeyStatus,
+}
+
+/// The set of published PASETO verification keys
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PaserkSet {
+    /// Published keys, ordered by `kid`
+    pub keys: Vec<PaserkKey>,
+}
+
+/// Serves the [`KeyManager`]'s verification keys over HTTP
+///
+/// `ServiceBuilder` mounts [`router`](Self::router) automatically when key
+/// rotation is enabled; use this type directly to mount the routes elsewhere.
+#[derive(Clone)]
+pub struct KeyPublisher {
+    manager: Arc<KeyManager>,
+}
+
+impl KeyPublisher {
+    /// Create a publisher for the given key manager
+    pub fn new(manager: Arc<KeyManager>) -> Self {
+        Self { manager }
+    }
+
+    /// Build the JWK Set of all JWT verification keys
+    ///
+    /// Keys whose material holds no private key to derive the public
+    /// parameters from are skipped with a warning.
+    #[cfg(feature = "jwt")]
+    pub async fn jwks(&self) -> Result<jsonwebtoken::jwk::JwkSet, Error> {
+        let keys = self.verification_keys().await?;
+        let keys = keys
+            .iter()
+            .filter_map(|key| match jwk_for_key(key) {
+                Ok(jwk) => jwk,
+                Err(e) => {
+                    tracing::warn!(kid = %key.kid, "Not publishing key in JWKS: {}", e);
+                    None
+                }
+            })
+            .collect();
+        Ok(jsonwebtoken::jwk::JwkSet { keys })
+    }
+
+    /// Build the PASERK listing of all PASETO v4.public verification keys
+    pub async fn paserk_set(&self) -> Result<PaserkSet, Error> {
+        let keys = self.verification_keys().await?;
+        let keys = keys
+            .iter()
+            .filter(|key| key.format == KeyFormat::PasetoV4Public)
+            .filter_map(|key| match ed25519_public_key(&key.key_material) {
+                Some(public_key) => Some(PaserkKey {
+                    kid: key.kid.clone(),
+                    paserk: paserk_public(public_key),
+                    status: key.status,
+                }),
+                None => {
+                    tracing::warn!(
+                        kid = %key.kid,
+                        len = key.key_material.len(),
+                        "Not publishing PASETO key: material is not a 64-byte Ed25519 keypair"
+                    );
+                    None
+                }
+            })
+            .collect();
+        Ok(PaserkSet { keys })
+    }
+
+    /// Build the routes serving [`JWKS_PATH`] and [`PASERK_PATH`]
+    pub fn router(self) -> Router {
+        let router = Router::new().route(PASERK_PATH, get(paserk_handler));
+        #[cfg(feature = "jwt")]
+        let router = router.route(JWKS_PATH, get(jwks_handler));
+        router.with_state(self)
+    }
+
+    /// The `Cache-Control` value for the current rotation schedule
+    async fn cache_control(&self) -> Result<String, Error> {
+        let activated_at = self
+            .verification_keys()
+            .await?
+            .iter()
+            .find(|key| key.status == KeyStatus::Active)
+            .and_then(|key| key.activated_at);
+        let max_age = cache_max_age(activated_at, self.manager.config(), Utc::now());
+        Ok(format!("public, max-age={max_age}"))
+    }
+
+    /// Verification keys in a stable order
+    async fn verification_keys(&self) -> Result<Vec<CachedKey>, Error> {
+        let mut keys = self.manager.get_all_verification_keys().await?;
+        keys.sort_by(|a, b| a.kid.cmp(&b.kid));
+        Ok(keys)
+    }
+}
+
+#[cfg(feature = "jwt")]
+async fn jwks_handler(State(publisher): State<KeyPublisher>) -> Result<Response, Error> {
+    let jwks = publisher.jwks().await?;
+    let cache_control = publisher.cache_control().await?;
+    Ok(([(header::CACHE_CONTROL, cache_control)], Json(jwks)).into_response())
+}
+
+async fn paserk_handler(State(publisher): State<KeyPublisher>) -> Result<Response, Error> {
+    let paserk = publisher.paserk_set().await?;
+    let cache_control = publisher.cache_control().await?;
+    Ok(([(header::CACHE_CONTROL, cache_control)], Json(paserk)).into_response())
+}
+
+/// Seconds a published key set may be cached: until the next scheduled
+/// rotation, but never longer than one check interval
+///
+/// The rotation period can be months, and a compromised key pulled early
+/// must not stay in verifiers' caches that long. Without an active key
+/// (before bootstrap) the set is expected to change at the next agent tick.
+fn cache_max_age(
+    activated_at: Option<DateTime<Utc>>,
+    config: &KeyRotationConfig,
+    now: DateTime<Utc>,
+) -> u64 {
+    match activated_at {
+        Some(activated_at) => {
+            let rotation_period = i64::try_from(config.rotation_period_secs).unwrap_or(i64::MAX);
+            let rotates_at = activated_at.timestamp().saturating_add(rotation_period);
+            u64::try_from(rotates_at - now.timestamp())
+                .unwrap_or(0)
+                .min(config.check_interval_secs)
+        }
+        None => config.check_interval_secs,
+    }
+}
+
+/// The public half of Ed25519 key material
+///
+/// Rotated v4.public keys are stored as 64-byte keypairs (secret || public),
+/// the only form the signer accepts. Anything else is refused: 32 bytes is
+/// most likely a bare secret seed, which must never be published.
+fn ed25519_public_key(material: &[u8]) -> Option<&[u8]> {
+    match material.len() {
+        64 => Some(&material[32..]),
+        _ => None,
+    }
+}
+
+/// Encode an Ed25519 public key as a PASERK `k4.public` string
+pub fn paserk_public(public_key: &[u8]) -> String {
+    format!("k4.public.{}", URL_SAFE_NO_PAD.encode(public_key))
+}
+
+/// Derive the public JWK for a rotated JWT signing key
+///
+/// Returns `Ok(None)` for keys that are not JWT keys.
+#[cfg(feature = "jwt")]
+pub fn jwk_for_key(key: &CachedKey) -> Result<Option<jsonwebtoken::jwk::Jwk>, Error> {
+    use jsonwebtoken::jwk::{Jwk, KeyAlgorithm, PublicKeyUse};
+    use jsonwebtoken::{Algorithm, EncodingKey};
+
+    let (encoding_key, algorithm, key_algorithm) = match key.format {
+        KeyFormat::JwtRs256 => (
+            EncodingKey::from_rsa_pem(&key.key_material),
+            Algorithm::RS256,
+            KeyAlgorithm::RS256,
+        ),
+        KeyFormat::JwtEs256 => (
+            EncodingKey::from_ec_pem(&key.key_material),
+            Algorithm::ES256,
+            KeyAlgorithm::ES256,
+        ),
+        KeyFormat::PasetoV4Local | KeyFormat::PasetoV4Public => return Ok(None),
+    };
+    let encoding_key = encoding_key.map_err(|e| Error::Jwt(Box::new(e)))?;
+
+    let mut jwk =
+        Jwk::from_encoding_key(&encoding_key, algorithm).map_err(|e| Error::Jwt(Box::new(e)))?;
+    jwk.common.key_id = Some(key.kid.clone());
+    jwk.common.public_key_use = Some(PublicKeyUse::Signature);
+    jwk.common.key_algorithm = Some(key_algorithm);
+    Ok(Some(jwk))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "jwt")]
+    fn cached(format: KeyFormat, key_material: Vec<u8>) -> CachedKey {
+        CachedKey {
+            kid: "kid-1".to_string(),
+            format,
+            key_material,
+            status: KeyStatus::Active,
+            activated_at: None,
+        }
+    }
+
+    #[test]
+    fn test_paserk_uses_public_half_of_keypair() {
+        let mut keypair = vec![1u8; 32];
+        keypair.extend([2u8; 32]);
+        let public_key = ed25519_public_key(&keypair).unwrap();
+        assert_eq!(public_key, [2u8; 32]);
+        assert_eq!(
+            paserk_public(public_key),
+            format!("k4.public.{}", URL_SAFE_NO_PAD.encode([2u8; 32]))
+        );
+        assert!(ed25519_public_key(&[0u8; 16]).is_none());
+    }
+
+    #[test]
+    fn test_bare_32_byte_material_is_never_published() {
+        // Could be a secret seed; echoing it would leak the signing key
+        assert!(ed25519_public_key(&[3u8; 32]).is_none());
+    }
+
+    #[test]
+    fn test_cache_max_age_follows_rotation_schedule() {
+        let config = KeyRotationConfig {
+            rotation_period_secs: 3600,
+            check_interval_secs: 60,
+            ..KeyRotationConfig::default()
+        };
+        let now = Utc::now();
+
+        // Rotation is far off: capped at one check interval
+        let activated = now - chrono::Duration::seconds(600);
+        assert_eq!(cache_max_age(Some(activated), &config, now), 60);
+
+        // Rotation is sooner than the next check
+        let activated = now - chrono::Duration::seconds(3570);
+        assert_eq!(cache_max_age(Some(activated), &config, now), 30);
+
+        // Overdue for rotation: don't cache at all
+        let overdue = now - chrono::Duration::seconds(7200);
+        assert_eq!(cache_max_age(Some(overdue), &config, now), 0);
+
+        // No active key yet
+        assert_eq!(cache_max_age(None, &config, now), 60);
+    }
+
+    #[cfg(feature = "jwt")]
+    #[test]
+    fn test_jwk_for_es256_key() {
+        let key_pair = rcgen::KeyPair::generate().unwrap();
+        let key = cached(KeyFormat::JwtEs256, key_pair.serialize_pem().into_bytes());
+
+        let jwk = jwk_for_key(&key).unwrap().unwrap();
+        assert_eq!(jwk.common.key_id.as_deref(), Some("kid-1"));
+        assert!(matches!(
+            jwk.algorithm,
+            jsonwebtoken::jwk::AlgorithmParameters::EllipticCurve(_)
+        ));
+        // Only the public point is published
+        let json = serde_json::to_value(&jwk).unwrap();
+        assert!(json.get("d").is_none());
+    }
+
+    #[cfg(feature = "jwt")]
+    #[test]
+    fn test_symmetric_keys_are_never_published() {
+        let key = cached(KeyFormat::PasetoV4Local, vec![7u8; 32]);
+        assert!(jwk_for_key(&key).unwrap().is_none());
+    }
+}
@@ -2,7 +2,7 @@
 //!
 //! This module complements the existing token validation middleware with
 //! token generation capabilities, password hashing, API key management,
-//! and OAuth/OIDC support.
+//! OAuth/OIDC support, and a client-credentials issuer for service tokens.
 //!
 //! # Features
 //!
@@ -44,20 +44,26 @@ pub mod api_keys;
 // Key rotation (NIST SC-12)
 pub mod key_rotation;
 
+// OAuth 2.0 client-credentials issuer
+pub mod client_credentials;
+
 // OAuth/OIDC providers (requires oauth feature)
 #[cfg(feature = "oauth")]
 pub mod oauth;
 
 // Re-exports for convenience
 pub use config::{
-    AuthConfig, PasetoGenerationConfig, PasswordConfig, RefreshTokenConfig, TokenGenerationConfig,
+    ApiKeyConfig, AuthConfig, AuthRoutesConfig, BreachedPasswordConfig, BreachedPasswordFormat,
+    ClientCredentialsConfig, PasetoGenerationConfig, PasswordConfig, RefreshTokenConfig,
+    TokenGenerationConfig,
 };
 
 #[cfg(feature = "oauth")]
-pub use config::{ApiKeyConfig, OAuthConfig, OAuthProviderConfig};
+pub use config::{OAuthConfig, OAuthProviderConfig};
 
-pub use password::PasswordHasher;
+pub use password::{BloomFilter, PasswordHasher, PasswordPolicyError, PasswordViolation};
 
+pub use tokens::issuer::{RefreshLookup, TokenIssuer};
 pub use tokens::paseto_generator::PasetoGenerator;
 pub use tokens::refresh::{RefreshTokenData, RefreshTokenMetadata, RefreshTokenStorage};
 pub use tokens::{TokenGenerator, TokenPair};
@@ -78,7 +84,7 @@ pub use tokens::refresh::SurrealDbRefreshStorage;
 pub use tokens::jwt_generator::JwtGenerator;
 
 // API key exports
-pub use api_keys::{ApiKey, ApiKeyGenerator, ApiKeyStorage};
+pub use api_keys::{ApiKey, ApiKeyGenerator, ApiKeyQuota, ApiKeyStorage, ApiKeyUsage};
 
 #[cfg(feature = "cache")]
 pub use api_keys::RedisApiKeyStorage;
@@ -96,7 +102,7 @@ pub use api_keys::SurrealDbApiKeyStorage;
 #[cfg(feature = "oauth")]
 pub use oauth::{
     generate_state, CustomOidcConfig, CustomOidcProvider, GitHubProvider, GoogleProvider,
-    OAuthProvider, OAuthStateManager, OAuthTokens, OAuthUserInfo, StateData,
+    OAuthProvider, OAuthStateManager, OAuthTokens, OAuthUserInfo, OidcProvider, StateData,
 };
 
 #[cfg(all(feature = "oauth", feature = "cache"))]
@@ -104,8 +110,8 @@ pub use oauth::RedisOAuthStateManager;
 
 // Key rotation exports
 pub use key_rotation::{
-    CachedKey, KeyFormat, KeyManager, KeyRotationConfig, KeyRotationStorage, KeyStatus,
-    SigningKeyMetadata,
+    CachedKey, KeyFormat, KeyManager, KeyPublisher, KeyRotationConfig, KeyRotationStorage,
+    KeyStatus, PaserkSet, SigningKeyMetadata,
 };
 
 #[cfg(feature = "database")]
@@ -116,3 +122,18 @@ pub use key_rotation::TursoKeyRotationStorage;
 
 #[cfg(feature = "surrealdb")]
 pub use key_rotation::SurrealKeyRotationStorage;
+
+// Client-credentials issuer exports
+pub use client_credentials::{
+    ClientCredentialsError, ClientCredentialsServer, ClientRegistration, ClientStorage,
+    ClientTokenResponse, IntrospectionResponse, NewClient,
+};
+
+#[cfg(feature = "cache")]
+pub use client_credentials::RedisClientStorage;
+
+#[cfg(feature = "database")]
+pub use client_credentials::PgClientStorage;
+
+#[cfg(feature = "turso")]
+pub use client_credentials::TursoClientStorage;
@@ -40,6 +40,7 @@ pub use providers::{
     custom::{CustomOidcConfig, CustomOidcProvider},
     github::GitHubProvider,
     google::GoogleProvider,
+    oidc::OidcProvider,
 };
 
 // State management
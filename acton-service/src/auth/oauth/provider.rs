@@ -3,6 +3,7 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 
+use super::state::StateData;
 use crate::error::Error;
 
 /// OAuth tokens received from a provider
@@ -85,4 +86,27 @@ pub trait OAuthProvider: Send + Sync {
     ///
     /// * `refresh_token` - Refresh token from the provider
     async fn refresh_token(&self, refresh_token: &str) -> Result<OAuthTokens, Error>;
+
+    /// Generate the authorization URL for a login started with `data`
+    ///
+    /// Providers that support PKCE and nonces derive the code challenge and
+    /// nonce from `data`; the default ignores them.
+    fn authorization_url_for(&self, state: &str, _data: &StateData, scopes: &[String]) -> String {
+        self.authorization_url(state, scopes)
+    }
+
+    /// Finish a login: exchange the code and identify the user
+    ///
+    /// `data` is the state consumed in the callback. The default exchanges
+    /// the code and calls the userinfo endpoint; OIDC providers verify the
+    /// ID token instead.
+    async fn complete_login(
+        &self,
+        code: &str,
+        _data: &StateData,
+    ) -> Result<(OAuthTokens, OAuthUserInfo), Error> {
+        let tokens = self.exchange_code(code).await?;
+        let user_info = self.get_user_info(&tokens.access_token).await?;
+        Ok((tokens, user_info))
+    }
 }
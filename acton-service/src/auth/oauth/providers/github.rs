@@ -237,6 +237,7 @@ mod tests {
             authorization_endpoint: None,
             token_endpoint: None,
             userinfo_endpoint: None,
+            issuer: None,
         };
 
         let provider = GitHubProvider::new(&config).unwrap();
@@ -257,6 +258,7 @@ mod tests {
             authorization_endpoint: None,
             token_endpoint: None,
             userinfo_endpoint: None,
+            issuer: None,
         };
 
         let provider = GitHubProvider::new(&config).unwrap();
@@ -193,6 +193,7 @@ mod tests {
             authorization_endpoint: None,
             token_endpoint: None,
             userinfo_endpoint: None,
+            issuer: None,
         };
 
         let provider = GoogleProvider::new(&config).unwrap();
@@ -214,6 +215,7 @@ mod tests {
             authorization_endpoint: None,
             token_endpoint: None,
             userinfo_endpoint: None,
+            issuer: None,
         };
 
         let provider = GoogleProvider::new(&config).unwrap();
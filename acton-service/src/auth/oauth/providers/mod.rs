@@ -1,11 +1,14 @@
 //! OAuth provider implementations
 //!
-//! Built-in support for Google, GitHub, and custom OIDC providers.
+//! Built-in support for Google, GitHub, custom OAuth2 providers, and any
+//! OpenID Connect provider via discovery.
 
 pub mod custom;
 pub mod github;
 pub mod google;
+pub mod oidc;
 
 pub use custom::{CustomOidcConfig, CustomOidcProvider};
 pub use github::GitHubProvider;
 pub use google::GoogleProvider;
+pub use oidc::OidcProvider;
@@ -0,0 +1,600 @@
+//! Generic OpenID Connect provider configured by discovery
+//!
+//! Reads the provider's endpoints and signing keys from
+//! `{issuer}/.well-known/openid-configuration`, so any compliant IdP (Okta,
+//! Entra ID, Keycloak, ...) works from just an issuer URL and client
+//! credentials.
+//!
+//! Logins started with [`StateData::with_pkce`] use PKCE S256 and a nonce.
+//! [`complete_login`](OAuthProvider::complete_login) then verifies the ID
+//! token's signature against the provider's JWKS and checks `iss`, `aud`,
+//! `exp` and `nonce` before mapping its claims into [`OAuthUserInfo`].
+
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use openidconnect::core::{
+    CoreAuthenticationFlow, CoreClient, CoreJwsSigningAlgorithm, CoreProviderMetadata,
+};
+use openidconnect::{
+    AuthorizationCode, ClaimsVerificationError, ClientId, ClientSecret, CsrfToken,
+    EndpointMaybeSet, EndpointNotSet, EndpointSet, IssuerUrl, Nonce, OAuth2TokenResponse,
+    PkceCodeChallenge, PkceCodeVerifier, RedirectUrl, RefreshToken, Scope,
+    SignatureVerificationError, TokenResponse,
+};
+use reqwest::Client as HttpClient;
+
+use crate::auth::config::OAuthProviderConfig;
+use crate::error::Error;
+
+use super::super::{generate_state, OAuthProvider, OAuthTokens, OAuthUserInfo, StateData};
+
+/// Client as built from discovered provider metadata
+type DiscoveredClient = CoreClient<
+    EndpointSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointNotSet,
+    EndpointMaybeSet,
+    EndpointMaybeSet,
+>;
+
+/// Minimum time between re-discoveries triggered by unknown signing keys
+const MIN_REDISCOVERY_INTERVAL: Duration = Duration::from_secs(60);
+
+/// What discovery yields for one provider
+#[derive(Clone)]
+struct Discovered {
+    client: DiscoveredClient,
+    signing_algs: Vec<CoreJwsSigningAlgorithm>,
+    userinfo_endpoint: Option<String>,
+}
+
+/// OpenID Connect provider driven by `.well-known/openid-configuration`
+#[derive(Clone)]
+pub struct OidcProvider {
+    name: String,
+    issuer: IssuerUrl,
+    client_id: String,
+    client_secret: String,
+    redirect_uri: RedirectUrl,
+    default_scopes: Vec<String>,
+    http_client: HttpClient,
+    discovered: Arc<RwLock<Discovered>>,
+    last_discovery: Arc<Mutex<Instant>>,
+}
+
+impl OidcProvider {
+    /// Discover a provider from configuration
+    ///
+    /// `config.issuer` must be set; `name` identifies the provider in
+    /// [`OAuthUserInfo::provider`] and [`StateData::provider`].
+    pub async fn discover(
+        name: impl Into<String>,
+        config: &OAuthProviderConfig,
+    ) -> Result<Self, Error> {
+        let name = name.into();
+        let issuer = config.issuer.as_deref().ok_or_else(|| {
+            Error::Internal(format!(
+                "OAuth provider '{}' has no issuer configured",
+                name
+            ))
+        })?;
+        let issuer = IssuerUrl::new(issuer.to_string())
+            .map_err(|e| Error::Internal(format!("Invalid issuer URL: {}", e)))?;
+        let redirect_uri = RedirectUrl::new(config.redirect_uri.clone())
+            .map_err(|e| Error::Internal(format!("Invalid redirect URI: {}", e)))?;
+
+        // Following redirects would let a compromised IdP point token
+        // requests at internal hosts
+        let http_client = HttpClient::builder()
+            .redirect(reqwest::redirect::Policy::none())
+            .build()
+            .map_err(|e| Error::Internal(format!("Failed to create HTTP client: {}", e)))?;
+
+        let default_scopes = if config.scopes.is_empty() {
+            vec![
+                "openid".to_string(),
+                "email".to_string(),
+                "profile".to_string(),
+            ]
+        } else {
+            config.scopes.clone()
+        };
+
+        let discovered = discover(
+            &http_client,
+            &issuer,
+            &config.client_id,
+            &config.client_secret,
+            &redirect_uri,
+        )
+        .await?;
+
+        Ok(Self {
+            name,
+            issuer,
+            client_id: config.client_id.clone(),
+            client_secret: config.client_secret.clone(),
+            redirect_uri,
+            default_scopes,
+            http_client,
+            discovered: Arc::new(RwLock::new(discovered)),
+            last_discovery: Arc::new(Mutex::new(Instant::now())),
+        })
+    }
+
+    /// The issuer this provider was discovered from
+    pub fn issuer(&self) -> &str {
+        self.issuer.as_str()
+    }
+
+    fn discovered(&self) -> Discovered {
+        self.discovered
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone()
+    }
+
+    /// Re-run discovery to pick up rotated signing keys
+    ///
+    /// Returns `false` without fetching if the last discovery was too recent,
+    /// so tokens with forged key IDs cannot make us hammer the IdP.
+    async fn rediscover(&self) -> Result<bool, Error> {
+        {
+            let mut last = self
+                .last_discovery
+                .lock()
+                .unwrap_or_else(|e| e.into_inner());
+            if last.elapsed() < MIN_REDISCOVERY_INTERVAL {
+                return Ok(false);
+            }
+            *last = Instant::now();
+        }
+
+        let discovered = discover(
+            &self.http_client,
+            &self.issuer,
+            &self.client_id,
+            &self.client_secret,
+            &self.redirect_uri,
+        )
+        .await?;
+        *self.discovered.write().unwrap_or_else(|e| e.into_inner()) = discovered;
+        Ok(true)
+    }
+
+    /// Verify an ID token and return its claims as JSON
+    fn verify_id_token(
+        &self,
+        discovered: &Discovered,
+        id_token: &openidconnect::core::CoreIdToken,
+        nonce: &Nonce,
+    ) -> Result<serde_json::Value, ClaimsVerificationError> {
+        let verifier = discovered
+            .client
+            .id_token_verifier()
+            .set_allowed_algs(discovered.signing_algs.clone());
+        let claims = id_token.claims(&verifier, nonce)?;
+        serde_json::to_value(claims)
+            .map_err(|e| ClaimsVerificationError::Other(format!("Unreadable claims: {}", e)))
+    }
+
+    fn build_authorization_url(
+        &self,
+        state: &str,
+        additional_scopes: &[String],
+        pkce_verifier: Option<&str>,
+        nonce: Option<&str>,
+    ) -> String {
+        let discovered = self.discovered();
+        let state = state.to_string();
+        let nonce = nonce.map(str::to_string).unwrap_or_else(generate_state);
+
+        let mut auth_request = discovered.client.authorize_url(
+            CoreAuthenticationFlow::AuthorizationCode,
+            move || CsrfToken::new(state),
+            move || Nonce::new(nonce),
+        );
+
+        // `openid` is always requested by the OIDC client itself
+        let mut requested: Vec<&String> = Vec::new();
+        for scope in self.default_scopes.iter().chain(additional_scopes) {
+            if scope != "openid" && !requested.contains(&scope) {
+                requested.push(scope);
+            }
+        }
+        for scope in requested {
+            auth_request = auth_request.add_scope(Scope::new(scope.clone()));
+        }
+
+        if let Some(verifier) = pkce_verifier {
+            let challenge = PkceCodeChallenge::from_code_verifier_sha256(&PkceCodeVerifier::new(
+                verifier.to_string(),
+            ));
+            auth_request = auth_request.set_pkce_challenge(challenge);
+        }
+
+        let (url, _, _) = auth_request.url();
+        url.to_string()
+    }
+}
+
+/// Fetch provider metadata and JWKS and build a client from them
+async fn discover(
+    http_client: &HttpClient,
+    issuer: &IssuerUrl,
+    client_id: &str,
+    client_secret: &str,
+    redirect_uri: &RedirectUrl,
+) -> Result<Discovered, Error> {
+    let metadata = CoreProviderMetadata::discover_async(issuer.clone(), http_client)
+        .await
+        .map_err(|e| {
+            Error::External(format!(
+                "OIDC discovery for {} failed: {}",
+                issuer.as_str(),
+                e
+            ))
+        })?;
+
+    let signing_algs = metadata.id_token_signing_alg_values_supported().clone();
+    let userinfo_endpoint = metadata
+        .userinfo_endpoint()
+        .map(|endpoint| endpoint.url().to_string());
+
+    let client = CoreClient::from_provider_metadata(
+        metadata,
+        ClientId::new(client_id.to_string()),
+        Some(ClientSecret::new(client_secret.to_string())),
+    )
+    .set_redirect_uri(redirect_uri.clone());
+
+    Ok(Discovered {
+        client,
+        signing_algs,
+        userinfo_endpoint,
+    })
+}
+
+/// Map standard OIDC claims (from an ID token or userinfo response)
+pub(crate) fn user_info_from_claims(
+    provider: &str,
+    claims: serde_json::Value,
+) -> Result<OAuthUserInfo, Error> {
+    let sub = claims["sub"]
+        .as_str()
+        .ok_or_else(|| Error::External("Missing sub claim in response".to_string()))?;
+
+    Ok(OAuthUserInfo {
+        provider: provider.to_string(),
+        provider_user_id: sub.to_string(),
+        email: claims["email"].as_str().map(|s| s.to_string()),
+        email_verified: claims["email_verified"].as_bool().unwrap_or(false),
+        name: claims["name"]
+            .as_str()
+            .or(claims["preferred_username"].as_str())
+            .map(|s| s.to_string()),
+        picture: claims["picture"].as_str().map(|s| s.to_string()),
+        raw: claims,
+    })
+}
+
+#[async_trait]
+impl OAuthProvider for OidcProvider {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Authorization URL without PKCE
+    ///
+    /// The nonce in this URL is not retained, so the resulting login can only
+    /// be finished with [`exchange_code`](OAuthProvider::exchange_code). Prefer
+    /// [`authorization_url_for`](OAuthProvider::authorization_url_for).
+    fn authorization_url(&self, state: &str, additional_scopes: &[String]) -> String {
+        self.build_authorization_url(state, additional_scopes, None, None)
+    }
+
+    fn authorization_url_for(
+        &self,
+        state: &str,
+        data: &StateData,
+        additional_scopes: &[String],
+    ) -> String {
+        self.build_authorization_url(
+            state,
+            additional_scopes,
+            data.pkce_verifier.as_deref(),
+            data.nonce.as_deref(),
+        )
+    }
+
+    /// Exchange a code without PKCE; the returned ID token is not verified
+    async fn exchange_code(&self, code: &str) -> Result<OAuthTokens, Error> {
+        let discovered = self.discovered();
+        let token_result = discovered
+            .client
+            .exchange_code(AuthorizationCode::new(code.to_string()))
+            .map_err(|e| Error::External(format!("{} has no token endpoint: {}", self.name, e)))?
+            .request_async(&self.http_client)
+            .await
+            .map_err(|e| Error::External(format!("{} token exchange failed: {}", self.name, e)))?;
+
+        Ok(OAuthTokens {
+            access_token: token_result.access_token().secret().clone(),
+            refresh_token: token_result.refresh_token().map(|t| t.secret().clone()),
+            expires_in: token_result.expires_in().map(|d| d.as_secs() as i64),
+            token_type: "Bearer".to_string(),
+            id_token: token_result.id_token().map(|t| t.to_string()),
+        })
+    }
+
+    async fn get_user_info(&self, access_token: &str) -> Result<OAuthUserInfo, Error> {
+        let endpoint = self.discovered().userinfo_endpoint.ok_or_else(|| {
+            Error::Internal(format!(
+                "{} does not publish a userinfo endpoint",
+                self.name
+            ))
+        })?;
+
+        let response = self
+            .http_client
+            .get(&endpoint)
+            .bearer_auth(access_token)
+            .send()
+            .await
+            .map_err(|e| Error::External(format!("Failed to fetch user info: {}", e)))?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(Error::External(format!(
+                "User info request failed: {} - {}",
+                status, body
+            )));
+        }
+
+        let user_info: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| Error::External(format!("Failed to parse user info: {}", e)))?;
+
+        user_info_from_claims(&self.name, user_info)
+    }
+
+    async fn refresh_token(&self, refresh_token: &str) -> Result<OAuthTokens, Error> {
+        let discovered = self.discovered();
+        let token_result = discovered
+            .client
+            .exchange_refresh_token(&RefreshToken::new(refresh_token.to_string()))
+            .map_err(|e| Error::External(format!("{} has no token endpoint: {}", self.name, e)))?
+            .request_async(&self.http_client)
+            .await
+            .map_err(|e| Error::External(format!("{} token refresh failed: {}", self.name, e)))?;
+
+        Ok(OAuthTokens {
+            access_token: token_result.access_token().secret().clone(),
+            refresh_token: token_result.refresh_token().map(|t| t.secret().clone()),
+            expires_in: token_result.expires_in().map(|d| d.as_secs() as i64),
+            token_type: "Bearer".to_string(),
+            id_token: token_result.id_token().map(|t| t.to_string()),
+        })
+    }
+
+    async fn complete_login(
+        &self,
+        code: &str,
+        data: &StateData,
+    ) -> Result<(OAuthTokens, OAuthUserInfo), Error> {
+        let (Some(pkce_verifier), Some(nonce)) = (&data.pkce_verifier, &data.nonce) else {
+            return Err(Error::BadRequest(
+                "OIDC login state has no PKCE verifier or nonce".to_string(),
+            ));
+        };
+        let nonce = Nonce::new(nonce.clone());
+
+        let discovered = self.discovered();
+        let token_result = discovered
+            .client
+            .exchange_code(AuthorizationCode::new(code.to_string()))
+            .map_err(|e| Error::External(format!("{} has no token endpoint: {}", self.name, e)))?
+            .set_pkce_verifier(PkceCodeVerifier::new(pkce_verifier.clone()))
+            .request_async(&self.http_client)
+            .await
+            .map_err(|e| Error::External(format!("{} token exchange failed: {}", self.name, e)))?;
+
+        let id_token = token_result
+            .id_token()
+            .ok_or_else(|| Error::External(format!("{} returned no ID token", self.name)))?;
+
+        let mut verified = self.verify_id_token(&discovered, id_token, &nonce);
+        // The IdP may have rotated its keys since we last looked
+        if matches!(
+            verified,
+            Err(ClaimsVerificationError::SignatureVerification(
+                SignatureVerificationError::NoMatchingKey
+            ))
+        ) && self.rediscover().await?
+        {
+            verified = self.verify_id_token(&self.discovered(), id_token, &nonce);
+        }
+        let claims =
+            verified.map_err(|e| Error::Unauthorized(format!("Invalid ID token: {}", e)))?;
+
+        let tokens = OAuthTokens {
+            access_token: token_result.access_token().secret().clone(),
+            refresh_token: token_result.refresh_token().map(|t| t.secret().clone()),
+            expires_in: token_result.expires_in().map(|d| d.as_secs() as i64),
+            token_type: "Bearer".to_string(),
+            id_token: Some(id_token.to_string()),
+        };
+        let user_info = user_info_from_claims(&self.name, claims)?;
+        Ok((tokens, user_info))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_user_info_from_claims() {
+        let claims = serde_json::json!({
+            "sub": "00u1",
+            "email": "ada@example.com",
+            "email_verified": true,
+            "preferred_username": "ada",
+        });
+        let user = user_info_from_claims("corp", claims).unwrap();
+        assert_eq!(user.provider, "corp");
+        assert_eq!(user.provider_user_id, "00u1");
+        assert_eq!(user.email.as_deref(), Some("ada@example.com"));
+        assert!(user.email_verified);
+        assert_eq!(user.name.as_deref(), Some("ada"));
+
+        assert!(user_info_from_claims("corp", serde_json::json!({})).is_err());
+    }
+
+    /// End-to-end login against a stub IdP serving discovery, JWKS and a
+    /// token endpoint that insists on the PKCE verifier
+    #[cfg(feature = "jwt")]
+    mod stub_idp {
+        use super::*;
+        use axum::{extract::Form, http::StatusCode, routing::get, routing::post, Json, Router};
+        use jsonwebtoken::jwk::{Jwk, JwkSet};
+        use jsonwebtoken::{encode, Algorithm, EncodingKey, Header};
+        use std::collections::HashMap;
+
+        const CLIENT_ID: &str = "acton-test";
+        const VERIFIER: &str = "dBjftJeZ4CVP-mB92K27uhbUJU1p1r_wW1gFWFOEjXk";
+
+        /// Start a stub IdP whose ID tokens carry `nonce`
+        async fn start(nonce: &'static str) -> String {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let issuer = format!("http://{}", listener.local_addr().unwrap());
+
+            let key_pair = rcgen::KeyPair::generate().unwrap();
+            let encoding_key =
+                EncodingKey::from_ec_pem(key_pair.serialize_pem().as_bytes()).unwrap();
+            let mut jwk = Jwk::from_encoding_key(&encoding_key, Algorithm::ES256).unwrap();
+            jwk.common.key_id = Some("k1".to_string());
+            let jwks = JwkSet { keys: vec![jwk] };
+
+            let discovery = serde_json::json!({
+                "issuer": issuer,
+                "authorization_endpoint": format!("{issuer}/authorize"),
+                "token_endpoint": format!("{issuer}/token"),
+                "jwks_uri": format!("{issuer}/jwks"),
+                "response_types_supported": ["code"],
+                "subject_types_supported": ["public"],
+                "id_token_signing_alg_values_supported": ["ES256"],
+            });
+
+            let token_issuer = issuer.clone();
+            let app = Router::new()
+                .route(
+                    "/.well-known/openid-configuration",
+                    get(move || async move { Json(discovery) }),
+                )
+                .route("/jwks", get(move || async move { Json(jwks) }))
+                .route(
+                    "/token",
+                    post(move |Form(form): Form<HashMap<String, String>>| {
+                        let issuer = token_issuer.clone();
+                        let encoding_key = encoding_key.clone();
+                        async move {
+                            if form.get("code_verifier").map(String::as_str) != Some(VERIFIER) {
+                                return Err(StatusCode::BAD_REQUEST);
+                            }
+                            let now = chrono::Utc::now().timestamp();
+                            let mut header = Header::new(Algorithm::ES256);
+                            header.kid = Some("k1".to_string());
+                            let claims = serde_json::json!({
+                                "iss": issuer,
+                                "aud": CLIENT_ID,
+                                "sub": "00u1",
+                                "iat": now,
+                                "exp": now + 300,
+                                "nonce": nonce,
+                                "email": "ada@example.com",
+                                "email_verified": true,
+                                "name": "Ada",
+                            });
+                            let id_token = encode(&header, &claims, &encoding_key).unwrap();
+                            Ok(Json(serde_json::json!({
+                                "access_token": "at",
+                                "token_type": "Bearer",
+                                "expires_in": 300,
+                                "id_token": id_token,
+                            })))
+                        }
+                    }),
+                );
+            tokio::spawn(async move { axum::serve(listener, app).await });
+            issuer
+        }
+
+        async fn provider(issuer: String) -> OidcProvider {
+            let config = OAuthProviderConfig {
+                client_id: CLIENT_ID.to_string(),
+                client_secret: "secret".to_string(),
+                redirect_uri: "https://app.example.com/callback".to_string(),
+                scopes: Vec::new(),
+                authorization_endpoint: None,
+                token_endpoint: None,
+                userinfo_endpoint: None,
+                issuer: Some(issuer),
+            };
+            OidcProvider::discover("corp", &config).await.unwrap()
+        }
+
+        fn state_data(nonce: &str) -> StateData {
+            let mut data = StateData::new("corp");
+            data.pkce_verifier = Some(VERIFIER.to_string());
+            data.nonce = Some(nonce.to_string());
+            data
+        }
+
+        #[tokio::test]
+        async fn test_authorization_url_uses_pkce_and_nonce() {
+            let provider = provider(start("n-1").await).await;
+            let url = provider.authorization_url_for("s-1", &state_data("n-1"), &[]);
+
+            assert!(url.contains("code_challenge_method=S256"));
+            // RFC 7636 appendix B test vector
+            assert!(url.contains("code_challenge=E9Melhoa2OwvFrEMTJguCHaoeK1t8URWbuGJSstw-cM"));
+            assert!(url.contains("nonce=n-1"));
+            assert!(url.contains("state=s-1"));
+            assert!(!url.contains(VERIFIER));
+        }
+
+        #[tokio::test]
+        async fn test_complete_login_verifies_id_token() {
+            let provider = provider(start("n-1").await).await;
+
+            let (tokens, user) = provider
+                .complete_login("code", &state_data("n-1"))
+                .await
+                .unwrap();
+            assert!(tokens.id_token.is_some());
+            assert_eq!(user.provider_user_id, "00u1");
+            assert_eq!(user.email.as_deref(), Some("ada@example.com"));
+            assert_eq!(user.name.as_deref(), Some("Ada"));
+        }
+
+        #[tokio::test]
+        async fn test_complete_login_rejects_replayed_nonce() {
+            let provider = provider(start("n-1").await).await;
+
+            let result = provider.complete_login("code", &state_data("n-2")).await;
+            assert!(matches!(result, Err(Error::Unauthorized(_))));
+
+            // No verifier stored: refuse before contacting the IdP
+            let result = provider
+                .complete_login("code", &StateData::new("corp"))
+                .await;
+            assert!(matches!(result, Err(Error::BadRequest(_))));
+        }
+    }
+}
@@ -22,6 +22,44 @@ pub struct StateData {
 
     /// Additional custom data
     pub extra: Option<serde_json::Value>,
+
+    /// PKCE code verifier, sent with the code exchange
+    #[serde(default)]
+    pub pkce_verifier: Option<String>,
+
+    /// OIDC nonce the ID token must echo back
+    #[serde(default)]
+    pub nonce: Option<String>,
+}
+
+impl StateData {
+    /// Create state data for a login with the given provider
+    pub fn new(provider: impl Into<String>) -> Self {
+        Self {
+            provider: provider.into(),
+            redirect_uri: None,
+            created_at: chrono::Utc::now().timestamp(),
+            extra: None,
+            pkce_verifier: None,
+            nonce: None,
+        }
+    }
+
+    /// Set where to send the user after authentication
+    pub fn with_redirect_uri(mut self, redirect_uri: impl Into<String>) -> Self {
+        self.redirect_uri = Some(redirect_uri.into());
+        self
+    }
+
+    /// Generate a fresh PKCE verifier and nonce for this login
+    ///
+    /// Both stay server-side with the state; only the S256 challenge and the
+    /// nonce are sent in the authorization URL.
+    pub fn with_pkce(mut self) -> Self {
+        self.pkce_verifier = Some(generate_state());
+        self.nonce = Some(generate_state());
+        self
+    }
 }
 
 /// OAuth state manager trait
@@ -175,6 +213,8 @@ mod tests {
             redirect_uri: Some("https://example.com".to_string()),
             created_at: 1234567890,
             extra: Some(serde_json::json!({"foo": "bar"})),
+            pkce_verifier: None,
+            nonce: None,
         };
 
         let json = serde_json::to_string(&data).unwrap();
@@ -184,4 +224,17 @@ mod tests {
         assert_eq!(parsed.redirect_uri, Some("https://example.com".to_string()));
         assert_eq!(parsed.created_at, 1234567890);
     }
+
+    #[test]
+    fn test_state_data_without_pkce_fields_still_parses() {
+        let json = r#"{"provider":"google","redirect_uri":null,"created_at":1,"extra":null}"#;
+        let parsed: StateData = serde_json::from_str(json).unwrap();
+        assert!(parsed.pkce_verifier.is_none());
+        assert!(parsed.nonce.is_none());
+
+        let data = StateData::new("corp").with_pkce();
+        // RFC 7636 requires 43-128 characters
+        assert_eq!(data.pkce_verifier.as_ref().unwrap().len(), 43);
+        assert_ne!(data.pkce_verifier, data.nonce);
+    }
 }
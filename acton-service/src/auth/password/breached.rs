@@ -0,0 +1,419 @@
+//! Offline breached-password lookups
+//!
+//! Passwords are checked by SHA-1 digest against a local corpus, so no
+//! password (or partial hash) ever leaves the process. Two corpus formats
+//! are supported:
+//!
+//! - **SHA-1 prefix ranges**: the k-anonymity layout published by Have I
+//!   Been Pwned. A directory holds one file per 5-hex-character digest
+//!   prefix (`21BD1.txt` or `21BD1`), each listing the remaining 35
+//!   characters and a breach count as `SUFFIX:COUNT` lines. Only the one
+//!   file for a password's prefix is read per check.
+//! - **Bloom filter**: a compact, in-memory set of digests built with
+//!   [`BloomFilter`]. It may report false positives (at the rate it was
+//!   sized for) but never false negatives.
+//!
+//! # Building a bloom filter
+//!
+//! ```rust,ignore
+//! use acton_service::auth::BloomFilter;
+//!
+//! let mut filter = BloomFilter::new(1_000_000, 0.001);
+//! for line in std::fs::read_to_string("pwned-passwords-sha1.txt")?.lines() {
+//!     let hash = line.split(':').next().unwrap_or_default();
+//!     filter.insert_sha1_hex(hash)?;
+//! }
+//! std::fs::write("pwned.bloom", filter.to_bytes())?;
+//! ```
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use sha1::{Digest, Sha1};
+
+use crate::auth::config::{BreachedPasswordConfig, BreachedPasswordFormat};
+use crate::error::Error;
+
+/// Magic bytes at the start of a serialized bloom filter
+const BLOOM_MAGIC: &[u8; 8] = b"ACTNBLM1";
+
+/// Serialized header: magic, hash count (u32 LE), bit count (u64 LE)
+const BLOOM_HEADER_LEN: usize = 8 + 4 + 8;
+
+/// Length of the digest prefix that names a range file
+const PREFIX_LEN: usize = 5;
+
+/// A loaded breached-password corpus
+#[derive(Debug)]
+pub(crate) enum BreachedPasswords {
+    /// Directory of SHA-1 prefix range files, read on demand
+    Ranges { dir: PathBuf, min_occurrences: u64 },
+    /// Bloom filter held in memory
+    Bloom(BloomFilter),
+}
+
+impl BreachedPasswords {
+    /// Open the corpus described by `config`
+    ///
+    /// Bloom filters are read into memory here; range directories are only
+    /// checked for existence.
+    pub(crate) fn open(config: &BreachedPasswordConfig) -> Result<Self, Error> {
+        match config.format {
+            BreachedPasswordFormat::Sha1Prefix => {
+                if !config.path.is_dir() {
+                    return Err(Error::Internal(format!(
+                        "Breached-password range directory not found: {}",
+                        config.path.display()
+                    )));
+                }
+                Ok(Self::Ranges {
+                    dir: config.path.clone(),
+                    min_occurrences: config.min_occurrences,
+                })
+            }
+            BreachedPasswordFormat::Bloom => {
+                let bytes = fs::read(&config.path).map_err(|e| {
+                    Error::Internal(format!(
+                        "Failed to read breached-password bloom filter {}: {}",
+                        config.path.display(),
+                        e
+                    ))
+                })?;
+                Ok(Self::Bloom(BloomFilter::from_bytes(&bytes)?))
+            }
+        }
+    }
+
+    /// Whether `password` appears in the corpus
+    ///
+    /// A range file that cannot be read is logged and treated as a miss, so
+    /// a damaged corpus does not lock users out of changing passwords.
+    pub(crate) fn contains(&self, password: &str) -> bool {
+        let digest = sha1_digest(password);
+        match self {
+            Self::Ranges {
+                dir,
+                min_occurrences,
+            } => exceeds(range_count(dir, &digest), *min_occurrences),
+            Self::Bloom(filter) => filter.contains_digest(&digest),
+        }
+    }
+
+    /// [`contains`](Self::contains), reading range files with `tokio::fs` so
+    /// the lookup does not block the async runtime
+    pub(crate) async fn contains_async(&self, password: &str) -> bool {
+        let digest = sha1_digest(password);
+        match self {
+            Self::Ranges {
+                dir,
+                min_occurrences,
+            } => exceeds(range_count_async(dir, &digest).await, *min_occurrences),
+            Self::Bloom(filter) => filter.contains_digest(&digest),
+        }
+    }
+}
+
+fn exceeds(count: io::Result<u64>, min_occurrences: u64) -> bool {
+    match count {
+        Ok(count) => count >= min_occurrences.max(1),
+        Err(e) => {
+            tracing::warn!("Breached-password lookup failed: {}", e);
+            false
+        }
+    }
+}
+
+/// Breach count for `digest` in its prefix range file (0 when absent)
+fn range_count(dir: &Path, digest: &[u8; 20]) -> io::Result<u64> {
+    let hex = to_hex_upper(digest);
+    let (prefix, suffix) = hex.split_at(PREFIX_LEN);
+
+    let contents = match fs::read_to_string(dir.join(format!("{prefix}.txt"))) {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            match fs::read_to_string(dir.join(prefix)) {
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+                other => other?,
+            }
+        }
+        other => other?,
+    };
+
+    Ok(count_in_range(&contents, suffix))
+}
+
+/// [`range_count`] on `tokio::fs`
+async fn range_count_async(dir: &Path, digest: &[u8; 20]) -> io::Result<u64> {
+    let hex = to_hex_upper(digest);
+    let (prefix, suffix) = hex.split_at(PREFIX_LEN);
+
+    let contents = match tokio::fs::read_to_string(dir.join(format!("{prefix}.txt"))).await {
+        Err(e) if e.kind() == io::ErrorKind::NotFound => {
+            match tokio::fs::read_to_string(dir.join(prefix)).await {
+                Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(0),
+                other => other?,
+            }
+        }
+        other => other?,
+    };
+
+    Ok(count_in_range(&contents, suffix))
+}
+
+/// The count on the `SUFFIX:COUNT` line for `suffix` (0 when absent)
+fn count_in_range(contents: &str, suffix: &str) -> u64 {
+    for line in contents.lines() {
+        let (candidate, count) = line.trim().split_once(':').unwrap_or((line.trim(), "1"));
+        if candidate.eq_ignore_ascii_case(suffix) {
+            return count.trim().parse().unwrap_or(1);
+        }
+    }
+
+    0
+}
+
+/// Bloom filter of SHA-1 password digests
+///
+/// Bit positions are derived from the digest by double hashing, so
+/// membership checks need no extra hashing beyond the SHA-1 itself.
+/// Serialized as the magic `ACTNBLM1`, the hash count (`u32` LE), the bit
+/// count (`u64` LE), and then the bit array.
+#[derive(Clone)]
+pub struct BloomFilter {
+    bits: Vec<u8>,
+    num_bits: u64,
+    num_hashes: u32,
+}
+
+impl BloomFilter {
+    /// Create an empty filter sized for `expected_items` at the given false
+    /// positive rate (e.g. `0.001` for 0.1%)
+    pub fn new(expected_items: usize, false_positive_rate: f64) -> Self {
+        let n = expected_items.max(1) as f64;
+        let p = false_positive_rate.clamp(1e-12, 0.5);
+        let ln2 = std::f64::consts::LN_2;
+
+        let num_bits = ((-n * p.ln()) / (ln2 * ln2)).ceil().max(64.0) as u64;
+        let num_hashes = ((num_bits as f64 / n) * ln2).round().clamp(1.0, 32.0) as u32;
+
+        Self {
+            bits: vec![0; num_bits.div_ceil(8) as usize],
+            num_bits,
+            num_hashes,
+        }
+    }
+
+    /// Add a plaintext password
+    pub fn insert_password(&mut self, password: &str) {
+        self.insert_digest(&sha1_digest(password));
+    }
+
+    /// Add a password by its hex-encoded SHA-1 digest
+    ///
+    /// Accepts either case, matching the Have I Been Pwned downloads.
+    pub fn insert_sha1_hex(&mut self, hex: &str) -> Result<(), Error> {
+        let digest = parse_sha1_hex(hex)
+            .ok_or_else(|| Error::BadRequest(format!("Invalid SHA-1 hex digest: {}", hex)))?;
+        self.insert_digest(&digest);
+        Ok(())
+    }
+
+    /// Whether the filter (probably) contains a plaintext password
+    pub fn contains_password(&self, password: &str) -> bool {
+        self.contains_digest(&sha1_digest(password))
+    }
+
+    /// Load a filter serialized with [`to_bytes`](Self::to_bytes)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let invalid = |reason: &str| Error::Internal(format!("Invalid bloom filter: {}", reason));
+
+        if bytes.len() < BLOOM_HEADER_LEN || &bytes[..8] != BLOOM_MAGIC {
+            return Err(invalid("missing header"));
+        }
+
+        let num_hashes = u32::from_le_bytes(bytes[8..12].try_into().expect("4-byte slice"));
+        let num_bits = u64::from_le_bytes(bytes[12..20].try_into().expect("8-byte slice"));
+        let bits = &bytes[BLOOM_HEADER_LEN..];
+
+        if num_hashes == 0 || num_bits == 0 {
+            return Err(invalid("empty filter"));
+        }
+        if bits.len() as u64 != num_bits.div_ceil(8) {
+            return Err(invalid("bit array length does not match header"));
+        }
+
+        Ok(Self {
+            bits: bits.to_vec(),
+            num_bits,
+            num_hashes,
+        })
+    }
+
+    /// Serialize the filter for [`from_bytes`](Self::from_bytes)
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(BLOOM_HEADER_LEN + self.bits.len());
+        bytes.extend_from_slice(BLOOM_MAGIC);
+        bytes.extend_from_slice(&self.num_hashes.to_le_bytes());
+        bytes.extend_from_slice(&self.num_bits.to_le_bytes());
+        bytes.extend_from_slice(&self.bits);
+        bytes
+    }
+
+    fn insert_digest(&mut self, digest: &[u8; 20]) {
+        let indexes: Vec<u64> = self.indexes(digest).collect();
+        for index in indexes {
+            self.bits[(index / 8) as usize] |= 1 << (index % 8);
+        }
+    }
+
+    fn contains_digest(&self, digest: &[u8; 20]) -> bool {
+        self.indexes(digest)
+            .all(|index| self.bits[(index / 8) as usize] & (1 << (index % 8)) != 0)
+    }
+
+    fn indexes(&self, digest: &[u8; 20]) -> impl Iterator<Item = u64> + '_ {
+        let h1 = u64::from_le_bytes(digest[0..8].try_into().expect("8-byte slice"));
+        // Odd step so successive probes never collapse onto one bit
+        let h2 = u64::from_le_bytes(digest[8..16].try_into().expect("8-byte slice")) | 1;
+
+        (0..u64::from(self.num_hashes))
+            .map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits)
+    }
+}
+
+impl std::fmt::Debug for BloomFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BloomFilter")
+            .field("num_bits", &self.num_bits)
+            .field("num_hashes", &self.num_hashes)
+            .finish()
+    }
+}
+
+fn sha1_digest(password: &str) -> [u8; 20] {
+    Sha1::digest(password.as_bytes()).into()
+}
+
+fn to_hex_upper(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02X}", b)).collect()
+}
+
+fn parse_sha1_hex(hex: &str) -> Option<[u8; 20]> {
+    let hex = hex.trim();
+    if hex.len() != 40 || !hex.is_ascii() {
+        return None;
+    }
+
+    let mut digest = [0u8; 20];
+    for (i, byte) in digest.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16).ok()?;
+    }
+    Some(digest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // SHA-1("password")
+    const PASSWORD_SHA1: &str = "5BAA61E4C9B93F3F0682250B6CF8331B7EE68FD8";
+
+    #[test]
+    fn test_range_lookup() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("5BAA6.txt"),
+            "0018A45C4D1DEF81644B54AB7F969B88D65:1\r\n\
+             1E4C9B93F3F0682250B6CF8331B7EE68FD8:9545824\r\n",
+        )
+        .unwrap();
+
+        let config = BreachedPasswordConfig {
+            format: BreachedPasswordFormat::Sha1Prefix,
+            path: dir.path().to_path_buf(),
+            min_occurrences: 1,
+        };
+        let corpus = BreachedPasswords::open(&config).unwrap();
+        assert!(corpus.contains("password"));
+        assert!(!corpus.contains("correct horse battery staple"));
+
+        let strict = BreachedPasswords::open(&BreachedPasswordConfig {
+            min_occurrences: 10_000_000,
+            ..config
+        })
+        .unwrap();
+        assert!(!strict.contains("password"));
+    }
+
+    #[test]
+    fn test_range_lookup_without_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(
+            dir.path().join("5BAA6"),
+            "1e4c9b93f3f0682250b6cf8331b7ee68fd8:3\n",
+        )
+        .unwrap();
+
+        let corpus = BreachedPasswords::open(&BreachedPasswordConfig {
+            format: BreachedPasswordFormat::Sha1Prefix,
+            path: dir.path().to_path_buf(),
+            min_occurrences: 1,
+        })
+        .unwrap();
+        assert!(corpus.contains("password"));
+    }
+
+    #[test]
+    fn test_missing_range_directory() {
+        let result = BreachedPasswords::open(&BreachedPasswordConfig {
+            format: BreachedPasswordFormat::Sha1Prefix,
+            path: PathBuf::from("/nonexistent/pwned"),
+            min_occurrences: 1,
+        });
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_bloom_filter_membership() {
+        let mut filter = BloomFilter::new(1000, 0.001);
+        filter.insert_password("hunter2");
+        filter.insert_sha1_hex(PASSWORD_SHA1).unwrap();
+
+        assert!(filter.contains_password("hunter2"));
+        assert!(filter.contains_password("password"));
+        assert!(!filter.contains_password("correct horse battery staple"));
+        assert!(filter.insert_sha1_hex("not-hex").is_err());
+    }
+
+    #[test]
+    fn test_bloom_filter_roundtrip() {
+        let mut filter = BloomFilter::new(100, 0.01);
+        filter.insert_password("letmein");
+
+        let restored = BloomFilter::from_bytes(&filter.to_bytes()).unwrap();
+        assert!(restored.contains_password("letmein"));
+        assert!(!restored.contains_password("correct horse battery staple"));
+
+        assert!(BloomFilter::from_bytes(b"garbage").is_err());
+        let mut truncated = filter.to_bytes();
+        truncated.pop();
+        assert!(BloomFilter::from_bytes(&truncated).is_err());
+    }
+
+    #[test]
+    fn test_bloom_corpus_from_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pwned.bloom");
+        let mut filter = BloomFilter::new(100, 0.01);
+        filter.insert_password("password");
+        fs::write(&path, filter.to_bytes()).unwrap();
+
+        let corpus = BreachedPasswords::open(&BreachedPasswordConfig {
+            format: BreachedPasswordFormat::Bloom,
+            path,
+            min_occurrences: 1,
+        })
+        .unwrap();
+        assert!(corpus.contains("password"));
+    }
+}
@@ -3,6 +3,10 @@
 //! Provides secure password hashing following OWASP recommendations.
 //! Uses Argon2id, which is the recommended algorithm for password hashing.
 //!
+//! Passwords are checked against the policy in `PasswordConfig` before they
+//! are hashed: length bounds, required character classes, blocked words,
+//! and an optional offline breached-password corpus (see [`BloomFilter`]).
+//!
 //! # Example
 //!
 //! ```rust,ignore
@@ -18,6 +22,8 @@
 //! assert!(!hasher.verify("wrong_password", &hash)?);
 //! ```
 
+use std::sync::Arc;
+
 use argon2::{
     password_hash::{
         rand_core::OsRng, PasswordHash, PasswordHasher as Argon2Hasher, PasswordVerifier,
@@ -29,14 +35,23 @@ use argon2::{
 use crate::auth::config::PasswordConfig;
 use crate::error::Error;
 
+mod breached;
+mod policy;
+
+pub use breached::BloomFilter;
+pub use policy::{PasswordPolicyError, PasswordViolation};
+
+use policy::PasswordPolicy;
+
 /// Password hasher using Argon2id
 ///
 /// This hasher uses Argon2id with OWASP-recommended parameters by default.
-/// The parameters can be customized via `PasswordConfig`.
+/// The parameters and password policy can be customized via `PasswordConfig`.
 #[derive(Clone)]
 pub struct PasswordHasher {
     params: Params,
-    min_password_length: usize,
+    policy: Arc<PasswordPolicy>,
+    history_size: usize,
 }
 
 impl Default for PasswordHasher {
@@ -52,31 +67,87 @@ impl PasswordHasher {
     ///
     /// * `config` - Password hashing configuration
     ///
+    /// # Panics
+    ///
+    /// Panics if the Argon2 parameters are invalid or the configured
+    /// breached-password corpus cannot be loaded. Use
+    /// [`try_new`](Self::try_new) to handle these as errors.
+    ///
     /// # Example
     ///
     /// ```rust,ignore
     /// use acton_service::auth::{PasswordHasher, PasswordConfig};
     ///
     /// let config = PasswordConfig {
-    ///     memory_cost_kib: 65536,
-    ///     time_cost: 3,
-    ///     parallelism: 4,
     ///     min_password_length: 12,
+    ///     require_digit: true,
+    ///     ..Default::default()
     /// };
     /// let hasher = PasswordHasher::new(config);
     /// ```
     pub fn new(config: PasswordConfig) -> Self {
+        Self::try_new(config).expect("Invalid password hasher configuration")
+    }
+
+    /// Create a new password hasher, returning an error for invalid Argon2
+    /// parameters or an unreadable breached-password corpus
+    pub fn try_new(config: PasswordConfig) -> Result<Self, Error> {
         let params = Params::new(
             config.memory_cost_kib,
             config.time_cost,
             config.parallelism,
             None, // Use default output length
         )
-        .expect("Invalid Argon2 parameters");
+        .map_err(|e| Error::Internal(format!("Invalid Argon2 parameters: {}", e)))?;
 
-        Self {
+        Ok(Self {
             params,
-            min_password_length: config.min_password_length,
+            policy: Arc::new(PasswordPolicy::from_config(&config)?),
+            history_size: config.history_size,
+        })
+    }
+
+    /// Check a password against the configured policy
+    ///
+    /// `context` holds terms the password must not contain, such as the
+    /// account's email and username or the service name. Every failed rule
+    /// is reported; convert the error into [`Error`] for a 422 response with
+    /// one `password` field error per rule.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let hasher = PasswordHasher::default();
+    /// hasher.check_policy(&password, &[&email, "Acme"])?;
+    /// ```
+    pub fn check_policy(
+        &self,
+        password: &str,
+        context: &[&str],
+    ) -> Result<(), PasswordPolicyError> {
+        let violations = self.policy.violations(password, context);
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(PasswordPolicyError::new(violations))
+        }
+    }
+
+    /// [`check_policy`](Self::check_policy) for async code
+    ///
+    /// A `sha1-prefix` breached-password corpus is read from disk on each
+    /// check; this variant reads it with `tokio::fs` instead of blocking the
+    /// runtime's worker thread.
+    pub async fn check_policy_async(
+        &self,
+        password: &str,
+        context: &[&str],
+    ) -> Result<(), PasswordPolicyError> {
+        let violations = self.policy.violations_async(password, context).await;
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(PasswordPolicyError::new(violations))
         }
     }
 
@@ -93,7 +164,7 @@ impl PasswordHasher {
     /// # Returns
     ///
     /// A PHC string format hash on success, or an error if:
-    /// - The password is too short (less than `min_password_length`)
+    /// - The password fails the policy (see [`check_policy`](Self::check_policy))
     /// - There's a cryptographic error during hashing
     ///
     /// # Example
@@ -104,14 +175,14 @@ impl PasswordHasher {
     /// // hash looks like: $argon2id$v=19$m=65536,t=3,p=4$...
     /// ```
     pub fn hash(&self, password: &str) -> Result<String, Error> {
-        // Validate password length
-        if password.len() < self.min_password_length {
-            return Err(Error::ValidationError(format!(
-                "Password must be at least {} characters",
-                self.min_password_length
-            )));
-        }
+        self.check_policy(password, &[])
+            .map_err(|e| Error::ValidationError(e.to_string()))?;
+
+        self.hash_unchecked(password)
+    }
 
+    /// Hash a password that has already passed [`check_policy`](Self::check_policy)
+    pub(crate) fn hash_unchecked(&self, password: &str) -> Result<String, Error> {
         // Generate a random salt
         let salt = SaltString::generate(&mut OsRng);
 
@@ -242,7 +313,12 @@ impl PasswordHasher {
 
     /// Get the minimum password length requirement
     pub fn min_password_length(&self) -> usize {
-        self.min_password_length
+        self.policy.min_length()
+    }
+
+    /// Get the number of previous passwords an account may not reuse
+    pub fn history_size(&self) -> usize {
+        self.history_size
     }
 }
 
@@ -292,6 +368,51 @@ mod tests {
         assert!(hasher.hash("012345678901").is_ok());
     }
 
+    #[test]
+    fn test_hash_enforces_policy() {
+        let hasher = PasswordHasher::new(PasswordConfig {
+            require_digit: true,
+            require_symbol: true,
+            ..Default::default()
+        });
+
+        match hasher.hash("nodigitsorsymbols") {
+            Err(Error::ValidationError(msg)) => {
+                assert!(msg.contains("digit"));
+                assert!(msg.contains("symbol"));
+            }
+            other => panic!("Expected ValidationError, got {:?}", other),
+        }
+        assert!(hasher.hash("has-digit-4").is_ok());
+    }
+
+    #[test]
+    fn test_check_policy_with_context() {
+        let hasher = PasswordHasher::default();
+
+        let err = hasher
+            .check_policy("jsmith-password", &["jsmith@example.com"])
+            .unwrap_err();
+        assert_eq!(err.violations(), &[PasswordViolation::ContainsContext]);
+        assert!(hasher
+            .check_policy("jsmith-password", &["other@example.com"])
+            .is_ok());
+    }
+
+    #[test]
+    fn test_try_new_rejects_missing_corpus() {
+        let result = PasswordHasher::try_new(PasswordConfig {
+            breached: Some(crate::auth::config::BreachedPasswordConfig {
+                format: crate::auth::config::BreachedPasswordFormat::Bloom,
+                path: "/nonexistent/pwned.bloom".into(),
+                min_occurrences: 1,
+            }),
+            ..Default::default()
+        });
+
+        assert!(result.is_err());
+    }
+
     #[test]
     fn test_needs_rehash_same_params() {
         let hasher = PasswordHasher::default();
@@ -0,0 +1,430 @@
+//! Password policy rules
+//!
+//! [`PasswordPolicy`] applies the rules from [`PasswordConfig`]: length
+//! bounds, character classes, blocked and context words, and the optional
+//! breached-password corpus. Every failed rule is reported, not just the
+//! first, so a client can show all of them at once.
+
+use std::fmt;
+
+use super::breached::BreachedPasswords;
+use crate::auth::config::PasswordConfig;
+use crate::error::Error;
+use crate::responses::ValidationError;
+
+/// Field name policy violations are reported under
+const PASSWORD_FIELD: &str = "password";
+
+/// Context terms shorter than this are too common to reject on
+const MIN_CONTEXT_LEN: usize = 3;
+
+/// A single password policy rule a password failed
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PasswordViolation {
+    /// Shorter than `min_password_length`
+    TooShort {
+        /// Minimum length in characters
+        min: usize,
+    },
+    /// Longer than `max_password_length`
+    TooLong {
+        /// Maximum length in characters
+        max: usize,
+    },
+    /// No uppercase letter
+    MissingUppercase,
+    /// No lowercase letter
+    MissingLowercase,
+    /// No digit
+    MissingDigit,
+    /// No character other than a letter or digit
+    MissingSymbol,
+    /// Contains a blocked word or the account's email, username, or service name
+    ContainsContext,
+    /// Found in the breached-password corpus
+    Breached,
+    /// Matches one of the account's recent passwords
+    Reused,
+}
+
+impl PasswordViolation {
+    /// Machine-readable error code (e.g. `"TOO_SHORT"`)
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::TooShort { .. } => "TOO_SHORT",
+            Self::TooLong { .. } => "TOO_LONG",
+            Self::MissingUppercase => "MISSING_UPPERCASE",
+            Self::MissingLowercase => "MISSING_LOWERCASE",
+            Self::MissingDigit => "MISSING_DIGIT",
+            Self::MissingSymbol => "MISSING_SYMBOL",
+            Self::ContainsContext => "CONTAINS_CONTEXT",
+            Self::Breached => "BREACHED",
+            Self::Reused => "REUSED",
+        }
+    }
+
+    /// Human-readable description of the rule
+    pub fn message(&self) -> String {
+        match self {
+            Self::TooShort { min } => format!("Password must be at least {} characters", min),
+            Self::TooLong { max } => format!("Password must be at most {} characters", max),
+            Self::MissingUppercase => "Password must contain an uppercase letter".to_string(),
+            Self::MissingLowercase => "Password must contain a lowercase letter".to_string(),
+            Self::MissingDigit => "Password must contain a digit".to_string(),
+            Self::MissingSymbol => "Password must contain a symbol".to_string(),
+            Self::ContainsContext => {
+                "Password must not contain your email, username, or other easily guessed words"
+                    .to_string()
+            }
+            Self::Breached => {
+                "Password has appeared in a data breach; choose a different one".to_string()
+            }
+            Self::Reused => "Password was used recently; choose a different one".to_string(),
+        }
+    }
+}
+
+impl fmt::Display for PasswordViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message())
+    }
+}
+
+/// A password failed one or more policy rules
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PasswordPolicyError {
+    violations: Vec<PasswordViolation>,
+}
+
+impl PasswordPolicyError {
+    /// Create an error from the rules a password failed
+    pub fn new(violations: Vec<PasswordViolation>) -> Self {
+        Self { violations }
+    }
+
+    /// The rules the password failed
+    pub fn violations(&self) -> &[PasswordViolation] {
+        &self.violations
+    }
+
+    /// Convert to a 422 response with one `password` field error per violation
+    pub fn to_validation_error(&self) -> ValidationError {
+        let mut errors = ValidationError::new("Password does not meet requirements");
+        for violation in &self.violations {
+            errors.add_field_error(PASSWORD_FIELD, violation.code(), violation.message());
+        }
+        errors
+    }
+}
+
+impl From<PasswordViolation> for PasswordPolicyError {
+    fn from(violation: PasswordViolation) -> Self {
+        Self::new(vec![violation])
+    }
+}
+
+impl fmt::Display for PasswordPolicyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let messages: Vec<String> = self.violations.iter().map(|v| v.message()).collect();
+        f.write_str(&messages.join("; "))
+    }
+}
+
+impl std::error::Error for PasswordPolicyError {}
+
+impl From<PasswordPolicyError> for Error {
+    fn from(err: PasswordPolicyError) -> Self {
+        Error::FieldValidation(Box::new(err.to_validation_error()))
+    }
+}
+
+/// Password rules compiled from [`PasswordConfig`]
+#[derive(Debug)]
+pub(super) struct PasswordPolicy {
+    min_length: usize,
+    max_length: usize,
+    require_uppercase: bool,
+    require_lowercase: bool,
+    require_digit: bool,
+    require_symbol: bool,
+    blocked_words: Vec<String>,
+    breached: Option<BreachedPasswords>,
+}
+
+impl PasswordPolicy {
+    /// Compile the policy, loading the breached-password corpus if configured
+    pub(super) fn from_config(config: &PasswordConfig) -> Result<Self, Error> {
+        let breached = config
+            .breached
+            .as_ref()
+            .map(BreachedPasswords::open)
+            .transpose()?;
+
+        Ok(Self {
+            min_length: config.min_password_length,
+            max_length: config.max_password_length,
+            require_uppercase: config.require_uppercase,
+            require_lowercase: config.require_lowercase,
+            require_digit: config.require_digit,
+            require_symbol: config.require_symbol,
+            blocked_words: config
+                .blocked_words
+                .iter()
+                .map(|w| w.trim().to_lowercase())
+                .filter(|w| !w.is_empty())
+                .collect(),
+            breached,
+        })
+    }
+
+    pub(super) fn min_length(&self) -> usize {
+        self.min_length
+    }
+
+    /// Every rule `password` fails, given context terms it must not contain
+    ///
+    /// Context terms shorter than three characters are ignored; for an email
+    /// address, its local part is checked as well.
+    pub(super) fn violations(&self, password: &str, context: &[&str]) -> Vec<PasswordViolation> {
+        let mut violations = self.rule_violations(password, context);
+
+        // Skip the corpus lookup for passwords that are already rejected
+        if violations.is_empty() {
+            if let Some(ref breached) = self.breached {
+                if breached.contains(password) {
+                    violations.push(PasswordViolation::Breached);
+                }
+            }
+        }
+
+        violations
+    }
+
+    /// [`violations`](Self::violations) without blocking the async runtime
+    /// on a range-directory corpus
+    pub(super) async fn violations_async(
+        &self,
+        password: &str,
+        context: &[&str],
+    ) -> Vec<PasswordViolation> {
+        let mut violations = self.rule_violations(password, context);
+
+        if violations.is_empty() {
+            if let Some(ref breached) = self.breached {
+                if breached.contains_async(password).await {
+                    violations.push(PasswordViolation::Breached);
+                }
+            }
+        }
+
+        violations
+    }
+
+    /// Every rule except the breached-password corpus
+    fn rule_violations(&self, password: &str, context: &[&str]) -> Vec<PasswordViolation> {
+        let mut violations = Vec::new();
+
+        let length = password.chars().count();
+        if length < self.min_length {
+            violations.push(PasswordViolation::TooShort {
+                min: self.min_length,
+            });
+        }
+        if self.max_length > 0 && length > self.max_length {
+            violations.push(PasswordViolation::TooLong {
+                max: self.max_length,
+            });
+        }
+
+        if self.require_uppercase && !password.chars().any(char::is_uppercase) {
+            violations.push(PasswordViolation::MissingUppercase);
+        }
+        if self.require_lowercase && !password.chars().any(char::is_lowercase) {
+            violations.push(PasswordViolation::MissingLowercase);
+        }
+        if self.require_digit && !password.chars().any(|c| c.is_ascii_digit()) {
+            violations.push(PasswordViolation::MissingDigit);
+        }
+        if self.require_symbol && !password.chars().any(|c| !c.is_alphanumeric()) {
+            violations.push(PasswordViolation::MissingSymbol);
+        }
+
+        if self.contains_context(password, context) {
+            violations.push(PasswordViolation::ContainsContext);
+        }
+
+        violations
+    }
+
+    fn contains_context(&self, password: &str, context: &[&str]) -> bool {
+        let password = password.to_lowercase();
+
+        let context_terms = context.iter().flat_map(|term| {
+            let term = term.trim().to_lowercase();
+            let local_part = term
+                .split_once('@')
+                .map(|(local, _)| local.to_string())
+                .filter(|local| !local.is_empty());
+            std::iter::once(term).chain(local_part)
+        });
+
+        self.blocked_words
+            .iter()
+            .cloned()
+            .chain(context_terms)
+            .filter(|term| term.chars().count() >= MIN_CONTEXT_LEN)
+            .any(|term| password.contains(&term))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(config: PasswordConfig) -> PasswordPolicy {
+        PasswordPolicy::from_config(&config).unwrap()
+    }
+
+    #[test]
+    fn test_length_bounds() {
+        let policy = policy(PasswordConfig {
+            min_password_length: 10,
+            max_password_length: 16,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            policy.violations("short", &[]),
+            vec![PasswordViolation::TooShort { min: 10 }]
+        );
+        assert_eq!(
+            policy.violations("this password is far too long", &[]),
+            vec![PasswordViolation::TooLong { max: 16 }]
+        );
+        assert!(policy.violations("just right!", &[]).is_empty());
+    }
+
+    #[test]
+    fn test_length_counts_characters() {
+        let policy = policy(PasswordConfig::default());
+        // 8 characters, 16 bytes
+        assert!(policy.violations("пароль12", &[]).is_empty());
+    }
+
+    #[test]
+    fn test_character_classes() {
+        let policy = policy(PasswordConfig {
+            require_uppercase: true,
+            require_lowercase: true,
+            require_digit: true,
+            require_symbol: true,
+            ..Default::default()
+        });
+
+        assert_eq!(
+            policy.violations("alllowercase", &[]),
+            vec![
+                PasswordViolation::MissingUppercase,
+                PasswordViolation::MissingDigit,
+                PasswordViolation::MissingSymbol,
+            ]
+        );
+        assert!(policy.violations("Mixed-Case-42", &[]).is_empty());
+    }
+
+    #[test]
+    fn test_context_words() {
+        let policy = policy(PasswordConfig {
+            blocked_words: vec!["Acme".to_string()],
+            ..Default::default()
+        });
+
+        assert_eq!(
+            policy.violations("welcome-to-ACME", &[]),
+            vec![PasswordViolation::ContainsContext]
+        );
+        assert_eq!(
+            policy.violations("alice-rules-2024", &["alice@example.com"]),
+            vec![PasswordViolation::ContainsContext]
+        );
+        assert_eq!(
+            policy.violations("my-bobby-tables", &["bobby"]),
+            vec![PasswordViolation::ContainsContext]
+        );
+        // Terms under three characters are ignored
+        assert!(policy.violations("zebra-crossing", &["ze"]).is_empty());
+    }
+
+    #[test]
+    fn test_breached_password() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("5BAA6.txt"),
+            "1E4C9B93F3F0682250B6CF8331B7EE68FD8:9545824\n",
+        )
+        .unwrap();
+
+        let policy = policy(PasswordConfig {
+            breached: Some(crate::auth::config::BreachedPasswordConfig {
+                format: crate::auth::config::BreachedPasswordFormat::Sha1Prefix,
+                path: dir.path().to_path_buf(),
+                min_occurrences: 1,
+            }),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            policy.violations("password", &[]),
+            vec![PasswordViolation::Breached]
+        );
+        assert!(policy.violations("correct horse battery", &[]).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_breached_password_async() {
+        let dir = tempfile::tempdir().unwrap();
+        std::fs::write(
+            dir.path().join("5BAA6"),
+            "1E4C9B93F3F0682250B6CF8331B7EE68FD8:3\n",
+        )
+        .unwrap();
+
+        let policy = policy(PasswordConfig {
+            breached: Some(crate::auth::config::BreachedPasswordConfig {
+                format: crate::auth::config::BreachedPasswordFormat::Sha1Prefix,
+                path: dir.path().to_path_buf(),
+                min_occurrences: 3,
+            }),
+            ..Default::default()
+        });
+
+        assert_eq!(
+            policy.violations_async("password", &[]).await,
+            vec![PasswordViolation::Breached]
+        );
+        assert!(policy
+            .violations_async("correct horse battery", &[])
+            .await
+            .is_empty());
+    }
+
+    #[test]
+    fn test_policy_error_to_validation_error() {
+        let err = PasswordPolicyError::new(vec![
+            PasswordViolation::TooShort { min: 12 },
+            PasswordViolation::MissingDigit,
+        ]);
+
+        assert_eq!(
+            err.to_string(),
+            "Password must be at least 12 characters; Password must contain a digit"
+        );
+
+        let validation = err.to_validation_error();
+        assert_eq!(validation.error_count(), 2);
+        let field_errors = validation.errors.get("password").unwrap();
+        assert_eq!(field_errors[0].code, "TOO_SHORT");
+        assert_eq!(field_errors[1].code, "MISSING_DIGIT");
+    }
+}
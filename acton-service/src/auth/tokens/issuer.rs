@@ -0,0 +1,413 @@
+//! Issuing access/refresh token pairs
+//!
+//! Combines a [`TokenGenerator`] for the access token with a
+//! [`RefreshTokenStorage`] for the refresh token, so login flows hand out a
+//! complete [`TokenPair`] in one call.
+//!
+//! Refresh tokens are opaque random strings. Only their BLAKE3 hash is
+//! stored (as the token ID), so a leaked storage table holds nothing a client
+//! could present.
+//!
+//! Refreshing rotates the token within its family. A rotated (revoked) token
+//! presented again means two parties hold the same family, so with
+//! `detect_reuse` on the whole family is revoked.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::Utc;
+
+use super::refresh::{RefreshTokenData, RefreshTokenMetadata, RefreshTokenStorage};
+use super::{TokenGenerator, TokenPair};
+use crate::auth::config::RefreshTokenConfig;
+use crate::error::Error;
+use crate::middleware::Claims;
+
+type SignFn = dyn Fn(&Claims) -> Result<String, Error> + Send + Sync;
+
+/// Issues [`TokenPair`]s from claims
+#[derive(Clone)]
+pub struct TokenIssuer {
+    sign: Arc<SignFn>,
+    access_lifetime: Duration,
+    refresh_storage: Arc<dyn RefreshTokenStorage>,
+    refresh_lifetime: Duration,
+    rotate_on_refresh: bool,
+    detect_reuse: bool,
+}
+
+/// What a presented refresh token turned out to be
+#[derive(Debug, Clone)]
+pub enum RefreshLookup {
+    /// A live token that may be exchanged
+    Valid(RefreshTokenData),
+    /// A revoked token presented again with `detect_reuse` on; its family
+    /// has now been revoked
+    Reused(RefreshTokenData),
+    /// A revoked token, with `detect_reuse` off
+    Revoked(RefreshTokenData),
+    /// An expired token
+    Expired(RefreshTokenData),
+    /// Not a token this issuer knows
+    Unknown,
+}
+
+impl TokenIssuer {
+    /// Create an issuer from a generator and refresh token storage
+    pub fn new<G>(
+        generator: G,
+        refresh_storage: Arc<dyn RefreshTokenStorage>,
+        config: &RefreshTokenConfig,
+    ) -> Self
+    where
+        G: TokenGenerator + 'static,
+    {
+        let access_lifetime = generator.default_lifetime();
+        Self {
+            sign: Arc::new(move |claims: &Claims| generator.generate_token(claims)),
+            access_lifetime,
+            refresh_storage,
+            refresh_lifetime: Duration::from_secs(config.lifetime_secs.max(0) as u64),
+            rotate_on_refresh: config.rotate_on_refresh,
+            detect_reuse: config.detect_reuse,
+        }
+    }
+
+    /// Issue a token pair starting a new refresh token family
+    ///
+    /// `claims.sub` becomes the refresh token's user ID.
+    pub async fn issue(
+        &self,
+        claims: &Claims,
+        metadata: &RefreshTokenMetadata,
+    ) -> Result<TokenPair, Error> {
+        let access_token = (self.sign)(claims)?;
+
+        let refresh_token = random_token();
+        let family_id = random_token();
+        let expires_at = Utc::now()
+            + chrono::Duration::from_std(self.refresh_lifetime)
+                .map_err(|e| Error::Internal(format!("Invalid refresh lifetime: {}", e)))?;
+
+        self.refresh_storage
+            .store(
+                &refresh_token_id(&refresh_token),
+                &claims.sub,
+                &family_id,
+                expires_at,
+                metadata,
+            )
+            .await?;
+
+        Ok(TokenPair::new(
+            access_token,
+            refresh_token,
+            self.access_lifetime.as_secs() as i64,
+            self.refresh_lifetime.as_secs() as i64,
+        ))
+    }
+
+    /// Look up a presented refresh token
+    ///
+    /// Revokes the token's family when a revoked token is presented and
+    /// reuse detection is on.
+    pub async fn lookup_refresh(&self, refresh_token: &str) -> Result<RefreshLookup, Error> {
+        let Some(data) = self
+            .refresh_storage
+            .get(&refresh_token_id(refresh_token))
+            .await?
+        else {
+            return Ok(RefreshLookup::Unknown);
+        };
+
+        if data.is_revoked {
+            if !self.detect_reuse {
+                return Ok(RefreshLookup::Revoked(data));
+            }
+            self.refresh_storage.revoke_family(&data.family_id).await?;
+            return Ok(RefreshLookup::Reused(data));
+        }
+
+        if data.expires_at <= Utc::now() {
+            return Ok(RefreshLookup::Expired(data));
+        }
+
+        Ok(RefreshLookup::Valid(data))
+    }
+
+    /// Exchange a live refresh token for a new pair
+    ///
+    /// `current` comes from [`lookup_refresh`](Self::lookup_refresh). With
+    /// `rotate_on_refresh` on, the presented token is revoked and a new one
+    /// issued in the same family; otherwise the presented token is handed
+    /// back with its remaining lifetime.
+    pub async fn refresh(
+        &self,
+        refresh_token: &str,
+        current: &RefreshTokenData,
+        claims: &Claims,
+        metadata: &RefreshTokenMetadata,
+    ) -> Result<TokenPair, Error> {
+        let access_token = (self.sign)(claims)?;
+
+        if !self.rotate_on_refresh {
+            let remaining = (current.expires_at - Utc::now()).num_seconds().max(0);
+            return Ok(TokenPair::new(
+                access_token,
+                refresh_token.to_string(),
+                self.access_lifetime.as_secs() as i64,
+                remaining,
+            ));
+        }
+
+        let new_refresh_token = random_token();
+        let expires_at = Utc::now()
+            + chrono::Duration::from_std(self.refresh_lifetime)
+                .map_err(|e| Error::Internal(format!("Invalid refresh lifetime: {}", e)))?;
+
+        self.refresh_storage
+            .rotate(
+                &current.token_id,
+                &refresh_token_id(&new_refresh_token),
+                &current.user_id,
+                &current.family_id,
+                expires_at,
+                metadata,
+            )
+            .await?;
+
+        Ok(TokenPair::new(
+            access_token,
+            new_refresh_token,
+            self.access_lifetime.as_secs() as i64,
+            self.refresh_lifetime.as_secs() as i64,
+        ))
+    }
+
+    /// How long issued access tokens live
+    pub fn access_lifetime(&self) -> Duration {
+        self.access_lifetime
+    }
+
+    /// The refresh token storage backing this issuer
+    pub fn refresh_storage(&self) -> &Arc<dyn RefreshTokenStorage> {
+        &self.refresh_storage
+    }
+}
+
+/// The storage ID of a refresh token (its BLAKE3 hash)
+pub fn refresh_token_id(refresh_token: &str) -> String {
+    blake3::hash(refresh_token.as_bytes()).to_hex().to_string()
+}
+
+/// 256 random bits, base64url-encoded
+fn random_token() -> String {
+    let bytes: [u8; 32] = rand::random();
+    URL_SAFE_NO_PAD.encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::auth::tokens::ClaimsBuilder;
+    use async_trait::async_trait;
+    use chrono::DateTime;
+    use std::sync::Mutex;
+
+    #[derive(Clone)]
+    struct StaticGenerator;
+
+    impl TokenGenerator for StaticGenerator {
+        fn generate_token(&self, claims: &Claims) -> Result<String, Error> {
+            Ok(format!("access-for-{}", claims.sub))
+        }
+
+        fn generate_token_with_expiry(
+            &self,
+            claims: &Claims,
+            _expires_in: Duration,
+        ) -> Result<String, Error> {
+            self.generate_token(claims)
+        }
+
+        fn default_lifetime(&self) -> Duration {
+            Duration::from_secs(900)
+        }
+    }
+
+    #[derive(Default)]
+    struct MemoryRefreshStorage {
+        tokens: Mutex<Vec<RefreshTokenData>>,
+    }
+
+    #[async_trait]
+    impl RefreshTokenStorage for MemoryRefreshStorage {
+        async fn store(
+            &self,
+            token_id: &str,
+            user_id: &str,
+            family_id: &str,
+            expires_at: DateTime<Utc>,
+            metadata: &RefreshTokenMetadata,
+        ) -> Result<(), Error> {
+            self.tokens.lock().unwrap().push(RefreshTokenData {
+                token_id: token_id.to_string(),
+                user_id: user_id.to_string(),
+                family_id: family_id.to_string(),
+                is_revoked: false,
+                expires_at,
+                metadata: metadata.clone(),
+            });
+            Ok(())
+        }
+
+        async fn get(&self, token_id: &str) -> Result<Option<RefreshTokenData>, Error> {
+            Ok(self
+                .tokens
+                .lock()
+                .unwrap()
+                .iter()
+                .find(|t| t.token_id == token_id)
+                .cloned())
+        }
+
+        async fn revoke(&self, token_id: &str) -> Result<(), Error> {
+            for token in self.tokens.lock().unwrap().iter_mut() {
+                if token.token_id == token_id {
+                    token.is_revoked = true;
+                }
+            }
+            Ok(())
+        }
+
+        async fn revoke_family(&self, family_id: &str) -> Result<u64, Error> {
+            let mut revoked = 0;
+            for token in self.tokens.lock().unwrap().iter_mut() {
+                if token.family_id == family_id && !token.is_revoked {
+                    token.is_revoked = true;
+                    revoked += 1;
+                }
+            }
+            Ok(revoked)
+        }
+
+        async fn revoke_all_for_user(&self, _user_id: &str) -> Result<u64, Error> {
+            Ok(0)
+        }
+
+        async fn rotate(
+            &self,
+            old_token_id: &str,
+            new_token_id: &str,
+            user_id: &str,
+            family_id: &str,
+            expires_at: DateTime<Utc>,
+            metadata: &RefreshTokenMetadata,
+        ) -> Result<(), Error> {
+            self.revoke(old_token_id).await?;
+            self.store(new_token_id, user_id, family_id, expires_at, metadata)
+                .await
+        }
+
+        async fn cleanup_expired(&self) -> Result<u64, Error> {
+            Ok(0)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_issue_stores_only_refresh_token_hash() {
+        let storage = Arc::new(MemoryRefreshStorage::default());
+        let issuer = TokenIssuer::new(
+            StaticGenerator,
+            storage.clone(),
+            &RefreshTokenConfig::default(),
+        );
+        let claims = ClaimsBuilder::new().user("acct_1").build().unwrap();
+
+        let pair = issuer
+            .issue(&claims, &RefreshTokenMetadata::default())
+            .await
+            .unwrap();
+        assert_eq!(pair.access_token, "access-for-user:acct_1");
+        assert_eq!(pair.expires_in, 900);
+        assert_eq!(pair.refresh_expires_in, 604800);
+
+        // Looked up by hash, never by the token itself
+        assert!(storage.get(&pair.refresh_token).await.unwrap().is_none());
+        let stored = storage
+            .get(&refresh_token_id(&pair.refresh_token))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(stored.user_id, "user:acct_1");
+    }
+
+    #[tokio::test]
+    async fn test_refresh_rotates_and_detects_reuse() {
+        let storage = Arc::new(MemoryRefreshStorage::default());
+        let issuer = TokenIssuer::new(
+            StaticGenerator,
+            storage.clone(),
+            &RefreshTokenConfig::default(),
+        );
+        let claims = ClaimsBuilder::new().user("acct_1").build().unwrap();
+        let metadata = RefreshTokenMetadata::default();
+        let first = issuer.issue(&claims, &metadata).await.unwrap();
+
+        let RefreshLookup::Valid(current) =
+            issuer.lookup_refresh(&first.refresh_token).await.unwrap()
+        else {
+            panic!("freshly issued token must be valid");
+        };
+        let second = issuer
+            .refresh(&first.refresh_token, &current, &claims, &metadata)
+            .await
+            .unwrap();
+        assert_ne!(second.refresh_token, first.refresh_token);
+
+        // The rotated-away token is refused and takes its family down with it
+        assert!(matches!(
+            issuer.lookup_refresh(&first.refresh_token).await.unwrap(),
+            RefreshLookup::Reused(_)
+        ));
+        assert!(matches!(
+            issuer.lookup_refresh(&second.refresh_token).await.unwrap(),
+            RefreshLookup::Reused(_)
+        ));
+        assert!(matches!(
+            issuer.lookup_refresh("never-issued").await.unwrap(),
+            RefreshLookup::Unknown
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_without_rotation_keeps_token() {
+        let storage = Arc::new(MemoryRefreshStorage::default());
+        let config = RefreshTokenConfig {
+            rotate_on_refresh: false,
+            ..Default::default()
+        };
+        let issuer = TokenIssuer::new(StaticGenerator, storage, &config);
+        let claims = ClaimsBuilder::new().user("acct_1").build().unwrap();
+        let metadata = RefreshTokenMetadata::default();
+        let first = issuer.issue(&claims, &metadata).await.unwrap();
+
+        let RefreshLookup::Valid(current) =
+            issuer.lookup_refresh(&first.refresh_token).await.unwrap()
+        else {
+            panic!("freshly issued token must be valid");
+        };
+        let second = issuer
+            .refresh(&first.refresh_token, &current, &claims, &metadata)
+            .await
+            .unwrap();
+        assert_eq!(second.refresh_token, first.refresh_token);
+        assert!(second.refresh_expires_in <= first.refresh_expires_in);
+        assert!(matches!(
+            issuer.lookup_refresh(&first.refresh_token).await.unwrap(),
+            RefreshLookup::Valid(_)
+        ));
+    }
+}
@@ -31,6 +31,7 @@ pub mod paseto_generator;
 #[cfg(feature = "jwt")]
 pub mod jwt_generator;
 
+pub mod issuer;
 pub mod refresh;
 
 use std::collections::HashMap;
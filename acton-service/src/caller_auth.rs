@@ -17,9 +17,10 @@
 //! - [`CallerSan`] is a caller's name, taken from a DNS or URI SAN. Matching is
 //!   byte-exact within a kind. There is no wildcard, suffix or subdomain
 //!   matching, and a DNS SAN never matches a URI allowlist entry.
-//! - [`CallerAllowlist`] is the set of names permitted to call. It cannot be
-//!   constructed empty: an empty allowlist that admits everyone is the classic
-//!   way this control fails open.
+//! - [`CallerAllowlist`] is the set of names permitted to call, plus any
+//!   [`SpiffeIdPattern`]s admitting a subtree of one SPIFFE trust domain. It
+//!   cannot be constructed empty: an empty allowlist that admits everyone is
+//!   the classic way this control fails open.
 //! - [`CallerAuthMode`] selects what counts as proof — a certificate, a bearer
 //!   token, or either. [`CallerAuthMode::MtlsOrBearer`] exists so a deployment
 //!   can cut over without a flag day.
@@ -37,14 +38,33 @@
 //! is not being accepted" from "my certificate is fine, you have not authorized
 //! me" without reading the server's logs.
 //!
-//! # What this does not do
+//! # SPIFFE workloads
 //!
-//! A certificate-authorized request carries no
-//! [`Claims`](crate::middleware::token::Claims). Cedar authorization
-//! ([`CedarAuthz`](crate::middleware::CedarAuthz)) derives its principal from
-//! claims, so Cedar-protected routes still require a bearer token even when
-//! the caller is allowlisted here. This layer is a transport-level admission
-//! control, not a replacement for policy authorization.
+//! A caller presenting an X.509-SVID is named by its SPIFFE ID, a URI SAN like
+//! any other. Two things set it apart, both opt-in:
+//!
+//! - An allowlist entry ending in `/*`, such as
+//!   `spiffe://prod.local/ns/payments/*`, admits every workload under that
+//!   path in that trust domain. Exact `spiffe://` entries are checked against
+//!   the SPIFFE ID grammar when the allowlist is built.
+//! - [`CallerAuthPolicy::with_spiffe_bundles`] attaches one trust bundle per
+//!   trust domain. The listener's CA bundle has to trust every federated
+//!   domain's roots to complete the handshake at all; with per-domain bundles
+//!   attached, a SPIFFE caller must additionally chain to the bundle of the
+//!   trust domain its own ID names, so one domain's CA cannot mint identities
+//!   in another.
+//!
+//! See [`crate::spiffe`] for the ID grammar and bundle rotation.
+//!
+//! # Policy authorization
+//!
+//! This layer is a transport-level admission control, not a replacement for
+//! policy authorization. Cedar ([`CedarAuthz`](crate::middleware::CedarAuthz))
+//! composes with it: a request that carries no
+//! [`Claims`](crate::middleware::token::Claims) but does carry a
+//! [`CallerIdentity`] is evaluated with that caller as a `Workload` principal,
+//! so certificate-authorized callers can be authorized by policy without a
+//! bearer token.
 //!
 //! # Example
 //!
@@ -75,10 +95,12 @@ use std::task::{Context, Poll};
 
 use axum::response::IntoResponse;
 use http::StatusCode;
+use rustls_pki_types::CertificateDer;
 use serde::{Deserialize, Serialize};
 use tower::{Layer, Service};
 
 use crate::error::ErrorResponse;
+use crate::spiffe::{SpiffeId, SpiffeIdError, SpiffeIdPattern, SpiffeTrustBundles};
 
 /// Longest accepted `subjectAltName` value, in bytes.
 ///
@@ -262,12 +284,19 @@ pub enum CallerSanError {
 
 /// The set of callers permitted to reach a route.
 ///
+/// Exact caller names, plus any [`SpiffeIdPattern`]s. A pattern is the only
+/// way one entry admits more than one caller, and it is scoped to a single
+/// SPIFFE trust domain by construction.
+///
 /// Cannot be constructed empty. An allowlist with no entries either admits
 /// everyone (fails open, silently) or admits no one (fails closed, and no
 /// operator writes that on purpose); refusing to build one turns the mistake
 /// into a startup error instead of a runtime surprise.
 #[derive(Debug, Clone, PartialEq, Eq)]
-pub struct CallerAllowlist(BTreeSet<CallerSan>);
+pub struct CallerAllowlist {
+    names: BTreeSet<CallerSan>,
+    spiffe_patterns: BTreeSet<SpiffeIdPattern>,
+}
 
 impl CallerAllowlist {
     /// Build an allowlist from caller names.
@@ -280,19 +309,41 @@ impl CallerAllowlist {
     where
         I: IntoIterator<Item = CallerSan>,
     {
-        let set: BTreeSet<CallerSan> = entries.into_iter().collect();
-        if set.is_empty() {
+        Self::with_patterns(entries, [])
+    }
+
+    /// Build an allowlist from caller names and SPIFFE ID patterns.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CallerAuthConfigError::EmptyAllowlist`] if both iterators
+    /// yield nothing.
+    pub fn with_patterns<I, P>(entries: I, patterns: P) -> Result<Self, CallerAuthConfigError>
+    where
+        I: IntoIterator<Item = CallerSan>,
+        P: IntoIterator<Item = SpiffeIdPattern>,
+    {
+        let names: BTreeSet<CallerSan> = entries.into_iter().collect();
+        let spiffe_patterns: BTreeSet<SpiffeIdPattern> = patterns.into_iter().collect();
+        if names.is_empty() && spiffe_patterns.is_empty() {
             return Err(CallerAuthConfigError::EmptyAllowlist);
         }
-        Ok(Self(set))
+        Ok(Self {
+            names,
+            spiffe_patterns,
+        })
     }
 
     /// Build an allowlist from configuration strings, inferring each kind with
     /// [`CallerSan::parse`].
     ///
+    /// Entries starting with `spiffe://` are held to the SPIFFE ID grammar,
+    /// and one ending in `/*` becomes a [`SpiffeIdPattern`].
+    ///
     /// # Errors
     ///
-    /// Returns [`CallerAuthConfigError::InvalidEntry`] for the first
+    /// Returns [`CallerAuthConfigError::InvalidEntry`] or
+    /// [`CallerAuthConfigError::InvalidSpiffeEntry`] for the first
     /// unparseable entry, or [`CallerAuthConfigError::EmptyAllowlist`] if
     /// there are none.
     pub fn from_entries<I, S>(entries: I) -> Result<Self, CallerAuthConfigError>
@@ -300,35 +351,79 @@ impl CallerAllowlist {
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        let parsed = entries
-            .into_iter()
-            .map(|entry| {
-                let entry = entry.as_ref();
-                CallerSan::parse(entry).map_err(|source| CallerAuthConfigError::InvalidEntry {
+        let mut names = Vec::new();
+        let mut patterns = Vec::new();
+        for entry in entries {
+            let entry = entry.as_ref();
+            let invalid_spiffe = |source| CallerAuthConfigError::InvalidSpiffeEntry {
+                entry: entry.to_string(),
+                source,
+            };
+
+            if entry.starts_with("spiffe://") {
+                if entry.ends_with("/*") {
+                    patterns.push(SpiffeIdPattern::parse(entry).map_err(invalid_spiffe)?);
+                    continue;
+                }
+                SpiffeId::parse(entry).map_err(invalid_spiffe)?;
+            }
+            names.push(CallerSan::parse(entry).map_err(|source| {
+                CallerAuthConfigError::InvalidEntry {
                     entry: entry.to_string(),
                     source,
-                })
-            })
-            .collect::<Result<Vec<_>, _>>()?;
-        Self::new(parsed)
+                }
+            })?);
+        }
+        Self::with_patterns(names, patterns)
     }
 
-    /// Whether this caller is allowed.
+    /// Whether this caller is allowed, by name or by SPIFFE ID pattern.
     #[must_use]
     pub fn contains(&self, san: &CallerSan) -> bool {
-        self.0.contains(san)
+        if self.names.contains(san) {
+            return true;
+        }
+        if san.kind != SanKind::Uri || self.spiffe_patterns.is_empty() {
+            return false;
+        }
+        SpiffeId::parse(&san.value).is_ok_and(|id| {
+            self.spiffe_patterns
+                .iter()
+                .any(|pattern| pattern.matches(&id))
+        })
     }
 
-    /// How many callers are allowed. Always at least one — there is
-    /// deliberately no `is_empty`, because an empty allowlist cannot exist.
+    /// How many entries the allowlist holds, names and patterns together.
+    /// Always at least one — there is deliberately no `is_empty`, because an
+    /// empty allowlist cannot exist.
     #[must_use]
     pub fn count(&self) -> usize {
-        self.0.len()
+        self.names.len() + self.spiffe_patterns.len()
     }
 
-    /// Iterate the allowed callers.
+    /// Iterate the callers allowed by exact name.
     pub fn iter(&self) -> impl Iterator<Item = &CallerSan> {
-        self.0.iter()
+        self.names.iter()
+    }
+
+    /// Iterate the SPIFFE ID patterns.
+    pub fn spiffe_patterns(&self) -> impl Iterator<Item = &SpiffeIdPattern> {
+        self.spiffe_patterns.iter()
+    }
+
+    /// Every SPIFFE trust domain an entry names, exactly or by pattern.
+    fn spiffe_trust_domains(&self) -> BTreeSet<String> {
+        let exact = self
+            .names
+            .iter()
+            .filter(|san| san.kind == SanKind::Uri)
+            .filter_map(|san| SpiffeId::parse(&san.value).ok())
+            .map(|id| id.trust_domain().to_string());
+        let patterns = self
+            .spiffe_patterns
+            .iter()
+            .map(|pattern| pattern.trust_domain().to_string());
+        exact.chain(patterns).collect()
     }
 }
 
@@ -337,7 +432,7 @@ impl<'a> IntoIterator for &'a CallerAllowlist {
     type IntoIter = std::collections::btree_set::Iter<'a, CallerSan>;
 
     fn into_iter(self) -> Self::IntoIter {
-        self.0.iter()
+        self.names.iter()
     }
 }
 
@@ -399,6 +494,7 @@ pub struct CallerAuthPolicy {
     mode: CallerAuthMode,
     allowlist: Option<CallerAllowlist>,
     public_paths: Arc<[String]>,
+    spiffe_bundles: Option<SpiffeTrustBundles>,
 }
 
 impl CallerAuthPolicy {
@@ -409,6 +505,7 @@ impl CallerAuthPolicy {
             mode: CallerAuthMode::Bearer,
             allowlist: None,
             public_paths: Arc::from(Vec::new()),
+            spiffe_bundles: None,
         }
     }
 
@@ -419,6 +516,7 @@ impl CallerAuthPolicy {
             mode: CallerAuthMode::Mtls,
             allowlist: Some(allowlist),
             public_paths: Arc::from(Vec::new()),
+            spiffe_bundles: None,
         }
     }
 
@@ -429,6 +527,7 @@ impl CallerAuthPolicy {
             mode: CallerAuthMode::MtlsOrBearer,
             allowlist: Some(allowlist),
             public_paths: Arc::from(Vec::new()),
+            spiffe_bundles: None,
         }
     }
 
@@ -445,6 +544,36 @@ impl CallerAuthPolicy {
         self
     }
 
+    /// Verify SPIFFE callers against their own trust domain's bundle.
+    ///
+    /// Once attached, a caller admitted under a SPIFFE ID must present a chain
+    /// that verifies against the bundle of that ID's trust domain, on top of
+    /// the listener's handshake verification.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`CallerAuthConfigError::SpiffeBundlesWithoutCertificateMode`]
+    /// under [`CallerAuthMode::Bearer`], and
+    /// [`CallerAuthConfigError::UnbundledTrustDomain`] when the allowlist names
+    /// a trust domain with no bundle, whose callers could then never verify.
+    pub fn with_spiffe_bundles(
+        mut self,
+        bundles: SpiffeTrustBundles,
+    ) -> Result<Self, CallerAuthConfigError> {
+        let Some(allowlist) = self.allowlist.as_ref() else {
+            return Err(CallerAuthConfigError::SpiffeBundlesWithoutCertificateMode);
+        };
+        if let Some(trust_domain) = allowlist
+            .spiffe_trust_domains()
+            .into_iter()
+            .find(|trust_domain| !bundles.contains(trust_domain))
+        {
+            return Err(CallerAuthConfigError::UnbundledTrustDomain { trust_domain });
+        }
+        self.spiffe_bundles = Some(bundles);
+        Ok(self)
+    }
+
     /// The configured mode.
     #[must_use]
     pub fn mode(&self) -> CallerAuthMode {
@@ -463,6 +592,12 @@ impl CallerAuthPolicy {
         &self.public_paths
     }
 
+    /// The per-trust-domain SPIFFE bundles, if attached.
+    #[must_use]
+    pub fn spiffe_bundles(&self) -> Option<&SpiffeTrustBundles> {
+        self.spiffe_bundles.as_ref()
+    }
+
     /// Whether this policy needs the listener to verify client certificates.
     ///
     /// A `true` here with no `client_ca_path` on the listener is a
@@ -501,6 +636,38 @@ pub enum CallerAuthConfigError {
         source: CallerSanError,
     },
 
+    /// A `spiffe://` allowlist entry was not a valid SPIFFE ID or pattern.
+    #[error("[caller_auth].allowlist entry '{entry}' is not a valid SPIFFE ID: {source}")]
+    InvalidSpiffeEntry {
+        /// The offending entry.
+        entry: String,
+        /// Why it was rejected.
+        source: SpiffeIdError,
+    },
+
+    /// SPIFFE trust bundles were configured under [`CallerAuthMode::Bearer`],
+    /// where no certificate is ever checked against them.
+    #[error(
+        "[caller_auth.spiffe] is set but mode is 'bearer', so no certificate is ever checked \
+         against its bundles; set mode to 'mtls' or 'mtls-or-bearer', or remove the section"
+    )]
+    SpiffeBundlesWithoutCertificateMode,
+
+    /// The allowlist names a SPIFFE trust domain that has no bundle.
+    #[error(
+        "[caller_auth].allowlist admits SPIFFE IDs in trust domain '{trust_domain}', but \
+         [caller_auth.spiffe].trust_bundles has no bundle for it, so none of them could ever \
+         verify"
+    )]
+    UnbundledTrustDomain {
+        /// The trust domain without a bundle.
+        trust_domain: String,
+    },
+
+    /// A SPIFFE trust bundle could not be loaded.
+    #[error("[caller_auth.spiffe] could not be loaded: {0}")]
+    TrustBundle(String),
+
     /// A certificate mode was configured on a listener that never asks for a
     /// client certificate.
     #[error(
@@ -631,12 +798,26 @@ pub enum CallerAuthError {
     /// The caller was named, and that name is not allowlisted.
     #[error("caller {0} is not on the allowlist")]
     NotAllowlisted(CallerSan),
+
+    /// The caller's SPIFFE ID is allowlisted, but its certificate does not
+    /// chain to that ID's trust domain bundle.
+    #[error("caller {caller} is not vouched for by its trust domain: {reason}")]
+    UntrustedTrustDomain {
+        /// The SPIFFE ID the certificate claimed.
+        caller: CallerSan,
+        /// Why verification failed.
+        reason: String,
+    },
 }
 
 impl CallerAuthError {
     /// Whether the caller failed to prove any identity (`401`), as opposed to
     /// proving one that is not authorized (`403`).
     ///
+    /// [`CallerAuthError::UntrustedTrustDomain`] counts as authentication: a
+    /// certificate whose own trust domain does not vouch for it has not proven
+    /// the identity it names.
+    ///
     /// [`CallerAuthError::NoUsableSan`] counts as authorization: the chain
     /// verified, so the caller *has* a trusted certificate — it simply carries
     /// no name that an allowlist could ever contain. Answering `401` there
@@ -645,7 +826,10 @@ impl CallerAuthError {
     #[must_use]
     pub fn is_authentication_failure(&self) -> bool {
         match self {
-            Self::NoClientCertificate | Self::NoCredential | Self::MalformedCertificate(_) => true,
+            Self::NoClientCertificate
+            | Self::NoCredential
+            | Self::MalformedCertificate(_)
+            | Self::UntrustedTrustDomain { .. } => true,
             Self::NoUsableSan | Self::NotAllowlisted(_) => false,
         }
     }
@@ -670,6 +854,7 @@ impl CallerAuthError {
             Self::MalformedCertificate(_) => "CLIENT_CERT_UNPARSABLE",
             Self::NoUsableSan => "CLIENT_CERT_NO_SAN",
             Self::NotAllowlisted(_) => "CALLER_NOT_ALLOWED",
+            Self::UntrustedTrustDomain { .. } => "CLIENT_CERT_WRONG_TRUST_DOMAIN",
         }
     }
 }
@@ -682,8 +867,11 @@ impl CallerAuthError {
 ///
 /// `leaf_der` is the DER-encoded end-entity certificate from a *completed*
 /// mutual-TLS handshake — rustls has already verified it against the
-/// configured CA bundle. This function never re-checks the chain; it only
-/// names the caller and consults the allowlist.
+/// configured CA bundle. This function never re-checks the chain against that
+/// bundle; it only names the caller and consults the allowlist. With
+/// [SPIFFE bundles](CallerAuthPolicy::with_spiffe_bundles) attached, use
+/// [`authorize_chain`] instead: a leaf alone carries no intermediates to
+/// verify through.
 ///
 /// `bearer_present` says whether the request carried a bearer credential. It
 /// says nothing about that credential being valid, which is the token
@@ -699,12 +887,36 @@ pub fn authorize(
     leaf_der: Option<&[u8]>,
     bearer_present: bool,
 ) -> Result<AuthenticatedCaller, CallerAuthError> {
+    let chain = leaf_der.map(|leaf| [CertificateDer::from(leaf)]);
+    authorize_chain(policy, chain.as_ref().map(|c| c.as_slice()), bearer_present)
+}
+
+/// Decide whether a caller may proceed, given its whole verified chain.
+///
+/// As [`authorize`], with `chain` the certificates the client presented, leaf
+/// first. The intermediates matter only when the policy carries
+/// [SPIFFE bundles](CallerAuthPolicy::with_spiffe_bundles): a SPIFFE caller is
+/// then verified against its own trust domain's bundle, and an intermediate
+/// CA sits between most SVIDs and their root.
+///
+/// # Errors
+///
+/// As [`authorize`], plus [`CallerAuthError::UntrustedTrustDomain`] when a
+/// SPIFFE caller does not chain to its trust domain's bundle.
+pub fn authorize_chain(
+    policy: &CallerAuthPolicy,
+    chain: Option<&[CertificateDer<'_>]>,
+    bearer_present: bool,
+) -> Result<AuthenticatedCaller, CallerAuthError> {
+    // An empty chain is no certificate at all.
+    let chain = chain.filter(|chain| !chain.is_empty());
+
     match policy.mode {
         CallerAuthMode::Bearer => Ok(AuthenticatedCaller::BearerDeferred),
 
         CallerAuthMode::Mtls => {
-            let leaf = leaf_der.ok_or(CallerAuthError::NoClientCertificate)?;
-            authorize_certificate(policy, leaf).map(AuthenticatedCaller::Certificate)
+            let chain = chain.ok_or(CallerAuthError::NoClientCertificate)?;
+            authorize_certificate(policy, chain).map(AuthenticatedCaller::Certificate)
         }
 
         CallerAuthMode::MtlsOrBearer => {
@@ -714,8 +926,8 @@ pub fn authorize(
             // credential get its turn: during a cutover, a caller whose
             // certificate is not yet allowlisted must keep working on the
             // credential it had before, not start failing.
-            let cert_outcome = match leaf_der {
-                Some(leaf) => authorize_certificate(policy, leaf),
+            let cert_outcome = match chain {
+                Some(chain) => authorize_certificate(policy, chain),
                 None => Err(CallerAuthError::NoClientCertificate),
             };
 
@@ -736,12 +948,15 @@ pub fn authorize(
     }
 }
 
-/// Name the caller from its leaf certificate and consult the allowlist.
+/// Name the caller from its leaf certificate, consult the allowlist, and check
+/// a SPIFFE caller against its trust domain's bundle.
+///
+/// `chain` is non-empty.
 fn authorize_certificate(
     policy: &CallerAuthPolicy,
-    leaf_der: &[u8],
+    chain: &[CertificateDer<'_>],
 ) -> Result<CallerSan, CallerAuthError> {
-    let sans = subject_alt_names(leaf_der)?;
+    let sans = subject_alt_names(&chain[0])?;
     if sans.is_empty() {
         return Err(CallerAuthError::NoUsableSan);
     }
@@ -754,10 +969,38 @@ fn authorize_certificate(
         return Err(CallerAuthError::NotAllowlisted(sans[0].clone()));
     };
 
-    sans.iter()
+    let san = sans
+        .iter()
         .find(|san| allowlist.contains(san))
         .cloned()
-        .ok_or_else(|| CallerAuthError::NotAllowlisted(sans[0].clone()))
+        .ok_or_else(|| CallerAuthError::NotAllowlisted(sans[0].clone()))?;
+
+    if let Some(bundles) = policy.spiffe_bundles.as_ref() {
+        verify_trust_domain(bundles, &san, chain)?;
+    }
+    Ok(san)
+}
+
+/// Check that a SPIFFE caller chains to its own trust domain's bundle.
+///
+/// Names that are not SPIFFE IDs pass through untouched: the bundles speak
+/// only for SPIFFE trust domains. A `spiffe://` name that does not parse is
+/// refused rather than waved through, so a malformed ID cannot dodge the
+/// trust-domain check.
+fn verify_trust_domain(
+    bundles: &SpiffeTrustBundles,
+    san: &CallerSan,
+    chain: &[CertificateDer<'_>],
+) -> Result<(), CallerAuthError> {
+    if san.kind != SanKind::Uri || !san.value.starts_with("spiffe://") {
+        return Ok(());
+    }
+    let untrusted = |reason: String| CallerAuthError::UntrustedTrustDomain {
+        caller: san.clone(),
+        reason,
+    };
+    let id = SpiffeId::parse(&san.value).map_err(|e| untrusted(e.to_string()))?;
+    bundles.verify(&id, chain).map_err(untrusted)
 }
 
 /// Extract every usable DNS and URI `subjectAltName` from a DER leaf.
@@ -826,12 +1069,25 @@ pub struct CallerIdentity {
 }
 
 impl CallerIdentity {
+    pub(crate) fn new(san: CallerSan, waives_bearer: bool) -> Self {
+        Self { san, waives_bearer }
+    }
+
     /// The allowlisted name this caller proved.
     #[must_use]
     pub fn san(&self) -> &CallerSan {
         &self.san
     }
 
+    /// The caller's SPIFFE ID, when the name it proved is one.
+    #[must_use]
+    pub fn spiffe_id(&self) -> Option<SpiffeId> {
+        match self.san.kind {
+            SanKind::Uri => SpiffeId::parse(&self.san.value).ok(),
+            SanKind::Dns => None,
+        }
+    }
+
     /// Whether this certificate identity stands in for a bearer credential.
     ///
     /// True only under [`CallerAuthMode::MtlsOrBearer`], where the certificate
@@ -962,16 +1218,20 @@ where
             return Box::pin(async move { inner.call(req).await });
         }
 
-        let leaf = leaf_certificate(req.extensions());
+        let chain = peer_certificates(req.extensions());
         let bearer_present = has_bearer_credential(req.headers());
 
-        match authorize(&self.policy, leaf.as_deref(), bearer_present) {
+        match authorize_chain(
+            &self.policy,
+            chain.as_ref().map(crate::tls::PeerCertificates::as_slice),
+            bearer_present,
+        ) {
             Ok(AuthenticatedCaller::Certificate(san)) => {
                 tracing::debug!(caller = %san, "caller authorized by client certificate");
-                req.extensions_mut().insert(CallerIdentity {
+                req.extensions_mut().insert(CallerIdentity::new(
                     san,
-                    waives_bearer: self.policy.mode == CallerAuthMode::MtlsOrBearer,
-                });
+                    self.policy.mode == CallerAuthMode::MtlsOrBearer,
+                ));
                 Box::pin(async move { inner.call(req).await })
             }
             Ok(AuthenticatedCaller::BearerDeferred) => {
@@ -1040,15 +1300,12 @@ impl<S> CallerAuthService<S> {
     }
 }
 
-/// The verified leaf certificate for this request, if the connection was
+/// The verified certificate chain for this request, if the connection was
 /// mutually authenticated.
-fn leaf_certificate(extensions: &http::Extensions) -> Option<Vec<u8>> {
+fn peer_certificates(extensions: &http::Extensions) -> Option<crate::tls::PeerCertificates> {
     let connect_info =
         extensions.get::<axum::extract::ConnectInfo<crate::tls::TlsConnectInfo>>()?;
-    connect_info
-        .0
-        .peer_certificates()
-        .map(|chain| chain.leaf().as_ref().to_vec())
+    connect_info.0.peer_certificates().cloned()
 }
 
 /// Whether the request carries a bearer credential, without judging it.
@@ -1199,6 +1456,45 @@ mod tests {
             let list = allowlist(&["a.internal", "a.internal", "b.internal"]);
             assert_eq!(list.count(), 2);
         }
+
+        #[test]
+        fn spiffe_entries_are_held_to_the_spiffe_grammar() {
+            let list = allowlist(&[
+                "spiffe://prod.local/ns/payments/*",
+                "spiffe://prod.local/ns/billing/sa/api",
+            ]);
+            assert_eq!(list.count(), 2);
+            assert_eq!(list.spiffe_patterns().count(), 1);
+
+            for bad in [
+                "spiffe://Prod.Local/ns/billing",
+                "spiffe://prod.local/ns/*/sa/api",
+                "spiffe://prod.local/ns//api",
+            ] {
+                assert!(
+                    matches!(
+                        CallerAllowlist::from_entries([bad]),
+                        Err(CallerAuthConfigError::InvalidSpiffeEntry { .. })
+                    ),
+                    "{bad} must be refused"
+                );
+            }
+        }
+
+        #[test]
+        fn a_pattern_admits_its_subtree_within_one_trust_domain() {
+            let list = allowlist(&["spiffe://prod.local/ns/payments/*"]);
+            let uri = |value: &str| CallerSan::uri(value).expect("valid");
+
+            assert!(list.contains(&uri("spiffe://prod.local/ns/payments/sa/api")));
+            assert!(!list.contains(&uri("spiffe://prod.local/ns/payments")));
+            assert!(!list.contains(&uri("spiffe://prod.local/ns/payments-v2/sa/api")));
+            assert!(!list.contains(&uri("spiffe://staging.local/ns/payments/sa/api")));
+            assert!(
+                !list.contains(&CallerSan::dns("prod.local").expect("valid")),
+                "a pattern never matches a DNS name"
+            );
+        }
     }
 
     mod error_mapping {
@@ -1218,6 +1514,14 @@ mod tests {
                 CallerAuthError::MalformedCertificate("bad".into()).status(),
                 StatusCode::UNAUTHORIZED
             );
+            assert_eq!(
+                CallerAuthError::UntrustedTrustDomain {
+                    caller: CallerSan::uri("spiffe://prod.local/x").expect("valid"),
+                    reason: "bad".into(),
+                }
+                .status(),
+                StatusCode::UNAUTHORIZED
+            );
             assert_eq!(CallerAuthError::NoUsableSan.status(), StatusCode::FORBIDDEN);
             assert_eq!(
                 CallerAuthError::NotAllowlisted(CallerSan::dns("x.internal").expect("valid"))
@@ -1235,6 +1539,11 @@ mod tests {
                 CallerAuthError::NoUsableSan.code(),
                 CallerAuthError::NotAllowlisted(CallerSan::dns("x.internal").expect("valid"))
                     .code(),
+                CallerAuthError::UntrustedTrustDomain {
+                    caller: CallerSan::uri("spiffe://prod.local/x").expect("valid"),
+                    reason: "bad".into(),
+                }
+                .code(),
             ];
             let unique: BTreeSet<&str> = codes.iter().copied().collect();
             assert_eq!(unique.len(), codes.len());
@@ -1435,6 +1744,186 @@ mod tests {
         }
     }
 
+    mod spiffe_trust_domains {
+        use super::*;
+        use crate::config::SpiffeConfig;
+        use std::collections::BTreeMap;
+
+        struct Ca {
+            issuer: rcgen::Issuer<'static, rcgen::KeyPair>,
+            pem: String,
+        }
+
+        fn ca(name: &str) -> Ca {
+            let key = rcgen::KeyPair::generate().expect("ca key");
+            let mut params = rcgen::CertificateParams::new(Vec::new()).expect("ca params");
+            params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+            params.key_usages = vec![rcgen::KeyUsagePurpose::KeyCertSign];
+            params
+                .distinguished_name
+                .push(rcgen::DnType::CommonName, name);
+            let pem = params.self_signed(&key).expect("ca cert").pem();
+            Ca {
+                issuer: rcgen::Issuer::new(params, key),
+                pem,
+            }
+        }
+
+        /// An X.509-SVID for `spiffe_id`, leaf first, issued through an
+        /// intermediate as SPIRE issues them.
+        fn svid_chain(root: &Ca, spiffe_id: &str) -> Vec<CertificateDer<'static>> {
+            let intermediate_key = rcgen::KeyPair::generate().expect("intermediate key");
+            let mut params = rcgen::CertificateParams::new(Vec::new()).expect("params");
+            params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+            params.key_usages = vec![rcgen::KeyUsagePurpose::KeyCertSign];
+            params
+                .distinguished_name
+                .push(rcgen::DnType::CommonName, "intermediate");
+            let intermediate = params
+                .signed_by(&intermediate_key, &root.issuer)
+                .expect("intermediate");
+            let intermediate_issuer = rcgen::Issuer::new(params, intermediate_key);
+
+            let key = rcgen::KeyPair::generate().expect("svid key");
+            let mut params = rcgen::CertificateParams::new(Vec::new()).expect("params");
+            params.subject_alt_names = vec![uri_san(spiffe_id)];
+            params.extended_key_usages = vec![rcgen::ExtendedKeyUsagePurpose::ClientAuth];
+            let leaf = params.signed_by(&key, &intermediate_issuer).expect("svid");
+
+            vec![leaf.der().clone(), intermediate.der().clone()]
+        }
+
+        fn bundles(dir: &std::path::Path, domains: &[(&str, &Ca)]) -> SpiffeTrustBundles {
+            let trust_bundles = domains
+                .iter()
+                .map(|(domain, ca)| {
+                    let path = dir.join(format!("{domain}.pem"));
+                    std::fs::write(&path, &ca.pem).expect("write bundle");
+                    ((*domain).to_string(), path)
+                })
+                .collect::<BTreeMap<_, _>>();
+            SpiffeTrustBundles::from_config(&SpiffeConfig {
+                trust_bundles,
+                reload_interval_secs: None,
+            })
+            .expect("bundles load")
+        }
+
+        #[test]
+        fn a_caller_must_chain_to_its_own_trust_domain() {
+            let dir = tempfile::tempdir().expect("tempdir");
+            let prod = ca("prod");
+            let partner = ca("partner");
+            let policy = CallerAuthPolicy::mtls(allowlist(&["spiffe://prod.local/ns/payments/*"]))
+                .with_spiffe_bundles(bundles(
+                    dir.path(),
+                    &[("prod.local", &prod), ("partner.example", &partner)],
+                ))
+                .expect("every allowlisted trust domain has a bundle");
+            let id = "spiffe://prod.local/ns/payments/sa/api";
+
+            assert_eq!(
+                authorize_chain(&policy, Some(&svid_chain(&prod, id)), false)
+                    .expect("issued by its own trust domain"),
+                AuthenticatedCaller::Certificate(CallerSan::uri(id).expect("valid"))
+            );
+
+            // The partner CA completes the handshake, since the listener trusts
+            // every federated root, but it does not speak for prod.local.
+            let error = authorize_chain(&policy, Some(&svid_chain(&partner, id)), false)
+                .expect_err("a federated CA must not mint prod.local identities");
+            assert!(matches!(
+                error,
+                CallerAuthError::UntrustedTrustDomain { .. }
+            ));
+            assert_eq!(error.status(), StatusCode::UNAUTHORIZED);
+        }
+
+        #[test]
+        fn a_leaf_without_its_intermediate_does_not_verify() {
+            let dir = tempfile::tempdir().expect("tempdir");
+            let prod = ca("prod");
+            let policy = CallerAuthPolicy::mtls(allowlist(&["spiffe://prod.local/*"]))
+                .with_spiffe_bundles(bundles(dir.path(), &[("prod.local", &prod)]))
+                .expect("valid");
+            let chain = svid_chain(&prod, "spiffe://prod.local/api");
+
+            assert!(matches!(
+                authorize(&policy, Some(&chain[0]), false),
+                Err(CallerAuthError::UntrustedTrustDomain { .. })
+            ));
+        }
+
+        #[test]
+        fn mtls_or_bearer_falls_back_to_the_token_on_a_wrong_trust_domain() {
+            let dir = tempfile::tempdir().expect("tempdir");
+            let prod = ca("prod");
+            let rogue = ca("rogue");
+            let policy = CallerAuthPolicy::mtls_or_bearer(allowlist(&["spiffe://prod.local/*"]))
+                .with_spiffe_bundles(bundles(dir.path(), &[("prod.local", &prod)]))
+                .expect("valid");
+            let chain = svid_chain(&rogue, "spiffe://prod.local/api");
+
+            assert_eq!(
+                authorize_chain(&policy, Some(&chain), true).expect("bearer fallback"),
+                AuthenticatedCaller::BearerDeferred
+            );
+        }
+
+        #[test]
+        fn bundles_that_could_never_be_consulted_are_refused() {
+            let dir = tempfile::tempdir().expect("tempdir");
+            let prod = ca("prod");
+
+            assert_eq!(
+                CallerAuthPolicy::bearer()
+                    .with_spiffe_bundles(bundles(dir.path(), &[("prod.local", &prod)]))
+                    .expect_err("bearer mode checks no certificates"),
+                CallerAuthConfigError::SpiffeBundlesWithoutCertificateMode
+            );
+
+            assert_eq!(
+                CallerAuthPolicy::mtls(allowlist(&[
+                    "spiffe://prod.local/*",
+                    "spiffe://staging.local/api",
+                ]))
+                .with_spiffe_bundles(bundles(dir.path(), &[("prod.local", &prod)]))
+                .expect_err("staging.local callers could never verify"),
+                CallerAuthConfigError::UnbundledTrustDomain {
+                    trust_domain: "staging.local".to_string()
+                }
+            );
+        }
+
+        #[test]
+        fn non_spiffe_callers_are_not_held_to_a_bundle() {
+            let dir = tempfile::tempdir().expect("tempdir");
+            let prod = ca("prod");
+            let policy =
+                CallerAuthPolicy::mtls(allowlist(&["reporter.internal", "spiffe://prod.local/*"]))
+                    .with_spiffe_bundles(bundles(dir.path(), &[("prod.local", &prod)]))
+                    .expect("valid");
+            let der = leaf_with_sans(&[dns_san("reporter.internal")]);
+
+            assert!(authorize(&policy, Some(&der), false).is_ok());
+        }
+
+        #[test]
+        fn the_identity_exposes_the_spiffe_id() {
+            let identity = CallerIdentity::new(
+                CallerSan::uri("spiffe://prod.local/ns/payments/sa/api").expect("valid"),
+                false,
+            );
+            let id = identity.spiffe_id().expect("a SPIFFE caller");
+            assert_eq!(id.trust_domain(), "prod.local");
+            assert_eq!(id.path(), "/ns/payments/sa/api");
+
+            let dns =
+                CallerIdentity::new(CallerSan::dns("reporter.internal").expect("valid"), false);
+            assert!(dns.spiffe_id().is_none());
+        }
+    }
+
     mod listener_validation {
         use super::*;
 
@@ -0,0 +1,746 @@
+//! Certificate expiry monitoring (requires `tls` feature)
+//!
+//! An expired certificate fails every handshake at once, and nothing about the
+//! service looks wrong until it does. [`CertificateExpiryMonitor`] watches the
+//! certificates a service depends on and surfaces their `notAfter` before that
+//! happens:
+//!
+//! - the certificates each TLS listener serves, default and SNI alike,
+//! - the client-CA bundle a mutual-TLS listener verifies callers against,
+//! - the client certificate each registered
+//!   [`ClientIdentitySource`](crate::client_tls::ClientIdentitySource) presents.
+//!
+//! `ServiceBuilder` wires it up whenever TLS is configured or a client
+//! identity is registered with
+//! [`with_client_identity`](crate::service_builder::ServiceBuilder::with_client_identity):
+//!
+//! - **Health.** `/health` carries every watched certificate under
+//!   `details.certificates`, with `not_after`, `days_remaining` and `status`
+//!   as fields, for dashboards that should not parse the readiness message.
+//! - **Readiness.** A `tls_certificates` check on `/ready` lists every watched
+//!   certificate with its `notAfter`. It turns
+//!   [`Degraded`](crate::checks::CheckOutcome::Degraded) once one is inside its
+//!   `expiry_warning_days` window, or its expiry cannot be read, and
+//!   [`Unready`](crate::checks::CheckOutcome::Unready) once one has expired.
+//! - **Metrics.** With `otel-metrics` or `prometheus-metrics`, the
+//!   `tls.certificate.not_after` gauge reports each `notAfter` in Unix seconds,
+//!   labelled by `role`, `owner` and `path`.
+//! - **Alerts.** A background task rechecks hourly and logs each certificate
+//!   that enters its window or expires. With `audit` and `[audit.alerts]`
+//!   enabled, the same transitions are sent to the configured webhooks as
+//!   [`AuditAlertEvent::CertificateExpiring`](crate::audit::AuditAlertEvent::CertificateExpiring)
+//!   and
+//!   [`AuditAlertEvent::CertificateExpired`](crate::audit::AuditAlertEvent::CertificateExpired).
+//!
+//! Everything reads what is installed, not what is on disk, so a rotation is
+//! reflected as soon as the reload that applied it succeeds.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::checks::CheckOutcome;
+use crate::client_tls::ClientIdentitySource;
+use crate::tls::TlsConfigSource;
+
+/// The warning window used when `expiry_warning_days` is unset.
+///
+/// Two weeks covers a missed renewal on a monthly rotation and leaves a
+/// working week to fix it by hand.
+pub const DEFAULT_EXPIRY_WARNING_DAYS: u64 = 14;
+
+/// The name the monitor's readiness check reports under on `/ready`.
+pub const READINESS_CHECK_NAME: &str = "tls_certificates";
+
+/// The gauge reporting each watched certificate's `notAfter`.
+#[cfg(feature = "_metrics")]
+const NOT_AFTER_GAUGE: &str = "tls.certificate.not_after";
+
+/// How often the background task rechecks for alerting.
+///
+/// Expiry moves in days, so hourly is ample; `/ready` and the gauge are
+/// evaluated on every probe and scrape regardless.
+const EXPIRY_CHECK_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+/// What a watched certificate is for.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CertificateRole {
+    /// A certificate a TLS listener serves.
+    ServerCertificate,
+    /// A client-CA bundle a mutual-TLS listener verifies callers against.
+    ClientCa,
+    /// The client certificate a [`ClientIdentitySource`] presents to peers.
+    ClientIdentity,
+}
+
+impl CertificateRole {
+    /// The stable name used in metric labels and alert payloads.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::ServerCertificate => "server_certificate",
+            Self::ClientCa => "client_ca",
+            Self::ClientIdentity => "client_identity",
+        }
+    }
+}
+
+impl fmt::Display for CertificateRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Where a certificate stands relative to its expiry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExpiryStatus {
+    /// Outside its warning window.
+    Valid,
+    /// Inside its warning window but not yet expired.
+    Expiring,
+    /// Past its `notAfter`.
+    Expired,
+    /// The certificate does not parse as X.509, so its expiry is unknown.
+    Unknown,
+}
+
+impl ExpiryStatus {
+    /// The stable name used in `/health` details.
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Valid => "valid",
+            Self::Expiring => "expiring",
+            Self::Expired => "expired",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+/// A watched certificate as `/health` reports it, under
+/// `details.certificates`.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CertificateDetail {
+    /// `server_certificate`, `client_ca` or `client_identity`.
+    pub role: String,
+    /// The listener or client identity it belongs to.
+    pub owner: String,
+    /// The file it was read from.
+    pub path: PathBuf,
+    /// Its `notAfter`; absent if the certificate does not parse.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_after: Option<DateTime<Utc>>,
+    /// Whole days until `not_after`, negative once it has passed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub days_remaining: Option<i64>,
+    /// `valid`, `expiring`, `expired` or `unknown`.
+    pub status: String,
+}
+
+/// A watched certificate and when it expires.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MonitoredCertificate {
+    /// What the certificate is for.
+    pub role: CertificateRole,
+    /// The listener (`[tls]`, `[grpc.tls]`) or client identity it belongs to.
+    pub owner: String,
+    /// The file it was read from.
+    pub path: PathBuf,
+    /// Its `notAfter`, or `None` if it could not be parsed.
+    pub not_after: Option<DateTime<Utc>>,
+    /// How long before `not_after` it counts as expiring.
+    pub warning_window: chrono::Duration,
+}
+
+impl MonitoredCertificate {
+    /// Classify this certificate against `now`.
+    #[must_use]
+    pub fn status_at(&self, now: DateTime<Utc>) -> ExpiryStatus {
+        match self.not_after {
+            None => ExpiryStatus::Unknown,
+            Some(not_after) if not_after <= now => ExpiryStatus::Expired,
+            Some(not_after) if not_after - now <= self.warning_window => ExpiryStatus::Expiring,
+            Some(_) => ExpiryStatus::Valid,
+        }
+    }
+
+    /// This certificate as `/health` reports it.
+    #[must_use]
+    pub fn detail_at(&self, now: DateTime<Utc>) -> CertificateDetail {
+        CertificateDetail {
+            role: self.role.as_str().to_string(),
+            owner: self.owner.clone(),
+            path: self.path.clone(),
+            not_after: self.not_after,
+            days_remaining: self.not_after.map(|not_after| (not_after - now).num_days()),
+            status: self.status_at(now).as_str().to_string(),
+        }
+    }
+
+    /// One line for the readiness message, naming the certificate and its
+    /// `notAfter`.
+    fn describe(&self, now: DateTime<Utc>) -> String {
+        let what = format!("{} {} ({})", self.role, self.path.display(), self.owner);
+        match self.not_after {
+            None => format!("{what}: expiry unknown, the certificate does not parse"),
+            Some(not_after) => {
+                let not_after_text = not_after.to_rfc3339();
+                match self.status_at(now) {
+                    ExpiryStatus::Expired => {
+                        format!("{what}: EXPIRED, not_after {not_after_text}")
+                    }
+                    ExpiryStatus::Expiring => format!(
+                        "{what}: expires in {} days, not_after {not_after_text}",
+                        (not_after - now).num_days()
+                    ),
+                    _ => format!("{what}: not_after {not_after_text}"),
+                }
+            }
+        }
+    }
+}
+
+/// Turn a set of watched certificates into one readiness outcome.
+///
+/// The worst certificate decides: any expired one is `Unready`, any expiring
+/// or unreadable one `Degraded`. Every certificate is listed in the message
+/// either way, so `/ready` shows each `notAfter` even when all is well.
+fn evaluate(certificates: &[MonitoredCertificate], now: DateTime<Utc>) -> CheckOutcome {
+    if certificates.is_empty() {
+        return CheckOutcome::Ready;
+    }
+
+    let message = certificates
+        .iter()
+        .map(|certificate| certificate.describe(now))
+        .collect::<Vec<_>>()
+        .join("; ");
+    let statuses = || certificates.iter().map(|c| c.status_at(now));
+
+    if statuses().any(|s| s == ExpiryStatus::Expired) {
+        CheckOutcome::Unready(message)
+    } else if statuses().any(|s| matches!(s, ExpiryStatus::Expiring | ExpiryStatus::Unknown)) {
+        CheckOutcome::Degraded(message)
+    } else {
+        CheckOutcome::ReadyWithMessage(message)
+    }
+}
+
+/// Something the monitor reads certificates from.
+#[derive(Clone)]
+enum Watched {
+    Listener {
+        owner: String,
+        source: TlsConfigSource,
+        warning_window: chrono::Duration,
+    },
+    ClientIdentity {
+        owner: String,
+        source: ClientIdentitySource,
+        warning_window: chrono::Duration,
+    },
+}
+
+impl Watched {
+    fn certificates(&self, into: &mut Vec<MonitoredCertificate>) {
+        match self {
+            Self::Listener {
+                owner,
+                source,
+                warning_window,
+            } => {
+                into.extend(
+                    source
+                        .certificates()
+                        .into_iter()
+                        .map(|served| MonitoredCertificate {
+                            role: CertificateRole::ServerCertificate,
+                            owner: owner.clone(),
+                            path: served.cert_path,
+                            not_after: served.not_after,
+                            warning_window: *warning_window,
+                        }),
+                );
+                if let Some(ca) = source.client_ca() {
+                    into.push(MonitoredCertificate {
+                        role: CertificateRole::ClientCa,
+                        owner: owner.clone(),
+                        path: ca.ca_path,
+                        not_after: ca.not_after,
+                        warning_window: *warning_window,
+                    });
+                }
+            }
+            Self::ClientIdentity {
+                owner,
+                source,
+                warning_window,
+            } => into.push(MonitoredCertificate {
+                role: CertificateRole::ClientIdentity,
+                owner: owner.clone(),
+                path: source.origin().cert_path.clone(),
+                not_after: source.not_after(),
+                warning_window: *warning_window,
+            }),
+        }
+    }
+}
+
+/// Convert a configured `expiry_warning_days` into a window.
+fn warning_window(days: Option<u64>) -> chrono::Duration {
+    let days = days.unwrap_or(DEFAULT_EXPIRY_WARNING_DAYS);
+    i64::try_from(days)
+        .ok()
+        .and_then(chrono::Duration::try_days)
+        .unwrap_or(chrono::Duration::MAX)
+}
+
+/// Watches TLS certificates for approaching expiry.
+///
+/// Cheap to clone; every clone watches the same set. See the
+/// [module documentation](self) for what is reported where.
+#[derive(Clone, Default)]
+pub struct CertificateExpiryMonitor {
+    watched: Arc<RwLock<Vec<Watched>>>,
+    #[cfg(feature = "audit")]
+    hooks: Arc<[Arc<dyn crate::audit::AuditAlertHook>]>,
+}
+
+impl fmt::Debug for CertificateExpiryMonitor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CertificateExpiryMonitor")
+            .field("certificates", &self.certificates())
+            .finish_non_exhaustive()
+    }
+}
+
+impl CertificateExpiryMonitor {
+    /// Create a monitor watching nothing.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send expiry transitions to `hooks` as well as the log.
+    #[cfg(feature = "audit")]
+    #[must_use]
+    pub fn with_alert_hooks(mut self, hooks: Vec<Arc<dyn crate::audit::AuditAlertHook>>) -> Self {
+        self.hooks = hooks.into();
+        self
+    }
+
+    /// Watch the certificates a TLS listener serves, and its client-CA bundle.
+    ///
+    /// `owner` names the listener in messages and labels, e.g. `[tls]`. The
+    /// warning window is the source's `expiry_warning_days`. A source already
+    /// watched, such as a gRPC listener inheriting the HTTP credentials, is
+    /// not added twice. A static source, built from an already-loaded
+    /// `ServerConfig`, has no files to describe and reports nothing.
+    pub fn watch_listener(&self, owner: impl Into<String>, source: &TlsConfigSource) {
+        let mut watched = self.watched.write().unwrap_or_else(|e| e.into_inner());
+        let already = watched.iter().any(|w| match w {
+            Watched::Listener { source: other, .. } => other.ptr_eq(source),
+            Watched::ClientIdentity { .. } => false,
+        });
+        if !already {
+            watched.push(Watched::Listener {
+                owner: owner.into(),
+                warning_window: warning_window(
+                    source.origin().and_then(|tls| tls.expiry_warning_days),
+                ),
+                source: source.clone(),
+            });
+        }
+    }
+
+    /// Watch the client certificate a [`ClientIdentitySource`] presents.
+    ///
+    /// `owner` names the identity in messages and labels, typically the peer
+    /// it is used to call. The warning window is the source's
+    /// `expiry_warning_days`.
+    pub fn watch_client_identity(&self, owner: impl Into<String>, source: &ClientIdentitySource) {
+        self.watched
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(Watched::ClientIdentity {
+                owner: owner.into(),
+                warning_window: warning_window(source.origin().expiry_warning_days),
+                source: source.clone(),
+            });
+    }
+
+    /// Whether anything is being watched.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.watched
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_empty()
+    }
+
+    /// Every watched certificate as currently installed.
+    #[must_use]
+    pub fn certificates(&self) -> Vec<MonitoredCertificate> {
+        let watched = self
+            .watched
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .clone();
+        let mut certificates = Vec::new();
+        for w in &watched {
+            w.certificates(&mut certificates);
+        }
+        certificates
+    }
+
+    /// Every watched certificate in the form `/health` reports it.
+    #[must_use]
+    pub fn details(&self) -> Vec<CertificateDetail> {
+        let now = Utc::now();
+        self.certificates()
+            .iter()
+            .map(|certificate| certificate.detail_at(now))
+            .collect()
+    }
+
+    /// The readiness outcome for the watched certificates right now.
+    ///
+    /// What the `tls_certificates` check on `/ready` reports.
+    #[must_use]
+    pub fn check(&self) -> CheckOutcome {
+        evaluate(&self.certificates(), Utc::now())
+    }
+
+    /// Register the `tls.certificate.not_after` gauge.
+    ///
+    /// Observed at collection time, so every scrape reflects the certificates
+    /// installed at that moment. Does nothing when no meter provider is
+    /// installed.
+    #[cfg(feature = "_metrics")]
+    pub(crate) fn register_gauge(&self) {
+        use opentelemetry::KeyValue;
+
+        let Some(meter) = crate::observability::get_meter() else {
+            return;
+        };
+        let monitor = self.clone();
+        // The SDK keeps the callback registered for the provider's lifetime;
+        // the instrument handle itself need not be held.
+        let _gauge = meter
+            .i64_observable_gauge(NOT_AFTER_GAUGE)
+            .with_unit("s")
+            .with_description("notAfter of each monitored TLS certificate, in Unix seconds")
+            .with_callback(move |observer| {
+                for certificate in monitor.certificates() {
+                    let Some(not_after) = certificate.not_after else {
+                        continue;
+                    };
+                    observer.observe(
+                        not_after.timestamp(),
+                        &[
+                            KeyValue::new("role", certificate.role.as_str()),
+                            KeyValue::new("owner", certificate.owner),
+                            KeyValue::new("path", certificate.path.display().to_string()),
+                        ],
+                    );
+                }
+            })
+            .build();
+    }
+
+    /// Start the background task that alerts on expiry.
+    ///
+    /// Checks immediately and then hourly. A certificate is reported once when
+    /// it enters its warning window and once when it expires: at `WARN` and
+    /// `ERROR` respectively, and to the alert hooks. A rotation that brings it
+    /// back into validity rearms both.
+    pub(crate) fn spawn_alerts(&self, service_name: String) -> tokio::task::JoinHandle<()> {
+        let monitor = self.clone();
+        tokio::spawn(async move {
+            let mut alerts = ExpiryAlerts::default();
+            let mut ticker = tokio::time::interval(EXPIRY_CHECK_INTERVAL);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                ticker.tick().await;
+                let now = Utc::now();
+                for (certificate, status) in alerts.transitions(monitor.certificates(), now) {
+                    monitor.alert(&certificate, status, now, &service_name);
+                }
+            }
+        })
+    }
+
+    /// Log one transition and hand it to the alert hooks.
+    #[cfg_attr(not(feature = "audit"), allow(unused_variables))]
+    fn alert(
+        &self,
+        certificate: &MonitoredCertificate,
+        status: ExpiryStatus,
+        now: DateTime<Utc>,
+        service_name: &str,
+    ) {
+        let Some(not_after) = certificate.not_after else {
+            return;
+        };
+        match status {
+            ExpiryStatus::Expiring => tracing::warn!(
+                role = %certificate.role,
+                owner = %certificate.owner,
+                path = %certificate.path.display(),
+                not_after = %not_after,
+                days_remaining = (not_after - now).num_days(),
+                "TLS certificate expires soon; renew it before handshakes start failing"
+            ),
+            ExpiryStatus::Expired => tracing::error!(
+                role = %certificate.role,
+                owner = %certificate.owner,
+                path = %certificate.path.display(),
+                not_after = %not_after,
+                "TLS certificate has expired; handshakes that depend on it now fail"
+            ),
+            ExpiryStatus::Valid | ExpiryStatus::Unknown => return,
+        }
+
+        #[cfg(feature = "audit")]
+        {
+            use crate::audit::AuditAlertEvent;
+
+            let event = if status == ExpiryStatus::Expired {
+                AuditAlertEvent::CertificateExpired {
+                    role: certificate.role.to_string(),
+                    owner: certificate.owner.clone(),
+                    path: certificate.path.display().to_string(),
+                    not_after,
+                    service_name: service_name.to_string(),
+                }
+            } else {
+                AuditAlertEvent::CertificateExpiring {
+                    role: certificate.role.to_string(),
+                    owner: certificate.owner.clone(),
+                    path: certificate.path.display().to_string(),
+                    not_after,
+                    days_remaining: (not_after - now).num_days(),
+                    service_name: service_name.to_string(),
+                }
+            };
+            for hook in self.hooks.iter() {
+                let hook = Arc::clone(hook);
+                let event = event.clone();
+                tokio::spawn(async move {
+                    hook.on_alert(event).await;
+                });
+            }
+        }
+    }
+}
+
+/// The last status reported for each certificate, so each transition alerts
+/// once rather than on every check.
+#[derive(Default)]
+struct ExpiryAlerts {
+    last: HashMap<(CertificateRole, String, PathBuf), ExpiryStatus>,
+}
+
+impl ExpiryAlerts {
+    /// The certificates that entered the expiring or expired state since the
+    /// previous call, with the state they entered.
+    fn transitions(
+        &mut self,
+        certificates: Vec<MonitoredCertificate>,
+        now: DateTime<Utc>,
+    ) -> Vec<(MonitoredCertificate, ExpiryStatus)> {
+        let mut entered = Vec::new();
+        for certificate in certificates {
+            let status = certificate.status_at(now);
+            let key = (
+                certificate.role,
+                certificate.owner.clone(),
+                certificate.path.clone(),
+            );
+            let previous = self.last.insert(key, status);
+            if previous != Some(status)
+                && matches!(status, ExpiryStatus::Expiring | ExpiryStatus::Expired)
+            {
+                entered.push((certificate, status));
+            }
+        }
+        entered
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn certificate(not_after: Option<DateTime<Utc>>) -> MonitoredCertificate {
+        MonitoredCertificate {
+            role: CertificateRole::ServerCertificate,
+            owner: "[tls]".to_string(),
+            path: PathBuf::from("/etc/tls/server.pem"),
+            not_after,
+            warning_window: chrono::Duration::days(14),
+        }
+    }
+
+    #[test]
+    fn status_follows_the_warning_window() {
+        let now = Utc::now();
+
+        assert_eq!(
+            certificate(Some(now + chrono::Duration::days(30))).status_at(now),
+            ExpiryStatus::Valid
+        );
+        assert_eq!(
+            certificate(Some(now + chrono::Duration::days(3))).status_at(now),
+            ExpiryStatus::Expiring
+        );
+        assert_eq!(
+            certificate(Some(now - chrono::Duration::seconds(1))).status_at(now),
+            ExpiryStatus::Expired
+        );
+        assert_eq!(certificate(None).status_at(now), ExpiryStatus::Unknown);
+    }
+
+    #[test]
+    fn the_worst_certificate_decides_readiness() {
+        let now = Utc::now();
+        let valid = certificate(Some(now + chrono::Duration::days(90)));
+        let expiring = certificate(Some(now + chrono::Duration::days(2)));
+        let expired = certificate(Some(now - chrono::Duration::days(1)));
+
+        assert!(matches!(
+            evaluate(std::slice::from_ref(&valid), now),
+            CheckOutcome::ReadyWithMessage(_)
+        ));
+        assert!(matches!(
+            evaluate(&[valid.clone(), expiring.clone()], now),
+            CheckOutcome::Degraded(_)
+        ));
+        assert!(matches!(
+            evaluate(&[valid, expiring, expired], now),
+            CheckOutcome::Unready(_)
+        ));
+        assert!(matches!(
+            evaluate(&[certificate(None)], now),
+            CheckOutcome::Degraded(_)
+        ));
+        assert_eq!(evaluate(&[], now), CheckOutcome::Ready);
+    }
+
+    #[test]
+    fn the_readiness_message_reports_each_not_after() {
+        let now = Utc::now();
+        let not_after = now + chrono::Duration::days(90);
+
+        let CheckOutcome::ReadyWithMessage(message) =
+            evaluate(&[certificate(Some(not_after))], now)
+        else {
+            panic!("a valid certificate must be ready");
+        };
+        assert!(
+            message.contains(&not_after.to_rfc3339()),
+            "the message must carry the notAfter: {message}"
+        );
+        assert!(message.contains("server_certificate /etc/tls/server.pem ([tls])"));
+    }
+
+    #[test]
+    fn the_health_detail_carries_not_after_as_a_field() {
+        let now = Utc::now();
+        let not_after = now + chrono::Duration::days(5) + chrono::Duration::hours(1);
+
+        let detail = certificate(Some(not_after)).detail_at(now);
+        assert_eq!(detail.role, "server_certificate");
+        assert_eq!(detail.owner, "[tls]");
+        assert_eq!(detail.not_after, Some(not_after));
+        assert_eq!(detail.days_remaining, Some(5));
+        assert_eq!(detail.status, "expiring");
+
+        let json = serde_json::to_value(&detail).expect("serializes");
+        assert_eq!(json["not_after"], serde_json::json!(not_after));
+
+        let unparsed = serde_json::to_value(certificate(None).detail_at(now)).expect("serializes");
+        assert!(unparsed.get("not_after").is_none());
+        assert_eq!(unparsed["status"], "unknown");
+    }
+
+    #[test]
+    fn each_transition_alerts_once_and_a_rotation_rearms_it() {
+        let now = Utc::now();
+        let mut alerts = ExpiryAlerts::default();
+        let expiring = certificate(Some(now + chrono::Duration::days(5)));
+
+        let first = alerts.transitions(vec![expiring.clone()], now);
+        assert_eq!(first.len(), 1);
+        assert_eq!(first[0].1, ExpiryStatus::Expiring);
+
+        assert!(
+            alerts.transitions(vec![expiring.clone()], now).is_empty(),
+            "an unchanged status must not alert again"
+        );
+
+        let expired = alerts.transitions(vec![expiring.clone()], now + chrono::Duration::days(6));
+        assert_eq!(expired.len(), 1);
+        assert_eq!(expired[0].1, ExpiryStatus::Expired);
+
+        let rotated = certificate(Some(now + chrono::Duration::days(90)));
+        assert!(alerts.transitions(vec![rotated], now).is_empty());
+        assert_eq!(
+            alerts.transitions(vec![expiring], now).len(),
+            1,
+            "after a rotation back to valid, expiring again must alert again"
+        );
+    }
+
+    #[test]
+    fn an_unset_window_uses_the_default() {
+        assert_eq!(
+            warning_window(None),
+            chrono::Duration::days(DEFAULT_EXPIRY_WARNING_DAYS as i64)
+        );
+        assert_eq!(warning_window(Some(30)), chrono::Duration::days(30));
+        assert_eq!(warning_window(Some(u64::MAX)), chrono::Duration::MAX);
+    }
+
+    #[tokio::test]
+    async fn a_listener_reports_its_served_certificate() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let key = rcgen::KeyPair::generate().expect("key");
+        let cert = rcgen::CertificateParams::new(vec!["localhost".to_string()])
+            .expect("params")
+            .self_signed(&key)
+            .expect("self-signed");
+        let cert_path = dir.path().join("server.pem");
+        let key_path = dir.path().join("server-key.pem");
+        std::fs::write(&cert_path, cert.pem()).expect("write cert");
+        std::fs::write(&key_path, key.serialize_pem()).expect("write key");
+
+        let tls_config: crate::config::TlsConfig = serde_json::from_value(serde_json::json!({
+            "cert_path": cert_path,
+            "key_path": key_path,
+            "expiry_warning_days": 30,
+        }))
+        .expect("tls config");
+        let source = TlsConfigSource::from_tls_config(&tls_config).expect("source loads");
+
+        let monitor = CertificateExpiryMonitor::new();
+        monitor.watch_listener("[tls]", &source);
+        monitor.watch_listener("[grpc.tls]", &source.clone());
+
+        let certificates = monitor.certificates();
+        assert_eq!(
+            certificates.len(),
+            1,
+            "an inherited source must not be watched twice"
+        );
+        assert_eq!(certificates[0].role, CertificateRole::ServerCertificate);
+        assert_eq!(certificates[0].path, cert_path);
+        assert_eq!(certificates[0].warning_window, chrono::Duration::days(30));
+        assert!(certificates[0].not_after.is_some());
+    }
+}
@@ -33,6 +33,7 @@ use std::sync::Arc;
 use std::time::Duration;
 
 use arc_swap::ArcSwap;
+use chrono::{DateTime, Utc};
 use rustls_pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
 use tokio_rustls::rustls::client::danger::{
     HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier,
@@ -67,6 +68,9 @@ const GRPC_ALPN: [&[u8]; 1] = [b"h2"];
 /// a failure names which side of the handshake the bundle belongs to.
 const PEER_CA_ROLE: &str = "peer CA";
 
+/// The role string used in error messages for the peer's revocation lists.
+const PEER_CRL_ROLE: &str = "peer CRL";
+
 /// How long a connection attempt may spend on the TCP connect plus the TLS
 /// handshake before it is abandoned.
 ///
@@ -348,6 +352,11 @@ impl RotatingClientCertResolver {
         self.current.store(Arc::new(key));
     }
 
+    /// The `notAfter` of the leaf certificate the next handshake would present.
+    fn not_after(&self) -> Option<DateTime<Utc>> {
+        crate::tls::leaf_not_after(&self.current.load().cert)
+    }
+
     /// The key the next handshake would present.
     ///
     /// Test-only: nothing in the crate needs to read the identity back, and
@@ -472,21 +481,33 @@ impl ServerCertVerifier for RotatingWebPkiVerifier {
 /// Build the [`WebPkiServerVerifier`] for the peer roots named by `config`.
 ///
 /// The single place both the initial load and a reload construct a verifier, so
-/// `root_ca_path` and `exclusive_roots` cannot be honoured differently by the
-/// two paths.
+/// `root_ca_path`, `exclusive_roots` and `crl_paths` cannot be honoured
+/// differently by the two paths.
+///
+/// With `crl_paths` set, a CRL past its `nextUpdate` fails the handshake rather
+/// than being trusted stale. An issuer no CRL covers is rejected only under
+/// `exclusive_roots`: alongside the built-in web PKI roots, whose CAs the
+/// operator has supplied no CRLs for, requiring one would reject every public
+/// peer.
 fn build_peer_verifier(config: &ClientIdentityConfig) -> Result<Arc<WebPkiServerVerifier>> {
     crate::crypto::ensure_default_crypto_provider();
 
     let roots = build_root_store(config)?;
-    WebPkiServerVerifier::builder(Arc::new(roots))
-        .build()
-        .map_err(|e| {
-            Error::Tls(format!(
-                "Failed to build a peer certificate verifier from the configured \
-                 {} roots: {}",
-                PEER_CA_ROLE, e
-            ))
-        })
+    let mut builder = WebPkiServerVerifier::builder(Arc::new(roots));
+    if !config.crl_paths.is_empty() {
+        let crls = crate::tls::load_crls(&config.crl_paths, PEER_CRL_ROLE)?;
+        builder = builder.with_crls(crls).enforce_revocation_expiration();
+        if !config.exclusive_roots {
+            builder = builder.allow_unknown_revocation_status();
+        }
+    }
+    builder.build().map_err(|e| {
+        Error::Tls(format!(
+            "Failed to build a peer certificate verifier from the configured \
+             {} roots: {}",
+            PEER_CA_ROLE, e
+        ))
+    })
 }
 
 /// Load a rustls [`ClientConfig`] that presents this service's client
@@ -520,19 +541,15 @@ fn build_peer_verifier(config: &ClientIdentityConfig) -> Result<Arc<WebPkiServer
 ///
 /// # Errors
 ///
-/// Returns an error when the certificate, key or CA bundle cannot be read or
-/// parsed, when the chain is empty, when the key does not match the leaf
-/// certificate, or when rustls rejects the resulting pair.
+/// Returns an error when the certificate, key, CA bundle or a CRL cannot be
+/// read or parsed, when the chain is empty, when the key does not match the
+/// leaf certificate, or when rustls rejects the resulting pair.
 pub fn load_rustls_client_config(config: &ClientIdentityConfig) -> Result<Arc<ClientConfig>> {
     let material = load_identity_material(config)?;
-    let roots = build_root_store(config)?;
-
-    // Installed already by the pair check above; called again because this
-    // function's contract is to be usable on its own.
-    crate::crypto::ensure_default_crypto_provider();
+    let verifier = build_peer_verifier(config)?;
 
     let client_config = ClientConfig::builder()
-        .with_root_certificates(roots)
+        .with_webpki_verifier(verifier)
         .with_client_auth_cert(material.chain.clone(), material.key.clone_key())
         .map_err(|e| Error::Tls(format!("Failed to build rustls client config: {}", e)))?;
 
@@ -587,9 +604,12 @@ pub fn load_reqwest_identity(config: &ClientIdentityConfig) -> Result<reqwest::I
 /// # Errors
 ///
 /// Returns an error when the certificate, key or CA bundle cannot be read or
-/// parsed, when the chain is empty, or when the key does not match the leaf
-/// certificate.
+/// parsed, when the chain is empty, when the key does not match the leaf
+/// certificate, or when `crl_paths` is set: `reqwest` would accept the CRLs but
+/// trust them past their `nextUpdate`, so revocation checking goes through
+/// [`ClientIdentitySource`] or [`load_rustls_client_config`] instead.
 pub fn reqwest_client_builder(config: &ClientIdentityConfig) -> Result<reqwest::ClientBuilder> {
+    refuse_crl_paths(config, "reqwest_client_builder")?;
     crate::crypto::ensure_default_crypto_provider();
 
     let identity = load_reqwest_identity(config)?;
@@ -641,12 +661,14 @@ pub fn reqwest_client_builder(config: &ClientIdentityConfig) -> Result<reqwest::
 /// # Errors
 ///
 /// Returns an error when the certificate, key or CA bundle cannot be read or
-/// parsed, when the chain is empty, or when the key does not match the leaf
-/// certificate.
+/// parsed, when the chain is empty, when the key does not match the leaf
+/// certificate, or when `crl_paths` is set, which `tonic` has no way to apply;
+/// use [`ClientIdentitySource::grpc_channel`] for revocation checking.
 #[cfg(feature = "grpc")]
 pub fn tonic_client_tls_config(
     config: &ClientIdentityConfig,
 ) -> Result<tonic::transport::ClientTlsConfig> {
+    refuse_crl_paths(config, "tonic_client_tls_config")?;
     // `tonic` builds its rustls configuration lazily at connect time, which is
     // after any point where we could install the provider for it.
     crate::crypto::ensure_default_crypto_provider();
@@ -667,6 +689,23 @@ pub fn tonic_client_tls_config(
     Ok(tls)
 }
 
+/// Refuse a configuration with `crl_paths` on a path that cannot honour it.
+///
+/// Silently dropping the lists would leave a revoked peer trusted while the
+/// configuration says otherwise, so the builders that hand the trust anchors to
+/// another library's verifier fail instead.
+fn refuse_crl_paths(config: &ClientIdentityConfig, builder: &str) -> Result<()> {
+    if config.crl_paths.is_empty() {
+        return Ok(());
+    }
+    Err(Error::Tls(format!(
+        "{} cannot enforce crl_paths (an expired CRL would be trusted or the \
+         lists ignored); use ClientIdentitySource or load_rustls_client_config \
+         for revocation checking",
+        builder
+    )))
+}
+
 /// A rotatable client identity: the certificate this service presents to its
 /// peers, and the anchors it verifies them against, both replaceable from disk
 /// while the service runs.
@@ -822,6 +861,16 @@ impl ClientIdentitySource {
         &self.inner.origin
     }
 
+    /// When the client certificate this source presents expires.
+    ///
+    /// Reads the certificate currently installed, so after a failed
+    /// [`reload`](Self::reload) it still reports the previous one. `None` if
+    /// the leaf certificate does not parse as X.509.
+    #[must_use]
+    pub fn not_after(&self) -> Option<DateTime<Utc>> {
+        self.inner.resolver.not_after()
+    }
+
     /// Reread the identity files and install the new credentials in place.
     ///
     /// On success, every handshake from this point on presents the new
@@ -1036,7 +1085,8 @@ impl ClientIdentitySource {
     /// # Errors
     ///
     /// Returns an error when the certificate, key or CA bundle cannot be read,
-    /// parsed or validated.
+    /// parsed or validated, or when the source was configured with
+    /// `crl_paths`, which `tonic`'s own TLS plumbing cannot enforce.
     #[cfg(feature = "grpc")]
     pub fn tonic_client_tls_config_snapshot(&self) -> Result<tonic::transport::ClientTlsConfig> {
         tonic_client_tls_config(&self.inner.origin)
@@ -1283,7 +1333,9 @@ mod tests {
             key_path,
             root_ca_path: None,
             exclusive_roots: false,
+            crl_paths: Vec::new(),
             connect_timeout_secs: None,
+            expiry_warning_days: None,
         }
     }
 
@@ -2148,11 +2200,13 @@ mod tests {
         }
     }
 
-    /// A CA certificate and a leaf signed by it, both PEM-encoded.
+    /// A CA certificate and a leaf signed by it, both PEM-encoded, plus a CRL
+    /// from the CA that revokes the leaf.
     struct TestChain {
         ca_pem: String,
         leaf_pem: String,
         leaf_key_pem: String,
+        leaf_revoked_crl_pem: String,
     }
 
     /// Issue a CA and a server certificate for `127.0.0.1` under it.
@@ -2162,10 +2216,13 @@ mod tests {
     /// the way a deployment would.
     fn generate_server_chain() -> TestChain {
         use rcgen::{
-            BasicConstraints, CertificateParams, DistinguishedName, DnType, IsCa, Issuer, KeyPair,
-            KeyUsagePurpose,
+            date_time_ymd, BasicConstraints, CertificateParams, CertificateRevocationListParams,
+            DistinguishedName, DnType, IsCa, Issuer, KeyIdMethod, KeyPair, KeyUsagePurpose,
+            RevokedCertParams, SerialNumber,
         };
 
+        const LEAF_SERIAL: u64 = 7;
+
         let ca_key = KeyPair::generate().expect("ca key");
         let mut ca_params = CertificateParams::new(Vec::new()).expect("ca params");
         ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
@@ -2181,19 +2238,124 @@ mod tests {
         let mut leaf_dn = DistinguishedName::new();
         leaf_dn.push(DnType::CommonName, "acton-service test server");
         leaf_params.distinguished_name = leaf_dn;
+        leaf_params.serial_number = Some(SerialNumber::from(LEAF_SERIAL));
 
         let issuer = Issuer::new(ca_params, ca_key);
         let leaf_cert = leaf_params
             .signed_by(&leaf_key, &issuer)
             .expect("leaf signed by ca");
 
+        let crl = CertificateRevocationListParams {
+            this_update: date_time_ymd(2020, 1, 1),
+            next_update: date_time_ymd(2099, 1, 1),
+            crl_number: SerialNumber::from(1u64),
+            issuing_distribution_point: None,
+            revoked_certs: vec![RevokedCertParams {
+                serial_number: SerialNumber::from(LEAF_SERIAL),
+                revocation_time: date_time_ymd(2020, 6, 1),
+                reason_code: None,
+                invalidity_date: None,
+            }],
+            key_identifier_method: KeyIdMethod::Sha256,
+        }
+        .signed_by(&issuer)
+        .expect("crl signed by ca");
+
         TestChain {
             ca_pem: ca_cert.pem(),
             leaf_pem: leaf_cert.pem(),
             leaf_key_pem: leaf_key.serialize_pem(),
+            leaf_revoked_crl_pem: crl.pem().expect("crl pem"),
         }
     }
 
+    /// Run `config`'s peer verifier over the test chain's leaf, as a handshake
+    /// with `127.0.0.1` would.
+    fn verify_test_server(
+        config: &ClientIdentityConfig,
+        chain: &TestChain,
+    ) -> std::result::Result<(), tokio_rustls::rustls::Error> {
+        use rustls_pki_types::pem::PemObject;
+
+        let leaf = CertificateDer::from_pem_slice(chain.leaf_pem.as_bytes()).expect("leaf parses");
+        let verifier = build_peer_verifier(config).expect("verifier builds");
+        verifier
+            .verify_server_cert(
+                &leaf,
+                &[],
+                &ServerName::try_from("127.0.0.1").expect("valid server name"),
+                &[],
+                UnixTime::now(),
+            )
+            .map(|_| ())
+    }
+
+    #[test]
+    fn the_peer_verifier_rejects_a_server_on_the_crl() {
+        let chain = generate_server_chain();
+        let dir = tempfile::tempdir().expect("temp dir");
+        let identity = generate_cert("client");
+        let mut config = write_identity(dir.path(), &identity);
+        let ca_path = dir.path().join("peer-ca.pem");
+        std::fs::write(&ca_path, &chain.ca_pem).expect("write ca");
+        config.root_ca_path = Some(ca_path);
+        config.exclusive_roots = true;
+
+        verify_test_server(&config, &chain).expect("without a CRL the server is trusted");
+
+        let crl_path = dir.path().join("peer.crl");
+        std::fs::write(&crl_path, &chain.leaf_revoked_crl_pem).expect("write crl");
+        config.crl_paths = vec![crl_path];
+
+        assert_eq!(
+            verify_test_server(&config, &chain),
+            Err(tokio_rustls::rustls::Error::InvalidCertificate(
+                tokio_rustls::rustls::CertificateError::Revoked
+            )),
+            "a server listed on a configured CRL must be rejected"
+        );
+    }
+
+    #[test]
+    fn crl_paths_are_refused_by_builders_that_cannot_enforce_them() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let identity = generate_cert("client");
+        let mut config = write_identity(dir.path(), &identity);
+        config.crl_paths = vec![dir.path().join("peer.crl")];
+
+        let err = reqwest_client_builder(&config)
+            .expect_err("reqwest cannot fail closed on an expired CRL");
+        assert!(
+            err.to_string().contains("cannot enforce crl_paths"),
+            "error must say why the configuration is refused: {err}"
+        );
+
+        #[cfg(feature = "grpc")]
+        {
+            let err =
+                tonic_client_tls_config(&config).expect_err("tonic cannot apply a CRL at all");
+            assert!(
+                err.to_string().contains("cannot enforce crl_paths"),
+                "error must say why the configuration is refused: {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn load_rustls_client_config_rejects_an_unreadable_crl() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let identity = generate_cert("client");
+        let mut config = write_identity(dir.path(), &identity);
+        config.crl_paths = vec![PathBuf::from("/nonexistent/peer.crl")];
+
+        let err = load_rustls_client_config(&config)
+            .expect_err("a CRL that cannot be read must not leave revocation unchecked");
+        assert!(
+            err.to_string().contains("Failed to open peer CRL file"),
+            "error must name the CRL: {err}"
+        );
+    }
+
     #[tokio::test]
     async fn a_live_handshake_presents_the_rotated_certificate_on_the_same_client() {
         use rustls_pki_types::pem::PemObject;
@@ -2410,9 +2572,13 @@ mod tests {
             key_path,
             client_ca_path: None,
             client_auth_optional: false,
+            client_crl_paths: Vec::new(),
             reload_interval_secs: None,
             reload_on_sighup: false,
             handshake_timeout_secs: None,
+            expiry_warning_days: None,
+            certificates: Vec::new(),
+            acme: None,
         };
         crate::tls::load_server_config(&tls_config).expect("server config loads")
     }
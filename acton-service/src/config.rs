@@ -292,7 +292,10 @@ fn default_paseto_purpose() -> String {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct JwtConfig {
     /// Path to public key for JWT verification
-    pub public_key_path: PathBuf,
+    ///
+    /// Optional when keys come from `jwks_url` or `trusted_issuers`.
+    #[serde(default)]
+    pub public_key_path: Option<PathBuf>,
 
     /// JWT algorithm (RS256, ES256, HS256)
     #[serde(default = "default_jwt_algorithm")]
@@ -317,6 +320,43 @@ pub struct JwtConfig {
     /// are always skipped regardless of this setting.
     #[serde(default)]
     pub public_paths: Vec<String>,
+
+    /// URL of a JWKS document to fetch verification keys from
+    ///
+    /// Keys are selected by the token's `kid`, and tokens are checked
+    /// against `issuer` and `audience` above.
+    #[serde(default)]
+    pub jwks_url: Option<String>,
+
+    /// Seconds between background refreshes of remote key sets (default: 300)
+    #[serde(default = "default_jwks_refresh_secs")]
+    pub jwks_refresh_secs: u64,
+
+    /// Minimum seconds between refreshes triggered by an unknown `kid` (default: 30)
+    ///
+    /// Bounds how often tokens with made-up `kid`s can make the service
+    /// refetch a key set.
+    #[serde(default = "default_jwks_min_refresh_secs")]
+    pub jwks_min_refresh_secs: u64,
+
+    /// Further issuers to accept tokens from, each with its own key set
+    #[serde(default)]
+    pub trusted_issuers: Vec<TrustedIssuerConfig>,
+}
+
+/// An external token issuer whose keys are fetched from a JWKS URL
+#[cfg(feature = "jwt")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrustedIssuerConfig {
+    /// Expected `iss` claim; also selects this issuer for a token
+    pub issuer: String,
+
+    /// URL of the issuer's JWKS document
+    pub jwks_url: String,
+
+    /// Expected `aud` claim for tokens from this issuer
+    #[serde(default)]
+    pub audience: Option<String>,
 }
 
 /// Rate limiting configuration
@@ -334,6 +374,12 @@ pub struct RateLimitConfig {
     #[serde(default = "default_window_secs")]
     pub window_secs: u64,
 
+    /// Algorithm used by the Redis-backed [`RateLimit`](crate::middleware::RateLimit)
+    ///
+    /// The in-memory governor middleware always uses GCRA.
+    #[serde(default)]
+    pub algorithm: RateLimitAlgorithm,
+
     /// Per-route rate limit overrides
     ///
     /// Routes can be specified as:
@@ -357,6 +403,23 @@ pub struct RateLimitConfig {
     #[serde(default)]
     pub routes: std::collections::HashMap<String, RouteRateLimitConfig>,
 
+    /// Quotas for API keys, keyed by scope
+    ///
+    /// An API key without its own `rate_limit`, `daily_quota` or
+    /// `monthly_quota` takes each value from the first of its scopes listed
+    /// here. Keys with no match fall back to `per_client_rpm` and no
+    /// daily or monthly allowance.
+    ///
+    /// # Example
+    /// ```toml
+    /// [rate_limit.api_key_scopes."tier:free"]
+    /// requests_per_minute = 60
+    /// daily_quota = 1000
+    /// monthly_quota = 20000
+    /// ```
+    #[serde(default)]
+    pub api_key_scopes: std::collections::HashMap<String, ApiKeyScopeQuota>,
+
     /// Whether to auto-apply the governor rate-limit middleware in `ServiceBuilder`.
     ///
     /// When `true` (default) and the `governor` feature is enabled, the middleware
@@ -386,13 +449,51 @@ impl Default for RateLimitConfig {
             per_user_rpm: default_per_user_rpm(),
             per_client_rpm: default_per_client_rpm(),
             window_secs: default_window_secs(),
+            algorithm: RateLimitAlgorithm::default(),
             routes: std::collections::HashMap::new(),
+            api_key_scopes: std::collections::HashMap::new(),
             auto_apply: true,
             trust_forwarded_headers: false,
         }
     }
 }
 
+/// API key quota for a scope
+///
+/// Every field is optional; unset fields fall through to the next source.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApiKeyScopeQuota {
+    /// Requests per minute
+    #[serde(default)]
+    pub requests_per_minute: Option<u32>,
+
+    /// Requests per UTC day
+    #[serde(default)]
+    pub daily_quota: Option<u64>,
+
+    /// Requests per UTC calendar month
+    #[serde(default)]
+    pub monthly_quota: Option<u64>,
+}
+
+/// Redis rate limiting algorithm
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RateLimitAlgorithm {
+    /// `INCR` counter per window; clients can burst to twice the limit
+    /// across a window boundary
+    #[default]
+    FixedWindow,
+
+    /// Timestamp log per key; exactly `limit` requests in any rolling window
+    SlidingLog,
+
+    /// Generic cell rate algorithm (token bucket): requests are spaced
+    /// evenly across the window, with up to `burst_size` allowed at once
+    #[serde(alias = "token_bucket")]
+    Gcra,
+}
+
 /// Per-route rate limit configuration
 ///
 /// Configures rate limiting for a specific route or route pattern.
@@ -402,10 +503,10 @@ pub struct RouteRateLimitConfig {
     /// Maximum requests per minute for this route
     pub requests_per_minute: u32,
 
-    /// Burst size for local (governor) rate limiting
+    /// Burst size (bucket capacity) for GCRA rate limiting
     ///
-    /// Allows temporary spikes above the base rate.
-    /// Only used with governor-based rate limiting.
+    /// Allows temporary spikes above the base rate. Used by the governor
+    /// middleware and by the Redis `gcra` algorithm.
     #[serde(default = "default_route_burst_size")]
     pub burst_size: u32,
 
@@ -418,6 +519,10 @@ pub struct RouteRateLimitConfig {
     /// fall back to IP-based tracking when `per_user` is true.
     #[serde(default = "default_true")]
     pub per_user: bool,
+
+    /// Algorithm override for this route (falls back to the global `algorithm`)
+    #[serde(default)]
+    pub algorithm: Option<RateLimitAlgorithm>,
 }
 
 /// Database configuration
@@ -621,6 +726,339 @@ pub struct NatsConfig {
     /// Whether to initialize connection lazily (in background)
     #[serde(default = "default_lazy_init")]
     pub lazy_init: bool,
+
+    /// JetStream streams to declare once the client connects
+    ///
+    /// Declaration is get-or-create: a stream that already exists is used as
+    /// is, and its server-side configuration is not updated to match.
+    ///
+    /// # Example
+    /// ```toml
+    /// [[nats.streams]]
+    /// name = "ORDERS"
+    /// subjects = ["orders.>"]
+    /// storage = "file"
+    /// max_age_secs = 604800
+    /// ```
+    #[serde(default)]
+    pub streams: Vec<NatsStreamConfig>,
+
+    /// Durable JetStream consumers, keyed by consumer name
+    ///
+    /// Each entry needs a handler registered under the same name via
+    /// `ServiceBuilder::with_event_handler`; the NATS pool agent starts the
+    /// consumer once connected and drains it on shutdown.
+    ///
+    /// # Example
+    /// ```toml
+    /// [nats.consumers.order-projector]
+    /// stream = "ORDERS"
+    /// filter_subjects = ["orders.created"]
+    /// max_deliver = 5
+    /// backoff_secs = [1, 10, 60]
+    /// ```
+    #[serde(default)]
+    pub consumers: std::collections::HashMap<String, NatsConsumerConfig>,
+
+    /// Seconds to wait for in-flight handlers when consumers drain on shutdown
+    #[serde(default = "default_nats_drain_timeout_secs")]
+    pub drain_timeout_secs: u64,
+
+    /// Transactional outbox relay (requires a database backend and the
+    /// background worker)
+    ///
+    /// # Example
+    /// ```toml
+    /// [nats.outbox]
+    /// poll_interval_ms = 500
+    /// max_attempts = 10
+    /// ```
+    #[serde(default)]
+    pub outbox: Option<OutboxConfig>,
+}
+
+/// JetStream stream storage backend
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NatsStreamStorage {
+    /// Persist messages to disk
+    #[default]
+    File,
+    /// Keep messages in memory only
+    Memory,
+}
+
+/// JetStream stream retention policy
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NatsStreamRetention {
+    /// Keep messages until a size, count or age limit is reached
+    #[default]
+    Limits,
+    /// Keep messages while any consumer has yet to acknowledge them
+    Interest,
+    /// Remove each message once one consumer acknowledges it
+    WorkQueue,
+}
+
+/// Declaration of a JetStream stream
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NatsStreamConfig {
+    /// Stream name (no spaces, tabs, or `.`)
+    pub name: String,
+
+    /// Subjects captured by the stream; wildcards are allowed
+    pub subjects: Vec<String>,
+
+    /// Storage backend
+    #[serde(default)]
+    pub storage: NatsStreamStorage,
+
+    /// Retention policy
+    #[serde(default)]
+    pub retention: NatsStreamRetention,
+
+    /// Maximum message age in seconds (0 = unlimited)
+    #[serde(default)]
+    pub max_age_secs: u64,
+
+    /// Maximum number of messages retained (0 = unlimited)
+    #[serde(default)]
+    pub max_messages: i64,
+
+    /// Maximum total size in bytes (0 = unlimited)
+    #[serde(default)]
+    pub max_bytes: i64,
+
+    /// Number of replicas in a clustered deployment
+    #[serde(default = "default_nats_stream_replicas")]
+    pub replicas: usize,
+
+    /// Window in seconds within which a repeated `Nats-Msg-Id` is dropped
+    /// as a duplicate (0 = server default)
+    #[serde(default)]
+    pub duplicate_window_secs: u64,
+}
+
+/// How a JetStream consumer receives messages
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NatsConsumerKind {
+    /// The service fetches batches on demand (recommended)
+    #[default]
+    Pull,
+    /// The server pushes messages to a delivery subject
+    Push,
+}
+
+/// Declaration of a durable JetStream consumer
+///
+/// The map key in `[nats.consumers]` is used as the durable name.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NatsConsumerConfig {
+    /// Stream the consumer reads from
+    ///
+    /// Need not appear in `[[nats.streams]]`; a stream owned by another
+    /// service is looked up by name.
+    pub stream: String,
+
+    /// Pull or push delivery
+    #[serde(default)]
+    pub kind: NatsConsumerKind,
+
+    /// Subjects within the stream to deliver (empty = all)
+    #[serde(default)]
+    pub filter_subjects: Vec<String>,
+
+    /// Delivery subject for push consumers (defaults to a fresh inbox)
+    #[serde(default)]
+    pub deliver_subject: Option<String>,
+
+    /// Queue group for push consumers, so replicas share the messages
+    #[serde(default)]
+    pub deliver_group: Option<String>,
+
+    /// Seconds the server waits for an acknowledgement before redelivering
+    #[serde(default = "default_nats_ack_wait_secs")]
+    pub ack_wait_secs: u64,
+
+    /// Maximum delivery attempts per message (-1 = unlimited)
+    #[serde(default = "default_nats_max_deliver")]
+    pub max_deliver: i64,
+
+    /// Server-side redelivery delays in seconds, one per attempt
+    ///
+    /// When set, these replace `ack_wait_secs` as the delay between
+    /// unacknowledged deliveries. The last value repeats.
+    #[serde(default)]
+    pub backoff_secs: Vec<u64>,
+
+    /// Seconds to delay redelivery when a handler returns an error
+    #[serde(default = "default_nats_nak_delay_secs")]
+    pub nak_delay_secs: u64,
+
+    /// Maximum unacknowledged messages in flight (0 = server default)
+    #[serde(default)]
+    pub max_ack_pending: i64,
+
+    /// Number of messages handled concurrently by this service instance
+    #[serde(default = "default_nats_consumer_concurrency")]
+    pub concurrency: usize,
+
+    /// Subject that poison messages are republished to before being terminated
+    ///
+    /// Must be captured by a JetStream stream, since the republish waits for
+    /// a stream acknowledgement.
+    #[serde(default)]
+    pub dead_letter_subject: Option<String>,
+
+    /// Failed deliveries before a message is dead-lettered (0 = `max_deliver`)
+    #[serde(default)]
+    pub dead_letter_after: u32,
+
+    /// Skip messages this consumer has already processed
+    ///
+    /// Requires the `cache` or `database` feature.
+    #[serde(default)]
+    pub dedup: Option<NatsDedupConfig>,
+}
+
+/// Store recording which messages a consumer has processed
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum NatsDedupBackend {
+    /// The `[redis]` pool (requires the `cache` feature)
+    Redis,
+    /// The `[database]` pool (requires the `database` feature)
+    Postgres,
+}
+
+/// Idempotency guard for a JetStream consumer
+///
+/// Each message id is claimed before the handler runs and recorded once it
+/// acks or terminates, so redeliveries and republished duplicates are acked
+/// without running the handler again.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NatsDedupConfig {
+    /// Dedup store (default: Redis when `[redis]` is configured, else Postgres)
+    #[serde(default)]
+    pub backend: Option<NatsDedupBackend>,
+
+    /// Seconds a processed message id is remembered
+    #[serde(default = "default_nats_dedup_ttl_secs")]
+    pub ttl_secs: u64,
+
+    /// Seconds an in-flight claim blocks duplicates (0 = `ack_wait_secs`)
+    ///
+    /// A claim left by a handler that crashed expires after this long.
+    #[serde(default)]
+    pub lease_secs: u64,
+}
+
+/// Transactional outbox relay configuration
+///
+/// Events enqueued inside a database transaction are written to the
+/// `event_outbox` table and published to NATS by a relay running on the
+/// background worker. Rows are marked sent once NATS accepts them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxConfig {
+    /// Whether the relay runs (default: true when the section is present)
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Milliseconds between polls of the outbox table
+    #[serde(default = "default_outbox_poll_interval_ms")]
+    pub poll_interval_ms: u64,
+
+    /// Maximum rows claimed per poll
+    #[serde(default = "default_outbox_batch_size")]
+    pub batch_size: usize,
+
+    /// Publish attempts before a row is marked failed and left for inspection
+    #[serde(default = "default_outbox_max_attempts")]
+    pub max_attempts: u32,
+
+    /// Delay before the first retry; doubles with each further attempt
+    #[serde(default = "default_outbox_retry_base_delay_secs")]
+    pub retry_base_delay_secs: u64,
+
+    /// Upper bound on the delay between retries
+    #[serde(default = "default_outbox_retry_max_delay_secs")]
+    pub retry_max_delay_secs: u64,
+
+    /// Seconds a claimed row is hidden from other relays while it is published
+    ///
+    /// Lets several replicas run the relay against one table. A relay that
+    /// dies mid-batch releases its rows when the lease expires.
+    #[serde(default = "default_outbox_lease_secs")]
+    pub lease_secs: u64,
+
+    /// Publish through JetStream and wait for the stream's acknowledgement
+    ///
+    /// The event id is sent as `Nats-Msg-Id`, so a row republished after a
+    /// crash is dropped by the stream's duplicate window. When false, rows
+    /// are published with core NATS and marked sent after a flush.
+    #[serde(default = "default_true")]
+    pub jetstream: bool,
+
+    /// Seconds to keep sent rows before deleting them (0 = keep forever)
+    #[serde(default = "default_outbox_sent_retention_secs")]
+    pub sent_retention_secs: u64,
+
+    /// Age of the oldest pending row at which the outbox reports unhealthy
+    /// (0 = never)
+    #[serde(default = "default_outbox_max_pending_age_secs")]
+    pub max_pending_age_secs: u64,
+}
+
+impl Default for OutboxConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            poll_interval_ms: default_outbox_poll_interval_ms(),
+            batch_size: default_outbox_batch_size(),
+            max_attempts: default_outbox_max_attempts(),
+            retry_base_delay_secs: default_outbox_retry_base_delay_secs(),
+            retry_max_delay_secs: default_outbox_retry_max_delay_secs(),
+            lease_secs: default_outbox_lease_secs(),
+            jetstream: true,
+            sent_retention_secs: default_outbox_sent_retention_secs(),
+            max_pending_age_secs: default_outbox_max_pending_age_secs(),
+        }
+    }
+}
+
+impl OutboxConfig {
+    /// Longest duration any outbox setting may hold (100 years)
+    ///
+    /// Lease, retry and retention deadlines are computed as timestamps, so
+    /// values past this would overflow the date arithmetic.
+    pub const MAX_DURATION_SECS: u64 = 100 * 365 * 24 * 60 * 60;
+
+    /// Check that every duration fits the relay's timestamp arithmetic
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Internal`](crate::error::Error::Internal) naming the
+    /// first setting above [`Self::MAX_DURATION_SECS`].
+    pub fn validate(&self) -> Result<()> {
+        let durations = [
+            ("retry_base_delay_secs", self.retry_base_delay_secs),
+            ("retry_max_delay_secs", self.retry_max_delay_secs),
+            ("lease_secs", self.lease_secs),
+            ("sent_retention_secs", self.sent_retention_secs),
+            ("max_pending_age_secs", self.max_pending_age_secs),
+        ];
+        for (name, secs) in durations {
+            if secs > Self::MAX_DURATION_SECS {
+                return Err(crate::error::Error::Internal(format!(
+                    "[nats.outbox] {name} = {secs} is out of range (maximum {})",
+                    Self::MAX_DURATION_SECS
+                )));
+            }
+        }
+        Ok(())
+    }
 }
 
 /// ClickHouse analytical database configuration
@@ -958,6 +1396,7 @@ impl Default for GraphQLConfig {
 // as a plain `Option<TlsConfig>`, never `#[serde(flatten)]`ed (which is the one
 // case that would misbehave with this attribute).
 #[serde(deny_unknown_fields)]
+#[non_exhaustive]
 pub struct TlsConfig {
     /// Enable TLS (default: true when section is present)
     #[serde(default = "default_true")]
@@ -984,6 +1423,22 @@ pub struct TlsConfig {
     #[serde(default = "default_false")]
     pub client_auth_optional: bool,
 
+    /// PEM files of certificate revocation lists (CRLs) checked against client
+    /// certificates. Empty (the default) disables revocation checking.
+    ///
+    /// Every certificate in the client's chain is checked, not just the leaf,
+    /// so each CA that issues into the chain needs a CRL here: a certificate
+    /// whose issuer has no CRL has unknown revocation status and is rejected.
+    /// An expired CRL, one whose `nextUpdate` has passed, is also fail-closed:
+    /// every certificate it covers is rejected until a fresh CRL is loaded.
+    ///
+    /// The files are reread with the certificates on every reload, so a CRL
+    /// publisher that rewrites them in place is picked up by
+    /// `reload_interval_secs` or `reload_on_sighup`. Ignored when
+    /// `client_ca_path` is absent.
+    #[serde(default)]
+    pub client_crl_paths: Vec<PathBuf>,
+
     /// Poll the credential files this often, in seconds, and reload them when
     /// their contents change. `None` (the default) disables polling.
     ///
@@ -1028,6 +1483,275 @@ pub struct TlsConfig {
     /// instantly.
     #[serde(default)]
     pub handshake_timeout_secs: Option<u64>,
+
+    /// How many days before a certificate expires the `tls_certificates`
+    /// readiness check turns `Degraded`. `None` (the default) uses
+    /// [`DEFAULT_EXPIRY_WARNING_DAYS`](crate::cert_expiry::DEFAULT_EXPIRY_WARNING_DAYS).
+    ///
+    /// Applies to every certificate this section serves and to its client-CA
+    /// bundle. An expired certificate turns the check `Unready` whatever the
+    /// window.
+    #[serde(default)]
+    pub expiry_warning_days: Option<u64>,
+
+    /// Additional certificates, selected by the hostname a client sends in its
+    /// TLS Server Name Indication (SNI) extension.
+    ///
+    /// `cert_path` and `key_path` above remain the default certificate: it is
+    /// served to clients that send no SNI and to clients whose SNI matches none
+    /// of these entries. An exact hostname beats a wildcard, so
+    /// `api.example.com` wins over `*.example.com` whichever is listed first.
+    ///
+    /// Every entry is reread on reload alongside the default certificate, and
+    /// each one rotates independently: an entry whose files are unreadable or
+    /// mismatched keeps serving its previous certificate while the others take
+    /// their new ones, and the reload reports the failure.
+    ///
+    /// ```toml
+    /// [[tls.certificates]]
+    /// hostnames = ["api.example.com", "*.api.example.com"]
+    /// cert_path = "/etc/tls/api.crt"
+    /// key_path = "/etc/tls/api.key"
+    /// ```
+    #[serde(default)]
+    pub certificates: Vec<SniCertificateConfig>,
+
+    /// Obtain and renew the default certificate from an ACME (RFC 8555)
+    /// certificate authority, such as Let's Encrypt, instead of expecting
+    /// something else to provision `cert_path` and `key_path`.
+    ///
+    /// The issued certificate and key are written to `cert_path` and
+    /// `key_path` and installed through [`crate::tls::TlsConfigSource::reload`],
+    /// so everything that watches those files keeps working. Requires the
+    /// `acme` feature; a build without it refuses to start with this set rather
+    /// than serving a certificate nothing will renew. See `crate::acme`.
+    ///
+    /// ```toml
+    /// [tls.acme]
+    /// directory_url = "https://acme-v02.api.letsencrypt.org/directory"
+    /// domains = ["api.example.com"]
+    /// contact = ["mailto:ops@example.com"]
+    /// accept_terms_of_service = true
+    /// account_key_path = "/var/lib/acme/account.pem"
+    /// ```
+    #[serde(default)]
+    pub acme: Option<AcmeConfig>,
+}
+
+#[cfg(feature = "tls")]
+impl TlsConfig {
+    /// Create an enabled TLS configuration serving `cert_path` and `key_path`,
+    /// with every other key at its default.
+    pub fn new(cert_path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> Self {
+        Self {
+            enabled: true,
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+            client_ca_path: None,
+            client_auth_optional: false,
+            client_crl_paths: Vec::new(),
+            reload_interval_secs: None,
+            reload_on_sighup: false,
+            handshake_timeout_secs: None,
+            expiry_warning_days: None,
+            certificates: Vec::new(),
+            acme: None,
+        }
+    }
+
+    /// Set whether TLS is enabled.
+    #[must_use]
+    pub fn with_enabled(mut self, enabled: bool) -> Self {
+        self.enabled = enabled;
+        self
+    }
+
+    /// Verify client certificates against this CA bundle (mutual TLS).
+    #[must_use]
+    pub fn with_client_ca_path(mut self, path: impl Into<PathBuf>) -> Self {
+        self.client_ca_path = Some(path.into());
+        self
+    }
+
+    /// Set whether a client certificate is optional under mutual TLS.
+    #[must_use]
+    pub fn with_client_auth_optional(mut self, optional: bool) -> Self {
+        self.client_auth_optional = optional;
+        self
+    }
+
+    /// Check client certificates against these CRL files.
+    #[must_use]
+    pub fn with_client_crl_paths(mut self, paths: Vec<PathBuf>) -> Self {
+        self.client_crl_paths = paths;
+        self
+    }
+
+    /// Poll the credential files for changes every `secs` seconds.
+    #[must_use]
+    pub fn with_reload_interval_secs(mut self, secs: u64) -> Self {
+        self.reload_interval_secs = Some(secs);
+        self
+    }
+
+    /// Set whether `SIGHUP` reloads the credential files.
+    #[must_use]
+    pub fn with_reload_on_sighup(mut self, reload: bool) -> Self {
+        self.reload_on_sighup = reload;
+        self
+    }
+
+    /// Drop connections whose handshake takes longer than `secs` seconds.
+    #[must_use]
+    pub fn with_handshake_timeout_secs(mut self, secs: u64) -> Self {
+        self.handshake_timeout_secs = Some(secs);
+        self
+    }
+
+    /// Turn the certificate readiness check `Degraded` this many days before
+    /// expiry.
+    #[must_use]
+    pub fn with_expiry_warning_days(mut self, days: u64) -> Self {
+        self.expiry_warning_days = Some(days);
+        self
+    }
+
+    /// Serve these additional certificates by SNI hostname.
+    #[must_use]
+    pub fn with_certificates(mut self, certificates: Vec<SniCertificateConfig>) -> Self {
+        self.certificates = certificates;
+        self
+    }
+
+    /// Obtain and renew the default certificate over ACME.
+    #[must_use]
+    pub fn with_acme(mut self, acme: AcmeConfig) -> Self {
+        self.acme = Some(acme);
+        self
+    }
+}
+
+/// A certificate served to clients that ask for one of its hostnames
+/// (requires `tls` feature)
+///
+/// One entry of [`TlsConfig::certificates`].
+#[cfg(feature = "tls")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+#[non_exhaustive]
+pub struct SniCertificateConfig {
+    /// SNI hostnames that select this certificate, compared case-insensitively.
+    ///
+    /// Each is either an exact name (`api.example.com`) or a wildcard whose `*`
+    /// is the whole leftmost label (`*.example.com`). A wildcard matches exactly
+    /// one label, as it would in the certificate itself: `*.example.com` matches
+    /// `api.example.com` but neither `example.com` nor `a.b.example.com`. A
+    /// hostname may appear in only one entry; a duplicate is rejected at load.
+    pub hostnames: Vec<String>,
+
+    /// Path to the PEM-encoded certificate chain served for these hostnames
+    pub cert_path: PathBuf,
+
+    /// Path to the PEM-encoded private key for `cert_path`
+    pub key_path: PathBuf,
+}
+
+#[cfg(feature = "tls")]
+impl SniCertificateConfig {
+    /// Create an entry serving `cert_path` and `key_path` for `hostnames`.
+    pub fn new(
+        hostnames: Vec<String>,
+        cert_path: impl Into<PathBuf>,
+        key_path: impl Into<PathBuf>,
+    ) -> Self {
+        Self {
+            hostnames,
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+        }
+    }
+}
+
+/// ACME certificate provisioning for a TLS listener (requires `tls` feature;
+/// provisioning itself requires `acme`)
+///
+/// One entry of [`TlsConfig::acme`]. The certificate covers every name in
+/// `domains` and is renewed `renew_before_days` before it expires.
+#[cfg(feature = "tls")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AcmeConfig {
+    /// The ACME server's directory URL, for example
+    /// `https://acme-v02.api.letsencrypt.org/directory`.
+    pub directory_url: String,
+
+    /// DNS names the certificate is issued for. Every name must resolve to
+    /// this listener for its challenge to succeed.
+    pub domains: Vec<String>,
+
+    /// Contact URLs registered with the account, typically
+    /// `mailto:` addresses the CA uses for expiry and policy notices.
+    #[serde(default)]
+    pub contact: Vec<String>,
+
+    /// Agree to the CA's terms of service. Must be `true`: every public CA
+    /// refuses to create an account without it, and agreeing on an operator's
+    /// behalf is not something a default should do.
+    #[serde(default = "default_false")]
+    pub accept_terms_of_service: bool,
+
+    /// Where the ACME account key is kept, as a PEM-encoded PKCS#8 ECDSA P-256
+    /// key. Generated on first use; keep it between restarts, since the CA
+    /// rate-limits new accounts.
+    pub account_key_path: PathBuf,
+
+    /// How the CA is asked to validate control of `domains` (default:
+    /// `tls-alpn-01`).
+    #[serde(default)]
+    pub challenge: AcmeChallenge,
+
+    /// Renew this many days before the certificate expires. `None` (the
+    /// default) uses 30 days, a third of a 90-day certificate's lifetime.
+    #[serde(default)]
+    pub renew_before_days: Option<u64>,
+
+    /// A PEM CA bundle to trust for the connection to `directory_url`, on top
+    /// of the public roots. Only needed for a private CA, or a test server
+    /// such as Pebble whose directory is served with its own root.
+    #[serde(default)]
+    pub directory_ca_path: Option<PathBuf>,
+}
+
+/// How an ACME server validates control of a domain (requires `tls` feature)
+#[cfg(feature = "tls")]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum AcmeChallenge {
+    /// The CA opens a TLS connection to port 443 offering the `acme-tls/1`
+    /// protocol, and the listener answers with a certificate proving control
+    /// (RFC 8737). Needs nothing but the HTTPS listener itself, so it is the
+    /// default.
+    #[default]
+    #[serde(rename = "tls-alpn-01")]
+    TlsAlpn01,
+
+    /// The CA fetches `/.well-known/acme-challenge/<token>` over plain HTTP on
+    /// port 80. The service answers it on its HTTP router, so port 80 must
+    /// reach that router, directly or through a redirect to HTTPS, which CAs
+    /// follow.
+    #[serde(rename = "http-01")]
+    Http01,
+}
+
+#[cfg(feature = "tls")]
+impl AcmeChallenge {
+    /// The challenge type as the ACME protocol names it.
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::TlsAlpn01 => "tls-alpn-01",
+            Self::Http01 => "http-01",
+        }
+    }
 }
 
 /// Caller authorization for mutual-TLS deployments (requires `tls` feature)
@@ -1047,8 +1771,19 @@ pub struct TlsConfig {
 ///
 /// Entries are matched byte-exactly against the DNS and URI `subjectAltName`
 /// values of the caller's leaf certificate. An entry containing `://` is
-/// treated as a URI SAN, anything else as a DNS SAN; there is no wildcard or
-/// suffix matching.
+/// treated as a URI SAN, anything else as a DNS SAN. The one exception to
+/// exact matching is a SPIFFE ID pattern ending in `/*`, such as
+/// `spiffe://cluster.local/ns/prod/*`, which admits every workload under that
+/// path in that trust domain; see [`crate::spiffe::SpiffeIdPattern`].
+///
+/// Add a `[caller_auth.spiffe]` table to also check that each SPIFFE caller's
+/// certificate chains to its own trust domain's bundle:
+///
+/// ```toml
+/// [caller_auth.spiffe]
+/// trust_bundles = { "cluster.local" = "/run/spiffe/bundle.pem" }
+/// reload_interval_secs = 30
+/// ```
 ///
 /// Combinations that would look like protection without being it are refused
 /// at startup rather than accepted: a certificate mode with no
@@ -1079,14 +1814,71 @@ pub struct CallerAuthConfig {
     /// `/api-docs` on HTTP; the gRPC health and reflection services on gRPC).
     #[serde(default)]
     pub public_paths: Vec<String>,
+
+    /// Per-trust-domain bundles for SPIFFE callers (optional).
+    #[serde(default)]
+    pub spiffe: Option<SpiffeConfig>,
+}
+
+/// SPIFFE trust bundles for caller authorization (requires `tls` feature)
+///
+/// Maps each trust domain to the file holding its X.509 bundle, as PEM
+/// certificates or a SPIFFE JSON bundle. A SPIFFE caller is then admitted only
+/// if its certificate chains to the bundle of the trust domain its ID names,
+/// so a federated CA cannot vouch for identities outside its own domain.
+///
+/// The files are reread every `reload_interval_secs` and, when
+/// `[tls].reload_on_sighup` is set, on `SIGHUP`, so spiffe-helper can rotate
+/// them in place. See [`crate::spiffe`].
+#[cfg(feature = "tls")]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SpiffeConfig {
+    /// Trust domain name to bundle file path.
+    #[serde(default)]
+    pub trust_bundles: std::collections::BTreeMap<String, PathBuf>,
+
+    /// How often to reread the bundles, in seconds. Omit to reload only on
+    /// `SIGHUP` or restart.
+    #[serde(default)]
+    pub reload_interval_secs: Option<u64>,
 }
 
 #[cfg(feature = "tls")]
 impl CallerAuthConfig {
+    /// Build the policy this section describes, loading any SPIFFE trust
+    /// bundles it names.
+    ///
+    /// [`Self::to_policy`] plus the file I/O it leaves out. This is what the
+    /// service builder calls.
+    ///
+    /// # Errors
+    ///
+    /// As [`Self::to_policy`], plus
+    /// [`CallerAuthConfigError::TrustBundle`](crate::caller_auth::CallerAuthConfigError::TrustBundle)
+    /// when a bundle cannot be loaded and the other
+    /// [`CallerAuthPolicy::with_spiffe_bundles`](crate::caller_auth::CallerAuthPolicy::with_spiffe_bundles)
+    /// refusals.
+    pub fn load_policy(
+        &self,
+    ) -> std::result::Result<
+        crate::caller_auth::CallerAuthPolicy,
+        crate::caller_auth::CallerAuthConfigError,
+    > {
+        let policy = self.to_policy()?;
+        let Some(spiffe) = &self.spiffe else {
+            return Ok(policy);
+        };
+        let bundles = crate::spiffe::SpiffeTrustBundles::from_config(spiffe)
+            .map_err(|e| crate::caller_auth::CallerAuthConfigError::TrustBundle(e.to_string()))?;
+        policy.with_spiffe_bundles(bundles)
+    }
+
     /// Build the policy this section describes.
     ///
-    /// Pure: derives the policy solely from `self`. Cross-section checks that
-    /// need the TLS configuration live in
+    /// Pure: derives the policy solely from `self`, without reading the
+    /// `[caller_auth.spiffe]` bundles (see [`Self::load_policy`]). Cross-section
+    /// checks that need the TLS configuration live in
     /// [`crate::caller_auth::validate_listener`].
     ///
     /// # Errors
@@ -1188,6 +1980,23 @@ pub struct ClientIdentityConfig {
     #[serde(default = "default_false")]
     pub exclusive_roots: bool,
 
+    /// PEM files of certificate revocation lists (CRLs) checked against the
+    /// *peer's* server certificate. Empty (the default) disables revocation
+    /// checking.
+    ///
+    /// An expired CRL rejects every certificate it covers until a fresh one is
+    /// loaded. With [`exclusive_roots`](Self::exclusive_roots) set, every
+    /// issuer in the peer's chain is yours, so one without a CRL here is
+    /// rejected as unknown; without it, certificates from issuers no CRL
+    /// covers, such as the public web PKI, are accepted unchecked.
+    ///
+    /// Honoured by [`crate::client_tls::ClientIdentitySource`] and
+    /// [`crate::client_tls::load_rustls_client_config`], which reread the files
+    /// on every reload. The `reqwest` and `tonic` builders cannot apply this
+    /// policy and refuse a configuration that sets it.
+    #[serde(default)]
+    pub crl_paths: Vec<PathBuf>,
+
     /// Seconds to allow for the TCP connect plus the TLS handshake when opening
     /// a connection to a peer.
     ///
@@ -1206,6 +2015,13 @@ pub struct ClientIdentityConfig {
     /// `handshake_timeout_secs`.
     #[serde(default)]
     pub connect_timeout_secs: Option<u64>,
+
+    /// How many days before the client certificate expires a monitored
+    /// [`crate::client_tls::ClientIdentitySource`] turns the `tls_certificates`
+    /// readiness check `Degraded`. `None` (the default) uses
+    /// [`DEFAULT_EXPIRY_WARNING_DAYS`](crate::cert_expiry::DEFAULT_EXPIRY_WARNING_DAYS).
+    #[serde(default)]
+    pub expiry_warning_days: Option<u64>,
 }
 
 /// Journald logging configuration (requires `journald` feature)
@@ -1337,6 +2153,10 @@ pub struct MiddlewareConfig {
     /// Security headers configuration (HSTS, X-Content-Type-Options, etc.)
     #[serde(default)]
     pub security_headers: SecurityHeadersConfig,
+
+    /// Idempotency-Key configuration (requires `idempotency` feature)
+    #[serde(default)]
+    pub idempotency: Option<IdempotencyConfig>,
 }
 
 impl Default for MiddlewareConfig {
@@ -1351,10 +2171,100 @@ impl Default for MiddlewareConfig {
             compression: true,
             cors_mode: default_cors_mode(),
             security_headers: SecurityHeadersConfig::default(),
+            idempotency: None,
         }
     }
 }
 
+/// Idempotency-Key middleware configuration
+///
+/// Applies to unsafe methods (POST, PUT, PATCH, DELETE). The first response
+/// for a key is stored in Redis and replayed for retries with the same key;
+/// keys are scoped to the authenticated principal.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IdempotencyConfig {
+    /// Enable the middleware
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Request header carrying the idempotency key
+    #[serde(default = "default_idempotency_header")]
+    pub header: String,
+
+    /// How long a stored response is replayed, in seconds
+    #[serde(default = "default_idempotency_ttl_secs")]
+    pub ttl_secs: u64,
+
+    /// How long an in-flight request holds its key, in seconds
+    ///
+    /// Bounds the lock left behind by a request that never completes.
+    #[serde(default = "default_idempotency_lock_secs")]
+    pub lock_secs: u64,
+
+    /// Reject unsafe requests without a key (400) instead of passing them through
+    #[serde(default)]
+    pub required: bool,
+
+    /// Largest response body that is stored; larger responses are not replayable
+    #[serde(default = "default_idempotency_max_response_bytes")]
+    pub max_response_bytes: usize,
+
+    /// Largest request body buffered for fingerprinting; larger requests get 413
+    ///
+    /// The service builder lowers this to `body_limit_mb` if it is larger,
+    /// since the middleware runs outside the body limit layer.
+    #[serde(default = "default_idempotency_max_request_bytes")]
+    pub max_request_bytes: usize,
+
+    /// Per-route overrides
+    ///
+    /// Route patterns follow the same syntax as [`RateLimitConfig::routes`].
+    ///
+    /// # Example
+    /// ```toml
+    /// [middleware.idempotency.routes."POST /api/v1/payments"]
+    /// required = true
+    /// ttl_secs = 604800
+    ///
+    /// [middleware.idempotency.routes."/api/v1/search"]
+    /// enabled = false
+    /// ```
+    #[serde(default)]
+    pub routes: std::collections::HashMap<String, RouteIdempotencyConfig>,
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            header: default_idempotency_header(),
+            ttl_secs: default_idempotency_ttl_secs(),
+            lock_secs: default_idempotency_lock_secs(),
+            required: false,
+            max_response_bytes: default_idempotency_max_response_bytes(),
+            max_request_bytes: default_idempotency_max_request_bytes(),
+            routes: std::collections::HashMap::new(),
+        }
+    }
+}
+
+/// Per-route idempotency configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteIdempotencyConfig {
+    /// Whether idempotency keys are honoured on this route
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+
+    /// Reject requests without a key on this route, even when the global
+    /// `required` is off
+    #[serde(default)]
+    pub required: bool,
+
+    /// Replay TTL for this route (falls back to the global `ttl_secs`)
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+}
+
 /// Request tracking configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RequestTrackingConfig {
@@ -1639,6 +2549,16 @@ fn default_jwt_algorithm() -> String {
     "RS256".to_string()
 }
 
+#[cfg(feature = "jwt")]
+fn default_jwks_refresh_secs() -> u64 {
+    300 // 5 minutes
+}
+
+#[cfg(feature = "jwt")]
+fn default_jwks_min_refresh_secs() -> u64 {
+    30
+}
+
 fn default_per_user_rpm() -> u32 {
     200
 }
@@ -1671,6 +2591,66 @@ fn default_redis_max_connections() -> usize {
     20
 }
 
+fn default_outbox_poll_interval_ms() -> u64 {
+    500
+}
+
+fn default_outbox_batch_size() -> usize {
+    100
+}
+
+fn default_outbox_max_attempts() -> u32 {
+    10
+}
+
+fn default_outbox_retry_base_delay_secs() -> u64 {
+    1
+}
+
+fn default_outbox_retry_max_delay_secs() -> u64 {
+    300
+}
+
+fn default_outbox_lease_secs() -> u64 {
+    30
+}
+
+fn default_outbox_sent_retention_secs() -> u64 {
+    86400
+}
+
+fn default_outbox_max_pending_age_secs() -> u64 {
+    300
+}
+
+fn default_nats_drain_timeout_secs() -> u64 {
+    30
+}
+
+fn default_nats_stream_replicas() -> usize {
+    1
+}
+
+fn default_nats_ack_wait_secs() -> u64 {
+    30
+}
+
+fn default_nats_max_deliver() -> i64 {
+    5
+}
+
+fn default_nats_nak_delay_secs() -> u64 {
+    5
+}
+
+fn default_nats_consumer_concurrency() -> usize {
+    1
+}
+
+fn default_nats_dedup_ttl_secs() -> u64 {
+    86400
+}
+
 fn default_max_reconnects() -> usize {
     10
 }
@@ -1727,6 +2707,26 @@ fn default_cors_mode() -> String {
     "restrictive".to_string()
 }
 
+fn default_idempotency_header() -> String {
+    "Idempotency-Key".to_string()
+}
+
+fn default_idempotency_ttl_secs() -> u64 {
+    86400 // 24 hours
+}
+
+fn default_idempotency_lock_secs() -> u64 {
+    60
+}
+
+fn default_idempotency_max_response_bytes() -> usize {
+    1024 * 1024 // 1 MB
+}
+
+fn default_idempotency_max_request_bytes() -> usize {
+    default_body_limit_mb() * 1024 * 1024
+}
+
 fn default_request_id_header() -> String {
     "x-request-id".to_string()
 }
@@ -2285,6 +3285,7 @@ port = 9091
             mode: crate::caller_auth::CallerAuthMode::Bearer,
             allowlist: vec!["reporter.internal".to_string()],
             public_paths: Vec::new(),
+            spiffe: None,
         };
         assert_eq!(
             dead_allowlist
@@ -2297,6 +3298,7 @@ port = 9091
             mode: crate::caller_auth::CallerAuthMode::Mtls,
             allowlist: Vec::new(),
             public_paths: Vec::new(),
+            spiffe: None,
         };
         assert_eq!(
             empty_allowlist
@@ -2306,6 +3308,48 @@ port = 9091
         );
     }
 
+    #[cfg(feature = "tls")]
+    #[test]
+    fn caller_auth_config_loads_spiffe_trust_bundles() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let bundle = dir.path().join("prod.pem");
+        let ca = rcgen::generate_simple_self_signed(vec!["spiffe-ca".to_string()])
+            .expect("self-signed ca");
+        std::fs::write(&bundle, ca.cert.pem()).expect("write bundle");
+
+        let json = serde_json::json!({
+            "mode": "mtls",
+            "allowlist": ["spiffe://prod.local/ns/payments/*"],
+            "spiffe": {
+                "trust_bundles": { "prod.local": bundle },
+                "reload_interval_secs": 30
+            }
+        });
+        let caller_auth: CallerAuthConfig =
+            serde_json::from_value(json).expect("caller auth config must parse");
+
+        let policy = caller_auth.load_policy().expect("valid policy");
+        let bundles = policy.spiffe_bundles().expect("bundles attached");
+        assert_eq!(bundles.trust_domains().collect::<Vec<_>>(), ["prod.local"]);
+        assert_eq!(
+            bundles.reload_interval(),
+            Some(std::time::Duration::from_secs(30))
+        );
+
+        let bearer = CallerAuthConfig {
+            mode: crate::caller_auth::CallerAuthMode::Bearer,
+            allowlist: Vec::new(),
+            public_paths: Vec::new(),
+            spiffe: caller_auth.spiffe.clone(),
+        };
+        assert_eq!(
+            bearer
+                .load_policy()
+                .expect_err("bundles nothing consults must be refused"),
+            crate::caller_auth::CallerAuthConfigError::SpiffeBundlesWithoutCertificateMode
+        );
+    }
+
     #[cfg(feature = "tls")]
     #[test]
     fn tls_config_parses_mutual_tls_fields() {
@@ -2402,7 +3446,9 @@ port = 9091
                 per_user_rpm: 100,
                 per_client_rpm: 500,
                 window_secs: 60,
+                algorithm: RateLimitAlgorithm::default(),
                 routes: std::collections::HashMap::new(),
+                api_key_scopes: std::collections::HashMap::new(),
                 auto_apply: true,
                 trust_forwarded_headers: false,
             },
@@ -2473,7 +3519,9 @@ port = 9091
                 per_user_rpm: 200,
                 per_client_rpm: 1000,
                 window_secs: 60,
+                algorithm: RateLimitAlgorithm::default(),
                 routes: std::collections::HashMap::new(),
+                api_key_scopes: std::collections::HashMap::new(),
                 auto_apply: true,
                 trust_forwarded_headers: false,
             },
@@ -2701,6 +3749,35 @@ bind = "not-an-ip"
         assert!(result.is_err(), "an invalid bind address must be rejected");
     }
 
+    #[test]
+    fn test_rate_limit_algorithm_parses_from_toml() {
+        let toml = r#"
+[service]
+name = "limited-service"
+
+[rate_limit]
+algorithm = "sliding_log"
+
+[rate_limit.routes."POST /api/v1/uploads"]
+requests_per_minute = 5
+algorithm = "token_bucket"
+"#;
+        let config: Config<()> = Figment::new()
+            .merge(Serialized::defaults(Config::<()>::default()))
+            .merge(Toml::string(toml))
+            .extract()
+            .expect("rate limit algorithms must deserialize");
+        assert_eq!(config.rate_limit.algorithm, RateLimitAlgorithm::SlidingLog);
+        assert_eq!(
+            config.rate_limit.routes["POST /api/v1/uploads"].algorithm,
+            Some(RateLimitAlgorithm::Gcra)
+        );
+        assert_eq!(
+            Config::<()>::default().rate_limit.algorithm,
+            RateLimitAlgorithm::FixedWindow
+        );
+    }
+
     #[test]
     fn test_grpc_effective_bind_falls_back_to_service() {
         let service_bind = IpAddr::V4(Ipv4Addr::LOCALHOST);
@@ -351,6 +351,14 @@ pub enum Error {
     #[error("Validation error: {0}")]
     ValidationError(String),
 
+    /// Validation error with field-level details (422)
+    ///
+    /// Responds with the [`ValidationError`](crate::responses::ValidationError)
+    /// body itself, so clients get the `errors` map keyed by field rather than
+    /// a single message.
+    #[error("Validation error: {0}")]
+    FieldValidation(Box<crate::responses::ValidationError>),
+
     /// Not supported error (501)
     #[error("Not supported: {0}")]
     NotSupported(String),
@@ -399,6 +407,16 @@ pub enum Error {
         /// Seconds until the lockout expires
         retry_after_secs: u64,
     },
+
+    /// API key daily or monthly quota exhausted
+    #[cfg(feature = "auth")]
+    #[error("Quota exceeded: {message}")]
+    QuotaExceeded {
+        /// Human-readable message
+        message: String,
+        /// Seconds until the quota period rolls over
+        retry_after_secs: u64,
+    },
 }
 
 /// Error response body
@@ -600,6 +618,8 @@ impl IntoResponse for Error {
                 ErrorResponse::with_code(StatusCode::UNPROCESSABLE_ENTITY, "VALIDATION_ERROR", msg),
             ),
 
+            Error::FieldValidation(errors) => return errors.into_response(),
+
             Error::NotSupported(msg) => (
                 StatusCode::NOT_IMPLEMENTED,
                 ErrorResponse::with_code(StatusCode::NOT_IMPLEMENTED, "NOT_SUPPORTED", msg),
@@ -702,6 +722,30 @@ impl IntoResponse for Error {
                 }
                 return response;
             }
+
+            #[cfg(feature = "auth")]
+            Error::QuotaExceeded {
+                ref message,
+                retry_after_secs,
+            } => {
+                // HTTP 429 with its own code so clients can tell an exhausted
+                // allowance from a per-minute rate limit
+                let error_response = ErrorResponse::with_code(
+                    StatusCode::TOO_MANY_REQUESTS,
+                    "QUOTA_EXCEEDED",
+                    message.clone(),
+                );
+                let mut response =
+                    (StatusCode::TOO_MANY_REQUESTS, Json(error_response)).into_response();
+                if let Ok(value) =
+                    axum::http::header::HeaderValue::from_str(&retry_after_secs.to_string())
+                {
+                    response
+                        .headers_mut()
+                        .insert(axum::http::header::RETRY_AFTER, value);
+                }
+                return response;
+            }
         };
 
         (status, Json(error_response)).into_response()
@@ -715,6 +759,12 @@ impl fmt::Display for ErrorResponse {
 }
 
 // Manual From implementations for boxed errors
+impl From<crate::responses::ValidationError> for Error {
+    fn from(err: crate::responses::ValidationError) -> Self {
+        Error::FieldValidation(Box::new(err))
+    }
+}
+
 impl From<figment::Error> for Error {
     fn from(err: figment::Error) -> Self {
         Error::Config(Box::new(err))
@@ -1010,6 +1060,18 @@ mod tests {
         assert_eq!(err.code, Some("INVALID_EMAIL".to_string()));
     }
 
+    #[test]
+    fn test_field_validation_response() {
+        let mut errors = crate::responses::ValidationError::new("Password rejected");
+        errors.add_field_error("password", "TOO_SHORT", "Password is too short");
+
+        let err = Error::from(errors);
+        assert_eq!(err.to_string(), "Validation error: Password rejected");
+
+        let response = err.into_response();
+        assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    }
+
     // =========================================================================
     // DatabaseError Tests
     // =========================================================================
@@ -0,0 +1,1050 @@
+//! JetStream consumers run by the NATS pool agent
+//!
+//! Streams and durable consumers are declared in `[nats]` configuration;
+//! handlers are registered against consumer names with
+//! [`ServiceBuilder::with_event_handler`](crate::service_builder::ServiceBuilder::with_event_handler).
+//! Once the client connects, the pool agent declares the streams, creates or
+//! binds each consumer, and feeds its messages to the handler. On shutdown it
+//! stops fetching and waits (up to `drain_timeout_secs`) for in-flight
+//! handlers before the client is closed.
+//!
+//! Every message is settled explicitly. A handler returns an [`AckAction`];
+//! a handler error or panic becomes a delayed `Nak`, so the message is
+//! redelivered subject to the consumer's `max_deliver` and `backoff_secs`.
+//!
+//! With `dead_letter_subject` set, a message that is terminated, or still
+//! failing after `dead_letter_after` deliveries (default `max_deliver`), is
+//! republished there with its original headers plus `Dead-Letter-*`
+//! diagnostics, then terminated. If the republish fails the message is naked
+//! instead, so it is never dropped silently while redeliveries remain.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use acton_service::events::{json_handler, AckAction};
+//!
+//! #[derive(Deserialize)]
+//! struct OrderCreated { id: Uuid }
+//!
+//! ServiceBuilder::new()
+//!     .with_event_handler(
+//!         "order-projector",
+//!         json_handler(|order: OrderCreated, _msg| async move {
+//!             project(order).await?;
+//!             Ok(AckAction::Ack)
+//!         }),
+//!     )
+//!     .build()
+//!     .serve()
+//!     .await?;
+//! ```
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::panic::AssertUnwindSafe;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_nats::jetstream::{self, consumer, stream, AckKind};
+use async_trait::async_trait;
+use futures::{FutureExt, Stream, StreamExt};
+use serde::de::DeserializeOwned;
+use tokio_util::sync::CancellationToken;
+use tokio_util::task::TaskTracker;
+use tracing::Instrument;
+
+use super::envelope::{Event, EventEnvelope};
+
+use crate::config::{
+    NatsConfig, NatsConsumerConfig, NatsConsumerKind, NatsStreamConfig, NatsStreamRetention,
+    NatsStreamStorage,
+};
+use crate::error::{Error, Result};
+
+/// Delay between attempts to (re)establish a consumer after a failure
+const CONSUMER_RETRY_DELAY: Duration = Duration::from_secs(5);
+
+/// Why a message was dead-lettered
+pub const DEAD_LETTER_REASON_HEADER: &str = "Dead-Letter-Reason";
+/// Consumer that dead-lettered the message
+pub const DEAD_LETTER_CONSUMER_HEADER: &str = "Dead-Letter-Consumer";
+/// Subject the message was originally published to
+pub const DEAD_LETTER_SUBJECT_HEADER: &str = "Dead-Letter-Subject";
+/// Sequence of the message in its original stream
+pub const DEAD_LETTER_SEQUENCE_HEADER: &str = "Dead-Letter-Stream-Sequence";
+/// Deliveries attempted before the message was dead-lettered
+pub const DEAD_LETTER_DELIVERIES_HEADER: &str = "Dead-Letter-Deliveries";
+
+/// How a handler settles a message with JetStream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AckAction {
+    /// Processed; the message will not be delivered again
+    Ack,
+    /// Not processed; redeliver, after the given delay if any
+    Nak(Option<Duration>),
+    /// Will never succeed; stop redelivering without counting it as processed
+    Term,
+}
+
+impl From<AckAction> for AckKind {
+    fn from(action: AckAction) -> Self {
+        match action {
+            AckAction::Ack => AckKind::Ack,
+            AckAction::Nak(delay) => AckKind::Nak(delay),
+            AckAction::Term => AckKind::Term,
+        }
+    }
+}
+
+/// A message delivered to an [`EventHandler`]
+///
+/// Cheap to clone; the payload is reference counted.
+#[derive(Clone, Debug)]
+pub struct ConsumerMessage {
+    consumer: Arc<str>,
+    inner: jetstream::Message,
+    delivered: u64,
+    stream_sequence: u64,
+}
+
+impl ConsumerMessage {
+    /// Name of the consumer that delivered the message
+    pub fn consumer(&self) -> &str {
+        &self.consumer
+    }
+
+    /// Subject the message was published to
+    pub fn subject(&self) -> &str {
+        self.inner.subject.as_str()
+    }
+
+    /// Raw payload
+    pub fn payload(&self) -> &[u8] {
+        &self.inner.payload
+    }
+
+    /// NATS headers, if the publisher set any
+    pub fn headers(&self) -> Option<&async_nats::HeaderMap> {
+        self.inner.headers.as_ref()
+    }
+
+    /// Delivery attempt for this message, starting at 1
+    pub fn delivered(&self) -> u64 {
+        self.delivered
+    }
+
+    /// Sequence number of the message within its stream
+    pub fn stream_sequence(&self) -> u64 {
+        self.stream_sequence
+    }
+
+    /// Decode the payload as JSON
+    pub fn json<T: DeserializeOwned>(&self) -> Result<T> {
+        serde_json::from_slice(&self.inner.payload).map_err(|e| {
+            Error::BadRequest(format!(
+                "Failed to decode event on {}: {}",
+                self.subject(),
+                e
+            ))
+        })
+    }
+
+    /// Decode the message as an [`EventEnvelope`], upcasting older schema
+    /// versions
+    pub fn envelope<T: Event>(&self) -> Result<EventEnvelope<T>> {
+        EventEnvelope::decode(self.headers(), self.payload())
+    }
+
+    /// Tell the server the handler is still working, extending the ack
+    /// deadline by another `ack_wait_secs`
+    pub async fn in_progress(&self) -> Result<()> {
+        self.inner
+            .ack_with(AckKind::Progress)
+            .await
+            .map_err(|e| Error::Nats(format!("Failed to extend ack deadline: {}", e)))
+    }
+
+    /// The underlying JetStream message
+    pub fn inner(&self) -> &jetstream::Message {
+        &self.inner
+    }
+}
+
+/// Processes messages delivered to one JetStream consumer
+///
+/// Implemented for any `Fn(ConsumerMessage) -> Future<Output = Result<AckAction>>`,
+/// so plain async closures can be registered directly. Use [`json_handler`]
+/// for handlers that want a decoded payload.
+#[async_trait]
+pub trait EventHandler: Send + Sync + 'static {
+    /// Handle one message and decide how to settle it
+    ///
+    /// Returning `Err` naks the message with the consumer's `nak_delay_secs`.
+    async fn handle(&self, message: ConsumerMessage) -> Result<AckAction>;
+}
+
+#[async_trait]
+impl<F, Fut> EventHandler for F
+where
+    F: Fn(ConsumerMessage) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<AckAction>> + Send + 'static,
+{
+    async fn handle(&self, message: ConsumerMessage) -> Result<AckAction> {
+        (self)(message).await
+    }
+}
+
+/// Handler that decodes each payload as JSON before calling `F`
+///
+/// Built by [`json_handler`].
+pub struct JsonHandler<T, F> {
+    handler: F,
+    _event: PhantomData<fn() -> T>,
+}
+
+/// Wrap an async function taking a decoded event into an [`EventHandler`]
+///
+/// A payload that does not decode as `T` is terminated rather than naked:
+/// redelivering the same bytes cannot change the outcome.
+pub fn json_handler<T, F, Fut>(handler: F) -> JsonHandler<T, F>
+where
+    T: DeserializeOwned + Send + 'static,
+    F: Fn(T, ConsumerMessage) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<AckAction>> + Send + 'static,
+{
+    JsonHandler {
+        handler,
+        _event: PhantomData,
+    }
+}
+
+#[async_trait]
+impl<T, F, Fut> EventHandler for JsonHandler<T, F>
+where
+    T: DeserializeOwned + Send + 'static,
+    F: Fn(T, ConsumerMessage) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<AckAction>> + Send + 'static,
+{
+    async fn handle(&self, message: ConsumerMessage) -> Result<AckAction> {
+        match message.json::<T>() {
+            Ok(event) => (self.handler)(event, message).await,
+            Err(e) => {
+                tracing::warn!(
+                    consumer = message.consumer(),
+                    subject = message.subject(),
+                    stream_sequence = message.stream_sequence(),
+                    "Terminating undecodable event: {}",
+                    e
+                );
+                Ok(AckAction::Term)
+            }
+        }
+    }
+}
+
+/// Handler that decodes each message as an [`EventEnvelope`] before calling `F`
+///
+/// Built by [`envelope_handler`].
+pub struct EnvelopeHandler<T, F> {
+    handler: F,
+    _event: PhantomData<fn() -> T>,
+}
+
+/// Wrap an async function taking a decoded envelope into an [`EventHandler`]
+///
+/// Like [`json_handler`], a message that does not decode (wrong event type,
+/// unknown schema version, failed upcast) is terminated, which sends it to
+/// the consumer's dead-letter subject when one is configured.
+pub fn envelope_handler<T, F, Fut>(handler: F) -> EnvelopeHandler<T, F>
+where
+    T: Event,
+    F: Fn(EventEnvelope<T>, ConsumerMessage) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<AckAction>> + Send + 'static,
+{
+    EnvelopeHandler {
+        handler,
+        _event: PhantomData,
+    }
+}
+
+#[async_trait]
+impl<T, F, Fut> EventHandler for EnvelopeHandler<T, F>
+where
+    T: Event,
+    F: Fn(EventEnvelope<T>, ConsumerMessage) -> Fut + Send + Sync + 'static,
+    Fut: Future<Output = Result<AckAction>> + Send + 'static,
+{
+    async fn handle(&self, message: ConsumerMessage) -> Result<AckAction> {
+        match message.envelope::<T>() {
+            Ok(envelope) => (self.handler)(envelope, message).await,
+            Err(e) => {
+                tracing::warn!(
+                    consumer = message.consumer(),
+                    subject = message.subject(),
+                    stream_sequence = message.stream_sequence(),
+                    "Terminating undecodable event: {}",
+                    e
+                );
+                Ok(AckAction::Term)
+            }
+        }
+    }
+}
+
+/// Handlers registered with `ServiceBuilder`, keyed by consumer name
+#[derive(Clone, Default)]
+pub(crate) struct EventHandlers {
+    handlers: HashMap<String, Arc<dyn EventHandler>>,
+}
+
+impl EventHandlers {
+    pub(crate) fn insert(&mut self, consumer: String, handler: Arc<dyn EventHandler>) {
+        self.handlers.insert(consumer, handler);
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.handlers.is_empty()
+    }
+
+    pub(crate) fn get(&self, consumer: &str) -> Option<Arc<dyn EventHandler>> {
+        self.handlers.get(consumer).cloned()
+    }
+}
+
+impl std::fmt::Debug for EventHandlers {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_set().entries(self.handlers.keys()).finish()
+    }
+}
+
+/// Check that configured consumers and registered handlers pair up
+///
+/// A consumer without a handler would bind a durable consumer nobody reads;
+/// a handler without a consumer would never run. Both are reported at build
+/// time instead of being discovered as silence in production.
+pub(crate) fn validate_consumers(
+    config: Option<&NatsConfig>,
+    handlers: &EventHandlers,
+) -> Result<()> {
+    let Some(config) = config else {
+        if handlers.is_empty() {
+            return Ok(());
+        }
+        return Err(Error::Internal(format!(
+            "event handlers are registered for {:?} but no [nats] section is configured",
+            handlers
+        )));
+    };
+
+    for name in handlers.handlers.keys() {
+        if !config.consumers.contains_key(name) {
+            return Err(Error::Internal(format!(
+                "an event handler is registered for consumer '{}' but [nats.consumers] does \
+                 not declare it",
+                name
+            )));
+        }
+    }
+
+    for (name, consumer) in &config.consumers {
+        if handlers.get(name).is_none() {
+            return Err(Error::Internal(format!(
+                "[nats.consumers.{}] is declared but no event handler is registered for it; \
+                 use ServiceBuilder::with_event_handler",
+                name
+            )));
+        }
+        if consumer.concurrency == 0 {
+            return Err(Error::Internal(format!(
+                "[nats.consumers.{}] concurrency must be at least 1",
+                name
+            )));
+        }
+        if consumer.ack_wait_secs == 0 {
+            return Err(Error::Internal(format!(
+                "[nats.consumers.{}] ack_wait_secs must be positive",
+                name
+            )));
+        }
+        if consumer.max_deliver == 0 || consumer.max_deliver < -1 {
+            return Err(Error::Internal(format!(
+                "[nats.consumers.{}] max_deliver must be positive or -1 for unlimited",
+                name
+            )));
+        }
+        #[cfg(not(any(feature = "cache", feature = "database")))]
+        if consumer.dedup.is_some() {
+            return Err(Error::Internal(format!(
+                "[nats.consumers.{}.dedup] requires the `cache` or `database` feature",
+                name
+            )));
+        }
+        if let Some(subject) = &consumer.dead_letter_subject {
+            if subject.is_empty() {
+                return Err(Error::Internal(format!(
+                    "[nats.consumers.{}] dead_letter_subject must not be empty",
+                    name
+                )));
+            }
+            if consumer.dead_letter_after == 0 && consumer.max_deliver < 0 {
+                return Err(Error::Internal(format!(
+                    "[nats.consumers.{}] dead_letter_after must be set when max_deliver is \
+                     unlimited",
+                    name
+                )));
+            }
+            if consumer.max_deliver > 0
+                && i64::from(consumer.dead_letter_after) > consumer.max_deliver
+            {
+                return Err(Error::Internal(format!(
+                    "[nats.consumers.{}] dead_letter_after ({}) exceeds max_deliver ({}), so \
+                     no message would ever be dead-lettered",
+                    name, consumer.dead_letter_after, consumer.max_deliver
+                )));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Running consumers owned by the NATS pool agent
+///
+/// Clone-able so `before_stop`, which only sees the agent by reference, can
+/// drain them.
+#[derive(Clone, Debug)]
+pub(crate) struct ConsumerSupervisor {
+    cancel: CancellationToken,
+    tracker: TaskTracker,
+}
+
+impl ConsumerSupervisor {
+    /// Declare streams and start one task per configured consumer
+    pub(crate) fn start(
+        client: async_nats::Client,
+        config: &NatsConfig,
+        handlers: &EventHandlers,
+    ) -> Self {
+        let cancel = CancellationToken::new();
+        let tracker = TaskTracker::new();
+
+        let js = jetstream::new(client.clone());
+        let streams = config.streams.clone();
+        let consumers: Vec<_> = config
+            .consumers
+            .iter()
+            .filter_map(|(name, cfg)| {
+                handlers
+                    .get(name)
+                    .map(|handler| (name.clone(), cfg.clone(), handler))
+            })
+            .collect();
+
+        let bootstrap_cancel = cancel.clone();
+        let bootstrap_tracker = tracker.clone();
+        tracker.spawn(async move {
+            // Consumers bind to streams, so every stream must exist first.
+            while let Err(e) = declare_streams(&js, &streams).await {
+                tracing::error!(
+                    "Failed to declare JetStream streams: {}. Retrying in {:?}",
+                    e,
+                    CONSUMER_RETRY_DELAY
+                );
+                tokio::select! {
+                    () = bootstrap_cancel.cancelled() => return,
+                    () = tokio::time::sleep(CONSUMER_RETRY_DELAY) => {}
+                }
+            }
+
+            for (name, cfg, handler) in consumers {
+                // Push consumers without a configured deliver subject get a
+                // private inbox, fixed for the life of this connection.
+                let deliver_subject = cfg
+                    .deliver_subject
+                    .clone()
+                    .unwrap_or_else(|| client.new_inbox());
+                let consumer = RunningConsumer {
+                    js: js.clone(),
+                    name: name.into(),
+                    config: cfg,
+                    deliver_subject,
+                    handler,
+                };
+                let cancel = bootstrap_cancel.clone();
+                bootstrap_tracker.spawn(supervise_consumer(consumer, cancel));
+            }
+        });
+
+        Self { cancel, tracker }
+    }
+
+    /// Stop fetching and wait for in-flight handlers, up to `timeout`
+    pub(crate) async fn drain(&self, timeout: Duration) {
+        self.cancel.cancel();
+        self.tracker.close();
+        if tokio::time::timeout(timeout, self.tracker.wait())
+            .await
+            .is_err()
+        {
+            tracing::warn!(
+                "JetStream consumers did not drain within {:?}; unacknowledged messages \
+                 will be redelivered",
+                timeout
+            );
+        } else {
+            tracing::info!("JetStream consumers drained");
+        }
+    }
+}
+
+async fn declare_streams(js: &jetstream::Context, streams: &[NatsStreamConfig]) -> Result<()> {
+    for declaration in streams {
+        js.get_or_create_stream(stream_config(declaration))
+            .await
+            .map_err(|e| {
+                Error::Nats(format!(
+                    "Failed to declare stream '{}': {}",
+                    declaration.name, e
+                ))
+            })?;
+        tracing::info!(stream = %declaration.name, "JetStream stream declared");
+    }
+    Ok(())
+}
+
+/// Everything one consumer task needs
+struct RunningConsumer {
+    js: jetstream::Context,
+    name: Arc<str>,
+    config: NatsConsumerConfig,
+    deliver_subject: String,
+    handler: Arc<dyn EventHandler>,
+}
+
+/// Keep one consumer running until cancelled, re-binding after failures
+async fn supervise_consumer(consumer: RunningConsumer, cancel: CancellationToken) {
+    loop {
+        match run_consumer(&consumer, &cancel).await {
+            Ok(()) => return,
+            Err(e) => {
+                tracing::error!(
+                    consumer = %consumer.name,
+                    "JetStream consumer stopped: {}. Retrying in {:?}",
+                    e,
+                    CONSUMER_RETRY_DELAY
+                );
+            }
+        }
+        tokio::select! {
+            () = cancel.cancelled() => return,
+            () = tokio::time::sleep(CONSUMER_RETRY_DELAY) => {}
+        }
+    }
+}
+
+/// Bind the consumer and process messages until cancelled
+///
+/// Returns `Ok` only when cancellation ended the loop; a message stream that
+/// ends on its own is an error, so the supervisor re-binds.
+async fn run_consumer(consumer: &RunningConsumer, cancel: &CancellationToken) -> Result<()> {
+    let RunningConsumer {
+        js,
+        name,
+        config,
+        deliver_subject,
+        handler,
+    } = consumer;
+    let stream = js.get_stream(&config.stream).await.map_err(|e| {
+        Error::Nats(format!(
+            "Failed to look up stream '{}': {}",
+            config.stream, e
+        ))
+    })?;
+
+    match config.kind {
+        NatsConsumerKind::Pull => {
+            let consumer: consumer::PullConsumer = stream
+                .get_or_create_consumer(name, pull_config(name, config))
+                .await
+                .map_err(|e| Error::Nats(format!("Failed to bind consumer '{}': {}", name, e)))?;
+            let messages = consumer
+                .messages()
+                .await
+                .map_err(|e| Error::Nats(format!("Failed to start consumer '{}': {}", name, e)))?;
+            tracing::info!(consumer = %name, stream = %config.stream, "JetStream pull consumer started");
+            process(messages, name, config, handler, cancel).await
+        }
+        NatsConsumerKind::Push => {
+            let consumer: consumer::PushConsumer = stream
+                .get_or_create_consumer(name, push_config(name, config, deliver_subject.clone()))
+                .await
+                .map_err(|e| Error::Nats(format!("Failed to bind consumer '{}': {}", name, e)))?;
+            let messages = consumer
+                .messages()
+                .await
+                .map_err(|e| Error::Nats(format!("Failed to start consumer '{}': {}", name, e)))?;
+            tracing::info!(consumer = %name, stream = %config.stream, "JetStream push consumer started");
+            process(messages, name, config, handler, cancel).await
+        }
+    }
+}
+
+async fn process<S, E>(
+    messages: S,
+    name: &Arc<str>,
+    config: &NatsConsumerConfig,
+    handler: &Arc<dyn EventHandler>,
+    cancel: &CancellationToken,
+) -> Result<()>
+where
+    S: Stream<Item = std::result::Result<jetstream::Message, E>>,
+    E: std::fmt::Display,
+{
+    let nak_delay = Duration::from_secs(config.nak_delay_secs);
+    let dead_letter = DeadLetter::from_config(config);
+    let dead_letter = dead_letter.as_ref();
+
+    // `take_until` stops pulling new messages the moment cancellation fires;
+    // `for_each_concurrent` then returns once the handlers already running
+    // have settled their messages. That is the drain.
+    messages
+        .take_until(cancel.clone().cancelled_owned())
+        .for_each_concurrent(config.concurrency, |item| async move {
+            match item {
+                Ok(message) => dispatch(message, name, handler, nak_delay, dead_letter).await,
+                Err(e) => tracing::warn!(consumer = %name, "JetStream delivery error: {}", e),
+            }
+        })
+        .await;
+
+    if cancel.is_cancelled() {
+        Ok(())
+    } else {
+        Err(Error::Nats(format!(
+            "message stream for consumer '{}' ended",
+            name
+        )))
+    }
+}
+
+async fn dispatch(
+    message: jetstream::Message,
+    name: &Arc<str>,
+    handler: &Arc<dyn EventHandler>,
+    nak_delay: Duration,
+    dead_letter: Option<&DeadLetter>,
+) {
+    let (delivered, stream_sequence) = match message.info() {
+        Ok(info) => (
+            u64::try_from(info.delivered).unwrap_or(1),
+            info.stream_sequence,
+        ),
+        Err(_) => (1, 0),
+    };
+    let delivery = ConsumerMessage {
+        consumer: name.clone(),
+        inner: message.clone(),
+        delivered,
+        stream_sequence,
+    };
+
+    let span = tracing::info_span!(
+        "event.handle",
+        consumer = %name,
+        subject = %message.subject,
+        stream_sequence,
+        delivered
+    );
+    // Continue the publisher's trace when the message carries one
+    #[cfg(feature = "observability")]
+    if let Some(trace) = message
+        .headers
+        .as_ref()
+        .and_then(super::envelope::TraceContext::from_headers)
+    {
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+        let _ = span.set_parent(trace.to_context());
+    }
+
+    // A panicking handler must not take the consumer loop down with it; the
+    // message is naked like any other failure and redelivered.
+    let (action, failure) = match AssertUnwindSafe(handler.handle(delivery).instrument(span))
+        .catch_unwind()
+        .await
+    {
+        Ok(Ok(action)) => (action, None),
+        Ok(Err(e)) => {
+            tracing::warn!(
+                consumer = %name,
+                stream_sequence,
+                delivered,
+                "Event handler failed: {}",
+                e
+            );
+            (AckAction::Nak(Some(nak_delay)), Some(e.to_string()))
+        }
+        Err(_) => {
+            tracing::error!(
+                consumer = %name,
+                stream_sequence,
+                delivered,
+                "Event handler panicked"
+            );
+            (
+                AckAction::Nak(Some(nak_delay)),
+                Some("event handler panicked".to_string()),
+            )
+        }
+    };
+
+    let action = match dead_letter {
+        Some(dead_letter) if dead_letter.applies(action, delivered) => {
+            let reason = failure.unwrap_or_else(|| match action {
+                AckAction::Term => "terminated by handler".to_string(),
+                _ => "naked by handler".to_string(),
+            });
+            dead_letter
+                .publish(&message, name, &reason, delivered, stream_sequence)
+                .await
+                .unwrap_or(AckAction::Nak(Some(nak_delay)))
+        }
+        _ => action,
+    };
+
+    if let Err(e) = message.ack_with(action.into()).await {
+        tracing::warn!(consumer = %name, stream_sequence, "Failed to settle message: {}", e);
+    }
+}
+
+/// Where and when a consumer dead-letters messages
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct DeadLetter {
+    subject: String,
+    after: u64,
+}
+
+impl DeadLetter {
+    /// `None` when the consumer has no dead-letter subject, or no delivery
+    /// limit to dead-letter at
+    fn from_config(config: &NatsConsumerConfig) -> Option<Self> {
+        let subject = config.dead_letter_subject.clone()?;
+        let after = if config.dead_letter_after > 0 {
+            u64::from(config.dead_letter_after)
+        } else {
+            u64::try_from(config.max_deliver).ok().filter(|n| *n > 0)?
+        };
+        Some(Self { subject, after })
+    }
+
+    /// Whether a delivery that ended in `action` should be dead-lettered
+    fn applies(&self, action: AckAction, delivered: u64) -> bool {
+        match action {
+            AckAction::Ack => false,
+            AckAction::Term => true,
+            AckAction::Nak(_) => delivered >= self.after,
+        }
+    }
+
+    /// Republish the message to the dead-letter subject
+    ///
+    /// Returns `Term` once the stream has stored the copy, or `None` if the
+    /// republish failed and the original must be redelivered instead.
+    async fn publish(
+        &self,
+        message: &jetstream::Message,
+        consumer: &str,
+        reason: &str,
+        delivered: u64,
+        stream_sequence: u64,
+    ) -> Option<AckAction> {
+        let mut headers = message.headers.clone().unwrap_or_default();
+        // Header values are single-line
+        let reason: String = reason
+            .chars()
+            .map(|c| if c.is_control() { ' ' } else { c })
+            .collect();
+        headers.insert(DEAD_LETTER_REASON_HEADER, reason.as_str());
+        headers.insert(DEAD_LETTER_CONSUMER_HEADER, consumer);
+        headers.insert(DEAD_LETTER_SUBJECT_HEADER, message.subject.as_str());
+        headers.insert(DEAD_LETTER_SEQUENCE_HEADER, stream_sequence.to_string());
+        headers.insert(DEAD_LETTER_DELIVERIES_HEADER, delivered.to_string());
+        // A message dead-lettered twice (the original's Term was lost) is
+        // dropped by the dead-letter stream's duplicate window.
+        if headers.get(async_nats::header::NATS_MESSAGE_ID).is_none() {
+            headers.insert(
+                async_nats::header::NATS_MESSAGE_ID,
+                format!("{}-{}", consumer, stream_sequence),
+            );
+        }
+
+        let result = async {
+            message
+                .context
+                .publish_with_headers(self.subject.clone(), headers, message.payload.clone())
+                .await
+                .map_err(|e| e.to_string())?
+                .await
+                .map_err(|e| e.to_string())
+        }
+        .await;
+
+        match result {
+            Ok(_) => {
+                tracing::warn!(
+                    consumer,
+                    stream_sequence,
+                    delivered,
+                    dead_letter_subject = %self.subject,
+                    "Event dead-lettered: {}",
+                    reason
+                );
+                Some(AckAction::Term)
+            }
+            Err(e) => {
+                tracing::error!(
+                    consumer,
+                    stream_sequence,
+                    delivered,
+                    dead_letter_subject = %self.subject,
+                    "Failed to dead-letter event: {}",
+                    e
+                );
+                None
+            }
+        }
+    }
+}
+
+fn stream_config(declaration: &NatsStreamConfig) -> stream::Config {
+    stream::Config {
+        name: declaration.name.clone(),
+        subjects: declaration.subjects.clone(),
+        storage: match declaration.storage {
+            NatsStreamStorage::File => stream::StorageType::File,
+            NatsStreamStorage::Memory => stream::StorageType::Memory,
+        },
+        retention: match declaration.retention {
+            NatsStreamRetention::Limits => stream::RetentionPolicy::Limits,
+            NatsStreamRetention::Interest => stream::RetentionPolicy::Interest,
+            NatsStreamRetention::WorkQueue => stream::RetentionPolicy::WorkQueue,
+        },
+        max_age: Duration::from_secs(declaration.max_age_secs),
+        max_messages: if declaration.max_messages > 0 {
+            declaration.max_messages
+        } else {
+            -1
+        },
+        max_bytes: if declaration.max_bytes > 0 {
+            declaration.max_bytes
+        } else {
+            -1
+        },
+        num_replicas: declaration.replicas.max(1),
+        duplicate_window: Duration::from_secs(declaration.duplicate_window_secs),
+        ..Default::default()
+    }
+}
+
+fn pull_config(name: &str, config: &NatsConsumerConfig) -> consumer::pull::Config {
+    consumer::pull::Config {
+        durable_name: Some(name.to_string()),
+        ack_policy: consumer::AckPolicy::Explicit,
+        ack_wait: Duration::from_secs(config.ack_wait_secs),
+        max_deliver: config.max_deliver,
+        backoff: backoff(config),
+        max_ack_pending: config.max_ack_pending,
+        filter_subjects: config.filter_subjects.clone(),
+        ..Default::default()
+    }
+}
+
+fn push_config(
+    name: &str,
+    config: &NatsConsumerConfig,
+    deliver_subject: String,
+) -> consumer::push::Config {
+    consumer::push::Config {
+        durable_name: Some(name.to_string()),
+        deliver_subject,
+        deliver_group: config.deliver_group.clone(),
+        ack_policy: consumer::AckPolicy::Explicit,
+        ack_wait: Duration::from_secs(config.ack_wait_secs),
+        max_deliver: config.max_deliver,
+        backoff: backoff(config),
+        max_ack_pending: config.max_ack_pending,
+        filter_subjects: config.filter_subjects.clone(),
+        ..Default::default()
+    }
+}
+
+fn backoff(config: &NatsConsumerConfig) -> Vec<Duration> {
+    config
+        .backoff_secs
+        .iter()
+        .map(|secs| Duration::from_secs(*secs))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn consumer_config() -> NatsConsumerConfig {
+        serde_json::from_value(serde_json::json!({ "stream": "ORDERS" })).unwrap()
+    }
+
+    fn nats_config(consumers: HashMap<String, NatsConsumerConfig>) -> NatsConfig {
+        serde_json::from_value(serde_json::json!({
+            "url": "nats://localhost:4222",
+            "consumers": consumers,
+        }))
+        .unwrap()
+    }
+
+    fn handlers(names: &[&str]) -> EventHandlers {
+        let mut handlers = EventHandlers::default();
+        for name in names {
+            handlers.insert(
+                (*name).to_string(),
+                Arc::new(|_msg: ConsumerMessage| async { Ok(AckAction::Ack) }),
+            );
+        }
+        handlers
+    }
+
+    #[test]
+    fn consumer_defaults_are_durable_pull_with_bounded_redelivery() {
+        let config = consumer_config();
+        assert_eq!(config.kind, NatsConsumerKind::Pull);
+        assert_eq!(config.max_deliver, 5);
+        assert_eq!(config.concurrency, 1);
+
+        let pull = pull_config("projector", &config);
+        assert_eq!(pull.durable_name.as_deref(), Some("projector"));
+        assert_eq!(pull.ack_policy, consumer::AckPolicy::Explicit);
+        assert_eq!(pull.ack_wait, Duration::from_secs(30));
+    }
+
+    #[test]
+    fn backoff_is_carried_into_both_consumer_kinds() {
+        let mut config = consumer_config();
+        config.backoff_secs = vec![1, 10, 60];
+        let expected = vec![
+            Duration::from_secs(1),
+            Duration::from_secs(10),
+            Duration::from_secs(60),
+        ];
+
+        assert_eq!(pull_config("c", &config).backoff, expected);
+        assert_eq!(
+            push_config("c", &config, "inbox".to_string()).backoff,
+            expected
+        );
+    }
+
+    #[test]
+    fn unlimited_stream_limits_map_to_the_server_sentinel() {
+        let declaration: NatsStreamConfig = serde_json::from_value(serde_json::json!({
+            "name": "ORDERS",
+            "subjects": ["orders.>"],
+            "retention": "work_queue",
+        }))
+        .unwrap();
+
+        let config = stream_config(&declaration);
+        assert_eq!(config.max_messages, -1);
+        assert_eq!(config.max_bytes, -1);
+        assert_eq!(config.retention, stream::RetentionPolicy::WorkQueue);
+    }
+
+    #[test]
+    fn ack_actions_map_to_jetstream_acks() {
+        assert!(matches!(AckKind::from(AckAction::Ack), AckKind::Ack));
+        assert!(matches!(AckKind::from(AckAction::Term), AckKind::Term));
+        assert!(matches!(
+            AckKind::from(AckAction::Nak(Some(Duration::from_secs(3)))),
+            AckKind::Nak(Some(d)) if d == Duration::from_secs(3)
+        ));
+    }
+
+    #[test]
+    fn matched_consumers_and_handlers_validate() {
+        let config = nats_config(HashMap::from([(
+            "projector".to_string(),
+            consumer_config(),
+        )]));
+        assert!(validate_consumers(Some(&config), &handlers(&["projector"])).is_ok());
+    }
+
+    #[test]
+    fn a_consumer_without_a_handler_is_rejected() {
+        let config = nats_config(HashMap::from([(
+            "projector".to_string(),
+            consumer_config(),
+        )]));
+        let err = validate_consumers(Some(&config), &handlers(&[])).unwrap_err();
+        assert!(err.to_string().contains("no event handler"));
+    }
+
+    #[test]
+    fn a_handler_without_a_consumer_is_rejected() {
+        let config = nats_config(HashMap::new());
+        let err = validate_consumers(Some(&config), &handlers(&["projector"])).unwrap_err();
+        assert!(err.to_string().contains("does not declare it"));
+
+        assert!(validate_consumers(None, &handlers(&["projector"])).is_err());
+        assert!(validate_consumers(None, &handlers(&[])).is_ok());
+    }
+
+    #[test]
+    fn dead_lettering_defaults_to_the_delivery_limit() {
+        let mut config = consumer_config();
+        assert!(DeadLetter::from_config(&config).is_none());
+
+        config.dead_letter_subject = Some("dlq.orders".to_string());
+        let dead_letter = DeadLetter::from_config(&config).unwrap();
+        assert_eq!(dead_letter.after, 5);
+
+        config.dead_letter_after = 2;
+        assert_eq!(DeadLetter::from_config(&config).unwrap().after, 2);
+    }
+
+    #[test]
+    fn only_exhausted_or_terminated_messages_are_dead_lettered() {
+        let dead_letter = DeadLetter {
+            subject: "dlq.orders".to_string(),
+            after: 3,
+        };
+        let nak = AckAction::Nak(None);
+
+        assert!(!dead_letter.applies(nak, 2));
+        assert!(dead_letter.applies(nak, 3));
+        assert!(dead_letter.applies(AckAction::Term, 1));
+        assert!(!dead_letter.applies(AckAction::Ack, 3));
+    }
+
+    #[test]
+    fn unreachable_dead_letter_thresholds_are_rejected() {
+        let mut consumer = consumer_config();
+        consumer.dead_letter_subject = Some("dlq.orders".to_string());
+        consumer.dead_letter_after = 6;
+        let config = nats_config(HashMap::from([("projector".to_string(), consumer.clone())]));
+        let err = validate_consumers(Some(&config), &handlers(&["projector"])).unwrap_err();
+        assert!(err.to_string().contains("exceeds max_deliver"));
+
+        consumer.dead_letter_after = 0;
+        consumer.max_deliver = -1;
+        let config = nats_config(HashMap::from([("projector".to_string(), consumer)]));
+        let err = validate_consumers(Some(&config), &handlers(&["projector"])).unwrap_err();
+        assert!(err.to_string().contains("dead_letter_after must be set"));
+    }
+
+    #[test]
+    fn zero_concurrency_is_rejected() {
+        let mut consumer = consumer_config();
+        consumer.concurrency = 0;
+        let config = nats_config(HashMap::from([("projector".to_string(), consumer)]));
+        assert!(validate_consumers(Some(&config), &handlers(&["projector"])).is_err());
+    }
+}
@@ -0,0 +1,549 @@
+//! Idempotency guard for JetStream consumers
+//!
+//! JetStream delivers at least once: a handler whose ack is lost, or that
+//! outlives `ack_wait_secs`, sees the message again, and a publisher retrying
+//! after a timeout can store it twice. A consumer with a
+//! `[nats.consumers.<name>.dedup]` section has its handler wrapped so that it
+//! runs at most once per message id:
+//!
+//! 1. The message id is claimed in the dedup store for `lease_secs`.
+//! 2. If it was already processed, the message is acked without running the
+//!    handler and counted in `events.dedup.skipped`.
+//! 3. If another delivery holds the claim, the message is naked to retry
+//!    after `nak_delay_secs`.
+//! 4. Otherwise the handler runs. `Ack` and `Term` record the id as processed
+//!    for `ttl_secs`; `Nak` or an error releases the claim so the redelivery
+//!    runs the handler again.
+//!
+//! The message id is the `Nats-Msg-Id` header (which [`EventEnvelope`] and
+//! the outbox relay set), falling back to the stream sequence. Ids are scoped
+//! to the consumer, so two consumers of one stream each process the message.
+//!
+//! # Feature Dependencies
+//!
+//! - `events` + `cache`: Redis backend ([`RedisDedupStore`])
+//! - `events` + `database`: PostgreSQL backend ([`PgDedupStore`])
+//!
+//! # Example
+//!
+//! ```toml
+//! [nats.consumers.order-projector]
+//! stream = "ORDERS"
+//!
+//! [nats.consumers.order-projector.dedup]
+//! backend = "redis"
+//! ttl_secs = 86400
+//! ```
+//!
+//! [`EventEnvelope`]: super::EventEnvelope
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use tokio::sync::{Mutex, OnceCell};
+
+use super::consumer::{AckAction, ConsumerMessage, EventHandler, EventHandlers};
+use crate::config::{NatsConfig, NatsConsumerConfig, NatsDedupBackend, NatsDedupConfig};
+use crate::error::{Error, Result};
+
+#[cfg(feature = "database")]
+pub mod pg;
+
+#[cfg(feature = "cache")]
+pub mod redis;
+
+#[cfg(feature = "database")]
+pub use pg::PgDedupStore;
+
+#[cfg(feature = "cache")]
+pub use self::redis::RedisDedupStore;
+
+/// How often expired ids are deleted from stores that do not expire them
+const PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Outcome of claiming a message id
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DedupClaim {
+    /// The caller now holds the claim and should run the handler
+    Claimed,
+    /// Another delivery holds an unexpired claim
+    InProgress,
+    /// The message was already processed
+    Processed,
+}
+
+/// Trait for dedup persistence backends
+#[async_trait]
+pub trait DedupStore: Send + Sync {
+    /// Short backend name used in logs
+    fn backend(&self) -> &'static str;
+
+    /// Create tables or indexes the store needs
+    ///
+    /// Uses `IF NOT EXISTS` semantics, so it is safe to call repeatedly.
+    async fn initialize(&self) -> Result<()>;
+
+    /// Claim `key` for processing until `lease` elapses
+    ///
+    /// An expired claim can be taken over, so a crashed handler does not
+    /// block its message forever.
+    async fn claim(&self, key: &str, lease: Duration) -> Result<DedupClaim>;
+
+    /// Record `key` as processed for `ttl`
+    async fn complete(&self, key: &str, ttl: Duration) -> Result<()>;
+
+    /// Drop an in-flight claim so a redelivery can run the handler
+    async fn release(&self, key: &str) -> Result<()>;
+
+    /// Delete expired entries, returning how many were removed
+    ///
+    /// Stores with native expiry return `Ok(0)`.
+    async fn purge_expired(&self) -> Result<u64>;
+}
+
+/// Pools the dedup stores are built from once their agents connect
+#[derive(Clone, Default)]
+pub(crate) struct DedupPools {
+    #[cfg(feature = "cache")]
+    pub(crate) redis: Option<crate::agents::SharedRedisPool>,
+    #[cfg(feature = "database")]
+    pub(crate) db: Option<crate::agents::SharedDbPool>,
+}
+
+impl DedupPools {
+    fn has(&self, backend: NatsDedupBackend) -> bool {
+        match backend {
+            #[cfg(feature = "cache")]
+            NatsDedupBackend::Redis => self.redis.is_some(),
+            #[cfg(feature = "database")]
+            NatsDedupBackend::Postgres => self.db.is_some(),
+            #[allow(unreachable_patterns)]
+            _ => false,
+        }
+    }
+
+    /// Build the store for `backend`, or `None` while its pool is connecting
+    async fn store(&self, backend: NatsDedupBackend) -> Option<Arc<dyn DedupStore>> {
+        match backend {
+            #[cfg(feature = "cache")]
+            NatsDedupBackend::Redis => {
+                let pool = self.redis.as_ref()?.read().await.clone()?;
+                Some(Arc::new(RedisDedupStore::new(pool)))
+            }
+            #[cfg(feature = "database")]
+            NatsDedupBackend::Postgres => {
+                let pool = self.db.as_ref()?.read().await.clone()?;
+                Some(Arc::new(PgDedupStore::new(pool)))
+            }
+            #[allow(unreachable_patterns)]
+            _ => None,
+        }
+    }
+}
+
+/// Pick the dedup backend for a consumer
+///
+/// An explicit backend must have its pool configured; otherwise Redis is
+/// preferred over Postgres.
+fn resolve_backend(
+    consumer: &str,
+    dedup: &NatsDedupConfig,
+    pools: &DedupPools,
+) -> Result<NatsDedupBackend> {
+    match dedup.backend {
+        Some(backend) if pools.has(backend) => Ok(backend),
+        Some(backend) => Err(Error::Internal(format!(
+            "[nats.consumers.{}.dedup] backend {:?} needs {}",
+            consumer,
+            backend,
+            match backend {
+                NatsDedupBackend::Redis => "the `cache` feature and a [redis] section",
+                NatsDedupBackend::Postgres => "the `database` feature and a [database] section",
+            }
+        ))),
+        None => [NatsDedupBackend::Redis, NatsDedupBackend::Postgres]
+            .into_iter()
+            .find(|backend| pools.has(*backend))
+            .ok_or_else(|| {
+                Error::Internal(format!(
+                    "[nats.consumers.{}.dedup] needs a [redis] or [database] section",
+                    consumer
+                ))
+            }),
+    }
+}
+
+/// Wrap the handlers of consumers that opted in to deduplication
+///
+/// Every dedup-enabled consumer is wrapped; the first configuration error is
+/// returned after the others have been processed.
+pub(crate) fn guard_handlers(
+    handlers: &mut EventHandlers,
+    config: Option<&NatsConfig>,
+    pools: &DedupPools,
+) -> Result<()> {
+    let Some(config) = config else {
+        return Ok(());
+    };
+
+    let mut first_error = None;
+    for (name, consumer) in &config.consumers {
+        let Some(dedup) = &consumer.dedup else {
+            continue;
+        };
+        let Some(inner) = handlers.get(name) else {
+            continue;
+        };
+        match resolve_backend(name, dedup, pools) {
+            Ok(backend) => handlers.insert(
+                name.clone(),
+                Arc::new(IdempotentHandler::new(
+                    inner,
+                    consumer,
+                    dedup,
+                    backend,
+                    pools.clone(),
+                )),
+            ),
+            Err(e) => {
+                first_error.get_or_insert(e);
+            }
+        }
+    }
+
+    first_error.map_or(Ok(()), Err)
+}
+
+/// Dedup key for a message, scoped to its consumer
+fn dedup_key(
+    consumer: &str,
+    message_id: Option<&str>,
+    stream: &str,
+    stream_sequence: u64,
+) -> String {
+    match message_id.filter(|id| !id.is_empty()) {
+        Some(id) => format!("{}:{}", consumer, id),
+        None => format!("{}:{}:{}", consumer, stream, stream_sequence),
+    }
+}
+
+/// Releases a claim whose handler never returned
+///
+/// Dropped armed when the handler panics or its future is cancelled, so the
+/// redelivery runs without waiting out the lease. A handler that returns
+/// disarms it and settles the claim itself.
+struct ClaimGuard {
+    store: Option<Arc<dyn DedupStore>>,
+    key: String,
+}
+
+impl ClaimGuard {
+    fn new(store: Arc<dyn DedupStore>, key: String) -> Self {
+        Self {
+            store: Some(store),
+            key,
+        }
+    }
+
+    fn disarm(mut self) {
+        self.store = None;
+    }
+}
+
+impl Drop for ClaimGuard {
+    fn drop(&mut self) {
+        let Some(store) = self.store.take() else {
+            return;
+        };
+        let key = std::mem::take(&mut self.key);
+        match tokio::runtime::Handle::try_current() {
+            Ok(handle) => {
+                handle.spawn(async move {
+                    if let Err(e) = store.release(&key).await {
+                        tracing::warn!(key = %key, "Failed to release event dedup claim: {}", e);
+                    }
+                });
+            }
+            Err(_) => {
+                tracing::warn!(key = %key, "No tokio runtime to release event dedup claim");
+            }
+        }
+    }
+}
+
+/// Handler wrapper that skips already-processed messages
+struct IdempotentHandler {
+    inner: Arc<dyn EventHandler>,
+    backend: NatsDedupBackend,
+    pools: DedupPools,
+    store: OnceCell<Arc<dyn DedupStore>>,
+    stream: String,
+    ttl: Duration,
+    lease: Duration,
+    in_progress_delay: Duration,
+    last_purge: Mutex<Option<Instant>>,
+    #[cfg(feature = "_metrics")]
+    skipped: Option<opentelemetry::metrics::Counter<u64>>,
+}
+
+impl IdempotentHandler {
+    fn new(
+        inner: Arc<dyn EventHandler>,
+        consumer: &NatsConsumerConfig,
+        dedup: &NatsDedupConfig,
+        backend: NatsDedupBackend,
+        pools: DedupPools,
+    ) -> Self {
+        let lease_secs = if dedup.lease_secs > 0 {
+            dedup.lease_secs
+        } else {
+            consumer.ack_wait_secs
+        };
+
+        Self {
+            inner,
+            backend,
+            pools,
+            store: OnceCell::new(),
+            stream: consumer.stream.clone(),
+            ttl: Duration::from_secs(dedup.ttl_secs),
+            lease: Duration::from_secs(lease_secs),
+            in_progress_delay: Duration::from_secs(consumer.nak_delay_secs),
+            last_purge: Mutex::new(None),
+            #[cfg(feature = "_metrics")]
+            skipped: crate::observability::get_meter().map(|meter| {
+                meter
+                    .u64_counter("events.dedup.skipped")
+                    .with_description("Duplicate event deliveries skipped by the idempotency guard")
+                    .build()
+            }),
+        }
+    }
+
+    /// The dedup store, built and initialized once its pool connects
+    async fn store(&self) -> Result<&Arc<dyn DedupStore>> {
+        self.store
+            .get_or_try_init(|| async {
+                let store = self.pools.store(self.backend).await.ok_or_else(|| {
+                    Error::Internal(format!(
+                        "{:?} dedup store is not connected yet",
+                        self.backend
+                    ))
+                })?;
+                store.initialize().await?;
+                Ok(store)
+            })
+            .await
+    }
+
+    async fn purge_if_due(&self, store: &dyn DedupStore) {
+        {
+            let mut last_purge = self.last_purge.lock().await;
+            if last_purge.is_some_and(|at| at.elapsed() < PURGE_INTERVAL) {
+                return;
+            }
+            *last_purge = Some(Instant::now());
+        }
+        match store.purge_expired().await {
+            Ok(0) => {}
+            Ok(purged) => tracing::debug!(purged, "Purged expired event dedup entries"),
+            Err(e) => tracing::warn!("Failed to purge event dedup entries: {}", e),
+        }
+    }
+
+    fn record_skip(&self, message: &ConsumerMessage) {
+        tracing::debug!(
+            consumer = message.consumer(),
+            subject = message.subject(),
+            stream_sequence = message.stream_sequence(),
+            "Skipping already-processed event"
+        );
+        #[cfg(feature = "_metrics")]
+        if let Some(counter) = &self.skipped {
+            counter.add(
+                1,
+                &[opentelemetry::KeyValue::new(
+                    "consumer",
+                    message.consumer().to_string(),
+                )],
+            );
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for IdempotentHandler {
+    async fn handle(&self, message: ConsumerMessage) -> Result<AckAction> {
+        // A store that is not connected yet naks the message, like any
+        // other transient handler failure.
+        let store = self.store().await?;
+        let key = dedup_key(
+            message.consumer(),
+            message
+                .headers()
+                .and_then(|headers| headers.get(async_nats::header::NATS_MESSAGE_ID))
+                .map(|id| id.as_str()),
+            &self.stream,
+            message.stream_sequence(),
+        );
+
+        match store.claim(&key, self.lease).await? {
+            DedupClaim::Claimed => {}
+            DedupClaim::Processed => {
+                self.record_skip(&message);
+                return Ok(AckAction::Ack);
+            }
+            DedupClaim::InProgress => {
+                return Ok(AckAction::Nak(Some(self.in_progress_delay)));
+            }
+        }
+
+        let guard = ClaimGuard::new(Arc::clone(store), key.clone());
+        let result = self.inner.handle(message).await;
+        guard.disarm();
+        match &result {
+            Ok(AckAction::Ack | AckAction::Term) => {
+                // The handler's work is done; failing to record it only risks
+                // a later duplicate, so the message is still settled.
+                if let Err(e) = store.complete(&key, self.ttl).await {
+                    tracing::warn!(key = %key, "Failed to record processed event: {}", e);
+                }
+                self.purge_if_due(store.as_ref()).await;
+            }
+            Ok(AckAction::Nak(_)) | Err(_) => {
+                if let Err(e) = store.release(&key).await {
+                    tracing::warn!(key = %key, "Failed to release event dedup claim: {}", e);
+                }
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn dedup(backend: Option<NatsDedupBackend>) -> NatsDedupConfig {
+        serde_json::from_value(serde_json::json!({ "backend": backend })).unwrap()
+    }
+
+    #[test]
+    fn dedup_defaults_remember_ids_for_a_day() {
+        let config = dedup(None);
+        assert_eq!(config.ttl_secs, 86400);
+        assert_eq!(config.lease_secs, 0);
+    }
+
+    #[test]
+    fn keys_prefer_the_message_id_and_are_scoped_to_the_consumer() {
+        assert_eq!(
+            dedup_key("projector", Some("evt-1"), "ORDERS", 42),
+            "projector:evt-1"
+        );
+        assert_eq!(
+            dedup_key("projector", None, "ORDERS", 42),
+            "projector:ORDERS:42"
+        );
+        assert_eq!(
+            dedup_key("projector", Some(""), "ORDERS", 42),
+            "projector:ORDERS:42"
+        );
+        assert_ne!(
+            dedup_key("projector", Some("evt-1"), "ORDERS", 1),
+            dedup_key("mailer", Some("evt-1"), "ORDERS", 1)
+        );
+    }
+
+    #[test]
+    fn backends_without_a_pool_are_rejected() {
+        let pools = DedupPools::default();
+        assert!(resolve_backend("projector", &dedup(None), &pools).is_err());
+        let err = resolve_backend(
+            "projector",
+            &dedup(Some(NatsDedupBackend::Postgres)),
+            &pools,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("[database]"));
+    }
+
+    /// Records released keys; every other operation is a no-op
+    #[derive(Default)]
+    struct ReleaseLog(std::sync::Mutex<Vec<String>>);
+
+    #[async_trait]
+    impl DedupStore for ReleaseLog {
+        fn backend(&self) -> &'static str {
+            "test"
+        }
+
+        async fn initialize(&self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn claim(&self, _key: &str, _lease: Duration) -> Result<DedupClaim> {
+            Ok(DedupClaim::Claimed)
+        }
+
+        async fn complete(&self, _key: &str, _ttl: Duration) -> Result<()> {
+            Ok(())
+        }
+
+        async fn release(&self, key: &str) -> Result<()> {
+            self.0.lock().unwrap().push(key.to_string());
+            Ok(())
+        }
+
+        async fn purge_expired(&self) -> Result<u64> {
+            Ok(0)
+        }
+    }
+
+    #[tokio::test]
+    async fn claims_are_released_when_the_handler_panics() {
+        let log = Arc::new(ReleaseLog::default());
+        let store: Arc<dyn DedupStore> = log.clone();
+
+        let handler = tokio::spawn(async move {
+            let _guard = ClaimGuard::new(store, "projector:evt-1".to_string());
+            panic!("handler panicked");
+        });
+        assert!(handler.await.unwrap_err().is_panic());
+
+        // The release runs on a spawned task.
+        for _ in 0..100 {
+            if !log.0.lock().unwrap().is_empty() {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert_eq!(*log.0.lock().unwrap(), ["projector:evt-1"]);
+    }
+
+    #[tokio::test]
+    async fn disarmed_guards_leave_the_claim_alone() {
+        let log = Arc::new(ReleaseLog::default());
+        ClaimGuard::new(log.clone(), "projector:evt-1".to_string()).disarm();
+        tokio::task::yield_now().await;
+        assert!(log.0.lock().unwrap().is_empty());
+    }
+
+    #[cfg(feature = "database")]
+    #[test]
+    fn postgres_is_used_when_it_is_the_only_pool() {
+        let pools = DedupPools {
+            #[cfg(feature = "cache")]
+            redis: None,
+            db: Some(Arc::new(tokio::sync::RwLock::new(None))),
+        };
+        assert_eq!(
+            resolve_backend("projector", &dedup(None), &pools).unwrap(),
+            NatsDedupBackend::Postgres
+        );
+        assert!(
+            resolve_backend("projector", &dedup(Some(NatsDedupBackend::Redis)), &pools).is_err()
+        );
+    }
+}
@@ -0,0 +1,164 @@
+//! PostgreSQL dedup storage backend
+//!
+//! Stores claims and processed ids in an `event_dedup` table. Expired rows
+//! are taken over by the next claim and deleted by
+//! [`purge_expired`](DedupStore::purge_expired).
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+
+use super::{DedupClaim, DedupStore};
+use crate::error::{Error, Result};
+
+/// PostgreSQL-backed dedup storage
+pub struct PgDedupStore {
+    pool: PgPool,
+}
+
+impl PgDedupStore {
+    /// Create a new PostgreSQL dedup store
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// 9999-12-31T23:59:59Z, stored for leases and TTLs too long to add to `now`
+const FAR_FUTURE_SECS: i64 = 253_402_300_799;
+
+fn expires_at(now: DateTime<Utc>, after: Duration) -> DateTime<Utc> {
+    chrono::Duration::from_std(after)
+        .ok()
+        .and_then(|after| now.checked_add_signed(after))
+        .or_else(|| DateTime::from_timestamp(FAR_FUTURE_SECS, 0))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+#[async_trait]
+impl DedupStore for PgDedupStore {
+    fn backend(&self) -> &'static str {
+        "postgres"
+    }
+
+    async fn initialize(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS event_dedup (
+                key TEXT PRIMARY KEY,
+                status TEXT NOT NULL CHECK (status IN ('processing', 'done')),
+                expires_at TIMESTAMPTZ NOT NULL
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to create event_dedup table: {}", e)))?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_event_dedup_expires ON event_dedup (expires_at)",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to create event_dedup index: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn claim(&self, key: &str, lease: Duration) -> Result<DedupClaim> {
+        let now = Utc::now();
+
+        // Inserts a new claim, or takes over an expired one; a live row is
+        // left alone and nothing is returned.
+        let claimed: Option<(String,)> = sqlx::query_as(
+            r#"
+            INSERT INTO event_dedup (key, status, expires_at)
+            VALUES ($1, 'processing', $2)
+            ON CONFLICT (key) DO UPDATE
+                SET status = 'processing', expires_at = EXCLUDED.expires_at
+                WHERE event_dedup.expires_at <= $3
+            RETURNING key
+            "#,
+        )
+        .bind(key)
+        .bind(expires_at(now, lease))
+        .bind(now)
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to claim event: {}", e)))?;
+
+        if claimed.is_some() {
+            return Ok(DedupClaim::Claimed);
+        }
+
+        let status: Option<(String,)> =
+            sqlx::query_as("SELECT status FROM event_dedup WHERE key = $1")
+                .bind(key)
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(|e| Error::Internal(format!("Failed to read event claim: {}", e)))?;
+
+        Ok(match status {
+            Some((status,)) if status == "done" => DedupClaim::Processed,
+            _ => DedupClaim::InProgress,
+        })
+    }
+
+    async fn complete(&self, key: &str, ttl: Duration) -> Result<()> {
+        // Upsert, in case the claim expired and was purged mid-handler
+        sqlx::query(
+            r#"
+            INSERT INTO event_dedup (key, status, expires_at)
+            VALUES ($1, 'done', $2)
+            ON CONFLICT (key) DO UPDATE
+                SET status = 'done', expires_at = EXCLUDED.expires_at
+            "#,
+        )
+        .bind(key)
+        .bind(expires_at(Utc::now(), ttl))
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to record processed event: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn release(&self, key: &str) -> Result<()> {
+        sqlx::query("DELETE FROM event_dedup WHERE key = $1 AND status = 'processing'")
+            .bind(key)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to release event claim: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn purge_expired(&self) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM event_dedup WHERE expires_at <= $1")
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to purge event_dedup rows: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn leases_too_long_to_add_fall_back_to_the_far_future() {
+        let now = Utc::now();
+        assert_eq!(
+            expires_at(now, Duration::from_secs(30)),
+            now + chrono::Duration::seconds(30)
+        );
+        assert_eq!(
+            expires_at(now, Duration::MAX).timestamp(),
+            FAR_FUTURE_SECS
+        );
+    }
+}
@@ -0,0 +1,121 @@
+//! Redis dedup storage backend
+//!
+//! Each message id is one key under `events:dedup:`, holding `processing`
+//! while claimed and `done` once handled. Both states expire natively, so
+//! there is nothing to purge.
+
+use std::ops::DerefMut;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use deadpool_redis::Pool as RedisPool;
+
+use super::{DedupClaim, DedupStore};
+use crate::error::{Error, Result};
+
+const KEY_PREFIX: &str = "events:dedup:";
+const PROCESSING: &str = "processing";
+const DONE: &str = "done";
+
+/// Redis-backed dedup storage
+#[derive(Clone)]
+pub struct RedisDedupStore {
+    pool: RedisPool,
+}
+
+impl RedisDedupStore {
+    /// Create a new Redis dedup store
+    pub fn new(pool: RedisPool) -> Self {
+        Self { pool }
+    }
+
+    async fn connection(&self) -> Result<deadpool_redis::Connection> {
+        self.pool
+            .get()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to get Redis connection: {}", e)))
+    }
+}
+
+fn redis_key(key: &str) -> String {
+    format!("{}{}", KEY_PREFIX, key)
+}
+
+fn millis(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis())
+        .unwrap_or(u64::MAX)
+        .max(1)
+}
+
+#[async_trait]
+impl DedupStore for RedisDedupStore {
+    fn backend(&self) -> &'static str {
+        "redis"
+    }
+
+    async fn initialize(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn claim(&self, key: &str, lease: Duration) -> Result<DedupClaim> {
+        let mut conn = self.connection().await?;
+        let key = redis_key(key);
+
+        let claimed: Option<String> = redis::cmd("SET")
+            .arg(&key)
+            .arg(PROCESSING)
+            .arg("NX")
+            .arg("PX")
+            .arg(millis(lease))
+            .query_async(conn.deref_mut())
+            .await?;
+        if claimed.is_some() {
+            return Ok(DedupClaim::Claimed);
+        }
+
+        let state: Option<String> = redis::cmd("GET")
+            .arg(&key)
+            .query_async(conn.deref_mut())
+            .await?;
+
+        // A key that expired between SET and GET is reported in progress;
+        // the redelivery claims it.
+        Ok(match state.as_deref() {
+            Some(DONE) => DedupClaim::Processed,
+            _ => DedupClaim::InProgress,
+        })
+    }
+
+    async fn complete(&self, key: &str, ttl: Duration) -> Result<()> {
+        let mut conn = self.connection().await?;
+
+        let _: () = redis::cmd("SET")
+            .arg(redis_key(key))
+            .arg(DONE)
+            .arg("PX")
+            .arg(millis(ttl))
+            .query_async(conn.deref_mut())
+            .await?;
+
+        Ok(())
+    }
+
+    async fn release(&self, key: &str) -> Result<()> {
+        let mut conn = self.connection().await?;
+
+        // Only drop a claim; a concurrent completion must survive.
+        let _: i64 = redis::cmd("EVAL")
+            .arg("if redis.call('GET', KEYS[1]) == ARGV[1] then return redis.call('DEL', KEYS[1]) end return 0")
+            .arg(1)
+            .arg(redis_key(key))
+            .arg(PROCESSING)
+            .query_async(conn.deref_mut())
+            .await?;
+
+        Ok(())
+    }
+
+    async fn purge_expired(&self) -> Result<u64> {
+        Ok(0)
+    }
+}
@@ -0,0 +1,491 @@
+//! Typed event envelopes
+//!
+//! [`publish_json`](super::publish_json) sends a bare payload. An
+//! [`EventEnvelope`] adds the metadata consumers need to route, version and
+//! correlate events, carried in NATS headers so the payload stays the plain
+//! JSON encoding of the event:
+//!
+//! | Header | Contents |
+//! |--------|----------|
+//! | `Event-Id` | UUIDv7 event id (also sent as `Nats-Msg-Id`) |
+//! | `Event-Type` | [`Event::EVENT_TYPE`] |
+//! | `Event-Schema-Version` | [`Event::SCHEMA_VERSION`] at publish time |
+//! | `Event-Source` | Name of the publishing service |
+//! | `Event-Time` | RFC 3339 publish timestamp |
+//! | `x-request-id` | [`RequestId`] of the request that caused the event |
+//! | `traceparent` / `tracestate` | W3C trace context |
+//!
+//! Decoding checks the event type and upcasts payloads written with an older
+//! schema version through [`Event::upcast`], so handlers only ever see the
+//! current shape.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! use acton_service::events::{envelope_handler, publish_envelope, AckAction, Event, EventEnvelope};
+//!
+//! #[derive(Serialize, Deserialize)]
+//! struct OrderCreated { id: Uuid, total_cents: i64 }
+//!
+//! impl Event for OrderCreated {
+//!     const EVENT_TYPE: &'static str = "orders.created";
+//!     const SCHEMA_VERSION: u32 = 2;
+//!
+//!     fn upcast(from_version: u32, mut data: serde_json::Value) -> Result<serde_json::Value> {
+//!         // v1 carried `total` in whole units
+//!         if from_version == 1 {
+//!             let total = data["total"].as_i64().unwrap_or_default();
+//!             data["total_cents"] = (total * 100).into();
+//!         }
+//!         Ok(data)
+//!     }
+//! }
+//!
+//! let envelope = EventEnvelope::new(&config.service.name, order).with_request_id(request_id);
+//! publish_envelope(&client, "orders.created", &envelope).await?;
+//!
+//! // Consumer side
+//! let handler = envelope_handler(|event: EventEnvelope<OrderCreated>, _msg| async move {
+//!     project(event.data).await?;
+//!     Ok(AckAction::Ack)
+//! });
+//! ```
+
+use std::str::FromStr;
+
+use async_nats::HeaderMap;
+use chrono::{DateTime, Utc};
+use serde::{de::DeserializeOwned, Serialize};
+
+use crate::error::{Error, Result};
+use crate::ids::RequestId;
+
+/// Header carrying the event id
+pub const EVENT_ID_HEADER: &str = "Event-Id";
+/// Header carrying the event type
+pub const EVENT_TYPE_HEADER: &str = "Event-Type";
+/// Header carrying the payload's schema version
+pub const SCHEMA_VERSION_HEADER: &str = "Event-Schema-Version";
+/// Header carrying the publishing service's name
+pub const EVENT_SOURCE_HEADER: &str = "Event-Source";
+/// Header carrying the publish timestamp
+pub const EVENT_TIME_HEADER: &str = "Event-Time";
+/// Header carrying the correlated request id, matching the HTTP header
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+/// W3C trace context parent header
+pub const TRACEPARENT_HEADER: &str = "traceparent";
+/// W3C trace context vendor state header
+pub const TRACESTATE_HEADER: &str = "tracestate";
+
+/// A typed event with a stable name and schema version
+pub trait Event: Serialize + DeserializeOwned + Send + 'static {
+    /// Name identifying the event, e.g. `orders.created`
+    const EVENT_TYPE: &'static str;
+
+    /// Version of the payload shape this type encodes
+    ///
+    /// Bump it whenever the serialized form changes incompatibly, and teach
+    /// [`upcast`](Event::upcast) to convert the previous versions.
+    const SCHEMA_VERSION: u32 = 1;
+
+    /// Convert a payload written with an older schema version to the current one
+    ///
+    /// Called with the version the publisher wrote and its raw JSON; the
+    /// result is deserialized as `Self`. The default rejects every older
+    /// version.
+    fn upcast(from_version: u32, data: serde_json::Value) -> Result<serde_json::Value> {
+        let _ = data;
+        Err(Error::BadRequest(format!(
+            "{} schema version {} cannot be upcast to version {}",
+            Self::EVENT_TYPE,
+            from_version,
+            Self::SCHEMA_VERSION
+        )))
+    }
+}
+
+/// W3C trace context propagated with an event
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TraceContext {
+    /// `traceparent` header value
+    pub traceparent: String,
+    /// `tracestate` header value, if any
+    pub tracestate: Option<String>,
+}
+
+impl TraceContext {
+    /// Trace context of the current tracing span
+    ///
+    /// `None` without the `observability` feature, or when the span is not
+    /// part of a sampled OpenTelemetry trace.
+    pub fn current() -> Option<Self> {
+        #[cfg(feature = "observability")]
+        {
+            use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+            let context = tracing::Span::current().context();
+            let mut carrier = std::collections::HashMap::new();
+            opentelemetry::global::get_text_map_propagator(|propagator| {
+                propagator.inject_context(&context, &mut carrier)
+            });
+            Self::from_carrier(|name| carrier.get(name).cloned())
+        }
+        #[cfg(not(feature = "observability"))]
+        {
+            None
+        }
+    }
+
+    /// Trace context carried in NATS headers
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        Self::from_carrier(|name| headers.get(name).map(|value| value.as_str().to_string()))
+    }
+
+    /// OpenTelemetry context to use as the parent of a consuming span
+    #[cfg(feature = "observability")]
+    pub fn to_context(&self) -> opentelemetry::Context {
+        let mut carrier = std::collections::HashMap::new();
+        carrier.insert(TRACEPARENT_HEADER.to_string(), self.traceparent.clone());
+        if let Some(tracestate) = &self.tracestate {
+            carrier.insert(TRACESTATE_HEADER.to_string(), tracestate.clone());
+        }
+        opentelemetry::global::get_text_map_propagator(|propagator| propagator.extract(&carrier))
+    }
+
+    fn from_carrier(get: impl Fn(&str) -> Option<String>) -> Option<Self> {
+        let traceparent = get(TRACEPARENT_HEADER).filter(|value| !value.is_empty())?;
+        Some(Self {
+            traceparent,
+            tracestate: get(TRACESTATE_HEADER).filter(|value| !value.is_empty()),
+        })
+    }
+}
+
+/// An event with its metadata
+#[derive(Debug, Clone)]
+pub struct EventEnvelope<T> {
+    /// Unique event id (UUIDv7)
+    pub id: uuid::Uuid,
+    /// Event type name
+    pub event_type: String,
+    /// Schema version the payload was written with
+    ///
+    /// Decoded envelopes report the version as published, even after
+    /// upcasting `data` to the current shape.
+    pub schema_version: u32,
+    /// Name of the publishing service
+    pub source: String,
+    /// When the event occurred
+    pub occurred_at: DateTime<Utc>,
+    /// Request that caused the event, for log correlation
+    pub request_id: Option<RequestId>,
+    /// Trace the event was published from
+    pub trace_context: Option<TraceContext>,
+    /// The event itself
+    pub data: T,
+}
+
+impl<T: Event> EventEnvelope<T> {
+    /// Wrap an event published by `source`
+    ///
+    /// Captures the current trace context when the `observability` feature
+    /// is enabled.
+    pub fn new(source: impl Into<String>, data: T) -> Self {
+        Self {
+            id: uuid::Uuid::now_v7(),
+            event_type: T::EVENT_TYPE.to_string(),
+            schema_version: T::SCHEMA_VERSION,
+            source: source.into(),
+            occurred_at: Utc::now(),
+            request_id: None,
+            trace_context: TraceContext::current(),
+            data,
+        }
+    }
+
+    /// Correlate the event with the request that caused it
+    pub fn with_request_id(mut self, request_id: RequestId) -> Self {
+        self.request_id = Some(request_id);
+        self
+    }
+
+    /// Replace the captured trace context
+    pub fn with_trace_context(mut self, trace_context: TraceContext) -> Self {
+        self.trace_context = Some(trace_context);
+        self
+    }
+
+    /// Header name/value pairs describing the envelope
+    pub fn header_pairs(&self) -> Vec<(String, String)> {
+        let mut pairs = vec![
+            (EVENT_ID_HEADER.to_string(), self.id.to_string()),
+            (EVENT_TYPE_HEADER.to_string(), self.event_type.clone()),
+            (
+                SCHEMA_VERSION_HEADER.to_string(),
+                self.schema_version.to_string(),
+            ),
+            (EVENT_SOURCE_HEADER.to_string(), self.source.clone()),
+            (
+                EVENT_TIME_HEADER.to_string(),
+                self.occurred_at
+                    .to_rfc3339_opts(chrono::SecondsFormat::AutoSi, true),
+            ),
+        ];
+        if let Some(request_id) = &self.request_id {
+            pairs.push((REQUEST_ID_HEADER.to_string(), request_id.to_string()));
+        }
+        if let Some(trace) = &self.trace_context {
+            pairs.push((TRACEPARENT_HEADER.to_string(), trace.traceparent.clone()));
+            if let Some(tracestate) = &trace.tracestate {
+                pairs.push((TRACESTATE_HEADER.to_string(), tracestate.clone()));
+            }
+        }
+        pairs
+    }
+
+    /// NATS headers for publishing, including `Nats-Msg-Id` for JetStream
+    /// de-duplication
+    pub fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for (name, value) in self.header_pairs() {
+            headers.insert(name.as_str(), value);
+        }
+        headers.insert(async_nats::header::NATS_MESSAGE_ID, self.id.to_string());
+        headers
+    }
+
+    /// JSON-encoded event payload
+    pub fn payload(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(&self.data)
+            .map_err(|e| Error::Internal(format!("Failed to serialize event: {}", e)))
+    }
+
+    /// Decode an envelope from NATS headers and payload
+    ///
+    /// Fails when the headers are missing or name another event type, or the
+    /// payload is newer than [`Event::SCHEMA_VERSION`]. Older payloads go
+    /// through [`Event::upcast`]. An unparseable `x-request-id` is dropped
+    /// rather than failing the event.
+    pub fn decode(headers: Option<&HeaderMap>, payload: &[u8]) -> Result<Self> {
+        let headers = headers.ok_or_else(|| {
+            Error::BadRequest(format!("{} event has no envelope headers", T::EVENT_TYPE))
+        })?;
+        let header = |name: &str| headers.get(name).map(|value| value.as_str());
+        let required = |name: &str| {
+            header(name).ok_or_else(|| {
+                Error::BadRequest(format!(
+                    "{} event is missing the {} header",
+                    T::EVENT_TYPE,
+                    name
+                ))
+            })
+        };
+
+        let event_type = required(EVENT_TYPE_HEADER)?;
+        if event_type != T::EVENT_TYPE {
+            return Err(Error::BadRequest(format!(
+                "expected event type {}, got {}",
+                T::EVENT_TYPE,
+                event_type
+            )));
+        }
+
+        let schema_version: u32 = required(SCHEMA_VERSION_HEADER)?.parse().map_err(|_| {
+            Error::BadRequest(format!(
+                "{} event has an invalid schema version",
+                T::EVENT_TYPE
+            ))
+        })?;
+        let data = decode_data::<T>(schema_version, payload)?;
+
+        let id = uuid::Uuid::parse_str(required(EVENT_ID_HEADER)?).map_err(|e| {
+            Error::BadRequest(format!("{} event has an invalid id: {}", T::EVENT_TYPE, e))
+        })?;
+        let occurred_at = match header(EVENT_TIME_HEADER) {
+            Some(value) => DateTime::parse_from_rfc3339(value)
+                .map(|dt| dt.with_timezone(&Utc))
+                .map_err(|e| {
+                    Error::BadRequest(format!(
+                        "{} event has an invalid timestamp: {}",
+                        T::EVENT_TYPE,
+                        e
+                    ))
+                })?,
+            None => Utc::now(),
+        };
+
+        Ok(Self {
+            id,
+            event_type: event_type.to_string(),
+            schema_version,
+            source: header(EVENT_SOURCE_HEADER).unwrap_or_default().to_string(),
+            occurred_at,
+            request_id: header(REQUEST_ID_HEADER).and_then(|value| RequestId::from_str(value).ok()),
+            trace_context: TraceContext::from_headers(headers),
+            data,
+        })
+    }
+}
+
+/// Deserialize a payload written with `schema_version`, upcasting if older
+fn decode_data<T: Event>(schema_version: u32, payload: &[u8]) -> Result<T> {
+    let decode_error = |e: serde_json::Error| {
+        Error::BadRequest(format!("Failed to decode {} event: {}", T::EVENT_TYPE, e))
+    };
+
+    if schema_version == T::SCHEMA_VERSION {
+        return serde_json::from_slice(payload).map_err(decode_error);
+    }
+    if schema_version > T::SCHEMA_VERSION {
+        return Err(Error::BadRequest(format!(
+            "{} schema version {} is newer than the supported version {}",
+            T::EVENT_TYPE,
+            schema_version,
+            T::SCHEMA_VERSION
+        )));
+    }
+
+    let raw: serde_json::Value = serde_json::from_slice(payload).map_err(decode_error)?;
+    serde_json::from_value(T::upcast(schema_version, raw)?).map_err(decode_error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct OrderCreated {
+        id: u32,
+        total_cents: i64,
+    }
+
+    impl Event for OrderCreated {
+        const EVENT_TYPE: &'static str = "orders.created";
+        const SCHEMA_VERSION: u32 = 2;
+
+        fn upcast(from_version: u32, mut data: serde_json::Value) -> Result<serde_json::Value> {
+            match from_version {
+                1 => {
+                    let total = data["total"].as_i64().unwrap_or_default();
+                    data["total_cents"] = (total * 100).into();
+                    Ok(data)
+                }
+                _ => Err(Error::BadRequest("unknown version".to_string())),
+            }
+        }
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct OrderShipped {
+        id: u32,
+    }
+
+    impl Event for OrderShipped {
+        const EVENT_TYPE: &'static str = "orders.shipped";
+    }
+
+    fn order() -> OrderCreated {
+        OrderCreated {
+            id: 7,
+            total_cents: 1250,
+        }
+    }
+
+    fn v1_headers() -> HeaderMap {
+        let mut headers = EventEnvelope::new("orders", order()).headers();
+        headers.insert(SCHEMA_VERSION_HEADER, "1");
+        headers
+    }
+
+    #[test]
+    fn envelopes_round_trip_through_headers() {
+        let request_id = RequestId::new();
+        let envelope = EventEnvelope::new("orders", order())
+            .with_request_id(request_id.clone())
+            .with_trace_context(TraceContext {
+                traceparent: "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01".to_string(),
+                tracestate: Some("vendor=1".to_string()),
+            });
+
+        let headers = envelope.headers();
+        assert_eq!(
+            headers
+                .get(async_nats::header::NATS_MESSAGE_ID)
+                .map(|v| v.as_str().to_string()),
+            Some(envelope.id.to_string())
+        );
+
+        let decoded =
+            EventEnvelope::<OrderCreated>::decode(Some(&headers), &envelope.payload().unwrap())
+                .unwrap();
+        assert_eq!(decoded.id, envelope.id);
+        assert_eq!(decoded.event_type, "orders.created");
+        assert_eq!(decoded.schema_version, 2);
+        assert_eq!(decoded.source, "orders");
+        assert_eq!(decoded.occurred_at, envelope.occurred_at);
+        assert_eq!(decoded.request_id, Some(request_id));
+        assert_eq!(decoded.trace_context, envelope.trace_context);
+        assert_eq!(decoded.data, order());
+    }
+
+    #[test]
+    fn older_schema_versions_are_upcast() {
+        let payload = br#"{"id":7,"total":12}"#;
+        let decoded = EventEnvelope::<OrderCreated>::decode(Some(&v1_headers()), payload).unwrap();
+
+        assert_eq!(decoded.schema_version, 1);
+        assert_eq!(
+            decoded.data,
+            OrderCreated {
+                id: 7,
+                total_cents: 1200
+            }
+        );
+    }
+
+    #[test]
+    fn newer_schema_versions_are_rejected() {
+        let mut headers = EventEnvelope::new("orders", order()).headers();
+        headers.insert(SCHEMA_VERSION_HEADER, "3");
+
+        let err = EventEnvelope::<OrderCreated>::decode(Some(&headers), b"{}").unwrap_err();
+        assert!(err.to_string().contains("newer than the supported version"));
+    }
+
+    #[test]
+    fn older_versions_without_an_upcaster_are_rejected() {
+        let mut headers = EventEnvelope::new("orders", OrderShipped { id: 1 }).headers();
+        headers.insert(SCHEMA_VERSION_HEADER, "0");
+
+        let err =
+            EventEnvelope::<OrderShipped>::decode(Some(&headers), br#"{"id":1}"#).unwrap_err();
+        assert!(err.to_string().contains("cannot be upcast"));
+    }
+
+    #[test]
+    fn mismatched_or_missing_metadata_is_rejected() {
+        let headers = EventEnvelope::new("orders", OrderShipped { id: 1 }).headers();
+        let err =
+            EventEnvelope::<OrderCreated>::decode(Some(&headers), br#"{"id":1}"#).unwrap_err();
+        assert!(err
+            .to_string()
+            .contains("expected event type orders.created"));
+
+        assert!(EventEnvelope::<OrderCreated>::decode(None, b"{}").is_err());
+        assert!(EventEnvelope::<OrderCreated>::decode(Some(&HeaderMap::new()), b"{}").is_err());
+    }
+
+    #[test]
+    fn foreign_request_ids_are_dropped() {
+        let mut headers = EventEnvelope::new("orders", order()).headers();
+        headers.insert(REQUEST_ID_HEADER, "not-a-typeid");
+
+        let decoded = EventEnvelope::<OrderCreated>::decode(
+            Some(&headers),
+            br#"{"id":7,"total_cents":1250}"#,
+        )
+        .unwrap();
+        assert!(decoded.request_id.is_none());
+    }
+}
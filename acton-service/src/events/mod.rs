@@ -1,4 +1,26 @@
 //! NATS JetStream client management
+//!
+//! Besides the connection helpers here, this module runs durable JetStream
+//! consumers declared in `[nats]` configuration, wraps events in typed
+//! [`envelope`]s, and with a database backend enabled provides a
+//! transactional [`outbox`] and an idempotency guard for consumers
+//! ([`dedup`], also available with Redis).
+
+mod consumer;
+#[cfg(any(feature = "cache", feature = "database"))]
+pub mod dedup;
+pub mod envelope;
+
+#[cfg(any(feature = "database", feature = "turso", feature = "surrealdb"))]
+pub mod outbox;
+
+pub use consumer::{
+    envelope_handler, json_handler, AckAction, ConsumerMessage, EnvelopeHandler, EventHandler,
+    JsonHandler, DEAD_LETTER_CONSUMER_HEADER, DEAD_LETTER_DELIVERIES_HEADER,
+    DEAD_LETTER_REASON_HEADER, DEAD_LETTER_SEQUENCE_HEADER, DEAD_LETTER_SUBJECT_HEADER,
+};
+pub(crate) use consumer::{validate_consumers, ConsumerSupervisor, EventHandlers};
+pub use envelope::{Event, EventEnvelope, TraceContext};
 
 #[cfg(feature = "events")]
 use async_nats::Client;
@@ -127,6 +149,28 @@ pub async fn publish_json<T: serde::Serialize>(
     publish_event(client, subject, json).await
 }
 
+/// Publish an [`EventEnvelope`] to NATS
+///
+/// Metadata travels in headers (see [`envelope`]); the payload is the JSON
+/// encoding of the event.
+#[cfg(feature = "events")]
+pub async fn publish_envelope<T: Event>(
+    client: &Client,
+    subject: &str,
+    envelope: &EventEnvelope<T>,
+) -> Result<()> {
+    client
+        .publish_with_headers(
+            subject.to_string(),
+            envelope.headers(),
+            envelope.payload()?.into(),
+        )
+        .await
+        .map_err(|e| Error::Nats(format!("Failed to publish to {}: {}", subject, e)))?;
+
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -141,6 +185,10 @@ mod tests {
             retry_delay_secs: 2,
             optional: false,
             lazy_init: true,
+            streams: Vec::new(),
+            consumers: std::collections::HashMap::new(),
+            drain_timeout_secs: 30,
+            outbox: None,
         };
 
         assert_eq!(config.max_reconnects, 10);
@@ -0,0 +1,312 @@
+//! Transactional outbox for event publishing
+//!
+//! Writing to the database and then publishing to NATS is two operations: a
+//! crash between them either loses the event or publishes one for a change
+//! that was rolled back. The outbox makes the event part of the database
+//! transaction instead. The handler enqueues an [`OutboxEvent`] with the same
+//! transaction that writes its data, and a relay on the
+//! [`BackgroundWorker`](crate::agents::BackgroundWorker) publishes committed
+//! rows to NATS, retrying until they are accepted and marking them sent.
+//!
+//! Delivery is at-least-once. With `jetstream = true` (the default) the event
+//! id travels as `Nats-Msg-Id`, so a row republished after a crash is dropped
+//! by the stream's duplicate window.
+//!
+//! # Feature Dependencies
+//!
+//! - `events` + `database`: PostgreSQL backend ([`PgOutboxStorage`])
+//! - `events` + `turso`: Turso/libsql backend ([`TursoOutboxStorage`])
+//! - `events` + `surrealdb`: SurrealDB backend ([`SurrealOutboxStorage`])
+//!
+//! # Architecture
+//!
+//! ```text
+//! handler ──tx──> event_outbox ──relay (BackgroundWorker)──> NATS
+//!                     │
+//!                     └── backlog / oldest pending age ──> PoolHealthSummary
+//! ```
+//!
+//! The relay creates the `event_outbox` table as soon as the database pool
+//! connects, then polls it every `poll_interval_ms`. Rows are claimed with a
+//! lease, so several replicas can relay from one table without publishing the
+//! same row concurrently.
+//!
+//! # Quick Start
+//!
+//! ```toml
+//! [nats]
+//! url = "nats://localhost:4222"
+//!
+//! [nats.outbox]
+//!
+//! [background_worker]
+//! enabled = true
+//! ```
+//!
+//! ```rust,ignore
+//! use acton_service::events::outbox::{OutboxEvent, PgOutboxStorage};
+//!
+//! let mut tx = pool.begin().await?;
+//! sqlx::query("INSERT INTO orders (id, total) VALUES ($1, $2)")
+//!     .bind(order.id)
+//!     .bind(order.total)
+//!     .execute(&mut *tx)
+//!     .await?;
+//! PgOutboxStorage::enqueue(&mut tx, &OutboxEvent::json("orders.created", &order)?).await?;
+//! tx.commit().await?;
+//! ```
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use super::{Event, EventEnvelope};
+use crate::error::{Error, Result};
+
+mod relay;
+
+#[cfg(feature = "database")]
+pub mod pg;
+
+#[cfg(feature = "turso")]
+pub mod turso;
+
+#[cfg(feature = "surrealdb")]
+pub mod surrealdb_impl;
+
+#[cfg(feature = "database")]
+pub use pg::PgOutboxStorage;
+
+#[cfg(feature = "turso")]
+pub use turso::TursoOutboxStorage;
+
+#[cfg(feature = "surrealdb")]
+pub use surrealdb_impl::SurrealOutboxStorage;
+
+pub(crate) use relay::{outbox_health, run_relay};
+
+/// An event to publish once the surrounding transaction commits
+#[derive(Debug, Clone)]
+pub struct OutboxEvent {
+    /// Unique id, sent as `Nats-Msg-Id` for JetStream de-duplication
+    pub id: uuid::Uuid,
+    /// Subject to publish to
+    pub subject: String,
+    /// Message payload
+    pub payload: Vec<u8>,
+    /// Additional NATS headers
+    pub headers: Vec<(String, String)>,
+    /// When the event was enqueued
+    pub created_at: DateTime<Utc>,
+}
+
+impl OutboxEvent {
+    /// Create an event with a raw payload
+    pub fn new(subject: impl Into<String>, payload: impl Into<Vec<u8>>) -> Self {
+        Self {
+            id: uuid::Uuid::now_v7(),
+            subject: subject.into(),
+            payload: payload.into(),
+            headers: Vec::new(),
+            created_at: Utc::now(),
+        }
+    }
+
+    /// Create an event with a JSON-encoded payload
+    pub fn json<T: Serialize>(subject: impl Into<String>, data: &T) -> Result<Self> {
+        let payload = serde_json::to_vec(data)
+            .map_err(|e| Error::Internal(format!("Failed to serialize outbox event: {}", e)))?;
+        Ok(Self::new(subject, payload))
+    }
+
+    /// Create an event from an envelope
+    ///
+    /// The envelope's id becomes the outbox id, so the `Nats-Msg-Id` the relay
+    /// sends matches the envelope's `Event-Id`.
+    pub fn from_envelope<T: Event>(
+        subject: impl Into<String>,
+        envelope: &EventEnvelope<T>,
+    ) -> Result<Self> {
+        Ok(Self {
+            id: envelope.id,
+            subject: subject.into(),
+            payload: envelope.payload()?,
+            headers: envelope.header_pairs(),
+            created_at: envelope.occurred_at,
+        })
+    }
+
+    /// Add a NATS header to publish with the event
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Headers encoded for storage
+    pub(crate) fn headers_json(&self) -> Result<String> {
+        serde_json::to_string(&self.headers)
+            .map_err(|e| Error::Internal(format!("Failed to serialize outbox headers: {}", e)))
+    }
+}
+
+/// An outbox row claimed by the relay for publishing
+#[derive(Debug, Clone)]
+pub struct OutboxRecord {
+    /// Event id
+    pub id: String,
+    /// Subject to publish to
+    pub subject: String,
+    /// Message payload
+    pub payload: Vec<u8>,
+    /// Additional NATS headers
+    pub headers: Vec<(String, String)>,
+    /// Publish attempts that have already failed
+    pub attempts: u32,
+    /// When the event was enqueued
+    pub created_at: DateTime<Utc>,
+}
+
+/// Size of the unpublished backlog
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct OutboxBacklog {
+    /// Rows waiting to be published, including those awaiting a retry
+    pub pending: u64,
+    /// Rows that exhausted `max_attempts` and will not be retried
+    pub failed: u64,
+    /// Enqueue time of the oldest pending row
+    pub oldest_pending: Option<DateTime<Utc>>,
+}
+
+/// Trait for outbox persistence backends
+///
+/// Covers the relay's side of the outbox. Enqueueing happens inside the
+/// caller's transaction, whose type differs per backend, so each
+/// implementation provides its own `enqueue` associated function instead.
+#[async_trait]
+pub trait OutboxStorage: Send + Sync {
+    /// Short backend name reported in health output
+    fn backend(&self) -> &'static str;
+
+    /// Create the outbox table and indexes
+    ///
+    /// Uses `IF NOT EXISTS` semantics, so it is safe to call repeatedly.
+    async fn initialize(&self) -> Result<()>;
+
+    /// Claim up to `limit` pending rows that are due at `now`
+    ///
+    /// Claimed rows are hidden from other claims until `lease_until`, so
+    /// concurrent relays do not publish the same row. Returned rows are
+    /// ordered oldest first.
+    async fn claim_due(
+        &self,
+        now: DateTime<Utc>,
+        lease_until: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<OutboxRecord>>;
+
+    /// Mark a row as published
+    async fn mark_sent(&self, id: &str, sent_at: DateTime<Utc>) -> Result<()>;
+
+    /// Record a failed publish attempt
+    ///
+    /// With `retry_at` set the row becomes due again at that time; with
+    /// `None` it is marked failed and no longer retried.
+    async fn mark_failed(
+        &self,
+        id: &str,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<()>;
+
+    /// Count pending and failed rows and find the oldest pending one
+    async fn backlog(&self) -> Result<OutboxBacklog>;
+
+    /// Delete rows sent before `before`
+    ///
+    /// Returns the number of rows deleted.
+    async fn purge_sent(&self, before: DateTime<Utc>) -> Result<u64>;
+}
+
+/// Timestamp format stored by the text-timestamp backends
+///
+/// Fixed precision and a `Z` suffix keep lexical order equal to time order,
+/// which the due-row comparisons rely on.
+#[cfg(any(feature = "turso", feature = "surrealdb"))]
+pub(crate) fn format_timestamp(ts: DateTime<Utc>) -> String {
+    ts.to_rfc3339_opts(chrono::SecondsFormat::Micros, true)
+}
+
+#[cfg(any(feature = "turso", feature = "surrealdb"))]
+pub(crate) fn parse_timestamp(value: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.with_timezone(&Utc))
+        .map_err(|e| Error::Internal(format!("Failed to parse outbox timestamp: {}", e)))
+}
+
+pub(crate) fn parse_headers(value: &str) -> Result<Vec<(String, String)>> {
+    if value.is_empty() {
+        return Ok(Vec::new());
+    }
+    serde_json::from_str(value)
+        .map_err(|e| Error::Internal(format!("Failed to parse outbox headers: {}", e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn json_events_get_time_ordered_ids() {
+        let first = OutboxEvent::json("orders.created", &serde_json::json!({"id": 1})).unwrap();
+        let second = OutboxEvent::json("orders.created", &serde_json::json!({"id": 2})).unwrap();
+
+        assert_eq!(first.payload, br#"{"id":1}"#);
+        assert!(first.id < second.id);
+    }
+
+    #[test]
+    fn headers_round_trip_through_storage_encoding() {
+        let event = OutboxEvent::new("orders.created", b"{}".to_vec())
+            .with_header("Tenant", "acme")
+            .with_header("Trace", "abc");
+
+        let stored = event.headers_json().unwrap();
+        assert_eq!(parse_headers(&stored).unwrap(), event.headers);
+        assert!(parse_headers("").unwrap().is_empty());
+    }
+
+    #[test]
+    fn envelope_events_keep_the_envelope_id_and_headers() {
+        #[derive(serde::Serialize, serde::Deserialize)]
+        struct OrderCreated {
+            id: u32,
+        }
+
+        impl Event for OrderCreated {
+            const EVENT_TYPE: &'static str = "orders.created";
+        }
+
+        let envelope = EventEnvelope::new("orders", OrderCreated { id: 1 });
+        let event = OutboxEvent::from_envelope("orders.created", &envelope).unwrap();
+
+        assert_eq!(event.id, envelope.id);
+        assert_eq!(event.payload, br#"{"id":1}"#);
+        assert!(event
+            .headers
+            .contains(&("Event-Type".to_string(), "orders.created".to_string())));
+    }
+
+    #[cfg(any(feature = "turso", feature = "surrealdb"))]
+    #[test]
+    fn stored_timestamps_sort_lexically() {
+        let earlier = DateTime::parse_from_rfc3339("2026-01-01T00:00:00.5Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let later = DateTime::parse_from_rfc3339("2026-01-01T00:00:01Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        assert!(format_timestamp(earlier) < format_timestamp(later));
+        assert_eq!(parse_timestamp(&format_timestamp(later)).unwrap(), later);
+    }
+}
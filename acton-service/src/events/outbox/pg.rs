@@ -0,0 +1,222 @@
+//! PostgreSQL outbox storage backend
+//!
+//! Stores events in an `event_outbox` table. Claims use
+//! `FOR UPDATE SKIP LOCKED`, so relays in several replicas never block on, or
+//! double-claim, each other's rows.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{PgConnection, PgPool};
+
+use super::{parse_headers, OutboxBacklog, OutboxEvent, OutboxRecord, OutboxStorage};
+use crate::error::{Error, Result};
+
+/// PostgreSQL-backed outbox storage
+pub struct PgOutboxStorage {
+    pool: PgPool,
+}
+
+impl PgOutboxStorage {
+    /// Create a new PostgreSQL outbox storage
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Enqueue an event on the caller's connection
+    ///
+    /// Pass the transaction that writes the event's data (`&mut tx`); the row
+    /// becomes visible to the relay only if that transaction commits.
+    pub async fn enqueue(conn: &mut PgConnection, event: &OutboxEvent) -> Result<()> {
+        sqlx::query(
+            "INSERT INTO event_outbox \
+             (id, subject, payload, headers, created_at, next_attempt_at) \
+             VALUES ($1, $2, $3, $4, $5, $5)",
+        )
+        .bind(event.id.to_string())
+        .bind(&event.subject)
+        .bind(&event.payload)
+        .bind(event.headers_json()?)
+        .bind(event.created_at)
+        .execute(conn)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to enqueue outbox event: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl OutboxStorage for PgOutboxStorage {
+    fn backend(&self) -> &'static str {
+        "postgres"
+    }
+
+    async fn initialize(&self) -> Result<()> {
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS event_outbox (
+                id TEXT PRIMARY KEY,
+                subject TEXT NOT NULL,
+                payload BYTEA NOT NULL,
+                headers TEXT NOT NULL DEFAULT '[]',
+                status TEXT NOT NULL DEFAULT 'pending'
+                    CHECK (status IN ('pending', 'sent', 'failed')),
+                attempts INTEGER NOT NULL DEFAULT 0,
+                last_error TEXT,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                sent_at TIMESTAMPTZ
+            )
+            "#,
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to create event_outbox table: {}", e)))?;
+
+        sqlx::query(
+            "CREATE INDEX IF NOT EXISTS idx_event_outbox_due \
+             ON event_outbox (status, next_attempt_at)",
+        )
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to create event_outbox due index: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn claim_due(
+        &self,
+        now: DateTime<Utc>,
+        lease_until: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<OutboxRecord>> {
+        let mut rows = sqlx::query_as::<_, OutboxRow>(
+            r#"
+            UPDATE event_outbox SET next_attempt_at = $2
+            WHERE id IN (
+                SELECT id FROM event_outbox
+                WHERE status = 'pending' AND next_attempt_at <= $1
+                ORDER BY created_at
+                LIMIT $3
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, subject, payload, headers, attempts, created_at
+            "#,
+        )
+        .bind(now)
+        .bind(lease_until)
+        .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to claim outbox rows: {}", e)))?;
+
+        // RETURNING does not preserve the subquery's order
+        rows.sort_by_key(|row| row.created_at);
+        rows.into_iter().map(TryInto::try_into).collect()
+    }
+
+    async fn mark_sent(&self, id: &str, sent_at: DateTime<Utc>) -> Result<()> {
+        sqlx::query(
+            "UPDATE event_outbox SET status = 'sent', sent_at = $2, last_error = NULL \
+             WHERE id = $1",
+        )
+        .bind(id)
+        .bind(sent_at)
+        .execute(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to mark outbox row sent: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn mark_failed(
+        &self,
+        id: &str,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        let result = match retry_at {
+            Some(retry_at) => {
+                sqlx::query(
+                    "UPDATE event_outbox \
+                     SET attempts = attempts + 1, last_error = $2, next_attempt_at = $3 \
+                     WHERE id = $1",
+                )
+                .bind(id)
+                .bind(error)
+                .bind(retry_at)
+                .execute(&self.pool)
+                .await
+            }
+            None => {
+                sqlx::query(
+                    "UPDATE event_outbox \
+                     SET attempts = attempts + 1, last_error = $2, status = 'failed' \
+                     WHERE id = $1",
+                )
+                .bind(id)
+                .bind(error)
+                .execute(&self.pool)
+                .await
+            }
+        };
+
+        result.map_err(|e| Error::Internal(format!("Failed to record outbox failure: {}", e)))?;
+        Ok(())
+    }
+
+    async fn backlog(&self) -> Result<OutboxBacklog> {
+        let (pending, failed, oldest_pending): (i64, i64, Option<DateTime<Utc>>) = sqlx::query_as(
+            "SELECT \
+                 COUNT(*) FILTER (WHERE status = 'pending'), \
+                 COUNT(*) FILTER (WHERE status = 'failed'), \
+                 MIN(created_at) FILTER (WHERE status = 'pending') \
+             FROM event_outbox WHERE status <> 'sent'",
+        )
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to read outbox backlog: {}", e)))?;
+
+        Ok(OutboxBacklog {
+            pending: pending.max(0) as u64,
+            failed: failed.max(0) as u64,
+            oldest_pending,
+        })
+    }
+
+    async fn purge_sent(&self, before: DateTime<Utc>) -> Result<u64> {
+        let result = sqlx::query("DELETE FROM event_outbox WHERE status = 'sent' AND sent_at < $1")
+            .bind(before)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to purge sent outbox rows: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// Internal row type for sqlx mapping
+#[derive(sqlx::FromRow)]
+struct OutboxRow {
+    id: String,
+    subject: String,
+    payload: Vec<u8>,
+    headers: String,
+    attempts: i32,
+    created_at: DateTime<Utc>,
+}
+
+impl TryFrom<OutboxRow> for OutboxRecord {
+    type Error = Error;
+
+    fn try_from(row: OutboxRow) -> Result<Self> {
+        Ok(OutboxRecord {
+            headers: parse_headers(&row.headers)?,
+            id: row.id,
+            subject: row.subject,
+            payload: row.payload,
+            attempts: u32::try_from(row.attempts).unwrap_or(0),
+            created_at: row.created_at,
+        })
+    }
+}
@@ -0,0 +1,316 @@
+//! Outbox relay run on the background worker
+//!
+//! Polls the outbox for due rows, publishes them to NATS, and records the
+//! outcome. Storage is resolved from the connected database pool on each
+//! poll until one is available, so the relay starts cleanly even when the
+//! pool agents connect after `ServiceBuilder::build()` returns.
+
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_nats::jetstream;
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{de::DeserializeOwned, Serialize};
+
+use super::{OutboxRecord, OutboxStorage};
+use crate::config::OutboxConfig;
+use crate::pool_health::OutboxHealth;
+use crate::state::AppState;
+
+/// How often sent rows past their retention are deleted
+const PURGE_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Relay loop submitted to the background worker
+///
+/// Runs until the worker cancels it on shutdown. Database and NATS outages
+/// are waited out rather than returned, so the task never ends on its own.
+pub(crate) async fn run_relay<T>(state: AppState<T>, config: OutboxConfig) -> anyhow::Result<()>
+where
+    T: Serialize + DeserializeOwned + Clone + Default + Send + Sync + 'static,
+{
+    let mut ticker = tokio::time::interval(Duration::from_millis(config.poll_interval_ms.max(1)));
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    let mut storage: Option<Arc<dyn OutboxStorage>> = None;
+    let mut last_purge: Option<Instant> = None;
+
+    loop {
+        ticker.tick().await;
+
+        if storage.is_none() {
+            let Some(candidate) = resolve_storage(&state).await else {
+                continue;
+            };
+            // The table is created before the first claim, so enqueueing
+            // handlers only race the relay until the pool first connects.
+            if let Err(e) = candidate.initialize().await {
+                tracing::warn!("Failed to initialize event outbox: {}", e);
+                continue;
+            }
+            tracing::info!(backend = candidate.backend(), "Event outbox relay started");
+            storage = Some(candidate);
+        }
+        let Some(store) = storage.as_deref() else {
+            continue;
+        };
+
+        let Some(client) = state.nats().await else {
+            continue;
+        };
+
+        // Keep draining while full batches come back, so a backlog clears at
+        // publish speed rather than one batch per poll.
+        loop {
+            match relay_batch(store, &client, &config).await {
+                Ok(claimed) if claimed > 0 && claimed >= config.batch_size => continue,
+                Ok(_) => break,
+                Err(e) => {
+                    tracing::warn!("Event outbox relay failed: {}", e);
+                    break;
+                }
+            }
+        }
+
+        if config.sent_retention_secs > 0
+            && last_purge.is_none_or(|at| at.elapsed() >= PURGE_INTERVAL)
+        {
+            last_purge = Some(Instant::now());
+            let before = Utc::now()
+                .checked_sub_signed(secs(config.sent_retention_secs))
+                .unwrap_or(DateTime::<Utc>::MIN_UTC);
+            match store.purge_sent(before).await {
+                Ok(0) => {}
+                Ok(purged) => tracing::debug!(purged, "Purged sent outbox rows"),
+                Err(e) => tracing::warn!("Failed to purge sent outbox rows: {}", e),
+            }
+        }
+    }
+}
+
+/// Claim one batch and publish it
+///
+/// Returns how many rows were claimed. A publish failure stops the batch:
+/// NATS is most likely unavailable, and the rest of the batch is retried once
+/// its lease expires.
+async fn relay_batch(
+    store: &dyn OutboxStorage,
+    client: &async_nats::Client,
+    config: &OutboxConfig,
+) -> crate::error::Result<usize> {
+    let now = Utc::now();
+    let lease_until = later(now, config.lease_secs);
+    let records = store.claim_due(now, lease_until, config.batch_size).await?;
+    let js = config.jetstream.then(|| jetstream::new(client.clone()));
+
+    for record in &records {
+        match publish(client, js.as_ref(), record).await {
+            Ok(()) => store.mark_sent(&record.id, Utc::now()).await?,
+            Err(error) => {
+                let attempts = record.attempts + 1;
+                let retry_at = next_retry(attempts, config, Utc::now());
+                if retry_at.is_none() {
+                    tracing::error!(
+                        id = %record.id,
+                        subject = %record.subject,
+                        attempts,
+                        "Giving up on outbox event: {}",
+                        error
+                    );
+                } else {
+                    tracing::warn!(
+                        id = %record.id,
+                        subject = %record.subject,
+                        attempts,
+                        "Failed to publish outbox event: {}",
+                        error
+                    );
+                }
+                store.mark_failed(&record.id, &error, retry_at).await?;
+                return Ok(0);
+            }
+        }
+    }
+
+    Ok(records.len())
+}
+
+async fn publish(
+    client: &async_nats::Client,
+    js: Option<&jetstream::Context>,
+    record: &OutboxRecord,
+) -> Result<(), String> {
+    let mut headers = async_nats::HeaderMap::new();
+    for (name, value) in &record.headers {
+        headers.append(name.as_str(), value.as_str());
+    }
+    headers.insert(async_nats::header::NATS_MESSAGE_ID, record.id.as_str());
+    let payload = record.payload.clone().into();
+
+    match js {
+        Some(js) => {
+            js.publish_with_headers(record.subject.clone(), headers, payload)
+                .await
+                .map_err(|e| e.to_string())?
+                .await
+                .map_err(|e| e.to_string())?;
+        }
+        None => {
+            client
+                .publish_with_headers(record.subject.clone(), headers, payload)
+                .await
+                .map_err(|e| e.to_string())?;
+            client.flush().await.map_err(|e| e.to_string())?;
+        }
+    }
+
+    Ok(())
+}
+
+/// When a row that has failed `attempts` times becomes due again
+///
+/// Exponential backoff from `retry_base_delay_secs`, capped at
+/// `retry_max_delay_secs`. `None` once `max_attempts` is reached.
+fn next_retry(attempts: u32, config: &OutboxConfig, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if attempts >= config.max_attempts {
+        return None;
+    }
+    let exponent = attempts.saturating_sub(1).min(31);
+    let delay = config
+        .retry_base_delay_secs
+        .saturating_mul(1u64 << exponent)
+        .min(config.retry_max_delay_secs);
+    Some(later(now, delay))
+}
+
+/// `secs` as a `TimeDelta`, saturating instead of panicking when out of range
+///
+/// `OutboxConfig::validate` rejects such values at startup; this keeps a
+/// relay started without the builder from panicking on them.
+fn secs(secs: u64) -> TimeDelta {
+    i64::try_from(secs)
+        .ok()
+        .and_then(TimeDelta::try_seconds)
+        .unwrap_or(TimeDelta::MAX)
+}
+
+/// `now` plus `secs`, saturating at the latest representable time
+fn later(now: DateTime<Utc>, secs_after: u64) -> DateTime<Utc> {
+    now.checked_add_signed(secs(secs_after))
+        .unwrap_or(DateTime::<Utc>::MAX_UTC)
+}
+
+/// Build outbox storage from whichever database pool has connected
+async fn resolve_storage<T>(state: &AppState<T>) -> Option<Arc<dyn OutboxStorage>>
+where
+    T: Serialize + DeserializeOwned + Clone + Default + Send + Sync + 'static,
+{
+    #[cfg(feature = "database")]
+    if let Some(pool) = state.db().await {
+        return Some(Arc::new(super::PgOutboxStorage::new(pool)));
+    }
+
+    #[cfg(feature = "turso")]
+    if let Some(db) = state.turso().await {
+        return Some(Arc::new(super::TursoOutboxStorage::new(db)));
+    }
+
+    #[cfg(feature = "surrealdb")]
+    if let Some(client) = state.surrealdb().await {
+        return Some(Arc::new(super::SurrealOutboxStorage::new(client)));
+    }
+
+    None
+}
+
+/// Outbox backlog for `PoolHealthSummary`, when the outbox is enabled
+pub(crate) async fn outbox_health<T>(state: &AppState<T>) -> Option<OutboxHealth>
+where
+    T: Serialize + DeserializeOwned + Clone + Default + Send + Sync + 'static,
+{
+    let config = state
+        .config()
+        .nats
+        .as_ref()?
+        .outbox
+        .as_ref()
+        .filter(|outbox| outbox.enabled)?;
+    let storage = resolve_storage(state).await?;
+
+    Some(match storage.backlog().await {
+        Ok(backlog) => OutboxHealth::from_backlog(
+            storage.backend(),
+            &backlog,
+            config.max_pending_age_secs,
+            Utc::now(),
+        ),
+        Err(e) => OutboxHealth::unavailable(storage.backend(), e.to_string()),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config() -> OutboxConfig {
+        OutboxConfig {
+            max_attempts: 5,
+            retry_base_delay_secs: 2,
+            retry_max_delay_secs: 10,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn retries_back_off_exponentially_up_to_the_cap() {
+        let now = Utc::now();
+        let delays: Vec<i64> = (1..=4)
+            .map(|attempts| (next_retry(attempts, &config(), now).unwrap() - now).num_seconds())
+            .collect();
+
+        assert_eq!(delays, vec![2, 4, 8, 10]);
+    }
+
+    #[test]
+    fn retries_stop_at_max_attempts() {
+        assert!(next_retry(5, &config(), Utc::now()).is_none());
+        assert!(next_retry(6, &config(), Utc::now()).is_none());
+    }
+
+    #[test]
+    fn large_attempt_counts_do_not_overflow() {
+        let config = OutboxConfig {
+            max_attempts: u32::MAX,
+            retry_base_delay_secs: u64::MAX,
+            retry_max_delay_secs: 60,
+            ..Default::default()
+        };
+        let now = Utc::now();
+        let retry_at = next_retry(1_000, &config, now).unwrap();
+        assert_eq!((retry_at - now).num_seconds(), 60);
+    }
+
+    #[test]
+    fn out_of_range_durations_saturate_instead_of_panicking() {
+        let config = OutboxConfig {
+            retry_base_delay_secs: u64::MAX,
+            retry_max_delay_secs: u64::MAX,
+            ..config()
+        };
+        let now = Utc::now();
+
+        assert_eq!(next_retry(1, &config, now), Some(DateTime::<Utc>::MAX_UTC));
+        assert_eq!(later(now, i64::MAX as u64), DateTime::<Utc>::MAX_UTC);
+    }
+
+    #[test]
+    fn validate_rejects_durations_past_the_maximum() {
+        assert!(OutboxConfig::default().validate().is_ok());
+
+        let config = OutboxConfig {
+            lease_secs: u64::MAX,
+            ..Default::default()
+        };
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("lease_secs"), "{err}");
+    }
+}
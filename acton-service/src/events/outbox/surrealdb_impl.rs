@@ -0,0 +1,308 @@
+//! SurrealDB outbox storage backend
+//!
+//! Stores events in a SCHEMAFULL `event_outbox` table keyed by event id.
+//! Payloads are stored hex-encoded, and timestamps as fixed-precision
+//! RFC 3339 strings so due-row comparisons order correctly.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use surrealdb::types::SurrealValue;
+
+use super::{
+    format_timestamp, parse_headers, parse_timestamp, OutboxBacklog, OutboxEvent, OutboxRecord,
+    OutboxStorage,
+};
+use crate::error::{Error, Result};
+use crate::surrealdb_backend::SurrealClient;
+
+/// Transaction type for the SurrealDB client used by the framework
+pub type SurrealTransaction = surrealdb::method::Transaction<surrealdb::engine::any::Any>;
+
+/// SurrealDB-backed outbox storage
+pub struct SurrealOutboxStorage {
+    client: Arc<SurrealClient>,
+}
+
+impl SurrealOutboxStorage {
+    /// Create a new SurrealDB outbox storage
+    pub fn new(client: Arc<SurrealClient>) -> Self {
+        Self { client }
+    }
+
+    /// Enqueue an event inside the caller's transaction
+    ///
+    /// Pass the transaction that writes the event's data; the row becomes
+    /// visible to the relay only if that transaction commits:
+    ///
+    /// ```rust,ignore
+    /// let txn = (*client).clone().begin().await?;
+    /// txn.query("CREATE orders CONTENT $order")
+    ///     .bind(("order", order.clone()))
+    ///     .await?;
+    /// SurrealOutboxStorage::enqueue(&txn, &event).await?;
+    /// txn.commit().await?;
+    /// ```
+    pub async fn enqueue(txn: &SurrealTransaction, event: &OutboxEvent) -> Result<()> {
+        let created_at = format_timestamp(event.created_at);
+        let record = OutboxInsert {
+            event_id: event.id.to_string(),
+            subject: event.subject.clone(),
+            payload: hex_encode(&event.payload),
+            headers: event.headers_json()?,
+            status: "pending".to_string(),
+            attempts: 0,
+            last_error: None,
+            created_at: created_at.clone(),
+            next_attempt_at: created_at,
+            sent_at: None,
+        };
+
+        txn.query("CREATE type::thing('event_outbox', $id) CONTENT $data")
+            .bind(("id", event.id.simple().to_string()))
+            .bind(("data", record))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to enqueue outbox event: {}", e)))?;
+
+        Ok(())
+    }
+}
+
+/// Serializable record for SurrealDB inserts
+#[derive(Serialize, SurrealValue)]
+struct OutboxInsert {
+    event_id: String,
+    subject: String,
+    payload: String,
+    headers: String,
+    status: String,
+    attempts: i64,
+    last_error: Option<String>,
+    created_at: String,
+    next_attempt_at: String,
+    sent_at: Option<String>,
+}
+
+/// Deserializable record from SurrealDB queries
+#[derive(Deserialize, SurrealValue)]
+struct OutboxRow {
+    // SurrealDB returns `id` as a record id; `event_id` carries the plain value
+    #[allow(dead_code)]
+    id: serde_json::Value,
+    event_id: String,
+    subject: String,
+    payload: String,
+    headers: String,
+    attempts: i64,
+    created_at: String,
+}
+
+impl TryFrom<OutboxRow> for OutboxRecord {
+    type Error = Error;
+
+    fn try_from(row: OutboxRow) -> Result<Self> {
+        Ok(OutboxRecord {
+            payload: hex_decode(&row.payload)?,
+            headers: parse_headers(&row.headers)?,
+            attempts: u32::try_from(row.attempts).unwrap_or(0),
+            created_at: parse_timestamp(&row.created_at)?,
+            id: row.event_id,
+            subject: row.subject,
+        })
+    }
+}
+
+#[async_trait]
+impl OutboxStorage for SurrealOutboxStorage {
+    fn backend(&self) -> &'static str {
+        "surrealdb"
+    }
+
+    async fn initialize(&self) -> Result<()> {
+        self.client
+            .query(
+                r#"
+                DEFINE TABLE IF NOT EXISTS event_outbox SCHEMAFULL;
+
+                DEFINE FIELD IF NOT EXISTS event_id ON event_outbox TYPE string;
+                DEFINE FIELD IF NOT EXISTS subject ON event_outbox TYPE string;
+                DEFINE FIELD IF NOT EXISTS payload ON event_outbox TYPE string;
+                DEFINE FIELD IF NOT EXISTS headers ON event_outbox TYPE string;
+                DEFINE FIELD IF NOT EXISTS status ON event_outbox TYPE string
+                    ASSERT $value IN ['pending', 'sent', 'failed'];
+                DEFINE FIELD IF NOT EXISTS attempts ON event_outbox TYPE int;
+                DEFINE FIELD IF NOT EXISTS last_error ON event_outbox TYPE option<string>;
+                DEFINE FIELD IF NOT EXISTS created_at ON event_outbox TYPE string;
+                DEFINE FIELD IF NOT EXISTS next_attempt_at ON event_outbox TYPE string;
+                DEFINE FIELD IF NOT EXISTS sent_at ON event_outbox TYPE option<string>;
+
+                DEFINE INDEX IF NOT EXISTS idx_event_outbox_due ON event_outbox FIELDS status, next_attempt_at;
+                "#,
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to initialize event_outbox schema: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn claim_due(
+        &self,
+        now: DateTime<Utc>,
+        lease_until: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<OutboxRecord>> {
+        // The WHERE on the UPDATE re-checks due-ness, so a row another relay
+        // claimed between the SELECT and the UPDATE is skipped.
+        let mut result = self
+            .client
+            .query(
+                "UPDATE (SELECT id, created_at FROM event_outbox \
+                     WHERE status = 'pending' AND next_attempt_at <= $now \
+                     ORDER BY created_at LIMIT $limit).id \
+                 SET next_attempt_at = $lease \
+                 WHERE status = 'pending' AND next_attempt_at <= $now \
+                 RETURN AFTER",
+            )
+            .bind(("now", format_timestamp(now)))
+            .bind(("lease", format_timestamp(lease_until)))
+            .bind(("limit", i64::try_from(limit).unwrap_or(i64::MAX)))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to claim outbox rows: {}", e)))?;
+
+        let rows: Vec<OutboxRow> = result
+            .take(0)
+            .map_err(|e| Error::Internal(format!("Failed to deserialize outbox rows: {}", e)))?;
+
+        let mut records = rows
+            .into_iter()
+            .map(TryInto::try_into)
+            .collect::<Result<Vec<OutboxRecord>>>()?;
+        records.sort_by_key(|record| record.created_at);
+        Ok(records)
+    }
+
+    async fn mark_sent(&self, id: &str, sent_at: DateTime<Utc>) -> Result<()> {
+        self.client
+            .query(
+                "UPDATE type::thing('event_outbox', $id) \
+                 SET status = 'sent', sent_at = $sent_at, last_error = NONE",
+            )
+            .bind(("id", id.to_string()))
+            .bind(("sent_at", format_timestamp(sent_at)))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to mark outbox row sent: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn mark_failed(
+        &self,
+        id: &str,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        let result = match retry_at {
+            Some(retry_at) => {
+                self.client
+                    .query(
+                        "UPDATE type::thing('event_outbox', $id) \
+                         SET attempts += 1, last_error = $error, next_attempt_at = $retry_at",
+                    )
+                    .bind(("id", id.to_string()))
+                    .bind(("error", error.to_string()))
+                    .bind(("retry_at", format_timestamp(retry_at)))
+                    .await
+            }
+            None => {
+                self.client
+                    .query(
+                        "UPDATE type::thing('event_outbox', $id) \
+                         SET attempts += 1, last_error = $error, status = 'failed'",
+                    )
+                    .bind(("id", id.to_string()))
+                    .bind(("error", error.to_string()))
+                    .await
+            }
+        };
+
+        result.map_err(|e| Error::Internal(format!("Failed to record outbox failure: {}", e)))?;
+        Ok(())
+    }
+
+    async fn backlog(&self) -> Result<OutboxBacklog> {
+        let mut result = self
+            .client
+            .query(
+                "SELECT count() AS count FROM event_outbox WHERE status = 'pending' GROUP ALL; \
+                 SELECT count() AS count FROM event_outbox WHERE status = 'failed' GROUP ALL; \
+                 SELECT created_at FROM event_outbox WHERE status = 'pending' \
+                     ORDER BY created_at LIMIT 1;",
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to read outbox backlog: {}", e)))?;
+
+        #[derive(Deserialize, SurrealValue)]
+        struct CountRow {
+            count: i64,
+        }
+
+        #[derive(Deserialize, SurrealValue)]
+        struct CreatedRow {
+            created_at: String,
+        }
+
+        let pending: Vec<CountRow> = result.take(0).unwrap_or_default();
+        let failed: Vec<CountRow> = result.take(1).unwrap_or_default();
+        let oldest: Vec<CreatedRow> = result.take(2).unwrap_or_default();
+
+        Ok(OutboxBacklog {
+            pending: pending.first().map_or(0, |r| r.count.max(0) as u64),
+            failed: failed.first().map_or(0, |r| r.count.max(0) as u64),
+            oldest_pending: oldest
+                .first()
+                .map(|r| parse_timestamp(&r.created_at))
+                .transpose()?,
+        })
+    }
+
+    async fn purge_sent(&self, before: DateTime<Utc>) -> Result<u64> {
+        let mut result = self
+            .client
+            .query("DELETE event_outbox WHERE status = 'sent' AND sent_at < $before RETURN BEFORE")
+            .bind(("before", format_timestamp(before)))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to purge sent outbox rows: {}", e)))?;
+
+        let deleted: Vec<serde_json::Value> = result.take(0).unwrap_or_default();
+        Ok(deleted.len() as u64)
+    }
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    use std::fmt::Write;
+
+    bytes
+        .iter()
+        .fold(String::with_capacity(bytes.len() * 2), |mut out, b| {
+            let _ = write!(out, "{:02x}", b);
+            out
+        })
+}
+
+fn hex_decode(value: &str) -> Result<Vec<u8>> {
+    if !value.len().is_multiple_of(2) {
+        return Err(Error::Internal(
+            "Outbox payload has odd hex length".to_string(),
+        ));
+    }
+    (0..value.len())
+        .step_by(2)
+        .map(|i| {
+            value
+                .get(i..i + 2)
+                .and_then(|pair| u8::from_str_radix(pair, 16).ok())
+                .ok_or_else(|| Error::Internal("Invalid outbox payload encoding".to_string()))
+        })
+        .collect()
+}
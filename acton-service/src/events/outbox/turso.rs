@@ -0,0 +1,275 @@
+//! Turso/libsql outbox storage backend
+//!
+//! Stores events in an `event_outbox` table. Timestamps are fixed-precision
+//! RFC 3339 text so the due-row comparisons can be done as string comparisons.
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Arc;
+
+use super::{
+    format_timestamp, parse_headers, parse_timestamp, OutboxBacklog, OutboxEvent, OutboxRecord,
+    OutboxStorage,
+};
+use crate::error::{Error, Result};
+
+/// Turso-backed outbox storage
+pub struct TursoOutboxStorage {
+    db: Arc<libsql::Database>,
+}
+
+impl TursoOutboxStorage {
+    /// Create a new Turso outbox storage
+    pub fn new(db: Arc<libsql::Database>) -> Self {
+        Self { db }
+    }
+
+    /// Enqueue an event on the caller's connection
+    ///
+    /// Pass the transaction that writes the event's data (a
+    /// `libsql::Transaction` dereferences to its connection); the row becomes
+    /// visible to the relay only if that transaction commits.
+    pub async fn enqueue(conn: &libsql::Connection, event: &OutboxEvent) -> Result<()> {
+        let created_at = format_timestamp(event.created_at);
+        conn.execute(
+            "INSERT INTO event_outbox \
+             (id, subject, payload, headers, created_at, next_attempt_at) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?5)",
+            libsql::params![
+                event.id.to_string(),
+                event.subject.clone(),
+                event.payload.clone(),
+                event.headers_json()?,
+                created_at,
+            ],
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to enqueue outbox event: {}", e)))?;
+
+        Ok(())
+    }
+
+    /// Get a connection from the database
+    fn connect(&self) -> Result<libsql::Connection> {
+        self.db
+            .connect()
+            .map_err(|e| Error::Internal(format!("Failed to connect for outbox: {}", e)))
+    }
+}
+
+#[async_trait]
+impl OutboxStorage for TursoOutboxStorage {
+    fn backend(&self) -> &'static str {
+        "turso"
+    }
+
+    async fn initialize(&self) -> Result<()> {
+        let conn = self.connect()?;
+
+        conn.execute(
+            r#"
+            CREATE TABLE IF NOT EXISTS event_outbox (
+                id TEXT PRIMARY KEY,
+                subject TEXT NOT NULL,
+                payload BLOB NOT NULL,
+                headers TEXT NOT NULL DEFAULT '[]',
+                status TEXT NOT NULL DEFAULT 'pending'
+                    CHECK (status IN ('pending', 'sent', 'failed')),
+                attempts INTEGER NOT NULL DEFAULT 0,
+                last_error TEXT,
+                created_at TEXT NOT NULL,
+                next_attempt_at TEXT NOT NULL,
+                sent_at TEXT
+            )
+            "#,
+            (),
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to create event_outbox table: {}", e)))?;
+
+        conn.execute(
+            "CREATE INDEX IF NOT EXISTS idx_event_outbox_due \
+             ON event_outbox (status, next_attempt_at)",
+            (),
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to create event_outbox due index: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn claim_due(
+        &self,
+        now: DateTime<Utc>,
+        lease_until: DateTime<Utc>,
+        limit: usize,
+    ) -> Result<Vec<OutboxRecord>> {
+        let conn = self.connect()?;
+
+        // SQLite serializes writers, so the single UPDATE is the claim.
+        let mut rows = conn
+            .query(
+                "UPDATE event_outbox SET next_attempt_at = ?2 \
+                 WHERE id IN ( \
+                     SELECT id FROM event_outbox \
+                     WHERE status = 'pending' AND next_attempt_at <= ?1 \
+                     ORDER BY created_at LIMIT ?3 \
+                 ) \
+                 RETURNING id, subject, payload, headers, attempts, created_at",
+                libsql::params![
+                    format_timestamp(now),
+                    format_timestamp(lease_until),
+                    i64::try_from(limit).unwrap_or(i64::MAX),
+                ],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to claim outbox rows: {}", e)))?;
+
+        let mut records = Vec::new();
+        while let Some(row) = rows
+            .next()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to read outbox row: {}", e)))?
+        {
+            records.push(row_to_record(&row)?);
+        }
+
+        // RETURNING does not preserve the subquery's order
+        records.sort_by_key(|record| record.created_at);
+        Ok(records)
+    }
+
+    async fn mark_sent(&self, id: &str, sent_at: DateTime<Utc>) -> Result<()> {
+        let conn = self.connect()?;
+
+        conn.execute(
+            "UPDATE event_outbox SET status = 'sent', sent_at = ?2, last_error = NULL \
+             WHERE id = ?1",
+            libsql::params![id.to_string(), format_timestamp(sent_at)],
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to mark outbox row sent: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn mark_failed(
+        &self,
+        id: &str,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> Result<()> {
+        let conn = self.connect()?;
+
+        let result = match retry_at {
+            Some(retry_at) => {
+                conn.execute(
+                    "UPDATE event_outbox \
+                     SET attempts = attempts + 1, last_error = ?2, next_attempt_at = ?3 \
+                     WHERE id = ?1",
+                    libsql::params![
+                        id.to_string(),
+                        error.to_string(),
+                        format_timestamp(retry_at)
+                    ],
+                )
+                .await
+            }
+            None => {
+                conn.execute(
+                    "UPDATE event_outbox \
+                     SET attempts = attempts + 1, last_error = ?2, status = 'failed' \
+                     WHERE id = ?1",
+                    libsql::params![id.to_string(), error.to_string()],
+                )
+                .await
+            }
+        };
+
+        result.map_err(|e| Error::Internal(format!("Failed to record outbox failure: {}", e)))?;
+        Ok(())
+    }
+
+    async fn backlog(&self) -> Result<OutboxBacklog> {
+        let conn = self.connect()?;
+
+        let mut rows = conn
+            .query(
+                "SELECT \
+                     COALESCE(SUM(CASE WHEN status = 'pending' THEN 1 ELSE 0 END), 0), \
+                     COALESCE(SUM(CASE WHEN status = 'failed' THEN 1 ELSE 0 END), 0), \
+                     MIN(CASE WHEN status = 'pending' THEN created_at END) \
+                 FROM event_outbox WHERE status <> 'sent'",
+                (),
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to read outbox backlog: {}", e)))?;
+
+        let row = rows
+            .next()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to read outbox backlog: {}", e)))?
+            .ok_or_else(|| Error::Internal("Outbox backlog query returned no row".to_string()))?;
+
+        let pending: i64 = row
+            .get(0)
+            .map_err(|e| Error::Internal(format!("Failed to read pending count: {}", e)))?;
+        let failed: i64 = row
+            .get(1)
+            .map_err(|e| Error::Internal(format!("Failed to read failed count: {}", e)))?;
+        let oldest_pending: Option<String> = row
+            .get(2)
+            .map_err(|e| Error::Internal(format!("Failed to read oldest pending: {}", e)))?;
+
+        Ok(OutboxBacklog {
+            pending: pending.max(0) as u64,
+            failed: failed.max(0) as u64,
+            oldest_pending: oldest_pending.as_deref().map(parse_timestamp).transpose()?,
+        })
+    }
+
+    async fn purge_sent(&self, before: DateTime<Utc>) -> Result<u64> {
+        let conn = self.connect()?;
+
+        conn.execute(
+            "DELETE FROM event_outbox WHERE status = 'sent' AND sent_at < ?1",
+            libsql::params![format_timestamp(before)],
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to purge sent outbox rows: {}", e)))
+    }
+}
+
+/// Convert a libsql row into an `OutboxRecord`
+///
+/// Column order: id(0), subject(1), payload(2), headers(3), attempts(4),
+/// created_at(5)
+fn row_to_record(row: &libsql::Row) -> Result<OutboxRecord> {
+    let id: String = row
+        .get(0)
+        .map_err(|e| Error::Internal(format!("Failed to read outbox id: {}", e)))?;
+    let subject: String = row
+        .get(1)
+        .map_err(|e| Error::Internal(format!("Failed to read outbox subject: {}", e)))?;
+    let payload: Vec<u8> = row
+        .get(2)
+        .map_err(|e| Error::Internal(format!("Failed to read outbox payload: {}", e)))?;
+    let headers: String = row
+        .get(3)
+        .map_err(|e| Error::Internal(format!("Failed to read outbox headers: {}", e)))?;
+    let attempts: i64 = row
+        .get(4)
+        .map_err(|e| Error::Internal(format!("Failed to read outbox attempts: {}", e)))?;
+    let created_at: String = row
+        .get(5)
+        .map_err(|e| Error::Internal(format!("Failed to read outbox created_at: {}", e)))?;
+
+    Ok(OutboxRecord {
+        id,
+        subject,
+        payload,
+        headers: parse_headers(&headers)?,
+        attempts: u32::try_from(attempts).unwrap_or(0),
+        created_at: parse_timestamp(&created_at)?,
+    })
+}
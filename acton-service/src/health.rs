@@ -18,6 +18,32 @@ pub struct HealthResponse {
     /// Version
     #[serde(skip_serializing_if = "Option::is_none")]
     pub version: Option<String>,
+
+    /// Structured details, omitted when there is nothing to report
+    #[serde(default, skip_serializing_if = "HealthDetails::is_empty")]
+    pub details: HealthDetails,
+}
+
+/// Structured details carried by `/health`
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct HealthDetails {
+    /// Watched TLS certificates and their expiry (requires `tls` feature)
+    ///
+    /// See [`crate::cert_expiry`].
+    #[cfg(feature = "tls")]
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub certificates: Vec<crate::cert_expiry::CertificateDetail>,
+}
+
+impl HealthDetails {
+    /// Whether there is nothing to report
+    pub fn is_empty(&self) -> bool {
+        #[cfg(feature = "tls")]
+        if !self.certificates.is_empty() {
+            return false;
+        }
+        true
+    }
 }
 
 /// Readiness check response with dependency status
@@ -61,6 +87,13 @@ where
         status: if alive { "healthy" } else { "unhealthy" }.to_string(),
         service: state.config().service.name.clone(),
         version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        details: HealthDetails {
+            #[cfg(feature = "tls")]
+            certificates: state
+                .cert_expiry()
+                .map(|monitor| monitor.details())
+                .unwrap_or_default(),
+        },
     };
 
     let status = if alive {
@@ -680,6 +713,7 @@ mod tests {
             status: "healthy".to_string(),
             service: "test-service".to_string(),
             version: Some("1.0.0".to_string()),
+            details: HealthDetails::default(),
         };
 
         assert_eq!(response.status, "healthy");
@@ -725,6 +759,8 @@ mod tests {
                 .await
                 .into_response();
             assert_eq!(response.status(), StatusCode::OK);
+            let body = body_json(response).await;
+            assert!(body.get("details").is_none(), "nothing to report: {body}");
         }
 
         #[tokio::test]
@@ -160,9 +160,18 @@ pub mod tls;
 #[cfg(feature = "tls")]
 pub mod client_tls;
 
+#[cfg(feature = "tls")]
+pub mod cert_expiry;
+
+#[cfg(feature = "acme")]
+pub mod acme;
+
 #[cfg(feature = "tls")]
 pub mod caller_auth;
 
+#[cfg(feature = "tls")]
+pub mod spiffe;
+
 #[cfg(feature = "login-lockout")]
 pub mod lockout;
 
@@ -232,6 +241,18 @@ pub mod prelude {
     #[cfg(feature = "events")]
     pub use crate::pool_health::NatsClientHealth;
 
+    #[cfg(feature = "events")]
+    pub use crate::events::{
+        envelope_handler, json_handler, AckAction, ConsumerMessage, Event, EventEnvelope,
+        EventHandler,
+    };
+
+    #[cfg(all(
+        feature = "events",
+        any(feature = "database", feature = "turso", feature = "surrealdb")
+    ))]
+    pub use crate::events::outbox::{OutboxEvent, OutboxStorage};
+
     #[cfg(feature = "clickhouse")]
     pub use crate::pool_health::ClickHouseHealth;
 
@@ -273,6 +294,12 @@ pub mod prelude {
     #[cfg(feature = "cedar-authz")]
     pub use crate::middleware::CedarAuthz;
 
+    #[cfg(feature = "idempotency")]
+    pub use crate::config::IdempotencyConfig;
+
+    #[cfg(feature = "idempotency")]
+    pub use crate::middleware::Idempotency;
+
     #[cfg(feature = "tls")]
     pub use crate::caller_auth::{
         CallerAllowlist, CallerAuthError, CallerAuthLayer, CallerAuthMode, CallerAuthPolicy,
@@ -280,7 +307,10 @@ pub mod prelude {
     };
 
     #[cfg(feature = "tls")]
-    pub use crate::config::CallerAuthConfig;
+    pub use crate::config::{CallerAuthConfig, SpiffeConfig};
+
+    #[cfg(feature = "tls")]
+    pub use crate::spiffe::{SpiffeId, SpiffeIdPattern, SpiffeTrustBundles};
 
     #[cfg(all(feature = "cedar-authz", feature = "cache"))]
     pub use crate::middleware::{PolicyCache, RedisPolicyCache};
@@ -313,9 +343,11 @@ pub mod prelude {
     // Auth module exports
     #[cfg(feature = "auth")]
     pub use crate::auth::{
-        ApiKey, ApiKeyGenerator, AuthConfig, CachedKey, KeyFormat, KeyManager, KeyRotationConfig,
-        KeyStatus, PasetoGenerationConfig, PasetoGenerator, PasswordConfig, PasswordHasher,
-        RefreshTokenConfig, SigningKeyMetadata, TokenGenerationConfig, TokenGenerator, TokenPair,
+        ApiKey, ApiKeyConfig, ApiKeyGenerator, AuthConfig, CachedKey, ClientCredentialsConfig,
+        ClientCredentialsServer, KeyFormat, KeyManager, KeyRotationConfig, KeyStatus, NewClient,
+        PasetoGenerationConfig, PasetoGenerator, PasswordConfig, PasswordHasher,
+        PasswordPolicyError, PasswordViolation, RefreshTokenConfig, SigningKeyMetadata,
+        TokenGenerationConfig, TokenGenerator, TokenPair,
     };
 
     // Key rotation storage trait (requires auth + a database backend)
@@ -328,9 +360,12 @@ pub mod prelude {
     #[cfg(all(feature = "auth", feature = "jwt"))]
     pub use crate::auth::JwtGenerator;
 
+    #[cfg(feature = "auth")]
+    pub use crate::middleware::ApiKeyAuth;
+
     #[cfg(feature = "oauth")]
     pub use crate::auth::{
-        ApiKeyConfig, OAuthConfig, OAuthProvider, OAuthProviderConfig, OAuthTokens, OAuthUserInfo,
+        OAuthConfig, OAuthProvider, OAuthProviderConfig, OAuthTokens, OAuthUserInfo,
     };
 
     #[cfg(feature = "websocket")]
@@ -374,6 +409,9 @@ pub mod prelude {
         FlashMessage,
         FlashMessages,
         SessionAuth,
+        // Session-to-Claims bridge
+        session_claims_middleware,
+        SessionClaimsConfig,
         // Configuration
         SessionConfig,
         SessionData,
@@ -382,6 +420,14 @@ pub mod prelude {
         TypedSession,
     };
 
+    // Database-backed session listing
+    #[cfg(any(
+        feature = "session-postgres",
+        feature = "session-turso",
+        feature = "session-surrealdb"
+    ))]
+    pub use crate::session::{ActiveSession, PersistentSessionStore};
+
     // Re-export tower-sessions Session type for direct use
     #[cfg(feature = "session")]
     pub use tower_sessions::Session;
@@ -511,6 +557,18 @@ pub mod prelude {
     #[cfg(feature = "account-handlers")]
     pub use crate::accounts::handlers::account_routes;
 
+    #[cfg(feature = "account-handlers")]
+    pub use crate::accounts::auth_handlers::{auth_routes, TokenEndpoints};
+
+    #[cfg(all(feature = "account-handlers", feature = "oauth"))]
+    pub use crate::accounts::oauth_handlers::{oauth_login_routes, LoginCompletion, OAuthLogin};
+
+    #[cfg(feature = "passkeys")]
+    pub use crate::accounts::passkeys::PasskeyChallengeStore;
+
+    #[cfg(all(feature = "passkeys", feature = "cache"))]
+    pub use crate::accounts::passkeys::RedisPasskeyChallengeStore;
+
     // =========================================================================
     // Axum Re-exports
     // =========================================================================
@@ -0,0 +1,717 @@
+//! API key authentication middleware (requires `auth` feature)
+//!
+//! Authenticates requests carrying an API key header (`X-API-Key` by
+//! default) against an [`ApiKeyStorage`] backend. A valid key is turned into
+//! [`Claims`] so Cedar policies, `Claims::has_permission` and per-client rate
+//! limits work the same way they do for PASETO or JWT tokens:
+//!
+//! - `sub` is `client:{user_id}`, so [`Claims::client_id`] returns the owner
+//! - `perms` are the key's scopes
+//! - `exp` is the key's expiry (or never, for keys without one)
+//! - the `api_key_id` and `api_key_name` custom claims identify the key
+//!
+//! The authenticated [`ApiKey`] is inserted into the request extensions as
+//! well, which is what the rate limiting middleware uses to apply per-key
+//! limits and quotas.
+//!
+//! # Either API key or bearer token
+//!
+//! With [`with_bearer_fallback`](ApiKeyAuth::with_bearer_fallback) enabled,
+//! requests without an API key header are passed on to the token middleware
+//! instead of being rejected, and requests authenticated by a key are not
+//! asked for a token. `ServiceBuilder` turns this on whenever a `[token]`
+//! section is configured alongside `[auth.api_keys]`.
+//!
+//! A signed-in session under `[session.claims]` stands in for a key the same
+//! way, without a key header.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use axum::{
+    body::Body,
+    extract::{Request, State},
+    middleware::Next,
+    response::Response,
+};
+use tokio::sync::OnceCell;
+
+#[cfg(any(
+    feature = "cache",
+    feature = "database",
+    feature = "turso",
+    feature = "surrealdb"
+))]
+use tokio::sync::RwLock;
+
+use super::token::Claims;
+use crate::auth::api_keys::{ApiKey, ApiKeyStorage, ApiKeyUsage};
+use crate::auth::config::ApiKeyConfig;
+use crate::error::Error;
+
+/// How long a key lookup or usage count waits on another connection's write
+/// lock (the audit log shares the Turso file) before failing
+#[cfg(feature = "turso")]
+const TURSO_BUSY_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(5);
+
+/// Marker placed in the request extensions once an API key has authenticated
+/// the request, so bearer token middleware does not demand a token as well.
+///
+/// Crate-private on purpose: handlers and user middleware cannot forge it.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct ApiKeyAuthenticated;
+
+/// `[auth.api_keys] default_rate_limit`, passed to the rate limiters so it
+/// applies only after a key's own value and its scopes
+#[cfg(any(feature = "cache", feature = "governor"))]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct DefaultKeyRateLimit(pub(crate) u32);
+
+/// Whether the request was already authenticated by [`ApiKeyAuth`], so token
+/// middleware should not demand a bearer token.
+pub(crate) fn bearer_satisfied(extensions: &http::Extensions) -> bool {
+    extensions.get::<ApiKeyAuthenticated>().is_some()
+}
+
+/// Where the middleware gets its [`ApiKeyStorage`] from
+#[derive(Clone)]
+enum StorageSource {
+    /// A storage handed in by the caller
+    Fixed(Arc<dyn ApiKeyStorage>),
+    /// The service's shared Redis pool, filled in once the pool agent connects
+    #[cfg(feature = "cache")]
+    Redis(Arc<RwLock<Option<deadpool_redis::Pool>>>),
+    /// The service's shared PostgreSQL pool
+    #[cfg(feature = "database")]
+    Postgres(Arc<RwLock<Option<sqlx::PgPool>>>),
+    /// The service's shared Turso database
+    #[cfg(feature = "turso")]
+    Turso(Arc<RwLock<Option<Arc<libsql::Database>>>>),
+    /// The service's shared SurrealDB client
+    #[cfg(feature = "surrealdb")]
+    SurrealDb(Arc<RwLock<Option<Arc<crate::surrealdb_backend::SurrealClient>>>>),
+}
+
+/// API key authentication middleware state
+#[derive(Clone)]
+pub struct ApiKeyAuth {
+    source: StorageSource,
+    /// The storage in use; built from a shared pool on first use
+    resolved: Arc<OnceCell<Arc<dyn ApiKeyStorage>>>,
+    /// Key prefix the storage backends verify keys against
+    #[cfg_attr(
+        not(any(
+            feature = "cache",
+            feature = "database",
+            feature = "turso",
+            feature = "surrealdb"
+        )),
+        allow(dead_code)
+    )]
+    prefix: Arc<str>,
+    /// Header the key is read from
+    header: Arc<str>,
+    /// Requests/minute for keys without their own `rate_limit`
+    #[cfg_attr(not(any(feature = "cache", feature = "governor")), allow(dead_code))]
+    default_rate_limit: Option<u32>,
+    /// Path prefixes that bypass API key authentication
+    public_paths: Arc<[String]>,
+    /// Pass requests without an API key on to bearer token middleware
+    bearer_fallback: bool,
+}
+
+impl ApiKeyAuth {
+    /// Create API key authentication backed by the given storage
+    pub fn new(config: &ApiKeyConfig, storage: Arc<dyn ApiKeyStorage>) -> Self {
+        let mut auth = Self::with_source(config, StorageSource::Fixed(storage.clone()));
+        auth.resolved = Arc::new(OnceCell::new_with(Some(storage)));
+        auth
+    }
+
+    fn with_source(config: &ApiKeyConfig, source: StorageSource) -> Self {
+        Self {
+            source,
+            resolved: Arc::new(OnceCell::new()),
+            prefix: config.prefix.as_str().into(),
+            header: config.header.as_str().into(),
+            default_rate_limit: config.default_rate_limit,
+            public_paths: config.public_paths.clone().into(),
+            bearer_fallback: false,
+        }
+    }
+
+    /// Create API key authentication from the service's shared connection
+    /// storage, selecting the backend named by `config.storage`
+    ///
+    /// The pools are connected asynchronously by the pool agents, so the
+    /// storage is built on the first request that needs it.
+    #[cfg_attr(
+        not(any(
+            feature = "cache",
+            feature = "database",
+            feature = "turso",
+            feature = "surrealdb"
+        )),
+        allow(unused_variables, unreachable_code)
+    )]
+    pub(crate) fn from_state<T>(
+        config: &ApiKeyConfig,
+        state: &crate::state::AppState<T>,
+    ) -> Result<Self, Error>
+    where
+        T: serde::Serialize + serde::de::DeserializeOwned + Clone + Default + Send + Sync + 'static,
+    {
+        let service_config = state.config();
+        // Each backend pairs its shared storage with whether that connection
+        // is configured at all; an unconfigured pool would never fill in.
+        let (source, section): (Option<StorageSource>, &str) = match config.storage.as_str() {
+            #[cfg(feature = "cache")]
+            "redis" => (
+                Some(StorageSource::Redis(state.redis_lock().clone()))
+                    .filter(|_| service_config.redis.is_some()),
+                "[redis]",
+            ),
+            #[cfg(feature = "database")]
+            "postgres" => (
+                Some(StorageSource::Postgres(state.db_lock().clone()))
+                    .filter(|_| service_config.database.is_some()),
+                "[database]",
+            ),
+            #[cfg(feature = "turso")]
+            "turso" => (
+                Some(StorageSource::Turso(state.turso_lock().clone()))
+                    .filter(|_| service_config.turso.is_some()),
+                "[turso]",
+            ),
+            #[cfg(feature = "surrealdb")]
+            "surrealdb" => (
+                Some(StorageSource::SurrealDb(state.surrealdb_lock().clone()))
+                    .filter(|_| service_config.surrealdb.is_some()),
+                "[surrealdb]",
+            ),
+            other => {
+                return Err(Error::Internal(format!(
+                    "[auth.api_keys] storage '{other}' is unknown or its feature is not enabled \
+                     (expected one of: redis, postgres, turso, surrealdb)"
+                )));
+            }
+        };
+        let source = source.ok_or_else(|| {
+            Error::Internal(format!(
+                "[auth.api_keys] uses '{}' storage but no {section} section is configured",
+                config.storage
+            ))
+        })?;
+        Ok(Self::with_source(config, source))
+    }
+
+    /// Set path prefixes that bypass API key authentication
+    pub fn with_public_paths(mut self, public_paths: Vec<String>) -> Self {
+        self.public_paths = public_paths.into();
+        self
+    }
+
+    /// Accept either an API key or a bearer token
+    ///
+    /// Requests without an API key header are passed on untouched, so a
+    /// PASETO or JWT middleware layered inside this one can authenticate them.
+    /// Requests with a key are still rejected if the key is invalid.
+    pub fn with_bearer_fallback(mut self, enabled: bool) -> Self {
+        self.bearer_fallback = enabled;
+        self
+    }
+
+    /// Get the storage, building it from the shared pool on first use
+    async fn storage(&self) -> Result<Arc<dyn ApiKeyStorage>, Error> {
+        self.resolved
+            .get_or_try_init(|| async {
+                let storage: Arc<dyn ApiKeyStorage> = match &self.source {
+                    StorageSource::Fixed(storage) => storage.clone(),
+                    #[cfg(feature = "cache")]
+                    StorageSource::Redis(lock) => {
+                        let pool = lock.read().await.clone().ok_or_else(|| {
+                            Error::Internal("Redis pool not available".to_string())
+                        })?;
+                        Arc::new(crate::auth::api_keys::RedisApiKeyStorage::new(
+                            pool,
+                            &*self.prefix,
+                        ))
+                    }
+                    #[cfg(feature = "database")]
+                    StorageSource::Postgres(lock) => {
+                        let pool = lock.read().await.clone().ok_or_else(|| {
+                            Error::Internal("Database pool not available".to_string())
+                        })?;
+                        Arc::new(crate::auth::api_keys::PgApiKeyStorage::new(
+                            pool,
+                            &*self.prefix,
+                        ))
+                    }
+                    #[cfg(feature = "turso")]
+                    StorageSource::Turso(lock) => {
+                        let db = lock.read().await.clone().ok_or_else(|| {
+                            Error::Internal("Turso database not available".to_string())
+                        })?;
+                        let conn = db.connect().map_err(|e| {
+                            Error::Internal(format!("Failed to connect to Turso: {}", e))
+                        })?;
+                        conn.busy_timeout(TURSO_BUSY_TIMEOUT).map_err(|e| {
+                            Error::Internal(format!("Failed to set Turso busy timeout: {}", e))
+                        })?;
+                        Arc::new(crate::auth::api_keys::TursoApiKeyStorage::new(
+                            Arc::new(conn),
+                            &*self.prefix,
+                        ))
+                    }
+                    #[cfg(feature = "surrealdb")]
+                    StorageSource::SurrealDb(lock) => {
+                        let client = lock.read().await.clone().ok_or_else(|| {
+                            Error::Internal("SurrealDB client not available".to_string())
+                        })?;
+                        Arc::new(crate::auth::api_keys::SurrealDbApiKeyStorage::new(
+                            client,
+                            &*self.prefix,
+                        ))
+                    }
+                };
+                Ok::<_, Error>(storage)
+            })
+            .await
+            .cloned()
+    }
+
+    /// Storage for counting daily and monthly API key quotas
+    ///
+    /// Pass this to [`GovernorRateLimit::with_api_key_storage`] or
+    /// [`RateLimit::with_api_key_storage`] so quotas are counted in the same
+    /// storage keys are verified against. `ServiceBuilder` does this for the
+    /// governor it auto-applies. Like the middleware, the returned storage
+    /// builds on the shared pool the first time it is used.
+    ///
+    /// [`GovernorRateLimit::with_api_key_storage`]: crate::middleware::governor::GovernorRateLimit::with_api_key_storage
+    /// [`RateLimit::with_api_key_storage`]: crate::middleware::rate_limit::RateLimit::with_api_key_storage
+    pub fn quota_storage(&self) -> Arc<dyn ApiKeyStorage> {
+        Arc::new(SharedStorage(self.clone()))
+    }
+
+    /// Verify a presented key: prefix lookup, hash check, expiry and revocation
+    async fn authenticate(&self, presented: &str) -> Result<ApiKey, Error> {
+        let storage = self.storage().await?;
+        let api_key = match storage.get_by_key(presented).await {
+            Ok(Some(key)) => key,
+            // A malformed key is just another invalid credential to the caller
+            Ok(None) | Err(Error::ValidationError(_)) => {
+                return Err(Error::Unauthorized("Invalid API key".to_string()));
+            }
+            Err(e) => return Err(e),
+        };
+
+        if api_key.is_revoked {
+            return Err(Error::Unauthorized("API key has been revoked".to_string()));
+        }
+        if !api_key.is_valid() {
+            return Err(Error::Unauthorized("API key has expired".to_string()));
+        }
+
+        // Recording last use must not add a storage round trip to every request
+        let id = api_key.id.clone();
+        tokio::spawn(async move {
+            if let Err(e) = storage.update_last_used(&id).await {
+                tracing::warn!(api_key_id = %id, "Failed to update API key last_used_at: {}", e);
+            }
+        });
+
+        Ok(api_key)
+    }
+
+    /// Middleware function to validate API keys and inject claims
+    pub async fn middleware(
+        State(auth): State<Self>,
+        mut request: Request<Body>,
+        next: Next,
+    ) -> Result<Response, Error> {
+        // CORS preflight requests carry no credentials by spec
+        if request.method() == http::Method::OPTIONS {
+            return Ok(next.run(request).await);
+        }
+
+        // Skip authentication for infrastructure endpoints and configured public paths
+        let path = request.uri().path();
+        if path == "/health"
+            || path == "/ready"
+            || path.starts_with("/swagger-ui")
+            || path.starts_with("/api-docs")
+            || auth
+                .public_paths
+                .iter()
+                .any(|p| path.starts_with(p.as_str()))
+        {
+            return Ok(next.run(request).await);
+        }
+
+        // An allowlisted client certificate under `mtls-or-bearer` stands in
+        // for an API key just as it does for a token.
+        #[cfg(feature = "tls")]
+        if crate::caller_auth::bearer_waived(request.extensions()) {
+            return Ok(next.run(request).await);
+        }
+
+        #[cfg(feature = "audit")]
+        let audit_source = crate::middleware::request_context::audit_source_for_request(&request);
+
+        #[cfg(feature = "audit")]
+        let audit_logger = request
+            .extensions()
+            .get::<crate::audit::AuditLogger>()
+            .cloned();
+
+        let presented = match request.headers().get(&*auth.header) {
+            Some(value) => value.to_str().ok().map(str::to_string),
+            None if auth.bearer_fallback => return Ok(next.run(request).await),
+            #[cfg(feature = "session")]
+            None if crate::session::bearer_satisfied(request.extensions()) => {
+                return Ok(next.run(request).await)
+            }
+            None => None,
+        };
+
+        let Some(presented) = presented else {
+            #[cfg(feature = "audit")]
+            if let Some(ref logger) = audit_logger {
+                if logger.config().audit_auth_events {
+                    logger
+                        .log_auth(
+                            crate::audit::event::AuditEventKind::AuthTokenMissing,
+                            crate::audit::event::AuditSeverity::Informational,
+                            audit_source,
+                        )
+                        .await;
+                }
+            }
+            return Err(Error::Unauthorized(format!(
+                "Missing or malformed {} header",
+                auth.header
+            )));
+        };
+
+        let api_key = match auth.authenticate(&presented).await {
+            Ok(key) => key,
+            Err(e) => {
+                #[cfg(feature = "audit")]
+                if matches!(e, Error::Unauthorized(_)) {
+                    if let Some(ref logger) = audit_logger {
+                        if logger.config().audit_auth_events {
+                            logger
+                                .log_auth(
+                                    crate::audit::event::AuditEventKind::AuthTokenInvalid,
+                                    crate::audit::event::AuditSeverity::Warning,
+                                    audit_source,
+                                )
+                                .await;
+                        }
+                    }
+                }
+                return Err(e);
+            }
+        };
+
+        let claims = claims_for_key(&api_key);
+
+        #[cfg(feature = "audit")]
+        if let Some(ref logger) = audit_logger {
+            if logger.config().audit_auth_events {
+                let mut source = audit_source;
+                source.subject = Some(claims.sub.clone());
+                let event = crate::audit::event::AuditEvent::new(
+                    crate::audit::event::AuditEventKind::AuthLoginSuccess,
+                    crate::audit::event::AuditSeverity::Notice,
+                    logger.service_name().to_string(),
+                )
+                .with_source(source)
+                .with_metadata(serde_json::json!({ "api_key_id": api_key.id }));
+                logger.log(event).await;
+            }
+        }
+
+        let extensions = request.extensions_mut();
+        #[cfg(any(feature = "cache", feature = "governor"))]
+        if let Some(rpm) = auth.default_rate_limit {
+            extensions.insert(DefaultKeyRateLimit(rpm));
+        }
+        extensions.insert(claims);
+        extensions.insert(api_key);
+        extensions.insert(ApiKeyAuthenticated);
+
+        Ok(next.run(request).await)
+    }
+}
+
+/// The middleware's storage, resolved on each call
+///
+/// Shares [`ApiKeyAuth`]'s `OnceCell`, so it is the same storage instance
+/// once either side has built it.
+struct SharedStorage(ApiKeyAuth);
+
+#[async_trait::async_trait]
+impl ApiKeyStorage for SharedStorage {
+    async fn get_by_key(&self, key: &str) -> Result<Option<ApiKey>, Error> {
+        self.0.storage().await?.get_by_key(key).await
+    }
+
+    async fn get_by_prefix(&self, prefix: &str) -> Result<Option<ApiKey>, Error> {
+        self.0.storage().await?.get_by_prefix(prefix).await
+    }
+
+    async fn get_by_id(&self, id: &str) -> Result<Option<ApiKey>, Error> {
+        self.0.storage().await?.get_by_id(id).await
+    }
+
+    async fn create(&self, key: &ApiKey) -> Result<(), Error> {
+        self.0.storage().await?.create(key).await
+    }
+
+    async fn update_last_used(&self, id: &str) -> Result<(), Error> {
+        self.0.storage().await?.update_last_used(id).await
+    }
+
+    async fn revoke(&self, id: &str) -> Result<(), Error> {
+        self.0.storage().await?.revoke(id).await
+    }
+
+    async fn list_by_user(&self, user_id: &str) -> Result<Vec<ApiKey>, Error> {
+        self.0.storage().await?.list_by_user(user_id).await
+    }
+
+    async fn delete(&self, id: &str) -> Result<(), Error> {
+        self.0.storage().await?.delete(id).await
+    }
+
+    async fn record_usage(&self, id: &str) -> Result<ApiKeyUsage, Error> {
+        self.0.storage().await?.record_usage(id).await
+    }
+
+    async fn get_usage(&self, id: &str) -> Result<ApiKeyUsage, Error> {
+        self.0.storage().await?.get_usage(id).await
+    }
+}
+
+/// Build the claims an authenticated API key stands for
+fn claims_for_key(api_key: &ApiKey) -> Claims {
+    let mut custom = HashMap::new();
+    custom.insert(
+        "api_key_id".to_string(),
+        serde_json::Value::String(api_key.id.clone()),
+    );
+    custom.insert(
+        "api_key_name".to_string(),
+        serde_json::Value::String(api_key.name.clone()),
+    );
+
+    Claims {
+        sub: format!("client:{}", api_key.user_id),
+        email: None,
+        username: None,
+        roles: Vec::new(),
+        perms: api_key.scopes.clone(),
+        exp: api_key.expires_at.map_or(i64::MAX, |t| t.timestamp()),
+        iat: Some(api_key.created_at.timestamp()),
+        jti: None,
+        iss: None,
+        aud: None,
+        custom,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use axum::{routing::get, Router};
+    use chrono::{Duration, Utc};
+    use http::StatusCode;
+    use std::sync::Mutex;
+    use tower::ServiceExt;
+
+    use crate::auth::api_keys::ApiKeyGenerator;
+
+    struct MemoryStorage {
+        keys: Mutex<Vec<ApiKey>>,
+        generator: ApiKeyGenerator,
+    }
+
+    #[async_trait]
+    impl ApiKeyStorage for MemoryStorage {
+        async fn get_by_key(&self, key: &str) -> Result<Option<ApiKey>, Error> {
+            let lookup = ApiKeyGenerator::key_prefix_for_lookup(key)
+                .ok_or_else(|| Error::ValidationError("Invalid API key format".to_string()))?;
+            let Some(api_key) = self.get_by_prefix(&lookup).await? else {
+                return Ok(None);
+            };
+            Ok(self
+                .generator
+                .verify(key, &api_key.key_hash)?
+                .then_some(api_key))
+        }
+        async fn get_by_prefix(&self, prefix: &str) -> Result<Option<ApiKey>, Error> {
+            let keys = self.keys.lock().unwrap();
+            Ok(keys.iter().find(|k| k.prefix == prefix).cloned())
+        }
+        async fn get_by_id(&self, _id: &str) -> Result<Option<ApiKey>, Error> {
+            Ok(None)
+        }
+        async fn create(&self, key: &ApiKey) -> Result<(), Error> {
+            self.keys.lock().unwrap().push(key.clone());
+            Ok(())
+        }
+        async fn update_last_used(&self, _id: &str) -> Result<(), Error> {
+            Ok(())
+        }
+        async fn revoke(&self, _id: &str) -> Result<(), Error> {
+            Ok(())
+        }
+        async fn list_by_user(&self, _user_id: &str) -> Result<Vec<ApiKey>, Error> {
+            Ok(Vec::new())
+        }
+        async fn delete(&self, _id: &str) -> Result<(), Error> {
+            Ok(())
+        }
+        async fn record_usage(&self, _id: &str) -> Result<ApiKeyUsage, Error> {
+            Ok(ApiKeyUsage::default())
+        }
+        async fn get_usage(&self, _id: &str) -> Result<ApiKeyUsage, Error> {
+            Ok(ApiKeyUsage::default())
+        }
+    }
+
+    /// Store a key and return the plaintext handed to the client
+    async fn issue(storage: &MemoryStorage, revoked: bool, expires_in: Option<Duration>) -> String {
+        let (key, key_hash) = storage.generator.generate();
+        let api_key = ApiKey {
+            id: format!("key-{}", storage.keys.lock().unwrap().len()),
+            user_id: "billing-service".to_string(),
+            name: "billing".to_string(),
+            prefix: ApiKeyGenerator::key_prefix_for_lookup(&key).unwrap(),
+            key_hash,
+            scopes: vec!["invoices:read".to_string()],
+            rate_limit: None,
+            daily_quota: None,
+            monthly_quota: None,
+            is_revoked: revoked,
+            last_used_at: None,
+            expires_at: expires_in.map(|d| Utc::now() + d),
+            created_at: Utc::now(),
+        };
+        storage.create(&api_key).await.unwrap();
+        key
+    }
+
+    fn router(auth: ApiKeyAuth) -> Router {
+        Router::new()
+            .route(
+                "/whoami",
+                get(|claims: axum::Extension<Claims>| async move {
+                    format!(
+                        "{}|{}",
+                        claims.client_id().unwrap_or_default(),
+                        claims.has_permission("invoices:read")
+                    )
+                }),
+            )
+            .layer(axum::middleware::from_fn_with_state(
+                auth,
+                ApiKeyAuth::middleware,
+            ))
+    }
+
+    fn request(key: Option<&str>) -> Request<Body> {
+        let mut builder = Request::builder().uri("/whoami");
+        if let Some(key) = key {
+            builder = builder.header("X-API-Key", key);
+        }
+        builder.body(Body::empty()).unwrap()
+    }
+
+    fn storage() -> Arc<MemoryStorage> {
+        Arc::new(MemoryStorage {
+            keys: Mutex::new(Vec::new()),
+            generator: ApiKeyGenerator::new("sk_test"),
+        })
+    }
+
+    #[tokio::test]
+    async fn test_valid_key_injects_claims() {
+        let storage = storage();
+        let key = issue(&storage, false, Some(Duration::hours(1))).await;
+        let auth = ApiKeyAuth::new(&ApiKeyConfig::default(), storage);
+
+        let response = router(auth).oneshot(request(Some(&key))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        assert_eq!(&body[..], b"billing-service|true");
+    }
+
+    #[tokio::test]
+    async fn test_rejects_unknown_revoked_and_expired_keys() {
+        let storage = storage();
+        let revoked = issue(&storage, true, None).await;
+        let expired = issue(&storage, false, Some(Duration::hours(-1))).await;
+        let auth = ApiKeyAuth::new(&ApiKeyConfig::default(), storage);
+
+        for key in [
+            revoked.as_str(),
+            expired.as_str(),
+            "sk_test_AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAA",
+            "garbage",
+        ] {
+            let response = router(auth.clone())
+                .oneshot(request(Some(key)))
+                .await
+                .unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED, "key {key}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_missing_key_depends_on_bearer_fallback() {
+        let auth = ApiKeyAuth::new(&ApiKeyConfig::default(), storage());
+        let response = router(auth.clone()).oneshot(request(None)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+        // With fallback the request reaches the (here absent) token middleware;
+        // the handler then fails to extract Claims instead of a 401 from us.
+        let response = router(auth.with_bearer_fallback(true))
+            .oneshot(request(None))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+
+    #[test]
+    fn test_claims_for_key_without_expiry_never_expire() {
+        let api_key = ApiKey {
+            id: "key-1".to_string(),
+            user_id: "svc".to_string(),
+            name: "svc".to_string(),
+            prefix: "sk_test_abcdefgh".to_string(),
+            key_hash: String::new(),
+            scopes: vec!["read".to_string()],
+            rate_limit: None,
+            daily_quota: None,
+            monthly_quota: None,
+            is_revoked: false,
+            last_used_at: None,
+            expires_at: None,
+            created_at: Utc::now(),
+        };
+        let claims = claims_for_key(&api_key);
+        assert_eq!(claims.exp, i64::MAX);
+        assert!(claims.is_client());
+        assert_eq!(claims.perms, ["read"]);
+        assert_eq!(
+            claims.custom_claim("api_key_id"),
+            Some(&serde_json::json!("key-1"))
+        );
+    }
+}
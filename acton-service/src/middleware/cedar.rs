@@ -2,6 +2,24 @@
 //!
 //! This middleware integrates AWS Cedar policy-based authorization into acton-service.
 //! It validates authorization requests against Cedar policies after JWT authentication.
+//!
+//! The principal is taken from the JWT [`Claims`] when present. A request with
+//! no claims but a [`CallerIdentity`](crate::caller_auth::CallerIdentity) — a
+//! caller admitted on its client certificate by
+//! [`CallerAuthLayer`](crate::caller_auth::CallerAuthLayer) — is authorized as
+//! a `Workload` principal named by its `subjectAltName`, so mTLS callers can be
+//! authorized by policy without a bearer token:
+//!
+//! ```text
+//! permit(
+//!     principal == Workload::"spiffe://prod.local/ns/payments/sa/api",
+//!     action == Action::"POST /refunds",
+//!     resource
+//! );
+//!
+//! permit(principal is Workload, action, resource)
+//! when { principal has trustDomain && principal.trustDomain == "prod.local" };
+//! ```
 
 use axum::{
     body::Body,
@@ -241,6 +259,20 @@ impl CedarAuthz {
         resource: &EntityUid,
         context: Context,
         claims: &Claims,
+    ) -> Result<Decision, Error> {
+        let entities = build_entities(claims)?;
+        self.evaluate(principal, action, resource, context, &entities)
+            .await
+    }
+
+    /// Evaluate a request against a prepared entity set.
+    async fn evaluate(
+        &self,
+        principal: &EntityUid,
+        action: &EntityUid,
+        resource: &EntityUid,
+        context: Context,
+        entities: &Entities,
     ) -> Result<Decision, Error> {
         let cedar_request = CedarRequest::new(
             principal.clone(),
@@ -260,11 +292,10 @@ impl CedarAuthz {
         }
 
         let policy_set = self.policy_set.read().await;
-        let entities = build_entities(claims)?;
 
         let raw_decision = self
             .authorizer
-            .is_authorized(&cedar_request, &policy_set, &entities)
+            .is_authorized(&cedar_request, &policy_set, entities)
             .decision();
 
         // Cache decision (if enabled).
@@ -320,17 +351,15 @@ impl CedarAuthz {
             return Ok(next.run(request).await);
         }
 
-        // Extract JWT claims (inserted by JWT middleware)
-        let claims = request
-            .extensions()
-            .get::<Claims>()
-            .ok_or_else(|| {
-                Error::Unauthorized(
-                    "Missing JWT claims. Ensure JWT middleware runs before Cedar middleware."
-                        .to_string(),
-                )
-            })?
-            .clone();
+        // Extract JWT claims (inserted by JWT middleware), or the certificate
+        // identity caller authorization established in their place
+        let caller = RequestPrincipal::from_extensions(request.extensions()).ok_or_else(|| {
+            Error::Unauthorized(
+                "Missing JWT claims or caller identity. Ensure JWT or caller authorization \
+                 middleware runs before Cedar middleware."
+                    .to_string(),
+            )
+        })?;
 
         #[cfg(feature = "audit")]
         let audit_logger = request
@@ -352,7 +381,7 @@ impl CedarAuthz {
                     .get("user-agent")
                     .and_then(|v| v.to_str().ok())
                     .map(String::from),
-                subject: Some(claims.sub.clone()),
+                subject: Some(caller.subject().to_string()),
                 request_id: request
                     .headers()
                     .get("x-request-id")
@@ -365,13 +394,14 @@ impl CedarAuthz {
         let method = request.method().clone();
 
         // Build Cedar authorization request
-        let principal = build_principal(&claims)?;
+        let principal = caller.uid()?;
         let action = build_action_http(&method, &request, authz.path_normalizer)?;
-        let context = build_context_http(request.headers(), &claims)?;
+        let context = build_context_http(request.headers(), &caller)?;
         let resource = build_resource()?;
+        let entities = caller.entities()?;
 
         match authz
-            .authorize(&principal, &action, &resource, context, &claims)
+            .evaluate(&principal, &action, &resource, context, &entities)
             .await?
         {
             Decision::Allow => Ok(next.run(request).await),
@@ -448,6 +478,141 @@ fn build_principal(claims: &Claims) -> Result<EntityUid, Error> {
     Ok(principal)
 }
 
+/// Who a request is authorized as
+enum RequestPrincipal {
+    /// A caller authenticated by a JWT, named by its claims
+    Token(Box<Claims>),
+
+    /// A caller admitted on its client certificate alone, named by the
+    /// `subjectAltName` caller authorization matched
+    #[cfg(feature = "tls")]
+    Workload(crate::caller_auth::CallerIdentity),
+}
+
+impl RequestPrincipal {
+    /// The principal for this request
+    ///
+    /// Claims win when both are present: under `mode = "mtls"` the certificate
+    /// is an additional requirement, not a replacement for the token, and the
+    /// token is the finer-grained identity.
+    fn from_extensions(extensions: &http::Extensions) -> Option<Self> {
+        if let Some(claims) = extensions.get::<Claims>() {
+            return Some(Self::Token(Box::new(claims.clone())));
+        }
+        #[cfg(feature = "tls")]
+        if let Some(identity) = extensions.get::<crate::caller_auth::CallerIdentity>() {
+            return Some(Self::Workload(identity.clone()));
+        }
+        None
+    }
+
+    /// The principal's name, for audit records
+    fn subject(&self) -> &str {
+        match self {
+            Self::Token(claims) => &claims.sub,
+            #[cfg(feature = "tls")]
+            Self::Workload(identity) => identity.san().value(),
+        }
+    }
+
+    fn uid(&self) -> Result<EntityUid, Error> {
+        match self {
+            Self::Token(claims) => build_principal(claims),
+            #[cfg(feature = "tls")]
+            Self::Workload(identity) => build_workload_principal(identity),
+        }
+    }
+
+    fn entities(&self) -> Result<Entities, Error> {
+        match self {
+            Self::Token(claims) => build_entities(claims),
+            #[cfg(feature = "tls")]
+            Self::Workload(identity) => build_workload_entities(identity),
+        }
+    }
+
+    /// Add the principal's roles, permissions and identity details to a
+    /// request context
+    ///
+    /// A workload has no roles or permissions, but the keys are still present
+    /// (and empty) so a policy written against `context.roles` evaluates to a
+    /// denial rather than an evaluation error.
+    fn insert_context(&self, context_map: &mut serde_json::Map<String, serde_json::Value>) {
+        match self {
+            Self::Token(claims) => {
+                // Add user roles
+                context_map.insert("roles".to_string(), json!(claims.roles));
+
+                // Add permissions
+                context_map.insert("permissions".to_string(), json!(claims.perms));
+
+                // Add email if present
+                if let Some(email) = &claims.email {
+                    context_map.insert("email".to_string(), json!(email));
+                }
+
+                // Add username if present
+                if let Some(username) = &claims.username {
+                    context_map.insert("username".to_string(), json!(username));
+                }
+            }
+            #[cfg(feature = "tls")]
+            Self::Workload(_) => {
+                context_map.insert("roles".to_string(), json!([]));
+                context_map.insert("permissions".to_string(), json!([]));
+            }
+        }
+    }
+}
+
+/// Build Cedar principal from a certificate-authorized caller
+///
+/// Principal format: Workload::"spiffe://prod.local/ns/payments/sa/api" or
+/// Workload::"reporter.internal"
+#[cfg(feature = "tls")]
+fn build_workload_principal(
+    identity: &crate::caller_auth::CallerIdentity,
+) -> Result<EntityUid, Error> {
+    let type_name = "Workload"
+        .parse::<cedar_policy::EntityTypeName>()
+        .map_err(|e| Error::Internal(format!("Invalid principal type: {}", e)))?;
+    Ok(EntityUid::from_type_name_and_id(
+        type_name,
+        cedar_policy::EntityId::new(identity.san().value()),
+    ))
+}
+
+/// Build the entity for a certificate-authorized caller
+///
+/// Attributes: `san` and `sanKind` (`"DNS"` or `"URI"`) always, plus
+/// `trustDomain` and `path` when the caller is a SPIFFE workload.
+#[cfg(feature = "tls")]
+fn build_workload_entities(
+    identity: &crate::caller_auth::CallerIdentity,
+) -> Result<Entities, Error> {
+    use serde_json::Value;
+
+    let mut attrs = serde_json::Map::new();
+    attrs.insert("san".to_string(), json!(identity.san().value()));
+    attrs.insert(
+        "sanKind".to_string(),
+        json!(identity.san().kind().to_string()),
+    );
+    if let Some(spiffe_id) = identity.spiffe_id() {
+        attrs.insert("trustDomain".to_string(), json!(spiffe_id.trust_domain()));
+        attrs.insert("path".to_string(), json!(spiffe_id.path()));
+    }
+
+    let entity = json!({
+        "uid": { "type": "Workload", "id": identity.san().value() },
+        "attrs": attrs,
+        "parents": []
+    });
+
+    Entities::from_json_value(Value::Array(vec![entity]), None)
+        .map_err(|e| Error::Internal(format!("Failed to build entities: {}", e)))
+}
+
 /// Build Cedar action from HTTP method and request
 ///
 /// Uses Axum's MatchedPath to get the route pattern (most accurate).
@@ -507,25 +672,12 @@ fn normalize_path_generic(path: &str) -> String {
     path.to_string()
 }
 
-/// Build Cedar context from HTTP headers and claims
-fn build_context_http(headers: &HeaderMap, claims: &Claims) -> Result<Context, Error> {
+/// Build Cedar context from HTTP headers and the request principal
+fn build_context_http(headers: &HeaderMap, caller: &RequestPrincipal) -> Result<Context, Error> {
     let mut context_map = serde_json::Map::new();
 
-    // Add user roles
-    context_map.insert("roles".to_string(), json!(claims.roles));
-
-    // Add permissions
-    context_map.insert("permissions".to_string(), json!(claims.perms));
-
-    // Add email if present
-    if let Some(email) = &claims.email {
-        context_map.insert("email".to_string(), json!(email));
-    }
-
-    // Add username if present
-    if let Some(username) = &claims.username {
-        context_map.insert("username".to_string(), json!(username));
-    }
+    // Add roles, permissions and identity details
+    caller.insert_context(&mut context_map);
 
     // Add timestamp
     let now = chrono::Utc::now();
@@ -726,10 +878,12 @@ use tower::{Layer, Service};
 /// shape of tonic's generated servers, so a wrapped service can be registered
 /// with [`GrpcServicesBuilder::add_service`](crate::grpc::server::GrpcServicesBuilder::add_service)
 /// (the `NamedService` impl forwards the inner service's name). Authorization
-/// requires [`Claims`] in the request extensions, so an HTTP-level
-/// authentication layer such as
-/// [`GrpcTokenAuthLayer`](crate::grpc::middleware::GrpcTokenAuthLayer) must
-/// wrap this layer (run before it). Denials are returned as gRPC status
+/// requires [`Claims`] or a
+/// [`CallerIdentity`](crate::caller_auth::CallerIdentity) in the request
+/// extensions, so an HTTP-level authentication layer such as
+/// [`GrpcTokenAuthLayer`](crate::grpc::middleware::GrpcTokenAuthLayer) or
+/// [`CallerAuthLayer`](crate::caller_auth::CallerAuthLayer) must wrap this
+/// layer (run before it). Denials are returned as gRPC status
 /// responses (`PERMISSION_DENIED`), not transport errors.
 ///
 /// # Example
@@ -847,31 +1001,34 @@ async fn authorize_grpc_request(
         return Ok(());
     }
 
-    // Extract claims from request extensions (set by a token auth layer)
-    let claims = extensions
-        .get::<Claims>()
-        .ok_or_else(|| {
-            Status::unauthenticated(
-                "Missing authentication claims. Ensure a token authentication \
-                 layer runs before the Cedar layer.",
-            )
-        })?
-        .clone();
+    // Extract claims (set by a token auth layer) or the certificate identity
+    // (set by a caller authorization layer) from request extensions
+    let caller = RequestPrincipal::from_extensions(extensions).ok_or_else(|| {
+        Status::unauthenticated(
+            "Missing authentication claims or caller identity. Ensure a token \
+             authentication or caller authorization layer runs before the Cedar layer.",
+        )
+    })?;
 
     // Build Cedar authorization request
-    let principal =
-        build_principal(&claims).map_err(|_| Status::internal("Failed to build principal"))?;
+    let principal = caller
+        .uid()
+        .map_err(|_| Status::internal("Failed to build principal"))?;
 
     let action =
         build_action_grpc(method_path).map_err(|_| Status::internal("Failed to build action"))?;
 
-    let context = build_context_grpc(headers, &claims)
+    let context = build_context_grpc(headers, &caller)
         .map_err(|_| Status::internal("Failed to build context"))?;
 
     let resource = build_resource().map_err(|_| Status::internal("Failed to parse resource"))?;
 
+    let entities = caller
+        .entities()
+        .map_err(|_| Status::internal("Failed to build entities"))?;
+
     let decision = authz
-        .authorize(&principal, &action, &resource, context, &claims)
+        .evaluate(&principal, &action, &resource, context, &entities)
         .await
         .map_err(|e| Status::internal(format!("Cedar authorization error: {}", e)))?;
 
@@ -892,7 +1049,7 @@ async fn authorize_grpc_request(
                             .get("user-agent")
                             .and_then(|v| v.to_str().ok())
                             .map(String::from),
-                        subject: Some(claims.sub.clone()),
+                        subject: Some(caller.subject().to_string()),
                         request_id: headers
                             .get("x-request-id")
                             .and_then(|v| v.to_str().ok())
@@ -925,29 +1082,16 @@ fn build_action_grpc(method_path: &str) -> Result<EntityUid, Error> {
     Ok(action)
 }
 
-/// Build Cedar context from gRPC request headers and claims
+/// Build Cedar context from gRPC request headers and the request principal
 ///
 /// gRPC metadata is carried in HTTP headers, so reading headers here is
 /// equivalent to reading tonic's `MetadataMap`.
 #[cfg(feature = "grpc")]
-fn build_context_grpc(headers: &HeaderMap, claims: &Claims) -> Result<Context, Error> {
+fn build_context_grpc(headers: &HeaderMap, caller: &RequestPrincipal) -> Result<Context, Error> {
     let mut context_map = serde_json::Map::new();
 
-    // Add user roles
-    context_map.insert("roles".to_string(), json!(claims.roles));
-
-    // Add permissions
-    context_map.insert("permissions".to_string(), json!(claims.perms));
-
-    // Add email if present
-    if let Some(email) = &claims.email {
-        context_map.insert("email".to_string(), json!(email));
-    }
-
-    // Add username if present
-    if let Some(username) = &claims.username {
-        context_map.insert("username".to_string(), json!(username));
-    }
+    // Add roles, permissions and identity details
+    caller.insert_context(&mut context_map);
 
     // Add timestamp
     let now = chrono::Utc::now();
@@ -1036,6 +1180,110 @@ mod tests {
         assert_eq!(principal.to_string(), r#"User::"user:123""#);
     }
 
+    #[cfg(feature = "tls")]
+    mod workload_principal {
+        use super::super::*;
+        use crate::caller_auth::{CallerIdentity, CallerSan};
+
+        fn workload(san: CallerSan) -> RequestPrincipal {
+            let mut extensions = http::Extensions::new();
+            extensions.insert(CallerIdentity::new(san, true));
+            RequestPrincipal::from_extensions(&extensions).expect("a certificate identity")
+        }
+
+        async fn decide(policy: &str, caller: &RequestPrincipal) -> Decision {
+            let policy_file = tempfile::NamedTempFile::new().unwrap();
+            std::fs::write(policy_file.path(), policy).unwrap();
+            let authz = CedarAuthz::builder(CedarConfig {
+                enabled: true,
+                policy_path: policy_file.path().to_path_buf(),
+                hot_reload: false,
+                hot_reload_interval_secs: 60,
+                cache_enabled: false,
+                cache_ttl_secs: 60,
+                fail_open: false,
+            })
+            .build()
+            .await
+            .unwrap();
+
+            authz
+                .evaluate(
+                    &caller.uid().unwrap(),
+                    &r#"Action::"POST /refunds""#.parse().unwrap(),
+                    &build_resource().unwrap(),
+                    build_context_http(&HeaderMap::new(), caller).unwrap(),
+                    &caller.entities().unwrap(),
+                )
+                .await
+                .unwrap()
+        }
+
+        #[test]
+        fn a_certificate_caller_is_a_workload_named_by_its_san() {
+            let caller =
+                workload(CallerSan::uri("spiffe://prod.local/ns/payments/sa/api").unwrap());
+            assert_eq!(
+                caller.uid().unwrap().to_string(),
+                r#"Workload::"spiffe://prod.local/ns/payments/sa/api""#
+            );
+            assert_eq!(caller.subject(), "spiffe://prod.local/ns/payments/sa/api");
+        }
+
+        #[test]
+        fn claims_take_precedence_over_a_certificate_identity() {
+            let mut extensions = http::Extensions::new();
+            extensions.insert(CallerIdentity::new(
+                CallerSan::dns("reporter.internal").unwrap(),
+                false,
+            ));
+            extensions.insert(Claims {
+                sub: "user:123".to_string(),
+                email: None,
+                username: None,
+                roles: vec![],
+                perms: vec![],
+                exp: 0,
+                iat: None,
+                jti: None,
+                iss: None,
+                aud: None,
+                custom: Default::default(),
+            });
+
+            let caller = RequestPrincipal::from_extensions(&extensions).unwrap();
+            assert_eq!(caller.uid().unwrap().to_string(), r#"User::"user:123""#);
+        }
+
+        #[tokio::test]
+        async fn policies_can_scope_workloads_by_trust_domain() {
+            let policy = r#"
+                permit(principal is Workload, action, resource)
+                when { principal has trustDomain && principal.trustDomain == "prod.local" };
+            "#;
+
+            let prod = workload(CallerSan::uri("spiffe://prod.local/ns/payments/sa/api").unwrap());
+            assert_eq!(decide(policy, &prod).await, Decision::Allow);
+
+            let staging =
+                workload(CallerSan::uri("spiffe://staging.local/ns/payments/sa/api").unwrap());
+            assert_eq!(decide(policy, &staging).await, Decision::Deny);
+
+            let dns = workload(CallerSan::dns("reporter.internal").unwrap());
+            assert_eq!(decide(policy, &dns).await, Decision::Deny);
+        }
+
+        #[tokio::test]
+        async fn role_policies_deny_a_workload_rather_than_erroring() {
+            let policy = r#"
+                permit(principal, action, resource) when { context.roles.contains("admin") };
+            "#;
+
+            let caller = workload(CallerSan::dns("reporter.internal").unwrap());
+            assert_eq!(decide(policy, &caller).await, Decision::Deny);
+        }
+    }
+
     // Note: test_build_action_http removed as it requires constructing a full Request<Body>
     // which is complex. The path normalization logic is tested via test_normalize_path_generic.
     // Integration tests should cover the full middleware flow.
@@ -1159,6 +1407,27 @@ mod tests {
             assert_eq!(grpc_status(&resp), Some("16"));
         }
 
+        #[cfg(feature = "tls")]
+        #[tokio::test]
+        async fn a_certificate_identity_stands_in_for_claims() {
+            use crate::caller_auth::{CallerIdentity, CallerSan};
+
+            let authz = test_authz(
+                r#"permit(principal == Workload::"reporter.internal", action, resource);"#,
+                true,
+            )
+            .await;
+            let mut svc = CedarAuthzLayer::new(authz).layer(TestSvc);
+            let mut req = grpc_request("/test.v1.TestService/Do", None);
+            req.extensions_mut().insert(CallerIdentity::new(
+                CallerSan::dns("reporter.internal").unwrap(),
+                true,
+            ));
+
+            let resp = svc.call(req).await.unwrap();
+            assert_eq!(resp.body(), "ok");
+        }
+
         #[tokio::test]
         async fn disabled_cedar_passes_through() {
             let authz = test_authz("", false).await;
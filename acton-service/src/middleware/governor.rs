@@ -45,6 +45,14 @@ use crate::error::Error;
 #[cfg(feature = "governor")]
 use crate::middleware::{normalize_path, Claims, CompiledRoutePatterns};
 
+#[cfg(feature = "governor")]
+use super::rate_limit::ApiKeyLimit;
+
+#[cfg(all(feature = "governor", feature = "auth"))]
+use crate::auth::api_keys::{ApiKey, ApiKeyQuota, ApiKeyStorage};
+#[cfg(all(feature = "governor", feature = "auth"))]
+use crate::middleware::api_key::DefaultKeyRateLimit;
+
 /// Configuration for governor-based rate limiting
 #[derive(Debug, Clone)]
 pub struct GovernorConfig {
@@ -172,8 +180,11 @@ pub struct GovernorRateLimit {
     route_patterns: Arc<CompiledRoutePatterns>,
     /// Per-route rate limiters, keyed by normalized route path
     route_limiters: Arc<DashMap<String, Arc<GovernorLimiter>>>,
-    /// Global rate limiters, keyed by user/client/IP identifier
+    /// Global rate limiters, keyed by user/client/API key/IP identifier
     global_limiters: Arc<DashMap<String, Arc<GovernorLimiter>>>,
+    /// Usage counters for API key daily and monthly quotas
+    #[cfg(feature = "auth")]
+    api_key_storage: Option<Arc<dyn ApiKeyStorage>>,
 }
 
 #[cfg(feature = "governor")]
@@ -186,17 +197,34 @@ impl GovernorRateLimit {
             route_patterns: Arc::new(route_patterns),
             route_limiters: Arc::new(DashMap::new()),
             global_limiters: Arc::new(DashMap::new()),
+            #[cfg(feature = "auth")]
+            api_key_storage: None,
         }
     }
 
+    /// Enforce daily and monthly API key quotas
+    ///
+    /// Requests carrying an authenticated [`ApiKey`] in their extensions are
+    /// counted against the key's allowances in `storage`. Without storage,
+    /// API keys still get their per-minute limit but no quota is enforced.
+    #[cfg(feature = "auth")]
+    pub fn with_api_key_storage(mut self, storage: Arc<dyn ApiKeyStorage>) -> Self {
+        self.api_key_storage = Some(storage);
+        self
+    }
+
     /// Middleware function to enforce rate limits
     ///
     /// Checks rate limits in the following order:
     /// 1. Per-route limits (if configured for the request path)
-    /// 2. Global per-user/per-client limits (if JWT/PASETO claims present)
-    /// 3. Per-IP fallback for anonymous requests (when no route-specific limit
+    /// 2. Per-API-key limits (if an authenticated `ApiKey` is in the extensions)
+    /// 3. Global per-user/per-client limits (if JWT/PASETO claims present)
+    /// 4. Per-IP fallback for anonymous requests (when no route-specific limit
     ///    matches and no claims are present)
     ///
+    /// API keys with a daily or monthly allowance are then counted against
+    /// it; an exhausted allowance is rejected with `429 QUOTA_EXCEEDED`.
+    ///
     /// The path used for route matching is the request URI as seen by this
     /// layer. When the layer is attached to the outer router (the default
     /// auto-apply position), this is the full pre-nest path. When the
@@ -220,6 +248,25 @@ impl GovernorRateLimit {
             .unwrap_or_else(|| request.uri().path().to_string());
 
         let claims = request.extensions().get::<Claims>().cloned();
+        #[cfg(feature = "auth")]
+        let api_key = request
+            .extensions()
+            .get::<ApiKey>()
+            .map(|key| {
+                let default_rpm = request
+                    .extensions()
+                    .get::<DefaultKeyRateLimit>()
+                    .map(|default| default.0);
+                let quota = ApiKeyQuota::resolve(key, &rate_limit.config, default_rpm);
+                (key.id.clone(), quota)
+            });
+        #[cfg(feature = "auth")]
+        let api_key_limit = api_key.as_ref().map(|(id, quota)| ApiKeyLimit {
+            id: id.as_str(),
+            requests_per_minute: quota.requests_per_minute,
+        });
+        #[cfg(not(feature = "auth"))]
+        let api_key_limit = None;
         // Resolve the peer address from whichever connect-info the listener
         // installed: `ConnectInfo<SocketAddr>` on plain TCP, or
         // `ConnectInfo<TlsConnectInfo>` on a directly-terminated TLS listener.
@@ -233,13 +280,32 @@ impl GovernorRateLimit {
         );
 
         // Check rate limit and get result for headers
-        let result = match rate_limit.check_rate_limit(&method, &path, claims.as_ref(), client_ip) {
+        #[cfg_attr(not(feature = "auth"), allow(unused_mut))]
+        let mut outcome =
+            rate_limit.check_rate_limit(&method, &path, claims.as_ref(), api_key_limit, client_ip);
+
+        #[cfg(feature = "auth")]
+        if outcome.is_ok() {
+            if let (Some((key_id, quota)), Some(storage)) = (&api_key, &rate_limit.api_key_storage)
+            {
+                if let Err(e) = quota.consume(storage.as_ref(), key_id).await {
+                    outcome = Err(e);
+                }
+            }
+        }
+
+        let result = match outcome {
             Ok(result) => result,
             Err(e) => {
+                #[cfg(feature = "audit")]
+                let denied = matches!(e, Error::RateLimitExceeded);
+                #[cfg(all(feature = "audit", feature = "auth"))]
+                let denied = denied || matches!(e, Error::QuotaExceeded { .. });
+
                 // Mirror the Redis rate limiter: rejections are audit-visible
                 // as HttpRequestDenied (issue #16).
                 #[cfg(feature = "audit")]
-                if matches!(e, Error::RateLimitExceeded) {
+                if denied {
                     if let Some(logger) = request
                         .extensions()
                         .get::<crate::audit::AuditLogger>()
@@ -279,6 +345,7 @@ impl GovernorRateLimit {
         method: &str,
         path: &str,
         claims: Option<&Claims>,
+        api_key: Option<ApiKeyLimit<'_>>,
         client_ip: Option<IpAddr>,
     ) -> Result<GovernorRateLimitResult, Error> {
         let normalized_path = normalize_path(path);
@@ -291,8 +358,11 @@ impl GovernorRateLimit {
             );
 
             let key = if route_config.per_user {
-                // Per-user route limit - prefer claims, fall back to IP, then "unknown"
-                if let Some(claims) = claims {
+                // Per-user route limit - prefer the API key, then claims, then
+                // fall back to IP, then "unknown"
+                if let Some(api_key) = &api_key {
+                    format!("route:{}:api_key:{}", normalized_path, api_key.id)
+                } else if let Some(claims) = claims {
                     format!("route:{}:user:{}", normalized_path, claims.sub)
                 } else if let Some(ip) = client_ip {
                     format!("route:{}:ip:{}", normalized_path, ip)
@@ -312,6 +382,15 @@ impl GovernorRateLimit {
             );
         }
 
+        // API keys carry their own per-minute limit
+        if let Some(api_key) = api_key {
+            let limit = api_key.requests_per_minute;
+            let burst_size = (limit / 10).max(1);
+            let key = format!("governor:api_key:{}", api_key.id);
+
+            return self.check_with_limiter(&self.global_limiters, &key, limit, burst_size);
+        }
+
         // Fall back to global user/client limits
         if let Some(claims) = claims {
             let (key, limit) = if claims.is_user() {
@@ -559,6 +638,7 @@ mod tests {
                 requests_per_minute: 10,
                 burst_size: 2,
                 per_user: true,
+                algorithm: None,
             },
         );
 
@@ -618,6 +698,7 @@ mod tests {
                 requests_per_minute: 10,
                 burst_size: 1,
                 per_user: false, // global to avoid claims/IP confusion
+                algorithm: None,
             },
         );
 
@@ -628,11 +709,11 @@ mod tests {
         let rl = GovernorRateLimit::new(config);
 
         // Full path matches.
-        let first = rl.check_rate_limit("POST", "/api/v1/uploads", None, None);
+        let first = rl.check_rate_limit("POST", "/api/v1/uploads", None, None, None);
         assert!(first.is_ok());
 
         // The 2nd hit on the same global route bucket trips the burst=1 limit.
-        let second = rl.check_rate_limit("POST", "/api/v1/uploads", None, None);
+        let second = rl.check_rate_limit("POST", "/api/v1/uploads", None, None, None);
         assert!(matches!(second, Err(Error::RateLimitExceeded)));
 
         // Post-nest path on a fresh middleware does NOT match the route key
@@ -647,13 +728,14 @@ mod tests {
                         requests_per_minute: 10,
                         burst_size: 1,
                         per_user: false,
+                        algorithm: None,
                     },
                 );
                 m
             },
             ..RateLimitConfig::default()
         });
-        let post_nest = rl2.check_rate_limit("POST", "/uploads", None, None);
+        let post_nest = rl2.check_rate_limit("POST", "/uploads", None, None, None);
         assert!(
             post_nest.is_ok(),
             "post-nest path must not match the full-path config key"
@@ -678,15 +760,15 @@ mod tests {
         let ip_b = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 2));
 
         // First request from ip_a succeeds.
-        let r1 = rl.check_rate_limit("GET", "/whatever", None, Some(ip_a));
+        let r1 = rl.check_rate_limit("GET", "/whatever", None, None, Some(ip_a));
         assert!(r1.is_ok());
 
         // Second request from ip_a is rate-limited (burst exhausted).
-        let r2 = rl.check_rate_limit("GET", "/whatever", None, Some(ip_a));
+        let r2 = rl.check_rate_limit("GET", "/whatever", None, None, Some(ip_a));
         assert!(matches!(r2, Err(Error::RateLimitExceeded)));
 
         // ip_b gets a fresh bucket.
-        let r3 = rl.check_rate_limit("GET", "/whatever", None, Some(ip_b));
+        let r3 = rl.check_rate_limit("GET", "/whatever", None, None, Some(ip_b));
         assert!(r3.is_ok());
     }
 
@@ -702,10 +784,41 @@ mod tests {
         };
         let rl = GovernorRateLimit::new(config);
 
-        let first = rl.check_rate_limit("GET", "/x", None, None);
+        let first = rl.check_rate_limit("GET", "/x", None, None, None);
         assert!(first.is_ok());
 
-        let second = rl.check_rate_limit("GET", "/x", None, None);
+        let second = rl.check_rate_limit("GET", "/x", None, None, None);
         assert!(matches!(second, Err(Error::RateLimitExceeded)));
     }
+
+    #[cfg(feature = "governor")]
+    #[test]
+    fn test_api_key_gets_its_own_bucket() {
+        use super::ApiKeyLimit;
+        use std::net::{IpAddr, Ipv4Addr};
+
+        let config = RateLimitConfig {
+            per_user_rpm: 1,
+            ..RateLimitConfig::default()
+        };
+        let rl = GovernorRateLimit::new(config);
+        let ip = IpAddr::V4(Ipv4Addr::new(10, 0, 0, 1));
+        let key = |id| {
+            Some(ApiKeyLimit {
+                id,
+                requests_per_minute: 1,
+            })
+        };
+
+        // The key's limit applies, not the per-IP one behind it
+        assert!(rl.check_rate_limit("GET", "/x", None, key("a"), Some(ip)).is_ok());
+        assert!(matches!(
+            rl.check_rate_limit("GET", "/x", None, key("a"), Some(ip)),
+            Err(Error::RateLimitExceeded)
+        ));
+
+        // Another key from the same address has its own bucket
+        assert!(rl.check_rate_limit("GET", "/x", None, key("b"), Some(ip)).is_ok());
+        assert!(rl.check_rate_limit("GET", "/x", None, None, Some(ip)).is_ok());
+    }
 }
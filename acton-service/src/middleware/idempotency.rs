@@ -0,0 +1,596 @@
+//! Idempotency-Key middleware
+//!
+//! Makes retries of unsafe requests (POST, PUT, PATCH, DELETE) safe. The
+//! first response for an `Idempotency-Key` is stored in Redis and replayed
+//! verbatim for later requests carrying the same key:
+//!
+//! - a duplicate that arrives while the original is still running gets 409
+//! - a key reused with a different method, path or body gets 422
+//! - 5xx responses are not stored, so the client may retry them
+//!
+//! Keys are scoped to the authenticated principal (`Claims::sub`), so two
+//! callers cannot observe each other's responses by guessing keys.
+//!
+//! The request body is buffered to fingerprint it, up to `max_request_bytes`
+//! (413 beyond that). The layer sits outside the service's body limit, so
+//! the builder caps `max_request_bytes` at `body_limit_mb`.
+
+use std::ops::DerefMut;
+use std::sync::Arc;
+use std::time::Duration;
+
+use axum::{
+    body::{Body, Bytes, HttpBody},
+    extract::{Request, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use deadpool_redis::Pool as RedisPool;
+use serde::{Deserialize, Serialize};
+use tokio::sync::RwLock;
+use tracing::{debug, warn};
+
+use super::route_matcher::{normalize_path, CompiledRoutePatterns};
+use crate::{
+    config::{IdempotencyConfig, RouteIdempotencyConfig},
+    error::Error,
+    middleware::Claims,
+};
+
+const KEY_PREFIX: &str = "idempotency:";
+
+/// Longest accepted idempotency key
+const MAX_KEY_LEN: usize = 255;
+
+/// Response header marking a replayed response
+pub const IDEMPOTENT_REPLAYED_HEADER: &str = "idempotent-replayed";
+
+/// Headers that describe the original connection, not the response
+const SKIPPED_HEADERS: [HeaderName; 4] = [
+    header::CONNECTION,
+    header::CONTENT_LENGTH,
+    header::DATE,
+    header::TRANSFER_ENCODING,
+];
+
+/// What Redis holds for a key
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+enum StoredRecord {
+    /// The original request is still running
+    InFlight { fingerprint: String },
+    /// The original request finished; its response is replayable
+    Completed {
+        fingerprint: String,
+        status: u16,
+        headers: Vec<(String, String)>,
+        /// Base64-encoded response body
+        body: String,
+    },
+}
+
+impl StoredRecord {
+    fn fingerprint(&self) -> &str {
+        match self {
+            Self::InFlight { fingerprint } | Self::Completed { fingerprint, .. } => fingerprint,
+        }
+    }
+}
+
+/// Effective settings for one request after route overrides
+#[derive(Debug, PartialEq, Eq)]
+struct RoutePolicy {
+    required: bool,
+    ttl_secs: u64,
+}
+
+/// Idempotency-Key middleware state
+#[derive(Clone)]
+pub struct Idempotency {
+    config: Arc<IdempotencyConfig>,
+    route_patterns: Arc<CompiledRoutePatterns<RouteIdempotencyConfig>>,
+    redis_pool: Arc<RwLock<Option<RedisPool>>>,
+}
+
+impl Idempotency {
+    /// Create a new idempotency middleware with a Redis backend
+    pub fn new(config: IdempotencyConfig, redis_pool: RedisPool) -> Self {
+        Self::with_shared_pool(config, Arc::new(RwLock::new(Some(redis_pool))))
+    }
+
+    /// Create from the service's shared Redis pool storage, which the pool
+    /// agent fills in once connected
+    pub(crate) fn with_shared_pool(
+        config: IdempotencyConfig,
+        redis_pool: Arc<RwLock<Option<RedisPool>>>,
+    ) -> Self {
+        let route_patterns = CompiledRoutePatterns::compile(&config.routes);
+        Self {
+            config: Arc::new(config),
+            route_patterns: Arc::new(route_patterns),
+            redis_pool,
+        }
+    }
+
+    /// Middleware function enforcing idempotency keys
+    pub async fn middleware(
+        State(idempotency): State<Self>,
+        request: Request<Body>,
+        next: Next,
+    ) -> Result<Response, Error> {
+        if !is_unsafe_method(request.method()) {
+            return Ok(next.run(request).await);
+        }
+
+        let method = request.method().as_str().to_string();
+        let normalized_path = normalize_path(request.uri().path());
+        let Some(policy) = idempotency.policy(&method, &normalized_path) else {
+            return Ok(next.run(request).await);
+        };
+
+        let key = match request.headers().get(idempotency.config.header.as_str()) {
+            Some(value) => validate_key(value)?.to_string(),
+            None if policy.required => {
+                return Err(Error::BadRequest(format!(
+                    "Missing {} header",
+                    idempotency.config.header
+                )));
+            }
+            None => return Ok(next.run(request).await),
+        };
+
+        let principal = request
+            .extensions()
+            .get::<Claims>()
+            .map(|claims| claims.sub.clone());
+        let redis_key = redis_key(principal.as_deref(), &key);
+
+        let (parts, body) = request.into_parts();
+        let body = match read_body(&parts.headers, body, idempotency.config.max_request_bytes).await
+        {
+            Ok(body) => body,
+            Err(status) => return Ok(status.into_response()),
+        };
+        let path_and_query = parts
+            .uri
+            .path_and_query()
+            .map(|pq| pq.as_str())
+            .unwrap_or("/");
+        let fingerprint = fingerprint(&method, path_and_query, &body);
+
+        let pool = idempotency.pool().await?;
+        let mut conn = pool.get().await.map_err(|e| {
+            Error::Redis(Box::new(redis::RedisError::from((
+                redis::ErrorKind::IoError,
+                "Failed to get Redis connection",
+                e.to_string(),
+            ))))
+        })?;
+
+        let in_flight = encode(&StoredRecord::InFlight {
+            fingerprint: fingerprint.clone(),
+        })?;
+        let claimed: Option<String> = redis::cmd("SET")
+            .arg(&redis_key)
+            .arg(&in_flight)
+            .arg("NX")
+            .arg("EX")
+            .arg(idempotency.config.lock_secs.max(1))
+            .query_async(conn.deref_mut())
+            .await?;
+
+        if claimed.is_none() {
+            let stored: Option<String> = redis::cmd("GET")
+                .arg(&redis_key)
+                .query_async(conn.deref_mut())
+                .await?;
+            return match stored {
+                Some(stored) => replay(&stored, &fingerprint),
+                // Expired between SET and GET; the client's retry will claim it
+                None => Err(Error::Conflict(
+                    "A request with this idempotency key is in progress".to_string(),
+                )),
+            };
+        }
+        drop(conn);
+
+        let response = next.run(Request::from_parts(parts, Body::from(body))).await;
+
+        let storable = !response.status().is_server_error()
+            && response
+                .body()
+                .size_hint()
+                .upper()
+                .is_some_and(|upper| upper <= idempotency.config.max_response_bytes as u64);
+        if !storable {
+            debug!(
+                "Not storing {} response for idempotency key; releasing",
+                response.status()
+            );
+            idempotency.release(&redis_key, &in_flight).await;
+            return Ok(response);
+        }
+
+        let (parts, body) = response.into_parts();
+        let body = match axum::body::to_bytes(body, idempotency.config.max_response_bytes).await {
+            Ok(body) => body,
+            Err(e) => {
+                idempotency.release(&redis_key, &in_flight).await;
+                return Err(Error::Internal(format!(
+                    "Failed to buffer response body: {}",
+                    e
+                )));
+            }
+        };
+
+        let completed = encode(&StoredRecord::Completed {
+            fingerprint,
+            status: parts.status.as_u16(),
+            headers: stored_headers(&parts.headers),
+            body: STANDARD.encode(&body),
+        })?;
+        if let Err(e) = idempotency
+            .store(&redis_key, &completed, Duration::from_secs(policy.ttl_secs))
+            .await
+        {
+            // The handler already ran; its response must reach the client
+            warn!("Failed to store idempotent response: {}", e);
+            idempotency.release(&redis_key, &in_flight).await;
+        }
+
+        Ok(Response::from_parts(parts, Body::from(body)))
+    }
+
+    /// Resolve route overrides; `None` when the route opts out
+    fn policy(&self, method: &str, normalized_path: &str) -> Option<RoutePolicy> {
+        match self.route_patterns.match_route(method, normalized_path) {
+            Some(route) if !route.enabled => None,
+            Some(route) => Some(RoutePolicy {
+                required: route.required || self.config.required,
+                ttl_secs: route.ttl_secs.unwrap_or(self.config.ttl_secs),
+            }),
+            None => Some(RoutePolicy {
+                required: self.config.required,
+                ttl_secs: self.config.ttl_secs,
+            }),
+        }
+    }
+
+    async fn pool(&self) -> Result<RedisPool, Error> {
+        self.redis_pool
+            .read()
+            .await
+            .clone()
+            .ok_or_else(|| Error::Internal("Redis pool not available".to_string()))
+    }
+
+    async fn store(&self, redis_key: &str, record: &str, ttl: Duration) -> Result<(), Error> {
+        let pool = self.pool().await?;
+        let mut conn = pool
+            .get()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to get Redis connection: {}", e)))?;
+
+        let _: () = redis::cmd("SET")
+            .arg(redis_key)
+            .arg(record)
+            .arg("EX")
+            .arg(ttl.as_secs().max(1))
+            .query_async(conn.deref_mut())
+            .await?;
+
+        Ok(())
+    }
+
+    /// Drop the in-flight record so the client can retry; a record written
+    /// by anything else is left alone
+    async fn release(&self, redis_key: &str, in_flight: &str) {
+        let result: Result<(), Error> = async {
+            let pool = self.pool().await?;
+            let mut conn = pool
+                .get()
+                .await
+                .map_err(|e| Error::Internal(format!("Failed to get Redis connection: {}", e)))?;
+            let _: i64 = redis::cmd("EVAL")
+                .arg("if redis.call('GET', KEYS[1]) == ARGV[1] then return redis.call('DEL', KEYS[1]) end return 0")
+                .arg(1)
+                .arg(redis_key)
+                .arg(in_flight)
+                .query_async(conn.deref_mut())
+                .await?;
+            Ok(())
+        }
+        .await;
+
+        if let Err(e) = result {
+            warn!("Failed to release idempotency key: {}", e);
+        }
+    }
+}
+
+fn is_unsafe_method(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::POST | Method::PUT | Method::PATCH | Method::DELETE
+    )
+}
+
+/// Accept 1-255 visible ASCII characters
+fn validate_key(value: &HeaderValue) -> Result<&str, Error> {
+    value
+        .to_str()
+        .ok()
+        .filter(|key| {
+            !key.is_empty() && key.len() <= MAX_KEY_LEN && key.bytes().all(|b| b.is_ascii_graphic())
+        })
+        .ok_or_else(|| {
+            Error::BadRequest(format!(
+                "Idempotency key must be 1-{} visible ASCII characters",
+                MAX_KEY_LEN
+            ))
+        })
+}
+
+/// Hash principal and key together so neither needs escaping
+fn redis_key(principal: Option<&str>, key: &str) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(principal.unwrap_or("anonymous").as_bytes());
+    hasher.update(&[0]);
+    hasher.update(key.as_bytes());
+    format!("{}{}", KEY_PREFIX, hasher.finalize().to_hex())
+}
+
+fn fingerprint(method: &str, path_and_query: &str, body: &[u8]) -> String {
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(method.as_bytes());
+    hasher.update(&[0]);
+    hasher.update(path_and_query.as_bytes());
+    hasher.update(&[0]);
+    hasher.update(body);
+    hasher.finalize().to_hex().to_string()
+}
+
+fn stored_headers(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .filter(|(name, _)| !SKIPPED_HEADERS.contains(name))
+        .filter_map(|(name, value)| {
+            value
+                .to_str()
+                .ok()
+                .map(|value| (name.as_str().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+fn encode(record: &StoredRecord) -> Result<String, Error> {
+    serde_json::to_string(record)
+        .map_err(|e| Error::Internal(format!("Failed to encode idempotency record: {}", e)))
+}
+
+/// Answer a duplicate request from the stored record
+fn replay(stored: &str, fingerprint: &str) -> Result<Response, Error> {
+    let record: StoredRecord = serde_json::from_str(stored)
+        .map_err(|e| Error::Internal(format!("Corrupt idempotency record: {}", e)))?;
+
+    if record.fingerprint() != fingerprint {
+        return Err(Error::ValidationError(
+            "Idempotency key was already used with a different request".to_string(),
+        ));
+    }
+
+    match record {
+        StoredRecord::InFlight { .. } => Err(Error::Conflict(
+            "A request with this idempotency key is in progress".to_string(),
+        )),
+        StoredRecord::Completed {
+            status,
+            headers,
+            body,
+            ..
+        } => {
+            let body = STANDARD
+                .decode(body)
+                .map_err(|e| Error::Internal(format!("Corrupt idempotency record: {}", e)))?;
+            let mut response = Response::new(Body::from(Bytes::from(body)));
+            *response.status_mut() = StatusCode::from_u16(status)
+                .map_err(|e| Error::Internal(format!("Corrupt idempotency record: {}", e)))?;
+
+            let response_headers = response.headers_mut();
+            for (name, value) in headers {
+                if let (Ok(name), Ok(value)) = (
+                    HeaderName::from_bytes(name.as_bytes()),
+                    HeaderValue::from_str(&value),
+                ) {
+                    response_headers.append(name, value);
+                }
+            }
+            response_headers.insert(
+                HeaderName::from_static(IDEMPOTENT_REPLAYED_HEADER),
+                HeaderValue::from_static("true"),
+            );
+
+            Ok(response)
+        }
+    }
+}
+
+/// Buffer a request body of at most `limit` bytes
+///
+/// 413 if the body (or its declared `Content-Length`) is larger, 400 if it
+/// cannot be read.
+async fn read_body(headers: &HeaderMap, body: Body, limit: usize) -> Result<Bytes, StatusCode> {
+    use futures::StreamExt;
+
+    let declared = headers
+        .get(header::CONTENT_LENGTH)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse::<u64>().ok());
+    if declared.is_some_and(|length| length > limit as u64) {
+        return Err(StatusCode::PAYLOAD_TOO_LARGE);
+    }
+
+    let mut stream = body.into_data_stream();
+    let mut buffered = Vec::new();
+    while let Some(chunk) = stream.next().await {
+        let chunk = chunk.map_err(|_| StatusCode::BAD_REQUEST)?;
+        if buffered.len() + chunk.len() > limit {
+            return Err(StatusCode::PAYLOAD_TOO_LARGE);
+        }
+        buffered.extend_from_slice(&chunk);
+    }
+    Ok(Bytes::from(buffered))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn idempotency(routes: HashMap<String, RouteIdempotencyConfig>) -> Idempotency {
+        let config = IdempotencyConfig {
+            routes,
+            ..Default::default()
+        };
+        Idempotency::with_shared_pool(config, Arc::new(RwLock::new(None)))
+    }
+
+    #[test]
+    fn test_only_unsafe_methods_are_guarded() {
+        assert!(is_unsafe_method(&Method::POST));
+        assert!(is_unsafe_method(&Method::DELETE));
+        assert!(!is_unsafe_method(&Method::GET));
+        assert!(!is_unsafe_method(&Method::OPTIONS));
+    }
+
+    #[test]
+    fn test_validate_key() {
+        assert!(validate_key(&HeaderValue::from_static("0b1c-42")).is_ok());
+        assert!(validate_key(&HeaderValue::from_static("")).is_err());
+        assert!(validate_key(&HeaderValue::from_static("has space")).is_err());
+        let long = "k".repeat(MAX_KEY_LEN + 1);
+        assert!(validate_key(&HeaderValue::from_str(&long).unwrap()).is_err());
+    }
+
+    #[test]
+    fn test_redis_key_is_scoped_to_principal() {
+        assert_ne!(redis_key(Some("alice"), "k1"), redis_key(Some("bob"), "k1"));
+        assert_ne!(redis_key(None, "k1"), redis_key(Some("alice"), "k1"));
+        assert_eq!(
+            redis_key(Some("alice"), "k1"),
+            redis_key(Some("alice"), "k1")
+        );
+        assert!(redis_key(None, "k1").starts_with(KEY_PREFIX));
+    }
+
+    #[test]
+    fn test_fingerprint_covers_method_path_and_body() {
+        let base = fingerprint("POST", "/orders", b"{}");
+        assert_eq!(base, fingerprint("POST", "/orders", b"{}"));
+        assert_ne!(base, fingerprint("PUT", "/orders", b"{}"));
+        assert_ne!(base, fingerprint("POST", "/orders?x=1", b"{}"));
+        assert_ne!(base, fingerprint("POST", "/orders", b"{\"a\":1}"));
+    }
+
+    #[test]
+    fn test_route_policy_overrides() {
+        let mut routes = HashMap::new();
+        routes.insert(
+            "POST /api/v1/payments".to_string(),
+            RouteIdempotencyConfig {
+                enabled: true,
+                required: true,
+                ttl_secs: Some(60),
+            },
+        );
+        routes.insert(
+            "/api/v1/search".to_string(),
+            RouteIdempotencyConfig {
+                enabled: false,
+                required: false,
+                ttl_secs: None,
+            },
+        );
+        let idempotency = idempotency(routes);
+
+        assert_eq!(
+            idempotency.policy("POST", "/api/v1/payments"),
+            Some(RoutePolicy {
+                required: true,
+                ttl_secs: 60
+            })
+        );
+        assert_eq!(idempotency.policy("POST", "/api/v1/search"), None);
+        assert_eq!(
+            idempotency.policy("POST", "/api/v1/orders"),
+            Some(RoutePolicy {
+                required: false,
+                ttl_secs: IdempotencyConfig::default().ttl_secs
+            })
+        );
+    }
+
+    #[test]
+    fn test_replay_completed_response() {
+        let stored = encode(&StoredRecord::Completed {
+            fingerprint: "fp".to_string(),
+            status: 201,
+            headers: vec![("content-type".to_string(), "application/json".to_string())],
+            body: STANDARD.encode(b"{\"id\":1}"),
+        })
+        .unwrap();
+
+        let response = replay(&stored, "fp").unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(response.headers()["content-type"], "application/json");
+        assert_eq!(response.headers()[IDEMPOTENT_REPLAYED_HEADER], "true");
+    }
+
+    #[test]
+    fn test_replay_rejects_in_flight_and_mismatched_requests() {
+        let stored = encode(&StoredRecord::InFlight {
+            fingerprint: "fp".to_string(),
+        })
+        .unwrap();
+
+        assert!(matches!(replay(&stored, "fp"), Err(Error::Conflict(_))));
+        assert!(matches!(
+            replay(&stored, "other"),
+            Err(Error::ValidationError(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_read_body_is_bounded() {
+        let none = HeaderMap::new();
+        let body = read_body(&none, Body::from("12345"), 5).await.unwrap();
+        assert_eq!(&body[..], b"12345");
+
+        // A streamed body is cut off at the limit
+        let chunks = futures::stream::iter(vec![
+            Ok::<_, std::io::Error>(Bytes::from_static(b"1234")),
+            Ok(Bytes::from_static(b"56")),
+        ]);
+        assert_eq!(
+            read_body(&none, Body::from_stream(chunks), 5).await,
+            Err(StatusCode::PAYLOAD_TOO_LARGE)
+        );
+
+        // A declared length over the limit is refused before reading
+        let mut declared = HeaderMap::new();
+        declared.insert(header::CONTENT_LENGTH, HeaderValue::from_static("6"));
+        assert_eq!(
+            read_body(&declared, Body::empty(), 5).await,
+            Err(StatusCode::PAYLOAD_TOO_LARGE)
+        );
+
+        // Other read failures are the client's, not a size problem
+        let broken = futures::stream::iter(vec![Err::<Bytes, _>(std::io::Error::other("reset"))]);
+        assert_eq!(
+            read_body(&none, Body::from_stream(broken), 5).await,
+            Err(StatusCode::BAD_REQUEST)
+        );
+    }
+}
@@ -0,0 +1,493 @@
+//! Remote JWKS key sets for JWT validation (requires `jwt` feature)
+//!
+//! Lets [`JwtAuth`](super::JwtAuth) validate tokens from external identity
+//! providers (Keycloak, Auth0, another acton service publishing
+//! `/.well-known/jwks.json`) whose signing keys rotate.
+//!
+//! Each trusted issuer has its own [`JwksKeySet`]. A key set is fetched on
+//! first use, refreshed in the background every `jwks_refresh_secs`, and
+//! refetched when a token names a `kid` it does not know — at most once per
+//! `jwks_min_refresh_secs`, so tokens with made-up `kid`s cannot turn the
+//! service into a request amplifier against the IdP.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::{Duration, Instant};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use jsonwebtoken::jwk::{JwkSet, PublicKeyUse};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+
+use super::token::Claims;
+use crate::config::JwtConfig;
+use crate::error::Error;
+
+/// How long a JWKS fetch may take before it is abandoned
+const FETCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A verification key taken from a JWK Set
+#[derive(Clone)]
+struct JwksKey {
+    key: DecodingKey,
+    /// The algorithm the JWK is restricted to, if it names one
+    algorithm: Option<Algorithm>,
+}
+
+/// A JWK Set fetched from a URL and cached in memory
+pub struct JwksKeySet {
+    url: String,
+    client: reqwest::Client,
+    /// Keys by `kid`; keys published without a `kid` are kept under `""`
+    keys: RwLock<HashMap<String, JwksKey>>,
+    /// When the set was last fetched, successfully or not
+    last_fetch: Mutex<Option<Instant>>,
+    /// Held for the duration of a fetch, so a miss waits for one in flight
+    fetching: tokio::sync::Mutex<()>,
+    min_refresh_interval: Duration,
+}
+
+impl JwksKeySet {
+    /// Create an empty key set; keys are fetched on first use
+    pub fn new(url: impl Into<String>, min_refresh_interval: Duration) -> Result<Self, Error> {
+        let client = reqwest::Client::builder()
+            .timeout(FETCH_TIMEOUT)
+            .build()
+            .map_err(|e| Error::Internal(format!("Failed to build JWKS HTTP client: {}", e)))?;
+        Ok(Self {
+            url: url.into(),
+            client,
+            keys: RwLock::new(HashMap::new()),
+            last_fetch: Mutex::new(None),
+            fetching: tokio::sync::Mutex::new(()),
+            min_refresh_interval,
+        })
+    }
+
+    /// The URL the key set is fetched from
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// Fetch the key set and replace the cached keys
+    ///
+    /// Keys marked for encryption (`"use": "enc"`) and keys that cannot be
+    /// turned into a verification key are skipped.
+    pub async fn refresh(&self) -> Result<(), Error> {
+        let _fetching = self.fetching.lock().await;
+        self.fetch().await
+    }
+
+    async fn fetch(&self) -> Result<(), Error> {
+        *self.last_fetch.lock().unwrap_or_else(|e| e.into_inner()) = Some(Instant::now());
+
+        let jwks: JwkSet = self
+            .client
+            .get(&self.url)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| Error::External(format!("Failed to fetch JWKS from {}: {}", self.url, e)))?
+            .json()
+            .await
+            .map_err(|e| Error::External(format!("Invalid JWKS from {}: {}", self.url, e)))?;
+
+        let mut keys = HashMap::with_capacity(jwks.keys.len());
+        for jwk in &jwks.keys {
+            if matches!(jwk.common.public_key_use, Some(PublicKeyUse::Encryption)) {
+                continue;
+            }
+            let kid = jwk.common.key_id.clone().unwrap_or_default();
+            match DecodingKey::from_jwk(jwk) {
+                Ok(key) => {
+                    let algorithm = jwk
+                        .common
+                        .key_algorithm
+                        .as_ref()
+                        .and_then(|alg| serde_json::to_value(alg).ok())
+                        .and_then(|alg| alg.as_str().and_then(|s| s.parse().ok()));
+                    keys.insert(kid, JwksKey { key, algorithm });
+                }
+                Err(e) => {
+                    tracing::warn!(url = %self.url, kid = %kid, "Skipping unusable JWK: {}", e);
+                }
+            }
+        }
+
+        tracing::debug!(url = %self.url, keys = keys.len(), "Refreshed JWKS");
+        *self.keys.write().unwrap_or_else(|e| e.into_inner()) = keys;
+        Ok(())
+    }
+
+    /// Refresh now unless the set was fetched within the minimum interval
+    ///
+    /// Returns whether a fetch happened. A fetch already in flight (the first
+    /// background refresh, say) is waited for rather than counted as recent.
+    async fn refresh_if_allowed(&self) -> Result<bool, Error> {
+        let _fetching = self.fetching.lock().await;
+        let recently_fetched = self
+            .last_fetch
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .is_some_and(|at| at.elapsed() < self.min_refresh_interval);
+        if recently_fetched {
+            return Ok(false);
+        }
+        self.fetch().await?;
+        Ok(true)
+    }
+
+    /// Candidate keys for a token: the `kid` match, or every key without one
+    fn candidates(&self, kid: Option<&str>) -> Vec<JwksKey> {
+        let keys = self.keys.read().unwrap_or_else(|e| e.into_inner());
+        match kid {
+            Some(kid) => keys.get(kid).cloned().into_iter().collect(),
+            None => keys.values().cloned().collect(),
+        }
+    }
+
+    /// Keys for a token, refetching the set on a miss
+    ///
+    /// A failed refetch is logged and the cached set is used as is, so an
+    /// unreachable IdP turns into a rejected token rather than a 502.
+    async fn keys_for(&self, kid: Option<&str>) -> Vec<JwksKey> {
+        let candidates = self.candidates(kid);
+        if !candidates.is_empty() {
+            return candidates;
+        }
+        // Even without a fetch of our own, one that was in flight may have
+        // brought the key in
+        if let Err(e) = self.refresh_if_allowed().await {
+            tracing::warn!(url = %self.url, "JWKS refresh on unknown key failed: {}", e);
+        }
+        self.candidates(kid)
+    }
+
+    /// Refresh the key set every `every` until the last handle is dropped
+    ///
+    /// Does nothing outside a tokio runtime; refresh-on-miss still applies.
+    pub(crate) fn spawn_refresh(self: &Arc<Self>, every: Duration) {
+        let Ok(handle) = tokio::runtime::Handle::try_current() else {
+            tracing::debug!(url = %self.url, "No tokio runtime; JWKS refreshes on demand only");
+            return;
+        };
+        let weak: Weak<Self> = Arc::downgrade(self);
+        handle.spawn(async move {
+            let mut ticker = tokio::time::interval(every.max(Duration::from_secs(1)));
+            loop {
+                ticker.tick().await;
+                let Some(key_set) = weak.upgrade() else {
+                    break;
+                };
+                if let Err(e) = key_set.refresh().await {
+                    tracing::warn!(url = %key_set.url, "Background JWKS refresh failed: {}", e);
+                }
+            }
+        });
+    }
+}
+
+/// An issuer whose tokens are accepted, with the key set they are signed by
+struct TrustedIssuer {
+    /// Expected `iss`; `None` accepts tokens from any issuer not listed elsewhere
+    issuer: Option<String>,
+    audience: Option<String>,
+    keys: Arc<JwksKeySet>,
+}
+
+/// Validates JWTs against the key sets of one or more trusted issuers
+pub(crate) struct RemoteJwtValidator {
+    issuers: Vec<TrustedIssuer>,
+}
+
+impl RemoteJwtValidator {
+    /// Build from `jwks_url` and `trusted_issuers`; `None` if neither is set
+    pub(crate) fn from_config(config: &JwtConfig) -> Result<Option<Self>, Error> {
+        let min_refresh = Duration::from_secs(config.jwks_min_refresh_secs);
+        let mut issuers = Vec::new();
+
+        if let Some(url) = &config.jwks_url {
+            issuers.push(TrustedIssuer {
+                issuer: config.issuer.clone(),
+                audience: config.audience.clone(),
+                keys: Arc::new(JwksKeySet::new(url, min_refresh)?),
+            });
+        }
+        for trusted in &config.trusted_issuers {
+            issuers.push(TrustedIssuer {
+                issuer: Some(trusted.issuer.clone()),
+                audience: trusted.audience.clone(),
+                keys: Arc::new(JwksKeySet::new(&trusted.jwks_url, min_refresh)?),
+            });
+        }
+
+        if issuers.is_empty() {
+            return Ok(None);
+        }
+        let refresh_every = Duration::from_secs(config.jwks_refresh_secs);
+        for issuer in &issuers {
+            issuer.keys.spawn_refresh(refresh_every);
+        }
+        Ok(Some(Self { issuers }))
+    }
+
+    /// Validate a token against the key set of the issuer it names
+    pub(crate) async fn validate(&self, token: &str) -> Result<Claims, Error> {
+        let header = decode_header(token)?;
+        if !is_asymmetric(header.alg) {
+            return Err(Error::Unauthorized(format!(
+                "Algorithm {:?} is not accepted for remotely verified tokens",
+                header.alg
+            )));
+        }
+
+        let iss = unverified_issuer(token);
+        let issuer = self
+            .issuers
+            .iter()
+            .find(|i| i.issuer.is_some() && i.issuer == iss)
+            .or_else(|| self.issuers.iter().find(|i| i.issuer.is_none()))
+            .ok_or_else(|| Error::Unauthorized("Token issuer is not trusted".to_string()))?;
+
+        let keys = issuer.keys.keys_for(header.kid.as_deref()).await;
+        if keys.is_empty() {
+            return Err(Error::Unauthorized(
+                "Token is signed with an unknown key".to_string(),
+            ));
+        }
+
+        let mut validation = Validation::new(header.alg);
+        if let Some(iss) = &issuer.issuer {
+            validation.set_issuer(&[iss]);
+        }
+        if let Some(aud) = &issuer.audience {
+            validation.set_audience(&[aud]);
+        }
+
+        let mut last_error = None;
+        for candidate in keys {
+            if candidate.algorithm.is_some_and(|alg| alg != header.alg) {
+                continue;
+            }
+            match decode::<Claims>(token, &candidate.key, &validation) {
+                Ok(data) => return Ok(data.claims),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(match last_error {
+            Some(e) => e.into(),
+            None => Error::Unauthorized(format!(
+                "No key in the issuer's key set accepts {:?}",
+                header.alg
+            )),
+        })
+    }
+}
+
+/// Whether an algorithm is a public-key signature
+///
+/// HMAC is refused outright: with a published key set it would let anyone
+/// holding a public key forge tokens.
+fn is_asymmetric(alg: Algorithm) -> bool {
+    !matches!(alg, Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512)
+}
+
+/// Read the `iss` claim without verifying the token
+///
+/// Only used to pick the key set to verify against; the claim is checked
+/// again by the signature-verified decode.
+fn unverified_issuer(token: &str) -> Option<String> {
+    let payload = token.split('.').nth(1)?;
+    let bytes = URL_SAFE_NO_PAD.decode(payload).ok()?;
+    let value: serde_json::Value = serde_json::from_slice(&bytes).ok()?;
+    value.get("iss")?.as_str().map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::TrustedIssuerConfig;
+    use axum::{routing::get, Json, Router};
+    use jsonwebtoken::jwk::Jwk;
+    use jsonwebtoken::{encode, EncodingKey, Header};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    /// A signing key and its public JWK
+    struct TestKey {
+        kid: String,
+        encoding_key: EncodingKey,
+        jwk: Jwk,
+    }
+
+    fn test_key(kid: &str) -> TestKey {
+        let key_pair = rcgen::KeyPair::generate().unwrap();
+        let encoding_key = EncodingKey::from_ec_pem(key_pair.serialize_pem().as_bytes()).unwrap();
+        let mut jwk = Jwk::from_encoding_key(&encoding_key, Algorithm::ES256).unwrap();
+        jwk.common.key_id = Some(kid.to_string());
+        TestKey {
+            kid: kid.to_string(),
+            encoding_key,
+            jwk,
+        }
+    }
+
+    /// Serve a JWK Set on a local port, counting fetches
+    async fn stub_idp(jwks: JwkSet) -> (String, Arc<AtomicUsize>) {
+        let hits = Arc::new(AtomicUsize::new(0));
+        let counter = hits.clone();
+        let app = Router::new().route(
+            "/jwks.json",
+            get(move || {
+                counter.fetch_add(1, Ordering::SeqCst);
+                let jwks = jwks.clone();
+                async move { Json(jwks) }
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (format!("http://{addr}/jwks.json"), hits)
+    }
+
+    fn sign(key: &TestKey, kid: &str, iss: &str, aud: &str) -> String {
+        let mut header = Header::new(Algorithm::ES256);
+        header.kid = Some(kid.to_string());
+        let claims = serde_json::json!({
+            "sub": "user:42",
+            "iss": iss,
+            "aud": aud,
+            "exp": chrono::Utc::now().timestamp() + 300,
+        });
+        encode(&header, &claims, &key.encoding_key).unwrap()
+    }
+
+    fn config(jwks_url: Option<String>, trusted_issuers: Vec<TrustedIssuerConfig>) -> JwtConfig {
+        JwtConfig {
+            public_key_path: None,
+            algorithm: "ES256".to_string(),
+            issuer: Some("https://idp-a".to_string()),
+            audience: Some("orders".to_string()),
+            public_paths: Vec::new(),
+            jwks_url,
+            jwks_refresh_secs: 3600,
+            jwks_min_refresh_secs: 3600,
+            trusted_issuers,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_validates_by_kid_and_caches_key_set() {
+        let key = test_key("a1");
+        let (url, hits) = stub_idp(JwkSet {
+            keys: vec![key.jwk.clone()],
+        })
+        .await;
+        let validator = RemoteJwtValidator::from_config(&config(Some(url), Vec::new()))
+            .unwrap()
+            .unwrap();
+
+        for _ in 0..3 {
+            let token = sign(&key, &key.kid, "https://idp-a", "orders");
+            let claims = validator.validate(&token).await.unwrap();
+            assert_eq!(claims.sub, "user:42");
+        }
+        // Initial fetch only (background ticks are an hour apart)
+        assert!(hits.load(Ordering::SeqCst) <= 2);
+
+        let wrong_audience = sign(&key, &key.kid, "https://idp-a", "billing");
+        assert!(validator.validate(&wrong_audience).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_unknown_kid_refresh_is_rate_limited() {
+        let key = test_key("a1");
+        let (url, _hits) = stub_idp(JwkSet {
+            keys: vec![key.jwk.clone()],
+        })
+        .await;
+        let key_set = JwksKeySet::new(url, Duration::from_secs(3600)).unwrap();
+
+        // The first miss fetches the set; a second miss inside the interval does not
+        assert!(key_set.refresh_if_allowed().await.unwrap());
+        assert_eq!(key_set.keys_for(Some("a1")).await.len(), 1);
+        assert!(key_set.keys_for(Some("rotated")).await.is_empty());
+        assert!(!key_set.refresh_if_allowed().await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_unreachable_idp_rejects_token_and_keeps_cached_keys() {
+        // Bind and drop a listener to get a port nothing answers on
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/jwks.json", listener.local_addr().unwrap());
+        drop(listener);
+
+        let key = test_key("a1");
+        let validator = RemoteJwtValidator::from_config(&config(Some(url.clone()), Vec::new()))
+            .unwrap()
+            .unwrap();
+        let token = sign(&key, &key.kid, "https://idp-a", "orders");
+        let err = validator.validate(&token).await.unwrap_err();
+        assert!(matches!(err, Error::Unauthorized(_)), "{err}");
+
+        let key_set = JwksKeySet::new(url, Duration::ZERO).unwrap();
+        key_set.keys.write().unwrap().insert(
+            key.kid.clone(),
+            JwksKey {
+                key: DecodingKey::from_jwk(&key.jwk).unwrap(),
+                algorithm: None,
+            },
+        );
+        assert!(key_set.keys_for(Some("rotated")).await.is_empty());
+        assert_eq!(key_set.keys_for(Some("a1")).await.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_each_trusted_issuer_has_its_own_keys_and_audience() {
+        let key_a = test_key("a1");
+        let key_b = test_key("b1");
+        let (url_a, _) = stub_idp(JwkSet {
+            keys: vec![key_a.jwk.clone()],
+        })
+        .await;
+        let (url_b, _) = stub_idp(JwkSet {
+            keys: vec![key_b.jwk.clone()],
+        })
+        .await;
+        let validator = RemoteJwtValidator::from_config(&config(
+            Some(url_a),
+            vec![TrustedIssuerConfig {
+                issuer: "https://idp-b".to_string(),
+                jwks_url: url_b,
+                audience: Some("partners".to_string()),
+            }],
+        ))
+        .unwrap()
+        .unwrap();
+
+        let from_a = sign(&key_a, "a1", "https://idp-a", "orders");
+        let from_b = sign(&key_b, "b1", "https://idp-b", "partners");
+        assert!(validator.validate(&from_a).await.is_ok());
+        assert!(validator.validate(&from_b).await.is_ok());
+
+        // B's key claiming to be A, and A's audience on a B token
+        let forged = sign(&key_b, "b1", "https://idp-a", "orders");
+        assert!(validator.validate(&forged).await.is_err());
+        let wrong_audience = sign(&key_b, "b1", "https://idp-b", "orders");
+        assert!(validator.validate(&wrong_audience).await.is_err());
+    }
+
+    #[test]
+    fn test_unverified_issuer() {
+        let payload = URL_SAFE_NO_PAD.encode(br#"{"iss":"https://idp-a","sub":"x"}"#);
+        let token = format!("e30.{payload}.sig");
+        assert_eq!(unverified_issuer(&token).as_deref(), Some("https://idp-a"));
+        assert_eq!(unverified_issuer("not-a-token"), None);
+    }
+
+    #[test]
+    fn test_hmac_is_refused() {
+        assert!(!is_asymmetric(Algorithm::HS256));
+        assert!(is_asymmetric(Algorithm::RS256));
+        assert!(is_asymmetric(Algorithm::ES256));
+    }
+}
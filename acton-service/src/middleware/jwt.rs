@@ -15,6 +15,7 @@ use std::{fs, sync::Arc};
 #[cfg(feature = "cache")]
 use super::token::TokenRevocation;
 
+use super::jwks::RemoteJwtValidator;
 use super::token::{extract_token, Claims, TokenValidator};
 use crate::{config::JwtConfig, error::Error};
 
@@ -28,9 +29,13 @@ use crate::auth::key_rotation::manager::KeyManager;
 /// key in the rotation system. If the `kid` is not found or absent, it falls back to
 /// trying all verification keys or the static key. This ensures backward compatibility
 /// with tokens issued before key rotation was enabled.
+///
+/// With `jwks_url` or `trusted_issuers` configured, tokens are also verified
+/// against the key sets of external issuers (see [`super::jwks`]).
 #[derive(Clone)]
 pub struct JwtAuth {
-    decoding_key: Arc<DecodingKey>,
+    decoding_key: Option<Arc<DecodingKey>>,
+    remote: Option<Arc<RemoteJwtValidator>>,
     validation: Validation,
     #[cfg(feature = "cache")]
     revocation: Option<Arc<dyn TokenRevocation>>,
@@ -43,21 +48,15 @@ pub struct JwtAuth {
 impl JwtAuth {
     /// Create a new JWT authentication middleware
     pub fn new(config: &JwtConfig) -> Result<Self, Error> {
-        // Read the public key file
-        let public_key = fs::read(&config.public_key_path).map_err(|e| {
-            let path_display = config.public_key_path.display().to_string();
-            Error::Config(Box::new(figment::Error::from(format!(
-                "Failed to read JWT public key from path '{}'\n\n\
-                Troubleshooting:\n\
-                1. Verify the file exists: ls -la {}\n\
-                2. Check file permissions (must be readable)\n\
-                3. Verify the path is correct in configuration\n\
-                4. For RS256/ES256: Use PEM format public key\n\
-                5. For HS256: Use raw secret file\n\n\
-                Error: {}",
-                path_display, path_display, e
-            ))))
-        })?;
+        let remote = RemoteJwtValidator::from_config(config)?.map(Arc::new);
+        let public_key =
+            match &config.public_key_path {
+                Some(path) => Some(Self::read_public_key(path)?),
+                None if remote.is_some() => None,
+                None => return Err(Error::Config(Box::new(figment::Error::from(
+                    "JWT authentication needs a public_key_path, a jwks_url, or trusted_issuers",
+                )))),
+            };
 
         // Parse the algorithm
         let algorithm = match config.algorithm.to_uppercase().as_str() {
@@ -78,20 +77,23 @@ impl JwtAuth {
         };
 
         // Create decoding key based on algorithm
-        let decoding_key = match algorithm {
-            Algorithm::RS256 | Algorithm::RS384 | Algorithm::RS512 => {
-                DecodingKey::from_rsa_pem(&public_key)?
-            }
-            Algorithm::ES256 | Algorithm::ES384 => DecodingKey::from_ec_pem(&public_key)?,
-            Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => {
-                DecodingKey::from_secret(&public_key)
-            }
-            _ => {
-                return Err(Error::Config(Box::new(figment::Error::from(format!(
-                    "Unsupported algorithm: {:?}",
-                    algorithm
-                )))))
-            }
+        let decoding_key = match public_key {
+            Some(public_key) => Some(match algorithm {
+                Algorithm::RS256 | Algorithm::RS384 | Algorithm::RS512 => {
+                    DecodingKey::from_rsa_pem(&public_key)?
+                }
+                Algorithm::ES256 | Algorithm::ES384 => DecodingKey::from_ec_pem(&public_key)?,
+                Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => {
+                    DecodingKey::from_secret(&public_key)
+                }
+                _ => {
+                    return Err(Error::Config(Box::new(figment::Error::from(format!(
+                        "Unsupported algorithm: {:?}",
+                        algorithm
+                    )))))
+                }
+            }),
+            None => None,
         };
 
         // Create validation rules
@@ -104,7 +106,8 @@ impl JwtAuth {
         }
 
         Ok(Self {
-            decoding_key: Arc::new(decoding_key),
+            decoding_key: decoding_key.map(Arc::new),
+            remote,
             validation,
             #[cfg(feature = "cache")]
             revocation: None,
@@ -114,6 +117,24 @@ impl JwtAuth {
         })
     }
 
+    /// Read the static verification key file
+    fn read_public_key(path: &std::path::Path) -> Result<Vec<u8>, Error> {
+        fs::read(path).map_err(|e| {
+            let path_display = path.display().to_string();
+            Error::Config(Box::new(figment::Error::from(format!(
+                "Failed to read JWT public key from path '{}'\n\n\
+                Troubleshooting:\n\
+                1. Verify the file exists: ls -la {}\n\
+                2. Check file permissions (must be readable)\n\
+                3. Verify the path is correct in configuration\n\
+                4. For RS256/ES256: Use PEM format public key\n\
+                5. For HS256: Use raw secret file\n\n\
+                Error: {}",
+                path_display, path_display, e
+            ))))
+        })
+    }
+
     /// Set the token revocation checker
     ///
     /// This allows the middleware to check if tokens have been revoked.
@@ -176,6 +197,20 @@ impl JwtAuth {
             return Ok(next.run(request).await);
         }
 
+        // Under "either" auth a valid API key already produced the Claims;
+        // the token is the alternative credential, not a second one.
+        #[cfg(feature = "auth")]
+        if super::api_key::bearer_satisfied(request.extensions()) {
+            return Ok(next.run(request).await);
+        }
+
+        // With `[session.claims]` enabled, a signed-in session produced the
+        // Claims and the request carries no bearer token to check.
+        #[cfg(feature = "session")]
+        if crate::session::bearer_satisfied(request.extensions()) {
+            return Ok(next.run(request).await);
+        }
+
         // Build audit source info before validation. Prefers the RequestContext
         // extension so failures still carry the peer IP and generated request ID;
         // falls back to headers for hand-wired routers.
@@ -318,8 +353,29 @@ impl TokenValidator for JwtAuth {
             }
         }
 
+        // Keys fetched from trusted issuers' JWKS endpoints
+        if let Some(ref remote) = self.remote {
+            let result = tokio::task::block_in_place(|| {
+                tokio::runtime::Handle::current().block_on(remote.validate(token))
+            });
+            match (result, &self.decoding_key) {
+                (Ok(claims), _) => return Ok(claims),
+                (Err(e), None) => return Err(e),
+                // Fall back to the static key, but report the remote failure
+                // if that does not verify the token either
+                (Err(e), Some(static_key)) => {
+                    return decode::<Claims>(token, static_key, &self.validation)
+                        .map(|data| data.claims)
+                        .map_err(|_| e);
+                }
+            }
+        }
+
         // Default: validate with the static key
-        let token_data = decode::<Claims>(token, &self.decoding_key, &self.validation)?;
+        let decoding_key = self.decoding_key.as_ref().ok_or_else(|| {
+            Error::Internal("JWT authentication has no verification key".to_string())
+        })?;
+        let token_data = decode::<Claims>(token, decoding_key, &self.validation)?;
         Ok(token_data.claims)
     }
 }
@@ -14,6 +14,17 @@ pub mod revocation;
 #[cfg(feature = "jwt")]
 pub mod jwt;
 
+// Remote JWKS key sets for JWT validation (requires jwt feature)
+#[cfg(feature = "jwt")]
+pub mod jwks;
+
+// API key authentication (requires auth feature)
+#[cfg(feature = "auth")]
+pub mod api_key;
+
+#[cfg(feature = "idempotency")]
+pub mod idempotency;
+
 pub mod rate_limit;
 pub mod request_context;
 pub mod request_tracking;
@@ -50,6 +61,16 @@ pub use revocation::RedisTokenRevocation;
 #[cfg(feature = "jwt")]
 pub use jwt::JwtAuth;
 
+#[cfg(feature = "jwt")]
+pub use jwks::JwksKeySet;
+
+// API key exports (requires auth feature)
+#[cfg(feature = "auth")]
+pub use api_key::ApiKeyAuth;
+
+#[cfg(feature = "idempotency")]
+pub use idempotency::Idempotency;
+
 // Other middleware exports
 pub use rate_limit::RateLimit;
 pub use request_context::{request_context_middleware, RequestContext};
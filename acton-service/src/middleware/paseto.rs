@@ -189,6 +189,20 @@ impl PasetoAuth {
             return Ok(next.run(request).await);
         }
 
+        // Under "either" auth a valid API key already produced the Claims;
+        // the token is the alternative credential, not a second one.
+        #[cfg(feature = "auth")]
+        if super::api_key::bearer_satisfied(request.extensions()) {
+            return Ok(next.run(request).await);
+        }
+
+        // With `[session.claims]` enabled, a signed-in session produced the
+        // Claims and the request carries no bearer token to check.
+        #[cfg(feature = "session")]
+        if crate::session::bearer_satisfied(request.extensions()) {
+            return Ok(next.run(request).await);
+        }
+
         // Build audit source info before validation (available regardless of outcome).
         // Prefers the RequestContext extension so failures still carry the peer IP
         // and generated request ID; falls back to headers for hand-wired routers.
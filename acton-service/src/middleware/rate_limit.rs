@@ -2,12 +2,22 @@
 //!
 //! Provides distributed rate limiting with per-route configuration support.
 //! Uses Redis for shared state across multiple service instances.
+//!
+//! Three algorithms are available (see [`RateLimitAlgorithm`]):
+//! - fixed window: `INCR` + `EXPIRE`, cheap but allows boundary bursts
+//! - sliding log: a sorted set of request timestamps per key
+//! - GCRA: a single theoretical-arrival-time value per key
+//!
+//! The sliding log and GCRA checks run as Lua scripts using the Redis
+//! server clock, so they are atomic and agree across instances.
 
 #[cfg(feature = "cache")]
 use deadpool_redis::Pool as RedisPool;
 #[cfg(feature = "cache")]
 use std::ops::DerefMut;
 use std::sync::Arc;
+#[cfg(feature = "cache")]
+use std::sync::LazyLock;
 
 use axum::{
     body::Body,
@@ -17,13 +27,23 @@ use axum::{
 };
 
 #[cfg(feature = "cache")]
-use axum::http::{header::HeaderValue, HeaderName};
+use axum::{
+    http::{header::HeaderValue, HeaderName},
+    response::IntoResponse,
+};
 
+#[cfg(feature = "cache")]
+use crate::config::RateLimitAlgorithm;
 use crate::{config::RateLimitConfig, error::Error};
 
 #[cfg(feature = "cache")]
 use crate::middleware::Claims;
 
+#[cfg(all(feature = "cache", feature = "auth"))]
+use crate::auth::api_keys::{ApiKey, ApiKeyQuota, ApiKeyStorage};
+#[cfg(all(feature = "cache", feature = "auth"))]
+use crate::middleware::api_key::DefaultKeyRateLimit;
+
 use super::route_matcher::CompiledRoutePatterns;
 
 #[cfg(feature = "cache")]
@@ -36,6 +56,7 @@ use tracing::{debug, warn};
 ///
 /// Provides Redis-backed distributed rate limiting with support for:
 /// - Global per-user and per-client limits
+/// - Per-API-key limits with daily and monthly quotas
 /// - Per-route rate limit overrides
 /// - Automatic path normalization for dynamic segments
 #[derive(Clone)]
@@ -46,19 +67,102 @@ pub struct RateLimit {
     route_patterns: Arc<CompiledRoutePatterns>,
     #[cfg(feature = "cache")]
     redis_pool: Option<RedisPool>,
+    #[cfg(all(feature = "cache", feature = "auth"))]
+    api_key_storage: Option<Arc<dyn ApiKeyStorage>>,
 }
 
 /// Rate limit check result containing limit info for response headers
 #[cfg(feature = "cache")]
+#[derive(Debug, PartialEq, Eq)]
 struct RateLimitResult {
-    /// Maximum requests allowed in window
+    /// Maximum requests allowed in window (bucket capacity for GCRA)
     limit: u32,
-    /// Current request count (after this request)
-    count: u32,
-    /// Seconds until window resets
+    /// Requests left before the limit is hit
+    remaining: u32,
+    /// Seconds until the limit fully resets
     reset_secs: u64,
+    /// Seconds to wait before retrying; `Some` when the request is denied
+    retry_after_secs: Option<u64>,
 }
 
+/// Per-minute limit for the API key that authenticated a request
+///
+/// Shared with the governor middleware.
+#[cfg(any(feature = "cache", feature = "governor"))]
+#[cfg_attr(not(feature = "auth"), allow(dead_code))]
+pub(crate) struct ApiKeyLimit<'a> {
+    pub(crate) id: &'a str,
+    pub(crate) requests_per_minute: u32,
+}
+
+/// Sliding log: drop timestamps older than the window, then admit the
+/// request if fewer than `limit` remain.
+///
+/// KEYS[1] = log key; ARGV[1] = limit; ARGV[2] = window (ms); ARGV[3] = unique member.
+/// Returns `{allowed, remaining, reset_ms, retry_after_ms}`.
+#[cfg(feature = "cache")]
+const SLIDING_LOG_LUA: &str = r#"
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local limit = tonumber(ARGV[1])
+local window = tonumber(ARGV[2])
+
+redis.call('ZREMRANGEBYSCORE', KEYS[1], '-inf', now - window)
+local count = redis.call('ZCARD', KEYS[1])
+local allowed = 0
+if count < limit then
+  redis.call('ZADD', KEYS[1], now, ARGV[3])
+  redis.call('PEXPIRE', KEYS[1], window)
+  count = count + 1
+  allowed = 1
+end
+
+local oldest = redis.call('ZRANGE', KEYS[1], 0, 0, 'WITHSCORES')
+local reset = window
+if oldest[2] then
+  reset = tonumber(oldest[2]) + window - now
+end
+if allowed == 1 then
+  return {1, limit - count, reset, 0}
+end
+return {0, 0, reset, reset}
+"#;
+
+/// GCRA: admit the request if the theoretical arrival time (TAT) after it
+/// stays within `burst` emission intervals of now.
+///
+/// KEYS[1] = TAT key; ARGV[1] = emission interval (ms); ARGV[2] = burst.
+/// Returns `{allowed, remaining, reset_ms, retry_after_ms}`.
+#[cfg(feature = "cache")]
+const GCRA_LUA: &str = r#"
+local time = redis.call('TIME')
+local now = tonumber(time[1]) * 1000 + math.floor(tonumber(time[2]) / 1000)
+local interval = tonumber(ARGV[1])
+local burst = tonumber(ARGV[2])
+
+local tat = tonumber(redis.call('GET', KEYS[1])) or now
+if tat < now then
+  tat = now
+end
+local new_tat = tat + interval
+local allow_at = new_tat - interval * burst
+
+if allow_at > now then
+  return {0, 0, math.ceil(tat - now), math.ceil(allow_at - now)}
+end
+
+new_tat = math.ceil(new_tat)
+redis.call('SET', KEYS[1], new_tat, 'PX', new_tat - now)
+return {1, math.floor((now - allow_at) / interval), new_tat - now, 0}
+"#;
+
+#[cfg(feature = "cache")]
+static SLIDING_LOG_SCRIPT: LazyLock<redis::Script> =
+    LazyLock::new(|| redis::Script::new(SLIDING_LOG_LUA));
+
+#[cfg(feature = "cache")]
+static GCRA_SCRIPT: LazyLock<redis::Script> = LazyLock::new(|| redis::Script::new(GCRA_LUA));
+
 impl RateLimit {
     /// Create a new rate limiting middleware with Redis backend
     #[cfg(feature = "cache")]
@@ -68,9 +172,22 @@ impl RateLimit {
             config,
             route_patterns: Arc::new(route_patterns),
             redis_pool: Some(redis_pool),
+            #[cfg(feature = "auth")]
+            api_key_storage: None,
         }
     }
 
+    /// Enforce daily and monthly API key quotas
+    ///
+    /// Requests carrying an authenticated [`ApiKey`] in their extensions are
+    /// counted against the key's allowances in `storage`. Without storage,
+    /// API keys still get their per-minute limit but no quota is enforced.
+    #[cfg(all(feature = "cache", feature = "auth"))]
+    pub fn with_api_key_storage(mut self, storage: Arc<dyn ApiKeyStorage>) -> Self {
+        self.api_key_storage = Some(storage);
+        self
+    }
+
     /// Create a new rate limiting middleware without Redis (for testing)
     #[cfg(not(feature = "cache"))]
     pub fn new(config: RateLimitConfig) -> Self {
@@ -85,8 +202,12 @@ impl RateLimit {
     ///
     /// Checks rate limits in the following order:
     /// 1. Per-route limits (if configured for the request path)
-    /// 2. Global per-user limits (if JWT claims present)
-    /// 3. Global per-client limits (if client token)
+    /// 2. Per-API-key limits (if an authenticated `ApiKey` is in the extensions)
+    /// 3. Global per-user limits (if JWT claims present)
+    /// 4. Global per-client limits (if client token)
+    ///
+    /// API keys with a daily or monthly allowance are then counted against
+    /// it; an exhausted allowance is rejected with `429 QUOTA_EXCEEDED`.
     pub async fn middleware(
         #[cfg_attr(not(feature = "cache"), allow(unused_variables))] State(rate_limit): State<Self>,
         request: Request<Body>,
@@ -97,6 +218,25 @@ impl RateLimit {
             let method = request.method().as_str().to_string();
             let path = request.uri().path().to_string();
             let claims = request.extensions().get::<Claims>().cloned();
+            #[cfg(feature = "auth")]
+            let api_key = request
+                .extensions()
+                .get::<ApiKey>()
+                .map(|key| {
+                    let default_rpm = request
+                        .extensions()
+                        .get::<DefaultKeyRateLimit>()
+                        .map(|default| default.0);
+                    let quota = ApiKeyQuota::resolve(key, &rate_limit.config, default_rpm);
+                    (key.id.clone(), quota)
+                });
+            #[cfg(feature = "auth")]
+            let api_key_limit = api_key.as_ref().map(|(id, quota)| ApiKeyLimit {
+                id: id.as_str(),
+                requests_per_minute: quota.requests_per_minute,
+            });
+            #[cfg(not(feature = "auth"))]
+            let api_key_limit = None;
 
             #[cfg(feature = "audit")]
             let audit_logger = request
@@ -111,29 +251,50 @@ impl RateLimit {
             };
 
             // Check rate limit and get result for headers
-            let result = match rate_limit
-                .check_rate_limit_with_route(&method, &path, claims.as_ref())
-                .await
-            {
-                Ok(r) => r,
-                Err(e) => {
-                    #[cfg(feature = "audit")]
-                    if matches!(e, Error::RateLimitExceeded) {
-                        if let Some(ref logger) = audit_logger {
-                            if logger.config().audit_auth_events {
-                                logger
-                                    .log_auth(
-                                        crate::audit::event::AuditEventKind::HttpRequestDenied,
-                                        crate::audit::event::AuditSeverity::Warning,
-                                        audit_source,
-                                    )
-                                    .await;
-                            }
+            let result = rate_limit
+                .check_rate_limit_with_route(&method, &path, claims.as_ref(), api_key_limit)
+                .await?;
+
+            #[cfg_attr(not(feature = "auth"), allow(unused_mut))]
+            let mut denial = result
+                .retry_after_secs
+                .is_some()
+                .then_some(Error::RateLimitExceeded);
+
+            #[cfg(feature = "auth")]
+            if denial.is_none() {
+                if let (Some((key_id, quota)), Some(storage)) =
+                    (&api_key, &rate_limit.api_key_storage)
+                {
+                    match quota.consume(storage.as_ref(), key_id).await {
+                        Ok(_) => {}
+                        Err(e @ Error::QuotaExceeded { .. }) => {
+                            warn!("API key quota exhausted for {}", key_id);
+                            denial = Some(e);
                         }
+                        Err(e) => return Err(e),
+                    }
+                }
+            }
+
+            if let Some(denial) = denial {
+                #[cfg(feature = "audit")]
+                if let Some(ref logger) = audit_logger {
+                    if logger.config().audit_auth_events {
+                        logger
+                            .log_auth(
+                                crate::audit::event::AuditEventKind::HttpRequestDenied,
+                                crate::audit::event::AuditSeverity::Warning,
+                                audit_source,
+                            )
+                            .await;
                     }
-                    return Err(e);
                 }
-            };
+
+                let mut response = denial.into_response();
+                Self::add_rate_limit_headers(&mut response, &result);
+                return Ok(response);
+            }
 
             // Run the request
             let mut response = next.run(request).await;
@@ -155,6 +316,7 @@ impl RateLimit {
         method: &str,
         path: &str,
         claims: Option<&Claims>,
+        api_key: Option<ApiKeyLimit<'_>>,
     ) -> Result<RateLimitResult, Error> {
         let normalized_path = normalize_path(path);
 
@@ -166,8 +328,10 @@ impl RateLimit {
             );
 
             let key = if route_config.per_user {
-                // Per-user route limit
-                if let Some(claims) = claims {
+                // Per-user route limit; an API key counts as its own caller
+                if let Some(api_key) = &api_key {
+                    format!("route:{}:api_key:{}", normalized_path, api_key.id)
+                } else if let Some(claims) = claims {
                     format!("route:{}:user:{}", normalized_path, claims.sub)
                 } else {
                     // No claims, use global route limit
@@ -179,10 +343,25 @@ impl RateLimit {
             };
 
             return self
-                .check_and_increment(
+                .check_limit(
                     &key,
                     route_config.requests_per_minute,
-                    self.config.window_secs,
+                    route_config.burst_size,
+                    route_config.algorithm.unwrap_or(self.config.algorithm),
+                )
+                .await;
+        }
+
+        // API keys carry their own per-minute limit
+        if let Some(api_key) = api_key {
+            let limit = api_key.requests_per_minute;
+            let burst_size = (limit / 10).max(1);
+            return self
+                .check_limit(
+                    &format!("ratelimit:api_key:{}", api_key.id),
+                    limit,
+                    burst_size,
+                    self.config.algorithm,
                 )
                 .await;
         }
@@ -207,8 +386,10 @@ impl RateLimit {
                 )
             };
 
+            // Same 10% burst allowance as the governor middleware
+            let burst_size = (limit / 10).max(1);
             return self
-                .check_and_increment(&key, limit, self.config.window_secs)
+                .check_limit(&key, limit, burst_size, self.config.algorithm)
                 .await;
         }
 
@@ -217,35 +398,85 @@ impl RateLimit {
         warn!("Rate limit middleware called without JWT claims and no route-specific limit");
         Ok(RateLimitResult {
             limit: self.config.per_user_rpm,
-            count: 0,
+            remaining: self.config.per_user_rpm,
             reset_secs: self.config.window_secs,
+            retry_after_secs: None,
         })
     }
 
-    /// Check and increment rate limit counter in Redis
+    /// Check a key against its limit with the selected algorithm
     #[cfg(feature = "cache")]
-    async fn check_and_increment(
+    async fn check_limit(
         &self,
         key: &str,
         limit: u32,
-        window_secs: u64,
+        burst_size: u32,
+        algorithm: RateLimitAlgorithm,
     ) -> Result<RateLimitResult, Error> {
+        let window_secs = self.config.window_secs.max(1);
+
+        if limit == 0 {
+            warn!("Rate limit for {} is zero; denying request", key);
+            return Ok(RateLimitResult {
+                limit,
+                remaining: 0,
+                reset_secs: window_secs,
+                retry_after_secs: Some(window_secs),
+            });
+        }
+
+        let result = match algorithm {
+            RateLimitAlgorithm::FixedWindow => {
+                self.check_and_increment(key, limit, window_secs).await?
+            }
+            RateLimitAlgorithm::SlidingLog => {
+                self.check_sliding_log(key, limit, window_secs).await?
+            }
+            RateLimitAlgorithm::Gcra => {
+                self.check_gcra(key, limit, burst_size.max(1), window_secs)
+                    .await?
+            }
+        };
+
+        if result.retry_after_secs.is_some() {
+            warn!(
+                "Rate limit exceeded for {} ({:?}, limit: {})",
+                key, algorithm, limit
+            );
+        }
+
+        Ok(result)
+    }
+
+    /// Get a pooled Redis connection
+    #[cfg(feature = "cache")]
+    async fn connection(&self) -> Result<deadpool_redis::Connection, Error> {
         let redis_pool = self
             .redis_pool
             .as_ref()
             .ok_or_else(|| Error::Internal("Redis pool not configured".to_string()))?;
 
-        let mut conn = redis_pool.get().await.map_err(|e| {
+        redis_pool.get().await.map_err(|e| {
             let redis_err = redis::RedisError::from((
                 redis::ErrorKind::IoError,
                 "Failed to get Redis connection",
                 e.to_string(),
             ));
             Error::Redis(Box::new(redis_err))
-        })?;
+        })
+    }
+
+    /// Check and increment a fixed-window counter in Redis
+    #[cfg(feature = "cache")]
+    async fn check_and_increment(
+        &self,
+        key: &str,
+        limit: u32,
+        window_secs: u64,
+    ) -> Result<RateLimitResult, Error> {
+        let mut conn = self.connection().await?;
 
         // Use INCR and EXPIRE for simple rate limiting
-        // In production, you might want to use a more sophisticated algorithm (sliding window, token bucket)
         let count: u32 = redis::cmd("INCR")
             .arg(key)
             .query_async(conn.deref_mut())
@@ -269,23 +500,63 @@ impl RateLimit {
 
         let reset_secs = if ttl > 0 { ttl as u64 } else { window_secs };
 
-        // Check if limit exceeded
-        if count > limit {
-            warn!(
-                "Rate limit exceeded for {}: {} requests (limit: {})",
-                key, count, limit
-            );
-            return Err(Error::RateLimitExceeded);
-        }
-
         Ok(RateLimitResult {
             limit,
-            count,
+            remaining: limit.saturating_sub(count),
             reset_secs,
+            retry_after_secs: (count > limit).then_some(reset_secs),
         })
     }
 
+    /// Check a sliding-log limit (atomic Lua script)
+    #[cfg(feature = "cache")]
+    async fn check_sliding_log(
+        &self,
+        key: &str,
+        limit: u32,
+        window_secs: u64,
+    ) -> Result<RateLimitResult, Error> {
+        let mut conn = self.connection().await?;
+
+        let reply: (i64, i64, i64, i64) = SLIDING_LOG_SCRIPT
+            .key(format!("{}:log", key))
+            .arg(limit)
+            .arg(window_secs.saturating_mul(1000))
+            .arg(uuid::Uuid::new_v4().to_string())
+            .invoke_async(conn.deref_mut())
+            .await?;
+
+        Ok(script_result(limit, reply))
+    }
+
+    /// Check a GCRA (token bucket) limit (atomic Lua script)
+    ///
+    /// `limit` requests per window are spaced one emission interval apart;
+    /// up to `burst_size` may arrive at once.
+    #[cfg(feature = "cache")]
+    async fn check_gcra(
+        &self,
+        key: &str,
+        limit: u32,
+        burst_size: u32,
+        window_secs: u64,
+    ) -> Result<RateLimitResult, Error> {
+        let mut conn = self.connection().await?;
+
+        let emission_interval_ms = (window_secs as f64 * 1000.0) / f64::from(limit);
+        let reply: (i64, i64, i64, i64) = GCRA_SCRIPT
+            .key(format!("{}:gcra", key))
+            .arg(emission_interval_ms)
+            .arg(burst_size)
+            .invoke_async(conn.deref_mut())
+            .await?;
+
+        Ok(script_result(burst_size, reply))
+    }
+
     /// Add rate limit headers to response
+    ///
+    /// Denied responses also carry `Retry-After`.
     #[cfg(feature = "cache")]
     fn add_rate_limit_headers(response: &mut Response, result: &RateLimitResult) {
         let headers = response.headers_mut();
@@ -295,8 +566,7 @@ impl RateLimit {
             headers.insert(HeaderName::from_static("x-ratelimit-limit"), value);
         }
 
-        let remaining = result.limit.saturating_sub(result.count);
-        if let Ok(value) = HeaderValue::from_str(&remaining.to_string()) {
+        if let Ok(value) = HeaderValue::from_str(&result.remaining.to_string()) {
             headers.insert(HeaderName::from_static("x-ratelimit-remaining"), value);
         }
 
@@ -309,13 +579,42 @@ impl RateLimit {
         if let Ok(value) = HeaderValue::from_str(&reset_timestamp.to_string()) {
             headers.insert(HeaderName::from_static("x-ratelimit-reset"), value);
         }
+
+        if let Some(retry_after) = result.retry_after_secs {
+            if let Ok(value) = HeaderValue::from_str(&retry_after.to_string()) {
+                headers.insert(axum::http::header::RETRY_AFTER, value);
+            }
+        }
     }
 }
 
+/// Convert a Lua `{allowed, remaining, reset_ms, retry_after_ms}` reply
+#[cfg(feature = "cache")]
+fn script_result(
+    limit: u32,
+    (allowed, remaining, reset_ms, retry_after_ms): (i64, i64, i64, i64),
+) -> RateLimitResult {
+    RateLimitResult {
+        limit,
+        remaining: u32::try_from(remaining.max(0)).unwrap_or(limit).min(limit),
+        reset_secs: ceil_secs(reset_ms),
+        // Never tell a denied client to retry immediately
+        retry_after_secs: (allowed == 0).then(|| ceil_secs(retry_after_ms).max(1)),
+    }
+}
+
+/// Round milliseconds up to whole seconds
+#[cfg(feature = "cache")]
+fn ceil_secs(ms: i64) -> u64 {
+    u64::try_from(ms.max(0)).unwrap_or(0).div_ceil(1000)
+}
+
 #[cfg(test)]
 mod tests {
     #[cfg(not(feature = "cache"))]
     use super::{RateLimit, RateLimitConfig};
+    #[cfg(not(feature = "cache"))]
+    use crate::config::RateLimitAlgorithm;
 
     #[test]
     fn test_rate_limit_creation() {
@@ -325,7 +624,9 @@ mod tests {
                 per_user_rpm: 200,
                 per_client_rpm: 1000,
                 window_secs: 60,
+                algorithm: RateLimitAlgorithm::default(),
                 routes: std::collections::HashMap::new(),
+                api_key_scopes: std::collections::HashMap::new(),
                 auto_apply: true,
                 trust_forwarded_headers: false,
             };
@@ -349,6 +650,7 @@ mod tests {
                     requests_per_minute: 10,
                     burst_size: 2,
                     per_user: true,
+                    algorithm: None,
                 },
             );
 
@@ -356,7 +658,9 @@ mod tests {
                 per_user_rpm: 200,
                 per_client_rpm: 1000,
                 window_secs: 60,
+                algorithm: RateLimitAlgorithm::default(),
                 routes,
+                api_key_scopes: std::collections::HashMap::new(),
                 auto_apply: true,
                 trust_forwarded_headers: false,
             };
@@ -366,4 +670,51 @@ mod tests {
             assert!(!rate_limit.route_patterns.is_empty());
         }
     }
+
+    #[cfg(feature = "cache")]
+    #[test]
+    fn test_ceil_secs_rounds_up() {
+        use super::ceil_secs;
+
+        assert_eq!(ceil_secs(0), 0);
+        assert_eq!(ceil_secs(1), 1);
+        assert_eq!(ceil_secs(1000), 1);
+        assert_eq!(ceil_secs(1001), 2);
+        assert_eq!(ceil_secs(-5), 0);
+    }
+
+    #[cfg(feature = "cache")]
+    #[test]
+    fn test_script_result_allowed() {
+        use super::{script_result, RateLimitResult};
+
+        assert_eq!(
+            script_result(10, (1, 7, 2500, 0)),
+            RateLimitResult {
+                limit: 10,
+                remaining: 7,
+                reset_secs: 3,
+                retry_after_secs: None,
+            }
+        );
+    }
+
+    #[cfg(feature = "cache")]
+    #[test]
+    fn test_script_result_denied_sets_retry_after() {
+        use super::{script_result, RateLimitResult};
+
+        assert_eq!(
+            script_result(5, (0, 0, 12_000, 1_200)),
+            RateLimitResult {
+                limit: 5,
+                remaining: 0,
+                reset_secs: 12,
+                retry_after_secs: Some(2),
+            }
+        );
+
+        // A sub-millisecond wait still asks the client to back off
+        assert_eq!(script_result(5, (0, 0, 0, 0)).retry_after_secs, Some(1));
+    }
 }
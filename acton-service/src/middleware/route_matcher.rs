@@ -1,7 +1,8 @@
-//! Per-route rate limit matching
+//! Per-route configuration matching
 //!
-//! Provides route pattern matching for per-route rate limiting configuration.
-//! Supports exact paths, method prefixes, wildcards, and automatic ID normalization.
+//! Provides route pattern matching for per-route middleware configuration
+//! (rate limits, idempotency keys). Supports exact paths, method prefixes,
+//! wildcards, and automatic ID normalization.
 
 use regex::Regex;
 use std::cmp::Reverse;
@@ -22,21 +23,24 @@ static UUID_REGEX: LazyLock<Regex> = LazyLock::new(|| {
 /// 1. Method-prefixed exact matches (e.g., `POST /api/v1/uploads`)
 /// 2. Exact path matches (e.g., `/api/v1/users`)
 /// 3. Wildcard patterns sorted by specificity (most specific first)
+///
+/// Generic over the per-route configuration type; defaults to
+/// [`RouteRateLimitConfig`].
 #[derive(Debug, Clone)]
-pub struct CompiledRoutePatterns {
+pub struct CompiledRoutePatterns<C = RouteRateLimitConfig> {
     /// Method-prefixed exact matches (e.g., "POST /api/v1/uploads" -> config)
-    method_exact: HashMap<String, RouteRateLimitConfig>,
+    method_exact: HashMap<String, C>,
 
     /// Exact path matches (e.g., "/api/v1/users" -> config)
-    exact: HashMap<String, RouteRateLimitConfig>,
+    exact: HashMap<String, C>,
 
     /// Wildcard patterns sorted by specificity (most specific first)
-    patterns: Vec<CompiledPattern>,
+    patterns: Vec<CompiledPattern<C>>,
 }
 
 /// A compiled wildcard pattern
 #[derive(Debug, Clone)]
-struct CompiledPattern {
+struct CompiledPattern<C> {
     /// Original pattern string for debugging
     #[allow(dead_code)]
     original: String,
@@ -47,29 +51,29 @@ struct CompiledPattern {
     /// Compiled regex for path matching
     regex: Regex,
 
-    /// Rate limit configuration
-    config: RouteRateLimitConfig,
+    /// Route configuration
+    config: C,
 
     /// Specificity score (higher = more specific, takes priority)
     specificity: usize,
 }
 
-impl CompiledRoutePatterns {
+impl<C: Clone> CompiledRoutePatterns<C> {
     /// Compile route patterns from configuration
     ///
     /// Parses route pattern strings and compiles them into efficient matchers.
-    pub fn compile(routes: &HashMap<String, RouteRateLimitConfig>) -> Self {
+    pub fn compile(routes: &HashMap<String, C>) -> Self {
         let mut method_exact = HashMap::new();
         let mut exact = HashMap::new();
         let mut patterns = Vec::new();
 
         for (pattern, config) in routes {
-            let (method, path) = Self::parse_method_prefix(pattern);
+            let (method, path) = <CompiledRoutePatterns>::parse_method_prefix(pattern);
 
-            if Self::has_wildcards(&path) || path.contains("{id}") {
+            if <CompiledRoutePatterns>::has_wildcards(&path) || path.contains("{id}") {
                 // Wildcard pattern - compile to regex
-                let regex = Self::compile_pattern_to_regex(&path);
-                let specificity = Self::calculate_specificity(&path);
+                let regex = <CompiledRoutePatterns>::compile_pattern_to_regex(&path);
+                let specificity = <CompiledRoutePatterns>::calculate_specificity(&path);
 
                 patterns.push(CompiledPattern {
                     original: pattern.clone(),
@@ -100,16 +104,16 @@ impl CompiledRoutePatterns {
 
     /// Match a request path and method against configured patterns
     ///
-    /// Returns the rate limit config for the most specific matching pattern,
-    /// or None if no patterns match.
+    /// Returns the config for the most specific matching pattern, or None if
+    /// no patterns match.
     ///
     /// # Arguments
     /// * `method` - HTTP method (e.g., "GET", "POST")
     /// * `path` - Request path (e.g., "/api/v1/users/123")
     ///
     /// # Returns
-    /// The `RouteRateLimitConfig` for the matching pattern, or None.
-    pub fn match_route(&self, method: &str, path: &str) -> Option<&RouteRateLimitConfig> {
+    /// The route config for the matching pattern, or None.
+    pub fn match_route(&self, method: &str, path: &str) -> Option<&C> {
         // Normalize the path (replace IDs with {id})
         let normalized = normalize_path(path);
 
@@ -146,7 +150,9 @@ impl CompiledRoutePatterns {
     pub fn is_empty(&self) -> bool {
         self.method_exact.is_empty() && self.exact.is_empty() && self.patterns.is_empty()
     }
+}
 
+impl CompiledRoutePatterns {
     /// Parse method prefix from pattern (e.g., "POST /api/users" -> (Some("POST"), "/api/users"))
     fn parse_method_prefix(pattern: &str) -> (Option<String>, String) {
         let trimmed = pattern.trim();
@@ -352,6 +358,7 @@ mod tests {
                 requests_per_minute: 100,
                 burst_size: 10,
                 per_user: true,
+                algorithm: None,
             },
         );
 
@@ -373,6 +380,7 @@ mod tests {
                 requests_per_minute: 10,
                 burst_size: 2,
                 per_user: true,
+                algorithm: None,
             },
         );
         routes.insert(
@@ -381,6 +389,7 @@ mod tests {
                 requests_per_minute: 100,
                 burst_size: 10,
                 per_user: true,
+                algorithm: None,
             },
         );
 
@@ -406,6 +415,7 @@ mod tests {
                 requests_per_minute: 50,
                 burst_size: 5,
                 per_user: true,
+                algorithm: None,
             },
         );
 
@@ -432,6 +442,7 @@ mod tests {
                 requests_per_minute: 20,
                 burst_size: 2,
                 per_user: true,
+                algorithm: None,
             },
         );
 
@@ -456,6 +467,7 @@ mod tests {
                 requests_per_minute: 100,
                 burst_size: 10,
                 per_user: true,
+                algorithm: None,
             },
         );
         // More specific pattern
@@ -465,6 +477,7 @@ mod tests {
                 requests_per_minute: 50,
                 burst_size: 5,
                 per_user: true,
+                algorithm: None,
             },
         );
 
@@ -174,6 +174,80 @@ impl NatsClientHealth {
     }
 }
 
+/// Transactional outbox backlog status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg(all(
+    feature = "events",
+    any(feature = "database", feature = "turso", feature = "surrealdb")
+))]
+pub struct OutboxHealth {
+    /// Storage backend holding the outbox
+    pub backend: String,
+
+    /// Rows waiting to be published
+    pub pending: u64,
+
+    /// Rows that exhausted their publish attempts
+    pub failed: u64,
+
+    /// Age in seconds of the oldest pending row
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_pending_age_secs: Option<u64>,
+
+    /// Whether the relay is keeping up
+    pub healthy: bool,
+
+    /// Why the backlog could not be read
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[cfg(all(
+    feature = "events",
+    any(feature = "database", feature = "turso", feature = "surrealdb")
+))]
+impl OutboxHealth {
+    /// Create health status from a backlog reading
+    ///
+    /// Unhealthy once the oldest pending row is older than
+    /// `max_pending_age_secs` (0 disables the check). Failed rows are
+    /// reported but do not affect health: they wait for an operator, not
+    /// for the relay.
+    pub fn from_backlog(
+        backend: &str,
+        backlog: &crate::events::outbox::OutboxBacklog,
+        max_pending_age_secs: u64,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> Self {
+        let oldest_pending_age_secs = backlog
+            .oldest_pending
+            .map(|oldest| u64::try_from((now - oldest).num_seconds()).unwrap_or(0));
+        let healthy = max_pending_age_secs == 0
+            || oldest_pending_age_secs.is_none_or(|age| age <= max_pending_age_secs);
+
+        Self {
+            backend: backend.to_string(),
+            pending: backlog.pending,
+            failed: backlog.failed,
+            oldest_pending_age_secs,
+            healthy,
+            error: None,
+        }
+    }
+
+    /// Create health status for an outbox whose backlog could not be read
+    pub fn unavailable(backend: &str, error: String) -> Self {
+        Self {
+            backend: backend.to_string(),
+            pending: 0,
+            failed: 0,
+            oldest_pending_age_secs: None,
+            healthy: false,
+            error: Some(error),
+        }
+    }
+}
+
 /// ClickHouse analytical database health status
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[cfg(feature = "clickhouse")]
@@ -233,6 +307,14 @@ pub struct PoolHealthSummary {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub clickhouse: Option<ClickHouseHealth>,
 
+    /// Transactional outbox backlog
+    #[cfg(all(
+        feature = "events",
+        any(feature = "database", feature = "turso", feature = "surrealdb")
+    ))]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub outbox: Option<OutboxHealth>,
+
     /// Overall healthy status
     pub healthy: bool,
 }
@@ -253,6 +335,11 @@ impl PoolHealthSummary {
             surrealdb: None,
             #[cfg(feature = "clickhouse")]
             clickhouse: None,
+            #[cfg(all(
+                feature = "events",
+                any(feature = "database", feature = "turso", feature = "surrealdb")
+            ))]
+            outbox: None,
             healthy: true,
         }
     }
@@ -325,12 +412,30 @@ impl PoolHealthSummary {
             }
         };
 
+        let outbox_healthy = {
+            #[cfg(all(
+                feature = "events",
+                any(feature = "database", feature = "turso", feature = "surrealdb")
+            ))]
+            {
+                self.outbox.as_ref().is_none_or(|outbox| outbox.healthy)
+            }
+            #[cfg(not(all(
+                feature = "events",
+                any(feature = "database", feature = "turso", feature = "surrealdb")
+            )))]
+            {
+                true
+            }
+        };
+
         database_healthy
             && cache_healthy
             && events_healthy
             && turso_healthy
             && surrealdb_healthy
             && clickhouse_healthy
+            && outbox_healthy
     }
 }
 
@@ -353,6 +458,58 @@ mod tests {
         );
     }
 
+    #[cfg(all(
+        feature = "events",
+        any(feature = "database", feature = "turso", feature = "surrealdb")
+    ))]
+    mod outbox_health_tests {
+        use super::*;
+        use crate::events::outbox::OutboxBacklog;
+        use chrono::{Duration, Utc};
+
+        #[test]
+        fn test_outbox_health_reports_oldest_pending_age() {
+            let now = Utc::now();
+            let backlog = OutboxBacklog {
+                pending: 3,
+                failed: 1,
+                oldest_pending: Some(now - Duration::seconds(42)),
+            };
+            let health = OutboxHealth::from_backlog("postgres", &backlog, 300, now);
+
+            assert!(health.healthy);
+            assert_eq!(health.pending, 3);
+            assert_eq!(health.failed, 1);
+            assert_eq!(health.oldest_pending_age_secs, Some(42));
+        }
+
+        #[test]
+        fn test_outbox_health_unhealthy_when_backlog_is_stale() {
+            let now = Utc::now();
+            let backlog = OutboxBacklog {
+                pending: 1,
+                failed: 0,
+                oldest_pending: Some(now - Duration::seconds(301)),
+            };
+
+            assert!(!OutboxHealth::from_backlog("turso", &backlog, 300, now).healthy);
+            assert!(OutboxHealth::from_backlog("turso", &backlog, 0, now).healthy);
+
+            let mut summary = PoolHealthSummary::new();
+            summary.outbox = Some(OutboxHealth::from_backlog("turso", &backlog, 300, now));
+            assert!(!summary.is_healthy());
+        }
+
+        #[test]
+        fn test_outbox_health_empty_backlog_is_healthy() {
+            let health =
+                OutboxHealth::from_backlog("postgres", &OutboxBacklog::default(), 300, Utc::now());
+
+            assert!(health.healthy);
+            assert!(health.oldest_pending_age_secs.is_none());
+        }
+    }
+
     #[cfg(feature = "clickhouse")]
     mod clickhouse_health_tests {
         use super::*;
@@ -306,6 +306,17 @@ impl ValidationError {
     pub fn error_count(&self) -> usize {
         self.errors.values().map(|v| v.len()).sum()
     }
+
+    /// Get the top-level error message
+    pub fn message(&self) -> &str {
+        &self.error
+    }
+}
+
+impl std::fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.error)
+    }
 }
 
 impl IntoResponse for ValidationError {
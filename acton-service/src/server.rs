@@ -176,6 +176,14 @@ impl Server {
                 let source = crate::tls::TlsConfigSource::from_tls_config(tls_config)?;
                 crate::tls::warn_if_reload_config_is_unusable(Some(&source), tls_config, "[tls]");
 
+                // The caller's router is final; the `http-01` route goes on
+                // after it so no layer of theirs asks the CA for credentials.
+                #[cfg(feature = "acme")]
+                let app = match crate::acme::http01_router([&source]) {
+                    Some(http01) => app.merge(http01),
+                    None => app,
+                };
+
                 // This path serves one listener, so the handle carries only the
                 // HTTP slot and there is no gRPC interval to pass.
                 let reload_handle = crate::tls::TlsReloadHandle::new(Some(source.clone()), None);
@@ -426,9 +434,13 @@ mod tests {
                 key_path: "/nonexistent/key.pem".into(),
                 client_ca_path: None,
                 client_auth_optional: false,
+                client_crl_paths: Vec::new(),
                 reload_interval_secs: Some(0),
                 reload_on_sighup: false,
                 handshake_timeout_secs: None,
+                expiry_warning_days: None,
+                certificates: Vec::new(),
+                acme: None,
             }),
             ..Default::default()
         };
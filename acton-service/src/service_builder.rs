@@ -147,12 +147,19 @@ where
     /// serve time. See [`ServiceBuilder::with_tls_reload`].
     #[cfg(feature = "tls")]
     tls_reload_hook: Option<Box<dyn FnOnce(crate::tls::TlsReloadHandle) + Send>>,
+    /// Certificates reported by the `tls_certificates` readiness check. The
+    /// listeners are added once their TLS resolves in `build()`.
+    #[cfg(feature = "tls")]
+    cert_expiry: crate::cert_expiry::CertificateExpiryMonitor,
     /// App-defined liveness checks folded into `/health`.
     liveness_checks: Vec<crate::checks::RegisteredCheck>,
     /// App-defined readiness checks folded into `/ready`.
     readiness_checks: Vec<crate::checks::RegisteredCheck>,
     /// Shared deadline for one endpoint's registered checks.
     check_deadline: std::time::Duration,
+    /// Handlers for the JetStream consumers declared in `[nats.consumers]`.
+    #[cfg(feature = "events")]
+    event_handlers: crate::events::EventHandlers,
 }
 
 impl<T> ServiceBuilder<T>
@@ -181,9 +188,13 @@ where
             grpc_tls_config_override: None,
             #[cfg(feature = "tls")]
             tls_reload_hook: None,
+            #[cfg(feature = "tls")]
+            cert_expiry: crate::cert_expiry::CertificateExpiryMonitor::new(),
             liveness_checks: Vec::new(),
             readiness_checks: Vec::new(),
             check_deadline: crate::checks::DEFAULT_CHECK_DEADLINE,
+            #[cfg(feature = "events")]
+            event_handlers: crate::events::EventHandlers::default(),
         }
     }
 
@@ -375,6 +386,25 @@ where
         self
     }
 
+    /// Monitor the client certificate an outbound identity presents.
+    ///
+    /// Adds `source` to the `tls_certificates` readiness check alongside the
+    /// listeners' certificates, with its `notAfter` in the check's message and
+    /// the `tls.certificate.not_after` gauge, and alerts as it nears expiry.
+    /// `name` identifies it in all three, typically the peer it calls. See
+    /// [`crate::cert_expiry`] for the thresholds.
+    ///
+    /// Repeatable, once per identity.
+    #[cfg(feature = "tls")]
+    pub fn with_client_identity(
+        self,
+        name: impl Into<String>,
+        source: &crate::client_tls::ClientIdentitySource,
+    ) -> Self {
+        self.cert_expiry.watch_client_identity(name, source);
+        self
+    }
+
     /// Register a custom actor extension.
     ///
     /// The actor will be spawned under a framework-managed supervisor during
@@ -401,6 +431,47 @@ where
         self
     }
 
+    /// Register the handler for a JetStream consumer declared in
+    /// `[nats.consumers.<name>]`.
+    ///
+    /// The NATS pool agent declares `[[nats.streams]]`, binds the durable
+    /// consumer once connected, and feeds each message to `handler`, settling it
+    /// with the returned [`AckAction`](crate::events::AckAction). On shutdown
+    /// the consumer stops fetching and in-flight handlers get up to
+    /// `drain_timeout_secs` to finish before the connection closes.
+    ///
+    /// Every declared consumer needs exactly one handler, and every handler a
+    /// declared consumer; a mismatch is a startup error. Registering the same
+    /// name twice replaces the earlier handler.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// use acton_service::events::{json_handler, AckAction};
+    ///
+    /// ServiceBuilder::new()
+    ///     .with_event_handler(
+    ///         "order-projector",
+    ///         json_handler(|order: OrderCreated, _msg| async move {
+    ///             project(order).await?;
+    ///             Ok(AckAction::Ack)
+    ///         }),
+    ///     )
+    ///     .build()
+    ///     .serve()
+    ///     .await?;
+    /// ```
+    #[cfg(feature = "events")]
+    pub fn with_event_handler(
+        mut self,
+        consumer: impl Into<String>,
+        handler: impl crate::events::EventHandler,
+    ) -> Self {
+        self.event_handlers
+            .insert(consumer.into(), std::sync::Arc::new(handler));
+        self
+    }
+
     /// Set the service configuration (optional, defaults to Config::default())
     pub fn with_config(mut self, config: Config<T>) -> Self {
         self.config = Some(config);
@@ -641,6 +712,16 @@ where
             eprintln!("Warning: Failed to initialize metrics: {}", e);
         }
 
+        // Consumers and handlers must pair up before anything connects
+        #[cfg(feature = "events")]
+        if let Err(e) =
+            crate::events::validate_consumers(config.nats.as_ref(), &self.event_handlers)
+        {
+            record_startup_error(&mut startup_error, e);
+        }
+        #[cfg(feature = "events")]
+        let event_handlers = std::mem::take(&mut self.event_handlers);
+
         // Determine if we need to spawn pool agents
         #[cfg(feature = "database")]
         let needs_db_agent = config.database.is_some();
@@ -747,6 +828,27 @@ where
                 None
             };
 
+        // Consumers that opted in to deduplication get their handlers wrapped.
+        // The dedup store is built from the shared pool once its agent connects.
+        #[cfg(all(feature = "events", any(feature = "cache", feature = "database")))]
+        let event_handlers = {
+            let mut event_handlers = event_handlers;
+            let pools = crate::events::dedup::DedupPools {
+                #[cfg(feature = "cache")]
+                redis: shared_redis_pool.clone(),
+                #[cfg(feature = "database")]
+                db: shared_db_pool.clone(),
+            };
+            if let Err(e) = crate::events::dedup::guard_handlers(
+                &mut event_handlers,
+                config.nats.as_ref(),
+                &pools,
+            ) {
+                record_startup_error(&mut startup_error, e);
+            }
+            event_handlers
+        };
+
         // Agent handles for AppState
         #[cfg(feature = "database")]
         let mut db_agent_handle: Option<acton_reactive::prelude::ActorHandle> = None;
@@ -828,6 +930,7 @@ where
                                 runtime,
                                 nats_config.clone(),
                                 shared_nats_client.clone(),
+                                event_handlers.clone(),
                             )
                             .await
                             {
@@ -1297,6 +1400,39 @@ where
             state
         };
 
+        // Certificate expiry joins the readiness checks whenever anything may
+        // present or trust a certificate. The listeners' TLS resolves further
+        // down; the check reads the shared monitor, so it sees them once they
+        // are added.
+        #[cfg(feature = "tls")]
+        {
+            #[allow(unused_mut)]
+            let mut tls_in_play = self.tls_config_override.is_some()
+                || config.tls.as_ref().is_some_and(|tls| tls.enabled)
+                || !self.cert_expiry.is_empty();
+            #[cfg(feature = "grpc")]
+            {
+                tls_in_play |= self.grpc_tls_config_override.is_some()
+                    || config
+                        .grpc
+                        .as_ref()
+                        .and_then(|grpc| grpc.tls.as_ref())
+                        .is_some_and(|tls| tls.enabled);
+            }
+            if tls_in_play {
+                state.set_cert_expiry(self.cert_expiry.clone());
+                let monitor = self.cert_expiry.clone();
+                self.readiness_checks
+                    .push(crate::checks::RegisteredCheck::new(
+                        crate::cert_expiry::READINESS_CHECK_NAME,
+                        move || {
+                            let monitor = monitor.clone();
+                            async move { monitor.check() }
+                        },
+                    ));
+            }
+        }
+
         // Install app-defined checks on whichever state is in play — built
         // here or caller-provided — so `/health` and `/ready` see them either
         // way.
@@ -1306,6 +1442,72 @@ where
             self.check_deadline,
         ));
 
+        // The outbox relay runs on the background worker, resolving its
+        // storage from whichever database pool connects.
+        #[cfg(all(
+            feature = "events",
+            any(feature = "database", feature = "turso", feature = "surrealdb")
+        ))]
+        if let Some(outbox) = config
+            .nats
+            .as_ref()
+            .and_then(|nats| nats.outbox.clone())
+            .filter(|outbox| outbox.enabled)
+        {
+            #[allow(unused_mut)]
+            let mut has_database = false;
+            #[cfg(feature = "database")]
+            {
+                has_database |= config.database.is_some();
+            }
+            #[cfg(feature = "turso")]
+            {
+                has_database |= config.turso.is_some();
+            }
+            #[cfg(feature = "surrealdb")]
+            {
+                has_database |= config.surrealdb.is_some();
+            }
+
+            if let Err(err) = outbox.validate() {
+                record_startup_error(&mut startup_error, err);
+            } else if !has_database {
+                record_startup_error(
+                    &mut startup_error,
+                    crate::error::Error::Internal(
+                        "[nats.outbox] is enabled but no [database], [turso] or [surrealdb] \
+                         section is configured to hold the outbox table"
+                            .to_string(),
+                    ),
+                );
+            } else if let Some(worker) = state.background_worker().cloned() {
+                let relay_state = state.clone();
+                match tokio::runtime::Handle::try_current() {
+                    Ok(handle) => {
+                        handle.spawn(async move {
+                            worker
+                                .submit("event-outbox-relay", move || {
+                                    crate::events::outbox::run_relay(relay_state, outbox)
+                                })
+                                .await;
+                        });
+                    }
+                    Err(_) => {
+                        tracing::warn!("No tokio runtime available for the event outbox relay");
+                    }
+                }
+            } else {
+                record_startup_error(
+                    &mut startup_error,
+                    crate::error::Error::Internal(
+                        "[nats.outbox] is enabled but the background worker is not; set \
+                         [background_worker] enabled = true so the outbox relay can run"
+                            .to_string(),
+                    ),
+                );
+            }
+        }
+
         // Clone state before it's consumed by Router::with_state().
         // AppState uses Arc internally, so this is a cheap reference-count bump.
         let state_clone = state.clone();
@@ -1488,86 +1690,36 @@ where
 
         let mut app = Self::apply_middleware(app, &config, tls_active);
 
-        // Apply session middleware if configured
-        // Session runs after general middleware, before JWT/Cedar
-        // This allows session-based auth and JWT auth to coexist
-        #[cfg(feature = "session")]
-        if let Some(ref session_config) = config.session {
-            use crate::session::SessionStorage;
-
-            match session_config.storage {
-                #[cfg(feature = "session-memory")]
-                SessionStorage::Memory => {
-                    use crate::session::create_memory_session_layer;
-                    tracing::info!("Initializing in-memory session store");
-                    let session_layer = create_memory_session_layer(session_config);
-                    app = app.layer(session_layer);
-                }
-                #[cfg(feature = "session-redis")]
-                SessionStorage::Redis => {
-                    use crate::session::create_redis_session_layer;
-                    if let Some(ref redis_url) = session_config.redis_url {
-                        tracing::info!("Initializing Redis session store");
-                        // Checked before any connection attempt, so a
-                        // single-threaded runtime fails fast rather than
-                        // reaching Redis and then panicking in tokio.
-                        match runtime_supports_block_in_place() {
-                            Some(true) => {
-                                let session_config_clone = session_config.clone();
-                                let redis_url_clone = redis_url.clone();
-                                match tokio::task::block_in_place(|| {
-                                    tokio::runtime::Handle::current().block_on(async {
-                                        create_redis_session_layer(
-                                            &session_config_clone,
-                                            &redis_url_clone,
-                                        )
-                                        .await
-                                    })
-                                }) {
-                                    Ok(session_layer) => {
-                                        app = app.layer(session_layer);
-                                    }
-                                    Err(e) => {
-                                        tracing::error!(
-                                            "Failed to create Redis session store: {}",
-                                            e
-                                        );
-                                    }
-                                }
-                            }
-                            Some(false) => {
-                                let err = crate::error::Error::Internal(
-                                    "Redis-backed sessions are enabled but the tokio runtime \
-                                     is single-threaded, so the session store cannot be \
-                                     initialized from ServiceBuilder::build(); use a \
-                                     multi-threaded runtime (#[tokio::main], or \
-                                     #[tokio::test(flavor = \"multi_thread\")] in tests) or \
-                                     set [session] storage = \"memory\""
-                                        .to_string(),
-                                );
-                                tracing::error!("{}", err);
-                                record_startup_error(&mut startup_error, err);
-                            }
-                            None => {
-                                tracing::error!(
-                                    "No tokio runtime available for Redis session initialization"
-                                );
-                            }
-                        }
-                    } else {
-                        tracing::error!(
-                            "Redis session storage configured but redis_url is missing"
-                        );
-                    }
-                }
-                #[cfg(not(feature = "session-memory"))]
-                SessionStorage::Memory => {
-                    tracing::error!("Memory session storage requested but 'session-memory' feature is not enabled");
-                }
-                #[cfg(not(feature = "session-redis"))]
-                SessionStorage::Redis => {
-                    tracing::error!("Redis session storage requested but 'session-redis' feature is not enabled");
-                }
+        // Auto-apply Idempotency-Key middleware if configured
+        // NOTE: Applied BEFORE Cedar in layer order, so it runs after auth:
+        // Request → Token Auth → Cedar → Idempotency → General MW → Handler
+        // Keys are scoped to Claims, and only authorized requests are stored or replayed.
+        // It also runs before the general body limit, hence `max_request_bytes`.
+        #[cfg(feature = "idempotency")]
+        if let Some(ref idempotency_config) = config.middleware.idempotency {
+            if !idempotency_config.enabled {
+                tracing::debug!("Idempotency-Key middleware disabled by configuration");
+            } else if config.redis.is_none() {
+                let err = crate::error::Error::Internal(
+                    "[middleware.idempotency] is enabled but no [redis] section is configured"
+                        .to_string(),
+                );
+                tracing::error!("{}", err);
+                record_startup_error(&mut startup_error, err);
+            } else {
+                tracing::debug!("Auto-applying Idempotency-Key middleware");
+                // Layered outside the body limit, so it must not buffer more
+                let mut idempotency_config = idempotency_config.clone();
+                idempotency_config.max_request_bytes = idempotency_config
+                    .max_request_bytes
+                    .min(config.middleware.body_limit_mb * 1024 * 1024);
+                app = app.layer(axum::middleware::from_fn_with_state(
+                    crate::middleware::idempotency::Idempotency::with_shared_pool(
+                        idempotency_config,
+                        state_clone.redis_lock().clone(),
+                    ),
+                    crate::middleware::idempotency::Idempotency::middleware,
+                ));
             }
         }
 
@@ -1607,10 +1759,40 @@ where
         // middleware. The layer is attached to the OUTER router, so
         // `request.uri().path()` sees the full pre-nest path -- route keys like
         // "POST /api/v1/uploads" match as documented.
+        //
+        // API key authentication is resolved first so the governor counts
+        // daily and monthly quotas in the same storage keys are verified in.
+        #[cfg(feature = "auth")]
+        let api_key_auth = match config.auth.as_ref().and_then(|a| a.api_keys.as_ref()) {
+            Some(api_key_config) if !api_key_config.enabled => {
+                tracing::debug!("API key authentication disabled by configuration");
+                None
+            }
+            Some(api_key_config) => {
+                match crate::middleware::api_key::ApiKeyAuth::from_state(
+                    api_key_config,
+                    &state_clone,
+                ) {
+                    Ok(api_key_auth) => Some(api_key_auth),
+                    Err(e) => {
+                        tracing::error!("{}", e);
+                        record_startup_error(&mut startup_error, e);
+                        None
+                    }
+                }
+            }
+            None => None,
+        };
+
         #[cfg(feature = "governor")]
         if config.rate_limit.auto_apply {
             let gov =
                 crate::middleware::governor::GovernorRateLimit::new(config.rate_limit.clone());
+            #[cfg(feature = "auth")]
+            let gov = match &api_key_auth {
+                Some(api_key_auth) => gov.with_api_key_storage(api_key_auth.quota_storage()),
+                None => gov,
+            };
             tracing::debug!("Auto-applying governor rate-limit middleware");
             app = app.layer(axum::middleware::from_fn_with_state(
                 gov,
@@ -1694,6 +1876,210 @@ where
             }
         }
 
+        // Auto-apply API key authentication if `[auth.api_keys]` is configured.
+        //
+        // Applied AFTER token auth in source order, so it runs BEFORE it: a
+        // request with a valid key reaches the token middleware already
+        // authenticated and is let through. With a `[token]` section as well,
+        // the two are alternatives and requests without a key header fall
+        // through to token validation; without one, every request needs a key.
+        #[cfg(feature = "auth")]
+        if let Some(api_key_auth) = api_key_auth {
+            tracing::debug!(
+                either_bearer = config.token.is_some(),
+                "Auto-applying API key authentication middleware"
+            );
+            app = app.layer(axum::middleware::from_fn_with_state(
+                api_key_auth.with_bearer_fallback(config.token.is_some()),
+                crate::middleware::api_key::ApiKeyAuth::middleware,
+            ));
+        }
+
+        // Apply session middleware if configured
+        //
+        // Applied AFTER the authentication layers in source order, so it runs
+        // BEFORE them: Request → General MW → Session → API Key → Token Auth
+        // → Cedar → Handler. Session-based auth and token auth coexist, and
+        // with `[session.claims]` enabled the claims bridge (layered first, so
+        // it runs inside the session layer) hands a signed-in session's
+        // Claims to token auth, Cedar, the governor, and audit.
+        #[cfg(feature = "session")]
+        if let Some(ref session_config) = config.session {
+            use crate::session::SessionStorage;
+
+            if session_config.claims.enabled {
+                tracing::debug!("Auto-applying session claims middleware");
+                app = app.layer(axum::middleware::from_fn(
+                    crate::session::session_claims_middleware,
+                ));
+            }
+
+            match session_config.storage {
+                #[cfg(feature = "session-memory")]
+                SessionStorage::Memory => {
+                    use crate::session::create_memory_session_layer;
+                    tracing::info!("Initializing in-memory session store");
+                    let session_layer = create_memory_session_layer(session_config);
+                    app = app.layer(session_layer);
+                }
+                #[cfg(feature = "session-redis")]
+                SessionStorage::Redis => {
+                    use crate::session::create_redis_session_layer;
+                    if let Some(ref redis_url) = session_config.redis_url {
+                        tracing::info!("Initializing Redis session store");
+                        // Checked before any connection attempt, so a
+                        // single-threaded runtime fails fast rather than
+                        // reaching Redis and then panicking in tokio.
+                        match runtime_supports_block_in_place() {
+                            Some(true) => {
+                                let session_config_clone = session_config.clone();
+                                let redis_url_clone = redis_url.clone();
+                                match tokio::task::block_in_place(|| {
+                                    tokio::runtime::Handle::current().block_on(async {
+                                        create_redis_session_layer(
+                                            &session_config_clone,
+                                            &redis_url_clone,
+                                        )
+                                        .await
+                                    })
+                                }) {
+                                    Ok(session_layer) => {
+                                        app = app.layer(session_layer);
+                                    }
+                                    Err(e) => {
+                                        tracing::error!(
+                                            "Failed to create Redis session store: {}",
+                                            e
+                                        );
+                                    }
+                                }
+                            }
+                            Some(false) => {
+                                let err = crate::error::Error::Internal(
+                                    "Redis-backed sessions are enabled but the tokio runtime \
+                                     is single-threaded, so the session store cannot be \
+                                     initialized from ServiceBuilder::build(); use a \
+                                     multi-threaded runtime (#[tokio::main], or \
+                                     #[tokio::test(flavor = \"multi_thread\")] in tests) or \
+                                     set [session] storage = \"memory\""
+                                        .to_string(),
+                                );
+                                tracing::error!("{}", err);
+                                record_startup_error(&mut startup_error, err);
+                            }
+                            None => {
+                                tracing::error!(
+                                    "No tokio runtime available for Redis session initialization"
+                                );
+                            }
+                        }
+                    } else {
+                        tracing::error!(
+                            "Redis session storage configured but redis_url is missing"
+                        );
+                    }
+                }
+                #[cfg(feature = "session-postgres")]
+                SessionStorage::Postgres => {
+                    if config.database.is_none() {
+                        let err = crate::error::Error::Internal(
+                            "[session] storage = \"postgres\" requires a [database] section"
+                                .to_string(),
+                        );
+                        tracing::error!("{}", err);
+                        record_startup_error(&mut startup_error, err);
+                    } else {
+                        tracing::info!("Initializing PostgreSQL session store");
+                        app = crate::session::store::install(
+                            app,
+                            session_config,
+                            crate::session::PgSessionStore::from_shared(
+                                state_clone.db_lock().clone(),
+                            ),
+                            state_clone.background_worker().cloned(),
+                        );
+                    }
+                }
+                #[cfg(feature = "session-turso")]
+                SessionStorage::Turso => {
+                    if config.turso.is_none() {
+                        let err = crate::error::Error::Internal(
+                            "[session] storage = \"turso\" requires a [turso] section".to_string(),
+                        );
+                        tracing::error!("{}", err);
+                        record_startup_error(&mut startup_error, err);
+                    } else {
+                        tracing::info!("Initializing Turso session store");
+                        app = crate::session::store::install(
+                            app,
+                            session_config,
+                            crate::session::TursoSessionStore::from_shared(
+                                state_clone.turso_lock().clone(),
+                            ),
+                            state_clone.background_worker().cloned(),
+                        );
+                    }
+                }
+                #[cfg(feature = "session-surrealdb")]
+                SessionStorage::SurrealDb => {
+                    if config.surrealdb.is_none() {
+                        let err = crate::error::Error::Internal(
+                            "[session] storage = \"surrealdb\" requires a [surrealdb] section"
+                                .to_string(),
+                        );
+                        tracing::error!("{}", err);
+                        record_startup_error(&mut startup_error, err);
+                    } else {
+                        tracing::info!("Initializing SurrealDB session store");
+                        app = crate::session::store::install(
+                            app,
+                            session_config,
+                            crate::session::SurrealSessionStore::from_shared(
+                                state_clone.surrealdb_lock().clone(),
+                            ),
+                            state_clone.background_worker().cloned(),
+                        );
+                    }
+                }
+                #[cfg(not(feature = "session-memory"))]
+                SessionStorage::Memory => {
+                    tracing::error!("Memory session storage requested but 'session-memory' feature is not enabled");
+                }
+                #[cfg(not(feature = "session-redis"))]
+                SessionStorage::Redis => {
+                    tracing::error!("Redis session storage requested but 'session-redis' feature is not enabled");
+                }
+                #[cfg(not(feature = "session-postgres"))]
+                SessionStorage::Postgres => {
+                    tracing::error!("PostgreSQL session storage requested but 'session-postgres' feature is not enabled");
+                }
+                #[cfg(not(feature = "session-turso"))]
+                SessionStorage::Turso => {
+                    tracing::error!("Turso session storage requested but 'session-turso' feature is not enabled");
+                }
+                #[cfg(not(feature = "session-surrealdb"))]
+                SessionStorage::SurrealDb => {
+                    tracing::error!("SurrealDB session storage requested but 'session-surrealdb' feature is not enabled");
+                }
+            }
+        }
+
+        // Publish the key rotation manager's verification keys at
+        // `/.well-known/jwks.json` and `/.well-known/paserk.json`.
+        //
+        // Merged AFTER the token and Cedar layers so these routes are not
+        // wrapped by them: the services fetching keys to verify tokens have
+        // no token yet. They are also outside the general middleware applied
+        // above (no request ID, timeout, CORS or panic recovery). They ARE
+        // wrapped by the caller-authorization layer below, so with
+        // `[caller_auth] mode = "mtls"` fetching keys takes an allowlisted
+        // client certificate like any other request.
+        #[cfg(feature = "auth")]
+        if let Some(ref km) = key_manager {
+            tracing::debug!("Mounting key rotation publishing routes under /.well-known/");
+            app = app.merge(crate::auth::key_rotation::KeyPublisher::new(km.clone()).router());
+        }
+
         // Auto-apply mutual-TLS caller authorization if configured.
         //
         // Applied AFTER token auth in source order, so — axum applying layers
@@ -1707,7 +2093,7 @@ where
         // silently admits everyone is worse than no config at all.
         #[cfg(feature = "tls")]
         let caller_auth_policy = match config.caller_auth.as_ref() {
-            Some(caller_auth_config) => match caller_auth_config.to_policy() {
+            Some(caller_auth_config) => match caller_auth_config.load_policy() {
                 Ok(policy) => Some(policy),
                 Err(e) => {
                     let err = crate::error::Error::Internal(format!(
@@ -1838,6 +2224,50 @@ where
             }
         };
 
+        // Hand the resolved listeners to the expiry monitor. An inherited gRPC
+        // source is the HTTP one and is watched once.
+        #[cfg(feature = "tls")]
+        {
+            if let Some(ref source) = tls_config {
+                self.cert_expiry.watch_listener("[tls]", source);
+            }
+            #[cfg(feature = "grpc")]
+            if let Some(ref source) = grpc_tls_config {
+                self.cert_expiry.watch_listener("[grpc.tls]", source);
+            }
+            #[cfg(feature = "_metrics")]
+            if !self.cert_expiry.is_empty() {
+                self.cert_expiry.register_gauge();
+            }
+            #[cfg(feature = "audit")]
+            if let Some(alerts) = config
+                .audit
+                .as_ref()
+                .filter(|audit| audit.enabled)
+                .and_then(|audit| audit.alerts.as_ref())
+            {
+                self.cert_expiry = std::mem::take(&mut self.cert_expiry)
+                    .with_alert_hooks(crate::audit::alert_webhook::hooks_from_config(alerts));
+            }
+        }
+
+        // Answer `http-01` challenges for listeners that provision through
+        // ACME. Merged after every layer, caller authorization included, since
+        // the CA fetching the token presents no credentials at all. Unlike the
+        // key-publishing routes above, nothing wraps these routes.
+        #[cfg(feature = "acme")]
+        {
+            let sources = [
+                tls_config.as_ref(),
+                #[cfg(feature = "grpc")]
+                grpc_tls_config.as_ref(),
+            ];
+            if let Some(router) = crate::acme::http01_router(sources.into_iter().flatten()) {
+                tracing::debug!("Mounting ACME http-01 challenge route under /.well-known/");
+                app = app.merge(router);
+            }
+        }
+
         // Cross-check the caller-authorization policy against the listeners it
         // guards. A certificate mode on a listener that never asks for a client
         // certificate rejects every caller, and does so with a message about
@@ -2135,6 +2565,12 @@ where
             grpc_tls_handshake_timeout,
             #[cfg(feature = "tls")]
             tls_reload_on_sighup,
+            #[cfg(feature = "tls")]
+            cert_expiry: self.cert_expiry,
+            #[cfg(feature = "tls")]
+            spiffe_bundles: caller_auth_policy
+                .as_ref()
+                .and_then(|policy| policy.spiffe_bundles().cloned()),
             agent_runtime: self.agent_runtime,
             startup_error,
         }
@@ -2577,6 +3013,13 @@ where
     /// Whether either TLS section asked for `SIGHUP`-driven reloading.
     #[cfg(feature = "tls")]
     tls_reload_on_sighup: bool,
+    /// The certificates whose expiry `serve()` alerts on.
+    #[cfg(feature = "tls")]
+    cert_expiry: crate::cert_expiry::CertificateExpiryMonitor,
+    /// The `[caller_auth.spiffe]` bundles `serve()` keeps current, shared
+    /// with the caller-authorization layers.
+    #[cfg(feature = "tls")]
+    spiffe_bundles: Option<crate::spiffe::SpiffeTrustBundles>,
     agent_runtime: Option<acton_reactive::prelude::ActorRuntime>,
     /// Fatal misconfiguration recorded during `build()`. `serve()` returns this
     /// before binding any listener, so a service that could not honour its
@@ -2775,7 +3218,27 @@ where
         // the trigger tasks, which would otherwise outlive the listeners they
         // rotate. Every serve path below is covered by this one call.
         #[cfg(feature = "tls")]
-        let _tls_reload_tasks = self.install_tls_reload_triggers();
+        let mut _tls_reload_tasks = self.install_tls_reload_triggers();
+
+        // The expiry alerts ride on the same guard, for the same reason.
+        #[cfg(feature = "tls")]
+        if !self.cert_expiry.is_empty() {
+            _tls_reload_tasks.push(
+                self.cert_expiry
+                    .spawn_alerts(self.config.service.name.clone()),
+            );
+        }
+
+        // As do the SPIFFE trust bundles, which rotate under the same SIGHUP
+        // switch as the listener credentials.
+        #[cfg(feature = "tls")]
+        if let Some(bundles) = self.spiffe_bundles.clone() {
+            if let Some(task) =
+                crate::spiffe::spawn_bundle_reload(bundles, self.tls_reload_on_sighup)
+            {
+                _tls_reload_tasks.push(task);
+            }
+        }
 
         // Graceful shutdown signal
         async fn shutdown_signal() {
@@ -2833,6 +3296,8 @@ where
                         let grpc_tls_config = self.grpc_tls_config.clone();
                         #[cfg(feature = "tls")]
                         let grpc_tls_handshake_timeout = self.grpc_tls_handshake_timeout;
+                        #[cfg(all(feature = "tls", feature = "audit"))]
+                        let grpc_tls_audit_logger = self.state.audit_logger().cloned();
 
                         let grpc_handle = tokio::spawn(async move {
                             #[cfg(feature = "tls")]
@@ -2842,6 +3307,9 @@ where
                                     tls_source.clone(),
                                 )
                                 .with_handshake_timeout(grpc_tls_handshake_timeout);
+                                #[cfg(feature = "audit")]
+                                let tls_listener =
+                                    tls_listener.with_audit_logger(grpc_tls_audit_logger);
                                 return axum::serve(
                                     tls_listener,
                                     grpc_app.into_make_service_with_connect_info::<
@@ -2867,6 +3335,9 @@ where
                                 tls_source.clone(),
                             )
                             .with_handshake_timeout(self.tls_handshake_timeout);
+                            #[cfg(feature = "audit")]
+                            let tls_listener =
+                                tls_listener.with_audit_logger(self.state.audit_logger().cloned());
                             tracing::info!("TLS enabled (HTTPS) for both HTTP and gRPC");
                             let http_result = axum::serve(
                                 tls_listener,
@@ -2926,6 +3397,9 @@ where
                                 tls_source.clone(),
                             )
                             .with_handshake_timeout(self.tls_handshake_timeout);
+                            #[cfg(feature = "audit")]
+                            let tls_listener =
+                                tls_listener.with_audit_logger(self.state.audit_logger().cloned());
                             tracing::info!("TLS enabled (HTTPS) for hybrid HTTP+gRPC");
                             axum::serve(
                                 tls_listener,
@@ -2984,6 +3458,8 @@ where
             let tls_listener =
                 crate::tls::TlsListener::with_config_source(listener, tls_source.clone())
                     .with_handshake_timeout(self.tls_handshake_timeout);
+            #[cfg(feature = "audit")]
+            let tls_listener = tls_listener.with_audit_logger(self.state.audit_logger().cloned());
             tracing::info!("TLS enabled (HTTPS)");
             axum::serve(
                 tls_listener,
@@ -3198,9 +3674,13 @@ mod tests {
             key_path,
             client_ca_path: None,
             client_auth_optional: false,
+            client_crl_paths: Vec::new(),
             reload_interval_secs: None,
             reload_on_sighup: false,
             handshake_timeout_secs: None,
+            expiry_warning_days: None,
+            certificates: Vec::new(),
+            acme: None,
         }
     }
 
@@ -3224,6 +3704,7 @@ mod tests {
                     mode: crate::caller_auth::CallerAuthMode::Mtls,
                     allowlist: vec!["reporter.internal".to_string()],
                     public_paths: Vec::new(),
+                    spiffe: None,
                 }),
                 ..Default::default()
             })
@@ -3256,6 +3737,7 @@ mod tests {
                     mode: crate::caller_auth::CallerAuthMode::MtlsOrBearer,
                     allowlist: vec!["reporter.internal".to_string()],
                     public_paths: Vec::new(),
+                    spiffe: None,
                 }),
                 ..Default::default()
             })
@@ -3283,6 +3765,7 @@ mod tests {
                     mode: crate::caller_auth::CallerAuthMode::Bearer,
                     allowlist: vec!["reporter.internal".to_string()],
                     public_paths: Vec::new(),
+                    spiffe: None,
                 }),
                 ..Default::default()
             })
@@ -3318,6 +3801,7 @@ mod tests {
                     mode: crate::caller_auth::CallerAuthMode::Mtls,
                     allowlist: vec!["reporter.internal".to_string()],
                     public_paths: Vec::new(),
+                    spiffe: None,
                 }),
                 ..Default::default()
             })
@@ -3624,9 +4108,13 @@ mod tests {
                 key_path: "/nonexistent/key.pem".into(),
                 client_ca_path: None,
                 client_auth_optional: false,
+                client_crl_paths: Vec::new(),
                 reload_interval_secs: None,
                 reload_on_sighup: false,
                 handshake_timeout_secs: None,
+                expiry_warning_days: None,
+                certificates: Vec::new(),
+                acme: None,
             }),
             ..Default::default()
         };
@@ -3670,9 +4158,13 @@ mod tests {
                 key_path: "/nonexistent/key.pem".into(),
                 client_ca_path: None,
                 client_auth_optional: false,
+                client_crl_paths: Vec::new(),
                 reload_interval_secs: None,
                 reload_on_sighup: false,
                 handshake_timeout_secs: None,
+                expiry_warning_days: None,
+                certificates: Vec::new(),
+                acme: None,
             }),
             port: 50051,
             reflection_enabled: false,
@@ -3710,9 +4202,13 @@ mod tests {
                 key_path: "/nonexistent/key.pem".into(),
                 client_ca_path: None,
                 client_auth_optional: false,
+                client_crl_paths: Vec::new(),
                 reload_interval_secs: None,
                 reload_on_sighup: false,
                 handshake_timeout_secs: None,
+                expiry_warning_days: None,
+                certificates: Vec::new(),
+                acme: None,
             }),
             ..Default::default()
         };
@@ -3743,9 +4239,13 @@ mod tests {
                 key_path: "/nonexistent/key.pem".into(),
                 client_ca_path: None,
                 client_auth_optional: false,
+                client_crl_paths: Vec::new(),
                 reload_interval_secs: None,
                 reload_on_sighup: false,
                 handshake_timeout_secs: None,
+                expiry_warning_days: None,
+                certificates: Vec::new(),
+                acme: None,
             }),
             ..Default::default()
         };
@@ -3977,9 +4477,13 @@ mod tests {
                 key_path: "/nonexistent/key.pem".into(),
                 client_ca_path: None,
                 client_auth_optional: false,
+                client_crl_paths: Vec::new(),
                 reload_interval_secs: None,
                 reload_on_sighup: false,
                 handshake_timeout_secs: None,
+                expiry_warning_days: None,
+                certificates: Vec::new(),
+                acme: None,
             }),
             ..base
         };
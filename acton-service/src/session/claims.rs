@@ -0,0 +1,136 @@
+//! Session-to-`Claims` bridge.
+//!
+//! Cedar, the governor rate limiter, audit logging, and the `Claims`
+//! extractor all read [`Claims`] from the request extensions, which only
+//! token middleware fills in. [`session_claims_middleware`] synthesizes them
+//! from a signed-in [`AuthSession`], so cookie-authenticated callers are
+//! treated the same as token callers:
+//!
+//! - `sub` is `user:{user_id}`, so [`Claims::user_id`] returns the user
+//! - `roles` are the session's roles
+//! - `email` and `username` come from the session's `extra` data
+//! - `exp` is the session's expiry and `iat` its authentication time
+//! - the `auth_method` custom claim is `"session"`
+//!
+//! A request carrying an `Authorization` header is left to the token
+//! middleware, so an explicit bearer token always wins over the cookie.
+//!
+//! `ServiceBuilder` applies the middleware when `[session.claims]` is
+//! enabled:
+//!
+//! ```toml
+//! [session.claims]
+//! enabled = true
+//! ```
+
+use std::collections::HashMap;
+
+use axum::{body::Body, http::Request, middleware::Next, response::Response};
+use tower_sessions::Session;
+
+use super::extractors::{AuthSession, TypedSession};
+use crate::middleware::Claims;
+
+/// Marker placed in the request extensions once a session has supplied the
+/// request's `Claims`, so token middleware does not demand a token as well.
+///
+/// Crate-private on purpose: handlers and user middleware cannot forge it.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct SessionAuthenticated;
+
+/// Whether the request was authenticated by its session, so token middleware
+/// should not demand a bearer token.
+pub(crate) fn bearer_satisfied(extensions: &http::Extensions) -> bool {
+    extensions.get::<SessionAuthenticated>().is_some()
+}
+
+/// Middleware that synthesizes [`Claims`] from a signed-in [`AuthSession`].
+///
+/// Must run inside the `SessionManagerLayer` and before token
+/// authentication. Requests without a signed-in session pass through
+/// unchanged and are authenticated (or rejected) by the token middleware.
+pub async fn session_claims_middleware(mut request: Request<Body>, next: Next) -> Response {
+    if request.headers().contains_key(http::header::AUTHORIZATION) {
+        return next.run(request).await;
+    }
+
+    let Some(session) = request.extensions().get::<Session>().cloned() else {
+        return next.run(request).await;
+    };
+
+    match session
+        .get::<AuthSession>(TypedSession::<AuthSession>::DATA_KEY)
+        .await
+    {
+        Ok(Some(auth)) => {
+            let exp = session.expiry_date().unix_timestamp();
+            if let Some(claims) = claims_for_session(&auth, exp) {
+                let extensions = request.extensions_mut();
+                extensions.insert(claims);
+                extensions.insert(SessionAuthenticated);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => tracing::warn!("Failed to read session for claims: {}", e),
+    }
+
+    next.run(request).await
+}
+
+/// Build the claims a signed-in session stands for
+fn claims_for_session(auth: &AuthSession, exp: i64) -> Option<Claims> {
+    let user_id = auth.user_id()?;
+
+    let mut custom = HashMap::new();
+    custom.insert(
+        "auth_method".to_string(),
+        serde_json::Value::String("session".to_string()),
+    );
+
+    Some(Claims {
+        sub: format!("user:{}", user_id),
+        email: auth.get_extra("email").map(str::to_string),
+        username: auth.get_extra("username").map(str::to_string),
+        roles: auth.roles.clone(),
+        perms: Vec::new(),
+        exp,
+        iat: auth.authenticated_at,
+        jti: None,
+        iss: None,
+        aud: None,
+        custom,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claims_for_signed_in_session() {
+        let mut auth = AuthSession::default();
+        auth.login_with_extra(
+            "user-123".to_string(),
+            vec!["admin".to_string()],
+            HashMap::from([("email".to_string(), "a@example.com".to_string())]),
+        );
+
+        let claims = claims_for_session(&auth, 1_900_000_000).unwrap();
+        assert_eq!(claims.sub, "user:user-123");
+        assert_eq!(claims.user_id(), Some("user-123"));
+        assert!(claims.has_role("admin"));
+        assert_eq!(claims.email.as_deref(), Some("a@example.com"));
+        assert_eq!(claims.username, None);
+        assert_eq!(claims.exp, 1_900_000_000);
+        assert_eq!(claims.iat, auth.authenticated_at);
+        assert_eq!(
+            claims.custom_claim("auth_method"),
+            Some(&serde_json::json!("session"))
+        );
+    }
+
+    #[test]
+    fn test_no_claims_for_anonymous_session() {
+        assert!(claims_for_session(&AuthSession::default(), 1_900_000_000).is_none());
+    }
+}
@@ -11,6 +11,13 @@ pub enum SessionStorage {
     Memory,
     /// Redis-backed storage (production, distributed).
     Redis,
+    /// PostgreSQL-backed storage using the service's `[database]` pool.
+    Postgres,
+    /// Turso/libsql-backed storage using the service's `[turso]` database.
+    Turso,
+    /// SurrealDB-backed storage using the service's `[surrealdb]` client.
+    #[serde(rename = "surrealdb")]
+    SurrealDb,
 }
 
 /// Session configuration.
@@ -97,6 +104,8 @@ pub struct SessionConfig {
     ///
     /// - `"memory"`: In-memory storage (development)
     /// - `"redis"`: Redis storage (production)
+    /// - `"postgres"`, `"turso"`, `"surrealdb"`: Database storage using the
+    ///   service's connection pool (production, no Redis required)
     ///
     /// Default: `"memory"`
     #[serde(default)]
@@ -110,9 +119,23 @@ pub struct SessionConfig {
     #[serde(default)]
     pub redis_url: Option<String>,
 
+    /// How often expired sessions are deleted, in seconds.
+    ///
+    /// Only used by the database stores; cleanup runs on the background
+    /// worker. Expired sessions are never loaded, so this only bounds how
+    /// long their rows are kept.
+    ///
+    /// Default: `300` (5 minutes)
+    #[serde(default = "default_cleanup_interval_secs")]
+    pub cleanup_interval_secs: u64,
+
     /// CSRF protection configuration.
     #[serde(default)]
     pub csrf: CsrfConfig,
+
+    /// Session-to-`Claims` bridge configuration.
+    #[serde(default)]
+    pub claims: SessionClaimsConfig,
 }
 
 impl Default for SessionConfig {
@@ -128,7 +151,9 @@ impl Default for SessionConfig {
             same_site: default_same_site(),
             storage: SessionStorage::default(),
             redis_url: None,
+            cleanup_interval_secs: default_cleanup_interval_secs(),
             csrf: CsrfConfig::default(),
+            claims: SessionClaimsConfig::default(),
         }
     }
 }
@@ -194,6 +219,31 @@ impl Default for CsrfConfig {
     }
 }
 
+/// Session-to-`Claims` bridge configuration.
+///
+/// When enabled, requests from a signed-in [`AuthSession`](super::AuthSession)
+/// without an `Authorization` header get [`Claims`](crate::middleware::Claims)
+/// synthesized from the session, so Cedar, `Claims` extractors, per-user rate
+/// limits, and audit subjects treat them like token callers.
+///
+/// # Example
+///
+/// ```toml
+/// [session.claims]
+/// enabled = true
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SessionClaimsConfig {
+    /// Synthesize `Claims` for signed-in sessions.
+    ///
+    /// Cookie-authenticated callers then pass token authentication, so
+    /// state-changing routes should also be covered by CSRF protection.
+    ///
+    /// Default: `false`
+    #[serde(default)]
+    pub enabled: bool,
+}
+
 // Default value functions
 fn default_cookie_name() -> String {
     "session_id".to_string()
@@ -219,6 +269,10 @@ fn default_same_site() -> String {
     "lax".to_string()
 }
 
+fn default_cleanup_interval_secs() -> u64 {
+    300 // 5 minutes
+}
+
 fn default_csrf_enabled() -> bool {
     true
 }
@@ -257,6 +311,7 @@ mod tests {
         assert!(config.http_only);
         assert_eq!(config.same_site, "lax");
         assert_eq!(config.storage, SessionStorage::Memory);
+        assert!(!config.claims.enabled);
     }
 
     #[test]
@@ -276,5 +331,13 @@ mod tests {
 
         assert_eq!(serde_json::to_string(&memory).unwrap(), "\"memory\"");
         assert_eq!(serde_json::to_string(&redis).unwrap(), "\"redis\"");
+        assert_eq!(
+            serde_json::to_string(&SessionStorage::Postgres).unwrap(),
+            "\"postgres\""
+        );
+        assert_eq!(
+            serde_json::to_string(&SessionStorage::SurrealDb).unwrap(),
+            "\"surrealdb\""
+        );
     }
 }
@@ -66,13 +66,20 @@ pub struct TypedSession<T> {
     /// without access to the request (issue #16).
     #[cfg(feature = "audit")]
     audit: Option<(crate::audit::AuditLogger, crate::audit::event::AuditSource)>,
+    /// Captured at extraction for listing and revoking a user's sessions
+    #[cfg(any(
+        feature = "session-postgres",
+        feature = "session-turso",
+        feature = "session-surrealdb"
+    ))]
+    sessions: Option<std::sync::Arc<dyn super::store::PersistentSessionStore>>,
 }
 
 impl<T> TypedSession<T>
 where
     T: Default + DeserializeOwned + Serialize + Send + Sync,
 {
-    const DATA_KEY: &'static str = "_typed_session_data";
+    pub(crate) const DATA_KEY: &'static str = "_typed_session_data";
 
     /// Get a reference to the session data.
     #[must_use]
@@ -208,6 +215,84 @@ impl TypedSession<AuthSession> {
         }
         Ok(())
     }
+
+    /// List the signed-in user's sessions, most recently active first.
+    ///
+    /// Requires a database session store (`storage = "postgres"`, `"turso"`,
+    /// or `"surrealdb"`). The session making the request is marked
+    /// [`current`](super::ActiveSession::current).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Unauthorized`] if not signed in, or an error if the
+    /// session store does not support listing or cannot be read.
+    #[cfg(any(
+        feature = "session-postgres",
+        feature = "session-turso",
+        feature = "session-surrealdb"
+    ))]
+    pub async fn active_sessions(&self) -> Result<Vec<super::ActiveSession>, Error> {
+        let (store, user_id) = self.session_index()?;
+        let current = self
+            .session
+            .id()
+            .map(|id| super::store::session_handle(&id));
+
+        let mut sessions = store.list_user_sessions(user_id).await?;
+        for session in &mut sessions {
+            session.current = current.as_deref() == Some(session.handle.as_str());
+        }
+        Ok(sessions)
+    }
+
+    /// Revoke one of the signed-in user's sessions by its handle.
+    ///
+    /// Revoking the current session flushes it, as [`destroy`](Self::destroy)
+    /// does. Returns `false` if the user has no session with that handle.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Unauthorized`] if not signed in, or an error if the
+    /// session store does not support revocation or cannot be written.
+    #[cfg(any(
+        feature = "session-postgres",
+        feature = "session-turso",
+        feature = "session-surrealdb"
+    ))]
+    pub async fn revoke_session(&self, handle: &str) -> Result<bool, Error> {
+        let (store, user_id) = self.session_index()?;
+
+        // Deleting the current row would be undone by the end-of-request save
+        let is_current = self
+            .session
+            .id()
+            .is_some_and(|id| super::store::session_handle(&id) == handle);
+        if is_current {
+            self.destroy().await?;
+            return Ok(true);
+        }
+
+        store.revoke_user_session(user_id, handle).await
+    }
+
+    #[cfg(any(
+        feature = "session-postgres",
+        feature = "session-turso",
+        feature = "session-surrealdb"
+    ))]
+    fn session_index(&self) -> Result<(&dyn super::store::PersistentSessionStore, &str), Error> {
+        let user_id = self
+            .data
+            .user_id()
+            .ok_or_else(|| Error::Unauthorized("Not signed in".to_string()))?;
+        let store = self.sessions.as_deref().ok_or_else(|| {
+            Error::Session(
+                "Session listing requires postgres, turso, or surrealdb session storage"
+                    .to_string(),
+            )
+        })?;
+        Ok((store, user_id))
+    }
 }
 
 impl<S, T> FromRequestParts<S> for TypedSession<T>
@@ -250,11 +335,27 @@ where
                 (logger, source)
             });
 
+        #[cfg(any(
+            feature = "session-postgres",
+            feature = "session-turso",
+            feature = "session-surrealdb"
+        ))]
+        let sessions = parts
+            .extensions
+            .get::<std::sync::Arc<dyn super::store::PersistentSessionStore>>()
+            .cloned();
+
         Ok(Self {
             session,
             data,
             #[cfg(feature = "audit")]
             audit,
+            #[cfg(any(
+                feature = "session-postgres",
+                feature = "session-turso",
+                feature = "session-surrealdb"
+            ))]
+            sessions,
         })
     }
 }
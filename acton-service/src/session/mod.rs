@@ -6,16 +6,23 @@
 //! # Features
 //!
 //! - **Cookie-based sessions**: Automatic session ID management via secure cookies
-//! - **Pluggable storage**: In-memory (development) or Redis (production)
+//! - **Pluggable storage**: In-memory (development), Redis, or the service's
+//!   PostgreSQL, Turso, or SurrealDB database (production)
+//! - **Active sessions**: List and revoke a user's sessions with database storage
 //! - **Type-safe session data**: `TypedSession<T>` for automatic serialization
 //! - **Flash messages**: One-time messages for post-redirect-get patterns
 //! - **CSRF protection**: Token-based protection for form submissions
+//! - **Claims bridge**: `Claims` for signed-in sessions, so Cedar and other
+//!   token-based middleware work for cookie-authenticated users
 //!
 //! # Feature Flags
 //!
 //! - `session`: Base session support (required)
 //! - `session-memory`: In-memory session store (for development)
 //! - `session-redis`: Redis session store (for production)
+//! - `session-postgres`: PostgreSQL session store, on the `database` pool
+//! - `session-turso`: Turso session store, on the `turso` database
+//! - `session-surrealdb`: SurrealDB session store, on the `surrealdb` client
 //!
 //! # Quick Start
 //!
@@ -52,16 +59,41 @@
 //! }
 //! ```
 
+mod claims;
 mod config;
 mod csrf;
 mod extractors;
 mod flash;
-
-pub use config::{CsrfConfig, SessionConfig, SessionStorage};
+#[cfg(any(
+    feature = "session-postgres",
+    feature = "session-turso",
+    feature = "session-surrealdb"
+))]
+pub(crate) mod store;
+
+pub(crate) use claims::bearer_satisfied;
+pub use claims::session_claims_middleware;
+pub use config::{CsrfConfig, SessionClaimsConfig, SessionConfig, SessionStorage};
 pub use csrf::{csrf_middleware, CsrfLayer, CsrfMiddleware, CsrfToken};
 pub use extractors::{AuthSession, SessionAuth, SessionData, TypedSession};
 pub use flash::{FlashKind, FlashMessage, FlashMessages};
 
+#[cfg(any(
+    feature = "session-postgres",
+    feature = "session-turso",
+    feature = "session-surrealdb"
+))]
+pub use store::{ActiveSession, PersistentSessionStore};
+
+#[cfg(feature = "session-postgres")]
+pub use store::PgSessionStore;
+
+#[cfg(feature = "session-turso")]
+pub use store::TursoSessionStore;
+
+#[cfg(feature = "session-surrealdb")]
+pub use store::SurrealSessionStore;
+
 // Re-export tower-sessions types for convenience
 pub use tower_sessions::{Expiry, Session, SessionManagerLayer};
 
@@ -74,31 +106,30 @@ pub use tower_sessions_redis_store::RedisStore;
 #[cfg(feature = "session-redis")]
 pub use tower_sessions_redis_store::fred;
 
-#[cfg(feature = "session-memory")]
-use time::Duration;
-
 #[cfg(feature = "session-redis")]
 use crate::error::Result;
 
-/// Create a `SessionManagerLayer` from configuration.
+/// Create a `SessionManagerLayer` for a store from configuration.
 ///
-/// This is the primary way to create session middleware from your configuration.
-/// The layer should be applied to your router in `ServiceBuilder`.
+/// Applies the configured expiry, cookie name, path, domain, and security
+/// attributes. Use this to put a custom `SessionStore` behind the same cookie
+/// settings as the built-in stores.
 ///
 /// # Example
 ///
 /// ```rust,ignore
-/// use acton_service::session::{create_session_layer, SessionConfig};
+/// use acton_service::session::{create_session_layer, MemoryStore, SessionConfig};
 ///
 /// let config = SessionConfig::default();
-/// let layer = create_session_layer(&config)?;
+/// let layer = create_session_layer(&config, MemoryStore::default());
 /// ```
-#[cfg(feature = "session-memory")]
-pub fn create_memory_session_layer(config: &SessionConfig) -> SessionManagerLayer<MemoryStore> {
+pub fn create_session_layer<S>(config: &SessionConfig, store: S) -> SessionManagerLayer<S>
+where
+    S: tower_sessions::SessionStore + Clone,
+{
+    use time::Duration;
     use tower_sessions::cookie::SameSite;
 
-    let store = MemoryStore::default();
-
     let expiry = if config.expiry_secs == 0 {
         Expiry::OnSessionEnd
     } else if let Some(inactivity) = config.inactivity_timeout_secs {
@@ -133,6 +164,14 @@ pub fn create_memory_session_layer(config: &SessionConfig) -> SessionManagerLaye
     layer
 }
 
+/// Create an in-memory session layer.
+///
+/// Sessions are lost on restart; use for development.
+#[cfg(feature = "session-memory")]
+pub fn create_memory_session_layer(config: &SessionConfig) -> SessionManagerLayer<MemoryStore> {
+    create_session_layer(config, MemoryStore::default())
+}
+
 /// Create a Redis-backed session layer.
 ///
 /// This function creates a new Redis connection pool using the `fred` client
@@ -147,7 +186,6 @@ pub async fn create_redis_session_layer(
     redis_url: &str,
 ) -> Result<SessionManagerLayer<RedisStore<tower_sessions_redis_store::fred::clients::Pool>>> {
     use crate::error::Error;
-    use tower_sessions::cookie::SameSite;
     use tower_sessions_redis_store::fred::prelude::*;
 
     let redis_config = Config::from_url(redis_url)
@@ -161,38 +199,5 @@ pub async fn create_redis_session_layer(
         .await
         .map_err(|e| Error::Internal(format!("Failed to connect to Redis for sessions: {e}")))?;
 
-    let store = RedisStore::new(pool);
-
-    let expiry = if config.expiry_secs == 0 {
-        Expiry::OnSessionEnd
-    } else if let Some(inactivity) = config.inactivity_timeout_secs {
-        Expiry::OnInactivity(Duration::seconds(inactivity as i64))
-    } else {
-        Expiry::OnInactivity(Duration::seconds(config.expiry_secs as i64))
-    };
-
-    let same_site = match config.same_site.to_lowercase().as_str() {
-        "strict" => SameSite::Strict,
-        "none" => SameSite::None,
-        _ => SameSite::Lax,
-    };
-
-    // Clone strings to satisfy 'static lifetime requirements
-    let cookie_name = config.cookie_name.clone();
-    let cookie_path = config.cookie_path.clone();
-    let cookie_domain = config.cookie_domain.clone();
-
-    let mut layer = SessionManagerLayer::new(store)
-        .with_name(cookie_name)
-        .with_expiry(expiry)
-        .with_secure(config.secure)
-        .with_http_only(config.http_only)
-        .with_same_site(same_site)
-        .with_path(cookie_path);
-
-    if let Some(domain) = cookie_domain {
-        layer = layer.with_domain(domain);
-    }
-
-    Ok(layer)
+    Ok(create_session_layer(config, RedisStore::new(pool)))
 }
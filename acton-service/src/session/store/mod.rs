@@ -0,0 +1,345 @@
+//! Database-backed session stores.
+//!
+//! These stores keep sessions in the service's own database, so durable
+//! sessions don't require Redis. Each store resolves its connection from the
+//! pool the service already has and creates an `http_sessions` table the
+//! first time it is used.
+//!
+//! # Available Backends
+//!
+//! - **PostgreSQL** (`session-postgres` feature): [`PgSessionStore`]
+//! - **Turso** (`session-turso` feature): [`TursoSessionStore`]
+//! - **SurrealDB** (`session-surrealdb` feature): [`SurrealSessionStore`]
+//!
+//! # Active Sessions
+//!
+//! Each row records the `user_id` of the [`AuthSession`] it holds, so the
+//! stores also implement [`PersistentSessionStore`] for listing a user's
+//! sessions ("active devices") and revoking one. Sessions are listed by an
+//! opaque handle, never by session ID, since the ID is the cookie value.
+//!
+//! # Cleanup
+//!
+//! Expired sessions are never loaded. `ServiceBuilder` deletes their rows
+//! every `cleanup_interval_secs` on the
+//! [`BackgroundWorker`](crate::agents::BackgroundWorker).
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use time::OffsetDateTime;
+use tokio::sync::{OnceCell, RwLock};
+use tower_sessions::session::{Id, Record};
+use tower_sessions::session_store;
+
+use super::config::SessionConfig;
+use super::extractors::{AuthSession, TypedSession};
+use crate::error::Error;
+
+#[cfg(feature = "session-postgres")]
+mod pg;
+#[cfg(feature = "session-surrealdb")]
+mod surrealdb_impl;
+#[cfg(feature = "session-turso")]
+mod turso;
+
+#[cfg(feature = "session-postgres")]
+pub use pg::PgSessionStore;
+#[cfg(feature = "session-surrealdb")]
+pub use surrealdb_impl::SurrealSessionStore;
+#[cfg(feature = "session-turso")]
+pub use turso::TursoSessionStore;
+
+/// A signed-in session, as listed for its user
+#[derive(Debug, Clone, Serialize)]
+pub struct ActiveSession {
+    /// Opaque identifier for revoking this session (not the session ID)
+    pub handle: String,
+    /// Authentication data held by the session
+    pub auth: AuthSession,
+    /// When the session expires
+    pub expires_at: DateTime<Utc>,
+    /// When the session was last saved, which is every request for
+    /// inactivity-based expiry
+    pub last_active_at: DateTime<Utc>,
+    /// Whether this is the session making the request
+    pub current: bool,
+}
+
+/// Per-user session listing and cleanup for database-backed stores
+///
+/// `ServiceBuilder` installs the configured store as
+/// `Extension<Arc<dyn PersistentSessionStore>>`; handlers usually go through
+/// [`TypedSession::active_sessions`] and [`TypedSession::revoke_session`]
+/// instead.
+#[async_trait]
+pub trait PersistentSessionStore: Send + Sync {
+    /// List a user's unexpired sessions, most recently active first
+    async fn list_user_sessions(&self, user_id: &str) -> Result<Vec<ActiveSession>, Error>;
+
+    /// Delete one of a user's sessions by handle
+    ///
+    /// Returns `false` if the user has no session with that handle.
+    async fn revoke_user_session(&self, user_id: &str, handle: &str) -> Result<bool, Error>;
+
+    /// Delete expired sessions, returning how many were removed
+    async fn delete_expired_sessions(&self) -> Result<u64, Error>;
+}
+
+/// A pool agent's connection, resolved when first used
+///
+/// Pool agents connect after `ServiceBuilder::build()` returns, so stores
+/// hold the shared handle and create their table once the pool is up.
+pub(crate) struct LazyConn<C> {
+    shared: Arc<RwLock<Option<C>>>,
+    schema: Arc<OnceCell<()>>,
+}
+
+impl<C> Clone for LazyConn<C> {
+    fn clone(&self) -> Self {
+        Self {
+            shared: self.shared.clone(),
+            schema: self.schema.clone(),
+        }
+    }
+}
+
+impl<C: Clone> LazyConn<C> {
+    /// Wrap an already connected pool
+    pub(crate) fn ready(conn: C) -> Self {
+        Self::shared(Arc::new(RwLock::new(Some(conn))))
+    }
+
+    /// Wrap a pool agent's shared handle
+    pub(crate) fn shared(shared: Arc<RwLock<Option<C>>>) -> Self {
+        Self {
+            shared,
+            schema: Arc::new(OnceCell::new()),
+        }
+    }
+
+    /// The connection, running `initialize` the first time one is available
+    pub(crate) async fn get<F, Fut>(&self, initialize: F) -> Result<C, Error>
+    where
+        F: FnOnce(C) -> Fut,
+        Fut: Future<Output = Result<(), Error>>,
+    {
+        let conn =
+            self.shared.read().await.clone().ok_or_else(|| {
+                Error::Internal("Session database is not connected yet".to_string())
+            })?;
+        self.schema
+            .get_or_try_init(|| initialize(conn.clone()))
+            .await?;
+        Ok(conn)
+    }
+}
+
+/// The columns a store writes for a record
+pub(crate) struct SessionRow {
+    pub id: String,
+    pub handle: String,
+    pub user_id: Option<String>,
+    pub data: String,
+    pub expires_at: DateTime<Utc>,
+}
+
+impl SessionRow {
+    pub(crate) fn from_record(record: &Record) -> session_store::Result<Self> {
+        let data = serde_json::to_string(&record.data)
+            .map_err(|e| session_store::Error::Encode(e.to_string()))?;
+
+        Ok(Self {
+            id: record.id.to_string(),
+            handle: session_handle(&record.id),
+            user_id: auth_session(&record.data).and_then(|auth| auth.user_id),
+            data,
+            expires_at: to_chrono(record.expiry_date),
+        })
+    }
+}
+
+/// Rebuild a record from its stored columns
+pub(crate) fn to_record(
+    id: &Id,
+    data: &str,
+    expires_at: DateTime<Utc>,
+) -> session_store::Result<Record> {
+    Ok(Record {
+        id: *id,
+        data: serde_json::from_str(data)
+            .map_err(|e| session_store::Error::Decode(e.to_string()))?,
+        expiry_date: to_time(expires_at),
+    })
+}
+
+/// Build a listing entry from stored columns
+pub(crate) fn to_active_session(
+    handle: String,
+    data: &str,
+    expires_at: DateTime<Utc>,
+    last_active_at: DateTime<Utc>,
+) -> Result<ActiveSession, Error> {
+    let data: HashMap<String, serde_json::Value> = serde_json::from_str(data)
+        .map_err(|e| Error::Session(format!("Failed to decode session data: {e}")))?;
+
+    Ok(ActiveSession {
+        handle,
+        auth: auth_session(&data).unwrap_or_default(),
+        expires_at,
+        last_active_at,
+        current: false,
+    })
+}
+
+/// The revocation handle for a session ID
+///
+/// A hash, so listing sessions never discloses the cookie values.
+pub(crate) fn session_handle(id: &Id) -> String {
+    blake3::hash(id.to_string().as_bytes()).to_hex()[..32].to_string()
+}
+
+pub(crate) fn backend_error(err: Error) -> session_store::Error {
+    session_store::Error::Backend(err.to_string())
+}
+
+fn auth_session(data: &HashMap<String, serde_json::Value>) -> Option<AuthSession> {
+    data.get(TypedSession::<AuthSession>::DATA_KEY)
+        .and_then(|value| serde_json::from_value(value.clone()).ok())
+}
+
+fn to_chrono(time: OffsetDateTime) -> DateTime<Utc> {
+    DateTime::from_timestamp(time.unix_timestamp(), time.nanosecond()).unwrap_or_default()
+}
+
+fn to_time(time: DateTime<Utc>) -> OffsetDateTime {
+    OffsetDateTime::from_unix_timestamp(time.timestamp()).unwrap_or(OffsetDateTime::UNIX_EPOCH)
+}
+
+/// Add a database store's session layer and listing extension to a router
+///
+/// Expired-session cleanup is submitted to `worker`; without one, expired
+/// rows stay until their session ID is presented again.
+pub(crate) fn install<S>(
+    app: axum::Router,
+    config: &SessionConfig,
+    store: S,
+    worker: Option<crate::agents::BackgroundWorker>,
+) -> axum::Router
+where
+    S: tower_sessions::SessionStore + PersistentSessionStore + Clone,
+{
+    let index: Arc<dyn PersistentSessionStore> = Arc::new(store.clone());
+
+    match (worker, tokio::runtime::Handle::try_current()) {
+        (Some(worker), Ok(handle)) => {
+            let interval = Duration::from_secs(config.cleanup_interval_secs.max(1));
+            let cleanup = index.clone();
+            handle.spawn(async move {
+                worker
+                    .submit("session-cleanup", move || run_cleanup(cleanup, interval))
+                    .await;
+            });
+        }
+        (None, _) => {
+            tracing::warn!(
+                "Database session store has no background worker; expired sessions will not \
+                 be cleaned up. Set [background_worker] enabled = true"
+            );
+        }
+        (Some(_), Err(_)) => {
+            tracing::warn!("No tokio runtime available for session cleanup");
+        }
+    }
+
+    app.layer(super::create_session_layer(config, store))
+        .layer(axum::Extension(index))
+}
+
+/// Cleanup loop submitted to the background worker
+///
+/// Runs until the worker cancels it on shutdown.
+async fn run_cleanup(
+    store: Arc<dyn PersistentSessionStore>,
+    interval: Duration,
+) -> anyhow::Result<()> {
+    let mut ticker = tokio::time::interval_at(tokio::time::Instant::now() + interval, interval);
+    ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+
+    loop {
+        ticker.tick().await;
+        match store.delete_expired_sessions().await {
+            Ok(0) => {}
+            Ok(deleted) => tracing::debug!(deleted, "Deleted expired sessions"),
+            Err(e) => tracing::warn!("Failed to delete expired sessions: {}", e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn record(auth: Option<AuthSession>) -> Record {
+        let mut data = HashMap::new();
+        if let Some(auth) = auth {
+            data.insert(
+                TypedSession::<AuthSession>::DATA_KEY.to_string(),
+                serde_json::to_value(auth).unwrap(),
+            );
+        }
+        Record {
+            id: Id::default(),
+            data,
+            expiry_date: OffsetDateTime::now_utc() + time::Duration::hours(1),
+        }
+    }
+
+    #[test]
+    fn test_row_records_user_id() {
+        let mut auth = AuthSession::default();
+        auth.login("user-123".to_string(), vec!["admin".to_string()]);
+
+        let row = SessionRow::from_record(&record(Some(auth))).unwrap();
+        assert_eq!(row.user_id.as_deref(), Some("user-123"));
+
+        let anonymous = SessionRow::from_record(&record(None)).unwrap();
+        assert_eq!(anonymous.user_id, None);
+    }
+
+    #[test]
+    fn test_row_roundtrip() {
+        let mut auth = AuthSession::default();
+        auth.login("user-123".to_string(), vec![]);
+        let original = record(Some(auth));
+
+        let row = SessionRow::from_record(&original).unwrap();
+        let restored = to_record(&original.id, &row.data, row.expires_at).unwrap();
+        assert_eq!(restored.id, original.id);
+        assert_eq!(restored.data, original.data);
+        assert_eq!(
+            restored.expiry_date.unix_timestamp(),
+            original.expiry_date.unix_timestamp()
+        );
+
+        let listed =
+            to_active_session(row.handle.clone(), &row.data, row.expires_at, Utc::now()).unwrap();
+        assert_eq!(listed.auth.user_id(), Some("user-123"));
+        assert!(!listed.current);
+    }
+
+    #[test]
+    fn test_handle_hides_session_id() {
+        let id = Id::default();
+        let handle = session_handle(&id);
+        assert_eq!(handle.len(), 32);
+        assert_eq!(handle, session_handle(&id));
+        assert!(!handle.contains(&id.to_string()));
+        assert_ne!(handle, session_handle(&Id::default()));
+    }
+}
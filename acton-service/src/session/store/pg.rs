@@ -0,0 +1,224 @@
+//! PostgreSQL session store.
+//!
+//! Stores sessions in an `http_sessions` table, indexed by user for
+//! listing and by expiry for cleanup.
+
+use std::fmt;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::PgPool;
+use tower_sessions::session::{Id, Record};
+use tower_sessions::{session_store, SessionStore};
+
+use super::{
+    backend_error, to_active_session, to_record, ActiveSession, LazyConn, PersistentSessionStore,
+    SessionRow,
+};
+use crate::error::Error;
+
+/// PostgreSQL-backed session store
+#[derive(Clone)]
+pub struct PgSessionStore {
+    conn: LazyConn<PgPool>,
+}
+
+impl PgSessionStore {
+    /// Create a session store on a connected pool
+    ///
+    /// The `http_sessions` table is created on first use.
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            conn: LazyConn::ready(pool),
+        }
+    }
+
+    /// Create a session store on a pool agent's shared handle
+    pub(crate) fn from_shared(shared: crate::agents::SharedDbPool) -> Self {
+        Self {
+            conn: LazyConn::shared(shared),
+        }
+    }
+
+    async fn pool(&self) -> Result<PgPool, Error> {
+        self.conn.get(initialize).await
+    }
+}
+
+impl fmt::Debug for PgSessionStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PgSessionStore").finish_non_exhaustive()
+    }
+}
+
+async fn initialize(pool: PgPool) -> Result<(), Error> {
+    sqlx::query(
+        r#"
+        CREATE TABLE IF NOT EXISTS http_sessions (
+            id TEXT PRIMARY KEY,
+            handle TEXT NOT NULL UNIQUE,
+            user_id TEXT,
+            data TEXT NOT NULL,
+            expires_at TIMESTAMPTZ NOT NULL,
+            updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+        )
+        "#,
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| Error::Internal(format!("Failed to create http_sessions table: {}", e)))?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_http_sessions_user \
+         ON http_sessions (user_id) WHERE user_id IS NOT NULL",
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| Error::Internal(format!("Failed to create http_sessions user index: {}", e)))?;
+
+    sqlx::query(
+        "CREATE INDEX IF NOT EXISTS idx_http_sessions_expires ON http_sessions (expires_at)",
+    )
+    .execute(&pool)
+    .await
+    .map_err(|e| {
+        Error::Internal(format!(
+            "Failed to create http_sessions expiry index: {}",
+            e
+        ))
+    })?;
+
+    Ok(())
+}
+
+#[async_trait]
+impl SessionStore for PgSessionStore {
+    async fn create(&self, record: &mut Record) -> session_store::Result<()> {
+        let pool = self.pool().await.map_err(backend_error)?;
+
+        // Draw a new ID on the (vanishingly unlikely) collision rather than
+        // overwrite someone else's session
+        loop {
+            let row = SessionRow::from_record(record)?;
+            let inserted = sqlx::query(
+                "INSERT INTO http_sessions (id, handle, user_id, data, expires_at) \
+                 VALUES ($1, $2, $3, $4, $5) \
+                 ON CONFLICT DO NOTHING",
+            )
+            .bind(&row.id)
+            .bind(&row.handle)
+            .bind(&row.user_id)
+            .bind(&row.data)
+            .bind(row.expires_at)
+            .execute(&pool)
+            .await
+            .map_err(|e| session_store::Error::Backend(e.to_string()))?
+            .rows_affected();
+
+            if inserted > 0 {
+                return Ok(());
+            }
+            record.id = Id::default();
+        }
+    }
+
+    async fn save(&self, record: &Record) -> session_store::Result<()> {
+        let pool = self.pool().await.map_err(backend_error)?;
+        let row = SessionRow::from_record(record)?;
+
+        sqlx::query(
+            "INSERT INTO http_sessions (id, handle, user_id, data, expires_at) \
+             VALUES ($1, $2, $3, $4, $5) \
+             ON CONFLICT (id) DO UPDATE SET \
+                 user_id = EXCLUDED.user_id, \
+                 data = EXCLUDED.data, \
+                 expires_at = EXCLUDED.expires_at, \
+                 updated_at = NOW()",
+        )
+        .bind(&row.id)
+        .bind(&row.handle)
+        .bind(&row.user_id)
+        .bind(&row.data)
+        .bind(row.expires_at)
+        .execute(&pool)
+        .await
+        .map_err(|e| session_store::Error::Backend(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn load(&self, session_id: &Id) -> session_store::Result<Option<Record>> {
+        let pool = self.pool().await.map_err(backend_error)?;
+
+        let row: Option<(String, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT data, expires_at FROM http_sessions \
+             WHERE id = $1 AND expires_at > NOW()",
+        )
+        .bind(session_id.to_string())
+        .fetch_optional(&pool)
+        .await
+        .map_err(|e| session_store::Error::Backend(e.to_string()))?;
+
+        row.map(|(data, expires_at)| to_record(session_id, &data, expires_at))
+            .transpose()
+    }
+
+    async fn delete(&self, session_id: &Id) -> session_store::Result<()> {
+        let pool = self.pool().await.map_err(backend_error)?;
+
+        sqlx::query("DELETE FROM http_sessions WHERE id = $1")
+            .bind(session_id.to_string())
+            .execute(&pool)
+            .await
+            .map_err(|e| session_store::Error::Backend(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PersistentSessionStore for PgSessionStore {
+    async fn list_user_sessions(&self, user_id: &str) -> Result<Vec<ActiveSession>, Error> {
+        let pool = self.pool().await?;
+
+        let rows: Vec<(String, String, DateTime<Utc>, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT handle, data, expires_at, updated_at FROM http_sessions \
+             WHERE user_id = $1 AND expires_at > NOW() \
+             ORDER BY updated_at DESC",
+        )
+        .bind(user_id)
+        .fetch_all(&pool)
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to list sessions: {}", e)))?;
+
+        rows.into_iter()
+            .map(|(handle, data, expires_at, updated_at)| {
+                to_active_session(handle, &data, expires_at, updated_at)
+            })
+            .collect()
+    }
+
+    async fn revoke_user_session(&self, user_id: &str, handle: &str) -> Result<bool, Error> {
+        let pool = self.pool().await?;
+
+        let result = sqlx::query("DELETE FROM http_sessions WHERE user_id = $1 AND handle = $2")
+            .bind(user_id)
+            .bind(handle)
+            .execute(&pool)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to revoke session: {}", e)))?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn delete_expired_sessions(&self) -> Result<u64, Error> {
+        let pool = self.pool().await?;
+
+        let result = sqlx::query("DELETE FROM http_sessions WHERE expires_at <= NOW()")
+            .execute(&pool)
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to delete expired sessions: {}", e)))?;
+
+        Ok(result.rows_affected())
+    }
+}
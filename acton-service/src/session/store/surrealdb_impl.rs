@@ -0,0 +1,264 @@
+//! SurrealDB session store.
+//!
+//! Stores sessions in a SCHEMAFULL `http_sessions` table keyed by session ID.
+//! Timestamps are Unix seconds so expiry checks are integer comparisons.
+
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use surrealdb::types::SurrealValue;
+use tower_sessions::session::{Id, Record};
+use tower_sessions::{session_store, SessionStore};
+
+use super::{
+    backend_error, to_active_session, to_record, ActiveSession, LazyConn, PersistentSessionStore,
+    SessionRow,
+};
+use crate::error::Error;
+use crate::surrealdb_backend::SurrealClient;
+
+/// Attempts at drawing an unused session ID before giving up
+const CREATE_ATTEMPTS: usize = 3;
+
+/// SurrealDB-backed session store
+#[derive(Clone)]
+pub struct SurrealSessionStore {
+    conn: LazyConn<Arc<SurrealClient>>,
+}
+
+impl SurrealSessionStore {
+    /// Create a session store on a connected client
+    ///
+    /// The `http_sessions` table is defined on first use.
+    pub fn new(client: Arc<SurrealClient>) -> Self {
+        Self {
+            conn: LazyConn::ready(client),
+        }
+    }
+
+    /// Create a session store on a pool agent's shared handle
+    pub(crate) fn from_shared(shared: crate::agents::SharedSurrealDb) -> Self {
+        Self {
+            conn: LazyConn::shared(shared),
+        }
+    }
+
+    async fn client(&self) -> Result<Arc<SurrealClient>, Error> {
+        self.conn.get(initialize).await
+    }
+}
+
+impl fmt::Debug for SurrealSessionStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SurrealSessionStore")
+            .finish_non_exhaustive()
+    }
+}
+
+async fn initialize(client: Arc<SurrealClient>) -> Result<(), Error> {
+    client
+        .query(
+            r#"
+            DEFINE TABLE IF NOT EXISTS http_sessions SCHEMAFULL;
+
+            DEFINE FIELD IF NOT EXISTS handle ON http_sessions TYPE string;
+            DEFINE FIELD IF NOT EXISTS user_id ON http_sessions TYPE option<string>;
+            DEFINE FIELD IF NOT EXISTS data ON http_sessions TYPE string;
+            DEFINE FIELD IF NOT EXISTS expires_at ON http_sessions TYPE int;
+            DEFINE FIELD IF NOT EXISTS updated_at ON http_sessions TYPE int;
+
+            DEFINE INDEX IF NOT EXISTS idx_http_sessions_handle ON http_sessions FIELDS handle UNIQUE;
+            DEFINE INDEX IF NOT EXISTS idx_http_sessions_user ON http_sessions FIELDS user_id;
+            DEFINE INDEX IF NOT EXISTS idx_http_sessions_expires ON http_sessions FIELDS expires_at;
+            "#,
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to define http_sessions table: {}", e)))?;
+
+    Ok(())
+}
+
+/// Serializable record for SurrealDB writes
+#[derive(Serialize, SurrealValue)]
+struct SessionContent {
+    handle: String,
+    user_id: Option<String>,
+    data: String,
+    expires_at: i64,
+    updated_at: i64,
+}
+
+impl From<SessionRow> for SessionContent {
+    fn from(row: SessionRow) -> Self {
+        Self {
+            handle: row.handle,
+            user_id: row.user_id,
+            data: row.data,
+            expires_at: row.expires_at.timestamp(),
+            updated_at: Utc::now().timestamp(),
+        }
+    }
+}
+
+/// Deserializable record from SurrealDB queries
+#[derive(Deserialize, SurrealValue)]
+struct StoredSession {
+    // SurrealDB returns `id` as a record id; listings use `handle` instead
+    #[allow(dead_code)]
+    id: serde_json::Value,
+    handle: String,
+    data: String,
+    expires_at: i64,
+    updated_at: i64,
+}
+
+fn from_unix(secs: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(secs, 0).unwrap_or_default()
+}
+
+#[async_trait]
+impl SessionStore for SurrealSessionStore {
+    async fn create(&self, record: &mut Record) -> session_store::Result<()> {
+        let client = self.client().await.map_err(backend_error)?;
+
+        // CREATE fails on an existing record, so draw a new ID on the
+        // (vanishingly unlikely) collision rather than overwrite it
+        let mut last_error = None;
+        for _ in 0..CREATE_ATTEMPTS {
+            let row = SessionRow::from_record(record)?;
+            let id = row.id.clone();
+
+            let mut result = client
+                .query("CREATE type::thing('http_sessions', $id) CONTENT $data")
+                .bind(("id", id))
+                .bind(("data", SessionContent::from(row)))
+                .await
+                .map_err(|e| session_store::Error::Backend(e.to_string()))?;
+
+            match result.take::<Vec<serde_json::Value>>(0) {
+                Ok(_) => return Ok(()),
+                Err(e) => {
+                    last_error = Some(e.to_string());
+                    record.id = Id::default();
+                }
+            }
+        }
+
+        Err(session_store::Error::Backend(format!(
+            "Failed to create session: {}",
+            last_error.unwrap_or_default()
+        )))
+    }
+
+    async fn save(&self, record: &Record) -> session_store::Result<()> {
+        let client = self.client().await.map_err(backend_error)?;
+        let row = SessionRow::from_record(record)?;
+        let id = row.id.clone();
+
+        client
+            .query("UPSERT type::thing('http_sessions', $id) CONTENT $data")
+            .bind(("id", id))
+            .bind(("data", SessionContent::from(row)))
+            .await
+            .map_err(|e| session_store::Error::Backend(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn load(&self, session_id: &Id) -> session_store::Result<Option<Record>> {
+        let client = self.client().await.map_err(backend_error)?;
+
+        let mut result = client
+            .query("SELECT * FROM type::thing('http_sessions', $id) WHERE expires_at > $now")
+            .bind(("id", session_id.to_string()))
+            .bind(("now", Utc::now().timestamp()))
+            .await
+            .map_err(|e| session_store::Error::Backend(e.to_string()))?;
+
+        let rows: Vec<StoredSession> = result
+            .take(0)
+            .map_err(|e| session_store::Error::Decode(e.to_string()))?;
+
+        rows.into_iter()
+            .next()
+            .map(|row| to_record(session_id, &row.data, from_unix(row.expires_at)))
+            .transpose()
+    }
+
+    async fn delete(&self, session_id: &Id) -> session_store::Result<()> {
+        let client = self.client().await.map_err(backend_error)?;
+
+        client
+            .query("DELETE type::thing('http_sessions', $id)")
+            .bind(("id", session_id.to_string()))
+            .await
+            .map_err(|e| session_store::Error::Backend(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PersistentSessionStore for SurrealSessionStore {
+    async fn list_user_sessions(&self, user_id: &str) -> Result<Vec<ActiveSession>, Error> {
+        let client = self.client().await?;
+
+        let mut result = client
+            .query(
+                "SELECT * FROM http_sessions \
+                 WHERE user_id = $user_id AND expires_at > $now \
+                 ORDER BY updated_at DESC",
+            )
+            .bind(("user_id", user_id.to_string()))
+            .bind(("now", Utc::now().timestamp()))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to list sessions: {}", e)))?;
+
+        let rows: Vec<StoredSession> = result
+            .take(0)
+            .map_err(|e| Error::Internal(format!("Failed to parse sessions: {}", e)))?;
+
+        rows.into_iter()
+            .map(|row| {
+                to_active_session(
+                    row.handle,
+                    &row.data,
+                    from_unix(row.expires_at),
+                    from_unix(row.updated_at),
+                )
+            })
+            .collect()
+    }
+
+    async fn revoke_user_session(&self, user_id: &str, handle: &str) -> Result<bool, Error> {
+        let client = self.client().await?;
+
+        let mut result = client
+            .query(
+                "DELETE http_sessions WHERE user_id = $user_id AND handle = $handle RETURN BEFORE",
+            )
+            .bind(("user_id", user_id.to_string()))
+            .bind(("handle", handle.to_string()))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to revoke session: {}", e)))?;
+
+        let deleted: Vec<serde_json::Value> = result.take(0).unwrap_or_default();
+        Ok(!deleted.is_empty())
+    }
+
+    async fn delete_expired_sessions(&self) -> Result<u64, Error> {
+        let client = self.client().await?;
+
+        let mut result = client
+            .query("DELETE http_sessions WHERE expires_at <= $now RETURN BEFORE")
+            .bind(("now", Utc::now().timestamp()))
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to delete expired sessions: {}", e)))?;
+
+        let deleted: Vec<serde_json::Value> = result.take(0).unwrap_or_default();
+        Ok(deleted.len() as u64)
+    }
+}
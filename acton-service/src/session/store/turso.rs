@@ -0,0 +1,274 @@
+//! Turso/libsql session store.
+//!
+//! Stores sessions in an `http_sessions` table. Timestamps are Unix seconds
+//! so expiry checks are integer comparisons.
+
+use std::fmt;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use tower_sessions::session::{Id, Record};
+use tower_sessions::{session_store, SessionStore};
+
+use super::{
+    backend_error, to_active_session, to_record, ActiveSession, LazyConn, PersistentSessionStore,
+    SessionRow,
+};
+use crate::error::Error;
+
+/// Turso-backed session store
+#[derive(Clone)]
+pub struct TursoSessionStore {
+    conn: LazyConn<Arc<libsql::Database>>,
+}
+
+impl TursoSessionStore {
+    /// Create a session store on an open database
+    ///
+    /// The `http_sessions` table is created on first use.
+    pub fn new(db: Arc<libsql::Database>) -> Self {
+        Self {
+            conn: LazyConn::ready(db),
+        }
+    }
+
+    /// Create a session store on a pool agent's shared handle
+    pub(crate) fn from_shared(shared: crate::agents::SharedTursoDb) -> Self {
+        Self {
+            conn: LazyConn::shared(shared),
+        }
+    }
+
+    /// Get a connection from the database
+    async fn connect(&self) -> Result<libsql::Connection, Error> {
+        let db = self.conn.get(initialize).await?;
+        connect(&db)
+    }
+}
+
+impl fmt::Debug for TursoSessionStore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("TursoSessionStore").finish_non_exhaustive()
+    }
+}
+
+fn connect(db: &libsql::Database) -> Result<libsql::Connection, Error> {
+    db.connect()
+        .map_err(|e| Error::Internal(format!("Failed to connect for sessions: {}", e)))
+}
+
+async fn initialize(db: Arc<libsql::Database>) -> Result<(), Error> {
+    let conn = connect(&db)?;
+
+    conn.execute(
+        r#"
+        CREATE TABLE IF NOT EXISTS http_sessions (
+            id TEXT PRIMARY KEY,
+            handle TEXT NOT NULL UNIQUE,
+            user_id TEXT,
+            data TEXT NOT NULL,
+            expires_at INTEGER NOT NULL,
+            updated_at INTEGER NOT NULL
+        )
+        "#,
+        (),
+    )
+    .await
+    .map_err(|e| Error::Internal(format!("Failed to create http_sessions table: {}", e)))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_http_sessions_user ON http_sessions (user_id)",
+        (),
+    )
+    .await
+    .map_err(|e| Error::Internal(format!("Failed to create http_sessions user index: {}", e)))?;
+
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_http_sessions_expires ON http_sessions (expires_at)",
+        (),
+    )
+    .await
+    .map_err(|e| {
+        Error::Internal(format!(
+            "Failed to create http_sessions expiry index: {}",
+            e
+        ))
+    })?;
+
+    Ok(())
+}
+
+fn from_unix(secs: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp(secs, 0).unwrap_or_default()
+}
+
+#[async_trait]
+impl SessionStore for TursoSessionStore {
+    async fn create(&self, record: &mut Record) -> session_store::Result<()> {
+        let conn = self.connect().await.map_err(backend_error)?;
+
+        // Draw a new ID on the (vanishingly unlikely) collision rather than
+        // overwrite someone else's session
+        loop {
+            let row = SessionRow::from_record(record)?;
+            let inserted = conn
+                .execute(
+                    "INSERT OR IGNORE INTO http_sessions \
+                     (id, handle, user_id, data, expires_at, updated_at) \
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+                    libsql::params![
+                        row.id,
+                        row.handle,
+                        row.user_id,
+                        row.data,
+                        row.expires_at.timestamp(),
+                        Utc::now().timestamp(),
+                    ],
+                )
+                .await
+                .map_err(|e| session_store::Error::Backend(e.to_string()))?;
+
+            if inserted > 0 {
+                return Ok(());
+            }
+            record.id = Id::default();
+        }
+    }
+
+    async fn save(&self, record: &Record) -> session_store::Result<()> {
+        let conn = self.connect().await.map_err(backend_error)?;
+        let row = SessionRow::from_record(record)?;
+
+        conn.execute(
+            "INSERT INTO http_sessions (id, handle, user_id, data, expires_at, updated_at) \
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6) \
+             ON CONFLICT (id) DO UPDATE SET \
+                 user_id = excluded.user_id, \
+                 data = excluded.data, \
+                 expires_at = excluded.expires_at, \
+                 updated_at = excluded.updated_at",
+            libsql::params![
+                row.id,
+                row.handle,
+                row.user_id,
+                row.data,
+                row.expires_at.timestamp(),
+                Utc::now().timestamp(),
+            ],
+        )
+        .await
+        .map_err(|e| session_store::Error::Backend(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn load(&self, session_id: &Id) -> session_store::Result<Option<Record>> {
+        let conn = self.connect().await.map_err(backend_error)?;
+
+        let mut rows = conn
+            .query(
+                "SELECT data, expires_at FROM http_sessions WHERE id = ?1 AND expires_at > ?2",
+                libsql::params![session_id.to_string(), Utc::now().timestamp()],
+            )
+            .await
+            .map_err(|e| session_store::Error::Backend(e.to_string()))?;
+
+        let Some(row) = rows
+            .next()
+            .await
+            .map_err(|e| session_store::Error::Backend(e.to_string()))?
+        else {
+            return Ok(None);
+        };
+
+        let data: String = row
+            .get(0)
+            .map_err(|e| session_store::Error::Decode(e.to_string()))?;
+        let expires_at: i64 = row
+            .get(1)
+            .map_err(|e| session_store::Error::Decode(e.to_string()))?;
+
+        to_record(session_id, &data, from_unix(expires_at)).map(Some)
+    }
+
+    async fn delete(&self, session_id: &Id) -> session_store::Result<()> {
+        let conn = self.connect().await.map_err(backend_error)?;
+
+        conn.execute(
+            "DELETE FROM http_sessions WHERE id = ?1",
+            libsql::params![session_id.to_string()],
+        )
+        .await
+        .map_err(|e| session_store::Error::Backend(e.to_string()))?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl PersistentSessionStore for TursoSessionStore {
+    async fn list_user_sessions(&self, user_id: &str) -> Result<Vec<ActiveSession>, Error> {
+        let conn = self.connect().await?;
+
+        let mut rows = conn
+            .query(
+                "SELECT handle, data, expires_at, updated_at FROM http_sessions \
+                 WHERE user_id = ?1 AND expires_at > ?2 \
+                 ORDER BY updated_at DESC",
+                libsql::params![user_id.to_string(), Utc::now().timestamp()],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to list sessions: {}", e)))?;
+
+        let map_err = |field: &str, e: libsql::Error| {
+            Error::Internal(format!("Failed to read field '{}': {}", field, e))
+        };
+
+        let mut sessions = Vec::new();
+        while let Some(row) = rows
+            .next()
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to read session row: {}", e)))?
+        {
+            let handle: String = row.get(0).map_err(|e| map_err("handle", e))?;
+            let data: String = row.get(1).map_err(|e| map_err("data", e))?;
+            let expires_at: i64 = row.get(2).map_err(|e| map_err("expires_at", e))?;
+            let updated_at: i64 = row.get(3).map_err(|e| map_err("updated_at", e))?;
+
+            sessions.push(to_active_session(
+                handle,
+                &data,
+                from_unix(expires_at),
+                from_unix(updated_at),
+            )?);
+        }
+
+        Ok(sessions)
+    }
+
+    async fn revoke_user_session(&self, user_id: &str, handle: &str) -> Result<bool, Error> {
+        let conn = self.connect().await?;
+
+        let deleted = conn
+            .execute(
+                "DELETE FROM http_sessions WHERE user_id = ?1 AND handle = ?2",
+                libsql::params![user_id.to_string(), handle.to_string()],
+            )
+            .await
+            .map_err(|e| Error::Internal(format!("Failed to revoke session: {}", e)))?;
+
+        Ok(deleted > 0)
+    }
+
+    async fn delete_expired_sessions(&self) -> Result<u64, Error> {
+        let conn = self.connect().await?;
+
+        conn.execute(
+            "DELETE FROM http_sessions WHERE expires_at <= ?1",
+            libsql::params![Utc::now().timestamp()],
+        )
+        .await
+        .map_err(|e| Error::Internal(format!("Failed to delete expired sessions: {}", e)))
+    }
+}
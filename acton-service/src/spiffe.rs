@@ -0,0 +1,907 @@
+//! SPIFFE workload identity (requires `tls` feature)
+//!
+//! [SPIFFE](https://spiffe.io) names workloads with URIs of the form
+//! `spiffe://<trust domain>/<path>` and issues each one an X.509-SVID: a
+//! certificate carrying that ID as its URI `subjectAltName`. This module gives
+//! [`crate::caller_auth`] the three pieces it needs to treat those IDs as
+//! first-class caller identities rather than opaque URIs:
+//!
+//! - [`SpiffeId`] parses and validates an ID against the SPIFFE ID grammar,
+//!   so a malformed allowlist entry is a startup error instead of a name no
+//!   certificate will ever carry.
+//! - [`SpiffeIdPattern`] is an allowlist entry scoped to one trust domain that
+//!   admits a whole subtree, such as `spiffe://prod.local/ns/payments/*`.
+//! - [`SpiffeTrustBundles`] holds one X.509 trust bundle per trust domain.
+//!   With it configured, an SVID is accepted only if it chains to the bundle
+//!   of the trust domain its own ID names, so a CA federated in for
+//!   `partner.example` cannot mint identities in `prod.local`.
+//!
+//! # Bundles and rotation
+//!
+//! Each bundle is a file of PEM certificates, or a SPIFFE bundle in its JSON
+//! (JWKS) form; the format is detected from the content. The listener's
+//! `[tls].client_ca_path` still decides which chains complete a handshake, so
+//! point it at a bundle holding every trust domain's roots — what
+//! spiffe-helper writes with `include_federated_domains = true` — and list the
+//! per-domain files here.
+//!
+//! Bundles rotate like every other credential: polled every
+//! `reload_interval_secs`, reloaded on `SIGHUP` when `[tls].reload_on_sighup`
+//! is set, and replaced as a set only when every file parses. spiffe-helper
+//! rewrites its files one at a time, so a poll can land between two writes;
+//! that read fails, the previous bundles stay in force, and the next poll
+//! picks up the finished set.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use arc_swap::ArcSwap;
+use rustls_pki_types::{CertificateDer, UnixTime};
+use tokio_rustls::rustls::server::danger::ClientCertVerifier;
+use tokio_rustls::rustls::RootCertStore;
+
+use crate::config::SpiffeConfig;
+use crate::error::{Error, Result};
+
+/// Longest accepted SPIFFE ID, in bytes, per the SPIFFE ID specification.
+const MAX_SPIFFE_ID_LEN: usize = 2048;
+
+/// Longest accepted trust domain name, in bytes.
+const MAX_TRUST_DOMAIN_LEN: usize = 255;
+
+/// The scheme every SPIFFE ID starts with.
+const SCHEME: &str = "spiffe://";
+
+// ---------------------------------------------------------------------------
+// IDs and patterns
+// ---------------------------------------------------------------------------
+
+/// A validated SPIFFE ID.
+///
+/// The grammar is the SPIFFE ID specification's, applied strictly: a
+/// lowercase trust domain of letters, digits, `.`, `-` and `_`; path segments
+/// of letters, digits, `.`, `-` and `_` that are neither empty, `.` nor `..`;
+/// no port, user info, query or fragment; no trailing `/`. Comparison is
+/// byte-exact, which the grammar makes unambiguous.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SpiffeId {
+    trust_domain: String,
+    /// Empty, or `/`-prefixed segments.
+    path: String,
+}
+
+impl SpiffeId {
+    /// Parse a SPIFFE ID.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpiffeIdError`] naming the first rule the value breaks.
+    pub fn parse(value: &str) -> std::result::Result<Self, SpiffeIdError> {
+        if value.len() > MAX_SPIFFE_ID_LEN {
+            return Err(SpiffeIdError::TooLong {
+                len: value.len(),
+                max: MAX_SPIFFE_ID_LEN,
+            });
+        }
+        let rest = value
+            .strip_prefix(SCHEME)
+            .ok_or_else(|| SpiffeIdError::NotSpiffe {
+                value: value.to_string(),
+            })?;
+        let (trust_domain, path) = match rest.find('/') {
+            Some(slash) => rest.split_at(slash),
+            None => (rest, ""),
+        };
+        validate_trust_domain(trust_domain)?;
+
+        if !path.is_empty() {
+            for segment in path[1..].split('/') {
+                validate_segment(value, segment)?;
+            }
+        }
+
+        Ok(Self {
+            trust_domain: trust_domain.to_string(),
+            path: path.to_string(),
+        })
+    }
+
+    /// The trust domain, e.g. `prod.local`.
+    #[must_use]
+    pub fn trust_domain(&self) -> &str {
+        &self.trust_domain
+    }
+
+    /// The path, e.g. `/ns/payments/sa/api`; empty for a trust domain's own ID.
+    #[must_use]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+}
+
+impl fmt::Display for SpiffeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{SCHEME}{}{}", self.trust_domain, self.path)
+    }
+}
+
+impl std::str::FromStr for SpiffeId {
+    type Err = SpiffeIdError;
+
+    fn from_str(value: &str) -> std::result::Result<Self, Self::Err> {
+        Self::parse(value)
+    }
+}
+
+/// An allowlist entry admitting every SPIFFE ID under a path in one trust
+/// domain.
+///
+/// Written as a SPIFFE ID with a final `/*` segment. The `*` stands for one or
+/// more whole segments, so `spiffe://prod.local/ns/payments/*` admits
+/// `spiffe://prod.local/ns/payments/sa/api` and
+/// `spiffe://prod.local/ns/payments/worker`, but not
+/// `spiffe://prod.local/ns/payments` itself, `spiffe://prod.local/ns/payments-v2/x`,
+/// or anything in another trust domain. `spiffe://prod.local/*` admits every
+/// workload in `prod.local`.
+///
+/// The trust domain is always literal: a pattern can never widen across trust
+/// domains, and a `*` anywhere but the final segment is refused.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SpiffeIdPattern {
+    /// The ID the pattern extends; its path is the required prefix.
+    prefix: SpiffeId,
+}
+
+impl SpiffeIdPattern {
+    /// Parse a pattern such as `spiffe://prod.local/ns/payments/*`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SpiffeIdError`] if the value does not end in `/*`, has a `*`
+    /// anywhere else, or is not otherwise a valid SPIFFE ID.
+    pub fn parse(value: &str) -> std::result::Result<Self, SpiffeIdError> {
+        let Some(prefix) = value.strip_suffix("/*") else {
+            return Err(SpiffeIdError::MisplacedWildcard {
+                value: value.to_string(),
+            });
+        };
+        if prefix.contains('*') {
+            return Err(SpiffeIdError::MisplacedWildcard {
+                value: value.to_string(),
+            });
+        }
+        Ok(Self {
+            prefix: SpiffeId::parse(prefix)?,
+        })
+    }
+
+    /// The trust domain this pattern is scoped to.
+    #[must_use]
+    pub fn trust_domain(&self) -> &str {
+        self.prefix.trust_domain()
+    }
+
+    /// Whether `id` falls under this pattern.
+    #[must_use]
+    pub fn matches(&self, id: &SpiffeId) -> bool {
+        let prefix = self.prefix.path();
+        id.trust_domain == self.prefix.trust_domain
+            && id.path.len() > prefix.len()
+            && id.path.starts_with(prefix)
+            && id.path.as_bytes()[prefix.len()] == b'/'
+    }
+}
+
+impl fmt::Display for SpiffeIdPattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/*", self.prefix)
+    }
+}
+
+/// Why a value is not a SPIFFE ID or pattern.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum SpiffeIdError {
+    /// The value does not start with `spiffe://`.
+    #[error("'{value}' is not a SPIFFE ID; it must start with spiffe://")]
+    NotSpiffe {
+        /// The offending value.
+        value: String,
+    },
+
+    /// The value exceeded the SPIFFE ID length limit.
+    #[error("SPIFFE ID is {len} bytes, over the {max}-byte limit")]
+    TooLong {
+        /// Length of the offending value.
+        len: usize,
+        /// The accepted maximum.
+        max: usize,
+    },
+
+    /// The trust domain is empty, too long, or has a character outside
+    /// lowercase letters, digits, `.`, `-` and `_`.
+    #[error(
+        "'{trust_domain}' is not a valid trust domain; use lowercase letters, digits, '.', '-' \
+         and '_' only, with no port or user info"
+    )]
+    InvalidTrustDomain {
+        /// The offending trust domain.
+        trust_domain: String,
+    },
+
+    /// A path segment is empty, `.`, `..`, or has a character outside
+    /// letters, digits, `.`, `-` and `_`.
+    #[error(
+        "SPIFFE ID '{value}' has an invalid path segment '{segment}'; segments use letters, \
+         digits, '.', '-' and '_', and cannot be empty, '.' or '..'"
+    )]
+    InvalidPathSegment {
+        /// The offending ID.
+        value: String,
+        /// The offending segment.
+        segment: String,
+    },
+
+    /// A pattern's `*` is not the whole final segment.
+    #[error(
+        "'{value}' is not a SPIFFE ID pattern; '*' may only appear as the whole final segment, \
+         as in spiffe://prod.local/ns/payments/*"
+    )]
+    MisplacedWildcard {
+        /// The offending value.
+        value: String,
+    },
+}
+
+fn validate_trust_domain(trust_domain: &str) -> std::result::Result<(), SpiffeIdError> {
+    let valid = !trust_domain.is_empty()
+        && trust_domain.len() <= MAX_TRUST_DOMAIN_LEN
+        && trust_domain.bytes().all(|b| {
+            b.is_ascii_lowercase() || b.is_ascii_digit() || matches!(b, b'.' | b'-' | b'_')
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(SpiffeIdError::InvalidTrustDomain {
+            trust_domain: trust_domain.to_string(),
+        })
+    }
+}
+
+fn validate_segment(value: &str, segment: &str) -> std::result::Result<(), SpiffeIdError> {
+    let valid = !matches!(segment, "" | "." | "..")
+        && segment
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || matches!(b, b'.' | b'-' | b'_'));
+    if valid {
+        Ok(())
+    } else {
+        Err(SpiffeIdError::InvalidPathSegment {
+            value: value.to_string(),
+            segment: segment.to_string(),
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Trust bundles
+// ---------------------------------------------------------------------------
+
+/// One X.509 trust bundle per SPIFFE trust domain, reloadable in place.
+///
+/// Cloning is cheap and every clone sees the same bundles, so the
+/// caller-authorization layers and the reload task share one set.
+#[derive(Clone)]
+pub struct SpiffeTrustBundles {
+    inner: Arc<TrustBundlesInner>,
+}
+
+struct TrustBundlesInner {
+    paths: BTreeMap<String, PathBuf>,
+    current: ArcSwap<BTreeMap<String, Arc<dyn ClientCertVerifier>>>,
+    reload_interval: Option<Duration>,
+    /// Fingerprint of the files behind `current`, for the poll.
+    fingerprint: Mutex<Option<u64>>,
+}
+
+impl SpiffeTrustBundles {
+    /// Load every bundle `config` lists.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Error::Tls`] if no trust domain is listed, a trust domain
+    /// name is invalid, `reload_interval_secs` is zero, or any bundle cannot
+    /// be read or holds no certificates.
+    pub fn from_config(config: &SpiffeConfig) -> Result<Self> {
+        if config.trust_bundles.is_empty() {
+            return Err(Error::Tls(
+                "[caller_auth.spiffe].trust_bundles lists no trust domains".to_string(),
+            ));
+        }
+        for trust_domain in config.trust_bundles.keys() {
+            validate_trust_domain(trust_domain)
+                .map_err(|e| Error::Tls(format!("[caller_auth.spiffe].trust_bundles: {e}")))?;
+        }
+        if config.reload_interval_secs == Some(0) {
+            return Err(Error::Tls(
+                "[caller_auth.spiffe].reload_interval_secs = 0 would reread the bundles in a \
+                 busy loop; omit it to disable polling"
+                    .to_string(),
+            ));
+        }
+
+        let fingerprint = fingerprint_bundles(&config.trust_bundles).ok();
+        let current = load_verifiers(&config.trust_bundles)?;
+        tracing::info!(
+            trust_domains = ?config.trust_bundles.keys().collect::<Vec<_>>(),
+            "SPIFFE trust bundles loaded"
+        );
+
+        Ok(Self {
+            inner: Arc::new(TrustBundlesInner {
+                paths: config.trust_bundles.clone(),
+                current: ArcSwap::from_pointee(current),
+                reload_interval: config.reload_interval_secs.map(Duration::from_secs),
+                fingerprint: Mutex::new(fingerprint),
+            }),
+        })
+    }
+
+    /// The trust domains with a bundle.
+    pub fn trust_domains(&self) -> impl Iterator<Item = &str> {
+        self.inner.paths.keys().map(String::as_str)
+    }
+
+    /// Whether `trust_domain` has a bundle.
+    #[must_use]
+    pub fn contains(&self, trust_domain: &str) -> bool {
+        self.inner.paths.contains_key(trust_domain)
+    }
+
+    /// The configured poll period, if polling is enabled.
+    #[must_use]
+    pub fn reload_interval(&self) -> Option<Duration> {
+        self.inner.reload_interval
+    }
+
+    /// Check that `chain` (leaf first) chains to the bundle of `id`'s trust
+    /// domain.
+    ///
+    /// # Errors
+    ///
+    /// Returns a description of the failure when the trust domain has no
+    /// bundle or the chain does not verify against it.
+    pub fn verify(
+        &self,
+        id: &SpiffeId,
+        chain: &[CertificateDer<'_>],
+    ) -> std::result::Result<(), String> {
+        let current = self.inner.current.load();
+        let verifier = current
+            .get(id.trust_domain())
+            .ok_or_else(|| format!("no trust bundle for trust domain '{}'", id.trust_domain()))?;
+        let (leaf, intermediates) = chain
+            .split_first()
+            .ok_or_else(|| "no certificate presented".to_string())?;
+        verifier
+            .verify_client_cert(leaf, intermediates, UnixTime::now())
+            .map(|_| ())
+            .map_err(|e| {
+                format!(
+                    "certificate does not chain to the '{}' trust bundle: {e}",
+                    id.trust_domain()
+                )
+            })
+    }
+
+    /// Reread every bundle and install them together.
+    ///
+    /// Fail-closed: if any file cannot be read or parsed, nothing is replaced
+    /// and the previous bundles stay in force.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first load failure.
+    pub fn reload(&self) -> Result<()> {
+        let fingerprint = fingerprint_bundles(&self.inner.paths).ok();
+        match load_verifiers(&self.inner.paths) {
+            Ok(verifiers) => {
+                self.inner.current.store(Arc::new(verifiers));
+                *self
+                    .inner
+                    .fingerprint
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner()) = fingerprint;
+                tracing::info!("SPIFFE trust bundles reloaded");
+                Ok(())
+            }
+            Err(e) => {
+                tracing::error!(
+                    error = %e,
+                    "SPIFFE trust bundle reload failed; keeping the previous bundles"
+                );
+                Err(e)
+            }
+        }
+    }
+
+    /// Reload only if the files changed since the bundles in force were read.
+    fn reload_if_changed(&self) -> Result<bool> {
+        let Ok(now) = fingerprint_bundles(&self.inner.paths) else {
+            // Unreadable mid-rotation; the next poll tries again.
+            return Ok(false);
+        };
+        let last = *self
+            .inner
+            .fingerprint
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        if last == Some(now) {
+            return Ok(false);
+        }
+        self.reload().map(|()| true)
+    }
+
+    /// Whether two handles share the same bundles.
+    #[must_use]
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.inner, &other.inner)
+    }
+}
+
+impl fmt::Debug for SpiffeTrustBundles {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SpiffeTrustBundles")
+            .field("trust_bundles", &self.inner.paths)
+            .field("reload_interval", &self.inner.reload_interval)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Handles compare by identity: two loads of the same files are still two
+/// independently reloading sets.
+impl PartialEq for SpiffeTrustBundles {
+    fn eq(&self, other: &Self) -> bool {
+        self.ptr_eq(other)
+    }
+}
+
+impl Eq for SpiffeTrustBundles {}
+
+/// Keep `bundles` current until the task is aborted.
+///
+/// Polls every [`SpiffeTrustBundles::reload_interval`] and, when `sighup` is
+/// set, also reloads on `SIGHUP` — the signal spiffe-helper's `renew_signal`
+/// sends once it has written a rotation. Returns `None` when neither trigger
+/// is configured.
+pub(crate) fn spawn_bundle_reload(
+    bundles: SpiffeTrustBundles,
+    sighup: bool,
+) -> Option<tokio::task::JoinHandle<()>> {
+    let interval = bundles.reload_interval();
+    let sighup = sighup && cfg!(unix);
+    if interval.is_none() && !sighup {
+        return None;
+    }
+    tracing::info!(
+        interval_secs = interval.map(|p| p.as_secs()),
+        sighup,
+        "watching SPIFFE trust bundles for rotation"
+    );
+
+    Some(tokio::spawn(async move {
+        #[cfg(unix)]
+        let mut hangup = if sighup {
+            use tokio::signal::unix::{signal, SignalKind};
+            match signal(SignalKind::hangup()) {
+                Ok(hangup) => Some(hangup),
+                Err(e) => {
+                    tracing::error!(
+                        error = %e,
+                        "failed to install the SIGHUP handler for SPIFFE trust bundles; \
+                         relying on polling alone"
+                    );
+                    None
+                }
+            }
+        } else {
+            None
+        };
+
+        let mut ticker = interval.map(|period| {
+            let mut ticker = tokio::time::interval(period);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Skip);
+            ticker
+        });
+        // A tokio interval's first tick completes immediately; the bundles were
+        // just loaded, so skip it.
+        if let Some(ref mut ticker) = ticker {
+            ticker.tick().await;
+        }
+
+        loop {
+            let poll = async {
+                match ticker.as_mut() {
+                    Some(ticker) => {
+                        ticker.tick().await;
+                    }
+                    None => std::future::pending().await,
+                }
+            };
+            #[cfg(unix)]
+            let signalled = async {
+                match hangup.as_mut() {
+                    Some(hangup) => {
+                        hangup.recv().await;
+                    }
+                    None => std::future::pending().await,
+                }
+            };
+            #[cfg(not(unix))]
+            let signalled = std::future::pending::<()>();
+
+            let forced = tokio::select! {
+                () = poll => false,
+                () = signalled => true,
+            };
+
+            // Reading the files is blocking I/O; keep it off the runtime
+            // workers, like the TLS credential poll. Failures are logged by
+            // `reload` and retried on the next trigger.
+            let bundles = bundles.clone();
+            let _ = tokio::task::spawn_blocking(move || {
+                if forced {
+                    bundles.reload().map(|()| true)
+                } else {
+                    bundles.reload_if_changed()
+                }
+            })
+            .await;
+        }
+    }))
+}
+
+/// Build one verifier per trust domain.
+fn load_verifiers(
+    paths: &BTreeMap<String, PathBuf>,
+) -> Result<BTreeMap<String, Arc<dyn ClientCertVerifier>>> {
+    paths
+        .iter()
+        .map(|(trust_domain, path)| {
+            let mut roots = RootCertStore::empty();
+            for cert in read_bundle(trust_domain, path)? {
+                roots.add(cert).map_err(|e| {
+                    Error::Tls(format!(
+                        "SPIFFE trust bundle for '{trust_domain}' ('{}') holds an unusable \
+                         certificate: {e}",
+                        path.display()
+                    ))
+                })?;
+            }
+            let verifier = crate::tls::build_client_verifier(roots, false)?;
+            Ok((trust_domain.clone(), verifier))
+        })
+        .collect()
+}
+
+/// Read a bundle file, in PEM or SPIFFE JSON form.
+fn read_bundle(trust_domain: &str, path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    let bytes = std::fs::read(path).map_err(|e| {
+        Error::Tls(format!(
+            "Failed to read SPIFFE trust bundle for '{trust_domain}' ('{}'): {e}",
+            path.display()
+        ))
+    })?;
+
+    let is_json = bytes
+        .iter()
+        .find(|b| !b.is_ascii_whitespace())
+        .is_some_and(|b| *b == b'{');
+    let certs = if is_json {
+        parse_json_bundle(&bytes)
+    } else {
+        use rustls_pki_types::pem::PemObject;
+        CertificateDer::pem_slice_iter(&bytes)
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())
+    }
+    .map_err(|reason| {
+        Error::Tls(format!(
+            "Failed to parse SPIFFE trust bundle for '{trust_domain}' ('{}'): {reason}",
+            path.display()
+        ))
+    })?;
+
+    if certs.is_empty() {
+        return Err(Error::Tls(format!(
+            "SPIFFE trust bundle for '{trust_domain}' ('{}') holds no X.509 authorities",
+            path.display()
+        )));
+    }
+    Ok(certs)
+}
+
+/// The X.509 authorities of a SPIFFE bundle in its JWKS form.
+///
+/// Only keys with `"use": "x509-svid"` are authorities for X.509-SVIDs; the
+/// others (JWT-SVID signing keys) are skipped. Each carries its certificate as
+/// the single entry of `x5c`, in standard base64.
+fn parse_json_bundle(bytes: &[u8]) -> std::result::Result<Vec<CertificateDer<'static>>, String> {
+    use base64::engine::general_purpose::STANDARD;
+    use base64::Engine;
+
+    #[derive(serde::Deserialize)]
+    struct Bundle {
+        #[serde(default)]
+        keys: Vec<Key>,
+    }
+    #[derive(serde::Deserialize)]
+    struct Key {
+        #[serde(rename = "use", default)]
+        usage: String,
+        #[serde(default)]
+        x5c: Vec<String>,
+    }
+
+    let bundle: Bundle = serde_json::from_slice(bytes).map_err(|e| e.to_string())?;
+    bundle
+        .keys
+        .iter()
+        .filter(|key| key.usage == "x509-svid")
+        .map(|key| match key.x5c.as_slice() {
+            [cert] => STANDARD
+                .decode(cert)
+                .map(CertificateDer::from)
+                .map_err(|e| format!("x5c is not base64: {e}")),
+            _ => Err("an x509-svid key must carry exactly one x5c certificate".to_string()),
+        })
+        .collect()
+}
+
+fn fingerprint_bundles(paths: &BTreeMap<String, PathBuf>) -> std::io::Result<u64> {
+    let mut hasher = DefaultHasher::new();
+    for (trust_domain, path) in paths {
+        trust_domain.hash(&mut hasher);
+        let bytes = std::fs::read(path)?;
+        bytes.len().hash(&mut hasher);
+        bytes.hash(&mut hasher);
+    }
+    Ok(hasher.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn id(value: &str) -> SpiffeId {
+        SpiffeId::parse(value).expect("valid SPIFFE ID")
+    }
+
+    fn pattern(value: &str) -> SpiffeIdPattern {
+        SpiffeIdPattern::parse(value).expect("valid pattern")
+    }
+
+    #[test]
+    fn ids_split_into_trust_domain_and_path() {
+        let parsed = id("spiffe://prod.local/ns/payments/sa/api");
+        assert_eq!(parsed.trust_domain(), "prod.local");
+        assert_eq!(parsed.path(), "/ns/payments/sa/api");
+        assert_eq!(parsed.to_string(), "spiffe://prod.local/ns/payments/sa/api");
+
+        let domain = id("spiffe://prod.local");
+        assert_eq!(domain.path(), "");
+    }
+
+    #[test]
+    fn ids_outside_the_grammar_are_refused() {
+        for bad in [
+            "https://prod.local/ns/a",
+            "SPIFFE://prod.local/ns/a",
+            "spiffe://Prod.Local/ns/a",
+            "spiffe://prod.local:8443/ns/a",
+            "spiffe://user@prod.local/ns/a",
+            "spiffe:///ns/a",
+            "spiffe://prod.local/",
+            "spiffe://prod.local/ns//a",
+            "spiffe://prod.local/ns/../a",
+            "spiffe://prod.local/ns/./a",
+            "spiffe://prod.local/ns/a?x=1",
+            "spiffe://prod.local/ns/a#frag",
+            "spiffe://prod.local/ns/a b",
+        ] {
+            assert!(SpiffeId::parse(bad).is_err(), "{bad} must be refused");
+        }
+    }
+
+    #[test]
+    fn a_pattern_admits_its_subtree_and_nothing_else() {
+        let payments = pattern("spiffe://prod.local/ns/payments/*");
+
+        assert!(payments.matches(&id("spiffe://prod.local/ns/payments/sa/api")));
+        assert!(payments.matches(&id("spiffe://prod.local/ns/payments/worker")));
+
+        assert!(!payments.matches(&id("spiffe://prod.local/ns/payments")));
+        assert!(!payments.matches(&id("spiffe://prod.local/ns/payments-v2/sa/api")));
+        assert!(!payments.matches(&id("spiffe://prod.local/ns/billing/sa/api")));
+        assert!(!payments.matches(&id("spiffe://staging.local/ns/payments/sa/api")));
+    }
+
+    #[test]
+    fn a_trust_domain_pattern_admits_every_workload_in_it() {
+        let everything = pattern("spiffe://prod.local/*");
+        assert!(everything.matches(&id("spiffe://prod.local/ns/a")));
+        assert!(!everything.matches(&id("spiffe://prod.local")));
+        assert!(!everything.matches(&id("spiffe://prod.local.evil/ns/a")));
+    }
+
+    #[test]
+    fn wildcards_outside_the_final_segment_are_refused() {
+        for bad in [
+            "spiffe://*.local/ns/a/*",
+            "spiffe://prod.local/ns/*/sa/api",
+            "spiffe://prod.local/ns/pay*",
+            "spiffe://prod.local/ns/*/*",
+            "spiffe://prod.local/ns/payments",
+        ] {
+            assert!(
+                SpiffeIdPattern::parse(bad).is_err(),
+                "{bad} must be refused"
+            );
+        }
+    }
+
+    struct Ca {
+        issuer: rcgen::Issuer<'static, rcgen::KeyPair>,
+        cert: rcgen::Certificate,
+    }
+
+    fn ca() -> Ca {
+        let key = rcgen::KeyPair::generate().expect("ca key");
+        let mut params = rcgen::CertificateParams::new(Vec::new()).expect("ca params");
+        params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+        params.key_usages = vec![rcgen::KeyUsagePurpose::KeyCertSign];
+        params
+            .distinguished_name
+            .push(rcgen::DnType::CommonName, "acton-service test SPIFFE CA");
+        let cert = params.self_signed(&key).expect("ca cert");
+        Ca {
+            issuer: rcgen::Issuer::new(params, key),
+            cert,
+        }
+    }
+
+    fn svid(ca: &Ca, spiffe_id: &str) -> CertificateDer<'static> {
+        let key = rcgen::KeyPair::generate().expect("svid key");
+        let mut params = rcgen::CertificateParams::new(Vec::new()).expect("svid params");
+        params.subject_alt_names = vec![rcgen::SanType::URI(
+            spiffe_id.try_into().expect("valid IA5 string"),
+        )];
+        params.extended_key_usages = vec![rcgen::ExtendedKeyUsagePurpose::ClientAuth];
+        params
+            .signed_by(&key, &ca.issuer)
+            .expect("svid")
+            .der()
+            .clone()
+    }
+
+    fn write_file(dir: &Path, name: &str, contents: &str) -> PathBuf {
+        let path = dir.join(name);
+        let mut file = std::fs::File::create(&path).expect("create");
+        file.write_all(contents.as_bytes()).expect("write");
+        path
+    }
+
+    fn json_bundle(ca: &Ca) -> String {
+        use base64::engine::general_purpose::STANDARD;
+        use base64::Engine;
+        serde_json::json!({
+            "keys": [
+                { "use": "x509-svid", "kty": "EC", "x5c": [STANDARD.encode(ca.cert.der())] },
+                { "use": "jwt-svid", "kty": "EC", "kid": "ignored" },
+            ],
+            "spiffe_sequence": 1,
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn an_svid_verifies_only_against_its_own_trust_domain() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let prod = ca();
+        let partner = ca();
+        let config = SpiffeConfig {
+            trust_bundles: BTreeMap::from([
+                (
+                    "prod.local".to_string(),
+                    write_file(dir.path(), "prod.pem", &prod.cert.pem()),
+                ),
+                (
+                    "partner.example".to_string(),
+                    write_file(dir.path(), "partner.json", &json_bundle(&partner)),
+                ),
+            ]),
+            reload_interval_secs: None,
+        };
+        let bundles = SpiffeTrustBundles::from_config(&config).expect("bundles load");
+
+        let api = id("spiffe://prod.local/ns/payments/sa/api");
+        bundles
+            .verify(&api, &[svid(&prod, &api.to_string())])
+            .expect("prod CA vouches for prod IDs");
+
+        // The partner CA is trusted, but only for its own trust domain.
+        let forged = bundles.verify(&api, &[svid(&partner, &api.to_string())]);
+        assert!(forged.is_err(), "a federated CA must not mint prod IDs");
+
+        let partner_id = id("spiffe://partner.example/billing");
+        bundles
+            .verify(&partner_id, &[svid(&partner, &partner_id.to_string())])
+            .expect("JSON bundle vouches for its trust domain");
+
+        let unknown = id("spiffe://other.example/x");
+        assert!(bundles
+            .verify(&unknown, &[svid(&prod, &unknown.to_string())])
+            .is_err());
+    }
+
+    #[test]
+    fn a_failed_reload_keeps_the_previous_bundles() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let prod = ca();
+        let path = write_file(dir.path(), "prod.pem", &prod.cert.pem());
+        let config = SpiffeConfig {
+            trust_bundles: BTreeMap::from([("prod.local".to_string(), path.clone())]),
+            reload_interval_secs: Some(30),
+        };
+        let bundles = SpiffeTrustBundles::from_config(&config).expect("bundles load");
+        let api = id("spiffe://prod.local/api");
+        let chain = [svid(&prod, &api.to_string())];
+
+        assert!(!bundles.reload_if_changed().expect("unchanged"));
+
+        // A half-written rotation, as spiffe-helper leaves between writes.
+        write_file(dir.path(), "prod.pem", "-----BEGIN CERTIFICATE-----\nMII");
+        assert!(bundles.reload_if_changed().is_err());
+        bundles
+            .verify(&api, &chain)
+            .expect("previous bundle still in force");
+
+        // The rotation completes with a new CA, and the next poll takes it.
+        let rotated = ca();
+        write_file(dir.path(), "prod.pem", &rotated.cert.pem());
+        assert!(bundles.reload_if_changed().expect("rotated"));
+        assert!(bundles.verify(&api, &chain).is_err());
+        bundles
+            .verify(&api, &[svid(&rotated, &api.to_string())])
+            .expect("new bundle in force");
+    }
+
+    #[test]
+    fn invalid_configuration_is_refused() {
+        let dir = tempfile::tempdir().expect("tempdir");
+        let path = write_file(dir.path(), "prod.pem", &ca().cert.pem());
+
+        let empty = SpiffeConfig {
+            trust_bundles: BTreeMap::new(),
+            reload_interval_secs: None,
+        };
+        assert!(SpiffeTrustBundles::from_config(&empty).is_err());
+
+        let bad_domain = SpiffeConfig {
+            trust_bundles: BTreeMap::from([("Prod.Local".to_string(), path.clone())]),
+            reload_interval_secs: None,
+        };
+        assert!(SpiffeTrustBundles::from_config(&bad_domain).is_err());
+
+        let zero = SpiffeConfig {
+            trust_bundles: BTreeMap::from([("prod.local".to_string(), path)]),
+            reload_interval_secs: Some(0),
+        };
+        assert!(SpiffeTrustBundles::from_config(&zero).is_err());
+    }
+}
@@ -116,6 +116,10 @@ where
 
     /// App-defined liveness/readiness checks folded into `/health` and `/ready`
     health_checks: crate::checks::HealthChecks,
+
+    /// Certificate expiry monitor reported in the `/health` details
+    #[cfg(feature = "tls")]
+    cert_expiry: Option<crate::cert_expiry::CertificateExpiryMonitor>,
 }
 
 impl<T> Default for AppState<T>
@@ -149,6 +153,8 @@ where
             broker: None,
             actor_extensions: crate::extensions::ActorExtensions::default(),
             health_checks: crate::checks::HealthChecks::default(),
+            #[cfg(feature = "tls")]
+            cert_expiry: None,
         }
     }
 }
@@ -188,6 +194,8 @@ where
             broker: None,
             actor_extensions: crate::extensions::ActorExtensions::default(),
             health_checks: crate::checks::HealthChecks::default(),
+            #[cfg(feature = "tls")]
+            cert_expiry: None,
         }
     }
 
@@ -212,6 +220,21 @@ where
         self.health_checks = checks;
     }
 
+    /// The certificate expiry monitor, when the service watches certificates
+    #[cfg(feature = "tls")]
+    pub fn cert_expiry(&self) -> Option<&crate::cert_expiry::CertificateExpiryMonitor> {
+        self.cert_expiry.as_ref()
+    }
+
+    /// Install the certificate expiry monitor (called once by the service builder).
+    #[cfg(feature = "tls")]
+    pub(crate) fn set_cert_expiry(
+        &mut self,
+        monitor: crate::cert_expiry::CertificateExpiryMonitor,
+    ) {
+        self.cert_expiry = Some(monitor);
+    }
+
     /// Get the database pool
     ///
     /// Returns a cloned PgPool if available. PgPool uses Arc internally,
@@ -593,6 +616,14 @@ where
             }
         }
 
+        #[cfg(all(
+            feature = "events",
+            any(feature = "database", feature = "turso", feature = "surrealdb")
+        ))]
+        {
+            summary.outbox = crate::events::outbox::outbox_health(self).await;
+        }
+
         summary.healthy = summary.is_healthy();
         summary
     }
@@ -883,6 +914,8 @@ where
             broker: None,
             actor_extensions: crate::extensions::ActorExtensions::default(),
             health_checks: crate::checks::HealthChecks::default(),
+            #[cfg(feature = "tls")]
+            cert_expiry: None,
         })
     }
 }
@@ -42,16 +42,19 @@ use std::collections::hash_map::DefaultHasher;
 use std::hash::{Hash, Hasher};
 use std::io;
 use std::net::SocketAddr;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 
 use arc_swap::ArcSwap;
-use rustls_pki_types::CertificateDer;
+use chrono::{DateTime, Utc};
+use rustls_pki_types::{CertificateDer, CertificateRevocationListDer, PrivateKeyDer};
 use tokio::net::{TcpListener, TcpStream};
 use tokio::sync::mpsc;
+use tokio_rustls::rustls::crypto::CryptoProvider;
 use tokio_rustls::rustls::server::danger::ClientCertVerifier;
-use tokio_rustls::rustls::server::WebPkiClientVerifier;
+use tokio_rustls::rustls::server::{ClientHello, ResolvesServerCert, WebPkiClientVerifier};
+use tokio_rustls::rustls::sign::CertifiedKey;
 use tokio_rustls::rustls::{RootCertStore, ServerConfig};
 use tokio_rustls::server::TlsStream;
 use tokio_rustls::TlsAcceptor;
@@ -82,6 +85,15 @@ use crate::error::Result;
 /// reread, so it is static and [`reload`](Self::reload) reports that rather
 /// than silently doing nothing.
 ///
+/// # Multiple certificates
+///
+/// When [`TlsConfig::certificates`] lists SNI certificates, each one is a
+/// separate unit of rotation. A reload rereads all of them; an entry that fails
+/// keeps serving its previous certificate while the rest install their new
+/// ones, and the reload returns an error naming every entry that failed.
+/// [`certificates`](Self::certificates) reports what each entry is serving,
+/// including when it expires.
+///
 /// # Example
 ///
 /// ```rust,ignore
@@ -110,6 +122,21 @@ struct TlsConfigSourceInner {
     /// being spawned is caught on the first tick rather than missed until the
     /// next rotation.
     initial_fingerprint: Option<u64>,
+    /// The per-hostname certificate resolver, when the origin lists SNI
+    /// certificates. Held here rather than only inside `current` so a reload
+    /// can swap each certificate in place and rebuild the `ServerConfig` around
+    /// the same resolver.
+    sni: Option<Arc<SniCertResolver>>,
+    /// What the listener is serving, refreshed after every load and reload.
+    served: ArcSwap<Vec<ServedCertificate>>,
+    /// The client-CA bundle installed with `current`, if it requests client
+    /// certificates.
+    client_ca: ArcSwap<Option<TrustedClientCa>>,
+    /// Pending ACME challenge responses, when the origin provisions its
+    /// certificate through ACME. Outlives every `ServerConfig` installed in
+    /// `current`, each of which answers from it.
+    #[cfg(feature = "acme")]
+    acme: Option<crate::acme::AcmeChallenges>,
 }
 
 impl TlsConfigSource {
@@ -125,6 +152,11 @@ impl TlsConfigSource {
                 current: ArcSwap::new(server_config),
                 origin: None,
                 initial_fingerprint: None,
+                sni: None,
+                served: ArcSwap::from_pointee(Vec::new()),
+                client_ca: ArcSwap::from_pointee(None),
+                #[cfg(feature = "acme")]
+                acme: None,
             }),
         }
     }
@@ -135,7 +167,26 @@ impl TlsConfigSource {
     /// by `tls_config` through [`load_server_config`], and remembers those paths
     /// so [`reload`](Self::reload) can reread them. Returns the load error if
     /// the initial read fails, leaving no source to serve from.
+    ///
+    /// With [`TlsConfig::acme`] set, a first start that finds no certificate
+    /// on disk writes a short-lived self-signed placeholder to serve until the
+    /// first one is issued; see `crate::acme`.
     pub fn from_tls_config(tls_config: &TlsConfig) -> Result<Self> {
+        #[cfg(feature = "acme")]
+        if tls_config.acme.is_some() {
+            crate::acme::prepare(tls_config)?;
+        }
+        #[cfg(not(feature = "acme"))]
+        if tls_config.acme.is_some() {
+            return Err(crate::error::Error::Tls(
+                "[tls.acme] is configured, but this build does not include the `acme` \
+                 feature, so nothing would obtain or renew the certificate. Enable the \
+                 `acme` feature, or remove [tls.acme] and provision cert_path and \
+                 key_path another way."
+                    .to_string(),
+            ));
+        }
+
         // Fingerprint the files *before* loading them, so the recorded baseline
         // can only be as old as — never newer than — the credentials actually
         // installed. A rotation during startup then reads as "the files differ
@@ -144,14 +195,39 @@ impl TlsConfigSource {
         // here leaves the baseline unset, which makes the first successful tick
         // reconcile.
         let initial_fingerprint = fingerprint_credentials(tls_config).ok();
-        let server_config = load_server_config(tls_config)?;
-        Ok(Self {
+        let loaded = load_server_credentials(tls_config)?;
+        log_served_certificates(&loaded.served);
+        let source = Self {
             inner: Arc::new(TlsConfigSourceInner {
-                current: ArcSwap::new(server_config),
+                current: ArcSwap::new(Arc::clone(&loaded.server_config)),
                 origin: Some(tls_config.clone()),
                 initial_fingerprint,
+                sni: loaded.sni,
+                served: ArcSwap::from_pointee(loaded.served),
+                client_ca: ArcSwap::from_pointee(loaded.client_ca),
+                #[cfg(feature = "acme")]
+                acme: tls_config
+                    .acme
+                    .as_ref()
+                    .map(|_| crate::acme::AcmeChallenges::default()),
             }),
-        })
+        };
+        source.install(loaded.server_config);
+        Ok(source)
+    }
+
+    /// Make `server_config` the one new handshakes use.
+    ///
+    /// With ACME provisioning, the configuration is first wrapped so it also
+    /// answers TLS-ALPN-01 challenges; every load and reload goes through here
+    /// so none can install a configuration that does not.
+    fn install(&self, server_config: Arc<ServerConfig>) {
+        #[cfg(feature = "acme")]
+        let server_config = match self.inner.acme {
+            Some(ref challenges) => challenges.wrap_server_config(server_config),
+            None => server_config,
+        };
+        self.inner.current.store(server_config);
     }
 
     /// The configuration new handshakes currently use.
@@ -177,6 +253,38 @@ impl TlsConfigSource {
         self.inner.origin.as_ref()
     }
 
+    /// The certificates this source is serving, default first.
+    ///
+    /// One entry for the default `cert_path`, followed by one per
+    /// [`TlsConfig::certificates`] entry in configuration order. Each reports
+    /// the certificate currently installed, so after a partially failed reload
+    /// an entry that kept its previous certificate still shows that
+    /// certificate's expiry. Empty for a static source, which has no files to
+    /// describe.
+    #[must_use]
+    pub fn certificates(&self) -> Vec<ServedCertificate> {
+        (**self.inner.served.load()).clone()
+    }
+
+    /// The client-CA bundle new handshakes verify client certificates against.
+    ///
+    /// `None` when the listener does not request client certificates, and for a
+    /// static source, which has no files to describe. Like
+    /// [`certificates`](Self::certificates), this reports what is installed, so
+    /// a failed reload leaves it describing the previous bundle.
+    #[must_use]
+    pub fn client_ca(&self) -> Option<TrustedClientCa> {
+        (**self.inner.client_ca.load()).clone()
+    }
+
+    /// The ACME challenge responses this source answers, when its origin
+    /// provisions the certificate through ACME.
+    #[cfg(feature = "acme")]
+    #[must_use]
+    pub fn acme_challenges(&self) -> Option<&crate::acme::AcmeChallenges> {
+        self.inner.acme.as_ref()
+    }
+
     /// The fingerprint of the credential files at the moment this source loaded
     /// them, captured at build time.
     ///
@@ -221,9 +329,16 @@ impl TlsConfigSource {
             return Err(err);
         };
 
-        match load_server_config(origin) {
-            Ok(server_config) => {
-                self.inner.current.store(server_config);
+        if let Some(ref sni) = self.inner.sni {
+            return self.reload_sni(origin, sni);
+        }
+
+        match load_server_credentials(origin) {
+            Ok(loaded) => {
+                self.install(loaded.server_config);
+                log_served_certificates(&loaded.served);
+                self.inner.served.store(Arc::new(loaded.served));
+                self.inner.client_ca.store(Arc::new(loaded.client_ca));
                 tracing::info!(
                     cert_path = %origin.cert_path.display(),
                     key_path = %origin.key_path.display(),
@@ -244,6 +359,52 @@ impl TlsConfigSource {
             }
         }
     }
+
+    /// Reload a source serving SNI certificates, one certificate at a time.
+    ///
+    /// Each certificate is swapped in place inside the shared resolver, so one
+    /// entry's broken files cannot hold back another's rotation. The
+    /// `ServerConfig` is then rebuilt around the same resolver to pick up a
+    /// changed client-CA bundle; if that fails the previous one, which already
+    /// sees the new certificates, stays installed.
+    fn reload_sni(&self, origin: &TlsConfig, sni: &Arc<SniCertResolver>) -> Result<()> {
+        let mut failures = sni.reload();
+
+        match build_server_config(origin, ServerCertSelection::Resolver(Arc::clone(sni))) {
+            Ok(server_config) => {
+                self.install(server_config);
+                self.inner
+                    .client_ca
+                    .store(Arc::new(trusted_client_ca(origin)));
+            }
+            Err(e) => {
+                tracing::error!(
+                    error = %e,
+                    "TLS server configuration rebuild failed; continuing with the previous \
+                     client-authentication settings"
+                );
+                failures.push(e.to_string());
+            }
+        }
+
+        let served = sni.served();
+        log_served_certificates(&served);
+        self.inner.served.store(Arc::new(served));
+
+        if failures.is_empty() {
+            tracing::info!(
+                certificates = sni.len(),
+                "TLS credentials reloaded; new handshakes use the new certificates"
+            );
+            Ok(())
+        } else {
+            Err(crate::error::Error::Tls(format!(
+                "TLS credential reload partly failed; whatever failed keeps serving its \
+                 previous credentials: {}",
+                failures.join("; ")
+            )))
+        }
+    }
 }
 
 impl std::fmt::Debug for TlsConfigSource {
@@ -449,6 +610,10 @@ fn fingerprint_credentials(tls_config: &TlsConfig) -> std::io::Result<u64> {
     // Hash the paths as well as the bytes: a config edit that repoints at a
     // different file with identical contents is not a rotation, but a config
     // that swaps which of two files is authoritative should not alias.
+    let sni_paths = tls_config
+        .certificates
+        .iter()
+        .flat_map(|entry| [&entry.cert_path, &entry.key_path]);
     for path in [
         Some(&tls_config.cert_path),
         Some(&tls_config.key_path),
@@ -456,6 +621,8 @@ fn fingerprint_credentials(tls_config: &TlsConfig) -> std::io::Result<u64> {
     ]
     .into_iter()
     .flatten()
+    .chain(&tls_config.client_crl_paths)
+    .chain(sni_paths)
     {
         path.hash(&mut hasher);
         // Length-prefix each file so concatenation cannot forge equality
@@ -777,10 +944,10 @@ pub(crate) fn warn_if_reload_config_is_unusable(
 
 /// Start the config-driven rotation triggers for a set of resolved credentials.
 ///
-/// The single implementation of "what `reload_interval_secs` and
-/// `reload_on_sighup` actually do", shared by every serve path: the
-/// `ServiceBuilder`/`ActonService` paths and the plain [`crate::server::Server`]
-/// path. Keeping it in one place is what stops the two from drifting into
+/// The single implementation of "what `reload_interval_secs`,
+/// `reload_on_sighup` and `[tls.acme]` actually do", shared by every serve
+/// path: the `ServiceBuilder`/`ActonService` paths and the plain
+/// [`crate::server::Server`] path. Keeping it in one place is what stops the two from drifting into
 /// different rotation behaviour for the same config file.
 ///
 /// `http_interval` and `grpc_interval` are the already-validated periods for
@@ -808,6 +975,17 @@ pub(crate) fn install_reload_triggers(
         tasks.push(spawn_reload_poll(source, TlsListenerKind::Grpc, period));
     }
 
+    // ACME renewal. Not a trigger like the others, since it writes the files
+    // before reloading them, but it lives as long as the listeners for the
+    // same reason. One task per source, so an inherited gRPC source is
+    // renewed once.
+    #[cfg(feature = "acme")]
+    for (listener, source) in handle.sources() {
+        if source.acme_challenges().is_some() {
+            tasks.push(crate::acme::spawn_renewal(source, listener));
+        }
+    }
+
     // SIGHUP trigger: one handler for every listener.
     if sighup {
         if handle.is_empty() {
@@ -872,6 +1050,9 @@ pub struct TlsListener {
     /// The pump task, kept so `Drop` can abort it rather than let it outlive the
     /// listener holding the socket open.
     pump: Option<tokio::task::JoinHandle<()>>,
+    /// Where handshakes rejected for a revoked client certificate are reported.
+    #[cfg(feature = "audit")]
+    audit_logger: Option<crate::audit::AuditLogger>,
 }
 
 impl Drop for TlsListener {
@@ -899,6 +1080,7 @@ async fn handshake_pump(
     config_source: TlsConfigSource,
     handshake_timeout: Duration,
     tx: mpsc::Sender<(TlsStream<TcpStream>, SocketAddr)>,
+    #[cfg(feature = "audit")] audit_logger: Option<crate::audit::AuditLogger>,
 ) {
     loop {
         // Accept a TCP connection. Preserve the previous error behaviour: log
@@ -918,6 +1100,8 @@ async fn handshake_pump(
         // configuration, preserving the per-connection rotation semantics.
         let acceptor = TlsAcceptor::from(config_source.load());
         let tx = tx.clone();
+        #[cfg(feature = "audit")]
+        let audit_logger = audit_logger.clone();
 
         // Each handshake runs in its own task, bounded by the timeout, so a peer
         // that never sends a ClientHello occupies only its own task and cannot
@@ -929,9 +1113,30 @@ async fn handshake_pump(
                     // the listener was dropped — so this connection is moot.
                     let _ = tx.send((tls_stream, addr)).await;
                 }
-                Ok(Err(e)) => {
-                    tracing::warn!("TLS handshake failed from {}: {}", addr, e);
-                }
+                Ok(Err(e)) => match classify_handshake_error(&e) {
+                    Some(HandshakeRejection::RevokedClientCert) => {
+                        tracing::warn!(
+                            "TLS handshake from {} rejected: the client certificate has been \
+                             revoked",
+                            addr
+                        );
+                        #[cfg(feature = "audit")]
+                        if let Some(ref logger) = audit_logger {
+                            audit_revoked_client_cert(logger, addr).await;
+                        }
+                    }
+                    Some(HandshakeRejection::ExpiredCrl) => {
+                        tracing::error!(
+                            "TLS handshake from {} rejected: a client CRL is past its \
+                             nextUpdate, so revocation cannot be checked. Every client it \
+                             covers is rejected until a fresh CRL is loaded.",
+                            addr
+                        );
+                    }
+                    None => {
+                        tracing::warn!("TLS handshake failed from {}: {}", addr, e);
+                    }
+                },
                 Err(_elapsed) => {
                     tracing::warn!(
                         "TLS handshake from {} did not complete within {:?}; dropping the \
@@ -945,6 +1150,60 @@ async fn handshake_pump(
     }
 }
 
+/// A handshake failure whose cause deserves more than the generic log line.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum HandshakeRejection {
+    /// The client's certificate is listed on a configured CRL.
+    RevokedClientCert,
+    /// A configured CRL is past its `nextUpdate`, so the client's certificate
+    /// could not be checked and was rejected.
+    ExpiredCrl,
+}
+
+/// Recognise the handshake failures revocation checking produces.
+///
+/// tokio-rustls reports a rejected handshake as an [`io::Error`] wrapping the
+/// [`rustls::Error`](tokio_rustls::rustls::Error), so the cause survives a
+/// downcast.
+fn classify_handshake_error(err: &io::Error) -> Option<HandshakeRejection> {
+    use tokio_rustls::rustls::{CertificateError, Error as RustlsError};
+
+    match err.get_ref()?.downcast_ref::<RustlsError>()? {
+        RustlsError::InvalidCertificate(CertificateError::Revoked) => {
+            Some(HandshakeRejection::RevokedClientCert)
+        }
+        RustlsError::InvalidCertificate(
+            CertificateError::ExpiredRevocationList
+            | CertificateError::ExpiredRevocationListContext { .. },
+        ) => Some(HandshakeRejection::ExpiredCrl),
+        _ => None,
+    }
+}
+
+/// Record a handshake rejected because the client's certificate was revoked.
+///
+/// A revoked certificate still being presented means a credential that was
+/// deliberately withdrawn is in use, which is worth an audit trail entry and
+/// not just a log line.
+#[cfg(feature = "audit")]
+async fn audit_revoked_client_cert(logger: &crate::audit::AuditLogger, addr: SocketAddr) {
+    use crate::audit::{AuditEvent, AuditEventKind, AuditSeverity, AuditSource};
+
+    let event = AuditEvent::new(
+        AuditEventKind::TlsClientCertRevoked,
+        AuditSeverity::Warning,
+        logger.service_name().to_string(),
+    )
+    .with_source(AuditSource {
+        ip: Some(addr.ip().to_string()),
+        ..Default::default()
+    })
+    .with_metadata(serde_json::json!({
+        "remote_addr": addr.to_string(),
+    }));
+    logger.log(event).await;
+}
+
 impl TlsListener {
     /// Create a TLS listener serving one fixed server configuration.
     ///
@@ -973,6 +1232,8 @@ impl TlsListener {
             handshake_timeout: DEFAULT_HANDSHAKE_TIMEOUT,
             rx: None,
             pump: None,
+            #[cfg(feature = "audit")]
+            audit_logger: None,
         }
     }
 
@@ -987,6 +1248,19 @@ impl TlsListener {
         self
     }
 
+    /// Record handshakes rejected for a revoked client certificate in the
+    /// audit log.
+    ///
+    /// Only meaningful when `client_crl_paths` is configured; without CRLs no
+    /// certificate is ever found revoked. Revocations are logged at `WARN`
+    /// whether or not this is set.
+    #[cfg(feature = "audit")]
+    #[must_use]
+    pub fn with_audit_logger(mut self, logger: Option<crate::audit::AuditLogger>) -> Self {
+        self.audit_logger = logger;
+        self
+    }
+
     /// The credential source this listener hands to each new handshake.
     #[must_use]
     pub fn config_source(&self) -> &TlsConfigSource {
@@ -1010,6 +1284,8 @@ impl axum::serve::Listener for TlsListener {
                 self.config_source.clone(),
                 self.handshake_timeout,
                 tx,
+                #[cfg(feature = "audit")]
+                self.audit_logger.clone(),
             ));
             self.rx = Some(rx);
             self.pump = Some(pump);
@@ -1102,6 +1378,109 @@ pub(crate) fn load_root_store(path: &Path, role: &str) -> Result<RootCertStore>
     Ok(roots)
 }
 
+/// Load the certificate revocation lists used to check client certificates.
+///
+/// Every PEM-encoded CRL in every file is returned. Returns an error if a file
+/// cannot be opened, cannot be parsed, or contains no CRLs: a CRL file that
+/// silently loaded as nothing would turn revocation checking off.
+///
+/// A CRL whose `nextUpdate` has already passed still loads, and is logged at
+/// `ERROR`. The verifier [`build_client_verifier_with_crls`] builds rejects
+/// every certificate it covers until a fresh one replaces it, so loading it is
+/// what keeps the listener fail-closed rather than falling back to no check.
+pub fn load_client_crls(paths: &[PathBuf]) -> Result<Vec<CertificateRevocationListDer<'static>>> {
+    load_crls(paths, "client CRL")
+}
+
+/// Load certificate revocation lists from PEM files.
+///
+/// `role` names what the lists are for and appears verbatim in every error
+/// message, as with [`load_root_store`].
+pub(crate) fn load_crls(
+    paths: &[PathBuf],
+    role: &str,
+) -> Result<Vec<CertificateRevocationListDer<'static>>> {
+    use rustls_pki_types::pem::PemObject;
+
+    let mut crls = Vec::new();
+    for path in paths {
+        let file_crls: Vec<CertificateRevocationListDer<'static>> =
+            CertificateRevocationListDer::pem_file_iter(path)
+                .map_err(|e| {
+                    crate::error::Error::Tls(format!(
+                        "Failed to open {} file '{}': {}",
+                        role,
+                        path.display(),
+                        e
+                    ))
+                })?
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .map_err(|e| {
+                    crate::error::Error::Tls(format!(
+                        "Failed to parse {} from '{}': {}",
+                        role,
+                        path.display(),
+                        e
+                    ))
+                })?;
+
+        if file_crls.is_empty() {
+            return Err(crate::error::Error::Tls(format!(
+                "The {} file '{}' contains no revocation lists",
+                role,
+                path.display()
+            )));
+        }
+
+        for crl in &file_crls {
+            log_crl_freshness(crl, path, role);
+        }
+        crls.extend(file_crls);
+    }
+    Ok(crls)
+}
+
+/// Log when a CRL stops being current, at `ERROR` if it already has.
+///
+/// A CRL that fails to parse here is left for the verifier builder to reject
+/// with its own error; this only reports on ones that parse.
+fn log_crl_freshness(crl: &CertificateRevocationListDer<'_>, path: &Path, role: &str) {
+    use x509_parser::prelude::FromDer;
+    use x509_parser::revocation_list::CertificateRevocationList;
+
+    let Ok((_, parsed)) = CertificateRevocationList::from_der(crl.as_ref()) else {
+        return;
+    };
+    let Some(next_update) = parsed
+        .next_update()
+        .and_then(|t| DateTime::from_timestamp(t.timestamp(), 0))
+    else {
+        tracing::debug!(
+            path = %path.display(),
+            "{} has no nextUpdate; it never expires",
+            role
+        );
+        return;
+    };
+
+    if next_update <= Utc::now() {
+        tracing::error!(
+            path = %path.display(),
+            next_update = %next_update,
+            "{} has expired; every certificate it covers will be rejected until a \
+             fresh one is loaded",
+            role
+        );
+    } else {
+        tracing::info!(
+            path = %path.display(),
+            next_update = %next_update,
+            "loaded {}",
+            role
+        );
+    }
+}
+
 /// Build a rustls [`ClientCertVerifier`] from a set of trust anchors.
 ///
 /// When `optional` is `true`, a client certificate is requested but not
@@ -1111,6 +1490,21 @@ pub(crate) fn load_root_store(path: &Path, role: &str) -> Result<RootCertStore>
 pub fn build_client_verifier(
     roots: RootCertStore,
     optional: bool,
+) -> Result<Arc<dyn ClientCertVerifier>> {
+    build_client_verifier_with_crls(roots, Vec::new(), optional)
+}
+
+/// Build a rustls [`ClientCertVerifier`] that also checks revocation.
+///
+/// As [`build_client_verifier`], plus every certificate in the client's chain
+/// is checked against `crls`. The check fails closed: a certificate whose
+/// issuer has no CRL in `crls`, or whose CRL is past its `nextUpdate`, is
+/// rejected. An empty `crls` checks nothing and is the same as
+/// [`build_client_verifier`].
+pub fn build_client_verifier_with_crls(
+    roots: RootCertStore,
+    crls: Vec<CertificateRevocationListDer<'static>>,
+    optional: bool,
 ) -> Result<Arc<dyn ClientCertVerifier>> {
     // The crypto provider must be installed before the verifier builder runs,
     // for the same reason as `ServerConfig::builder()` below.
@@ -1120,6 +1514,12 @@ pub fn build_client_verifier(
     if optional {
         builder = builder.allow_unauthenticated();
     }
+    if !crls.is_empty() {
+        // rustls ignores `nextUpdate` unless told otherwise, which would let a
+        // CRL publisher that stopped publishing leave a stale list in force
+        // forever. Unknown revocation status is already an error by default.
+        builder = builder.with_crls(crls).enforce_revocation_expiration();
+    }
 
     builder.build().map_err(|e| {
         crate::error::Error::Tls(format!(
@@ -1136,41 +1536,70 @@ pub fn build_client_verifier(
 /// is configured for mutual TLS: client certificates are verified against that
 /// CA bundle (required unless [`TlsConfig::client_auth_optional`] is set).
 /// Otherwise no client authentication is requested.
+///
+/// When [`TlsConfig::certificates`] lists SNI certificates, the configuration
+/// picks one per connection from the hostname the client asks for, falling
+/// back to `cert_path` and `key_path`. Every listed certificate must load for
+/// the configuration to build.
 pub fn load_server_config(tls_config: &TlsConfig) -> Result<Arc<ServerConfig>> {
-    use rustls_pki_types::pem::PemObject;
-    use rustls_pki_types::PrivateKeyDer;
-    use tokio_rustls::rustls;
-
-    // Read certificate chain
-    let cert_chain: Vec<rustls::pki_types::CertificateDer<'static>> =
-        CertificateDer::pem_file_iter(&tls_config.cert_path)
-            .map_err(|e| {
-                crate::error::Error::Tls(format!(
-                    "Failed to open TLS cert file '{}': {}",
-                    tls_config.cert_path.display(),
-                    e
-                ))
-            })?
-            .collect::<std::result::Result<Vec<_>, _>>()
-            .map_err(|e| {
-                crate::error::Error::Tls(format!("Failed to parse TLS certificates: {}", e))
-            })?;
+    load_server_credentials(tls_config).map(|loaded| loaded.server_config)
+}
 
-    if cert_chain.is_empty() {
-        return Err(crate::error::Error::Tls(
-            "TLS cert file contains no certificates".to_string(),
-        ));
+/// A freshly loaded server configuration, with what it serves.
+struct LoadedServerCredentials {
+    server_config: Arc<ServerConfig>,
+    /// The resolver inside `server_config`, when it serves SNI certificates.
+    sni: Option<Arc<SniCertResolver>>,
+    served: Vec<ServedCertificate>,
+    client_ca: Option<TrustedClientCa>,
+}
+
+/// How a [`ServerConfig`] picks the certificate it presents.
+enum ServerCertSelection {
+    /// The same certificate for every connection.
+    Single(Vec<CertificateDer<'static>>, PrivateKeyDer<'static>),
+    /// A certificate chosen per connection from the client's SNI hostname.
+    Resolver(Arc<SniCertResolver>),
+}
+
+fn load_server_credentials(tls_config: &TlsConfig) -> Result<LoadedServerCredentials> {
+    if tls_config.certificates.is_empty() {
+        let cert_chain = read_cert_chain(&tls_config.cert_path)?;
+        let key = read_private_key(&tls_config.key_path)?;
+        let served = vec![ServedCertificate {
+            hostnames: Vec::new(),
+            cert_path: tls_config.cert_path.clone(),
+            not_after: leaf_not_after(&cert_chain),
+        }];
+        let server_config =
+            build_server_config(tls_config, ServerCertSelection::Single(cert_chain, key))?;
+        return Ok(LoadedServerCredentials {
+            server_config,
+            sni: None,
+            served,
+            client_ca: trusted_client_ca(tls_config),
+        });
     }
 
-    // Read private key (first PEM-encoded key found in the file)
-    let key = PrivateKeyDer::from_pem_file(&tls_config.key_path).map_err(|e| {
-        crate::error::Error::Tls(format!(
-            "Failed to parse TLS private key from '{}': {}",
-            tls_config.key_path.display(),
-            e
-        ))
-    })?;
+    let resolver = Arc::new(SniCertResolver::load(tls_config)?);
+    let server_config = build_server_config(
+        tls_config,
+        ServerCertSelection::Resolver(Arc::clone(&resolver)),
+    )?;
+    Ok(LoadedServerCredentials {
+        server_config,
+        served: resolver.served(),
+        sni: Some(resolver),
+        client_ca: trusted_client_ca(tls_config),
+    })
+}
 
+/// Build a [`ServerConfig`] around certificates that are already loaded,
+/// applying the client-authentication posture and ALPN from `tls_config`.
+fn build_server_config(
+    tls_config: &TlsConfig,
+    selection: ServerCertSelection,
+) -> Result<Arc<ServerConfig>> {
     // Install the chosen rustls crypto provider before any builder call.
     // Without this, `ServerConfig::builder()` panics when multiple providers
     // are compiled into the binary (common via transitive deps).
@@ -1180,10 +1609,12 @@ pub fn load_server_config(tls_config: &TlsConfig) -> Result<Arc<ServerConfig>> {
     // switches the listener into mutual-TLS mode; its absence preserves the
     // prior server-only behaviour.
     let builder = ServerConfig::builder();
-    let config = match tls_config.client_ca_path {
+    let builder = match tls_config.client_ca_path {
         Some(ref ca_path) => {
             let roots = load_client_ca_roots(ca_path)?;
-            let verifier = build_client_verifier(roots, tls_config.client_auth_optional)?;
+            let crls = load_client_crls(&tls_config.client_crl_paths)?;
+            let verifier =
+                build_client_verifier_with_crls(roots, crls, tls_config.client_auth_optional)?;
             builder.with_client_cert_verifier(verifier)
         }
         None => {
@@ -1200,13 +1631,29 @@ pub fn load_server_config(tls_config: &TlsConfig) -> Result<Arc<ServerConfig>> {
                      client_ca_path to enable mutual TLS, or remove client_auth_optional."
                 );
             }
+            // Likewise for CRLs: with no client certificate requested there is
+            // nothing to check them against.
+            if !tls_config.client_crl_paths.is_empty() {
+                tracing::warn!(
+                    "client_crl_paths has no effect without client_ca_path: no client \
+                     certificate is requested, so none is checked for revocation. Set \
+                     client_ca_path to enable mutual TLS, or remove client_crl_paths."
+                );
+            }
             builder.with_no_client_auth()
         }
-    }
-    .with_single_cert(cert_chain, key)
-    .map_err(|e| {
-        crate::error::Error::Tls(format!("Failed to build TLS server config: {}", e))
-    })?;
+    };
+
+    let mut config = match selection {
+        ServerCertSelection::Single(cert_chain, key) => {
+            builder.with_single_cert(cert_chain, key).map_err(|e| {
+                crate::error::Error::Tls(format!("Failed to build TLS server config: {}", e))
+            })?
+        }
+        // The resolver checked every key against its certificate when it
+        // loaded them, which is the check `with_single_cert` would otherwise do.
+        ServerCertSelection::Resolver(resolver) => builder.with_cert_resolver(resolver),
+    };
 
     // Advertise ALPN so the listener answers a client's protocol offer during
     // the handshake. Without this rustls selects nothing, and a strict gRPC
@@ -1228,12 +1675,440 @@ pub fn load_server_config(tls_config: &TlsConfig) -> Result<Arc<ServerConfig>> {
     // a build where nothing else supplies h2 — compiling `grpc` pulls the same
     // hyper-util feature in through tonic, which is why the CI leg that runs
     // those tests is `tls-no-grpc`.
-    let mut config = config;
     config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
 
     Ok(Arc::new(config))
 }
 
+/// Read a PEM certificate chain, leaf first.
+pub(crate) fn read_cert_chain(path: &Path) -> Result<Vec<CertificateDer<'static>>> {
+    use rustls_pki_types::pem::PemObject;
+
+    let cert_chain: Vec<CertificateDer<'static>> = CertificateDer::pem_file_iter(path)
+        .map_err(|e| {
+            crate::error::Error::Tls(format!(
+                "Failed to open TLS cert file '{}': {}",
+                path.display(),
+                e
+            ))
+        })?
+        .collect::<std::result::Result<Vec<_>, _>>()
+        .map_err(|e| {
+            crate::error::Error::Tls(format!(
+                "Failed to parse TLS certificates from '{}': {}",
+                path.display(),
+                e
+            ))
+        })?;
+
+    if cert_chain.is_empty() {
+        return Err(crate::error::Error::Tls(format!(
+            "TLS cert file '{}' contains no certificates",
+            path.display()
+        )));
+    }
+
+    Ok(cert_chain)
+}
+
+/// Read the first PEM-encoded private key in a file.
+pub(crate) fn read_private_key(path: &Path) -> Result<PrivateKeyDer<'static>> {
+    use rustls_pki_types::pem::PemObject;
+
+    PrivateKeyDer::from_pem_file(path).map_err(|e| {
+        crate::error::Error::Tls(format!(
+            "Failed to parse TLS private key from '{}': {}",
+            path.display(),
+            e
+        ))
+    })
+}
+
+/// The `notAfter` of a chain's leaf certificate.
+///
+/// `None` when the leaf does not parse as X.509. rustls serves certificate
+/// bytes without interpreting them, so an unparseable leaf is not a load
+/// failure here; it is just a certificate whose expiry cannot be reported.
+pub(crate) fn leaf_not_after(cert_chain: &[CertificateDer<'_>]) -> Option<DateTime<Utc>> {
+    use x509_parser::prelude::FromDer;
+    use x509_parser::prelude::X509Certificate;
+
+    let leaf = cert_chain.first()?;
+    let (_, cert) = X509Certificate::from_der(leaf.as_ref()).ok()?;
+    DateTime::from_timestamp(cert.validity().not_after.timestamp(), 0)
+}
+
+/// A certificate a TLS listener is serving, and when it expires.
+///
+/// Returned by [`TlsConfigSource::certificates`]: one for the default
+/// `cert_path`, and one per [`TlsConfig::certificates`] entry.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ServedCertificate {
+    /// The SNI hostname patterns that select this certificate, as configured.
+    /// Empty for the default certificate, which is served whenever no pattern
+    /// matches.
+    pub hostnames: Vec<String>,
+    /// The file the certificate chain was read from.
+    pub cert_path: PathBuf,
+    /// The leaf certificate's `notAfter`, or `None` if the leaf could not be
+    /// parsed as X.509.
+    pub not_after: Option<DateTime<Utc>>,
+}
+
+/// A client-CA bundle a mutual-TLS listener verifies against, and when it
+/// expires.
+///
+/// Returned by [`TlsConfigSource::client_ca`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TrustedClientCa {
+    /// The file the bundle was read from.
+    pub ca_path: PathBuf,
+    /// The earliest `notAfter` among the bundle's certificates, or `None` if
+    /// none of them parse as X.509. Once it passes, clients issued under that
+    /// CA stop verifying.
+    pub not_after: Option<DateTime<Utc>>,
+}
+
+/// Describe the client-CA bundle `tls_config` names, if it names one.
+///
+/// Called once the bundle has loaded into a verifier, so a read failure here
+/// means the file changed in between; the expiry is then reported as unknown
+/// rather than failing a load that already succeeded.
+fn trusted_client_ca(tls_config: &TlsConfig) -> Option<TrustedClientCa> {
+    use rustls_pki_types::pem::PemObject;
+    use x509_parser::prelude::FromDer;
+    use x509_parser::prelude::X509Certificate;
+
+    let ca_path = tls_config.client_ca_path.as_ref()?;
+    let not_after = CertificateDer::pem_file_iter(ca_path)
+        .ok()
+        .and_then(|certs| {
+            certs
+                .filter_map(|cert| cert.ok())
+                .filter_map(|cert| {
+                    let (_, parsed) = X509Certificate::from_der(cert.as_ref()).ok()?;
+                    DateTime::from_timestamp(parsed.validity().not_after.timestamp(), 0)
+                })
+                .min()
+        });
+    Some(TrustedClientCa {
+        ca_path: ca_path.clone(),
+        not_after,
+    })
+}
+
+/// Log what each served certificate is and when it expires.
+///
+/// An expired certificate is logged at `WARN`: rustls serves it without
+/// complaint, and every client rejects it.
+fn log_served_certificates(served: &[ServedCertificate]) {
+    let now = Utc::now();
+    for certificate in served {
+        match certificate.not_after {
+            Some(not_after) if not_after <= now => tracing::warn!(
+                cert_path = %certificate.cert_path.display(),
+                hostnames = ?certificate.hostnames,
+                not_after = %not_after,
+                "serving an expired TLS certificate; clients will reject it"
+            ),
+            Some(not_after) => tracing::info!(
+                cert_path = %certificate.cert_path.display(),
+                hostnames = ?certificate.hostnames,
+                not_after = %not_after,
+                days_remaining = (not_after - now).num_days(),
+                "serving TLS certificate"
+            ),
+            None => tracing::warn!(
+                cert_path = %certificate.cert_path.display(),
+                hostnames = ?certificate.hostnames,
+                "serving a TLS certificate whose expiry cannot be read: the leaf \
+                 certificate does not parse as X.509"
+            ),
+        }
+    }
+}
+
+/// One SNI hostname pattern from [`SniCertificateConfig::hostnames`](crate::config::SniCertificateConfig::hostnames).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+enum HostnamePattern {
+    /// A full hostname, lowercased.
+    Exact(String),
+    /// A `*.` wildcard, stored as the lowercased suffix after the `*` (leading
+    /// dot included).
+    Wildcard(String),
+}
+
+impl HostnamePattern {
+    /// Parse a configured pattern, returning why it is unusable on failure.
+    fn parse(pattern: &str) -> std::result::Result<Self, &'static str> {
+        // DNS names compare case-insensitively, and a trailing dot only marks
+        // the name as fully qualified.
+        let normalized = pattern.trim().trim_end_matches('.').to_ascii_lowercase();
+
+        if let Some(suffix) = normalized.strip_prefix('*') {
+            if !suffix.starts_with('.') || suffix.len() < 2 || suffix.contains('*') {
+                return Err("a wildcard must be `*.` followed by a domain");
+            }
+            return Ok(Self::Wildcard(suffix.to_string()));
+        }
+
+        if normalized.is_empty() {
+            return Err("the hostname is empty");
+        }
+        if normalized.contains('*') {
+            return Err("`*` is only allowed as the whole leftmost label");
+        }
+        Ok(Self::Exact(normalized))
+    }
+
+    /// Whether a normalized SNI hostname matches this pattern.
+    fn matches(&self, name: &str) -> bool {
+        match self {
+            Self::Exact(exact) => name == exact,
+            // Exactly one label, as certificate wildcards match.
+            Self::Wildcard(suffix) => name
+                .strip_suffix(suffix.as_str())
+                .is_some_and(|label| !label.is_empty() && !label.contains('.')),
+        }
+    }
+}
+
+/// A certificate and key ready to present, with the certificate's expiry.
+#[derive(Debug)]
+struct LoadedCertificate {
+    key: Arc<CertifiedKey>,
+    not_after: Option<DateTime<Utc>>,
+}
+
+impl LoadedCertificate {
+    /// Read a certificate chain and its private key, confirming the key
+    /// belongs to the leaf.
+    fn load(cert_path: &Path, key_path: &Path) -> Result<Self> {
+        let cert_chain = read_cert_chain(cert_path)?;
+        let key = read_private_key(key_path)?;
+        let not_after = leaf_not_after(&cert_chain);
+
+        let provider = default_crypto_provider()?;
+        let signing_key = provider.key_provider.load_private_key(key).map_err(|e| {
+            crate::error::Error::Tls(format!(
+                "TLS private key from '{}' is not usable by the configured crypto \
+                 provider: {}",
+                key_path.display(),
+                e
+            ))
+        })?;
+
+        let certified = CertifiedKey::new(cert_chain, signing_key);
+        certified.keys_match().map_err(|e| {
+            crate::error::Error::Tls(format!(
+                "TLS private key '{}' does not match the certificate in '{}': {}",
+                key_path.display(),
+                cert_path.display(),
+                e
+            ))
+        })?;
+
+        Ok(Self {
+            key: Arc::new(certified),
+            not_after,
+        })
+    }
+}
+
+/// The process-wide rustls crypto provider, installing it first if needed.
+///
+/// The provider supplies the private-key loader, so it must be installed
+/// before a key is loaded, for the same reason `ServerConfig::builder()`
+/// needs it.
+pub(crate) fn default_crypto_provider() -> Result<Arc<CryptoProvider>> {
+    crate::crypto::ensure_default_crypto_provider();
+
+    CryptoProvider::get_default().cloned().ok_or_else(|| {
+        crate::error::Error::Tls(
+            "No rustls crypto provider is installed; enable exactly one of the \
+             `crypto-aws-lc-rs` or `crypto-ring` features"
+                .to_string(),
+        )
+    })
+}
+
+/// One certificate an [`SniCertResolver`] can present, swappable in place.
+#[derive(Debug)]
+struct SniSlot {
+    /// The patterns as configured, for reporting.
+    hostnames: Vec<String>,
+    patterns: Vec<HostnamePattern>,
+    cert_path: PathBuf,
+    key_path: PathBuf,
+    current: ArcSwap<LoadedCertificate>,
+}
+
+impl SniSlot {
+    fn load(
+        hostnames: Vec<String>,
+        patterns: Vec<HostnamePattern>,
+        cert_path: &Path,
+        key_path: &Path,
+    ) -> Result<Self> {
+        Ok(Self {
+            hostnames,
+            patterns,
+            cert_path: cert_path.to_path_buf(),
+            key_path: key_path.to_path_buf(),
+            current: ArcSwap::from_pointee(LoadedCertificate::load(cert_path, key_path)?),
+        })
+    }
+
+    /// Reread this slot's files, keeping the installed certificate on failure.
+    fn reload(&self) -> Result<()> {
+        let loaded = LoadedCertificate::load(&self.cert_path, &self.key_path)?;
+        self.current.store(Arc::new(loaded));
+        Ok(())
+    }
+
+    fn served(&self) -> ServedCertificate {
+        ServedCertificate {
+            hostnames: self.hostnames.clone(),
+            cert_path: self.cert_path.clone(),
+            not_after: self.current.load().not_after,
+        }
+    }
+}
+
+/// Picks the certificate for each handshake from the client's SNI hostname.
+///
+/// An exact pattern beats a wildcard; a client that sends no SNI, or one that
+/// matches nothing, gets the default certificate. Each certificate sits behind
+/// its own [`ArcSwap`], so reloading one is an atomic store that neither
+/// blocks handshakes nor touches the others.
+#[derive(Debug)]
+struct SniCertResolver {
+    /// Served when no entry matches: `cert_path` and `key_path`.
+    default: SniSlot,
+    /// One per [`TlsConfig::certificates`] entry, in configuration order.
+    entries: Vec<SniSlot>,
+}
+
+impl SniCertResolver {
+    /// Load the default certificate and every SNI entry.
+    ///
+    /// Fails if any certificate fails to load, or if a hostname pattern is
+    /// malformed or claimed by more than one entry: with two candidates for
+    /// one name, which certificate a client gets would depend on list order.
+    fn load(tls_config: &TlsConfig) -> Result<Self> {
+        let default = SniSlot::load(
+            Vec::new(),
+            Vec::new(),
+            &tls_config.cert_path,
+            &tls_config.key_path,
+        )?;
+
+        let mut seen = std::collections::HashSet::new();
+        let mut entries = Vec::with_capacity(tls_config.certificates.len());
+        for (index, entry) in tls_config.certificates.iter().enumerate() {
+            if entry.hostnames.is_empty() {
+                return Err(crate::error::Error::Tls(format!(
+                    "TLS certificates[{}] ('{}') lists no hostnames, so no client \
+                     could ever be served it",
+                    index,
+                    entry.cert_path.display()
+                )));
+            }
+
+            let mut patterns = Vec::with_capacity(entry.hostnames.len());
+            for hostname in &entry.hostnames {
+                let pattern = HostnamePattern::parse(hostname).map_err(|reason| {
+                    crate::error::Error::Tls(format!(
+                        "Invalid hostname '{}' in TLS certificates[{}]: {}",
+                        hostname, index, reason
+                    ))
+                })?;
+                if !seen.insert(pattern.clone()) {
+                    return Err(crate::error::Error::Tls(format!(
+                        "Hostname '{}' in TLS certificates[{}] is listed more than once; \
+                         each hostname may select only one certificate",
+                        hostname, index
+                    )));
+                }
+                patterns.push(pattern);
+            }
+
+            entries.push(SniSlot::load(
+                entry.hostnames.clone(),
+                patterns,
+                &entry.cert_path,
+                &entry.key_path,
+            )?);
+        }
+
+        Ok(Self { default, entries })
+    }
+
+    /// The slot serving `server_name`.
+    fn select(&self, server_name: Option<&str>) -> &SniSlot {
+        let Some(name) = server_name else {
+            return &self.default;
+        };
+        let name = name.trim_end_matches('.').to_ascii_lowercase();
+
+        let matching = |exact: bool| {
+            self.entries.iter().find(|slot| {
+                slot.patterns.iter().any(|pattern| {
+                    matches!(pattern, HostnamePattern::Exact(_)) == exact && pattern.matches(&name)
+                })
+            })
+        };
+
+        matching(true)
+            .or_else(|| matching(false))
+            .unwrap_or(&self.default)
+    }
+
+    fn slots(&self) -> impl Iterator<Item = &SniSlot> {
+        std::iter::once(&self.default).chain(&self.entries)
+    }
+
+    /// The number of certificates, the default included.
+    fn len(&self) -> usize {
+        1 + self.entries.len()
+    }
+
+    /// Reread every certificate, each independently.
+    ///
+    /// Returns one message per certificate that failed; each of those keeps
+    /// serving what it served before and is logged at `ERROR`.
+    fn reload(&self) -> Vec<String> {
+        self.slots()
+            .filter_map(|slot| match slot.reload() {
+                Ok(()) => None,
+                Err(e) => {
+                    tracing::error!(
+                        cert_path = %slot.cert_path.display(),
+                        key_path = %slot.key_path.display(),
+                        hostnames = ?slot.hostnames,
+                        error = %e,
+                        "TLS certificate reload failed; continuing to serve the previous \
+                         certificate for these hostnames"
+                    );
+                    Some(e.to_string())
+                }
+            })
+            .collect()
+    }
+
+    /// What every slot is currently serving, default first.
+    fn served(&self) -> Vec<ServedCertificate> {
+        self.slots().map(SniSlot::served).collect()
+    }
+}
+
+impl ResolvesServerCert for SniCertResolver {
+    fn resolve(&self, client_hello: ClientHello<'_>) -> Option<Arc<CertifiedKey>> {
+        let slot = self.select(client_hello.server_name());
+        Some(Arc::clone(&slot.current.load().key))
+    }
+}
+
 /// A verified client certificate chain from a mutual-TLS handshake.
 ///
 /// The chain is ordered leaf-first: [`PeerCertificates::leaf`] returns the
@@ -1428,9 +2303,13 @@ mod tests {
                 key_path: key.to_path_buf(),
                 client_ca_path: None,
                 client_auth_optional: false,
+                client_crl_paths: Vec::new(),
                 reload_interval_secs: None,
                 reload_on_sighup: false,
                 handshake_timeout_secs: None,
+                expiry_warning_days: None,
+                certificates: Vec::new(),
+                acme: None,
             }
         }
 
@@ -1548,9 +2427,13 @@ mod tests {
             key_path: key_file.path().to_path_buf(),
             client_ca_path: None,
             client_auth_optional: false,
+            client_crl_paths: Vec::new(),
             reload_interval_secs: None,
             reload_on_sighup: false,
             handshake_timeout_secs: None,
+            expiry_warning_days: None,
+            certificates: Vec::new(),
+            acme: None,
         };
 
         load_server_config(&config).expect("server-only TLS config must build");
@@ -1570,9 +2453,13 @@ mod tests {
             key_path: key_file.path().to_path_buf(),
             client_ca_path: Some(ca_file.path().to_path_buf()),
             client_auth_optional: true,
+            client_crl_paths: Vec::new(),
             reload_interval_secs: None,
             reload_on_sighup: false,
             handshake_timeout_secs: None,
+            expiry_warning_days: None,
+            certificates: Vec::new(),
+            acme: None,
         };
 
         load_server_config(&config).expect("mutual-TLS config must build");
@@ -1590,9 +2477,13 @@ mod tests {
             key_path: key_file.path().to_path_buf(),
             client_ca_path: Some(PathBuf::from("/nonexistent/client-ca.pem")),
             client_auth_optional: false,
+            client_crl_paths: Vec::new(),
             reload_interval_secs: None,
             reload_on_sighup: false,
             handshake_timeout_secs: None,
+            expiry_warning_days: None,
+            certificates: Vec::new(),
+            acme: None,
         };
 
         load_server_config(&config)
@@ -1613,9 +2504,13 @@ mod tests {
             key_path,
             client_ca_path: None,
             client_auth_optional: false,
+            client_crl_paths: Vec::new(),
             reload_interval_secs: None,
             reload_on_sighup: false,
             handshake_timeout_secs: None,
+            expiry_warning_days: None,
+            certificates: Vec::new(),
+            acme: None,
         }
     }
 
@@ -1630,9 +2525,13 @@ mod tests {
             key_path: key_file.path().to_path_buf(),
             client_ca_path: None,
             client_auth_optional: false,
+            client_crl_paths: Vec::new(),
             reload_interval_secs: None,
             reload_on_sighup: false,
             handshake_timeout_secs: None,
+            expiry_warning_days: None,
+            certificates: Vec::new(),
+            acme: None,
         };
         let server_config = load_server_config(&config).expect("config builds");
 
@@ -1660,9 +2559,13 @@ mod tests {
             key_path: key_file.path().to_path_buf(),
             client_ca_path: None,
             client_auth_optional: false,
+            client_crl_paths: Vec::new(),
             reload_interval_secs: None,
             reload_on_sighup: false,
             handshake_timeout_secs: None,
+            expiry_warning_days: None,
+            certificates: Vec::new(),
+            acme: None,
         };
         let server_config = load_server_config(&config).expect("config builds");
         let source = TlsConfigSource::from_server_config(server_config.clone());
@@ -1853,6 +2756,339 @@ mod tests {
         );
     }
 
+    // --- SNI certificates -------------------------------------------------
+
+    /// Write a cert and key for one SNI entry under `dir`, named by `stem`.
+    fn write_sni_entry(
+        dir: &Path,
+        stem: &str,
+        hostnames: &[&str],
+        cert: &TestCert,
+    ) -> crate::config::SniCertificateConfig {
+        let cert_path = dir.join(format!("{stem}.pem"));
+        let key_path = dir.join(format!("{stem}.key"));
+        std::fs::write(&cert_path, &cert.cert_pem).expect("write cert");
+        std::fs::write(&key_path, &cert.key_pem).expect("write key");
+
+        crate::config::SniCertificateConfig {
+            hostnames: hostnames.iter().map(|h| h.to_string()).collect(),
+            cert_path,
+            key_path,
+        }
+    }
+
+    fn selected_leaf(resolver: &SniCertResolver, name: Option<&str>) -> CertificateDer<'static> {
+        resolver.select(name).current.load().key.cert[0].clone()
+    }
+
+    #[test]
+    fn hostname_patterns_match_exact_names_and_single_label_wildcards() {
+        let exact = HostnamePattern::parse("API.Example.com.").expect("exact pattern");
+        assert!(exact.matches("api.example.com"));
+        assert!(!exact.matches("www.example.com"));
+
+        let wildcard = HostnamePattern::parse("*.example.com").expect("wildcard pattern");
+        assert!(wildcard.matches("api.example.com"));
+        assert!(
+            !wildcard.matches("example.com"),
+            "a wildcard must not match the bare domain"
+        );
+        assert!(
+            !wildcard.matches("a.b.example.com"),
+            "a wildcard must match exactly one label"
+        );
+
+        for invalid in ["", "*", "*.", "api.*.com", "**.example.com", "*example.com"] {
+            assert!(
+                HostnamePattern::parse(invalid).is_err(),
+                "'{invalid}' must be rejected"
+            );
+        }
+    }
+
+    #[test]
+    fn sni_prefers_an_exact_name_over_a_wildcard_and_falls_back_to_the_default() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let default = generate_cert("localhost");
+        let wildcard = generate_cert("*.example.com");
+        let api = generate_cert("api.example.com");
+
+        let mut tls_config = write_credentials(dir.path(), &default);
+        // The wildcard is listed first: precedence must not depend on order.
+        tls_config.certificates = vec![
+            write_sni_entry(dir.path(), "wildcard", &["*.example.com"], &wildcard),
+            write_sni_entry(dir.path(), "api", &["api.example.com"], &api),
+        ];
+
+        let resolver = SniCertResolver::load(&tls_config).expect("resolver loads");
+
+        assert_eq!(selected_leaf(&resolver, Some("api.example.com")), api.der);
+        assert_eq!(selected_leaf(&resolver, Some("API.example.com")), api.der);
+        assert_eq!(
+            selected_leaf(&resolver, Some("www.example.com")),
+            wildcard.der
+        );
+        assert_eq!(selected_leaf(&resolver, Some("other.test")), default.der);
+        assert_eq!(
+            selected_leaf(&resolver, None),
+            default.der,
+            "a client that sends no SNI must get the default certificate"
+        );
+    }
+
+    #[test]
+    fn sni_rejects_a_hostname_claimed_twice_or_an_entry_without_hostnames() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let default = generate_cert("localhost");
+        let first = generate_cert("api.example.com");
+        let second = generate_cert("api.example.com");
+
+        let mut tls_config = write_credentials(dir.path(), &default);
+        tls_config.certificates = vec![
+            write_sni_entry(dir.path(), "first", &["api.example.com"], &first),
+            write_sni_entry(dir.path(), "second", &["API.example.com"], &second),
+        ];
+        let err = load_server_config(&tls_config).expect_err("a duplicate hostname must fail");
+        assert!(
+            err.to_string().contains("listed more than once"),
+            "the error must name the ambiguity: {err}"
+        );
+
+        tls_config.certificates = vec![write_sni_entry(dir.path(), "first", &[], &first)];
+        let err =
+            load_server_config(&tls_config).expect_err("an entry with no hostnames must fail");
+        assert!(
+            err.to_string().contains("lists no hostnames"),
+            "the error must explain the unreachable entry: {err}"
+        );
+    }
+
+    #[test]
+    fn sni_rejects_an_entry_whose_key_does_not_match_its_certificate() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let default = generate_cert("localhost");
+        let api = generate_cert("api.example.com");
+        let stranger = generate_cert("api.example.com");
+
+        let mut tls_config = write_credentials(dir.path(), &default);
+        let entry = write_sni_entry(dir.path(), "api", &["api.example.com"], &api);
+        std::fs::write(&entry.key_path, &stranger.key_pem).expect("mismatched key");
+        tls_config.certificates = vec![entry];
+
+        let err = load_server_config(&tls_config).expect_err("a mismatched pair must fail");
+        assert!(
+            matches!(err, crate::error::Error::Tls(_)),
+            "a mismatched pair is a configuration mistake, got {err:?}"
+        );
+    }
+
+    #[test]
+    fn a_failed_sni_entry_keeps_its_certificate_while_the_others_rotate() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let default = generate_cert("localhost");
+        let api = generate_cert("api.example.com");
+        let www = generate_cert("www.example.com");
+
+        let mut tls_config = write_credentials(dir.path(), &default);
+        tls_config.certificates = vec![
+            write_sni_entry(dir.path(), "api", &["api.example.com"], &api),
+            write_sni_entry(dir.path(), "www", &["www.example.com"], &www),
+        ];
+        let source = TlsConfigSource::from_tls_config(&tls_config).expect("initial load");
+        let resolver = source
+            .inner
+            .sni
+            .clone()
+            .expect("an SNI source keeps its resolver");
+
+        // Rotate `api` cleanly; leave `www` half-written.
+        let new_api = generate_cert("api.example.com");
+        std::fs::write(&tls_config.certificates[0].cert_path, &new_api.cert_pem)
+            .expect("rewrite api cert");
+        std::fs::write(&tls_config.certificates[0].key_path, &new_api.key_pem)
+            .expect("rewrite api key");
+        std::fs::write(
+            &tls_config.certificates[1].cert_path,
+            "-----BEGIN CERTIFICATE-----\ntruncated",
+        )
+        .expect("corrupt www cert");
+
+        let err = source
+            .reload()
+            .expect_err("a broken entry must fail the reload");
+        assert!(
+            err.to_string()
+                .contains(&tls_config.certificates[1].cert_path.display().to_string()),
+            "the error must name the entry that failed: {err}"
+        );
+
+        assert_eq!(
+            selected_leaf(&resolver, Some("api.example.com")),
+            new_api.der,
+            "the healthy entry must rotate despite its neighbour failing"
+        );
+        assert_eq!(
+            selected_leaf(&resolver, Some("www.example.com")),
+            www.der,
+            "the broken entry must keep serving its last-good certificate"
+        );
+        assert_eq!(selected_leaf(&resolver, None), default.der);
+
+        // The broken entry heals on the next reload.
+        let new_www = generate_cert("www.example.com");
+        std::fs::write(&tls_config.certificates[1].cert_path, &new_www.cert_pem)
+            .expect("rewrite www cert");
+        std::fs::write(&tls_config.certificates[1].key_path, &new_www.key_pem)
+            .expect("rewrite www key");
+        source.reload().expect("a fully valid reload must succeed");
+        assert_eq!(
+            selected_leaf(&resolver, Some("www.example.com")),
+            new_www.der
+        );
+    }
+
+    #[test]
+    fn certificates_reports_every_served_certificate_with_its_expiry() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let default = generate_cert("localhost");
+        let api = generate_cert("api.example.com");
+
+        let mut tls_config = write_credentials(dir.path(), &default);
+        let single = TlsConfigSource::from_tls_config(&tls_config).expect("single-cert load");
+        let served = single.certificates();
+        assert_eq!(
+            served.len(),
+            1,
+            "a single-cert source serves one certificate"
+        );
+        assert!(served[0].hostnames.is_empty());
+        assert_eq!(served[0].cert_path, tls_config.cert_path);
+        assert!(
+            served[0].not_after.is_some_and(|t| t > Utc::now()),
+            "the expiry of a freshly generated certificate must be readable and in the future"
+        );
+
+        tls_config.certificates = vec![write_sni_entry(
+            dir.path(),
+            "api",
+            &["api.example.com"],
+            &api,
+        )];
+        let sni = TlsConfigSource::from_tls_config(&tls_config).expect("SNI load");
+        let served = sni.certificates();
+        assert_eq!(served.len(), 2, "the default plus one SNI entry");
+        assert!(
+            served[0].hostnames.is_empty(),
+            "the default is listed first"
+        );
+        assert_eq!(served[1].hostnames, vec!["api.example.com".to_string()]);
+        assert_eq!(served[1].cert_path, tls_config.certificates[0].cert_path);
+        assert!(served[1].not_after.is_some());
+
+        let server_config = load_server_config(&tls_config).expect("config builds");
+        assert!(
+            TlsConfigSource::from_server_config(server_config)
+                .certificates()
+                .is_empty(),
+            "a static source has no files to describe"
+        );
+    }
+
+    #[test]
+    fn fingerprint_covers_the_sni_certificates() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let default = generate_cert("localhost");
+        let api = generate_cert("api.example.com");
+
+        let mut tls_config = write_credentials(dir.path(), &default);
+        tls_config.certificates = vec![write_sni_entry(
+            dir.path(),
+            "api",
+            &["api.example.com"],
+            &api,
+        )];
+        let before = fingerprint_credentials(&tls_config).expect("fingerprint");
+
+        let rotated = generate_cert("api.example.com");
+        std::fs::write(&tls_config.certificates[0].cert_path, &rotated.cert_pem)
+            .expect("rewrite api cert");
+
+        assert_ne!(
+            fingerprint_credentials(&tls_config).expect("fingerprint"),
+            before,
+            "rotating only an SNI certificate must still read as a change"
+        );
+    }
+
+    /// End to end through a real handshake: the certificate the client receives
+    /// is the one its SNI asked for.
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn a_handshake_is_served_the_certificate_its_sni_selects() {
+        use axum::serve::Listener as _;
+
+        crate::crypto::ensure_default_crypto_provider();
+
+        let dir = tempfile::tempdir().expect("temp dir");
+        let default = generate_cert("localhost");
+        let api = generate_cert("api.example.com");
+        let mut tls_config = write_credentials(dir.path(), &default);
+        tls_config.certificates = vec![write_sni_entry(
+            dir.path(),
+            "api",
+            &["api.example.com"],
+            &api,
+        )];
+        let source = TlsConfigSource::from_tls_config(&tls_config).expect("server config loads");
+
+        let tcp = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind ephemeral port");
+        let addr = tcp.local_addr().expect("local addr");
+        let mut listener = TlsListener::with_config_source(tcp, source);
+
+        let provider = tokio_rustls::rustls::crypto::CryptoProvider::get_default()
+            .expect("a crypto provider is installed")
+            .clone();
+        let client_config = Arc::new(
+            tokio_rustls::rustls::ClientConfig::builder()
+                .dangerous()
+                .with_custom_certificate_verifier(Arc::new(AcceptAnyServerCert { provider }))
+                .with_no_client_auth(),
+        );
+
+        for (server_name, expected) in [("api.example.com", &api.der), ("localhost", &default.der)]
+        {
+            let connector = tokio_rustls::TlsConnector::from(Arc::clone(&client_config));
+            let client = tokio::spawn(async move {
+                let stream = tokio::net::TcpStream::connect(addr)
+                    .await
+                    .expect("the client connects");
+                let tls = connector
+                    .connect(
+                        rustls_pki_types::ServerName::try_from(server_name).expect("valid name"),
+                        stream,
+                    )
+                    .await
+                    .expect("the handshake completes");
+                tls.get_ref()
+                    .1
+                    .peer_certificates()
+                    .expect("the server presented a certificate")[0]
+                    .clone()
+            });
+
+            let _accepted = tokio::time::timeout(Duration::from_secs(5), listener.accept())
+                .await
+                .expect("the handshake is delivered");
+            let presented = client.await.expect("the client task must not panic");
+
+            assert_eq!(
+                &presented, expected,
+                "SNI '{server_name}' must be served its own certificate"
+            );
+        }
+    }
+
     // --- Poll-driven rotation -------------------------------------------
     //
     // The tick logic is exercised directly rather than through
@@ -2001,9 +3237,13 @@ mod tests {
             key_path: key_file.path().to_path_buf(),
             client_ca_path: None,
             client_auth_optional: false,
+            client_crl_paths: Vec::new(),
             reload_interval_secs: None,
             reload_on_sighup: false,
             handshake_timeout_secs: None,
+            expiry_warning_days: None,
+            certificates: Vec::new(),
+            acme: None,
         };
         let source =
             TlsConfigSource::from_server_config(load_server_config(&config).expect("config"));
@@ -2094,6 +3334,279 @@ mod tests {
         );
     }
 
+    #[test]
+    fn fingerprint_covers_the_client_crls() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let server = generate_cert("localhost");
+        let mut tls_config = write_credentials(dir.path(), &server);
+        let crl_path = dir.path().join("client.crl");
+        std::fs::write(&crl_path, "crl one").expect("write crl");
+        tls_config.client_crl_paths = vec![crl_path.clone()];
+
+        let before = fingerprint_credentials(&tls_config).expect("fingerprint");
+
+        // A CA publishing a fresh CRL is a rotation too: it changes which
+        // clients are accepted, and an expired list must be replaceable
+        // without a restart.
+        std::fs::write(&crl_path, "crl two").expect("rewrite crl");
+
+        assert_ne!(
+            before,
+            fingerprint_credentials(&tls_config).expect("fingerprint"),
+            "a changed client CRL must be detected as a rotation"
+        );
+    }
+
+    // --- Client certificate revocation ----------------------------------
+
+    /// A client CA, a client certificate it issued, and a CRL from that CA.
+    struct TestClientPki {
+        ca_pem: String,
+        client_pem: String,
+        client_key_pem: String,
+        crl_pem: String,
+    }
+
+    /// Issue a client certificate under a fresh CA, plus a CRL that lists it
+    /// when `revoke_client` is set and whose `nextUpdate` has passed when
+    /// `crl_expired` is set.
+    fn generate_client_pki(revoke_client: bool, crl_expired: bool) -> TestClientPki {
+        use rcgen::{
+            date_time_ymd, BasicConstraints, CertificateParams, CertificateRevocationListParams,
+            DistinguishedName, DnType, ExtendedKeyUsagePurpose, IsCa, Issuer, KeyIdMethod, KeyPair,
+            KeyUsagePurpose, RevocationReason, RevokedCertParams, SerialNumber,
+        };
+
+        const CLIENT_SERIAL: u64 = 4242;
+
+        let ca_key = KeyPair::generate().expect("ca key");
+        let mut ca_params = CertificateParams::new(Vec::new()).expect("ca params");
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        ca_params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::CrlSign];
+        let mut ca_dn = DistinguishedName::new();
+        ca_dn.push(DnType::CommonName, "acton-service test client CA");
+        ca_params.distinguished_name = ca_dn;
+        let ca_cert = ca_params.self_signed(&ca_key).expect("self-signed ca");
+        let issuer = Issuer::new(ca_params, ca_key);
+
+        let client_key = KeyPair::generate().expect("client key");
+        let mut client_params = CertificateParams::new(Vec::new()).expect("client params");
+        client_params.serial_number = Some(SerialNumber::from(CLIENT_SERIAL));
+        client_params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ClientAuth];
+        let mut client_dn = DistinguishedName::new();
+        client_dn.push(DnType::CommonName, "acton-service test client");
+        client_params.distinguished_name = client_dn;
+        let client_cert = client_params
+            .signed_by(&client_key, &issuer)
+            .expect("client signed by ca");
+
+        let next_update = if crl_expired {
+            date_time_ymd(2021, 1, 1)
+        } else {
+            date_time_ymd(2099, 1, 1)
+        };
+        let revoked_certs = if revoke_client {
+            vec![RevokedCertParams {
+                serial_number: SerialNumber::from(CLIENT_SERIAL),
+                revocation_time: date_time_ymd(2020, 6, 1),
+                reason_code: Some(RevocationReason::KeyCompromise),
+                invalidity_date: None,
+            }]
+        } else {
+            Vec::new()
+        };
+        let crl = CertificateRevocationListParams {
+            this_update: date_time_ymd(2020, 1, 1),
+            next_update,
+            crl_number: SerialNumber::from(1u64),
+            issuing_distribution_point: None,
+            revoked_certs,
+            key_identifier_method: KeyIdMethod::Sha256,
+        }
+        .signed_by(&issuer)
+        .expect("crl signed by ca");
+
+        TestClientPki {
+            ca_pem: ca_cert.pem(),
+            client_pem: client_cert.pem(),
+            client_key_pem: client_key.serialize_pem(),
+            crl_pem: crl.pem().expect("crl pem"),
+        }
+    }
+
+    /// Run one handshake from a client presenting `pki`'s certificate against a
+    /// server trusting `pki`'s CA and CRL, returning the server's outcome.
+    async fn server_handshake_with_client_crl(pki: &TestClientPki) -> io::Result<()> {
+        use rustls_pki_types::pem::PemObject;
+
+        crate::crypto::ensure_default_crypto_provider();
+
+        let dir = tempfile::tempdir().expect("temp dir");
+        let mut tls_config = write_credentials(dir.path(), &generate_cert("localhost"));
+        let ca_path = dir.path().join("client-ca.pem");
+        std::fs::write(&ca_path, &pki.ca_pem).expect("write ca");
+        let crl_path = dir.path().join("client.crl");
+        std::fs::write(&crl_path, &pki.crl_pem).expect("write crl");
+        tls_config.client_ca_path = Some(ca_path);
+        tls_config.client_crl_paths = vec![crl_path];
+
+        let source = TlsConfigSource::from_tls_config(&tls_config).expect("server config loads");
+        let acceptor = TlsAcceptor::from(source.load());
+
+        let tcp = TcpListener::bind("127.0.0.1:0")
+            .await
+            .expect("bind ephemeral port");
+        let addr = tcp.local_addr().expect("local addr");
+
+        let chain: Vec<CertificateDer<'static>> =
+            CertificateDer::pem_slice_iter(pki.client_pem.as_bytes())
+                .collect::<std::result::Result<Vec<_>, _>>()
+                .expect("client chain parses");
+        let key = PrivateKeyDer::from_pem_slice(pki.client_key_pem.as_bytes())
+            .expect("client key parses");
+        let provider = tokio_rustls::rustls::crypto::CryptoProvider::get_default()
+            .expect("a crypto provider is installed")
+            .clone();
+        let client_config = tokio_rustls::rustls::ClientConfig::builder()
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(AcceptAnyServerCert { provider }))
+            .with_client_auth_cert(chain, key)
+            .expect("client config");
+
+        // The client's own outcome is not the subject: under TLS 1.3 it can
+        // finish before the server has judged its certificate.
+        let client = tokio::spawn(async move {
+            let stream = TcpStream::connect(addr).await.expect("connect");
+            let _ = tokio_rustls::TlsConnector::from(Arc::new(client_config))
+                .connect(
+                    rustls_pki_types::ServerName::try_from("localhost").expect("valid name"),
+                    stream,
+                )
+                .await;
+        });
+
+        let (stream, _) = tcp.accept().await.expect("accept");
+        let outcome = acceptor.accept(stream).await.map(|_| ());
+        client.await.expect("the client task must not panic");
+        outcome
+    }
+
+    #[test]
+    fn load_client_crls_rejects_a_missing_file() {
+        let err = load_client_crls(&[PathBuf::from("/nonexistent/client.crl")])
+            .expect_err("a missing CRL must be an error, not an unchecked listener");
+
+        assert!(
+            err.to_string().contains("Failed to open client CRL file"),
+            "error must name the failure to open the file: {err}"
+        );
+    }
+
+    #[test]
+    fn load_client_crls_rejects_a_file_without_revocation_lists() {
+        let file = write_temp("# no revocation lists here\n");
+
+        let err = load_client_crls(&[file.path().to_path_buf()])
+            .expect_err("a CRL file with no lists must be an error");
+
+        assert!(
+            err.to_string().contains("contains no revocation lists"),
+            "error must explain that the file is empty: {err}"
+        );
+    }
+
+    #[test]
+    fn load_client_crls_accepts_an_expired_list_so_the_handshake_can_fail_closed() {
+        let pki = generate_client_pki(false, true);
+        let file = write_temp(&pki.crl_pem);
+
+        let crls = load_client_crls(&[file.path().to_path_buf()])
+            .expect("an expired CRL still loads; the verifier refuses it per handshake");
+
+        assert_eq!(crls.len(), 1);
+    }
+
+    #[test]
+    fn classify_handshake_error_recognises_revocation_failures() {
+        use tokio_rustls::rustls::{CertificateError, Error as RustlsError};
+
+        let wrap = |err: RustlsError| io::Error::new(io::ErrorKind::InvalidData, err);
+
+        assert_eq!(
+            classify_handshake_error(&wrap(RustlsError::InvalidCertificate(
+                CertificateError::Revoked
+            ))),
+            Some(HandshakeRejection::RevokedClientCert)
+        );
+        assert_eq!(
+            classify_handshake_error(&wrap(RustlsError::InvalidCertificate(
+                CertificateError::ExpiredRevocationList
+            ))),
+            Some(HandshakeRejection::ExpiredCrl)
+        );
+        assert_eq!(
+            classify_handshake_error(&wrap(RustlsError::InvalidCertificate(
+                CertificateError::UnknownIssuer
+            ))),
+            None
+        );
+        assert_eq!(
+            classify_handshake_error(&io::Error::from(io::ErrorKind::UnexpectedEof)),
+            None
+        );
+    }
+
+    #[tokio::test]
+    async fn a_client_absent_from_the_crl_is_accepted() {
+        let pki = generate_client_pki(false, false);
+
+        server_handshake_with_client_crl(&pki)
+            .await
+            .expect("a client the CRL does not list must complete the handshake");
+    }
+
+    #[tokio::test]
+    async fn a_revoked_client_certificate_is_rejected() {
+        let pki = generate_client_pki(true, false);
+
+        let err = server_handshake_with_client_crl(&pki)
+            .await
+            .expect_err("a revoked client certificate must fail the handshake");
+
+        assert_eq!(
+            classify_handshake_error(&err),
+            Some(HandshakeRejection::RevokedClientCert),
+            "the rejection must be recognisable as a revocation: {err}"
+        );
+    }
+
+    #[tokio::test]
+    async fn an_expired_crl_fails_closed() {
+        let pki = generate_client_pki(false, true);
+
+        let err = server_handshake_with_client_crl(&pki)
+            .await
+            .expect_err("a CRL past its nextUpdate must not be trusted");
+
+        assert_eq!(
+            classify_handshake_error(&err),
+            Some(HandshakeRejection::ExpiredCrl),
+            "the rejection must be recognisable as a stale CRL: {err}"
+        );
+    }
+
+    #[test]
+    fn client_crls_without_a_client_ca_still_build() {
+        let dir = tempfile::tempdir().expect("temp dir");
+        let server = generate_cert("localhost");
+        let mut tls_config = write_credentials(dir.path(), &server);
+        tls_config.client_crl_paths = vec![PathBuf::from("/nonexistent/client.crl")];
+
+        // Nothing asks for a client certificate, so there is nothing to check
+        // against the lists; the misconfiguration is logged, not fatal.
+        load_server_config(&tls_config).expect("CRLs are ignored without a client CA");
+    }
+
     // --- The reload handle ------------------------------------------------
 
     #[test]
@@ -2618,9 +4131,13 @@ mod tests {
             key_path: key_file.path().to_path_buf(),
             client_ca_path: None,
             client_auth_optional: true,
+            client_crl_paths: Vec::new(),
             reload_interval_secs: None,
             reload_on_sighup: false,
             handshake_timeout_secs: None,
+            expiry_warning_days: None,
+            certificates: Vec::new(),
+            acme: None,
         };
 
         load_server_config(&config)
@@ -0,0 +1,730 @@
+//! ACME provisioning end to end, against a Pebble-like directory in-process.
+//!
+//! The stub speaks enough RFC 8555 to issue a certificate: a directory,
+//! nonces, account registration, orders, authorizations, challenges,
+//! finalization and download. It checks what a real CA checks — every JWS
+//! signature and nonce, and each challenge by connecting back to the service
+//! the way Pebble does: a `acme-tls/1` handshake for `tls-alpn-01`, an HTTP
+//! fetch of the token for `http-01`. Only DNS is faked: every domain resolves
+//! to the service's loopback listener.
+//!
+//! The service side is the crate's own: a `TlsConfigSource` with `[tls.acme]`,
+//! served by `TlsListener` with `acme::http01_router` merged in, and renewed by
+//! `acme::renew_if_due`. TLS material follows `tls_alpn_http2.rs` (rcgen +
+//! tempfile).
+
+#![cfg(feature = "acme")]
+
+use std::collections::HashSet;
+use std::io::Write as _;
+use std::net::SocketAddr;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use acton_service::acme::{self, ACME_TLS_ALPN_PROTOCOL};
+use acton_service::config::{AcmeChallenge, AcmeConfig, TlsConfig};
+use acton_service::tls::{load_server_config, TlsConfigSource, TlsListener};
+use axum::body::Bytes;
+use axum::extract::{Path as UrlPath, State};
+use axum::http::{HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::Router;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use p256::ecdsa::signature::Verifier;
+use p256::ecdsa::{Signature, VerifyingKey};
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::{DigitallySignedStruct, SignatureScheme};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tokio::net::TcpListener;
+
+/// The only name the tests request; the stub "resolves" it to the service.
+const DOMAIN: &str = "localhost";
+
+/// RFC 8737's `id-pe-acmeIdentifier`.
+const ACME_IDENTIFIER_OID: &str = "1.3.6.1.5.5.7.1.31";
+
+fn write_temp(contents: &str) -> tempfile::NamedTempFile {
+    let mut file = tempfile::NamedTempFile::new().expect("temp file");
+    file.write_all(contents.as_bytes()).expect("write temp");
+    file.flush().expect("flush temp");
+    file
+}
+
+// ---------------------------------------------------------------------------
+// The stub directory
+// ---------------------------------------------------------------------------
+
+struct Stub {
+    base: String,
+    /// Where validations connect: the service under test.
+    service_addr: SocketAddr,
+    issuer: rcgen::Issuer<'static, rcgen::KeyPair>,
+    issuer_pem: String,
+    state: Mutex<StubState>,
+}
+
+#[derive(Default)]
+struct StubState {
+    next_nonce: u64,
+    nonces: HashSet<String>,
+    /// Registered account keys; the account URL is `/account/<index>`.
+    accounts: Vec<Value>,
+    orders: Vec<StubOrder>,
+    /// The challenge types the service asked the stub to validate.
+    validated: Vec<String>,
+}
+
+struct StubOrder {
+    account: usize,
+    domains: Vec<String>,
+    tokens: Vec<String>,
+    authz_valid: Vec<Option<bool>>,
+    certificate: Option<String>,
+}
+
+impl StubOrder {
+    fn status(&self) -> &'static str {
+        if self.certificate.is_some() {
+            "valid"
+        } else if self.authz_valid.contains(&Some(false)) {
+            "invalid"
+        } else if self.authz_valid.iter().all(|v| *v == Some(true)) {
+            "ready"
+        } else {
+            "pending"
+        }
+    }
+}
+
+impl Stub {
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base, path)
+    }
+
+    fn nonce(&self) -> String {
+        let mut state = self.state.lock().unwrap();
+        state.next_nonce += 1;
+        let nonce = format!("nonce-{}", state.next_nonce);
+        state.nonces.insert(nonce.clone());
+        nonce
+    }
+
+    fn reply(&self, status: StatusCode, location: Option<String>, body: Value) -> Response {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            "replay-nonce",
+            HeaderValue::from_str(&self.nonce()).unwrap(),
+        );
+        if let Some(location) = location {
+            headers.insert("location", HeaderValue::from_str(&location).unwrap());
+        }
+        (status, headers, body.to_string()).into_response()
+    }
+
+    fn problem(&self, kind: &str, detail: &str) -> Response {
+        self.reply(
+            StatusCode::BAD_REQUEST,
+            None,
+            json!({ "type": format!("urn:ietf:params:acme:error:{kind}"), "detail": detail }),
+        )
+    }
+
+    /// Check a JWS the way a CA must: a nonce it issued and has not seen
+    /// spent, the URL it was posted to, and a valid ES256 signature by the
+    /// key in `jwk` or the account named by `kid`.
+    ///
+    /// Returns the account index (if any) and the decoded payload, `Null` for
+    /// a POST-as-GET.
+    fn verify(&self, body: &[u8], path: &str) -> Result<(Option<usize>, Value), Box<Response>> {
+        let jws: Value = serde_json::from_slice(body)
+            .map_err(|_| Box::new(self.problem("malformed", "body")))?;
+        let field = |name: &str| jws[name].as_str().unwrap_or_default().to_string();
+        let (protected_b64, payload_b64, signature_b64) =
+            (field("protected"), field("payload"), field("signature"));
+        let protected: Value = URL_SAFE_NO_PAD
+            .decode(&protected_b64)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .ok_or_else(|| Box::new(self.problem("malformed", "protected header")))?;
+
+        let nonce = protected["nonce"].as_str().unwrap_or_default();
+        if !self.state.lock().unwrap().nonces.remove(nonce) {
+            return Err(Box::new(self.problem("badNonce", "unknown or spent nonce")));
+        }
+        assert_eq!(protected["alg"], "ES256");
+        assert_eq!(
+            protected["url"],
+            self.url(path),
+            "JWS url must match the request"
+        );
+
+        let (account, jwk) = match protected["kid"].as_str() {
+            Some(kid) => {
+                let index: usize = kid
+                    .rsplit('/')
+                    .next()
+                    .and_then(|i| i.parse().ok())
+                    .expect("kid is an account URL");
+                let jwk = self.state.lock().unwrap().accounts[index].clone();
+                (Some(index), jwk)
+            }
+            None => (None, protected["jwk"].clone()),
+        };
+
+        let coordinate = |name: &str| {
+            URL_SAFE_NO_PAD
+                .decode(jwk[name].as_str().expect("coordinate"))
+                .expect("base64url coordinate")
+        };
+        let mut sec1 = vec![0x04];
+        sec1.extend(coordinate("x"));
+        sec1.extend(coordinate("y"));
+        let key = VerifyingKey::from_sec1_bytes(&sec1).expect("P-256 public key");
+        let signature = URL_SAFE_NO_PAD
+            .decode(&signature_b64)
+            .ok()
+            .and_then(|bytes| Signature::from_slice(&bytes).ok())
+            .ok_or_else(|| Box::new(self.problem("malformed", "signature")))?;
+        key.verify(
+            format!("{protected_b64}.{payload_b64}").as_bytes(),
+            &signature,
+        )
+        .map_err(|_| Box::new(self.problem("unauthorized", "signature does not verify")))?;
+
+        let payload = if payload_b64.is_empty() {
+            Value::Null
+        } else {
+            let bytes = URL_SAFE_NO_PAD.decode(&payload_b64).expect("payload");
+            serde_json::from_slice(&bytes).expect("payload json")
+        };
+        Ok((account, payload))
+    }
+
+    fn key_authorization(&self, account: usize, token: &str) -> String {
+        let jwk = self.state.lock().unwrap().accounts[account].clone();
+        let canonical = format!(
+            r#"{{"crv":"P-256","kty":"EC","x":"{}","y":"{}"}}"#,
+            jwk["x"].as_str().unwrap(),
+            jwk["y"].as_str().unwrap()
+        );
+        let thumbprint = URL_SAFE_NO_PAD.encode(Sha256::digest(canonical.as_bytes()));
+        format!("{token}.{thumbprint}")
+    }
+
+    fn order_json(&self, id: usize) -> Value {
+        let state = self.state.lock().unwrap();
+        let order = &state.orders[id];
+        json!({
+            "status": order.status(),
+            "identifiers": order.domains.iter().map(|d| json!({"type": "dns", "value": d})).collect::<Vec<_>>(),
+            "authorizations": (0..order.domains.len()).map(|i| self.url(&format!("/authz/{id}/{i}"))).collect::<Vec<_>>(),
+            "finalize": self.url(&format!("/finalize/{id}")),
+            "certificate": order.certificate.as_ref().map(|_| self.url(&format!("/cert/{id}"))),
+        })
+    }
+
+    fn authz_json(&self, id: usize, index: usize) -> Value {
+        let state = self.state.lock().unwrap();
+        let order = &state.orders[id];
+        let status = match order.authz_valid[index] {
+            None => "pending",
+            Some(true) => "valid",
+            Some(false) => "invalid",
+        };
+        let token = &order.tokens[index];
+        let challenge = |kind: &str| {
+            json!({
+                "type": kind,
+                "url": self.url(&format!("/chall/{id}/{index}/{kind}")),
+                "token": token,
+                "status": status,
+            })
+        };
+        json!({
+            "status": status,
+            "identifier": { "type": "dns", "value": order.domains[index] },
+            "challenges": [challenge("http-01"), challenge("tls-alpn-01")],
+        })
+    }
+}
+
+async fn directory(State(stub): State<Arc<Stub>>) -> Response {
+    axum::Json(json!({
+        "newNonce": stub.url("/nonce"),
+        "newAccount": stub.url("/account"),
+        "newOrder": stub.url("/order"),
+    }))
+    .into_response()
+}
+
+async fn new_nonce(State(stub): State<Arc<Stub>>) -> Response {
+    stub.reply(StatusCode::OK, None, Value::Null)
+}
+
+async fn new_account(State(stub): State<Arc<Stub>>, body: Bytes) -> Response {
+    let (_, payload) = match stub.verify(&body, "/account") {
+        Ok(verified) => verified,
+        Err(problem) => return *problem,
+    };
+    assert_eq!(payload["termsOfServiceAgreed"], true);
+
+    let jws: Value = serde_json::from_slice(&body).unwrap();
+    let protected: Value = serde_json::from_slice(
+        &URL_SAFE_NO_PAD
+            .decode(jws["protected"].as_str().unwrap())
+            .unwrap(),
+    )
+    .unwrap();
+    let jwk = protected["jwk"].clone();
+
+    let mut state = stub.state.lock().unwrap();
+    let (index, status) = match state.accounts.iter().position(|known| *known == jwk) {
+        Some(index) => (index, StatusCode::OK),
+        None => {
+            state.accounts.push(jwk);
+            (state.accounts.len() - 1, StatusCode::CREATED)
+        }
+    };
+    drop(state);
+    stub.reply(
+        status,
+        Some(stub.url(&format!("/account/{index}"))),
+        json!({ "status": "valid" }),
+    )
+}
+
+async fn new_order(State(stub): State<Arc<Stub>>, body: Bytes) -> Response {
+    let (account, payload) = match stub.verify(&body, "/order") {
+        Ok(verified) => verified,
+        Err(problem) => return *problem,
+    };
+    let domains: Vec<String> = payload["identifiers"]
+        .as_array()
+        .expect("identifiers")
+        .iter()
+        .map(|id| id["value"].as_str().unwrap().to_string())
+        .collect();
+
+    let id = {
+        let mut state = stub.state.lock().unwrap();
+        let id = state.orders.len();
+        state.orders.push(StubOrder {
+            account: account.expect("orders are posted with a kid"),
+            tokens: (0..domains.len())
+                .map(|i| format!("token-{id}-{i}"))
+                .collect(),
+            authz_valid: vec![None; domains.len()],
+            domains,
+            certificate: None,
+        });
+        id
+    };
+    stub.reply(
+        StatusCode::CREATED,
+        Some(stub.url(&format!("/order/{id}"))),
+        stub.order_json(id),
+    )
+}
+
+async fn order(
+    State(stub): State<Arc<Stub>>,
+    UrlPath(id): UrlPath<usize>,
+    body: Bytes,
+) -> Response {
+    if let Err(problem) = stub.verify(&body, &format!("/order/{id}")) {
+        return *problem;
+    }
+    stub.reply(StatusCode::OK, None, stub.order_json(id))
+}
+
+async fn authz(
+    State(stub): State<Arc<Stub>>,
+    UrlPath((id, index)): UrlPath<(usize, usize)>,
+    body: Bytes,
+) -> Response {
+    if let Err(problem) = stub.verify(&body, &format!("/authz/{id}/{index}")) {
+        return *problem;
+    }
+    stub.reply(StatusCode::OK, None, stub.authz_json(id, index))
+}
+
+/// Responding to a challenge validates it on the spot, the way Pebble does
+/// with its validation delay set to zero.
+async fn challenge(
+    State(stub): State<Arc<Stub>>,
+    UrlPath((id, index, kind)): UrlPath<(usize, usize, String)>,
+    body: Bytes,
+) -> Response {
+    if let Err(problem) = stub.verify(&body, &format!("/chall/{id}/{index}/{kind}")) {
+        return *problem;
+    }
+    let (account, domain, token) = {
+        let state = stub.state.lock().unwrap();
+        let order = &state.orders[id];
+        (
+            order.account,
+            order.domains[index].clone(),
+            order.tokens[index].clone(),
+        )
+    };
+    let expected = stub.key_authorization(account, &token);
+
+    let valid = match kind.as_str() {
+        "tls-alpn-01" => validate_tls_alpn01(stub.service_addr, &domain, &expected).await,
+        "http-01" => validate_http01(stub.service_addr, &token, &expected).await,
+        other => panic!("unexpected challenge type {other}"),
+    };
+    {
+        let mut state = stub.state.lock().unwrap();
+        state.orders[id].authz_valid[index] = Some(valid);
+        state.validated.push(kind.clone());
+    }
+    stub.reply(
+        StatusCode::OK,
+        None,
+        json!({ "type": kind, "status": if valid { "valid" } else { "invalid" } }),
+    )
+}
+
+async fn finalize(
+    State(stub): State<Arc<Stub>>,
+    UrlPath(id): UrlPath<usize>,
+    body: Bytes,
+) -> Response {
+    let (_, payload) = match stub.verify(&body, &format!("/finalize/{id}")) {
+        Ok(verified) => verified,
+        Err(problem) => return *problem,
+    };
+    if stub.state.lock().unwrap().orders[id].status() != "ready" {
+        return stub.problem("orderNotReady", "authorizations are not valid");
+    }
+
+    let der = URL_SAFE_NO_PAD
+        .decode(payload["csr"].as_str().expect("csr"))
+        .expect("base64url csr");
+    let csr = rcgen::CertificateSigningRequestParams::from_der(&der.into()).expect("parse csr");
+    let leaf = csr.signed_by(&stub.issuer).expect("sign csr");
+    let chain = format!("{}{}", leaf.pem(), stub.issuer_pem);
+
+    stub.state.lock().unwrap().orders[id].certificate = Some(chain);
+    stub.reply(StatusCode::OK, None, stub.order_json(id))
+}
+
+async fn certificate(
+    State(stub): State<Arc<Stub>>,
+    UrlPath(id): UrlPath<usize>,
+    body: Bytes,
+) -> Response {
+    if let Err(problem) = stub.verify(&body, &format!("/cert/{id}")) {
+        return *problem;
+    }
+    let chain = stub.state.lock().unwrap().orders[id]
+        .certificate
+        .clone()
+        .expect("issued");
+    let mut response = chain.into_response();
+    response.headers_mut().insert(
+        "replay-nonce",
+        HeaderValue::from_str(&stub.nonce()).unwrap(),
+    );
+    response
+}
+
+/// Accepts any server certificate: a `tls-alpn-01` validation inspects the
+/// certificate rather than trusting it.
+#[derive(Debug)]
+struct AcceptAny;
+
+impl ServerCertVerifier for AcceptAny {
+    fn verify_server_cert(
+        &self,
+        _end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        _message: &[u8],
+        _cert: &CertificateDer<'_>,
+        _dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        Ok(HandshakeSignatureValid::assertion())
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        rustls::crypto::CryptoProvider::get_default()
+            .expect("provider installed by load_server_config")
+            .signature_verification_algorithms
+            .supported_schemes()
+    }
+}
+
+/// RFC 8737 §3: handshake offering only `acme-tls/1`, then check the
+/// certificate carries the key authorization's digest.
+async fn validate_tls_alpn01(addr: SocketAddr, domain: &str, key_authorization: &str) -> bool {
+    let mut config = rustls::ClientConfig::builder()
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(AcceptAny))
+        .with_no_client_auth();
+    config.alpn_protocols = vec![ACME_TLS_ALPN_PROTOCOL.to_vec()];
+
+    let connector = tokio_rustls::TlsConnector::from(Arc::new(config));
+    let Ok(tcp) = tokio::net::TcpStream::connect(addr).await else {
+        return false;
+    };
+    let server_name = ServerName::try_from(domain.to_string()).expect("server name");
+    let Ok(stream) = connector.connect(server_name, tcp).await else {
+        return false;
+    };
+    let (_, session) = stream.get_ref();
+    if session.alpn_protocol() != Some(ACME_TLS_ALPN_PROTOCOL) {
+        return false;
+    }
+    let Some(leaf) = session.peer_certificates().and_then(|chain| chain.first()) else {
+        return false;
+    };
+
+    use x509_parser::prelude::{FromDer, X509Certificate};
+    let (_, cert) = X509Certificate::from_der(leaf.as_ref()).expect("challenge certificate");
+    let digest = Sha256::digest(key_authorization.as_bytes());
+    cert.extensions().iter().any(|ext| {
+        ext.oid.to_id_string() == ACME_IDENTIFIER_OID
+            && ext.critical
+            && ext.value.get(2..) == Some(digest.as_slice())
+    })
+}
+
+/// RFC 8555 §8.3, reached through the HTTPS listener as a CA reaches it after
+/// following port 80's redirect: without checking the certificate.
+async fn validate_http01(addr: SocketAddr, token: &str, key_authorization: &str) -> bool {
+    let client = reqwest::Client::builder()
+        .danger_accept_invalid_certs(true)
+        .build()
+        .expect("validation client");
+    let url = format!(
+        "https://{DOMAIN}:{}/.well-known/acme-challenge/{token}",
+        addr.port()
+    );
+    match client.get(url).send().await {
+        Ok(response) if response.status().is_success() => {
+            response.text().await.ok().as_deref() == Some(key_authorization)
+        }
+        _ => false,
+    }
+}
+
+/// Start the stub over HTTPS and return it with the CA bundle that trusts it.
+async fn start_stub(service_addr: SocketAddr) -> (Arc<Stub>, tempfile::NamedTempFile) {
+    let served =
+        rcgen::generate_simple_self_signed(vec![DOMAIN.to_string()]).expect("stub certificate");
+    let cert_file = write_temp(&served.cert.pem());
+    let key_file = write_temp(&served.signing_key.serialize_pem());
+    let tls_config = TlsConfig::new(
+        cert_file.path().to_path_buf(),
+        key_file.path().to_path_buf(),
+    );
+    let server_config = load_server_config(&tls_config).expect("stub server config");
+
+    let tcp = TcpListener::bind("127.0.0.1:0").await.expect("bind stub");
+    let port = tcp.local_addr().expect("stub addr").port();
+
+    let ca_key = rcgen::KeyPair::generate().expect("ca key");
+    let mut ca_params = rcgen::CertificateParams::new(Vec::new()).expect("ca params");
+    ca_params.is_ca = rcgen::IsCa::Ca(rcgen::BasicConstraints::Unconstrained);
+    let ca_cert = ca_params.self_signed(&ca_key).expect("ca certificate");
+
+    let stub = Arc::new(Stub {
+        base: format!("https://{DOMAIN}:{port}"),
+        service_addr,
+        issuer: rcgen::Issuer::new(ca_params, ca_key),
+        issuer_pem: ca_cert.pem(),
+        state: Mutex::new(StubState::default()),
+    });
+
+    let app = Router::new()
+        .route("/dir", get(directory))
+        .route("/nonce", get(new_nonce))
+        .route("/account", post(new_account))
+        .route("/order", post(new_order))
+        .route("/order/{id}", post(order))
+        .route("/authz/{id}/{index}", post(authz))
+        .route("/chall/{id}/{index}/{kind}", post(challenge))
+        .route("/finalize/{id}", post(finalize))
+        .route("/cert/{id}", post(certificate))
+        .with_state(Arc::clone(&stub));
+    let listener = TlsListener::new(tcp, server_config);
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app.into_make_service()).await;
+        // Keep the stub's own TLS files alive as long as it serves.
+        drop((cert_file, key_file));
+    });
+
+    (stub, write_temp(&served.cert.pem()))
+}
+
+// ---------------------------------------------------------------------------
+// The service under test
+// ---------------------------------------------------------------------------
+
+struct Service {
+    source: TlsConfigSource,
+    stub: Arc<Stub>,
+    _stub_ca: tempfile::NamedTempFile,
+}
+
+/// A TLS listener provisioning through the stub, serving `http01_router`
+/// beside an ordinary route unless `mount_http01` is false.
+async fn start_service(dir: &Path, challenge: AcmeChallenge, mount_http01: bool) -> Service {
+    let tcp = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("bind service");
+    let addr = tcp.local_addr().expect("service addr");
+    let (stub, stub_ca) = start_stub(addr).await;
+
+    let tls_config =
+        TlsConfig::new(dir.join("cert.pem"), dir.join("key.pem")).with_acme(AcmeConfig {
+            directory_url: stub.url("/dir"),
+            domains: vec![DOMAIN.to_string()],
+            contact: vec!["mailto:ops@example.test".to_string()],
+            accept_terms_of_service: true,
+            account_key_path: dir.join("account.pem"),
+            challenge,
+            renew_before_days: None,
+            directory_ca_path: Some(stub_ca.path().to_path_buf()),
+        });
+    let source = TlsConfigSource::from_tls_config(&tls_config).expect("ACME source");
+
+    let mut app = Router::new().route("/health", get(|| async { "ok" }));
+    if mount_http01 {
+        if let Some(http01) = acme::http01_router([&source]) {
+            app = app.merge(http01);
+        }
+    }
+    let listener = TlsListener::with_config_source(tcp, source.clone());
+    tokio::spawn(async move {
+        let _ = axum::serve(listener, app.into_make_service()).await;
+    });
+
+    Service {
+        source,
+        stub,
+        _stub_ca: stub_ca,
+    }
+}
+
+fn serving_leaf(source: &TlsConfigSource) -> Vec<u8> {
+    use rustls::pki_types::pem::PemObject;
+    let path = &source.certificates()[0].cert_path;
+    CertificateDer::pem_file_iter(path)
+        .expect("cert file")
+        .next()
+        .expect("a certificate")
+        .expect("pem")
+        .to_vec()
+}
+
+async fn issues_and_installs(challenge: AcmeChallenge) {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let service = start_service(dir.path(), challenge, true).await;
+
+    let placeholder = serving_leaf(&service.source);
+    let placeholder_expiry = service.source.certificates()[0].not_after;
+
+    let renewed = acme::renew_if_due(&service.source)
+        .await
+        .expect("the stub issues a certificate");
+    assert!(renewed, "a placeholder is always due");
+
+    assert_ne!(serving_leaf(&service.source), placeholder);
+    assert!(
+        service.source.certificates()[0].not_after > placeholder_expiry,
+        "the installed certificate must be the issued one"
+    );
+    assert_eq!(
+        service.stub.state.lock().unwrap().validated,
+        vec![challenge.as_str().to_string()]
+    );
+
+    // The issued certificate covers the domain and is far from expiry.
+    let renewed_again = acme::renew_if_due(&service.source)
+        .await
+        .expect("second check");
+    assert!(!renewed_again, "a fresh certificate is not renewed");
+    assert_eq!(service.stub.state.lock().unwrap().orders.len(), 1);
+}
+
+#[tokio::test]
+async fn tls_alpn_01_issues_and_installs_a_certificate() {
+    issues_and_installs(AcmeChallenge::TlsAlpn01).await;
+}
+
+#[tokio::test]
+async fn http_01_issues_and_installs_a_certificate() {
+    issues_and_installs(AcmeChallenge::Http01).await;
+}
+
+#[tokio::test]
+async fn a_failed_validation_keeps_the_current_certificate() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    // The challenge route is never mounted, so the CA's fetch gets a 404.
+    let service = start_service(dir.path(), AcmeChallenge::Http01, false).await;
+    let placeholder = serving_leaf(&service.source);
+
+    let err = acme::renew_if_due(&service.source)
+        .await
+        .expect_err("validation cannot succeed");
+    assert!(err.to_string().contains("http-01"), "{err}");
+
+    assert_eq!(serving_leaf(&service.source), placeholder);
+}
+
+#[tokio::test]
+async fn an_ordinary_handshake_is_untouched_by_acme() {
+    let dir = tempfile::tempdir().expect("tempdir");
+    let service = start_service(dir.path(), AcmeChallenge::TlsAlpn01, true).await;
+
+    // With no order in flight, an `acme-tls/1` handshake gets no certificate
+    // at all, never the real one.
+    assert!(
+        !validate_tls_alpn01(service.stub.service_addr, DOMAIN, "anything").await,
+        "nothing is published outside an order"
+    );
+
+    // Every other handshake negotiates the usual protocols and certificate.
+    let mut config = rustls::ClientConfig::builder()
+        .dangerous()
+        .with_custom_certificate_verifier(Arc::new(AcceptAny))
+        .with_no_client_auth();
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    let tcp = tokio::net::TcpStream::connect(service.stub.service_addr)
+        .await
+        .expect("connect");
+    let stream = tokio_rustls::TlsConnector::from(Arc::new(config))
+        .connect(ServerName::try_from(DOMAIN).expect("server name"), tcp)
+        .await
+        .expect("ordinary handshake");
+    let (_, session) = stream.get_ref();
+    assert_eq!(session.alpn_protocol(), Some(&b"h2"[..]));
+    assert_eq!(
+        session.peer_certificates().expect("certificate")[0].to_vec(),
+        serving_leaf(&service.source)
+    );
+}
@@ -0,0 +1,177 @@
+//! A key over its daily quota must be refused by a service built from config.
+//!
+//! The full `ServiceBuilder::serve` path runs with `[auth.api_keys]` on Turso
+//! storage and the auto-applied governor, so the test covers the wiring in
+//! `build()` as well as the quota check: before the governor was handed the
+//! API key storage, quotas were resolved but never counted.
+//!
+//! The client is a raw `TcpStream`, following `tls_alpn_http2.rs`.
+
+#![cfg(all(feature = "governor", feature = "auth", feature = "turso"))]
+
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+
+use acton_service::auth::{
+    ApiKey, ApiKeyConfig, ApiKeyGenerator, ApiKeyStorage, AuthConfig, TursoApiKeyStorage,
+};
+use acton_service::config::{Config, TursoConfig};
+use acton_service::prelude::*;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+const LOOPBACK: IpAddr = IpAddr::V4(Ipv4Addr::LOCALHOST);
+/// Bounds every wait in this file; loopback answers in microseconds.
+const REPLY_TIMEOUT: Duration = Duration::from_secs(5);
+/// How long the spawned `serve` gets to bind and connect to Turso.
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(10);
+const PREFIX: &str = "sk_test";
+
+const SCHEMA: &str = "
+    CREATE TABLE api_keys (
+        id TEXT PRIMARY KEY,
+        user_id TEXT NOT NULL,
+        name TEXT NOT NULL,
+        key_prefix TEXT NOT NULL UNIQUE,
+        key_hash TEXT NOT NULL,
+        scopes TEXT NOT NULL DEFAULT '[]',
+        rate_limit INTEGER,
+        is_revoked INTEGER NOT NULL DEFAULT 0,
+        last_used_at TEXT,
+        expires_at TEXT,
+        created_at TEXT NOT NULL,
+        daily_quota INTEGER,
+        monthly_quota INTEGER
+    );
+    CREATE TABLE api_key_usage (
+        key_id TEXT NOT NULL,
+        period TEXT NOT NULL,
+        count INTEGER NOT NULL DEFAULT 0,
+        PRIMARY KEY (key_id, period)
+    );
+";
+
+/// Create the tables and a key allowed two requests a day
+async fn seed(path: &std::path::Path) -> String {
+    let db = libsql::Builder::new_local(path)
+        .build()
+        .await
+        .expect("open turso file");
+    let conn = db.connect().expect("connect turso file");
+    conn.execute_batch(SCHEMA).await.expect("create schema");
+
+    let (key, key_hash) = ApiKeyGenerator::new(PREFIX).generate();
+    let storage = TursoApiKeyStorage::new(Arc::new(conn), PREFIX);
+    storage
+        .create(&ApiKey {
+            id: "key-1".to_string(),
+            user_id: "billing-service".to_string(),
+            name: "billing".to_string(),
+            prefix: ApiKeyGenerator::key_prefix_for_lookup(&key).expect("lookup prefix"),
+            key_hash,
+            scopes: Vec::new(),
+            rate_limit: Some(1000),
+            daily_quota: Some(2),
+            monthly_quota: None,
+            is_revoked: false,
+            last_used_at: None,
+            expires_at: None,
+            created_at: chrono::Utc::now(),
+        })
+        .await
+        .expect("store key");
+    key
+}
+
+fn free_port() -> u16 {
+    std::net::TcpListener::bind("127.0.0.1:0")
+        .expect("reserve port")
+        .local_addr()
+        .expect("port addr")
+        .port()
+}
+
+/// One HTTP/1.1 GET carrying the key, response returned verbatim.
+async fn get_with_key(addr: SocketAddr, key: &str) -> std::io::Result<String> {
+    let mut stream = TcpStream::connect(addr).await?;
+    let request = format!(
+        "GET /api/v1/ping HTTP/1.1\r\nHost: {addr}\r\nX-API-Key: {key}\r\nConnection: close\r\n\r\n"
+    );
+    stream.write_all(request.as_bytes()).await?;
+
+    let mut response = Vec::new();
+    tokio::time::timeout(REPLY_TIMEOUT, stream.read_to_end(&mut response))
+        .await
+        .map_err(|_| std::io::Error::new(std::io::ErrorKind::TimedOut, "no reply"))??;
+    Ok(String::from_utf8_lossy(&response).into_owned())
+}
+
+// Multi-thread flavor: the default config enables the audit agent, which
+// `build()` refuses on a current-thread runtime.
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn a_key_over_its_daily_quota_is_refused() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let db_path = dir.path().join("keys.db");
+    let key = seed(&db_path).await;
+
+    let port = free_port();
+    let mut config = Config::<()>::default();
+    config.service.name = "api-key-quota-e2e".to_string();
+    config.service.bind = LOOPBACK;
+    config.service.port = port;
+    config.turso = Some(
+        serde_json::from_value::<TursoConfig>(serde_json::json!({ "path": db_path }))
+            .expect("turso config"),
+    );
+    config.auth = Some(AuthConfig {
+        api_keys: Some(ApiKeyConfig {
+            prefix: PREFIX.to_string(),
+            storage: "turso".to_string(),
+            ..ApiKeyConfig::default()
+        }),
+        ..AuthConfig::default()
+    });
+
+    let routes = VersionedApiBuilder::new()
+        .with_base_path("/api")
+        .add_version(ApiVersion::V1, |router| {
+            router.route("/ping", axum::routing::get(|| async { "pong" }))
+        })
+        .build_routes();
+    let service = ServiceBuilder::new()
+        .with_config(config)
+        .with_routes(routes)
+        .build();
+    let server = tokio::spawn(service.serve());
+
+    // Until the listener is up and the Turso pool has connected, requests
+    // fail without reaching the quota check.
+    let addr = SocketAddr::new(LOOPBACK, port);
+    let deadline = tokio::time::Instant::now() + STARTUP_TIMEOUT;
+    let first = loop {
+        match get_with_key(addr, &key).await {
+            Ok(response) if response.starts_with("HTTP/1.1 200") => break response,
+            Ok(_) | Err(_) if tokio::time::Instant::now() < deadline => {
+                tokio::time::sleep(Duration::from_millis(25)).await;
+            }
+            other => panic!("the service never accepted the key: {other:?}"),
+        }
+    };
+    assert!(first.ends_with("pong"), "got: {first}");
+
+    let second = get_with_key(addr, &key).await.expect("second request");
+    assert!(
+        second.starts_with("HTTP/1.1 200"),
+        "the second request is within the quota, got: {second}"
+    );
+
+    let third = get_with_key(addr, &key).await.expect("third request");
+    assert!(
+        third.starts_with("HTTP/1.1 429"),
+        "the third request is over the daily quota, got: {third}"
+    );
+    assert!(third.contains("QUOTA_EXCEEDED"), "got: {third}");
+
+    server.abort();
+}
@@ -67,6 +67,7 @@ async fn auto_apply_attaches_layer_when_routes_configured() {
             requests_per_minute: 10,
             burst_size: 1, // tiny burst so we trip the limit on the 2nd hit
             per_user: false,
+            algorithm: None,
         },
     );
 
@@ -108,6 +109,7 @@ async fn no_auto_apply_when_disabled() {
             requests_per_minute: 1,
             burst_size: 1,
             per_user: false,
+            algorithm: None,
         },
     );
 
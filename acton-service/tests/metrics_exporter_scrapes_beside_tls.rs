@@ -75,16 +75,7 @@ async fn a_tls_service_is_scrapeable_in_plaintext_through_the_exporter() {
     config.service.name = "tls-exporter-e2e".to_string();
     config.service.bind = LOOPBACK;
     config.service.port = http_port;
-    config.tls = Some(TlsConfig {
-        enabled: true,
-        cert_path: cert_file.path().to_path_buf(),
-        key_path: key_file.path().to_path_buf(),
-        client_ca_path: None,
-        client_auth_optional: false,
-        reload_interval_secs: None,
-        reload_on_sighup: false,
-        handshake_timeout_secs: None,
-    });
+    config.tls = Some(TlsConfig::new(cert_file.path().to_path_buf(), key_file.path().to_path_buf()));
     config.middleware.metrics = Some(
         MetricsConfig::new().with_exporter(MetricsExporterConfig::new(LOOPBACK, exporter_port)),
     );
@@ -64,16 +64,7 @@ async fn serve_tls() -> (SocketAddr, String) {
     let cert_file = write_temp(&cert_pem);
     let key_file = write_temp(&certified.signing_key.serialize_pem());
 
-    let tls_config = TlsConfig {
-        enabled: true,
-        cert_path: cert_file.path().to_path_buf(),
-        key_path: key_file.path().to_path_buf(),
-        client_ca_path: None,
-        client_auth_optional: false,
-        reload_interval_secs: None,
-        reload_on_sighup: false,
-        handshake_timeout_secs: None,
-    };
+    let tls_config = TlsConfig::new(cert_file.path().to_path_buf(), key_file.path().to_path_buf());
     // Also installs the process-wide crypto provider the client below needs.
     let server_config = load_server_config(&tls_config).expect("server config builds");
 